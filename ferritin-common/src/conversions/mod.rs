@@ -1,14 +1,23 @@
 //! Version conversions for rustdoc-types formats
 //!
 //! Each module (e.g., `v56`) handles conversion from that version to the next (v56 -> v57).
-//! Conversions can be chained: v55 -> v56 -> v57
+//! Conversions can be chained: v53 -> v54 -> v55 -> v56 -> v57
+//!
+//! Versions without a rustdoc-types dependency (v54 and older) are patched as JSON until they
+//! can be read as v55.
 
+pub mod v53;
+pub mod v54;
 pub mod v55;
 pub mod v56;
 
 use anyhow::{Context, Result};
 use rustdoc_types::{Crate, FORMAT_VERSION};
 use sonic_rs::JsonValueTrait;
+use sonic_rs::Value;
+
+/// The oldest format version that can be normalized (inclusive)
+pub const MIN_FORMAT_VERSION: u32 = 43;
 
 /// Load rustdoc JSON and normalize to the current format version
 ///
@@ -16,6 +25,9 @@ use sonic_rs::JsonValueTrait;
 /// 1. Parses the JSON to determine the format version
 /// 2. Parses with the appropriate rustdoc-types version
 /// 3. Converts through intermediate versions to reach FORMAT_VERSION (57)
+///
+/// JSON newer than FORMAT_VERSION is parsed as the current format, which works as long as the
+/// newer format only added fields.
 pub fn load_and_normalize(json: &[u8], format_version: Option<u32>) -> Result<Crate> {
     // First, peek at the format version without parsing the entire JSON
     let format_version = if let Some(format_version) = format_version {
@@ -47,19 +59,39 @@ pub fn load_and_normalize(json: &[u8], format_version: Option<u32>) -> Result<Cr
             let crate_56 = v55::convert_crate(crate_55).context("Failed to convert v55 to v56")?;
             v56::convert_crate(crate_56)
         }
-        v if v < 55 => {
+        v @ MIN_FORMAT_VERSION..=54 => {
+            // Patch the JSON up to v55, then convert as above
+            let mut json_value: Value = sonic_rs::serde::from_slice(json)
+                .with_context(|| format!("Failed to parse format version {v} as JSON"))?;
+            if v < 54 {
+                v53::convert_value(&mut json_value, v)
+                    .with_context(|| format!("Failed to convert v{v} to v54"))?;
+            }
+            v54::convert_value(&mut json_value);
+
+            let crate_55: rustdoc_types_55::Crate = sonic_rs::value::from_value(&json_value)
+                .with_context(|| format!("Failed to read converted v{v} crate as v55"))?;
+            let crate_56 = v55::convert_crate(crate_55).context("Failed to convert v55 to v56")?;
+            v56::convert_crate(crate_56)
+        }
+        v if v < MIN_FORMAT_VERSION => {
             anyhow::bail!(
-                "Format version {} is too old. Minimum supported version: 55, current version: {}",
+                "Format version {} is too old. Minimum supported version: {}, current version: {}",
                 v,
+                MIN_FORMAT_VERSION,
                 FORMAT_VERSION
             )
         }
         v => {
-            anyhow::bail!(
-                "Format version {} is too new. Maximum supported version: {}",
-                v,
-                FORMAT_VERSION
-            )
+            log::warn!(
+                "Format version {v} is newer than {FORMAT_VERSION}, reading it as {FORMAT_VERSION}"
+            );
+            sonic_rs::serde::from_slice(json).with_context(|| {
+                format!(
+                    "Format version {v} is too new, and could not be read as format version \
+                     {FORMAT_VERSION}. Maximum supported version: {FORMAT_VERSION}"
+                )
+            })
         }
     }
 }
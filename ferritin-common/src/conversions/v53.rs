@@ -0,0 +1,186 @@
+//! Conversion from rustdoc-types format versions 43 through 53 to 54
+//!
+//! Changes up to v54:
+//! - v44 added the `Crate::target` field - defaults to an empty triple with no features
+//! - v45 through v53 only changed how some attributes are printed, or wrapped fields in
+//!   `Option`, so their JSON reads the same way
+//! - v54 changed `Item::attrs` from `Vec<String>` to `Vec<Attribute>`
+//!
+//! Strategy: Patch the JSON in place, parsing each attribute string into the structured
+//! `Attribute` that rustdoc now emits, and keeping anything unrecognized as `Attribute::Other`.
+//! There is no rustdoc-types dependency for these versions, so attributes are built with the
+//! v55 types, which only add `Attribute::MacroUse` (see [`super::v54`]).
+
+use anyhow::{Context, Result};
+use rustdoc_types_55::{Attribute, AttributeRepr, ReprKind};
+use sonic_rs::{JsonValueMutTrait, JsonValueTrait, Value};
+
+/// Patch a v43 to v53 crate's JSON into the v54 layout
+pub fn convert_value(json_value: &mut Value, format_version: u32) -> Result<()> {
+    if format_version < 44
+        && let Some(obj) = json_value.as_object_mut()
+    {
+        obj.insert(
+            "target",
+            sonic_rs::json!({ "triple": "", "target_features": [] }),
+        );
+    }
+
+    if let Some(index) = json_value.get_mut("index")
+        && let Some(items) = index.as_object_mut()
+    {
+        for (_id, item) in items.iter_mut() {
+            let Some(attrs) = item.get_mut("attrs").and_then(|attrs| attrs.as_array_mut()) else {
+                continue;
+            };
+
+            for attr in attrs.iter_mut() {
+                if let Some(attr_str) = attr.as_str() {
+                    *attr = sonic_rs::value::to_value(&parse_attribute(attr_str))
+                        .context("Failed to serialize attribute")?;
+                }
+            }
+        }
+    }
+
+    if let Some(obj) = json_value.as_object_mut() {
+        obj.insert("format_version", sonic_rs::json!(54));
+    }
+
+    Ok(())
+}
+
+/// Parse an attribute as older rustdoc printed it, like `#[repr(C, align(8))]` or
+/// `#[attr = MustUse {reason: "..."}]`
+fn parse_attribute(attr: &str) -> Attribute {
+    let other = || Attribute::Other(attr.to_string());
+    let Some(inner) = attr
+        .strip_prefix("#[")
+        .and_then(|inner| inner.strip_suffix(']'))
+    else {
+        return other();
+    };
+    let inner = inner
+        .strip_prefix("unsafe(")
+        .and_then(|inner| inner.strip_suffix(')'))
+        .unwrap_or(inner);
+
+    match inner {
+        "non_exhaustive" | "attr = NonExhaustive" => Attribute::NonExhaustive,
+        "macro_export" | "attr = MacroExport" => Attribute::MacroExport,
+        "automatically_derived" | "attr = AutomaticallyDerived" => Attribute::AutomaticallyDerived,
+        "no_mangle" | "attr = NoMangle" => Attribute::NoMangle,
+        _ if inner.starts_with("must_use") || inner.starts_with("attr = MustUse") => {
+            Attribute::MustUse {
+                reason: quoted(inner),
+            }
+        }
+        _ if inner.starts_with("export_name") => {
+            quoted(inner).map_or_else(other, Attribute::ExportName)
+        }
+        _ if inner.starts_with("link_section") => {
+            quoted(inner).map_or_else(other, Attribute::LinkSection)
+        }
+        _ if inner.starts_with("target_feature") => match quoted(inner) {
+            Some(features) => Attribute::TargetFeature {
+                enable: features.split(',').map(|f| f.trim().to_string()).collect(),
+            },
+            None => other(),
+        },
+        _ => inner
+            .strip_prefix("repr(")
+            .and_then(|reprs| reprs.strip_suffix(')'))
+            .and_then(parse_repr)
+            .map_or_else(other, Attribute::Repr),
+    }
+}
+
+/// Parse the contents of `#[repr(...)]`, like `C, align(8)` or `u8`
+fn parse_repr(reprs: &str) -> Option<AttributeRepr> {
+    let mut repr = AttributeRepr {
+        kind: ReprKind::Rust,
+        align: None,
+        packed: None,
+        int: None,
+    };
+
+    for part in reprs.split(',').map(str::trim) {
+        match part {
+            "Rust" => repr.kind = ReprKind::Rust,
+            "C" => repr.kind = ReprKind::C,
+            "transparent" => repr.kind = ReprKind::Transparent,
+            "simd" => repr.kind = ReprKind::Simd,
+            "packed" => repr.packed = Some(1),
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+            | "i128" | "isize" => repr.int = Some(part.to_string()),
+            _ => {
+                let (name, value) = part.strip_suffix(')')?.split_once('(')?;
+                let value = value.trim().parse().ok()?;
+                match name.trim() {
+                    "align" => repr.align = Some(value),
+                    "packed" => repr.packed = Some(value),
+                    _ => return None,
+                }
+            }
+        }
+    }
+
+    Some(repr)
+}
+
+/// The text between the first and last double quote, with escaped quotes unescaped
+fn quoted(s: &str) -> Option<String> {
+    let start = s.find('"')?;
+    let end = s.rfind('"')?;
+    (end > start).then(|| s[start + 1..end].replace("\\\"", "\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_strings_become_structured_attributes() {
+        assert_eq!(
+            parse_attribute("#[non_exhaustive]"),
+            Attribute::NonExhaustive
+        );
+        assert_eq!(parse_attribute("#[unsafe(no_mangle)]"), Attribute::NoMangle);
+        assert_eq!(
+            parse_attribute("#[must_use]"),
+            Attribute::MustUse { reason: None }
+        );
+        assert_eq!(
+            parse_attribute(r#"#[attr = MustUse {reason: "use \"it\""}]"#),
+            Attribute::MustUse {
+                reason: Some(String::from(r#"use "it""#))
+            }
+        );
+        assert_eq!(
+            parse_attribute(r#"#[export_name = "entry"]"#),
+            Attribute::ExportName(String::from("entry"))
+        );
+        assert_eq!(
+            parse_attribute("#[repr(C, align(8))]"),
+            Attribute::Repr(AttributeRepr {
+                kind: ReprKind::C,
+                align: Some(8),
+                packed: None,
+                int: None,
+            })
+        );
+        assert_eq!(
+            parse_attribute("#[repr(u8)]"),
+            Attribute::Repr(AttributeRepr {
+                kind: ReprKind::Rust,
+                align: None,
+                packed: None,
+                int: Some(String::from("u8")),
+            })
+        );
+        assert_eq!(
+            parse_attribute(r#"#[doc(alias = "x")]"#),
+            Attribute::Other(String::from(r#"#[doc(alias = "x")]"#))
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Conversion from rustdoc-types format version 54 to 55
+//!
+//! Changes in v55:
+//! - Added `Attribute::MacroUse` enum variant, so `#[macro_use]` is no longer
+//!   `Attribute::Other`
+//!
+//! Strategy: Patch the JSON in place, then deserialize as v55. There is no rustdoc-types
+//! dependency for v54, so this works on the JSON directly rather than a parsed crate.
+
+use sonic_rs::{JsonValueMutTrait, JsonValueTrait, Value};
+
+/// Patch a v54 crate's JSON into the v55 layout
+pub fn convert_value(json_value: &mut Value) {
+    if let Some(index) = json_value.get_mut("index")
+        && let Some(items) = index.as_object_mut()
+    {
+        for (_id, item) in items.iter_mut() {
+            let Some(attrs) = item.get_mut("attrs").and_then(|attrs| attrs.as_array_mut()) else {
+                continue;
+            };
+
+            for attr in attrs.iter_mut() {
+                if attr
+                    .get("other")
+                    .and_then(|other| other.as_str())
+                    .is_some_and(|other| other == "#[macro_use]")
+                {
+                    *attr = sonic_rs::json!("macro_use");
+                }
+            }
+        }
+    }
+
+    if let Some(obj) = json_value.as_object_mut() {
+        obj.insert("format_version", sonic_rs::json!(55));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{
-    Navigator, RustdocData, navigator::parse_docsrs_url, rustdoc_data::kind_discriminator,
+    Navigator, RustdocData, docs_summary,
+    navigator::parse_docsrs_url,
+    rustdoc_data::{doc_aliases, kind_discriminator, stable_since},
 };
 use fieldwork::Fieldwork;
 use rustdoc_types::{
@@ -29,7 +31,7 @@ impl<'a> From<DocRef<'a, Item>> for ParentRef<'a> {
         }
     }
 }
-use semver::VersionReq;
+use semver::{Version, VersionReq};
 use std::{
     fmt::{self, Debug, Display, Formatter},
     hash::{Hash, Hasher},
@@ -112,10 +114,31 @@ impl<'a> DocRef<'a, Item> {
         self.crate_docs().path(&self.id)
     }
 
+    /// The item's path as `Navigator::resolve_path` takes it, with the crate's version when
+    /// it was loaded at another version than its name alone loads
+    pub fn link_path(&self) -> Option<String> {
+        self.crate_docs().link_path(&self.id)
+    }
+
     pub fn summary(&self) -> Option<&'a ItemSummary> {
         self.crate_docs().paths.get(&self.id)
     }
 
+    /// Alternative names from `#[doc(alias = "...")]`
+    pub fn doc_aliases(&self) -> impl Iterator<Item = &'a str> {
+        doc_aliases(self.item)
+    }
+
+    /// The Rust version the item was stabilized in, for std items and others that record it
+    pub fn stable_since(&self) -> Option<Version> {
+        stable_since(self.item)
+    }
+
+    /// The first sentence of the item's docs, as plain text
+    pub fn docs_summary(&self) -> Option<String> {
+        self.docs.as_deref().and_then(docs_summary)
+    }
+
     pub fn find_child(&self, child_name: &str) -> Option<DocRef<'a, Item>> {
         self.child_items()
             .find(|c| c.name().is_some_and(|n| n == child_name))
@@ -140,6 +163,17 @@ impl<'a> DocRef<'a, Item> {
         None
     }
 
+    /// The item this one was reached through, like the type or trait an associated item
+    /// belongs to, if it was found by traversal rather than by id
+    pub fn parent(&self) -> Option<DocRef<'a, Item>> {
+        let parent_ref = self.parent?;
+        let parent = DocRef::new(self.navigator, parent_ref.crate_docs, parent_ref.item);
+        Some(match parent_ref.name {
+            Some(name) => parent.with_name(name),
+            None => parent,
+        })
+    }
+
     /// Returns the fully-qualified, kind-discriminated path for this item, suitable for
     /// round-tripping through `Navigator::resolve_path`.
     ///
@@ -147,7 +181,8 @@ impl<'a> DocRef<'a, Item> {
     /// `vec` module itself returns `"std::mod@vec"`. The crate name is included as the first
     /// segment; the discriminator (`kind@`) appears only on the final segment.
     ///
-    /// Uses `crate_docs().name()` (the Navigator's canonical crate name) rather than
+    /// Uses `crate_docs().crate_specifier()` (the Navigator's canonical crate name, with the
+    /// version for crates loaded at another version than the name alone loads) rather than
     /// `ItemSummary::path[0]` (which rustdoc normalizes to underscores) so that the
     /// generated path round-trips correctly through `Navigator::resolve_path`.
     ///
@@ -160,7 +195,7 @@ impl<'a> DocRef<'a, Item> {
             let path = &summary.path;
             let tail = path.get(1..)?;
             let disc = kind_discriminator(self.kind());
-            let crate_name = self.crate_docs().name();
+            let crate_name = self.crate_docs().crate_specifier();
             return match tail {
                 [] => Some(format!("{crate_name}::{disc}@{}", path[0])),
                 [.., last] => {
@@ -187,7 +222,7 @@ impl<'a> DocRef<'a, Item> {
             if let Some(tail) = parent_summary.path.get(1..) {
                 let parent_key = tail.join("::");
                 if parent_ref.crate_docs.path_to_id.contains_key(&parent_key) {
-                    let crate_name = parent_ref.crate_docs.name();
+                    let crate_name = parent_ref.crate_docs.crate_specifier();
                     let parent_path = if parent_key.is_empty() {
                         crate_name.to_string()
                     } else {
@@ -208,8 +243,32 @@ impl<'a> DocRef<'a, Item> {
         Some(format!("{parent_path}::{disc}@{name}"))
     }
 
+    /// Where an impl block comes from, which decides where rustdoc lists it on a type's page,
+    /// or `None` if this isn't an impl block
+    pub fn impl_origin(&self) -> Option<ImplOrigin> {
+        let ItemEnum::Impl(impl_item) = self.inner() else {
+            return None;
+        };
+        Some(if impl_item.is_synthetic {
+            ImplOrigin::Auto
+        } else if impl_item.blanket_impl.is_some() {
+            ImplOrigin::Blanket
+        } else {
+            ImplOrigin::Explicit
+        })
+    }
+
     pub fn kind(&self) -> ItemKind {
         match self.item.inner {
+            // std documents keywords like `match` with modules that only its paths map tells
+            // apart from the others
+            ItemEnum::Module(_)
+                if self
+                    .summary()
+                    .is_some_and(|summary| summary.kind == ItemKind::Keyword) =>
+            {
+                ItemKind::Keyword
+            }
             ItemEnum::Module(_) => ItemKind::Module,
             ItemEnum::ExternCrate { .. } => ItemKind::ExternCrate,
             ItemEnum::Use(_) => ItemKind::Use,
@@ -246,6 +305,20 @@ impl<'a> DocRef<'a, Item> {
     }
 }
 
+/// Where an impl block on a type comes from
+///
+/// rustdoc lists the auto trait and blanket impls that apply to a type along with the ones
+/// written for it, and shows them in sections of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ImplOrigin {
+    /// Written for the type, by hand or with a derive
+    Explicit,
+    /// An auto trait like `Send` or `Unpin`, implemented (or not) because of the type's fields
+    Auto,
+    /// A generic impl for every type meeting some bounds, like `impl<T> From<T> for T`
+    Blanket,
+}
+
 impl<'a, T> Clone for DocRef<'a, T> {
     fn clone(&self) -> Self {
         *self
@@ -0,0 +1,135 @@
+use pulldown_cmark::{BrokenLink, Event, Options, Parser, Tag, TagEnd};
+use std::ops::Range;
+
+/// Words that end in a period without ending a sentence
+const ABBREVIATIONS: &[&str] = &["e.g", "i.e", "cf", "vs", "viz"];
+
+/// The first sentence of some docs, as plain text
+///
+/// Headings before the first paragraph are skipped, and the paragraph's markdown is stripped:
+/// links and emphasis keep only their text, and inline code loses its backticks. The
+/// sentence ends at a `.`, `!` or `?` followed by a space and a word that isn't lowercase,
+/// outside of inline code and not after an abbreviation like "e.g.". Returns None for docs
+/// with no paragraph.
+pub fn docs_summary(docs: &str) -> Option<String> {
+    let callback = |broken_link: BrokenLink| {
+        Some((
+            broken_link.reference.clone().into_static(),
+            broken_link.reference.into_static(),
+        ))
+    };
+    let parser = Parser::new_with_broken_link_callback(docs, Options::empty(), Some(&callback));
+
+    let mut text = String::new();
+    let mut code_ranges = vec![];
+    let mut in_paragraph = false;
+    for event in parser {
+        match event {
+            Event::Start(Tag::Paragraph) => in_paragraph = true,
+            Event::End(TagEnd::Paragraph) => {
+                if !text.trim().is_empty() {
+                    break;
+                }
+                in_paragraph = false;
+            }
+            Event::Text(fragment) if in_paragraph => push_collapsed(&mut text, &fragment),
+            Event::Code(code) if in_paragraph => {
+                let start = text.len();
+                push_collapsed(&mut text, &code);
+                code_ranges.push(start..text.len());
+            }
+            Event::SoftBreak | Event::HardBreak if in_paragraph => push_collapsed(&mut text, " "),
+            _ => {}
+        }
+    }
+
+    let summary = first_sentence(&text, &code_ranges).trim();
+    (!summary.is_empty()).then(|| summary.to_string())
+}
+
+/// Append `fragment` to `text`, with each run of whitespace shortened to one space
+fn push_collapsed(text: &mut String, fragment: &str) {
+    for c in fragment.chars() {
+        if c.is_whitespace() {
+            if !text.is_empty() && !text.ends_with(' ') {
+                text.push(' ');
+            }
+        } else {
+            text.push(c);
+        }
+    }
+}
+
+fn first_sentence<'a>(text: &'a str, code_ranges: &[Range<usize>]) -> &'a str {
+    for (index, c) in text.char_indices() {
+        if !matches!(c, '.' | '!' | '?') || code_ranges.iter().any(|code| code.contains(&index)) {
+            continue;
+        }
+
+        let end = index + c.len_utf8();
+        let Some(next_word) = text[end..].strip_prefix(' ') else {
+            continue;
+        };
+        let last_word = text[..index]
+            .rsplit(' ')
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches(['(', '"', '\''])
+            .to_lowercase();
+        if !next_word.starts_with(char::is_lowercase)
+            && !ABBREVIATIONS.contains(&last_word.as_str())
+        {
+            return &text[..end];
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::docs_summary;
+
+    #[test]
+    fn summary_is_the_first_sentence_of_the_first_paragraph() {
+        assert_eq!(
+            docs_summary("A contiguous growable array type. Written as `Vec<T>`.\n\nMore.")
+                .as_deref(),
+            Some("A contiguous growable array type.")
+        );
+        assert_eq!(
+            docs_summary("# Markdown: Syntax\n\nA guide to the syntax.").as_deref(),
+            Some("A guide to the syntax.")
+        );
+        assert_eq!(
+            docs_summary("Wraps a\nvalue that spans lines").as_deref(),
+            Some("Wraps a value that spans lines")
+        );
+        assert_eq!(docs_summary("```\nlet x = 1;\n```"), None);
+    }
+
+    #[test]
+    fn summary_strips_markdown() {
+        assert_eq!(
+            docs_summary("A **data structure** that works with [`Serde`](crate) and [`Vec`].")
+                .as_deref(),
+            Some("A data structure that works with Serde and Vec.")
+        );
+    }
+
+    #[test]
+    fn sentences_do_not_end_inside_code_or_after_abbreviations() {
+        assert_eq!(
+            docs_summary("Calls `self.finish(). Then` more, e.g. `Vec`. Second sentence.")
+                .as_deref(),
+            Some("Calls self.finish(). Then more, e.g. Vec.")
+        );
+        assert_eq!(
+            docs_summary("Version 1.0 is supported. See the docs").as_deref(),
+            Some("Version 1.0 is supported.")
+        );
+        assert_eq!(
+            docs_summary("Returns the value. and keeps going").as_deref(),
+            Some("Returns the value. and keeps going")
+        );
+    }
+}
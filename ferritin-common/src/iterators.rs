@@ -1,7 +1,6 @@
 use crate::doc_ref::{DocRef, ParentRef};
 use fieldwork::Fieldwork;
-use rustdoc_types::{Id, Item, ItemEnum, Type, Use};
-use std::collections::hash_map::Values;
+use rustdoc_types::{Id, Impl, Item, ItemEnum, StructKind, Type, Use};
 
 pub struct MethodIter<'a> {
     item: DocRef<'a, Item>,
@@ -25,6 +24,44 @@ impl<'a> DocRef<'a, Item> {
         MethodIter::new(*self)
     }
 
+    /// The impl blocks for this type that don't implement a trait
+    pub fn inherent_impls(&self) -> InherentImplBlockIter<'a> {
+        InherentImplBlockIter::new(*self)
+    }
+
+    /// The items in one of this type's impl blocks, as [`DocRef::methods`] yields them
+    pub fn impl_block_items(
+        &self,
+        impl_block: DocRef<'a, Item>,
+    ) -> impl Iterator<Item = DocRef<'a, Item>> + 'a {
+        let item = *self;
+        let ids = match impl_block.inner() {
+            ItemEnum::Impl(impl_item) => impl_item.items.as_slice(),
+            _ => &[],
+        };
+        ids.iter()
+            .filter_map(move |id| item.get(id))
+            .map(move |method| method.with_parent(item))
+    }
+
+    /// The fields of a struct or union, or the associated items declared in a trait, which
+    /// [`DocRef::child_items`] doesn't yield
+    pub fn members(&self) -> impl Iterator<Item = DocRef<'a, Item>> + 'a {
+        let item = *self;
+        let ids: Box<dyn Iterator<Item = &'a Id> + 'a> = match item.inner() {
+            ItemEnum::Struct(struct_item) => match &struct_item.kind {
+                StructKind::Plain { fields, .. } => Box::new(fields.iter()),
+                StructKind::Tuple(fields) => Box::new(fields.iter().flatten()),
+                StructKind::Unit => Box::new(std::iter::empty()),
+            },
+            ItemEnum::Union(union_item) => Box::new(union_item.fields.iter()),
+            ItemEnum::Trait(trait_item) => Box::new(trait_item.items.iter()),
+            _ => Box::new(std::iter::empty()),
+        };
+        ids.filter_map(move |id| item.get(id))
+            .map(move |member| member.with_parent(item))
+    }
+
     pub fn traits(&self) -> TraitIter<'a> {
         TraitIter::new(*self)
     }
@@ -32,6 +69,51 @@ impl<'a> DocRef<'a, Item> {
     pub fn child_items(&self) -> ChildItems<'a> {
         ChildItems::new(*self)
     }
+
+    /// Build a tree of the items under this one, depth first
+    ///
+    /// The items under a child are walked when `open` says so for the child and its depth,
+    /// which is 1 for this item's own children. `build` turns each child and what was built for
+    /// the items under it into a node, or leaves it out. Items re-exported from another crate
+    /// aren't walked into, and an item already on the way down is skipped, so that a module
+    /// re-exporting one of its parents isn't walked forever.
+    pub fn item_tree<T>(
+        &self,
+        open: &impl Fn(DocRef<'a, Item>, usize) -> bool,
+        build: &mut impl FnMut(DocRef<'a, Item>, Vec<T>) -> Option<T>,
+    ) -> Vec<T> {
+        item_tree(*self, 1, open, build, &mut vec![self.item()])
+    }
+}
+
+fn item_tree<'a, T>(
+    parent: DocRef<'a, Item>,
+    depth: usize,
+    open: &impl Fn(DocRef<'a, Item>, usize) -> bool,
+    build: &mut impl FnMut(DocRef<'a, Item>, Vec<T>) -> Option<T>,
+    ancestors: &mut Vec<&'a Item>,
+) -> Vec<T> {
+    let mut nodes = vec![];
+    for child in parent.child_items() {
+        if ancestors
+            .iter()
+            .any(|ancestor| std::ptr::eq(*ancestor, child.item()))
+        {
+            continue;
+        }
+
+        let children =
+            if std::ptr::eq(child.crate_docs(), parent.crate_docs()) && open(child, depth) {
+                ancestors.push(child.item());
+                let children = item_tree(child, depth + 1, open, build, ancestors);
+                ancestors.pop();
+                children
+            } else {
+                vec![]
+            };
+        nodes.extend(build(child, children));
+    }
+    nodes
 }
 
 impl<'a, T> DocRef<'a, T> {
@@ -40,13 +122,36 @@ impl<'a, T> DocRef<'a, T> {
     }
 }
 
+/// The items that could be impl blocks for `item`: the impls rustdoc lists on a struct, enum,
+/// union or primitive, and otherwise every item in the crate
+fn impl_candidates<'a>(item: DocRef<'a, Item>) -> Box<dyn Iterator<Item = &'a Item> + 'a> {
+    let crate_docs = item.crate_docs();
+    let impls = match &item.item().inner {
+        ItemEnum::Struct(struct_item) => &struct_item.impls,
+        ItemEnum::Enum(enum_item) => &enum_item.impls,
+        ItemEnum::Union(union_item) => &union_item.impls,
+        ItemEnum::Primitive(primitive) => &primitive.impls,
+        _ => return Box::new(crate_docs.items()),
+    };
+    Box::new(impls.iter().filter_map(move |id| crate_docs.item(id)))
+}
+
+/// Whether `impl_block` implements something for `item`. A primitive's impls are its own
+/// list, since their `for_` is a type like `[T]` rather than a path to the primitive.
+fn is_impl_for(impl_block: &Impl, item: DocRef<'_, Item>) -> bool {
+    match &impl_block.for_ {
+        Type::ResolvedPath(path) => path.id == item.id,
+        _ => matches!(item.inner(), ItemEnum::Primitive(_)),
+    }
+}
+
 pub struct TraitIter<'a> {
     item: DocRef<'a, Item>,
-    item_iter: Values<'a, Id, Item>,
+    item_iter: Box<dyn Iterator<Item = &'a Item> + 'a>,
 }
 impl<'a> TraitIter<'a> {
     fn new(item: DocRef<'a, Item>) -> Self {
-        let item_iter = item.crate_docs().index.values();
+        let item_iter = impl_candidates(item);
         Self { item, item_iter }
     }
 }
@@ -57,8 +162,7 @@ impl<'a> Iterator for TraitIter<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         for item in &mut self.item_iter {
             if let ItemEnum::Impl(impl_block) = &item.inner
-                && let Type::ResolvedPath(path) = &impl_block.for_
-                && path.id == self.item.id
+                && is_impl_for(impl_block, self.item)
                 && impl_block.trait_.is_some()
             {
                 return Some(self.item.build_ref(item));
@@ -138,9 +242,7 @@ impl<'a, T> Iterator for IdIter<'a, T> {
                         let source_item = use_item
                             .id
                             .and_then(|id| item.crate_docs().get(item.navigator(), &id))
-                            .or_else(|| {
-                                item.navigator().resolve_path(&use_item.source, &mut vec![])
-                            })?;
+                            .or_else(|| item.navigator().find_path(&use_item.source))?;
 
                         if use_item.is_glob {
                             self.glob_iter = match source_item.inner() {
@@ -173,14 +275,14 @@ impl<'a, T> Iterator for IdIter<'a, T> {
     }
 }
 
-pub(crate) struct InherentImplBlockIter<'a> {
+pub struct InherentImplBlockIter<'a> {
     item: DocRef<'a, Item>,
-    item_iter: Values<'a, Id, Item>,
+    item_iter: Box<dyn Iterator<Item = &'a Item> + 'a>,
 }
 
 impl<'a> InherentImplBlockIter<'a> {
     pub(crate) fn new(item: DocRef<'a, Item>) -> Self {
-        let item_iter = item.crate_docs().index.values();
+        let item_iter = impl_candidates(item);
         Self { item, item_iter }
     }
 }
@@ -191,8 +293,7 @@ impl<'a> Iterator for InherentImplBlockIter<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         for item in &mut self.item_iter {
             if let ItemEnum::Impl(impl_block) = &item.inner
-                && let Type::ResolvedPath(path) = &impl_block.for_
-                && path.id == self.item.id
+                && is_impl_for(impl_block, self.item)
                 && impl_block.trait_.is_none()
             {
                 return Some(DocRef::new(self.item.navigator(), self.item, item));
@@ -230,11 +331,7 @@ impl<'a> Iterator for ChildItems<'a> {
                     let source_item = use_item
                         .id
                         .and_then(|id| use_item.get(&id))
-                        .or_else(|| {
-                            use_item
-                                .navigator()
-                                .resolve_path(&use_item.source, &mut vec![])
-                        })?
+                        .or_else(|| use_item.navigator().find_path(&use_item.source))?
                         .with_name(name);
 
                     if use_item.is_glob {
@@ -285,7 +382,7 @@ impl<'a> ChildItems<'a> {
                 item.id_iter(&enum_item.variants).with_parent(parent),
                 item.methods(),
             ),
-            ItemEnum::Struct(_) => Self::AssociatedMethods(item.methods()),
+            ItemEnum::Struct(_) | ItemEnum::Primitive(_) => Self::AssociatedMethods(item.methods()),
             ItemEnum::Use(use_item) => ChildItems::Use(Some(item.build_ref(use_item)), None, false),
             _ => Self::None,
         }
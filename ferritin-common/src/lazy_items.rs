@@ -0,0 +1,159 @@
+//! Lazily parsed items, for crates whose rustdoc JSON is too large to parse up front
+//!
+//! Everything in the JSON apart from `index` is parsed when the crate loads. For `index`, only
+//! the byte range of each item is recorded, and an item is parsed the first time it is looked
+//! up. Viewing one page of a huge crate then only parses the items that page shows.
+
+use anyhow::{Context, Result};
+use elsa::sync::FrozenMap;
+use memchr::memmem;
+use rustc_hash::FxHashMap;
+use rustdoc_types::{Crate, ExternalCrate, Id, Item, ItemSummary, Target};
+use serde::Deserialize;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Range;
+
+/// JSON at least this large, in the current format, is loaded lazily
+pub(crate) const LAZY_LOAD_MIN_BYTES: usize = 64 * 1024 * 1024;
+
+/// The fields of [`Crate`] other than `index`, which serde skips over
+#[derive(Deserialize)]
+struct CrateWithoutIndex {
+    root: Id,
+    crate_version: Option<String>,
+    includes_private: bool,
+    paths: FxHashMap<Id, ItemSummary>,
+    external_crates: FxHashMap<u32, ExternalCrate>,
+    target: Target,
+    format_version: u32,
+}
+
+/// A crate's items, parsed from its rustdoc JSON as they are looked up
+pub(crate) struct LazyItems {
+    json: Vec<u8>,
+    ranges: FxHashMap<Id, Range<usize>>,
+    parsed: FrozenMap<Id, Box<Item>>,
+}
+
+impl Debug for LazyItems {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyItems")
+            .field("items", &self.ranges.len())
+            .finish()
+    }
+}
+
+impl PartialEq for LazyItems {
+    fn eq(&self, other: &Self) -> bool {
+        self.json == other.json
+    }
+}
+
+impl Eq for LazyItems {}
+
+impl LazyItems {
+    /// Parse everything but the items in `json`, which must be in the current format version.
+    /// The returned crate's `index` is empty: its items are found through the [`LazyItems`].
+    pub(crate) fn parse(json: Vec<u8>) -> Result<(Crate, Self)> {
+        let CrateWithoutIndex {
+            root,
+            crate_version,
+            includes_private,
+            paths,
+            external_crates,
+            target,
+            format_version,
+        } = sonic_rs::serde::from_slice(&json).context("Failed to parse crate metadata")?;
+
+        let index =
+            sonic_rs::get_from_slice(&json, &["index"]).context("Failed to find the item index")?;
+        let mut ranges = FxHashMap::default();
+        for entry in sonic_rs::to_object_iter(index.as_raw_str()) {
+            let (id, item) = entry.context("Failed to read the item index")?;
+            let id = id.parse().map(Id).context("Item ids should be numbers")?;
+            let item = item.as_raw_str();
+            let start = item.as_ptr() as usize - json.as_ptr() as usize;
+            ranges.insert(id, start..start + item.len());
+        }
+
+        let crate_data = Crate {
+            root,
+            crate_version,
+            includes_private,
+            index: Default::default(),
+            paths,
+            external_crates,
+            target,
+            format_version,
+        };
+        let items = Self {
+            json,
+            ranges,
+            parsed: FrozenMap::new(),
+        };
+        Ok((crate_data, items))
+    }
+
+    /// The item with this id, parsing it if this is the first time it is needed
+    pub(crate) fn get(&self, id: &Id) -> Option<&Item> {
+        if let Some(item) = self.parsed.get(id) {
+            return Some(item);
+        }
+
+        let range = self.ranges.get(id)?;
+        match sonic_rs::serde::from_slice::<Item>(&self.json[range.clone()]) {
+            Ok(item) => Some(self.parsed.insert(*id, Box::new(item))),
+            Err(error) => {
+                log::warn!("Could not parse item {id:?}: {error}");
+                None
+            }
+        }
+    }
+
+    /// The item with this id, if its JSON contains `needle`, so that a scan for an attribute
+    /// only parses the items that could have it
+    pub(crate) fn get_if_contains(&self, id: &Id, needle: &str) -> Option<&Item> {
+        let range = self.ranges.get(id)?;
+        memmem::find(&self.json[range.clone()], needle.as_bytes())?;
+        self.get(id)
+    }
+
+    /// Every item, each parsed as it is reached
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Item> {
+        self.ranges.keys().filter_map(|id| self.get(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_are_parsed_on_demand() {
+        let json = r#"{
+            "root": 0,
+            "crate_version": "1.0.0",
+            "includes_private": false,
+            "index": {
+                "0": {"id": 0, "crate_id": 0, "name": "lazy", "span": null,
+                      "visibility": "public", "docs": "Crate docs", "links": {},
+                      "attrs": [], "deprecation": null,
+                      "inner": {"module": {"is_crate": true, "items": [], "is_stripped": false}}}
+            },
+            "paths": {},
+            "external_crates": {},
+            "target": {"triple": "x86_64-unknown-linux-gnu", "target_features": []},
+            "format_version": 57
+        }"#;
+
+        let (crate_data, items) = LazyItems::parse(json.as_bytes().to_vec()).unwrap();
+        assert!(crate_data.index.is_empty());
+        assert!(items.parsed.get(&Id(0)).is_none());
+
+        let root = items.get(&crate_data.root).unwrap();
+        assert_eq!(root.name.as_deref(), Some("lazy"));
+        assert!(items.parsed.get(&Id(0)).is_some());
+        assert!(items.get_if_contains(&Id(0), "alias").is_none());
+        assert!(items.get(&Id(1)).is_none());
+    }
+}
@@ -5,19 +5,30 @@ pub use rustdoc_types;
 pub mod conversions;
 pub mod crate_name;
 pub mod doc_ref;
+mod docs_summary;
 pub mod iterators;
+mod lazy_items;
+mod method_lookup;
 mod navigator;
+mod parsed_cache;
+pub mod prelude;
+pub mod progress;
+mod resolution;
 mod rustdoc_data;
 pub mod search;
+mod source_files;
 pub mod sources;
 pub mod string_utils;
 
 // Re-export commonly used types
 pub use crate_name::CrateName;
-pub use doc_ref::DocRef;
-pub use navigator::{CrateInfo, Navigator};
-pub use rustdoc_data::RustdocData;
-pub use sources::CrateProvenance;
+pub use doc_ref::{DocRef, ImplOrigin};
+pub use docs_summary::docs_summary;
+pub use method_lookup::MethodMatch;
+pub use navigator::{CrateInfo, Navigator, Suggestion};
+pub use resolution::{ResolutionStep, ResolutionTrace, SegmentOutcome};
+pub use rustdoc_data::{RustdocData, parse_kind_discriminator, parse_rust_version};
+pub use sources::{CrateProvenance, DependencyKind, DependencyScope};
 
 #[cfg(test)]
 mod tests;
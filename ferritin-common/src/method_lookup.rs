@@ -0,0 +1,155 @@
+//! Finding methods that a type can call without defining them itself: trait methods, and
+//! methods of the types it dereferences to
+//!
+//! `String::len` is `str::len`, reached through `impl Deref<Target = str> for String`, and
+//! `TestStruct::clone` is the `Clone` impl's method. Rust's method resolution looks in the same
+//! places, in roughly this order.
+
+use crate::DocRef;
+use fieldwork::Fieldwork;
+use rustdoc_types::{Id, Impl, Item, ItemEnum, Type};
+
+/// How many `Deref` impls are followed before giving up, which only matters for a cycle
+const MAX_DEREFS: usize = 8;
+
+/// A method found beyond an item's own inherent methods
+#[derive(Fieldwork, Debug, Clone)]
+#[fieldwork(get)]
+pub struct MethodMatch<'a> {
+    /// The method
+    #[field(copy)]
+    method: DocRef<'a, Item>,
+    /// The types dereferenced to on the way to the method, in order
+    derefs: Vec<DocRef<'a, Item>>,
+    /// The trait the method is from, if it isn't an inherent method
+    trait_name: Option<String>,
+}
+
+impl<'a> DocRef<'a, Item> {
+    /// Find a method named `name` that isn't one of this type's inherent methods: one from a
+    /// trait this type implements, or one of a type it dereferences to
+    pub fn find_method(&self, name: &str) -> Option<MethodMatch<'a>> {
+        let mut derefs = vec![];
+        let mut current = *self;
+        for _ in 0..=MAX_DEREFS {
+            // This type's own inherent methods are its children, and were already looked at
+            if !derefs.is_empty()
+                && let Some(method) = current.inherent_method(name)
+            {
+                return Some(MethodMatch {
+                    method,
+                    derefs,
+                    trait_name: None,
+                });
+            }
+
+            if let Some((method, trait_name)) = current.trait_method(name) {
+                return Some(MethodMatch {
+                    method,
+                    derefs,
+                    trait_name: Some(trait_name),
+                });
+            }
+
+            current = current.deref_target()?;
+            derefs.push(current);
+        }
+        None
+    }
+
+    /// The type this type dereferences to, from its `Deref` impl
+    pub fn deref_target(&self) -> Option<DocRef<'a, Item>> {
+        let (impl_block, _) = self.impl_blocks().find(|(_, impl_item)| {
+            !impl_item.is_negative
+                && impl_item
+                    .trait_
+                    .as_ref()
+                    .is_some_and(|trait_path| last_segment(&trait_path.path) == "Deref")
+        })?;
+
+        let target =
+            impl_block
+                .impl_block_items(impl_block)
+                .find_map(|item| match item.inner() {
+                    ItemEnum::AssocType {
+                        type_: Some(target),
+                        ..
+                    } if item.name() == Some("Target") => Some(target),
+                    _ => None,
+                })?;
+
+        let primitive = match target {
+            Type::ResolvedPath(path) => return impl_block.get_path(path.id),
+            Type::Primitive(name) => name.as_str(),
+            Type::Slice(_) => "slice",
+            Type::Array { .. } => "array",
+            _ => return None,
+        };
+        self.navigator()
+            .find_path(&format!("std::prim@{primitive}"))
+    }
+
+    fn inherent_method(&self, name: &str) -> Option<DocRef<'a, Item>> {
+        let item = *self;
+        self.impl_blocks()
+            .filter(|(_, impl_item)| impl_item.trait_.is_none())
+            .flat_map(move |(impl_block, _)| item.impl_block_items(impl_block))
+            .find(|method| method.name() == Some(name))
+    }
+
+    /// A method from one of this type's trait impls, either written in the impl or provided
+    /// by the trait, with the trait's name
+    fn trait_method(&self, name: &str) -> Option<(DocRef<'a, Item>, String)> {
+        for (impl_block, impl_item) in self.impl_blocks() {
+            let Some(trait_path) = &impl_item.trait_ else {
+                continue;
+            };
+            if impl_item.is_negative {
+                continue;
+            }
+            let trait_name = last_segment(&trait_path.path).to_string();
+
+            if let Some(method) = self
+                .impl_block_items(impl_block)
+                .find(|method| method.name() == Some(name))
+            {
+                return Some((method, trait_name));
+            }
+
+            if impl_item.provided_trait_methods.iter().any(|m| m == name)
+                && let Some(method) = impl_block.get_path(trait_path.id).and_then(|trait_item| {
+                    trait_item
+                        .members()
+                        .find(|member| member.name() == Some(name))
+                })
+            {
+                return Some((method, trait_name));
+            }
+        }
+        None
+    }
+
+    /// The impl blocks rustdoc lists for a struct, enum, union or primitive, inherent and of
+    /// traits
+    fn impl_blocks(&self) -> impl Iterator<Item = (DocRef<'a, Item>, &'a Impl)> + 'a {
+        let item = *self;
+        let ids: &'a [Id] = match item.inner() {
+            ItemEnum::Struct(struct_item) => &struct_item.impls,
+            ItemEnum::Enum(enum_item) => &enum_item.impls,
+            ItemEnum::Union(union_item) => &union_item.impls,
+            ItemEnum::Primitive(primitive) => &primitive.impls,
+            _ => &[],
+        };
+        ids.iter()
+            .filter_map(move |id| item.get(id))
+            .filter_map(|impl_block| match impl_block.inner() {
+                ItemEnum::Impl(impl_item) => Some((impl_block, impl_item)),
+                _ => None,
+            })
+    }
+}
+
+/// `Deref` for `core::ops::Deref`
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
@@ -3,15 +3,23 @@
 use crate::CrateName;
 use crate::DocRef;
 use crate::RustdocData;
+use crate::prelude::{Edition, is_primitive, prelude_path};
+use crate::progress::{NoProgress, Progress};
+use crate::resolution::{ResolutionStep, ResolutionTrace, SegmentOutcome, record};
+use crate::rustdoc_data::parse_kind_discriminator;
 use crate::search::SearchIndex;
-use crate::sources::{CrateProvenance, DocsRsSource, LocalSource, Source, StdSource};
-use crate::string_utils::case_aware_jaro_winkler;
+use crate::sources::{
+    CrateProvenance, DependencyScope, DocsRsSource, LocalSource, Source, StdSource, VendoredSource,
+};
+use crate::string_utils::{case_aware_jaro_winkler, is_near_miss};
 use elsa::sync::FrozenMap;
 use fieldwork::Fieldwork;
+use rayon::prelude::*;
 use rustdoc_types::{Id, Item, ItemEnum, ItemKind};
 use semver::Version;
 use semver::VersionReq;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Debug;
 use std::path::PathBuf;
@@ -28,6 +36,25 @@ pub struct Suggestion<'a> {
     score: f64,
 }
 
+impl Suggestion<'_> {
+    /// The first sentence of the suggested item's docs, to show alongside the best suggestion
+    /// so that a near miss doesn't need a second lookup
+    pub fn summary(&self) -> Option<String> {
+        self.item.and_then(|item| item.docs_summary())
+    }
+}
+
+/// How much a suggestion's score is scaled by when a discriminator like `struct@` was given
+/// and the suggestion is a different kind of item
+const KIND_MISMATCH_WEIGHT: f64 = 0.9;
+
+/// The least a suggestion from elsewhere in the crate is scaled by for sharing none of the
+/// modules that were typed, rising to 1 for sharing all of them
+const UNRELATED_MODULE_WEIGHT: f64 = 0.9;
+
+/// How similar a name from elsewhere in the crate has to be to the typed name to be suggested
+const MIN_INDEX_NAME_SCORE: f64 = 0.8;
+
 /// Parse a docs.rs URL to extract crate name and version
 ///
 /// Examples:
@@ -66,7 +93,35 @@ pub struct CrateInfo {
     pub(crate) name: String,
     pub(crate) default_crate: bool,
     pub(crate) used_by: Vec<String>,
+    /// The dependency tables this crate is declared in, for local dependencies
+    pub(crate) dependency_scopes: Vec<DependencyScope>,
     pub(crate) json_path: Option<PathBuf>,
+    /// Path to the package's Cargo.toml, for crates that are available on disk
+    pub(crate) manifest_path: Option<PathBuf>,
+    /// The features the package declares, with the features and dependencies each one enables,
+    /// for crates in the workspace's dependency graph
+    pub(crate) features: BTreeMap<String, Vec<String>>,
+}
+
+impl CrateInfo {
+    /// Labels for the build, dev, or target-specific tables this dependency comes from
+    ///
+    /// Empty for crates that are an unconditional `[dependencies]` entry anywhere in the
+    /// workspace, since those are available regardless of how else they are used.
+    pub fn dependency_labels(&self) -> Vec<String> {
+        if self
+            .dependency_scopes
+            .iter()
+            .any(DependencyScope::is_unconditional_normal)
+        {
+            return vec![];
+        }
+
+        self.dependency_scopes
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
 }
 
 /// Navigator orchestrates documentation lookup across multiple sources
@@ -75,6 +130,8 @@ pub struct CrateInfo {
 /// 1. std (if crate name matches RUST_CRATES)
 /// 2. local (if LocalSource is present and has the crate)
 /// 3. docs.rs (if DocsRsSource is present)
+/// 4. downloaded registry sources, built locally (if VendoredSource is present), for crates
+///    that docs.rs has no rustdoc JSON for
 #[derive(Fieldwork, Default)]
 #[fieldwork(get, opt_in, with)]
 pub struct Navigator {
@@ -84,22 +141,41 @@ pub struct Navigator {
     docsrs_source: Option<DocsRsSource>,
     #[field]
     local_source: Option<LocalSource>,
+    #[field]
+    vendored_source: Option<VendoredSource>,
+
+    /// Resolve each path segment only by its name or a doc alias, never by a near miss in
+    /// case or spelling, for scripts that need a typo to fail
+    #[field]
+    exact_paths: bool,
 
-    /// Cached docs.
+    /// Cached docs, keyed by crate name and version, like `syn@2.0.100`, so that each version
+    /// of a crate in the dependency graph is kept apart.
     ///
     /// This is the only place in all of ferritin-common that stores RustdocData, and
     /// all references to &'a RustdocData or DocRef<'a> are borrowing from this map.
     ///
     /// A None value indicates permanent failure.
-    working_set: FrozenMap<CrateName<'static>, Box<Option<RustdocData>>>,
+    pub(crate) working_set: FrozenMap<CrateName<'static>, Box<Option<RustdocData>>>,
+
+    /// Which working set entry each crate name and version requirement that was asked for
+    /// resolved to, keyed by [`Navigator::working_set_key`]
+    resolved_keys: FrozenMap<CrateName<'static>, Box<CrateName<'static>>>,
 
     /// Map from internal name (underscores) to real name/version from external_crates
+    ///
+    /// Each crate is under its name and exact version, like `syn@=1.0.109`, for links to the
+    /// version a crate was built against, and under its name alone for paths without a version,
+    /// where the first crate to mention it decides.
     external_crate_names: FrozenMap<CrateName<'static>, Box<ExternalCrateInfo>>,
 
     /// Cached search indexes, built lazily on first search.
     ///
     /// A None value indicates permanent failure to build index.
     pub(crate) search_indexes: FrozenMap<CrateName<'static>, Box<Option<SearchIndex>>>,
+
+    /// Told about downloads, doc builds and indexing as they happen
+    progress: Option<Box<dyn Progress>>,
 }
 
 impl Debug for Navigator {
@@ -108,10 +184,22 @@ impl Debug for Navigator {
             .field("std_source", &self.std_source)
             .field("docsrs_source", &self.docsrs_source)
             .field("local_source", &self.local_source)
+            .field("vendored_source", &self.vendored_source)
             .finish()
     }
 }
 impl Navigator {
+    /// Report slow operations, like downloading or building docs, to `progress`
+    pub fn with_progress(mut self, progress: impl Progress + 'static) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Where slow operations are reported
+    pub fn progress(&self) -> &dyn Progress {
+        self.progress.as_deref().unwrap_or(&NoProgress)
+    }
+
     /// List all available crate names from all sources
     /// Returns crate names from std library and local workspace/dependencies
     pub fn list_available_crates(&self) -> impl Iterator<Item = &CrateInfo> {
@@ -132,6 +220,7 @@ impl Navigator {
             .and_then(|s| s.lookup(name, version))
             .or_else(|| self.local_source().and_then(|s| s.lookup(name, version)))
             .or_else(|| self.docsrs_source().and_then(|s| s.lookup(name, version)))
+            .or_else(|| self.vendored_source().and_then(|s| s.lookup(name, version)))
     }
 
     /// Get the project root path if a local context exists
@@ -139,93 +228,301 @@ impl Navigator {
         self.local_source.as_ref().map(|p| p.project_root())
     }
 
+    /// The workspace's edition, which decides which names are in the prelude. Without a
+    /// workspace, this is the latest edition.
+    pub fn edition(&self) -> Edition {
+        self.local_source
+            .as_ref()
+            .map(|local| *local.edition())
+            .unwrap_or_default()
+    }
+
     /// Resolve a path like "std::vec::Vec" or "tokio::runtime::Runtime"
     /// or (custom format for this crate) "tokio@1::runtime::Runtime" or "serde@1.0.228::de"
     ///
     /// This is the primary string entrypoint for any user-generated crate or type specification
     pub fn resolve_path<'a>(
         &'a self,
-        mut path: &str,
+        path: &str,
         suggestions: &mut Vec<Suggestion<'a>>,
+    ) -> Option<DocRef<'a, Item>> {
+        self.resolve_path_inner(path, &mut Some(suggestions), &mut None)
+    }
+
+    /// Resolve a path written in docs or code, like the source of a re-export or the target of
+    /// a link, rather than typed by a user
+    ///
+    /// The path is taken as written: there are no near-miss matches, and no suggestions are
+    /// worked out when it doesn't resolve.
+    pub fn find_path<'a>(&'a self, path: &str) -> Option<DocRef<'a, Item>> {
+        self.resolve_path_inner(path, &mut None, &mut None)
+    }
+
+    /// Resolve a path as [`Navigator::resolve_path`] does, recording each step attempted
+    ///
+    /// Used to explain why a path failed to resolve.
+    pub fn resolve_path_traced<'a>(
+        &'a self,
+        path: &str,
+        suggestions: &mut Vec<Suggestion<'a>>,
+        trace: &mut ResolutionTrace,
+    ) -> Option<DocRef<'a, Item>> {
+        self.resolve_path_inner(path, &mut Some(suggestions), &mut Some(trace))
+    }
+
+    /// Resolve `path`, collecting suggestions when it doesn't resolve if `suggestions` is
+    /// given. Without suggestions the path was written rather than typed, and near misses
+    /// aren't matched.
+    fn resolve_path_inner<'a>(
+        &'a self,
+        mut path: &str,
+        suggestions: &mut Option<&mut Vec<Suggestion<'a>>>,
+        trace: &mut Option<&mut ResolutionTrace>,
     ) -> Option<DocRef<'a, Item>> {
         if let Some(p) = path.strip_prefix("::") {
             path = p;
         }
 
+        // `keyword:match` names the docs of a keyword rather than an item
+        if let Some(keyword) = path.strip_prefix("keyword:") {
+            return self.resolve_path_inner(&format!("std::keyword@{keyword}"), suggestions, trace);
+        }
+
         let (crate_specifier, path_start_index) = if let Some(first_scope) = path.find("::") {
             (&path[..first_scope], Some(first_scope + 2))
         } else {
             (path, None)
         };
 
-        let (crate_name, version_req) = if let Some(at) = crate_specifier.find("@") {
-            (
-                &crate_specifier[..at],
-                VersionReq::parse(&crate_specifier[at + 1..]).unwrap_or(VersionReq::STAR),
-            )
-        } else {
-            (crate_specifier, VersionReq::STAR)
-        };
+        // Bare prelude names like `Option::map` resolve through std, unless the workspace has
+        // a crate of the same name
+        if !crate_specifier.contains('@')
+            && let Some(prelude_path) = prelude_path(crate_specifier, self.edition())
+            && self
+                .local_source()
+                .is_none_or(|local| local.lookup(crate_specifier, &VersionReq::STAR).is_none())
+        {
+            record(trace, || ResolutionStep::Prelude {
+                name: crate_specifier.to_string(),
+                edition: self.edition(),
+                path: prelude_path.to_string(),
+            });
+            let rest = path_start_index.map_or("", |index| &path[index - 2..]);
+            return self.resolve_path_inner(&format!("{prelude_path}{rest}"), suggestions, trace);
+        }
 
-        let Some(crate_data) = self.load_crate(crate_name, &version_req) else {
-            suggestions.extend(self.list_available_crates().map(|crate_info| Suggestion {
-                path: crate_info.name.clone(),
-                item: None,
-                score: case_aware_jaro_winkler(&crate_info.name, crate_name),
-            }));
+        // Primitive names like `str::trim` or `u32::MAX` resolve to the primitive's std docs,
+        // and otherwise to std's module of the same name, like `str::from_utf8`
+        if !crate_specifier.contains('@')
+            && is_primitive(crate_specifier)
+            && self
+                .local_source()
+                .is_none_or(|local| local.lookup(crate_specifier, &VersionReq::STAR).is_none())
+        {
+            let primitive_path = format!("std::prim@{crate_specifier}");
+            record(trace, || ResolutionStep::Primitive {
+                name: crate_specifier.to_string(),
+                path: primitive_path.clone(),
+            });
+            let rest = path_start_index.map_or("", |index| &path[index - 2..]);
+            let mut primitive_suggestions = suggestions.is_some().then(Vec::new);
+            if let Some(item) = self.resolve_path_inner(
+                &format!("{primitive_path}{rest}"),
+                &mut primitive_suggestions.as_mut(),
+                trace,
+            ) {
+                return Some(item);
+            }
+            let resolved = self.resolve_path_inner(
+                &format!("std::{crate_specifier}{rest}"),
+                suggestions,
+                trace,
+            );
+            if resolved.is_none() {
+                suggest(suggestions, || primitive_suggestions.into_iter().flatten());
+            }
+            return resolved;
+        }
+
+        let (crate_name, version_req) = parse_crate_specifier(crate_specifier);
+
+        let crate_data = self.load_crate(crate_name, &version_req);
+        record(trace, || ResolutionStep::Crate {
+            requested: crate_name.to_string(),
+            canonical: self.canonicalize(crate_name).to_string(),
+            version_req: version_req.clone(),
+            loaded: crate_data.map(|data| (data.name.clone(), data.version.clone())),
+        });
+
+        let Some(crate_data) = crate_data else {
+            // A bare name that isn't a crate may be a keyword, like `match` or `async`
+            if path_start_index.is_none() && !crate_specifier.contains('@') {
+                let keyword_path = format!("std::keyword@{crate_specifier}");
+                let keyword = self.resolve_path_inner(&keyword_path, &mut None, &mut None);
+                record(trace, || ResolutionStep::Keyword {
+                    name: crate_specifier.to_string(),
+                    path: keyword_path.clone(),
+                    found: keyword.is_some(),
+                });
+                if keyword.is_some() {
+                    return keyword;
+                }
+            }
+
+            suggest(suggestions, || {
+                self.list_available_crates().map(|crate_info| Suggestion {
+                    path: crate_info.name.clone(),
+                    item: None,
+                    score: case_aware_jaro_winkler(&crate_info.name, crate_name),
+                })
+            });
             return None;
         };
 
         // Start from crate root
         let item = crate_data.get(self, &crate_data.root)?;
-        if let Some(path_start_index) = path_start_index {
-            // Try tree traversal first: this returns the canonical public item (e.g. the
-            // re-exported module, not the primitive of the same name).
-            if let Some(item) =
-                self.find_children_recursive(item, path, path_start_index, suggestions)
-            {
-                return Some(item);
-            }
+        let Some(path_start_index) = path_start_index else {
+            return Some(item);
+        };
+
+        let resolved =
+            self.resolve_in_crate(crate_data, item, path, path_start_index, suggestions, trace);
+        if resolved.is_none() {
+            suggest(suggestions, || {
+                self.index_suggestions(crate_data, crate_specifier, &path[path_start_index..])
+            });
+        }
+        resolved
+    }
 
-            let suffix = &path[path_start_index..];
+    /// Resolve the part of `path` after the crate, starting from the crate's root
+    fn resolve_in_crate<'a>(
+        &'a self,
+        crate_data: &'a RustdocData,
+        item: DocRef<'a, Item>,
+        path: &str,
+        path_start_index: usize,
+        suggestions: &mut Option<&mut Vec<Suggestion<'a>>>,
+        trace: &mut Option<&mut ResolutionTrace>,
+    ) -> Option<DocRef<'a, Item>> {
+        // Try tree traversal first: this returns the canonical public item (e.g. the
+        // re-exported module, not the primitive of the same name).
+        if let Some(item) =
+            self.find_children_recursive(item, path, path_start_index, suggestions, trace)
+        {
+            return Some(item);
+        }
 
-            // Fallback: check the reverse path index for items whose ItemSummary::path passes
-            // through private modules that don't appear as children in the public item tree,
-            // making tree traversal fail for those paths.
-            if let Some(item) = crate_data
+        let suffix = &path[path_start_index..];
+
+        // Fallback: check the reverse path index for items whose ItemSummary::path passes
+        // through private modules that don't appear as children in the public item tree,
+        // making tree traversal fail for those paths.
+        let indexed = crate_data
+            .path_to_id
+            .get(suffix)
+            .and_then(|id| crate_data.item(id))
+            .map(|item| DocRef::new(self, crate_data, item));
+        record(trace, || ResolutionStep::PathIndex {
+            key: suffix.to_string(),
+            found: indexed.is_some(),
+        });
+        if let Some(item) = indexed {
+            return Some(item);
+        }
+
+        // Second fallback: for items absent from rustdoc's paths map (e.g. inherent
+        // methods; rust-lang/rust#152511) whose parent IS in the index. Strip the last
+        // segment, look up the remainder in path_to_id, then traverse into the child.
+        // One level of stripping is sufficient: only impl-block items are orphaned, and
+        // their parents (structs/enums/traits) always have an ItemSummary entry.
+        if let Some(sep) = suffix.rfind("::") {
+            let parent_suffix = &suffix[..sep];
+            let child_start = path_start_index + sep + 2;
+            let parent_item = crate_data
                 .path_to_id
-                .get(suffix)
-                .and_then(|&id| crate_data.index.get(&id))
-                .map(|item| DocRef::new(self, crate_data, item))
-            {
-                return Some(item);
+                .get(parent_suffix)
+                .and_then(|parent_id| crate_data.item(parent_id));
+            record(trace, || ResolutionStep::ParentPathIndex {
+                key: parent_suffix.to_string(),
+                found: parent_item.is_some(),
+            });
+            if let Some(parent_item) = parent_item {
+                let parent_ref = DocRef::new(self, crate_data, parent_item);
+                return self.find_children_recursive(
+                    parent_ref,
+                    path,
+                    child_start,
+                    suggestions,
+                    trace,
+                );
             }
+        }
 
-            // Second fallback: for items absent from rustdoc's paths map (e.g. inherent
-            // methods; rust-lang/rust#152511) whose parent IS in the index. Strip the last
-            // segment, look up the remainder in path_to_id, then traverse into the child.
-            // One level of stripping is sufficient: only impl-block items are orphaned, and
-            // their parents (structs/enums/traits) always have an ItemSummary entry.
-            if let Some(sep) = suffix.rfind("::") {
-                let parent_suffix = &suffix[..sep];
-                let child_start = path_start_index + sep + 2;
-                if let Some(&parent_id) = crate_data.path_to_id.get(parent_suffix)
-                    && let Some(parent_item) = crate_data.index.get(&parent_id)
-                {
-                    let parent_ref = DocRef::new(self, crate_data, parent_item);
-                    return self.find_children_recursive(
-                        parent_ref,
-                        path,
-                        child_start,
-                        suggestions,
-                    );
-                }
+        None
+    }
+
+    /// Suggestions from anywhere in the crate for a path whose last segment didn't resolve,
+    /// such as the path an item is re-exported at when the module it's defined in was typed.
+    /// Names like the typed one rank first, and among those the ones in modules sharing more
+    /// of the typed path.
+    fn index_suggestions<'a>(
+        &'a self,
+        crate_data: &'a RustdocData,
+        crate_specifier: &str,
+        suffix: &str,
+    ) -> Vec<Suggestion<'a>> {
+        let mut segments = suffix
+            .split("::")
+            .map(parse_discriminated_segment)
+            .collect::<Vec<_>>();
+        let Some((kind_filter, name)) = segments.pop() else {
+            return vec![];
+        };
+
+        let mut suggestions = vec![];
+        // Kind-qualified keys name the same items as the plain ones
+        for (key, id) in crate_data
+            .path_to_id
+            .iter()
+            .filter(|(key, _)| !key.contains('@'))
+        {
+            let mut candidate_segments = key.split("::").collect::<Vec<_>>();
+            let Some(candidate_name) = candidate_segments.pop() else {
+                continue;
+            };
+            let name_score = case_aware_jaro_winkler(name, candidate_name);
+            if name_score < MIN_INDEX_NAME_SCORE {
+                continue;
             }
+            let Some(item) = crate_data.item(id) else {
+                continue;
+            };
+            let item = DocRef::new(self, crate_data, item);
 
-            None
-        } else {
-            Some(item)
+            let shared = segments
+                .iter()
+                .zip(&candidate_segments)
+                .take_while(|((_, typed), candidate)| typed == *candidate)
+                .count();
+            let proximity = if segments.is_empty() {
+                1.0
+            } else {
+                shared as f64 / segments.len() as f64
+            };
+            let mut score = name_score
+                * (UNRELATED_MODULE_WEIGHT + (1.0 - UNRELATED_MODULE_WEIGHT) * proximity);
+            if kind_filter.is_some_and(|kind| kind != item.kind()) {
+                score *= KIND_MISMATCH_WEIGHT;
+            }
+
+            suggestions.push(Suggestion {
+                path: format!("{crate_specifier}::{key}"),
+                item: Some(item),
+                score,
+            });
         }
+        suggestions
     }
 
     pub fn canonicalize(&self, name: &str) -> CrateName<'static> {
@@ -236,6 +533,21 @@ impl Navigator {
             .unwrap_or_else(|| CrateName::from(String::from(name)))
     }
 
+    /// The key a request for a crate is remembered under: its name, with the version
+    /// requirement when there is one, like `serde@=1.0.100`
+    pub(crate) fn working_set_key(
+        &self,
+        name: &str,
+        version_req: &VersionReq,
+    ) -> CrateName<'static> {
+        let crate_name = self.canonicalize(name);
+        if *version_req == VersionReq::STAR {
+            crate_name
+        } else {
+            CrateName::from(format!("{crate_name}@{version_req}"))
+        }
+    }
+
     /// Load a crate by name and optional version
     ///
     /// If version is None:
@@ -245,8 +557,11 @@ impl Navigator {
     ///
     /// Returns None if the crate cannot be found in any source
     pub fn load_crate(&self, name: &str, version_req: &VersionReq) -> Option<&RustdocData> {
-        let crate_name = self.canonicalize(name);
-        if let Some(data) = self.working_set.get(&crate_name) {
+        let is_versioned = *version_req != VersionReq::STAR;
+        let crate_name = self.working_set_key(name, version_req);
+        if let Some(key) = self.resolved_keys.get(&crate_name)
+            && let Some(data) = self.working_set.get(key)
+        {
             return data.as_ref();
         }
 
@@ -255,11 +570,12 @@ impl Navigator {
         let (resolved_name, resolved_version, provenance_hint) =
             if let Some(external_crate) = self.external_crate_names.get(&crate_name) {
                 log::debug!("Found {crate_name} in external_crates");
-                (
-                    external_crate.name.to_string(),
-                    Some(external_crate.version.clone()),
-                    None,
-                )
+                let pinned = self
+                    .local_source()
+                    .and_then(|s| s.pinned_version(&external_crate.name))
+                    .filter(|_| !is_versioned);
+                let version = pinned.unwrap_or(&external_crate.version);
+                (external_crate.name.to_string(), Some(version.clone()), None)
             } else {
                 let lookup_result = self.lookup_crate(name, version_req)?;
                 (
@@ -270,11 +586,18 @@ impl Navigator {
             };
 
         // Try loading from the appropriate source based on provenance
-        if let Some(rv) = resolved_version.as_ref() {
-            log::info!("Resolved {resolved_name}@{rv}");
-        } else {
-            log::info!("Resolved {resolved_name}");
+        let key = match resolved_version.as_ref() {
+            Some(rv) => CrateName::from(format!("{resolved_name}@{rv}")),
+            None => CrateName::from(resolved_name.clone()),
+        };
+        log::info!("Resolved {key}");
+
+        // Another request, like one without a version, may have loaded the same version
+        if let Some(data) = self.working_set.get(&key) {
+            self.resolved_keys.insert(crate_name, Box::new(key));
+            return data.as_ref();
         }
+
         let start = std::time::Instant::now();
         let result = self.load(&resolved_name, resolved_version.as_ref(), provenance_hint);
         let elapsed = start.elapsed();
@@ -285,23 +608,125 @@ impl Navigator {
                 // Index external crates for future lookups
                 self.index_external_crates(&data);
 
-                // Build reverse path index before caching
-                data.build_path_index();
+                // Build reverse path index before caching, unless it was read along with the
+                // crate from the parsed docs cache
+                if data.path_to_id.is_empty() {
+                    data.build_path_index();
+                }
+
+                // Links out of a version other than the one the name loads keep to it
+                data.versioned_paths = is_versioned && data.version != self.default_version(name);
 
                 // Cache in working set
-                self.working_set
-                    .insert(CrateName::from(resolved_name), Box::new(Some(data)))
-                    .as_ref()
+                self.resolved_keys.insert(crate_name, Box::new(key.clone()));
+                self.working_set.insert(key, Box::new(Some(data))).as_ref()
             }
+            // A cancelled load isn't a failure, and is tried again the next time
+            None if self.progress().is_cancelled() => None,
             None => {
-                // // Mark as failed
-                self.working_set
-                    .insert(CrateName::from(resolved_name), Box::new(None));
+                // Mark as failed
+                self.resolved_keys.insert(crate_name, Box::new(key.clone()));
+                self.working_set.insert(key, Box::new(None));
                 None
             }
         }
     }
 
+    /// The version of a crate that loading it by name alone gives
+    fn default_version(&self, name: &str) -> Option<Version> {
+        if let Some(external_crate) = self.external_crate_names.get(&self.canonicalize(name)) {
+            let version = self
+                .local_source()
+                .and_then(|s| s.pinned_version(&external_crate.name))
+                .unwrap_or(&external_crate.version);
+            return Some(version.clone());
+        }
+        self.lookup_crate(name, &VersionReq::STAR)?.version.clone()
+    }
+
+    /// Load several crates in parallel, so that later lookups find them already parsed
+    ///
+    /// Each name may carry a version, like `serde@1.0.100`. Crates that are already in the
+    /// working set, or that failed to load before, are skipped. Parsing rustdoc JSON is the
+    /// slow part of loading a crate, so this is worth calling ahead of time for crates that are
+    /// likely to be needed soon, like those linked from the page being shown.
+    pub fn prefetch(&self, crate_names: &[&str]) {
+        crate_names.par_iter().for_each(|crate_name| {
+            let (crate_name, version_req) = parse_crate_specifier(crate_name);
+            if self
+                .resolved_keys
+                .get(&self.working_set_key(crate_name, &version_req))
+                .is_none()
+            {
+                self.load_crate(crate_name, &version_req);
+            }
+        });
+    }
+
+    /// Complete a partly typed path, like `tokio::sy` to `tokio::sync`, for shell completion
+    ///
+    /// A path without `::` is completed to the names of available crates. Otherwise the last
+    /// segment is completed to the children of the path before it: those found by prefix
+    /// matching over the crate's path index, along with re-exported items, which the index
+    /// doesn't hold. Candidates keep the path as it was typed, and come back sorted.
+    pub fn complete_path(&self, partial: &str) -> Vec<String> {
+        let Some((parent, prefix)) = partial.rsplit_once("::") else {
+            let mut names = self
+                .list_available_crates()
+                .map(|crate_info| crate_info.name())
+                .filter(|name| name.starts_with(partial))
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            names.sort();
+            names.dedup();
+            return names;
+        };
+
+        let (crate_segment, module_path) = parent.split_once("::").unwrap_or((parent, ""));
+        let Some(crate_root) = self.find_path(crate_segment) else {
+            return vec![];
+        };
+
+        // Kind-qualified keys like `sync::fn@channel` give the name of items that share it
+        // with an item of another kind, which have no unqualified key
+        let module_prefix = if module_path.is_empty() {
+            String::new()
+        } else {
+            format!("{module_path}::")
+        };
+        let mut names = crate_root
+            .crate_docs()
+            .path_to_id
+            .keys()
+            .filter_map(|key| {
+                let rest = key.strip_prefix(&module_prefix)?;
+                if rest.contains("::") {
+                    return None;
+                }
+                Some(rest.rsplit_once('@').map_or(rest, |(_, name)| name))
+            })
+            .filter(|name| name.starts_with(prefix))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        if let Some(parent_item) = self.find_path(parent) {
+            names.extend(
+                parent_item
+                    .child_items()
+                    .filter_map(|child| child.name())
+                    .filter(|name| name.starts_with(prefix))
+                    .map(str::to_string),
+            );
+        }
+
+        names.sort();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|name| format!("{parent}::{name}"))
+            .collect()
+    }
+
     /// Try loading from the appropriate source based on lookup result
     fn load(
         &self,
@@ -312,27 +737,51 @@ impl Navigator {
         match provenance_hint {
             Some(CrateProvenance::Std) => {
                 log::debug!("loading from std");
-                self.std_source()?.load(crate_name, version)
+                self.std_source()?
+                    .load(crate_name, version, self.progress())
             }
             Some(CrateProvenance::Workspace | CrateProvenance::LocalDependency) => {
                 log::debug!("loading from local");
-                self.local_source()?.load(crate_name, version)
+                let local_source = self.local_source()?;
+
+                // Crates from a private registry come from its docs server if it has one,
+                // and are built locally otherwise
+                if let Some(registry) = local_source.registry(crate_name)
+                    && let Some(version) = version
+                    && let Some(data) = self.docsrs_source().and_then(|s| {
+                        s.load_from_registry(crate_name, version, registry, self.progress())
+                    })
+                {
+                    return Some(data);
+                }
+
+                local_source.load(crate_name, version, self.progress())
             }
             Some(CrateProvenance::DocsRs) => {
                 log::debug!("loading from docs.rs");
-                self.docsrs_source()?.load(crate_name, version)
+                self.docsrs_source()
+                    .and_then(|s| s.load(crate_name, version, self.progress()))
+                    .or_else(|| {
+                        log::debug!("docs.rs has no docs, building from the registry source");
+                        self.vendored_source()?
+                            .load(crate_name, version, self.progress())
+                    })
             }
             None => {
                 log::debug!("No provenance hint available, cascading lookup for {crate_name}");
                 self.std_source()
-                    .and_then(|s| s.load(crate_name, version))
+                    .and_then(|s| s.load(crate_name, version, self.progress()))
                     .or_else(|| {
                         self.local_source()
-                            .and_then(|s| s.load(crate_name, version))
+                            .and_then(|s| s.load(crate_name, version, self.progress()))
                     })
                     .or_else(|| {
                         self.docsrs_source()
-                            .and_then(|s| s.load(crate_name, version))
+                            .and_then(|s| s.load(crate_name, version, self.progress()))
+                    })
+                    .or_else(|| {
+                        self.vendored_source()
+                            .and_then(|s| s.load(crate_name, version, self.progress()))
                     })
             }
         }
@@ -347,10 +796,13 @@ impl Navigator {
                 && let Ok(version) = Version::parse(version)
             {
                 log::trace!("{}@{}", real_name, version);
+                let versioned = CrateName::from(format!("{}@={version}", external.name));
                 let info = ExternalCrateInfo {
                     name: real_name.to_string(),
                     version,
                 };
+                self.external_crate_names
+                    .insert(versioned, Box::new(info.clone()));
                 self.external_crate_names
                     .insert(CrateName::from(external.name.clone()), Box::new(info));
             }
@@ -364,7 +816,8 @@ impl Navigator {
         ids: &[u32],
     ) -> Option<(DocRef<'a, Item>, Vec<&'a str>)> {
         let mut path = vec![];
-        let crate_docs = self.load_crate(crate_name, &VersionReq::STAR)?;
+        let (crate_name, version_req) = parse_crate_specifier(crate_name);
+        let crate_docs = self.load_crate(crate_name, &version_req)?;
         let mut item = crate_docs.get(self, &crate_docs.root)?;
         path.push(item.crate_docs().name());
         for id in ids {
@@ -373,7 +826,7 @@ impl Navigator {
                 item = use_item
                     .id
                     .and_then(|id| item.get(&id))
-                    .or_else(|| item.navigator().resolve_path(&use_item.source, &mut vec![]))?;
+                    .or_else(|| item.navigator().find_path(&use_item.source))?;
                 if !use_item.is_glob {
                     item.set_name(&use_item.name);
                 }
@@ -390,7 +843,8 @@ impl Navigator {
         item: DocRef<'a, Item>,
         path: &str,
         index: usize,
-        suggestions: &mut Vec<Suggestion<'a>>,
+        suggestions: &mut Option<&mut Vec<Suggestion<'a>>>,
+        trace: &mut Option<&mut ResolutionTrace>,
     ) -> Option<DocRef<'a, Item>> {
         let remaining = &path[path.len().min(index)..];
         if remaining.is_empty() {
@@ -412,34 +866,149 @@ impl Navigator {
             &path[next_segment_start..]
         );
 
-        for child in item.child_items() {
+        let parent = path[..index].trim_end_matches("::");
+        let segment_step = |outcome: SegmentOutcome| ResolutionStep::Segment {
+            parent: parent.to_string(),
+            name: segment_name.to_string(),
+            kind_filter,
+            outcome,
+        };
+
+        // A trait's associated items, like `Iterator::Item`, aren't among its children
+        let trait_items = matches!(item.inner(), ItemEnum::Trait(_))
+            .then(|| item.members())
+            .into_iter()
+            .flatten();
+        for child in item.child_items().chain(trait_items) {
             if let Some(name) = child.name()
                 && name == segment_name
                 && kind_filter.map_or(true, |k| child.kind() == k)
-                && let Some(child) =
-                    self.find_children_recursive(child, path, next_segment_start, suggestions)
+            {
+                record(trace, || {
+                    segment_step(SegmentOutcome::Matched(child.kind()))
+                });
+                if let Some(child) = self.find_children_recursive(
+                    child,
+                    path,
+                    next_segment_start,
+                    suggestions,
+                    trace,
+                ) {
+                    return Some(child);
+                }
+            }
+        }
+
+        // Fall back to doc aliases, e.g. `std::fs::mkdir` → `std::fs::create_dir`
+        for child in item.child_items() {
+            if child.doc_aliases().any(|alias| alias == segment_name)
+                && kind_filter.map_or(true, |k| child.kind() == k)
+            {
+                record(trace, || {
+                    segment_step(SegmentOutcome::MatchedAlias {
+                        name: child.name().unwrap_or_default().to_string(),
+                        kind: child.kind(),
+                    })
+                });
+                if let Some(child) = self.find_children_recursive(
+                    child,
+                    path,
+                    next_segment_start,
+                    suggestions,
+                    trace,
+                ) {
+                    return Some(child);
+                }
+            }
+        }
+
+        // Fall back to a method from a trait impl or a `Deref` target, e.g. `String::len` →
+        // `str::len`
+        if let Some(method_match) = item.find_method(segment_name)
+            && kind_filter.map_or(true, |k| method_match.method().kind() == k)
+        {
+            let method = method_match.method();
+            record(trace, || {
+                segment_step(SegmentOutcome::MatchedMethod {
+                    kind: method.kind(),
+                    derefs: method_match
+                        .derefs()
+                        .iter()
+                        .map(|target| target.name().unwrap_or_default().to_string())
+                        .collect(),
+                    trait_name: method_match.trait_name().clone(),
+                })
+            });
+            if let Some(child) =
+                self.find_children_recursive(method, path, next_segment_start, suggestions, trace)
             {
                 return Some(child);
             }
         }
 
-        suggestions.extend(self.generate_suggestions(item, path, index));
+        // Fall back to a near miss, e.g. `tokio::sync::mutex` → `tokio::sync::Mutex`, as long as
+        // there is only one and the path was typed rather than written
+        if !self.exact_paths
+            && suggestions.is_some()
+            && let Some(child) = near_miss_child(item, segment_name, kind_filter)
+        {
+            record(trace, || {
+                segment_step(SegmentOutcome::MatchedNearMiss {
+                    name: child.name().unwrap_or_default().to_string(),
+                    kind: child.kind(),
+                })
+            });
+            if let Some(child) =
+                self.find_children_recursive(child, path, next_segment_start, suggestions, trace)
+            {
+                return Some(child);
+            }
+        }
+
+        record(trace, || {
+            segment_step(SegmentOutcome::NotFound {
+                children: item.child_items().count(),
+                kind_mismatches: item
+                    .child_items()
+                    .filter(|child| {
+                        child.name() == Some(segment_name)
+                            && kind_filter.is_some_and(|k| child.kind() != k)
+                    })
+                    .map(|child| child.kind())
+                    .collect(),
+            })
+        });
+
+        suggest(suggestions, || {
+            self.generate_suggestions(item, path, index, kind_filter)
+        });
         None
     }
 
+    /// Suggestions among the children of `item`, the deepest item the typed path reached
     fn generate_suggestions<'a>(
         &'a self,
         item: DocRef<'a, Item>,
         path: &str,
         index: usize,
+        kind_filter: Option<ItemKind>,
     ) -> impl Iterator<Item = Suggestion<'a>> {
+        // A discriminator like `struct@` isn't part of any suggested path
+        let typed_path = path
+            .split("::")
+            .map(|segment| parse_discriminated_segment(segment).1)
+            .collect::<Vec<_>>()
+            .join("::");
         item.child_items().filter_map(move |item| {
             item.name().and_then(|name| {
                 let full_path = format!("{}{name}", &path[..index]);
                 if path.starts_with(&full_path) {
                     None
                 } else {
-                    let score = case_aware_jaro_winkler(path, &full_path);
+                    let mut score = case_aware_jaro_winkler(&typed_path, &full_path);
+                    if kind_filter.is_some_and(|kind| kind != item.kind()) {
+                        score *= KIND_MISMATCH_WEIGHT;
+                    }
                     Some(Suggestion {
                         path: full_path,
                         score,
@@ -451,6 +1020,87 @@ impl Navigator {
     }
 }
 
+/// Add the suggestions `make` works out to `suggestions`, when they're wanted, leaving out
+/// paths already suggested
+fn suggest<'a, I: IntoIterator<Item = Suggestion<'a>>>(
+    suggestions: &mut Option<&mut Vec<Suggestion<'a>>>,
+    make: impl FnOnce() -> I,
+) {
+    let Some(suggestions) = suggestions else {
+        return;
+    };
+    for suggestion in make() {
+        if !suggestions
+            .iter()
+            .any(|known| known.path == suggestion.path)
+        {
+            suggestions.push(suggestion);
+        }
+    }
+}
+
+/// Names shorter than this only match a segment that differs in case, since one wrong
+/// character in a short name may well be a different name
+const MIN_TYPO_LENGTH: usize = 4;
+
+/// The one child of `item` whose name is a near miss for `name`, preferring a child that
+/// differs only in case. Two equally near children are ambiguous, and give `None`.
+fn near_miss_child<'a>(
+    item: DocRef<'a, Item>,
+    name: &str,
+    kind_filter: Option<ItemKind>,
+) -> Option<DocRef<'a, Item>> {
+    let mut case_matches = vec![];
+    let mut typo_matches = vec![];
+    for child in item.child_items() {
+        // A child with exactly this name was already tried
+        let Some(child_name) = child.name().filter(|child_name| *child_name != name) else {
+            continue;
+        };
+        if kind_filter.is_some_and(|kind| child.kind() != kind) {
+            continue;
+        }
+        let matches = if child_name.eq_ignore_ascii_case(name) {
+            &mut case_matches
+        } else if name.len() >= MIN_TYPO_LENGTH && is_near_miss(child_name, name) {
+            &mut typo_matches
+        } else {
+            continue;
+        };
+        // Glob re-exports can yield the same item twice
+        if !matches
+            .iter()
+            .any(|known: &DocRef<'a, Item>| std::ptr::eq(known.item(), child.item()))
+        {
+            matches.push(child);
+        }
+    }
+
+    [case_matches, typo_matches]
+        .into_iter()
+        .find(|matches| !matches.is_empty())
+        .and_then(|matches| match matches[..] {
+            [only] => Some(only),
+            _ => None,
+        })
+}
+
+/// Split a crate specifier like `serde@1.0.100` or `tokio@1` into a crate name and version
+/// requirement.
+///
+/// A full version means exactly that version, rather than semver's default of any compatible
+/// version, so that `serde@1.0.100` shows 1.0.100 even though newer 1.0 releases exist.
+pub(crate) fn parse_crate_specifier(specifier: &str) -> (&str, VersionReq) {
+    let Some((name, version)) = specifier.split_once('@') else {
+        return (specifier, VersionReq::STAR);
+    };
+    let version_req = match Version::parse(version) {
+        Ok(version) => VersionReq::parse(&format!("={version}")),
+        Err(_) => VersionReq::parse(version),
+    };
+    (name, version_req.unwrap_or(VersionReq::STAR))
+}
+
 /// Parse a path segment that may carry a rustdoc kind discriminator prefix, e.g. `"fn@foo"`.
 ///
 /// Returns `(kind_filter, name)` where:
@@ -468,27 +1118,13 @@ fn parse_discriminated_segment(segment: &str) -> (Option<ItemKind>, &str) {
     };
     let (disc, name) = (&segment[..at], &segment[at + 1..]);
     match disc {
-        "mod" | "module" => (Some(ItemKind::Module), name),
-        "struct" => (Some(ItemKind::Struct), name),
-        "enum" => (Some(ItemKind::Enum), name),
-        "union" => (Some(ItemKind::Union), name),
-        "trait" => (Some(ItemKind::Trait), name),
-        "traitalias" => (Some(ItemKind::TraitAlias), name),
-        "fn" | "function" | "method" => (Some(ItemKind::Function), name),
-        "tyalias" | "typealias" => (Some(ItemKind::TypeAlias), name),
-        "type" => (Some(ItemKind::AssocType), name),
-        "const" | "constant" => (Some(ItemKind::Constant), name),
-        "static" => (Some(ItemKind::Static), name),
-        "macro" => (Some(ItemKind::Macro), name),
-        "attr" => (Some(ItemKind::ProcAttribute), name),
-        "derive" => (Some(ItemKind::ProcDerive), name),
-        "prim" | "primitive" => (Some(ItemKind::Primitive), name),
-        "field" => (Some(ItemKind::StructField), name),
-        "variant" => (Some(ItemKind::Variant), name),
         // `value@` matches any value-namespace item — strip prefix, no kind filter.
         "value" => (None, name),
-        // Unrecognised prefix: treat the whole string as the item name.
-        _ => (None, segment),
+        _ => match parse_kind_discriminator(disc) {
+            Some(kind) => (Some(kind), name),
+            // Unrecognised prefix: treat the whole string as the item name.
+            None => (None, segment),
+        },
     }
 }
 
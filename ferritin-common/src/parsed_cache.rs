@@ -0,0 +1,179 @@
+//! An on-disk cache of parsed rustdoc JSON
+//!
+//! Parsing the JSON is most of the time it takes to load a large crate like std or tokio. The
+//! parsed [`Crate`] and its path index are written with bincode beside the JSON, and read back
+//! on later loads for as long as the JSON's content hash still matches.
+//!
+//! JSON too large to hold in memory twice over is instead loaded lazily, item by item, with
+//! [`LazyItems`].
+
+use anyhow::{Context, Result};
+use rustc_hash::FxHasher;
+use rustdoc_types::{Crate, FORMAT_VERSION, Id};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::lazy_items::{LAZY_LOAD_MIN_BYTES, LazyItems};
+use crate::rustdoc_data::path_index;
+
+/// Bumped whenever the cached data changes shape, like the keys in the path index
+const CACHE_VERSION: u32 = 1;
+
+/// Extension of a cache file, which sits beside the JSON it was parsed from
+pub(crate) const CACHE_EXTENSION: &str = "parsed";
+
+/// Cache version, rustdoc format version and the JSON's content hash, as little-endian bytes
+const HEADER_LEN: usize = 16;
+
+/// A crate's docs, parsed and normalized to the current format, with its path index
+pub(crate) struct ParsedCrate {
+    pub(crate) crate_data: Crate,
+    /// Empty for lazily loaded crates, whose index is built once they are in the working set
+    pub(crate) path_to_id: HashMap<String, Id>,
+    pub(crate) lazy_items: Option<Arc<LazyItems>>,
+}
+
+/// Parse rustdoc JSON read from `json_path`, or read the result of parsing it last time
+///
+/// `format_version` is passed on to [`load_and_normalize`](crate::conversions::load_and_normalize)
+/// when the JSON has to be parsed. A cache that can't be written, like one beside a read-only
+/// toolchain, is skipped.
+pub(crate) fn load_or_parse(
+    json_path: &Path,
+    json: Vec<u8>,
+    format_version: Option<u32>,
+) -> Result<ParsedCrate> {
+    if json.len() >= LAZY_LOAD_MIN_BYTES && is_current_format(&json, format_version) {
+        log::debug!(
+            "Loading {} lazily ({:.2} MB)",
+            json_path.display(),
+            json.len() as f64 / 1_000_000.0
+        );
+        let (crate_data, lazy_items) = LazyItems::parse(json)?;
+        return Ok(ParsedCrate {
+            crate_data,
+            path_to_id: HashMap::new(),
+            lazy_items: Some(Arc::new(lazy_items)),
+        });
+    }
+
+    let cache_path = json_path.with_extension(CACHE_EXTENSION);
+    let header = header(&json);
+
+    let start = std::time::Instant::now();
+    if let Some(parsed) = read_cache(&cache_path, &header) {
+        log::debug!(
+            "⏱️ Read parsed docs from {} in {:?}",
+            cache_path.display(),
+            start.elapsed()
+        );
+        return Ok(parsed);
+    }
+
+    let crate_data = crate::conversions::load_and_normalize(&json, format_version)?;
+    let path_to_id = path_index(&crate_data, |id| crate_data.index.get(id));
+    if let Err(error) = write_cache(&cache_path, &header, &crate_data, &path_to_id) {
+        log::debug!("Could not cache parsed docs: {error:?}");
+    }
+
+    Ok(ParsedCrate {
+        crate_data,
+        path_to_id,
+        lazy_items: None,
+    })
+}
+
+/// Whether `json` is in the format version that can be read without conversion
+fn is_current_format(json: &[u8], format_version: Option<u32>) -> bool {
+    let format_version = format_version.or_else(|| {
+        sonic_rs::get_from_slice(json, &["format_version"])
+            .ok()?
+            .as_raw_str()
+            .parse()
+            .ok()
+    });
+    format_version == Some(FORMAT_VERSION)
+}
+
+/// The header a cache written from `json` starts with
+fn header(json: &[u8]) -> [u8; HEADER_LEN] {
+    let mut hasher = FxHasher::default();
+    hasher.write(json);
+
+    let mut header = [0; HEADER_LEN];
+    header[..4].copy_from_slice(&CACHE_VERSION.to_le_bytes());
+    header[4..8].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    header[8..].copy_from_slice(&hasher.finish().to_le_bytes());
+    header
+}
+
+/// The cached crate at `cache_path`, if there is one and it starts with `header`
+fn read_cache(cache_path: &Path, header: &[u8; HEADER_LEN]) -> Option<ParsedCrate> {
+    let bytes = fs::read(cache_path).ok()?;
+    let Some(body) = bytes.strip_prefix(header.as_slice()) else {
+        log::debug!("Parsed docs at {} are stale", cache_path.display());
+        return None;
+    };
+
+    match bincode::deserialize::<(Crate, HashMap<String, Id>)>(body) {
+        Ok((crate_data, path_to_id)) => Some(ParsedCrate {
+            crate_data,
+            path_to_id,
+            lazy_items: None,
+        }),
+        Err(error) => {
+            log::debug!(
+                "Could not read parsed docs at {}: {error}",
+                cache_path.display()
+            );
+            None
+        }
+    }
+}
+
+/// Write the cache through a temporary file, so that a concurrent load never reads half of it
+fn write_cache(
+    cache_path: &Path,
+    header: &[u8; HEADER_LEN],
+    crate_data: &Crate,
+    path_to_id: &HashMap<String, Id>,
+) -> Result<()> {
+    let temp_path = cache_path.with_extension(format!("{CACHE_EXTENSION}.tmp"));
+    let written = write_file(&temp_path, header, crate_data, path_to_id)
+        .and_then(|()| Ok(fs::rename(&temp_path, cache_path)?));
+
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written.with_context(|| format!("Failed to write {}", cache_path.display()))
+}
+
+fn write_file(
+    path: &Path,
+    header: &[u8; HEADER_LEN],
+    crate_data: &Crate,
+    path_to_id: &HashMap<String, Id>,
+) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(header)?;
+    bincode::serialize_into(&mut writer, &(crate_data, path_to_id))?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_changes_with_content() {
+        let json = br#"{"format_version": 57}"#;
+        assert_eq!(header(json), header(json));
+        assert_ne!(header(json), header(br#"{"format_version": 56}"#));
+        assert_eq!(header(json)[..4], CACHE_VERSION.to_le_bytes());
+    }
+}
@@ -0,0 +1,181 @@
+//! The std prelude, so that names like `Option` or `Iterator` resolve without a crate prefix
+
+use std::fmt::{self, Display, Formatter};
+
+/// A Rust edition, which determines the contents of the std prelude
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+pub enum Edition {
+    E2015,
+    E2018,
+    E2021,
+    /// Also used when there is no workspace to take an edition from
+    #[default]
+    E2024,
+}
+
+impl From<cargo_metadata::Edition> for Edition {
+    fn from(edition: cargo_metadata::Edition) -> Self {
+        match edition {
+            cargo_metadata::Edition::E2015 => Self::E2015,
+            cargo_metadata::Edition::E2018 => Self::E2018,
+            cargo_metadata::Edition::E2021 => Self::E2021,
+            _ => Self::E2024,
+        }
+    }
+}
+
+impl Display for Edition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::E2015 => "2015",
+            Self::E2018 => "2018",
+            Self::E2021 => "2021",
+            Self::E2024 => "2024",
+        })
+    }
+}
+
+/// Names in every edition's prelude, with the std paths they are re-exported from
+const PRELUDE_V1: &[(&str, &str)] = &[
+    ("Copy", "std::marker::Copy"),
+    ("Send", "std::marker::Send"),
+    ("Sized", "std::marker::Sized"),
+    ("Sync", "std::marker::Sync"),
+    ("Unpin", "std::marker::Unpin"),
+    ("Drop", "std::ops::Drop"),
+    ("Fn", "std::ops::Fn"),
+    ("FnMut", "std::ops::FnMut"),
+    ("FnOnce", "std::ops::FnOnce"),
+    ("drop", "std::mem::drop"),
+    ("size_of", "std::mem::size_of"),
+    ("size_of_val", "std::mem::size_of_val"),
+    ("align_of", "std::mem::align_of"),
+    ("align_of_val", "std::mem::align_of_val"),
+    ("Box", "std::boxed::Box"),
+    ("ToOwned", "std::borrow::ToOwned"),
+    ("Clone", "std::clone::Clone"),
+    ("PartialEq", "std::cmp::PartialEq"),
+    ("PartialOrd", "std::cmp::PartialOrd"),
+    ("Eq", "std::cmp::Eq"),
+    ("Ord", "std::cmp::Ord"),
+    ("AsRef", "std::convert::AsRef"),
+    ("AsMut", "std::convert::AsMut"),
+    ("Into", "std::convert::Into"),
+    ("From", "std::convert::From"),
+    ("Default", "std::default::Default"),
+    ("Iterator", "std::iter::Iterator"),
+    ("Extend", "std::iter::Extend"),
+    ("IntoIterator", "std::iter::IntoIterator"),
+    ("DoubleEndedIterator", "std::iter::DoubleEndedIterator"),
+    ("ExactSizeIterator", "std::iter::ExactSizeIterator"),
+    ("Option", "std::option::Option"),
+    ("Some", "std::option::Option::Some"),
+    ("None", "std::option::Option::None"),
+    ("Result", "std::result::Result"),
+    ("Ok", "std::result::Result::Ok"),
+    ("Err", "std::result::Result::Err"),
+    ("String", "std::string::String"),
+    ("ToString", "std::string::ToString"),
+    ("Vec", "std::vec::Vec"),
+];
+
+/// Added to the prelude in edition 2021
+const PRELUDE_2021: &[(&str, &str)] = &[
+    ("TryFrom", "std::convert::TryFrom"),
+    ("TryInto", "std::convert::TryInto"),
+    ("FromIterator", "std::iter::FromIterator"),
+];
+
+/// Added to the prelude in edition 2024
+const PRELUDE_2024: &[(&str, &str)] = &[
+    ("Future", "std::future::Future"),
+    ("IntoFuture", "std::future::IntoFuture"),
+];
+
+/// The std path that a bare name refers to through `edition`'s prelude, if it is in it
+pub fn prelude_path(name: &str, edition: Edition) -> Option<&'static str> {
+    let mut tables = vec![PRELUDE_V1];
+    if edition >= Edition::E2021 {
+        tables.push(PRELUDE_2021);
+    }
+    if edition >= Edition::E2024 {
+        tables.push(PRELUDE_2024);
+    }
+
+    tables
+        .into_iter()
+        .flatten()
+        .find(|(prelude_name, _)| *prelude_name == name)
+        .map(|(_, path)| *path)
+}
+
+/// The primitive types std documents, by the names of their pages. Like prelude names, these
+/// are in scope everywhere.
+const PRIMITIVES: &[&str] = &[
+    "bool",
+    "char",
+    "str",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "f16",
+    "f32",
+    "f64",
+    "f128",
+    "slice",
+    "array",
+    "tuple",
+    "unit",
+    "pointer",
+    "reference",
+    "fn",
+    "never",
+];
+
+/// Whether `name` is a primitive type like `str` or `u32`, documented in std as
+/// `std::prim@name`
+pub fn is_primitive(name: &str) -> bool {
+    PRIMITIVES.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prelude_depends_on_edition() {
+        assert_eq!(
+            prelude_path("Option", Edition::E2015),
+            Some("std::option::Option")
+        );
+        assert_eq!(prelude_path("TryFrom", Edition::E2018), None);
+        assert_eq!(
+            prelude_path("TryFrom", Edition::E2021),
+            Some("std::convert::TryFrom")
+        );
+        assert_eq!(prelude_path("Future", Edition::E2021), None);
+        assert_eq!(
+            prelude_path("Future", Edition::E2024),
+            Some("std::future::Future")
+        );
+        assert_eq!(prelude_path("HashMap", Edition::E2024), None);
+    }
+
+    #[test]
+    fn primitives_are_recognized() {
+        assert!(is_primitive("str"));
+        assert!(is_primitive("u32"));
+        assert!(is_primitive("slice"));
+        assert!(!is_primitive("String"));
+        assert!(!is_primitive("Str"));
+    }
+}
@@ -0,0 +1,154 @@
+//! Progress reporting for slow operations, like downloading docs from docs.rs, building docs
+//! with cargo, and indexing a crate for search
+
+use std::sync::Arc;
+
+/// Receives updates about slow operations, so that a frontend can show what is happening
+///
+/// Tasks can nest: indexing a crate may need to download it first. Each [`start`] is matched by
+/// one [`finish`], innermost first.
+///
+/// [`start`]: Progress::start
+/// [`finish`]: Progress::finish
+pub trait Progress: Send + Sync {
+    /// A slow task has started, described like "Downloading serde 1.0.219 from docs.rs"
+    fn start(&self, task: &str);
+
+    /// How far the innermost task has got, when that is known
+    fn advance(&self, done: u64, total: u64) {
+        let _ = (done, total);
+    }
+
+    /// The innermost task is over, whether or not it succeeded
+    fn finish(&self);
+
+    /// Whether whoever is waiting gave up on the work in progress, so that downloads should
+    /// stop where they can
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Progress that goes unreported
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn start(&self, _task: &str) {}
+
+    fn finish(&self) {}
+}
+
+impl<T: Progress + ?Sized> Progress for Arc<T> {
+    fn start(&self, task: &str) {
+        (**self).start(task);
+    }
+
+    fn advance(&self, done: u64, total: u64) {
+        (**self).advance(done, total);
+    }
+
+    fn finish(&self) {
+        (**self).finish();
+    }
+
+    fn is_cancelled(&self) -> bool {
+        (**self).is_cancelled()
+    }
+}
+
+/// A started task, which is reported as finished when dropped so that early returns and `?`
+/// don't leave it running
+#[must_use = "the task finishes when this is dropped"]
+pub struct ProgressTask<'a> {
+    progress: &'a dyn Progress,
+}
+
+impl<'a> ProgressTask<'a> {
+    pub fn start(progress: &'a dyn Progress, task: &str) -> Self {
+        progress.start(task);
+        Self { progress }
+    }
+
+    pub fn advance(&self, done: u64, total: u64) {
+        self.progress.advance(done, total);
+    }
+}
+
+impl Drop for ProgressTask<'_> {
+    fn drop(&mut self) {
+        self.progress.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Progress that records the updates it receives
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl Progress for Recorder {
+        fn start(&self, task: &str) {
+            self.0.lock().unwrap().push(format!("start {task}"));
+        }
+
+        fn advance(&self, done: u64, total: u64) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("advance {done}/{total}"));
+        }
+
+        fn finish(&self) {
+            self.0.lock().unwrap().push("finish".into());
+        }
+    }
+
+    fn download(progress: &dyn Progress, fail: bool) -> Result<(), ()> {
+        let task = ProgressTask::start(progress, "Downloading");
+        task.advance(1, 2);
+        let unpacking = ProgressTask::start(progress, "Unpacking");
+        if fail {
+            return Err(());
+        }
+        drop(unpacking);
+        task.advance(2, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn tasks_finish_innermost_first() {
+        let recorder = Arc::new(Recorder::default());
+        download(&recorder, false).unwrap();
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "start Downloading",
+                "advance 1/2",
+                "start Unpacking",
+                "finish",
+                "advance 2/2",
+                "finish",
+            ]
+        );
+    }
+
+    #[test]
+    fn tasks_finish_on_early_return() {
+        let recorder = Recorder::default();
+        assert!(download(&recorder, true).is_err());
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "start Downloading",
+                "advance 1/2",
+                "start Unpacking",
+                "finish",
+                "finish"
+            ]
+        );
+    }
+}
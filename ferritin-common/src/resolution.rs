@@ -0,0 +1,235 @@
+//! Resolution traces - a record of the steps `Navigator::resolve_path` attempted
+
+use crate::prelude::Edition;
+use rustdoc_types::ItemKind;
+use semver::{Version, VersionReq};
+use std::fmt::{self, Display, Formatter};
+
+/// The steps attempted while resolving a path, in order
+///
+/// Populated by [`Navigator::resolve_path_traced`](crate::Navigator::resolve_path_traced) so
+/// that a failed lookup can show exactly where resolution diverged from what the user expected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolutionTrace {
+    steps: Vec<ResolutionStep>,
+}
+
+impl ResolutionTrace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn steps(&self) -> &[ResolutionStep] {
+        &self.steps
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, step: ResolutionStep) {
+        self.steps.push(step);
+    }
+}
+
+/// A single step of path resolution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolutionStep {
+    /// The crate specifier was canonicalized and looked up in the available sources
+    Crate {
+        /// The crate name as written, without any `@version`
+        requested: String,
+        /// The name after canonicalization (e.g. `-` → `_`, `core` for `std` re-exports)
+        canonical: String,
+        version_req: VersionReq,
+        /// The name and version of the crate that was loaded, if any
+        loaded: Option<(String, Option<Version>)>,
+    },
+
+    /// A bare name was found in the std prelude and replaced by its std path
+    Prelude {
+        name: String,
+        edition: Edition,
+        /// The std path the name refers to, e.g. `std::option::Option`
+        path: String,
+    },
+
+    /// A bare primitive name was replaced by the path of its std docs
+    Primitive {
+        name: String,
+        /// The std path tried, e.g. `std::prim@str`
+        path: String,
+    },
+
+    /// A bare name that isn't a crate was looked up among std's keyword docs
+    Keyword {
+        name: String,
+        /// The std path tried, e.g. `std::keyword@match`
+        path: String,
+        found: bool,
+    },
+
+    /// A path segment was looked up among the children of the item resolved so far
+    Segment {
+        /// The path resolved so far, e.g. `std::collections`
+        parent: String,
+        /// The segment name with any discriminator stripped
+        name: String,
+        /// The kind required by a discriminator such as `fn@`, if any
+        kind_filter: Option<ItemKind>,
+        outcome: SegmentOutcome,
+    },
+
+    /// The crate's reverse path index was consulted for the whole remaining path
+    PathIndex { key: String, found: bool },
+
+    /// The crate's reverse path index was consulted for the parent of the last segment
+    ParentPathIndex { key: String, found: bool },
+}
+
+/// What happened when a segment was looked up among an item's children
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentOutcome {
+    /// A child with this name (and kind, if filtered) was found
+    Matched(ItemKind),
+
+    /// A child was found through one of its `#[doc(alias)]` names
+    MatchedAlias { name: String, kind: ItemKind },
+
+    /// The only child whose name differs from the segment just in case or by one mistyped,
+    /// missing, extra or swapped character
+    MatchedNearMiss { name: String, kind: ItemKind },
+
+    /// A method that isn't the parent's own, found through its trait impls or the types it
+    /// dereferences to
+    MatchedMethod {
+        kind: ItemKind,
+        /// The names of the types dereferenced to on the way, in order
+        derefs: Vec<String>,
+        /// The trait the method is from, if any
+        trait_name: Option<String>,
+    },
+
+    /// No child matched, or every match failed further down the path
+    NotFound {
+        /// How many children the parent has
+        children: usize,
+        /// Kinds of children that had the right name but were excluded by the discriminator
+        kind_mismatches: Vec<ItemKind>,
+    },
+}
+
+impl Display for ResolutionStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolutionStep::Crate {
+                requested,
+                canonical,
+                version_req,
+                loaded,
+            } => {
+                write!(f, "crate `{requested}@{version_req}`")?;
+                if canonical != requested {
+                    write!(f, " (canonicalized to `{canonical}`)")?;
+                }
+                match loaded {
+                    Some((name, Some(version))) => write!(f, ": loaded {name} {version}"),
+                    Some((name, None)) => write!(f, ": loaded {name}"),
+                    None => f.write_str(": not found in any source"),
+                }
+            }
+
+            ResolutionStep::Prelude {
+                name,
+                edition,
+                path,
+            } => write!(
+                f,
+                "`{name}` is in the {edition} prelude: resolving `{path}`"
+            ),
+
+            ResolutionStep::Primitive { name, path } => {
+                write!(f, "`{name}` is a primitive type: resolving `{path}`")
+            }
+
+            ResolutionStep::Keyword { name, path, found } => {
+                write!(f, "`{name}` is not a crate: keyword docs at `{path}`")?;
+                f.write_str(if *found { " found" } else { " not found" })
+            }
+
+            ResolutionStep::Segment {
+                parent,
+                name,
+                kind_filter,
+                outcome,
+            } => {
+                write!(f, "segment `{name}` in `{parent}`")?;
+                if let Some(kind) = kind_filter {
+                    write!(f, " (kind {kind:?})")?;
+                }
+                match outcome {
+                    SegmentOutcome::Matched(kind) => write!(f, ": matched {kind:?}"),
+                    SegmentOutcome::MatchedAlias { name, kind } => {
+                        write!(f, ": matched {kind:?} `{name}` by doc alias")
+                    }
+                    SegmentOutcome::MatchedNearMiss { name, kind } => {
+                        write!(f, ": matched {kind:?} `{name}` as a near miss")
+                    }
+                    SegmentOutcome::MatchedMethod {
+                        kind,
+                        derefs,
+                        trait_name,
+                    } => {
+                        write!(f, ": matched {kind:?}")?;
+                        if !derefs.is_empty() {
+                            write!(f, " through Deref to `{}`", derefs.join("` → `"))?;
+                        }
+                        if let Some(trait_name) = trait_name {
+                            write!(f, " from `{trait_name}`")?;
+                        }
+                        Ok(())
+                    }
+                    SegmentOutcome::NotFound {
+                        children,
+                        kind_mismatches,
+                    } => {
+                        write!(f, ": not found among {children} children")?;
+                        if !kind_mismatches.is_empty() {
+                            let kinds = kind_mismatches
+                                .iter()
+                                .map(|kind| format!("{kind:?}"))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            write!(f, " (same name but excluded by kind: {kinds})")?;
+                        }
+                        Ok(())
+                    }
+                }
+            }
+
+            ResolutionStep::PathIndex { key, found } => write!(
+                f,
+                "path index lookup `{key}`: {}",
+                if *found { "found" } else { "no entry" }
+            ),
+
+            ResolutionStep::ParentPathIndex { key, found } => write!(
+                f,
+                "parent path index lookup `{key}`: {}",
+                if *found { "found" } else { "no entry" }
+            ),
+        }
+    }
+}
+
+/// Push a step onto the trace, if one is being recorded
+///
+/// Takes a closure so that untraced resolution doesn't pay for building steps.
+pub(crate) fn record(
+    trace: &mut Option<&mut ResolutionTrace>,
+    step: impl FnOnce() -> ResolutionStep,
+) {
+    if let Some(trace) = trace {
+        trace.push(step());
+    }
+}
@@ -1,13 +1,16 @@
 use fieldwork::Fieldwork;
-use rustdoc_types::{Crate, ExternalCrate, Id, Item, ItemKind};
+use rustdoc_types::{Attribute, Crate, ExternalCrate, Id, Item, ItemKind};
 use semver::{Version, VersionReq};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{self, Debug, Formatter};
 use std::ops::Deref;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::CrateProvenance;
 use crate::doc_ref::{self, DocRef};
+use crate::lazy_items::LazyItems;
 use crate::navigator::{Navigator, parse_docsrs_url};
 
 /// Wrapper around rustdoc JSON data that provides convenient query methods
@@ -22,7 +25,8 @@ pub struct RustdocData {
 
     /// Reverse index from path string (excluding crate name) to `Id`, for local items.
     ///
-    /// Populated by [`RustdocData::build_path_index`] before crate insertion into Navigator.
+    /// Read from the parsed docs cache along with the crate, or populated by
+    /// [`RustdocData::build_path_index`] before crate insertion into Navigator.
     /// Used as a fallback in `Navigator::resolve_path` when tree traversal fails (e.g. when
     /// the path passes through a private module not visible in the public item tree).
     ///
@@ -33,6 +37,16 @@ pub struct RustdocData {
     ///   kind shares this path (i.e. unambiguous).
     #[field = false]
     pub(crate) path_to_id: HashMap<String, Id>,
+
+    /// For crates too large to parse up front, the items that would otherwise be in
+    /// `crate_data.index`, parsed as they are looked up
+    #[field = false]
+    pub(crate) lazy_items: Option<Arc<LazyItems>>,
+
+    /// Whether paths into this crate carry its version, because it was loaded at another
+    /// version than its name alone loads, like `serde@1.0.100`
+    #[field = false]
+    pub(crate) versioned_paths: bool,
 }
 
 impl Debug for RustdocData {
@@ -56,16 +70,37 @@ impl Deref for RustdocData {
 
 impl RustdocData {
     pub(crate) fn get<'a>(&'a self, navigator: &'a Navigator, id: &Id) -> Option<DocRef<'a, Item>> {
-        let item = self.crate_data.index.get(id)?;
+        let item = self.item(id)?;
         Some(DocRef::new(navigator, self, item))
     }
 
+    /// The item with this id. Use this rather than `index`, which is empty for crates that
+    /// were loaded lazily.
+    pub fn item(&self, id: &Id) -> Option<&Item> {
+        match &self.lazy_items {
+            Some(lazy_items) => lazy_items.get(id),
+            None => self.crate_data.index.get(id),
+        }
+    }
+
+    /// Every item in the crate, in no particular order. For a crate that was loaded lazily
+    /// this parses all of them, so following ids from a known item is preferable.
+    pub fn items(&self) -> impl Iterator<Item = &Item> {
+        self.lazy_items
+            .iter()
+            .flat_map(|lazy_items| lazy_items.iter())
+            .chain(self.crate_data.index.values())
+    }
+
     pub fn path<'a>(&'a self, id: &Id) -> Option<doc_ref::Path<'a>> {
         self.paths.get(id).map(|summary| summary.into())
     }
 
     pub fn root_item<'a>(&'a self, navigator: &'a Navigator) -> DocRef<'a, Item> {
-        DocRef::new(navigator, self, &self.index[&self.root])
+        let root = self
+            .item(&self.root)
+            .expect("the root module is in the index");
+        DocRef::new(navigator, self, root)
     }
 
     pub fn traverse_to_crate_by_id<'a>(
@@ -109,6 +144,44 @@ impl RustdocData {
             .find_by_path(item_summary.path.iter().skip(1))
     }
 
+    /// The first segment of paths into this crate: its name, with its version when that isn't
+    /// the version the name alone loads
+    pub fn crate_specifier(&self) -> Cow<'_, str> {
+        match &self.version {
+            Some(version) if self.versioned_paths => Cow::Owned(format!("{}@{version}", self.name)),
+            _ => Cow::Borrowed(&self.name),
+        }
+    }
+
+    /// The path of item `id` as `Navigator::resolve_path` takes it
+    ///
+    /// In a crate with versioned paths, the path carries the version, and a path into one of
+    /// its dependencies carries the version it was documented against, so that links followed
+    /// from an `@version` page stay on that version.
+    pub fn link_path(&self, id: &Id) -> Option<String> {
+        let summary = self.paths.get(id)?;
+        let (first, rest) = summary.path.split_first()?;
+        let crate_segment = if !self.versioned_paths {
+            Cow::Borrowed(first.as_str())
+        } else if summary.crate_id == 0 {
+            self.crate_specifier()
+        } else {
+            self.external_crates
+                .get(&summary.crate_id)
+                .and_then(|external| parse_docsrs_url(external.html_root_url.as_deref()?))
+                .map_or(Cow::Borrowed(first.as_str()), |(name, version)| {
+                    Cow::Owned(format!("{name}@{version}"))
+                })
+        };
+
+        let mut path = crate_segment.into_owned();
+        for segment in rest {
+            path.push_str("::");
+            path.push_str(segment);
+        }
+        Some(path)
+    }
+
     /// Build the reverse path index from `paths`, for use by `Navigator::resolve_path`.
     ///
     /// Indexes local items (`crate_id == 0`) by their path string (excluding the crate name
@@ -117,47 +190,155 @@ impl RustdocData {
     /// - A kind-qualified entry: `"private::struct@MyStruct"` → Id (always)
     /// - An unqualified entry: `"private::MyStruct"` → Id (only if no collision at that path)
     pub(crate) fn build_path_index(&mut self) {
-        // Collect all local items grouped by their unqualified path.
-        let mut by_unqualified: HashMap<String, Vec<(Id, ItemKind)>> = HashMap::new();
-        for (id, summary) in &self.crate_data.paths {
-            if summary.crate_id != 0 {
-                continue;
-            }
-            let Some(tail) = summary.path.get(1..) else {
+        self.path_to_id = match &self.lazy_items {
+            // Only items that mention an alias need parsing, for their `#[doc(alias)]`s
+            Some(lazy_items) => path_index(&self.crate_data, |id| {
+                lazy_items.get_if_contains(id, "alias")
+            }),
+            None => path_index(&self.crate_data, |id| self.crate_data.index.get(id)),
+        };
+    }
+}
+
+/// The reverse path index described at [`RustdocData::build_path_index`], with `item` used to
+/// look up the items that may have doc aliases
+pub(crate) fn path_index<'a>(
+    crate_data: &Crate,
+    item: impl Fn(&Id) -> Option<&'a Item>,
+) -> HashMap<String, Id> {
+    // Collect all local items grouped by their unqualified path.
+    let mut by_unqualified: HashMap<String, Vec<(Id, ItemKind)>> = HashMap::new();
+    for (id, summary) in &crate_data.paths {
+        if summary.crate_id != 0 {
+            continue;
+        }
+        let Some(tail) = summary.path.get(1..) else {
+            continue;
+        };
+        if tail.is_empty() {
+            continue;
+        }
+        by_unqualified
+            .entry(tail.join("::"))
+            .or_default()
+            .push((*id, summary.kind));
+    }
+
+    let mut map = HashMap::new();
+    for (unqualified, items) in &by_unqualified {
+        // Split into prefix and last segment name so the discriminator goes on the
+        // final segment only: e.g. "mod1::mod2::fn@name" not "fn@mod1::mod2::name".
+        let (prefix, last_name) = match unqualified.rfind("::") {
+            Some(sep) => (&unqualified[..sep + 2], &unqualified[sep + 2..]),
+            None => ("", unqualified.as_str()),
+        };
+
+        // Always insert a kind-qualified entry for each item.
+        for (id, kind) in items {
+            let qualified = format!("{prefix}{}@{last_name}", kind_discriminator(*kind));
+            map.insert(qualified, *id);
+        }
+
+        // Insert the unqualified entry only when it is unambiguous (exactly one item).
+        if items.len() == 1 {
+            map.insert(unqualified.clone(), items[0].0);
+        }
+    }
+
+    // Alias entries (`#[doc(alias = "mkdir")]` on `fs::create_dir` → `"fs::mkdir"`) go in
+    // last so they never shadow a real item name.
+    for (unqualified, items) in &by_unqualified {
+        let prefix = unqualified
+            .rfind("::")
+            .map_or("", |sep| &unqualified[..sep + 2]);
+        for (id, _) in items {
+            let Some(item) = item(id) else {
                 continue;
             };
-            if tail.is_empty() {
-                continue;
+            for alias in doc_aliases(item) {
+                map.entry(format!("{prefix}{alias}")).or_insert(*id);
             }
-            by_unqualified
-                .entry(tail.join("::"))
-                .or_default()
-                .push((*id, summary.kind));
         }
+    }
 
-        let mut map = HashMap::new();
-        for (unqualified, items) in &by_unqualified {
-            // Split into prefix and last segment name so the discriminator goes on the
-            // final segment only: e.g. "mod1::mod2::fn@name" not "fn@mod1::mod2::name".
-            let (prefix, last_name) = match unqualified.rfind("::") {
-                Some(sep) => (&unqualified[..sep + 2], &unqualified[sep + 2..]),
-                None => ("", unqualified.as_str()),
-            };
+    map
+}
 
-            // Always insert a kind-qualified entry for each item.
-            for (id, kind) in items {
-                let qualified = format!("{prefix}{}@{last_name}", kind_discriminator(*kind));
-                map.insert(qualified, *id);
-            }
+/// Returns the names given to an item with `#[doc(alias = "...")]` or `#[doc(alias(...))]`
+pub(crate) fn doc_aliases(item: &Item) -> impl Iterator<Item = &str> {
+    item.attrs.iter().flat_map(|attr| {
+        let aliases = match attr {
+            Attribute::Other(attr) => attr
+                .strip_prefix("#[doc(")
+                .and_then(|rest| rest.find("alias").map(|start| &rest[start..]))
+                .unwrap_or_default(),
+            _ => "",
+        };
 
-            // Insert the unqualified entry only when it is unambiguous (exactly one item).
-            if items.len() == 1 {
-                map.insert(unqualified.clone(), items[0].0);
-            }
-        }
+        // Quoted strings after `alias` are the alias names: `alias = "a"` or `alias("a", "b")`
+        aliases.split('"').skip(1).step_by(2)
+    })
+}
+
+/// The Rust version an item was stabilized in, from a `#[stable(since = "...")]` attribute
+pub(crate) fn stable_since(item: &Item) -> Option<Version> {
+    item.attrs.iter().find_map(|attr| match attr {
+        Attribute::Other(attr) => stability_since(attr),
+        _ => None,
+    })
+}
 
-        self.path_to_id = map;
+/// Read the `since` version from a stability attribute, either as written in source or as
+/// rustdoc's debug printing of the parsed attribute
+fn stability_since(attr: &str) -> Option<Version> {
+    if let Some(args) = attr.strip_prefix("#[stable(") {
+        let (_, rest) = args.split_once("since")?;
+        // `since = "CURRENT_RUSTC_VERSION"` on unreleased items doesn't parse, and is skipped
+        return parse_rust_version(rest.split('"').nth(1)?);
     }
+
+    // Printed as `since: Version(RustcVersion {major: 1, minor: 63, patch: 0})` or, by
+    // toolchains that print the version itself, `since: Version(1.63.0)`
+    let (_, since) = attr
+        .strip_prefix("#[attr = Stability")?
+        .split_once("since:")?;
+    let rest = since.trim_start().strip_prefix("Version(")?;
+    let written = rest
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()
+        .and_then(parse_rust_version);
+    if written.is_some() {
+        return written;
+    }
+
+    let number = |field: &str| -> Option<u64> {
+        let (_, value) = rest.split_once(field)?;
+        let digits = value
+            .trim_start_matches([':', ' '])
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?;
+        digits.parse().ok()
+    };
+    Some(Version::new(
+        number("major")?,
+        number("minor")?,
+        number("patch")?,
+    ))
+}
+
+/// Parse a Rust version like `1.63` or `1.63.0`, with missing components as zero
+pub fn parse_rust_version(version: &str) -> Option<Version> {
+    let mut parts = version
+        .trim()
+        .split('.')
+        .map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    parts
+        .next()
+        .is_none()
+        .then(|| Version::new(major, minor, patch))
 }
 
 /// Returns the rustdoc discriminator prefix for an item kind, e.g. `"mod"` for `Module`.
@@ -190,3 +371,78 @@ pub(crate) fn kind_discriminator(kind: ItemKind) -> &'static str {
         ItemKind::ExternCrate | ItemKind::Use | ItemKind::Impl | ItemKind::ExternType => "item",
     }
 }
+
+/// Parses a rustdoc discriminator like `"fn"` or `"struct"` into the item kind it stands for.
+///
+/// Accepts the spellings rustdoc does, so `"mod"` and `"module"` both mean `Module`, and
+/// `"fn"`, `"function"` and `"method"` all mean `Function`. Returns `None` for anything else,
+/// including `"value"`, which stands for several kinds.
+pub fn parse_kind_discriminator(discriminator: &str) -> Option<ItemKind> {
+    Some(match discriminator {
+        "mod" | "module" => ItemKind::Module,
+        "struct" => ItemKind::Struct,
+        "enum" => ItemKind::Enum,
+        "union" => ItemKind::Union,
+        "trait" => ItemKind::Trait,
+        "traitalias" => ItemKind::TraitAlias,
+        "fn" | "function" | "method" => ItemKind::Function,
+        "tyalias" | "typealias" => ItemKind::TypeAlias,
+        "type" => ItemKind::AssocType,
+        "const" | "constant" => ItemKind::Constant,
+        "static" => ItemKind::Static,
+        "macro" => ItemKind::Macro,
+        "attr" => ItemKind::ProcAttribute,
+        "derive" => ItemKind::ProcDerive,
+        "prim" | "primitive" => ItemKind::Primitive,
+        "field" => ItemKind::StructField,
+        "variant" => ItemKind::Variant,
+        "keyword" => ItemKind::Keyword,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stability_is_read_from_source_and_parsed_attributes() {
+        assert_eq!(
+            stability_since(r#"#[stable(feature = "option_zip", since = "1.46.0")]"#),
+            Some(Version::new(1, 46, 0))
+        );
+        assert_eq!(
+            stability_since(
+                "#[attr = Stability {stability: Stability {level: Stable {since: \
+                 Version(RustcVersion {major: 1, minor: 63, patch: 0}), \
+                 allowed_through_unstable_modules: None}, feature: \"scoped_threads\"}}]"
+            ),
+            Some(Version::new(1, 63, 0))
+        );
+        assert_eq!(
+            stability_since(
+                "#[attr = Stability {stability: Stability {level: Stable {since: \
+                 Version(1.63.0), allowed_through_unstable_modules: None}, feature: \
+                 \"scoped_threads\"}}]"
+            ),
+            Some(Version::new(1, 63, 0))
+        );
+        assert_eq!(
+            stability_since(
+                "#[attr = Stability {stability: Stability {level: Stable {since: Current, \
+                 allowed_through_unstable_modules: None}, feature: \"new\"}}]"
+            ),
+            None
+        );
+        assert_eq!(
+            stability_since(r#"#[stable(feature = "new", since = "CURRENT_RUSTC_VERSION")]"#),
+            None
+        );
+        assert_eq!(
+            stability_since(r#"#[rustc_const_stable(feature = "x", since = "1.2.0")]"#),
+            None
+        );
+        assert_eq!(parse_rust_version("1.70"), Some(Version::new(1, 70, 0)));
+        assert_eq!(parse_rust_version("1.70.0.1"), None);
+    }
+}
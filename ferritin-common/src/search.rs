@@ -1,9 +1,16 @@
+pub mod context;
 pub mod indexer;
+pub mod pattern;
 
-use crate::{Navigator, navigator::Suggestion};
+use crate::{
+    Navigator,
+    navigator::{Suggestion, parse_crate_specifier},
+};
 use rayon::prelude::*;
 
+pub use context::*;
 pub use indexer::*;
+pub use pattern::*;
 
 impl Navigator {
     /// Search across multiple crates with BM25 scoring
@@ -11,11 +18,15 @@ impl Navigator {
     /// Returns results sorted by score (descending). Empty crate list returns empty results.
     /// Empty query triggers index loading but returns no matches (useful for prewarming).
     ///
+    /// With a [`SearchContext`], the top results are re-ranked so that members of the context
+    /// item, items from its crate, and items of related kinds come first.
+    ///
     /// Returns Err with suggestions if no crates could be loaded/indexed.
     pub fn search<'nav, 'query>(
         &'nav self,
         query: &'query str,
         crate_names: &'query [&'query str],
+        context: Option<SearchContext<'nav>>,
     ) -> Result<Vec<ScoredResult<'query>>, Vec<Suggestion<'nav>>> {
         if crate_names.is_empty() {
             return Ok(vec![]);
@@ -53,7 +64,11 @@ impl Navigator {
             scorer.add(crate_name, results);
         }
 
-        Ok(scorer.score())
+        Ok(match context {
+            Some(context) => scorer
+                .score_with_boost(|crate_name, id_path| context.boost(self, crate_name, id_path)),
+            None => scorer.score(),
+        })
     }
 
     /// Get or build a search index for the given crate
@@ -63,7 +78,8 @@ impl Navigator {
         &'nav self,
         crate_name: &str,
     ) -> Result<&'nav SearchIndex, Vec<Suggestion<'nav>>> {
-        let crate_name = self.canonicalize(crate_name);
+        let (crate_name, version_req) = parse_crate_specifier(crate_name);
+        let crate_name = self.working_set_key(crate_name, &version_req);
 
         if let Some(cached) = self.search_indexes.get(&crate_name) {
             if let Some(index) = cached.as_ref() {
@@ -77,7 +93,7 @@ impl Navigator {
         log::info!("Loading search index for {}", crate_name);
 
         // Use existing SearchIndex::load_or_build which handles disk caching
-        let result = SearchIndex::load_or_build(self, crate_name.as_ref());
+        let result = SearchIndex::load_or_build(self, crate_name.as_ref(), self.progress());
 
         match result {
             Ok(index) => {
@@ -0,0 +1,88 @@
+use rustdoc_types::{Item, ItemKind};
+
+use crate::{DocRef, Navigator};
+
+/// Score multiplier for members of the context item (e.g. `Vec::push` while viewing `Vec`)
+const MEMBER_BOOST: f32 = 2.0;
+
+/// Score multiplier for results from the same crate as the context item
+const SAME_CRATE_BOOST: f32 = 1.25;
+
+/// Score multiplier for results of a kind related to the context item's kind
+const RELATED_KIND_BOOST: f32 = 1.25;
+
+/// The item being viewed when a search was started
+///
+/// Passed to [`Navigator::search`] so that results near what the user is looking at rank
+/// higher: members of the item itself, items from the same crate, and items of related kinds.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchContext<'a> {
+    item: DocRef<'a, Item>,
+}
+
+impl<'a> SearchContext<'a> {
+    pub fn new(item: DocRef<'a, Item>) -> Self {
+        Self { item }
+    }
+
+    pub fn item(&self) -> DocRef<'a, Item> {
+        self.item
+    }
+
+    /// The multiplier applied to the score of the result at `id_path` in `crate_name`
+    pub(crate) fn boost(&self, navigator: &Navigator, crate_name: &str, id_path: &[u32]) -> f32 {
+        let Some((result, _)) = navigator.get_item_from_id_path(crate_name, id_path) else {
+            return 1.0;
+        };
+
+        let mut boost = 1.0;
+
+        if result.crate_docs().name() == self.item.crate_docs().name() {
+            boost *= SAME_CRATE_BOOST;
+        }
+
+        if let Some((_, parent_path)) = id_path.split_last()
+            && !parent_path.is_empty()
+            && navigator
+                .get_item_from_id_path(crate_name, parent_path)
+                .is_some_and(|(parent, _)| parent == self.item)
+        {
+            boost *= MEMBER_BOOST;
+        }
+
+        if is_related_kind(self.item.kind(), result.kind()) {
+            boost *= RELATED_KIND_BOOST;
+        }
+
+        boost
+    }
+}
+
+/// Whether a result of kind `candidate` is likely relevant while viewing an item of kind
+/// `current`: the same kind, or the kind of something that lives on it
+fn is_related_kind(current: ItemKind, candidate: ItemKind) -> bool {
+    use ItemKind::*;
+
+    current == candidate
+        || matches!(
+            (current, candidate),
+            (
+                Struct | Enum | Union | Trait | TypeAlias | Primitive,
+                Function | AssocConst | AssocType | StructField | Variant
+            )
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn members_are_related_to_their_types() {
+        assert!(is_related_kind(ItemKind::Struct, ItemKind::Function));
+        assert!(is_related_kind(ItemKind::Trait, ItemKind::AssocType));
+        assert!(is_related_kind(ItemKind::Module, ItemKind::Module));
+        assert!(!is_related_kind(ItemKind::Function, ItemKind::Struct));
+        assert!(!is_related_kind(ItemKind::Module, ItemKind::Function));
+    }
+}
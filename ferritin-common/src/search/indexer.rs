@@ -23,6 +23,7 @@ use crate::{
     crate_name::CrateName,
     doc_ref::DocRef,
     navigator::{Navigator, Suggestion},
+    progress::{Progress, ProgressTask},
 };
 
 /// Represents either a resolved Item or an unresolved ItemSummary for link counting
@@ -169,6 +170,53 @@ struct DocumentLength(usize);
 #[derive(Debug, Clone, PartialEq, Eq, Archive, RkyvSerialize, RkyvDeserialize)]
 struct ItemPath(Vec<u32>);
 
+/// Hash of the text an item contributes to the index (name, doc aliases and docs), or of a
+/// module's whole subtree
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Archive,
+    RkyvSerialize,
+    RkyvDeserialize,
+)]
+#[rkyv(derive(PartialEq, Eq, PartialOrd, Ord))]
+#[repr(transparent)]
+struct ContentHash(u64);
+
+/// The weighted terms tokenized from a single item
+#[derive(Debug, Clone, Default, Archive, RkyvSerialize, RkyvDeserialize)]
+struct ItemTerms {
+    /// Tokens counted toward document length
+    length: usize,
+    terms: Vec<(TermHash, DocumentTermCount)>,
+}
+
+/// Tokenized modules from the previous build of an index, keyed by subtree hash, so modules
+/// whose subtree is unchanged aren't re-tokenized
+#[derive(Default, Debug, Clone)]
+struct ModuleTermsCache<'a> {
+    previous: BTreeMap<ContentHash, Vec<ItemTerms>>,
+    current: BTreeMap<ContentHash, Vec<ItemTerms>>,
+    /// Modules being indexed, innermost last
+    open: Vec<OpenModule<'a>>,
+    reused: usize,
+}
+
+/// A module being indexed, whose items are tokenized once its subtree hash is known
+#[derive(Default, Debug, Clone)]
+struct OpenModule<'a> {
+    /// Content hashes of the module's items and subtree hashes of its submodules, in order
+    hashes: Vec<ContentHash>,
+    /// The module's own items, and the documents they add terms to
+    items: Vec<(DocRef<'a, Item>, (u64, u32))>,
+}
+
 #[derive(Debug, Clone, Copy, Archive, RkyvSerialize, RkyvDeserialize)]
 struct Posting {
     document: DocumentId,
@@ -191,6 +239,8 @@ struct Terms<'a> {
     visited_crates: HashSet<CrateName<'a>>,
     link_counts: HashMap<ItemOrSummary<'a>, usize>,
     docref_by_id: HashMap<(u64, u32), DocRef<'a, Item>>,
+    /// Present when building an index that will be incrementally updated
+    module_terms_cache: Option<ModuleTermsCache<'a>>,
 }
 
 impl AddAssign for DocumentTermCount {
@@ -200,13 +250,15 @@ impl AddAssign for DocumentTermCount {
 }
 
 impl<'a> Terms<'a> {
-    fn add(&mut self, word: &str, count: DocumentTermCount, id: (u64, u32)) {
-        self.term_docs
-            .entry(hash_term(word))
-            .or_default()
-            .entry(id)
-            .or_default()
-            .add_assign(count);
+    /// Track tokenized modules, reusing those from `previous` whose subtree hasn't changed
+    fn with_module_terms_cache(previous: BTreeMap<ContentHash, Vec<ItemTerms>>) -> Self {
+        Self {
+            module_terms_cache: Some(ModuleTermsCache {
+                previous,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
     }
 
     fn finalize(self) -> SearchableTerms {
@@ -306,6 +358,18 @@ impl<'a> Terms<'a> {
             })
             .collect();
 
+        let module_terms = match self.module_terms_cache {
+            Some(cache) => {
+                log::debug!(
+                    "Reused {} of {} tokenized modules from the previous index",
+                    cache.reused,
+                    cache.current.len()
+                );
+                cache.current
+            }
+            None => BTreeMap::new(),
+        };
+
         SearchableTerms {
             version: INDEX_FORMAT_VERSION,
             terms,
@@ -313,6 +377,7 @@ impl<'a> Terms<'a> {
             total_document_length,
             authority_scores,
             max_authority,
+            module_terms,
         }
     }
 
@@ -343,6 +408,11 @@ impl<'a> Terms<'a> {
         // Store DocRef for later authority score lookup
         self.docref_by_id.insert(id, item);
 
+        let is_module = matches!(item.inner(), ItemEnum::Module(_));
+        if is_module && let Some(cache) = &mut self.module_terms_cache {
+            cache.open.push(OpenModule::default());
+        }
+
         self.add_for_item(item, id);
 
         match item.inner() {
@@ -371,41 +441,29 @@ impl<'a> Terms<'a> {
             self.recurse(child, &ids, true)
         }
 
+        if is_module && let Some(cache) = &mut self.module_terms_cache {
+            for (id, item_terms) in cache.close_module() {
+                self.add_item_terms(id, &item_terms);
+            }
+        }
+
         self.shortest_paths.insert(id, ids);
     }
 
     fn add_for_item(&mut self, item: DocRef<'a, Item>, id: (u64, u32)) {
-        let mut doc_length = 0;
-
-        // Item name gets very high weight - when someone searches for "vec",
-        // they almost certainly want the Vec struct, not its methods
-        if let Some(name) = item.name() {
-            doc_length += self.add_terms(name, id, 20);
-        }
-
-        if let Some(docs) = &item.docs {
-            // Strip code examples to reduce noise in search results
-            let mut prose_iter = prose_slices(docs);
-
-            // First prose block: split into first paragraph vs rest
-            if let Some(first_prose) = prose_iter.next() {
-                if let Some((first_para, rest)) = first_prose.split_once("\n\n") {
-                    doc_length += self.add_terms(first_para, id, 3);
-                    doc_length += self.add_terms(rest, id, 1);
-                } else {
-                    // No blank line in first prose block - whole thing is first paragraph
-                    doc_length += self.add_terms(first_prose, id, 3);
-                }
-            }
-
-            // All subsequent prose blocks get weight 1
-            for prose in prose_iter {
-                doc_length += self.add_terms(prose, id, 1);
+        // With a cache, the item's terms are added when its module is closed
+        match self
+            .module_terms_cache
+            .as_mut()
+            .and_then(|cache| cache.open.last_mut())
+        {
+            Some(module) => {
+                module.hashes.push(content_hash(item));
+                module.items.push((item, id));
             }
+            None => self.add_item_terms(id, &tokenize_item(item)),
         }
 
-        self.document_lengths.insert(id, DocumentLength(doc_length));
-
         // Count outgoing links for authority scoring
         for link_id in item.links.values() {
             let target = if let Some(item) = item.get(link_id) {
@@ -430,28 +488,137 @@ impl<'a> Terms<'a> {
         );
     }
 
-    fn add_terms(&mut self, text: &str, id: (u64, u32), weight: usize) -> usize {
-        let words = tokenize(text);
-        let doc_length = words.len();
+    fn add_item_terms(&mut self, id: (u64, u32), item_terms: &ItemTerms) {
+        for &(term, count) in &item_terms.terms {
+            self.term_docs
+                .entry(term)
+                .or_default()
+                .entry(id)
+                .or_default()
+                .add_assign(count);
+        }
+
+        self.document_lengths
+            .insert(id, DocumentLength(item_terms.length));
+    }
+}
+
+impl ModuleTermsCache<'_> {
+    /// Finish the innermost open module, returning the terms of its items: reused from the
+    /// previous index if the module's subtree is unchanged, and tokenized otherwise
+    fn close_module(&mut self) -> Vec<((u64, u32), ItemTerms)> {
+        let Some(module) = self.open.pop() else {
+            return vec![];
+        };
 
-        // Count word frequencies in this document
-        let mut word_counts: BTreeMap<&str, usize> = BTreeMap::new();
-        for word in &words {
-            *word_counts.entry(word).or_insert(0) += 1;
+        let mut hasher = FxHasher::default();
+        module.hashes.hash(&mut hasher);
+        let subtree_hash = ContentHash(hasher.finish());
+        if let Some(parent) = self.open.last_mut() {
+            parent.hashes.push(subtree_hash);
         }
 
-        // Add each unique word to the index with weighted count
-        for (word, count) in word_counts {
-            let weighted_count = count * weight;
-            self.add(word, DocumentTermCount(weighted_count), id);
+        let item_terms = match self.current.get(&subtree_hash) {
+            Some(item_terms) => item_terms.clone(),
+            None => {
+                let item_terms = match self.previous.remove(&subtree_hash) {
+                    Some(item_terms) => {
+                        self.reused += 1;
+                        item_terms
+                    }
+                    None => module
+                        .items
+                        .iter()
+                        .map(|&(item, _)| tokenize_item(item))
+                        .collect(),
+                };
+                self.current.insert(subtree_hash, item_terms.clone());
+                item_terms
+            }
+        };
+
+        module
+            .items
+            .into_iter()
+            .map(|(_, id)| id)
+            .zip(item_terms)
+            .collect()
+    }
+}
+
+/// Tokenize an item's name, doc aliases and docs into weighted terms
+fn tokenize_item(item: DocRef<'_, Item>) -> ItemTerms {
+    let mut terms = BTreeMap::new();
+    let mut length = 0;
+
+    // Item name gets very high weight - when someone searches for "vec",
+    // they almost certainly want the Vec struct, not its methods
+    if let Some(name) = item.name() {
+        length += add_terms(&mut terms, name, 20);
+    }
+
+    // Doc aliases are alternative names, so they carry the same weight. They don't count
+    // toward document length, so adding an alias doesn't dilute the item's other terms.
+    for alias in item.doc_aliases() {
+        add_terms(&mut terms, alias, 20);
+    }
+
+    if let Some(docs) = &item.docs {
+        // Strip code examples to reduce noise in search results
+        let mut prose_iter = prose_slices(docs);
+
+        // First prose block: split into first paragraph vs rest
+        if let Some(first_prose) = prose_iter.next() {
+            if let Some((first_para, rest)) = first_prose.split_once("\n\n") {
+                length += add_terms(&mut terms, first_para, 3);
+                length += add_terms(&mut terms, rest, 1);
+            } else {
+                // No blank line in first prose block - whole thing is first paragraph
+                length += add_terms(&mut terms, first_prose, 3);
+            }
+        }
+
+        // All subsequent prose blocks get weight 1
+        for prose in prose_iter {
+            length += add_terms(&mut terms, prose, 1);
         }
+    }
+
+    ItemTerms {
+        length,
+        terms: terms.into_iter().collect(),
+    }
+}
+
+/// Add each word in `text` to `terms` with the given weight, returning the number of words
+fn add_terms(
+    terms: &mut BTreeMap<TermHash, DocumentTermCount>,
+    text: &str,
+    weight: usize,
+) -> usize {
+    let words = tokenize(text);
+    for word in &words {
+        terms
+            .entry(hash_term(word))
+            .or_default()
+            .add_assign(DocumentTermCount(weight));
+    }
+    words.len()
+}
 
-        doc_length
+/// Hash the text that [`tokenize_item`] reads, so that a module's subtree hash changes with it
+fn content_hash(item: DocRef<'_, Item>) -> ContentHash {
+    let mut hasher = FxHasher::default();
+    item.name().hash(&mut hasher);
+    for alias in item.doc_aliases() {
+        alias.hash(&mut hasher);
     }
+    item.docs.hash(&mut hasher);
+    ContentHash(hasher.finish())
 }
 
 /// Index format version - increment to invalidate all cached indexes
-const INDEX_FORMAT_VERSION: u32 = 1;
+const INDEX_FORMAT_VERSION: u32 = 5;
 
 #[derive(Debug, Clone, Archive, RkyvSerialize, RkyvDeserialize)]
 struct SearchableTerms {
@@ -465,6 +632,17 @@ struct SearchableTerms {
     authority_scores: Vec<usize>,
     /// Maximum authority score in this crate (for normalization)
     max_authority: usize,
+    /// Each module's tokenized items by the module's subtree hash, kept for workspace crates so
+    /// that rebuilding the index after a docs rebuild only re-tokenizes modules that changed
+    module_terms: BTreeMap<ContentHash, Vec<ItemTerms>>,
+}
+
+/// An index read back from the on-disk cache
+enum CachedIndex {
+    /// Built from the current docs
+    Fresh(SearchableTerms),
+    /// Built from an older version of the docs
+    Stale(SearchableTerms),
 }
 
 /// A search index for a single crate
@@ -542,6 +720,7 @@ impl SearchIndex {
     pub fn load_or_build<'a>(
         navigator: &'a Navigator,
         crate_name: &str,
+        progress: &dyn Progress,
     ) -> Result<Self, Vec<Suggestion<'a>>> {
         let mut suggestions = vec![];
 
@@ -561,18 +740,34 @@ impl SearchIndex {
         let mut path = crate_docs.fs_path().to_path_buf();
         path.set_extension("index");
 
-        if let Some(terms) = Self::load(&path, mtime) {
-            log::debug!("Loaded cached index from disk for {crate_name}");
-            Ok(Self { crate_name, terms })
+        let previous = match Self::load(&path, mtime) {
+            Some(CachedIndex::Fresh(terms)) => {
+                log::debug!("Loaded cached index from disk for {crate_name}");
+                return Ok(Self { crate_name, terms });
+            }
+            Some(CachedIndex::Stale(terms)) => terms.module_terms,
+            None => BTreeMap::new(),
+        };
+
+        let _task = ProgressTask::start(progress, &format!("Indexing {crate_name} for search"));
+
+        // Workspace crates are rebuilt as they're edited, so keep their tokenized modules around
+        // to make the next rebuild incremental
+        let mut terms = if crate_docs.provenance().is_workspace() {
+            log::debug!(
+                "Building index for {crate_name} ({} cached modules)",
+                previous.len()
+            );
+            Terms::with_module_terms_cache(previous)
         } else {
             log::debug!("Building new index for {crate_name}");
-            let mut terms = Terms::default();
-            terms.recurse(item, &[], false);
-            let terms = terms.finalize();
-            log::debug!("Finished building index for {crate_name}");
-            Self::store(&terms, &path);
-            Ok(Self { terms, crate_name })
-        }
+            Terms::default()
+        };
+        terms.recurse(item, &[], false);
+        let terms = terms.finalize();
+        log::debug!("Finished building index for {crate_name}");
+        Self::store(&terms, &path);
+        Ok(Self { terms, crate_name })
     }
 
     fn store(terms: &SearchableTerms, path: &Path) {
@@ -590,37 +785,43 @@ impl SearchIndex {
         }
     }
 
-    fn load(path: &Path, mtime: Option<SystemTime>) -> Option<SearchableTerms> {
+    /// Load the cached index at `path`, noting whether it is older than the crate's docs
+    ///
+    /// A stale index is removed from disk but still returned, so that its tokenized items can be
+    /// reused when rebuilding.
+    fn load(path: &Path, mtime: Option<SystemTime>) -> Option<CachedIndex> {
         let mut file = File::open(path).ok()?;
         let index_mtime = file.metadata().ok().and_then(|m| m.modified().ok())?;
 
         let mtime = mtime?;
-        if index_mtime.duration_since(mtime).is_ok() {
-            let mut bytes = Vec::new();
-            file.read_to_end(&mut bytes).ok()?;
-            match rkyv::from_bytes::<SearchableTerms, Error>(&bytes) {
-                Ok(terms) => {
-                    if terms.version == INDEX_FORMAT_VERSION {
-                        Some(terms)
-                    } else {
-                        log::debug!(
-                            "Index version mismatch at {}: found {}, expected {}",
-                            path.display(),
-                            terms.version,
-                            INDEX_FORMAT_VERSION
-                        );
-                        let _ = fs::remove_file(path);
-                        None
-                    }
-                }
-                Err(_) => {
-                    let _ = fs::remove_file(path);
-                    None
-                }
+        let fresh = index_mtime.duration_since(mtime).is_ok();
+
+        let mut bytes = Vec::new();
+        let terms = file
+            .read_to_end(&mut bytes)
+            .ok()
+            .and_then(|_| rkyv::from_bytes::<SearchableTerms, Error>(&bytes).ok());
+
+        match terms {
+            Some(terms) if terms.version != INDEX_FORMAT_VERSION => {
+                log::debug!(
+                    "Index version mismatch at {}: found {}, expected {}",
+                    path.display(),
+                    terms.version,
+                    INDEX_FORMAT_VERSION
+                );
+                let _ = fs::remove_file(path);
+                None
+            }
+            Some(terms) if fresh => Some(CachedIndex::Fresh(terms)),
+            Some(terms) => {
+                let _ = fs::remove_file(path);
+                Some(CachedIndex::Stale(terms))
+            }
+            None => {
+                let _ = fs::remove_file(path);
+                None
             }
-        } else {
-            let _ = fs::remove_file(path);
-            None
         }
     }
 
@@ -637,6 +838,14 @@ impl SearchIndex {
     pub fn search<'a>(&self, query: &'a str) -> SearchResults<'a> {
         self.terms.search(query)
     }
+
+    /// Id paths of every indexed item, for matchers that work on names rather than terms
+    pub fn id_paths(&self) -> impl Iterator<Item = &[u32]> {
+        self.terms
+            .documents
+            .iter()
+            .map(|document| &*document.path.0)
+    }
 }
 
 // Public API types for BM25 scoring
@@ -681,6 +890,9 @@ pub struct ScoredResult<'a> {
     pub authority: f32,
 }
 
+/// How many of the top results [`BM25Scorer::score_with_boost`] applies its boost to
+pub const BOOST_CANDIDATES: usize = 100;
+
 /// BM25 scorer for combining results from multiple crates
 pub struct BM25Scorer<'a> {
     k1: f32,
@@ -710,6 +922,16 @@ impl<'a> BM25Scorer<'a> {
 
     /// Compute BM25 scores for all results and return them sorted by score
     pub fn score(self) -> Vec<ScoredResult<'a>> {
+        self.score_with_boost(|_, _| 1.0)
+    }
+
+    /// Compute BM25 scores, then multiply the top results' scores by `boost`
+    ///
+    /// `boost` is called with the crate name and id path of each of the
+    /// [`BOOST_CANDIDATES`] highest scoring results, and should return 1.0 for no change. This
+    /// lets callers rank results by context the index doesn't know about (such as the item
+    /// being viewed) without paying for it on every match.
+    pub fn score_with_boost(self, boost: impl Fn(&'a str, &[u32]) -> f32) -> Vec<ScoredResult<'a>> {
         log::debug!("Computing global statistics");
 
         // Aggregate global statistics
@@ -801,6 +1023,12 @@ impl<'a> BM25Scorer<'a> {
         // Sort by combined score (descending)
         scored.sort_by(|a, b| b.score.total_cmp(&a.score));
 
+        let candidates = scored.len().min(BOOST_CANDIDATES);
+        for result in &mut scored[..candidates] {
+            result.score *= boost(result.crate_name, &result.id_path);
+        }
+        scored[..candidates].sort_by(|a, b| b.score.total_cmp(&a.score));
+
         scored
     }
 }
@@ -816,10 +1044,16 @@ fn add_token<'a>(token: &'a str, tokens: &mut Vec<&'a str>) {
 }
 
 /// Simple tokenizer: split on whitespace and punctuation, lowercase, filter short words
+///
+/// Identifiers are also split into their camel and snake case parts, so `TcpListener` yields
+/// `Tcp` and `Listener` and `read_to_string` yields `read` and `string`. An acronym run ends
+/// before its last capital when followed by lowercase, so `HTTPServer` yields `HTTP` and `Server`.
 fn tokenize(text: &str) -> Vec<&str> {
     let mut tokens = vec![];
     let min_chars = 2;
     let mut last_case = None;
+    let mut second_last_case = None;
+    let mut last_index = 0;
     let mut word_start = 0;
     let mut subword_start = 0;
     let mut word_start_next_char = true;
@@ -840,7 +1074,13 @@ fn tokenize(text: &str) -> Vec<&str> {
 
         let current_case = c.is_alphabetic().then(|| c.is_uppercase());
         let case_change = last_case == Some(false) && current_case == Some(true);
+        let acronym_end = second_last_case == Some(true)
+            && last_case == Some(true)
+            && current_case == Some(false);
+        second_last_case = last_case;
         last_case = current_case;
+        let previous_index = last_index;
+        last_index = i;
 
         if c == '-' || c == '_' {
             if i.saturating_sub(subword_start) > min_chars {
@@ -860,6 +1100,11 @@ fn tokenize(text: &str) -> Vec<&str> {
                 add_token(&text[subword_start..i], &mut tokens);
             }
             subword_start = i;
+        } else if acronym_end {
+            if previous_index.saturating_sub(subword_start) > min_chars {
+                add_token(&text[subword_start..previous_index], &mut tokens);
+            }
+            subword_start = previous_index;
         }
     }
 
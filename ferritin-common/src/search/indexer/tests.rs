@@ -127,3 +127,63 @@ fn test_prose_slices_matches_pulldown_cmark() {
         );
     }
 }
+
+#[test]
+fn test_tokenize_acronyms() {
+    assert_eq!(
+        tokenize("TCPListener HTTPServer IOError"),
+        vec![
+            "TCP",
+            "Listener",
+            "TCPListener",
+            "HTTP",
+            "Server",
+            "HTTPServer",
+            "Error",
+            "IOError"
+        ]
+    );
+
+    // Runs of capitals with no lowercase after them are left whole
+    assert_eq!(
+        tokenize("TcpListener STRATEGY"),
+        vec!["Tcp", "Listener", "TcpListener", "STRATEGY"]
+    );
+}
+
+#[test]
+fn test_add_terms_weights_each_occurrence() {
+    let mut terms = BTreeMap::new();
+    assert_eq!(add_terms(&mut terms, "Vec vec push", 3), 3);
+    assert_eq!(terms[&hash_term("vec")], DocumentTermCount(6));
+    assert_eq!(terms[&hash_term("push")], DocumentTermCount(3));
+}
+
+#[test]
+fn test_unchanged_modules_are_reused() {
+    let navigator = crate::tests::test_navigator();
+    let root = navigator
+        .resolve_path("fixture-crate", &mut vec![])
+        .unwrap();
+    let build = |previous| {
+        let mut terms = Terms::with_module_terms_cache(previous);
+        terms.recurse(root, &[], false);
+        let reused = terms.module_terms_cache.as_ref().unwrap().reused;
+        (terms.finalize(), reused)
+    };
+
+    let (first, reused) = build(BTreeMap::new());
+    assert_eq!(reused, 0);
+    assert!(first.module_terms.len() > 1);
+
+    let (second, reused) = build(first.module_terms.clone());
+    assert_eq!(reused, first.module_terms.len());
+    assert_eq!(format!("{:?}", second.terms), format!("{:?}", first.terms));
+
+    // A module missing from the previous index is tokenized again, the rest are still reused
+    let mut previous = first.module_terms.clone();
+    previous.pop_first();
+    let (third, reused) = build(previous);
+    assert_eq!(reused, first.module_terms.len() - 1);
+    assert_eq!(format!("{:?}", third.terms), format!("{:?}", first.terms));
+}
@@ -0,0 +1,113 @@
+use rayon::prelude::*;
+use regex::Regex;
+use rustdoc_types::Item;
+
+use crate::{DocRef, Navigator, navigator::Suggestion};
+
+/// A query matched against item names and paths rather than documentation terms
+#[derive(Debug, Clone)]
+pub struct PathPattern {
+    regex: Regex,
+}
+
+impl PathPattern {
+    /// A regular expression, matched anywhere in an item's name or full path
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(|regex| Self { regex })
+    }
+
+    /// A glob where `*` matches any run of characters and `?` matches exactly one
+    ///
+    /// Unlike [`PathPattern::regex`], a glob must match the whole name or the whole path.
+    pub fn glob(pattern: &str) -> Result<Self, regex::Error> {
+        let mut regex = String::from("^");
+        for c in pattern.chars() {
+            match c {
+                '*' => regex.push_str(".*"),
+                '?' => regex.push('.'),
+                c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        regex.push('$');
+        Self::regex(&regex)
+    }
+
+    /// Whether an item with this name and full path matches
+    pub fn is_match(&self, name: &str, path: &str) -> bool {
+        self.regex.is_match(name) || self.regex.is_match(path)
+    }
+}
+
+/// An item whose name or path matched a [`PathPattern`]
+#[derive(Debug, Clone)]
+pub struct PatternMatch<'a> {
+    pub item: DocRef<'a, Item>,
+    /// Full path, e.g. `"std::str::FromStr::from_str"`
+    pub path: String,
+}
+
+impl Navigator {
+    /// Search across multiple crates for items whose name or path matches a pattern
+    ///
+    /// This walks every item in each crate's search index instead of scoring terms, so it
+    /// finds items by the shape of their name. Results are sorted with shorter (more
+    /// canonical) paths first.
+    ///
+    /// Returns Err with suggestions if no crates could be loaded/indexed.
+    pub fn search_by_pattern<'nav>(
+        &'nav self,
+        pattern: &PathPattern,
+        crate_names: &[&str],
+    ) -> Result<Vec<PatternMatch<'nav>>, Vec<Suggestion<'nav>>> {
+        if crate_names.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let results: Vec<_> = crate_names
+            .par_iter()
+            .map(|&crate_name| {
+                let index = self.get_or_build_search_index(crate_name)?;
+                Ok(index
+                    .id_paths()
+                    .filter_map(|id_path| self.get_item_from_id_path(crate_name, id_path))
+                    .filter_map(|(item, segments)| {
+                        let name = segments.last()?;
+                        let path = segments.join("::");
+                        pattern
+                            .is_match(name, &path)
+                            .then_some(PatternMatch { item, path })
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .collect();
+
+        let mut matches = Vec::new();
+        let mut any_loaded = false;
+        let mut first_error = None;
+
+        for result in results {
+            match result {
+                Ok(crate_matches) => {
+                    any_loaded = true;
+                    matches.extend(crate_matches);
+                }
+                Err(suggestions) if first_error.is_none() => first_error = Some(suggestions),
+                Err(_) => {}
+            }
+        }
+
+        // If no crates could be searched, return the first error
+        if !any_loaded && let Some(suggestions) = first_error {
+            return Err(suggestions);
+        }
+
+        matches.sort_by(|a, b| {
+            a.path
+                .len()
+                .cmp(&b.path.len())
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        Ok(matches)
+    }
+}
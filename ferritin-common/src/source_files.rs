@@ -0,0 +1,93 @@
+use crate::sources::parse_package_dir;
+use crate::{DocRef, Navigator};
+use rustdoc_types::{Item, Span};
+use semver::Version;
+use std::path::{Path, PathBuf};
+
+impl Navigator {
+    /// Resolve the file a span points into - if it's relative, make it relative to the
+    /// project root
+    pub fn span_file_path(&self, span: &Span) -> Option<PathBuf> {
+        if span.filename.is_absolute() {
+            Some(span.filename.clone())
+        } else {
+            // No project and relative path - can't resolve
+            self.project_root()
+                .map(|project_root| project_root.join(&span.filename))
+        }
+    }
+
+    /// The file a span of `item` points into, downloading the crate's source if it isn't on
+    /// this machine
+    ///
+    /// Crates documented on docs.rs have spans relative to their package, or, for items from
+    /// their dependencies, into the registry sources of the machine that built them.
+    pub fn source_file_path(&self, item: DocRef<'_, Item>, span: &Span) -> Option<PathBuf> {
+        let local_path = self.span_file_path(span);
+        if local_path.as_deref().is_some_and(Path::is_file) {
+            return local_path;
+        }
+
+        let crate_docs = item.crate_docs();
+        let (crate_name, version, relative_path) = match registry_source_path(&span.filename) {
+            Some(found) => found,
+            None if span.filename.is_relative()
+                && !crate_docs.provenance().is_workspace()
+                && !crate_docs.provenance().is_std() =>
+            {
+                (
+                    crate_docs.name(),
+                    crate_docs.version()?.clone(),
+                    span.filename.clone(),
+                )
+            }
+            None => return local_path,
+        };
+
+        let source_dir = self
+            .vendored_source()
+            .and_then(|vendored| vendored.source_dir(crate_name, &version))
+            .or_else(|| {
+                self.docsrs_source()?
+                    .crate_source(crate_name, &version, self.progress())
+                    .inspect_err(|error| log::warn!("{error}"))
+                    .ok()
+            })?;
+        Some(source_dir.join(relative_path))
+    }
+}
+
+/// The crate, version and path within the package of a file in cargo's registry sources, like
+/// `/home/user/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f/serde-1.0.219/src/de.rs`
+fn registry_source_path(path: &Path) -> Option<(&str, Version, PathBuf)> {
+    let components = path
+        .components()
+        .filter_map(|component| component.as_os_str().to_str())
+        .collect::<Vec<_>>();
+    let registry_src = components
+        .windows(2)
+        .position(|pair| pair == ["registry", "src"])?;
+    // Past `registry/src` come the registry's directory and the package's
+    let package_dir = components.get(registry_src + 3)?;
+    let (crate_name, version) = parse_package_dir(package_dir)?;
+    let relative_path = components[registry_src + 4..].iter().collect();
+    Some((crate_name, version, relative_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_packages_in_registry_sources() {
+        let (crate_name, version, relative_path) = registry_source_path(Path::new(
+            "/opt/rustwide/cargo-home/registry/src/index.crates.io-6f17d22bba15001f/serde-json-1.0.140/src/de.rs",
+        ))
+        .unwrap();
+        assert_eq!(crate_name, "serde-json");
+        assert_eq!(version, Version::new(1, 0, 140));
+        assert_eq!(relative_path, Path::new("src/de.rs"));
+
+        assert!(registry_source_path(Path::new("src/lib.rs")).is_none());
+    }
+}
@@ -4,18 +4,23 @@
 //! - StdSource: rustup-managed std library docs
 //! - LocalSource: workspace-local crates (built on demand)
 //! - DocsRsSource: fetched from docs.rs and cached
-use crate::{CrateName, RustdocData, navigator::CrateInfo};
+//! - VendoredSource: built from downloaded registry sources when docs.rs has no JSON
+use crate::{CrateName, RustdocData, navigator::CrateInfo, progress::Progress};
+use fieldwork::Fieldwork;
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Deserializer};
 
 mod docsrs;
 mod local;
 mod std;
+mod vendored;
 
 use ::std::borrow::Cow;
-pub use docsrs::DocsRsSource;
-pub use local::LocalSource;
-pub use std::StdSource;
+use ::std::fmt::{self, Display, Formatter};
+pub use docsrs::{CacheEntry, DocsRsSource};
+pub use local::{BuiltDocs, LocalSource, Registry};
+pub use std::{DEFAULT_TOOLCHAIN, StdSource, ToolchainDocs};
+pub use vendored::{VendoredSource, parse_package_dir};
 
 #[derive(Deserialize, Debug)]
 struct RustdocVersion {
@@ -57,6 +62,61 @@ impl CrateProvenance {
     }
 }
 
+/// The dependency table a local dependency is declared in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DependencyKind {
+    Normal,
+    Build,
+    Dev,
+}
+
+impl DependencyKind {
+    pub fn is_normal(&self) -> bool {
+        matches!(self, Self::Normal)
+    }
+
+    pub fn is_build(&self) -> bool {
+        matches!(self, Self::Build)
+    }
+
+    pub fn is_dev(&self) -> bool {
+        matches!(self, Self::Dev)
+    }
+}
+
+/// How a workspace package depends on a local dependency
+///
+/// A crate pulled in through several tables (e.g. `[dependencies]` and
+/// `[target.'cfg(windows)'.build-dependencies]`) has one scope per table.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Fieldwork)]
+#[fieldwork(get)]
+pub struct DependencyScope {
+    #[field(copy)]
+    pub(crate) kind: DependencyKind,
+    /// Target triple or `cfg(...)` expression this dependency is restricted to
+    pub(crate) target: Option<String>,
+}
+
+impl DependencyScope {
+    /// Whether this is an unconditional `[dependencies]` entry
+    pub fn is_unconditional_normal(&self) -> bool {
+        self.kind.is_normal() && self.target.is_none()
+    }
+}
+
+impl Display for DependencyScope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.kind, &self.target) {
+            (DependencyKind::Normal, None) => f.write_str("normal"),
+            (DependencyKind::Build, None) => f.write_str("build"),
+            (DependencyKind::Dev, None) => f.write_str("dev"),
+            (DependencyKind::Normal, Some(target)) => f.write_str(target),
+            (DependencyKind::Build, Some(target)) => write!(f, "build, {target}"),
+            (DependencyKind::Dev, Some(target)) => write!(f, "dev, {target}"),
+        }
+    }
+}
+
 /// Trait for documentation sources
 ///
 /// Each source (std, local workspace, docs.rs) implements this trait to provide:
@@ -77,7 +137,14 @@ pub trait Source {
     fn lookup<'a>(&'a self, crate_name: &str, version: &VersionReq) -> Option<Cow<'a, CrateInfo>>;
 
     /// Load the rustdoc JSON data for a crate (by canonical name)
-    fn load(&self, crate_name: &str, version: Option<&Version>) -> Option<RustdocData>;
+    ///
+    /// Downloading or building docs can take a while, and is reported to `progress`.
+    fn load(
+        &self,
+        crate_name: &str,
+        version: Option<&Version>,
+        progress: &dyn Progress,
+    ) -> Option<RustdocData>;
 
     /// List all available crates from this source
     /// Returns None if this source doesn't support listing (e.g., DocsRsSource)
@@ -85,3 +152,82 @@ pub trait Source {
         Box::new(::std::iter::empty())
     }
 }
+
+/// Run a cargo `command` to completion, reporting its build progress to `progress`
+///
+/// Cargo only draws its progress bar on a terminal unless asked to, and then writes it to
+/// stderr as lines ending in carriage returns, like `Building [=====>    ] 12/40: serde`.
+/// Those lines are left out of the output's stderr.
+pub(crate) fn run_cargo(
+    command: &mut ::std::process::Command,
+    progress: &dyn Progress,
+) -> ::std::io::Result<::std::process::Output> {
+    use ::std::io::{BufRead, BufReader, Read};
+    use ::std::process::Stdio;
+
+    let mut child = command
+        .env("CARGO_TERM_PROGRESS_WHEN", "always")
+        .env("CARGO_TERM_PROGRESS_WIDTH", "80")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Cargo could fill the stdout pipe while we wait on stderr, so it is read alongside
+    let mut stdout_pipe = child.stdout.take();
+    let stdout_reader = ::std::thread::spawn(move || {
+        let mut stdout = vec![];
+        if let Some(pipe) = &mut stdout_pipe {
+            let _ = pipe.read_to_end(&mut stdout);
+        }
+        stdout
+    });
+
+    let mut stderr = vec![];
+    if let Some(pipe) = child.stderr.take() {
+        let mut reader = BufReader::new(pipe);
+        let mut line = vec![];
+        while reader.read_until(b'\r', &mut line)? > 0 {
+            // A carriage return ends a progress line, and other lines end in newlines
+            for part in line.split_inclusive(|byte| *byte == b'\n') {
+                match cargo_progress(&String::from_utf8_lossy(part)) {
+                    Some((done, total)) => progress.advance(done, total),
+                    None => stderr.extend_from_slice(part),
+                }
+            }
+            line.clear();
+        }
+    }
+
+    Ok(::std::process::Output {
+        status: child.wait()?,
+        stdout: stdout_reader.join().unwrap_or_default(),
+        stderr,
+    })
+}
+
+/// The units done and the total in one of cargo's progress lines
+fn cargo_progress(line: &str) -> Option<(u64, u64)> {
+    let (_, counts) = line
+        .trim_start()
+        .strip_prefix("Building [")?
+        .split_once("] ")?;
+    let counts = counts.split(':').next()?;
+    let (done, total) = counts.trim().split_once('/')?;
+    Some((done.parse().ok()?, total.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cargo_progress_lines() {
+        assert_eq!(
+            cargo_progress("    Building [=====>          ] 12/40: serde, syn\r"),
+            Some((12, 40))
+        );
+        assert_eq!(cargo_progress("    Building [     ] 0/3\r"), Some((0, 3)));
+        assert_eq!(cargo_progress("   Compiling serde v1.0.219\n"), None);
+        assert_eq!(cargo_progress("error: could not compile `foo`\n"), None);
+    }
+}
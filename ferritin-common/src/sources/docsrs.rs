@@ -1,14 +1,52 @@
-use super::{CrateProvenance, Source};
+use super::{CrateProvenance, Registry, Source};
+use crate::parsed_cache::CACHE_EXTENSION;
+use crate::progress::Progress;
 use crate::{CrateInfo, RustdocData};
 use anyhow::Result;
 use fieldwork::Fieldwork;
 use semver::{Version, VersionReq};
-use std::{borrow::Cow, path::PathBuf};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 use trillium_smol::async_io::block_on;
 
 mod client;
 use client::{DocsRsClient, ResolvedMetadata};
 
+/// One downloaded crate version in the docs.rs cache
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub crate_name: String,
+    pub version: Version,
+    /// The rustdoc JSON format version docs.rs served it in
+    pub format_version: u32,
+    /// The cached rustdoc JSON
+    pub path: PathBuf,
+    /// Combined size of the JSON and the search index and parsed docs cache built from it, in
+    /// bytes
+    pub size: u64,
+    /// When the JSON was last read, or written if the filesystem doesn't record reads
+    pub last_used: Option<SystemTime>,
+}
+
+impl CacheEntry {
+    /// Delete the cached JSON, its search index and its parsed docs cache
+    pub fn remove(&self) -> io::Result<()> {
+        std::fs::remove_file(&self.path)?;
+        for extension in ["index", CACHE_EXTENSION] {
+            match std::fs::remove_file(self.path.with_extension(extension)) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Source for docs.rs documentation
 #[derive(Debug, Fieldwork)]
 pub struct DocsRsSource {
@@ -31,9 +69,100 @@ impl DocsRsSource {
             .map(|client| Self { client })
     }
 
-    /// Load a crate from docs.rs
-    async fn load_async(&self, crate_name: &str, version: &Version) -> Result<Option<RustdocData>> {
-        self.client.get_crate(crate_name, version).await
+    /// Only use previously downloaded docs, never making network requests
+    pub fn with_offline(self, offline: bool) -> Self {
+        Self {
+            client: self.client.with_offline(offline),
+        }
+    }
+
+    /// Fetch rustdoc JSON for crates.io crates from a docs.rs mirror at `docs_url` instead of
+    /// https://docs.rs
+    pub fn with_docs_url(self, docs_url: Option<String>) -> Self {
+        Self {
+            client: self.client.with_docs_url(docs_url),
+        }
+    }
+
+    /// Whether this source is restricted to previously downloaded docs
+    pub fn is_offline(&self) -> bool {
+        self.client.is_offline()
+    }
+
+    /// Load a crate from docs.rs, or from a private registry's docs server
+    async fn load_async(
+        &self,
+        crate_name: &str,
+        version: &Version,
+        registry: Option<&Registry>,
+        progress: &dyn Progress,
+    ) -> Result<Option<RustdocData>> {
+        self.client
+            .get_crate(crate_name, version, registry, progress)
+            .await
+    }
+
+    /// Load a crate from the docs server of the private registry it is published in
+    ///
+    /// Returns None when the registry has no docs server, so that the crate is built locally.
+    pub fn load_from_registry(
+        &self,
+        crate_name: &str,
+        version: &Version,
+        registry: &Registry,
+        progress: &dyn Progress,
+    ) -> Option<RustdocData> {
+        registry.docs_url()?;
+        block_on(self.load_async(crate_name, version, Some(registry), progress))
+            .inspect_err(|error| warn_unless_cancelled(error, progress))
+            .ok()
+            .flatten()
+    }
+
+    /// Whether docs for this crate version have already been downloaded
+    pub fn is_cached(&self, crate_name: &str, version: &Version) -> bool {
+        self.client.is_cached(crate_name, version)
+    }
+
+    /// The directory downloaded docs are cached in
+    pub fn cache_dir(&self) -> &Path {
+        self.client.cache_dir()
+    }
+
+    /// Every crate version in the cache, with its size and when it was last used
+    pub fn cache_entries(&self) -> Vec<CacheEntry> {
+        self.client.cache_entries()
+    }
+
+    /// Every version of this crate that has been downloaded, newest first
+    pub fn cached_versions(&self, crate_name: &str) -> Vec<Version> {
+        self.client.cached_versions(crate_name)
+    }
+
+    /// Download docs for a crate version into the cache, returning whether docs.rs has them
+    pub fn download(
+        &self,
+        crate_name: &str,
+        version: &Version,
+        progress: &dyn Progress,
+    ) -> Result<bool> {
+        Ok(block_on(self.load_async(crate_name, version, None, progress))?.is_some())
+    }
+
+    /// The unpacked source of a crates.io crate version, downloaded from static.crates.io into
+    /// the cache the first time it's asked for
+    pub fn crate_source(
+        &self,
+        crate_name: &str,
+        version: &Version,
+        progress: &dyn Progress,
+    ) -> Result<PathBuf> {
+        block_on(self.client.crate_source(crate_name, version, progress))
+    }
+
+    /// An image linked from docs, from the cache or downloaded the first time it's asked for
+    pub fn image(&self, url: &str) -> Result<Vec<u8>> {
+        block_on(self.client.image(url))
     }
 
     /// Docs.rs has unbounded crates, so we don't provide a list
@@ -56,17 +185,36 @@ impl Source for DocsRsSource {
         Some(Cow::Owned(CrateInfo {
             provenance: CrateProvenance::DocsRs,
             version: Some(version),
-            description: Some(description),
+            description,
             name,
             default_crate: false,
             used_by: vec![],
+            dependency_scopes: vec![],
             json_path: None,
+            manifest_path: None,
+            features: BTreeMap::new(),
         }))
     }
 
-    fn load(&self, crate_name: &str, version: Option<&Version>) -> Option<RustdocData> {
-        block_on(self.load_async(crate_name, version?))
+    fn load(
+        &self,
+        crate_name: &str,
+        version: Option<&Version>,
+        progress: &dyn Progress,
+    ) -> Option<RustdocData> {
+        block_on(self.load_async(crate_name, version?, None, progress))
+            .inspect_err(|error| warn_unless_cancelled(error, progress))
             .ok()
             .flatten()
     }
 }
+
+/// Log a failed download, unless it failed because it was cancelled, which isn't news to
+/// whoever cancelled it
+fn warn_unless_cancelled(error: &anyhow::Error, progress: &dyn Progress) {
+    if progress.is_cancelled() {
+        log::debug!("{error}");
+    } else {
+        log::warn!("{error}");
+    }
+}
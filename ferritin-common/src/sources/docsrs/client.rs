@@ -1,15 +1,29 @@
-use crate::sources::CrateProvenance;
+use super::CacheEntry;
+use crate::conversions::MIN_FORMAT_VERSION;
+use crate::parsed_cache::{self, CACHE_EXTENSION, ParsedCrate};
+use crate::progress::{NoProgress, Progress, ProgressTask};
+use crate::sources::{CrateProvenance, Registry};
 use crate::{RustdocData, sources::RustdocVersion};
 use anyhow::{Context, Result, anyhow};
 use fieldwork::Fieldwork;
+use futures_lite::AsyncReadExt;
+use rustc_hash::FxHasher;
 use rustdoc_types::FORMAT_VERSION;
 use semver::{Version, VersionReq};
 use serde::Deserialize;
-use trillium_client::{Client, Status};
+use sha2::{Digest, Sha256};
+use trillium_client::{Client, KnownHeaderName, Status};
 use trillium_rustls::RustlsConfig;
 use trillium_smol::ClientConfig;
 
+use std::future::poll_fn;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::pin::{Pin, pin};
+use std::process::Command;
+use std::task::Poll;
+use std::time::Duration;
+use trillium_smol::async_io::Timer;
 
 #[derive(Deserialize)]
 struct CratesIoResponse {
@@ -30,8 +44,16 @@ struct CrateVersion {
     pub(super) num: Version,
 }
 
-/// Minimum supported format version (inclusive)
-const MIN_FORMAT_VERSION: u32 = 55;
+#[derive(Deserialize)]
+struct CratesIoVersionResponse {
+    version: VersionChecksum,
+}
+
+#[derive(Deserialize, Debug)]
+struct VersionChecksum {
+    /// SHA-256 of the version's `.crate` file, in hex
+    checksum: String,
+}
 
 /// Client for fetching rustdoc JSON from docs.rs
 #[derive(Debug, Fieldwork)]
@@ -40,13 +62,20 @@ pub struct DocsRsClient {
     #[field(get)]
     cache_dir: PathBuf,
     format_version: u32,
+    /// Only use the cache, never making requests to crates.io or docs.rs
+    #[field(get = "is_offline", with)]
+    offline: bool,
+    /// Where rustdoc JSON is fetched from for crates.io crates, if not https://docs.rs
+    #[field(get)]
+    docs_url: Option<String>,
 }
 
 #[derive(Debug)]
 pub(super) struct ResolvedMetadata {
     pub(super) name: String,
     pub(super) version: Version,
-    pub(super) description: String,
+    /// Only known when resolved through crates.io
+    pub(super) description: Option<String>,
 }
 
 impl DocsRsClient {
@@ -58,14 +87,26 @@ impl DocsRsClient {
             http_client,
             cache_dir,
             format_version: FORMAT_VERSION,
+            offline: false,
+            docs_url: None,
         })
     }
 
+    /// Fetch rustdoc JSON for crates.io crates from a docs.rs mirror at `docs_url`
+    pub fn with_docs_url(mut self, docs_url: Option<String>) -> Self {
+        self.docs_url = docs_url;
+        self
+    }
+
     pub(super) async fn resolve(
         &self,
         crate_name: &str,
         version_req: &VersionReq,
     ) -> Result<Option<ResolvedMetadata>> {
+        if self.offline {
+            return Ok(self.resolve_cached(crate_name, version_req));
+        }
+
         let Some((
             CrateMetadata {
                 name,
@@ -93,12 +134,40 @@ impl DocsRsClient {
         Ok(version.map(|version| ResolvedMetadata {
             name,
             version,
-            description,
+            description: Some(description),
         }))
     }
 
+    /// Resolve a version requirement against the cache alone, for offline use
+    fn resolve_cached(
+        &self,
+        crate_name: &str,
+        version_req: &VersionReq,
+    ) -> Option<ResolvedMetadata> {
+        let version = self
+            .cached_versions(crate_name)
+            .into_iter()
+            .find(|version| version_req.matches(version));
+
+        if version.is_none() {
+            log::warn!(
+                "{crate_name} ({version_req}) is not in the docs.rs cache, and ferritin is \
+                 offline. Run once without --offline to download it."
+            );
+        }
+
+        version.map(|version| ResolvedMetadata {
+            name: crate_name.to_string(),
+            version,
+            description: None,
+        })
+    }
+
     /// Fetch rustdoc JSON for a crate, checking cache first
     ///
+    /// Crates from a private `registry` are fetched from its docs server and cached apart from
+    /// docs.rs downloads, since their names may collide with crates.io crates.
+    ///
     /// Returns:
     /// - Ok(Some(data)) if the crate was found (cached or fetched)
     /// - Ok(None) if docs.rs doesn't have this crate/version
@@ -107,14 +176,33 @@ impl DocsRsClient {
         &self,
         crate_name: &str,
         version: &Version,
+        registry: Option<&Registry>,
+        progress: &dyn Progress,
     ) -> Result<Option<RustdocData>> {
         log::debug!("DocsRsClient::get_crate('{}', {:?})", crate_name, version);
 
         // Check cache first (now that we have a specific version)
-        if let Some(cached) = self.load_from_cache(crate_name, version).await? {
+        if let Some(cached) = self.load_from_cache(crate_name, version, registry).await? {
             return Ok(Some(cached));
         }
 
+        if self.offline {
+            return Err(anyhow!(
+                "{crate_name}@{version} is not cached, and ferritin is offline. Run once without \
+                 --offline to download it."
+            ));
+        }
+
+        let docs_server = if registry.is_some() {
+            "its registry"
+        } else {
+            "docs.rs"
+        };
+        let _task = ProgressTask::start(
+            progress,
+            &format!("Downloading {crate_name} {version} docs from {docs_server}"),
+        );
+
         // Fetch from docs.rs
         // Try format versions in descending order (newest we support first)
         let mut bytes = None;
@@ -126,10 +214,8 @@ impl DocsRsClient {
                 format_ver
             );
 
-            if let Some(fetched) = self
-                .fetch_from_docsrs(crate_name, version, format_ver)
-                .await?
-            {
+            let fetch = self.fetch_from_docsrs(crate_name, version, format_ver, registry, progress);
+            if let Some(fetched) = cancellable(fetch, progress).await? {
                 bytes = Some(fetched);
                 break;
             }
@@ -156,7 +242,7 @@ impl DocsRsClient {
 
         // Save raw JSON to cache (indexed by source format version)
         let fs_path = self
-            .save_to_cache(crate_name, &crate_version, format_version, &json)
+            .save_to_cache(crate_name, &crate_version, format_version, &json, registry)
             .await?;
 
         // Normalize to current format version
@@ -171,6 +257,8 @@ impl DocsRsClient {
             fs_path,
             version: Some(crate_version),
             path_to_id: Default::default(),
+            lazy_items: None,
+            versioned_paths: false,
         };
 
         Ok(Some(data))
@@ -217,6 +305,160 @@ impl DocsRsClient {
         Ok(Some((krate, versions.into_iter().map(|v| v.num).collect())))
     }
 
+    /// The unpacked source of a crate version, from the cache or downloaded from
+    /// static.crates.io
+    ///
+    /// `.crate` files are gzipped tarballs holding a single `{crate}-{version}` directory. A
+    /// download is checked against the SHA-256 checksum crates.io publishes for it, then
+    /// unpacked with the system's `tar` under `{cache_dir}/src`, off the async executor.
+    pub async fn crate_source(
+        &self,
+        crate_name: &str,
+        version: &Version,
+        progress: &dyn Progress,
+    ) -> Result<PathBuf> {
+        let src_root = self.cache_dir.join("src");
+        let package_dir = format!("{crate_name}-{version}");
+        let source_dir = src_root.join(&package_dir);
+        if source_dir.join("Cargo.toml").is_file() {
+            return Ok(source_dir);
+        }
+
+        if self.offline {
+            return Err(anyhow!(
+                "The source of {crate_name}@{version} is not cached, and ferritin is offline. \
+                 Run once without --offline to download it."
+            ));
+        }
+
+        let _task = ProgressTask::start(
+            progress,
+            &format!("Downloading {crate_name} {version} source from crates.io"),
+        );
+
+        let checksum = cancellable(self.crate_checksum(crate_name, version), progress).await?;
+        let url = format!("https://static.crates.io/crates/{crate_name}/{package_dir}.crate");
+        log::debug!("Fetching crate source: {url}");
+        let download = async {
+            let mut conn =
+                self.http_client.get(url).await?.success().map_err(|e| {
+                    anyhow!("Failed to download {crate_name}@{version} source: {e}")
+                })?;
+            read_body(&mut conn, progress)
+                .await
+                .context("Failed to read crate download")
+        };
+        let bytes = cancellable(download, progress).await?;
+        verify_checksum(&bytes, &checksum)
+            .with_context(|| format!("The download of {crate_name}@{version} is corrupt"))?;
+
+        // Unpack next to the cache and move into place, so that an interrupted unpack is never
+        // mistaken for the source
+        let staging_dir = src_root.join(format!(".{package_dir}"));
+        let _ = async_fs::remove_dir_all(&staging_dir).await;
+        async_fs::create_dir_all(&staging_dir)
+            .await
+            .context("Failed to create crate source directory")?;
+        let tarball = staging_dir.join(format!("{package_dir}.crate"));
+        async_fs::write(&tarball, &bytes)
+            .await
+            .context("Failed to write crate download")?;
+
+        let unpack = {
+            let (tarball, staging_dir) = (tarball.clone(), staging_dir.clone());
+            blocking::unblock(move || {
+                Command::new("tar")
+                    .arg("-xzf")
+                    .arg(&tarball)
+                    .arg("-C")
+                    .arg(&staging_dir)
+                    .output()
+            })
+        };
+        let output = unpack.await.context("Failed to run tar")?;
+        if !output.status.success() {
+            let _ = async_fs::remove_dir_all(&staging_dir).await;
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!(
+                "Failed to unpack {crate_name}@{version} source: {stderr}"
+            ));
+        }
+
+        let _ = async_fs::remove_dir_all(&source_dir).await;
+        let renamed = async_fs::rename(staging_dir.join(&package_dir), &source_dir).await;
+        let _ = async_fs::remove_dir_all(&staging_dir).await;
+        renamed.context("Crate download has no source directory")?;
+
+        log::debug!("Unpacked crate source to {}", source_dir.display());
+        Ok(source_dir)
+    }
+
+    /// The SHA-256 checksum of a version's `.crate` file, as hex, from the crates.io API
+    async fn crate_checksum(&self, crate_name: &str, version: &Version) -> Result<String> {
+        let url = format!("https://crates.io/api/v1/crates/{crate_name}/{version}");
+        log::debug!("Fetching crate checksum: {url}");
+        let mut conn =
+            self.http_client.get(url).await?.success().map_err(|e| {
+                anyhow!("Failed to look up {crate_name}@{version} on crates.io: {e}")
+            })?;
+        let bytes = conn
+            .response_body()
+            .read_bytes()
+            .await
+            .context("Failed to read crates.io response")?;
+
+        let CratesIoVersionResponse { version: published } =
+            sonic_rs::serde::from_slice(&bytes).context("Failed to parse crates.io response")?;
+        Ok(published.checksum)
+    }
+
+    /// An image linked from docs, like a diagram or a logo, from the cache or downloaded
+    ///
+    /// Images are cached under `{cache_dir}/images`, named by a hash of their URL.
+    pub async fn image(&self, url: &str) -> Result<Vec<u8>> {
+        let mut hasher = FxHasher::default();
+        url.hash(&mut hasher);
+        let path = self
+            .cache_dir
+            .join("images")
+            .join(format!("{:016x}", hasher.finish()));
+        if let Ok(bytes) = async_fs::read(&path).await {
+            return Ok(bytes);
+        }
+
+        if self.offline {
+            return Err(anyhow!("{url} is not cached, and ferritin is offline"));
+        }
+
+        log::debug!("Fetching image: {url}");
+        let mut conn = self.http_client.get(url).await?;
+        // Image hosts commonly redirect to a CDN
+        if let Some(status) = conn.status()
+            && status.is_redirection()
+            && let Some(location) = conn.response_headers().get("location")
+        {
+            let location = location.to_string();
+            log::debug!("Following redirect to: {location}");
+            conn = self.http_client.get(location).await?;
+        }
+        let mut conn = conn
+            .success()
+            .map_err(|e| anyhow!("Failed to download {url}: {e}"))?;
+        let bytes = read_body_up_to(&mut conn, MAX_IMAGE_SIZE, &NoProgress)
+            .await
+            .with_context(|| format!("Failed to read image download from {url}"))?;
+
+        if let Some(parent) = path.parent() {
+            async_fs::create_dir_all(parent)
+                .await
+                .context("Failed to create image cache directory")?;
+        }
+        async_fs::write(&path, &bytes)
+            .await
+            .context("Failed to write image cache file")?;
+        Ok(bytes)
+    }
+
     /// Construct the cache file path for a crate
     ///
     /// Cache is organized by source format version (from docs.rs), not normalized version.
@@ -226,13 +468,115 @@ impl DocsRsClient {
         crate_name: &str,
         version: &Version,
         source_format_version: u32,
+        registry: Option<&Registry>,
     ) -> PathBuf {
-        self.cache_dir
+        let cache_root = match registry {
+            Some(registry) => self.cache_dir.join("registries").join(registry.name()),
+            None => self.cache_dir.clone(),
+        };
+        cache_root
             .join(source_format_version.to_string())
             .join(crate_name)
             .join(format!("{version}.json"))
     }
 
+    /// Whether a crate version is in the cache in any readable format version
+    pub(super) fn is_cached(&self, crate_name: &str, version: &Version) -> bool {
+        (MIN_FORMAT_VERSION..=self.format_version).any(|source_format| {
+            self.cache_path(crate_name, version, source_format, None)
+                .exists()
+        })
+    }
+
+    /// Versions of a crate in the cache in any readable format version, newest first
+    pub(super) fn cached_versions(&self, crate_name: &str) -> Vec<Version> {
+        let mut versions = (MIN_FORMAT_VERSION..=self.format_version)
+            .filter_map(|source_format| {
+                std::fs::read_dir(
+                    self.cache_dir
+                        .join(source_format.to_string())
+                        .join(crate_name),
+                )
+                .ok()
+            })
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                Version::parse(path.file_stem()?.to_str()?).ok()
+            })
+            .collect::<Vec<_>>();
+        versions.sort_by(|a, b| b.cmp(a));
+        versions.dedup();
+        versions
+    }
+
+    /// Every crate version in the cache, including ones in format versions that are no longer
+    /// readable
+    pub(super) fn cache_entries(&self) -> Vec<CacheEntry> {
+        let mut entries = vec![];
+        let Ok(format_dirs) = std::fs::read_dir(&self.cache_dir) else {
+            return entries;
+        };
+
+        for format_dir in format_dirs.flatten() {
+            let Some(format_version) = format_dir
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<u32>().ok())
+            else {
+                continue;
+            };
+
+            for crate_dir in std::fs::read_dir(format_dir.path())
+                .into_iter()
+                .flatten()
+                .flatten()
+            {
+                let crate_name = crate_dir.file_name().to_string_lossy().into_owned();
+                for file in std::fs::read_dir(crate_dir.path())
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                {
+                    let path = file.path();
+                    if path.extension().is_none_or(|extension| extension != "json") {
+                        continue;
+                    }
+                    let Some(version) = path
+                        .file_stem()
+                        .and_then(|stem| stem.to_str())
+                        .and_then(|stem| Version::parse(stem).ok())
+                    else {
+                        continue;
+                    };
+                    let Ok(metadata) = file.metadata() else {
+                        continue;
+                    };
+
+                    let index_size = ["index", CACHE_EXTENSION]
+                        .into_iter()
+                        .filter_map(|extension| path.with_extension(extension).metadata().ok())
+                        .map(|metadata| metadata.len())
+                        .sum::<u64>();
+
+                    entries.push(CacheEntry {
+                        crate_name: crate_name.clone(),
+                        version,
+                        format_version,
+                        size: metadata.len() + index_size,
+                        last_used: metadata.accessed().or_else(|_| metadata.modified()).ok(),
+                        path,
+                    });
+                }
+            }
+        }
+
+        entries
+    }
+
     /// Load from cache if available and valid
     ///
     /// Tries to find the crate in cache across different format versions.
@@ -241,10 +585,11 @@ impl DocsRsClient {
         &self,
         crate_name: &str,
         version: &Version,
+        registry: Option<&Registry>,
     ) -> Result<Option<RustdocData>> {
         // Try format versions in descending order (prefer newer versions)
         for source_format in (MIN_FORMAT_VERSION..=self.format_version).rev() {
-            let path = self.cache_path(crate_name, version, source_format);
+            let path = self.cache_path(crate_name, version, source_format, registry);
 
             if !path.exists() {
                 continue;
@@ -270,7 +615,11 @@ impl DocsRsClient {
 
             // Normalize to current format version
             let start = std::time::Instant::now();
-            let crate_data = crate::conversions::load_and_normalize(&json, Some(source_format))
+            let ParsedCrate {
+                crate_data,
+                path_to_id,
+                lazy_items,
+            } = parsed_cache::load_or_parse(&path, json, Some(source_format))
                 .context("Failed to normalize cached JSON")?;
             let parse_elapsed = start.elapsed();
             log::debug!("⏱️ Parsed {} in {:?}", crate_name, parse_elapsed);
@@ -286,7 +635,9 @@ impl DocsRsClient {
                 provenance: CrateProvenance::LocalDependency,
                 fs_path: path,
                 version,
-                path_to_id: Default::default(),
+                path_to_id,
+                lazy_items,
+                versioned_paths: false,
             };
 
             return Ok(Some(data));
@@ -295,7 +646,7 @@ impl DocsRsClient {
         Ok(None)
     }
 
-    /// Fetch from docs.rs
+    /// Fetch from docs.rs, or from a private registry's docs server
     /// Returns Ok(None) if the crate/version is not found (404)
     /// Returns Err for other errors
     async fn fetch_from_docsrs(
@@ -303,15 +654,55 @@ impl DocsRsClient {
         crate_name: &str,
         version: &Version,
         format_version: u32,
+        registry: Option<&Registry>,
+        progress: &dyn Progress,
     ) -> Result<Option<Vec<u8>>> {
+        let base_url = match registry {
+            Some(registry) => registry.docs_url(),
+            None => self.docs_url.as_deref(),
+        }
+        .unwrap_or("https://docs.rs")
+        .trim_end_matches('/');
+        let token = registry.and_then(Registry::token);
+        let Some(mut conn) = self
+            .request_json(base_url, token, crate_name, version, format_version)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        read_body(&mut conn, progress)
+            .await
+            .context("Failed to read response body")
+            .map(Some)
+    }
+
+    /// Request rustdoc JSON from the docs server at `base_url`, sending `token` to it, and
+    /// return the successful response to read it from
+    ///
+    /// The token is only sent along a redirect that stays on the same server, so that it
+    /// isn't handed to a CDN or anywhere else the server points to.
+    async fn request_json(
+        &self,
+        base_url: &str,
+        token: Option<String>,
+        crate_name: &str,
+        version: &Version,
+        format_version: u32,
+    ) -> Result<Option<trillium_client::Conn>> {
         // Construct URL with format version to ensure compatibility
         // https://docs.rs/crate/{crate_name}/{version}/json/{format_version}
         // (zstd compression is default)
-        let url = format!("https://docs.rs/crate/{crate_name}/{version}/json/{format_version}");
+        let url = format!("{base_url}/crate/{crate_name}/{version}/json/{format_version}");
 
         log::debug!("Fetching from docs.rs: {}", url);
 
-        let mut conn = self.http_client.get(url).await?;
+        let authorized = |conn: trillium_client::Conn| match &token {
+            Some(token) => conn.with_request_header(KnownHeaderName::Authorization, token.clone()),
+            None => conn,
+        };
+
+        let mut conn = authorized(self.http_client.get(url)).await?;
 
         // Check if we got a 404 (crate/version not found)
         if let Some(Status::NotFound) = conn.status() {
@@ -328,25 +719,22 @@ impl DocsRsClient {
             let redirect_url = if location_str.starts_with("http") {
                 location_str
             } else {
-                format!("https://docs.rs{}", location_str)
+                format!("{base_url}{location_str}")
             };
             log::debug!("Following redirect to: {}", redirect_url);
-            conn = self.http_client.get(redirect_url).await?;
+            conn = if same_origin(&redirect_url, base_url) {
+                authorized(self.http_client.get(redirect_url)).await?
+            } else {
+                self.http_client.get(redirect_url).await?
+            };
         }
 
         // Check for success after following redirects
-        let mut conn = conn
+        let conn = conn
             .success()
             .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
 
-        // Read response body
-        let bytes = conn
-            .response_body()
-            .read_bytes()
-            .await
-            .context("Failed to read response body")?;
-
-        Ok(Some(bytes))
+        Ok(Some(conn))
     }
 
     /// Decompress zstd-compressed data
@@ -363,8 +751,9 @@ impl DocsRsClient {
         version: &Version,
         format_version: u32,
         json: &[u8],
+        registry: Option<&Registry>,
     ) -> Result<PathBuf> {
-        let path = self.cache_path(crate_name, version, format_version);
+        let path = self.cache_path(crate_name, version, format_version, registry);
 
         // Create parent directories
         if let Some(parent) = path.parent() {
@@ -385,3 +774,304 @@ impl DocsRsClient {
         Ok(path)
     }
 }
+
+/// Whether two URLs have the same scheme, host and port
+fn same_origin(a: &str, b: &str) -> bool {
+    fn origin(url: &str) -> Option<(String, String, u16)> {
+        let (scheme, rest) = url.split_once("://")?;
+        let scheme = scheme.to_ascii_lowercase();
+        let authority = rest.split(['/', '?', '#']).next()?;
+        let authority = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !port.contains(']') => (host, Some(port.parse().ok()?)),
+            _ => (authority, None),
+        };
+        let port = match (port, scheme.as_str()) {
+            (Some(port), _) => port,
+            (None, "http") => 80,
+            (None, "https") => 443,
+            (None, _) => return None,
+        };
+        Some((scheme, host.to_ascii_lowercase(), port))
+    }
+
+    origin(a).is_some_and(|origin_a| origin(b) == Some(origin_a))
+}
+
+/// Check that `bytes` have the SHA-256 checksum `expected`, given in hex
+fn verify_checksum(bytes: &[u8], expected: &str) -> Result<()> {
+    let actual = Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(anyhow!("its checksum is {actual}, not {expected}"))
+    }
+}
+
+/// How much of a response body is read at a time
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The most room set aside for a response body up front, whatever length the server claims
+const MAX_PREALLOCATION: u64 = 64 * 1024 * 1024;
+
+/// The largest image downloaded for docs, which keeps a huge or endless response from filling
+/// memory and the cache
+const MAX_IMAGE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Read the body of a response, reporting how much of it has arrived when the server says how
+/// long it is
+async fn read_body(conn: &mut trillium_client::Conn, progress: &dyn Progress) -> Result<Vec<u8>> {
+    read_body_up_to(conn, u64::MAX, progress).await
+}
+
+/// Read the body of a response like [`read_body`], failing once it's longer than `limit` bytes
+async fn read_body_up_to(
+    conn: &mut trillium_client::Conn,
+    limit: u64,
+    progress: &dyn Progress,
+) -> Result<Vec<u8>> {
+    let mut body = conn.response_body();
+    let total = body.content_length();
+    if let Some(total) = total
+        && total > limit
+    {
+        return Err(anyhow!(
+            "The response is {total} bytes, more than the {limit} allowed"
+        ));
+    }
+    let mut bytes =
+        Vec::with_capacity(total.map_or(0, |total| total.min(MAX_PREALLOCATION) as usize));
+    let mut chunk = vec![0; READ_CHUNK_SIZE];
+    loop {
+        let read = body.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(bytes);
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+        if bytes.len() as u64 > limit {
+            return Err(anyhow!(
+                "The response is more than the {limit} bytes allowed"
+            ));
+        }
+        if let Some(total) = total {
+            progress.advance(bytes.len() as u64, total);
+        }
+    }
+}
+
+/// How often a download checks whether it was cancelled
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Run `download` unless `progress` is cancelled first
+///
+/// Only network requests are cut short, never writes to the cache, so that a cancelled
+/// download doesn't leave a partly written file behind.
+async fn cancellable<T>(
+    download: impl Future<Output = Result<T>>,
+    progress: &dyn Progress,
+) -> Result<T> {
+    let mut download = pin!(download);
+    let mut check = Timer::interval(CANCEL_CHECK_INTERVAL);
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = download.as_mut().poll(cx) {
+            return Poll::Ready(output);
+        }
+        if progress.is_cancelled() {
+            return Poll::Ready(Err(anyhow!("Cancelled")));
+        }
+        // Wake up again at the next check
+        while Pin::new(&mut check).poll(cx).is_ready() {}
+        Poll::Pending
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use trillium_smol::async_io::block_on;
+
+    /// Answer one request on `listener` with `response`, returning the request's head
+    fn serve_once(listener: TcpListener, response: String) -> std::thread::JoinHandle<String> {
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut head = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            // The head ends at the first blank line
+            while reader.read_line(&mut head).unwrap() > 2 {}
+            stream.write_all(response.as_bytes()).unwrap();
+            head
+        })
+    }
+
+    #[test]
+    fn offline_client_only_uses_the_cache() {
+        let cache_dir = std::env::temp_dir().join("ferritin-offline-test-empty-cache");
+        let client = DocsRsClient::new(cache_dir).unwrap().with_offline(true);
+        let version = Version::new(1, 0, 0);
+
+        let error = block_on(client.get_crate("serde", &version, None, &NoProgress)).unwrap_err();
+        assert!(error.to_string().contains("not cached"));
+        assert!(
+            block_on(client.resolve("serde", &VersionReq::STAR))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    /// Progress of work that whoever was waiting for has given up on
+    struct Cancelled;
+
+    impl Progress for Cancelled {
+        fn start(&self, _task: &str) {}
+
+        fn finish(&self) {}
+
+        fn is_cancelled(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn cancelled_downloads_stop_and_are_tried_again() {
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let docs_url = format!("http://{}", server.local_addr().unwrap());
+        // The first request never gets an answer, and later ones are told there are no docs
+        std::thread::spawn(move || {
+            let mut unanswered = vec![];
+            for stream in server.incoming() {
+                let mut stream = stream.unwrap();
+                if unanswered.is_empty() {
+                    unanswered.push(stream);
+                    continue;
+                }
+                let mut head = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                while reader.read_line(&mut head).unwrap() > 2 {}
+                let _ = stream.write_all(
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                );
+            }
+        });
+
+        let cache_dir = std::env::temp_dir().join("ferritin-cancelled-download-test-cache");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let client = DocsRsClient::new(cache_dir)
+            .unwrap()
+            .with_docs_url(Some(docs_url));
+        let version = Version::new(1, 0, 0);
+
+        let error = block_on(client.get_crate("slow", &version, None, &Cancelled)).unwrap_err();
+        assert_eq!(error.to_string(), "Cancelled");
+        assert!(!client.is_cached("slow", &version));
+
+        // Nothing was remembered about the cancelled download, so the server is asked again
+        let fetched = block_on(client.get_crate("slow", &version, None, &NoProgress)).unwrap();
+        assert!(fetched.is_none());
+    }
+
+    #[test]
+    fn oversized_images_are_not_downloaded() {
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/huge.png", server.local_addr().unwrap());
+        let _ = serve_once(
+            server,
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                MAX_IMAGE_SIZE + 1
+            ),
+        );
+
+        let cache_dir = std::env::temp_dir().join("ferritin-oversized-image-test-cache");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let client = DocsRsClient::new(cache_dir.clone()).unwrap();
+        let error = block_on(client.image(&url)).unwrap_err();
+        assert!(format!("{error:#}").contains("allowed"));
+        assert!(!cache_dir.join("images").exists());
+    }
+
+    #[test]
+    fn registry_tokens_are_not_sent_to_other_servers() {
+        let docs_server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let cdn = TcpListener::bind("127.0.0.1:0").unwrap();
+        let docs_url = format!("http://{}", docs_server.local_addr().unwrap());
+        let cdn_url = format!("http://{}", cdn.local_addr().unwrap());
+
+        let redirected = serve_once(
+            docs_server,
+            format!(
+                "HTTP/1.1 302 Found\r\nLocation: {cdn_url}/tiny.json.zst\r\n\
+                 Content-Length: 0\r\nConnection: close\r\n\r\n"
+            ),
+        );
+        let fetched = serve_once(
+            cdn,
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}".into(),
+        );
+
+        let cache_dir = std::env::temp_dir().join("ferritin-redirect-test-cache");
+        let client = DocsRsClient::new(cache_dir).unwrap();
+        let body = block_on(async {
+            let mut conn = client
+                .request_json(
+                    &docs_url,
+                    Some("Bearer secret".into()),
+                    "tiny",
+                    &Version::new(0, 1, 0),
+                    FORMAT_VERSION,
+                )
+                .await?
+                .unwrap();
+            read_body(&mut conn, &NoProgress).await
+        })
+        .unwrap();
+
+        assert_eq!(body, b"{}");
+        let docs_request = redirected.join().unwrap().to_ascii_lowercase();
+        assert!(
+            docs_request.contains("authorization: bearer secret"),
+            "{docs_request}"
+        );
+        let cdn_request = fetched.join().unwrap().to_ascii_lowercase();
+        assert!(!cdn_request.contains("authorization"), "{cdn_request}");
+    }
+
+    #[test]
+    fn downloads_are_checked_against_their_checksum() {
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert!(verify_checksum(b"abc", abc).is_ok());
+        assert!(verify_checksum(b"abc", &abc.to_uppercase()).is_ok());
+        assert!(verify_checksum(b"abd", abc).is_err());
+    }
+
+    #[test]
+    fn origins_compare_scheme_host_and_port() {
+        assert!(same_origin(
+            "https://docs.example.com/a",
+            "https://DOCS.example.com:443"
+        ));
+        assert!(same_origin(
+            "http://127.0.0.1:8080/x?y",
+            "http://127.0.0.1:8080"
+        ));
+        assert!(!same_origin(
+            "http://docs.example.com/a",
+            "https://docs.example.com"
+        ));
+        assert!(!same_origin(
+            "https://cdn.example.com/a",
+            "https://docs.example.com"
+        ));
+        assert!(!same_origin(
+            "https://docs.example.com:8443/a",
+            "https://docs.example.com"
+        ));
+    }
+}
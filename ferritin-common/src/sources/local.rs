@@ -1,7 +1,10 @@
-use super::CrateProvenance;
+use super::{CrateProvenance, DependencyKind, DependencyScope, run_cargo};
 use crate::RustdocData;
 use crate::crate_name::CrateName;
 use crate::navigator::CrateInfo;
+use crate::parsed_cache::{self, ParsedCrate};
+use crate::prelude::Edition;
+use crate::progress::{Progress, ProgressTask};
 use crate::sources::RustdocVersion;
 use crate::sources::Source;
 use anyhow::{Result, anyhow};
@@ -19,15 +22,67 @@ use std::process::Command;
 use std::time::SystemTime;
 use walkdir::WalkDir;
 
+mod config;
+mod guides;
+use config::FerritinMetadata;
+pub use config::Registry;
+use guides::{GuidePage, inject_guides};
+
+/// What a locally built rustdoc JSON file was generated from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltDocs {
+    pub format_version: u32,
+    pub crate_version: Option<Version>,
+}
+
 #[derive(Debug, Fieldwork)]
 #[field(get)]
 pub struct LocalSource {
     manifest_path: PathBuf,
     target_dir: PathBuf,
+    /// Each package in the dependency graph, at the version the workspace uses
     #[field = false]
     crates: FxHashMap<CrateName<'static>, CrateInfo>,
+    /// Further versions of packages that are in the dependency graph more than once, like
+    /// `syn` 1 and 2
+    #[field = false]
+    other_versions: FxHashMap<CrateName<'static>, Vec<CrateInfo>>,
     root_crate: Option<CrateName<'static>>,
+    /// Edition of the root package, or of the first workspace package in a virtual workspace
+    edition: Edition,
     can_rebuild: bool,
+    /// Markdown pages from `[workspace.metadata.ferritin] guides`
+    #[field = false]
+    guides: Vec<GuidePage>,
+    /// Crates from `[workspace.metadata.ferritin] ignore`, left out of listings
+    #[field = false]
+    ignored: FxHashSet<CrateName<'static>>,
+    /// Dependency versions from `[workspace.metadata.ferritin.pin]`
+    #[field = false]
+    pins: FxHashMap<CrateName<'static>, Version>,
+    /// Resolved dependency graph from `Cargo.lock`: package name to the names of its dependencies
+    #[field = false]
+    dependencies: FxHashMap<CrateName<'static>, Vec<String>>,
+    /// Names that workspace packages use for renamed dependencies, mapped to the package names
+    #[field = false]
+    renames: FxHashMap<CrateName<'static>, CrateName<'static>>,
+    /// Directories of `path = ...` dependencies outside the workspace, whose docs are rebuilt
+    /// when their sources change
+    #[field = false]
+    path_dependencies: FxHashMap<CrateName<'static>, PathBuf>,
+    /// The private registry each crate from one is published in
+    #[field = false]
+    registries: FxHashMap<CrateName<'static>, Registry>,
+    /// Binary targets of workspace packages, by target name
+    #[field = false]
+    binaries: FxHashMap<CrateName<'static>, BinaryTarget>,
+}
+
+/// A `[[bin]]` target of a workspace package
+#[derive(Debug)]
+struct BinaryTarget {
+    package: String,
+    package_dir: PathBuf,
 }
 
 impl LocalSource {
@@ -47,17 +102,84 @@ impl LocalSource {
         };
 
         let manifest_path: PathBuf = metadata.workspace_root.join("Cargo.toml").into();
+        let config = FerritinMetadata::from_workspace_metadata(&metadata.workspace_metadata);
+        let guides = GuidePage::from_paths(&config.guides, metadata.workspace_root.as_std_path());
         let mut reverse_deps: FxHashMap<&str, FxHashSet<&str>> = FxHashMap::default();
+        let mut dependency_scopes: FxHashMap<&str, FxHashSet<DependencyScope>> =
+            FxHashMap::default();
 
         let mut workspace_packages: FxHashSet<&str> = FxHashSet::default();
+        let mut renames = FxHashMap::default();
+        let mut binaries = FxHashMap::default();
 
         for package in metadata.workspace_packages() {
             workspace_packages.insert(&package.name);
+            for target in package.targets.iter().filter(|target| target.is_bin()) {
+                binaries.insert(
+                    CrateName::from(target.name.clone()),
+                    BinaryTarget {
+                        package: package.name.to_string(),
+                        package_dir: package
+                            .manifest_path
+                            .parent()
+                            .map(|dir| dir.as_std_path().to_path_buf())
+                            .unwrap_or_default(),
+                    },
+                );
+            }
             for dep in &package.dependencies {
+                if let Some(rename) = &dep.rename {
+                    renames.insert(
+                        CrateName::from(rename.clone()),
+                        CrateName::from(dep.name.clone()),
+                    );
+                }
+
                 reverse_deps
                     .entry(&dep.name)
                     .or_default()
                     .insert(&package.name);
+
+                let kind = match dep.kind {
+                    cargo_metadata::DependencyKind::Build => DependencyKind::Build,
+                    cargo_metadata::DependencyKind::Development => DependencyKind::Dev,
+                    _ => DependencyKind::Normal,
+                };
+
+                dependency_scopes
+                    .entry(&dep.name)
+                    .or_default()
+                    .insert(DependencyScope {
+                        kind,
+                        target: dep.target.as_ref().map(|platform| platform.to_string()),
+                    });
+            }
+        }
+
+        let package_names: FxHashMap<_, &str> = metadata
+            .packages
+            .iter()
+            .map(|package| (&package.id, &**package.name))
+            .collect();
+
+        // Packages that are in the graph at several versions are told apart by which workspace
+        // packages use each version directly, according to the resolved graph
+        let mut version_counts: FxHashMap<&str, usize> = FxHashMap::default();
+        for package in &metadata.packages {
+            *version_counts.entry(&**package.name).or_default() += 1;
+        }
+        let mut direct_users: FxHashMap<_, Vec<String>> = FxHashMap::default();
+        for node in metadata.resolve.iter().flat_map(|resolve| &resolve.nodes) {
+            let Some(&name) = package_names.get(&node.id) else {
+                continue;
+            };
+            if workspace_packages.contains(name) {
+                for dep in &node.deps {
+                    direct_users
+                        .entry(&dep.pkg)
+                        .or_default()
+                        .push(name.to_string());
+                }
             }
         }
 
@@ -65,8 +187,23 @@ impl LocalSource {
         let root_crate = metadata
             .root_package()
             .map(|p| CrateName::from(p.name.to_string()));
-
-        let mut crates = FxHashMap::default();
+        let edition = metadata
+            .root_package()
+            .or_else(|| metadata.workspace_packages().into_iter().next())
+            .map(|package| Edition::from(package.edition))
+            .unwrap_or_default();
+
+        // Docs for git dependencies are cached by commit, since the target directory only
+        // holds one build of each package
+        let git_cache_dir = home::cargo_home()
+            .ok()
+            .map(|cargo_home| cargo_home.join("rustdoc-json").join("git"));
+
+        let mut crates: FxHashMap<CrateName<'static>, CrateInfo> = FxHashMap::default();
+        let mut other_versions: FxHashMap<CrateName<'static>, Vec<CrateInfo>> =
+            FxHashMap::default();
+        let mut path_dependencies = FxHashMap::default();
+        let mut registries = FxHashMap::default();
         for package in &metadata.packages {
             // let is_crates_io = package
             //     .source
@@ -80,31 +217,135 @@ impl LocalSource {
                 CrateProvenance::LocalDependency
             };
 
-            let used_by = reverse_deps
+            let has_other_versions = version_counts
+                .get(&**package.name)
+                .is_some_and(|&count| count > 1);
+            let used_by = if has_other_versions {
+                direct_users.get(&package.id).cloned().unwrap_or_default()
+            } else {
+                reverse_deps
+                    .get(&**package.name)
+                    .into_iter()
+                    .flatten()
+                    .map(|name| name.to_string())
+                    .collect()
+            };
+
+            let mut scopes: Vec<_> = dependency_scopes
                 .get(&**package.name)
                 .into_iter()
                 .flatten()
-                .map(|name| name.to_string())
+                .cloned()
                 .collect();
+            scopes.sort();
 
             let doc_dir = target_dir.join("doc");
             let underscored = package.name.replace('-', "_");
-            let json_path = doc_dir.join(format!("{underscored}.json"));
-
-            crates.insert(
-                package.name.to_string().into(),
-                CrateInfo {
-                    provenance,
-                    version: Some(package.version.clone()),
-                    description: package.description.clone(),
-                    name: package.name.to_string(),
-                    default_crate: root_crate
-                        .as_ref()
-                        .is_some_and(|dc| &CrateName::from(&**package.name) == dc),
-                    used_by,
-                    json_path: Some(json_path),
-                },
+            // The target directory holds one build of each package name, so each version of a
+            // package that is in the graph more than once gets its own copy
+            let mut json_path = if has_other_versions {
+                doc_dir.join(format!("{underscored}-{}.json", package.version))
+            } else {
+                doc_dir.join(format!("{underscored}.json"))
+            };
+
+            match package.source.as_ref() {
+                Some(source) => {
+                    if let Some(registry) = config
+                        .registries
+                        .values()
+                        .find(|registry| registry.is_source(&source.repr))
+                    {
+                        registries
+                            .insert(CrateName::from(package.name.to_string()), registry.clone());
+                    }
+
+                    if let Some(commit) = git_commit(&source.repr)
+                        && let Some(git_cache_dir) = &git_cache_dir
+                    {
+                        json_path = git_cache_dir
+                            .join(&*package.name)
+                            .join(format!("{commit}.json"));
+                    }
+                }
+                None if provenance.is_local_dependency() => {
+                    if let Some(package_dir) = package.manifest_path.parent() {
+                        path_dependencies.insert(
+                            CrateName::from(package.name.to_string()),
+                            package_dir.to_path_buf().into_std_path_buf(),
+                        );
+                    }
+                }
+                None => {}
+            }
+
+            let name = CrateName::from(package.name.to_string());
+            let info = CrateInfo {
+                provenance,
+                version: Some(package.version.clone()),
+                description: package.description.clone(),
+                name: package.name.to_string(),
+                default_crate: root_crate.as_ref().is_some_and(|dc| &name == dc),
+                used_by,
+                dependency_scopes: scopes,
+                json_path: Some(json_path),
+                manifest_path: Some(package.manifest_path.clone().into_std_path_buf()),
+                features: package.features.clone(),
+            };
+
+            match crates.remove(&name) {
+                Some(existing) => {
+                    let (preferred, other) =
+                        if is_preferred_version(&info, &existing, root_crate.as_ref()) {
+                            (info, existing)
+                        } else {
+                            (existing, info)
+                        };
+                    crates.insert(name.clone(), preferred);
+                    other_versions.entry(name).or_default().push(other);
+                }
+                None => {
+                    crates.insert(name, info);
+                }
+            }
+        }
+
+        let mut pins = FxHashMap::default();
+        for (name, version) in config.pin {
+            let name = CrateName::from(name);
+            match crates.get_mut(&name) {
+                Some(info) if info.provenance.is_workspace() => {
+                    log::warn!("Ignoring pin for workspace crate {name}");
+                }
+                Some(info) => {
+                    // Pinned docs come from docs.rs, since the local build is of the locked version
+                    if info.version.as_ref() != Some(&version) {
+                        info.provenance = CrateProvenance::DocsRs;
+                        info.version = Some(version.clone());
+                        info.json_path = None;
+                    }
+                    pins.insert(name, version);
+                }
+                None => log::warn!("Ignoring pin for {name}, which is not a dependency"),
+            }
+        }
+
+        let ignored = config.ignore.into_iter().map(CrateName::from).collect();
+
+        let mut dependencies: FxHashMap<CrateName<'static>, Vec<String>> = FxHashMap::default();
+        for node in metadata.resolve.iter().flat_map(|resolve| &resolve.nodes) {
+            let Some(name) = package_names.get(&node.id) else {
+                continue;
+            };
+            let deps = dependencies.entry(name.to_string().into()).or_default();
+            deps.extend(
+                node.deps
+                    .iter()
+                    .filter_map(|dep| package_names.get(&dep.pkg))
+                    .map(|dep_name| dep_name.to_string()),
             );
+            deps.sort();
+            deps.dedup();
         }
 
         Ok(Self {
@@ -112,10 +353,139 @@ impl LocalSource {
             target_dir,
             can_rebuild: true,
             crates,
+            other_versions,
             root_crate,
+            edition,
+            guides,
+            ignored,
+            pins,
+            dependencies,
+            renames,
+            path_dependencies,
+            registries,
+            binaries,
+        })
+    }
+
+    /// The version a dependency is pinned to in `[workspace.metadata.ferritin.pin]`, if any
+    pub fn pinned_version(&self, crate_name: &str) -> Option<&Version> {
+        self.pins.get(&CrateName::from(crate_name))
+    }
+
+    /// The private registry a dependency is published in, if it is configured in
+    /// `[workspace.metadata.ferritin.registries]`
+    pub fn registry(&self, crate_name: &str) -> Option<&Registry> {
+        self.registries.get(&CrateName::from(crate_name))
+    }
+
+    /// Whether a crate is listed in `[workspace.metadata.ferritin] ignore`
+    pub fn is_ignored(&self, crate_name: &str) -> bool {
+        self.ignored.contains(&CrateName::from(crate_name))
+    }
+
+    /// Names of the packages a crate depends on directly, across all dependency kinds
+    pub fn dependencies(&self, crate_name: &str) -> &[String] {
+        self.dependencies
+            .get(&CrateName::from(crate_name))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Every package in the resolved dependency graph, including ignored ones, at the version
+    /// the workspace uses
+    pub fn packages(&self) -> impl Iterator<Item = &CrateInfo> {
+        self.crates.values()
+    }
+
+    /// Versions of a package other than the one the workspace uses, for packages that are in
+    /// the dependency graph more than once
+    pub fn other_versions(&self, crate_name: &str) -> &[CrateInfo] {
+        let crate_name = CrateName::from(crate_name);
+        self.other_versions
+            .get(self.package_name(&crate_name))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The package with this name at `version`, or at the version the workspace uses
+    fn package_version(&self, crate_name: &str, version: Option<&Version>) -> Option<&CrateInfo> {
+        let crate_name = CrateName::from(crate_name);
+        let crate_name = self.package_name(&crate_name);
+        self.crates
+            .get(crate_name)
+            .into_iter()
+            .chain(self.other_versions.get(crate_name).into_iter().flatten())
+            .find(|info| version.is_none_or(|version| info.version.as_ref() == Some(version)))
+    }
+
+    /// The format and crate version of a package's locally built rustdoc JSON, if it has been
+    /// built
+    pub fn built_docs(&self, crate_name: &str) -> Option<BuiltDocs> {
+        let json_path = self
+            .lookup(crate_name, &VersionReq::STAR)
+            .and_then(|info| info.json_path.clone())
+            .unwrap_or_else(|| self.json_path(crate_name));
+        let content = std::fs::read(json_path).ok()?;
+        let RustdocVersion {
+            format_version,
+            crate_version,
+        } = sonic_rs::serde::from_slice(&content).ok()?;
+        Some(BuiltDocs {
+            format_version,
+            crate_version,
         })
     }
 
+    /// Rebuild a package's rustdoc JSON, for docs that are out of date
+    pub fn rebuild(
+        &self,
+        crate_name: &str,
+        version: Option<&Version>,
+        progress: &dyn Progress,
+    ) -> Result<()> {
+        self.rebuild_docs(&CrateName::from(crate_name), version, progress)
+    }
+
+    /// Whether workspace guides are attached to this crate
+    ///
+    /// Guides go on the root package, or on every member of a virtual workspace.
+    fn hosts_guides(&self, crate_name: &CrateName<'_>) -> bool {
+        !self.guides.is_empty()
+            && self
+                .root_crate
+                .as_ref()
+                .is_none_or(|root_crate| root_crate == crate_name)
+    }
+
+    /// Drop a cached search index that was built with other guides than `guides`, or that
+    /// predates an edit to one of them
+    fn invalidate_stale_index(json_path: &Path, guides: &[GuidePage]) {
+        // Mirrors the index location used by `SearchIndex::load_or_build`
+        let index_path = json_path.with_extension("index");
+
+        // The guides the index was built with are noted next to it
+        let guides_path = json_path.with_extension("guides");
+        let configured = guides
+            .iter()
+            .map(|guide| guide.path.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if std::fs::read_to_string(&guides_path).unwrap_or_default() != configured {
+            let _ = std::fs::remove_file(&index_path);
+            let _ = std::fs::write(&guides_path, configured);
+            return;
+        }
+
+        let Some(index_updated) = index_path.metadata().ok().and_then(|m| m.modified().ok()) else {
+            return;
+        };
+        if guides
+            .iter()
+            .filter_map(GuidePage::modified)
+            .any(|guide_updated| guide_updated > index_updated)
+        {
+            let _ = std::fs::remove_file(index_path);
+        }
+    }
+
     /// Check if a crate name is a workspace package
     pub fn is_workspace_package(&self, crate_name: &str) -> bool {
         let crate_name = CrateName::from(crate_name);
@@ -146,6 +516,12 @@ impl LocalSource {
         self.crates.contains_key(crate_name)
     }
 
+    /// The package name behind a dependency name, which differs for renamed dependencies
+    /// like `json = { package = "serde_json" }`
+    fn package_name<'a>(&'a self, name: &'a CrateName<'a>) -> &'a CrateName<'a> {
+        self.renames.get(name).unwrap_or(name)
+    }
+
     /// Get the JSON path for a crate
     fn json_path(&self, crate_name: &str) -> PathBuf {
         let doc_dir = self.target_dir.join("doc");
@@ -154,9 +530,92 @@ impl LocalSource {
     }
 
     /// Load a workspace crate (may rebuild if needed)
-    pub fn load_workspace_crate(&self, crate_name: CrateName<'_>) -> Option<RustdocData> {
+    ///
+    /// Docs that are older than the sources are still shown, with a warning, when they can't
+    /// be rebuilt.
+    pub fn load_workspace_crate(
+        &self,
+        crate_name: CrateName<'_>,
+        progress: &dyn Progress,
+    ) -> Option<RustdocData> {
         let json_path = self.json_path(crate_name.as_ref());
         let mut tried_rebuilding = false;
+        let mut accept_stale = false;
+
+        loop {
+            let needs_rebuild = !accept_stale
+                && json_path
+                    .metadata()
+                    .ok()
+                    .and_then(|m| m.modified().ok())
+                    .is_none_or(|docs_updated| {
+                        modified_after(&self.project_root().join("src"), docs_updated)
+                    });
+
+            if !needs_rebuild
+                && let Ok(content) = std::fs::read(&json_path)
+                && let Ok(format_version) = sonic_rs::get_from_slice(&content, &["format_version"])
+                && let Ok(FORMAT_VERSION) = format_version.as_raw_str().parse()
+            {
+                let mut crate_data: Crate = sonic_rs::serde::from_slice(&content).ok()?;
+                let version = crate_data
+                    .crate_version
+                    .as_ref()
+                    .and_then(|v| Version::parse(v).ok());
+
+                let guides = if self.hosts_guides(&crate_name) {
+                    inject_guides(&mut crate_data, &self.guides);
+                    &self.guides[..]
+                } else {
+                    &[]
+                };
+                Self::invalidate_stale_index(&json_path, guides);
+
+                break Some(RustdocData {
+                    crate_data,
+                    name: crate_name.to_string(),
+                    provenance: CrateProvenance::Workspace,
+                    fs_path: json_path,
+                    version,
+                    path_to_id: Default::default(),
+                    lazy_items: None,
+                    versioned_paths: false,
+                });
+            } else if !tried_rebuilding && self.can_rebuild {
+                tried_rebuilding = true;
+                match self.rebuild_docs(&crate_name, None, progress) {
+                    Ok(()) => continue,
+                    Err(error) => log::warn!("Could not rebuild docs for {crate_name}: {error}"),
+                }
+            }
+
+            if needs_rebuild && json_path.exists() {
+                log::warn!("The docs for {crate_name} are older than its sources");
+                accept_stale = true;
+                continue;
+            }
+            break None;
+        }
+    }
+
+    /// Names of the binary targets in workspace packages
+    pub fn binaries(&self) -> impl Iterator<Item = &str> {
+        self.binaries.keys().map(|name| &**name)
+    }
+
+    /// Load a workspace binary target, including its private items, since that is where a
+    /// binary's command line is usually defined (may rebuild if needed)
+    ///
+    /// A binary's docs are kept as `{name}.bin.json`, since a binary and library of the same
+    /// name would otherwise share a JSON file.
+    pub fn load_binary(&self, bin_name: &str, progress: &dyn Progress) -> Option<RustdocData> {
+        let (bin_name, target) = self.binaries.get_key_value(&CrateName::from(bin_name))?;
+        let underscored = bin_name.replace('-', "_");
+        let json_path = self
+            .target_dir
+            .join("doc")
+            .join(format!("{underscored}.bin.json"));
+        let mut tried_rebuilding = false;
 
         loop {
             let needs_rebuild = json_path
@@ -164,12 +623,7 @@ impl LocalSource {
                 .ok()
                 .and_then(|m| m.modified().ok())
                 .is_none_or(|docs_updated| {
-                    WalkDir::new(self.project_root().join("src"))
-                        .into_iter()
-                        .filter_map(|entry| -> Option<SystemTime> {
-                            entry.ok()?.metadata().ok()?.modified().ok()
-                        })
-                        .any(|file_updated| file_updated > docs_updated)
+                    modified_after(&target.package_dir.join("src"), docs_updated)
                 });
 
             if !needs_rebuild
@@ -185,43 +639,108 @@ impl LocalSource {
 
                 break Some(RustdocData {
                     crate_data,
-                    name: crate_name.to_string(),
+                    name: bin_name.to_string(),
                     provenance: CrateProvenance::Workspace,
                     fs_path: json_path,
                     version,
                     path_to_id: Default::default(),
+                    lazy_items: None,
+                    versioned_paths: false,
                 });
             } else if !tried_rebuilding && self.can_rebuild {
                 tried_rebuilding = true;
-                if self.rebuild_docs(&crate_name, None).is_ok() {
-                    continue;
+                match self.rebuild_binary_docs(bin_name, target, &json_path, progress) {
+                    Ok(()) => continue,
+                    Err(error) => log::warn!("Could not build docs for {bin_name}: {error}"),
                 }
             }
             break None;
         }
     }
 
+    /// Document a binary target with nightly rustdoc, moving the JSON to `json_path`
+    ///
+    /// Rustdoc writes the JSON where a library of the same name has its docs, so those are
+    /// set aside during the build and put back after it.
+    fn rebuild_binary_docs(
+        &self,
+        bin_name: &str,
+        target: &BinaryTarget,
+        json_path: &Path,
+        progress: &dyn Progress,
+    ) -> Result<()> {
+        let _task = ProgressTask::start(progress, &format!("Building docs for {bin_name}"));
+        let built = self.json_path(bin_name);
+        let library_json = built.with_extension("json.lib");
+        let set_aside = std::fs::rename(&built, &library_json).is_ok();
+
+        let result = self.document_binary(bin_name, target, &built, json_path, progress);
+        if set_aside {
+            std::fs::rename(&library_json, &built)?;
+        }
+        result
+    }
+
+    /// Run rustdoc on a binary target and move the JSON it writes to `built` to `json_path`
+    fn document_binary(
+        &self,
+        bin_name: &str,
+        target: &BinaryTarget,
+        built: &Path,
+        json_path: &Path,
+        progress: &dyn Progress,
+    ) -> Result<()> {
+        let output = run_cargo(
+            Command::new("rustup")
+                .args([
+                    "run",
+                    "nightly",
+                    "cargo",
+                    "rustdoc",
+                    "--package",
+                    &target.package,
+                    "--bin",
+                    bin_name,
+                    "--",
+                    "-Z",
+                    "unstable-options",
+                    "--output-format=json",
+                    "--document-private-items",
+                ])
+                .current_dir(self.project_root()),
+            progress,
+        )?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("cargo rustdoc failed: {}", stderr));
+        }
+
+        std::fs::rename(built, json_path)
+            .or_else(|_| std::fs::copy(built, json_path).map(|_| ()))?;
+        Ok(())
+    }
+
     /// Load a dependency crate (may rebuild if needed)
+    ///
+    /// A path dependency's docs that are older than its sources are still shown, with a
+    /// warning, when they can't be rebuilt.
     pub fn load_dep(
         &self,
         crate_name: CrateName<'_>,
         version: Option<&Version>,
+        progress: &dyn Progress,
     ) -> Option<RustdocData> {
-        let info = self.lookup(&crate_name, &VersionReq::STAR)?;
+        let info = self.package_version(&crate_name, version)?;
         let json_path = info.json_path.as_deref()?;
-        let info_version = info.version.as_ref();
-
-        if let Some(version) = version
-            && let Some(info_version) = info_version
-            && version != info_version
-        {
-            return None;
-        }
 
         let mut tried_rebuilding = false;
+        let mut accept_stale = false;
 
         loop {
-            if let Ok(content) = std::fs::read(json_path)
+            let changed = !accept_stale && self.path_dependency_changed(&crate_name, json_path);
+            if !changed
+                && let Ok(content) = std::fs::read(json_path)
                 && let Ok(RustdocVersion {
                     format_version,
                     crate_version,
@@ -229,7 +748,11 @@ impl LocalSource {
                 && format_version == FORMAT_VERSION
                 && crate_version.as_ref() == version
             {
-                let crate_data: Crate = sonic_rs::serde::from_slice(&content).ok()?;
+                let ParsedCrate {
+                    crate_data,
+                    path_to_id,
+                    lazy_items,
+                } = parsed_cache::load_or_parse(json_path, content, Some(format_version)).ok()?;
                 let version = crate_data
                     .crate_version
                     .as_ref()
@@ -241,49 +764,136 @@ impl LocalSource {
                     provenance: CrateProvenance::LocalDependency,
                     fs_path: json_path.to_owned(),
                     version,
-                    path_to_id: Default::default(),
+                    path_to_id,
+                    lazy_items,
+                    versioned_paths: false,
                 });
             } else if !tried_rebuilding && self.can_rebuild {
                 tried_rebuilding = true;
-                if self.rebuild_docs(&crate_name, version).is_ok() {
-                    continue;
+                match self.rebuild_docs(&crate_name, version, progress) {
+                    Ok(()) => continue,
+                    Err(error) => log::warn!("Could not rebuild docs for {crate_name}: {error}"),
                 }
             }
+
+            if changed && json_path.exists() {
+                log::warn!("The docs for {crate_name} are older than its sources");
+                accept_stale = true;
+                continue;
+            }
             break None;
         }
     }
 
     /// Rebuild documentation for a crate
-    fn rebuild_docs(&self, crate_name: &CrateName<'_>, version: Option<&Version>) -> Result<()> {
+    fn rebuild_docs(
+        &self,
+        crate_name: &CrateName<'_>,
+        version: Option<&Version>,
+        progress: &dyn Progress,
+    ) -> Result<()> {
+        let info = self.package_version(crate_name, version);
+        // Cargo needs a version to pick out a package that is in the graph more than once
+        let version = version.or_else(|| {
+            info.and_then(|info| info.version.as_ref())
+                .filter(|_| !self.other_versions(crate_name).is_empty())
+        });
         let package_spec = match version {
             Some(v) => format!("{}@{}", crate_name, v),
             None => crate_name.to_string(),
         };
 
-        let output = Command::new("rustup")
-            .arg("run")
-            .args([
-                "nightly",
-                "cargo",
-                "doc",
-                "--no-deps",
-                "--package",
-                &package_spec,
-            ])
-            .env("RUSTDOCFLAGS", "-Z unstable-options --output-format=json")
-            .current_dir(self.project_root())
-            .output()?;
+        let _task = ProgressTask::start(
+            progress,
+            &format!("Building docs for {package_spec} with cargo doc"),
+        );
+        let output = run_cargo(
+            Command::new("rustup")
+                .arg("run")
+                .args([
+                    "nightly",
+                    "cargo",
+                    "doc",
+                    "--no-deps",
+                    "--package",
+                    &package_spec,
+                ])
+                .env("RUSTDOCFLAGS", "-Z unstable-options --output-format=json")
+                .current_dir(self.project_root()),
+            progress,
+        )?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             return Err(anyhow!("cargo doc failed: {}", stderr));
         }
+
+        // Git dependencies and packages with several versions are read from their own copy
+        // rather than the target directory
+        let built = self.json_path(crate_name.as_ref());
+        if let Some(cached) = info.and_then(|info| info.json_path.as_deref())
+            && cached != built
+        {
+            if let Some(parent) = cached.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&built, cached)?;
+        }
+
         Ok(())
     }
+
+    /// Whether a path dependency's sources were edited after its docs were built
+    fn path_dependency_changed(&self, crate_name: &CrateName<'_>, json_path: &Path) -> bool {
+        let Some(package_dir) = self.path_dependencies.get(crate_name) else {
+            return false;
+        };
+
+        json_path
+            .metadata()
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .is_none_or(|docs_updated| modified_after(&package_dir.join("src"), docs_updated))
+    }
+}
+
+/// Which of two versions of a package `crates` holds: the one the root package uses, then one
+/// that any workspace package uses, then the newest
+fn is_preferred_version(
+    candidate: &CrateInfo,
+    current: &CrateInfo,
+    root_crate: Option<&CrateName<'_>>,
+) -> bool {
+    let rank = |info: &CrateInfo| {
+        (
+            root_crate.is_some_and(|root_crate| {
+                info.used_by
+                    .iter()
+                    .any(|user| CrateName::from(user.as_str()) == *root_crate)
+            }),
+            !info.used_by.is_empty(),
+            info.version.clone(),
+        )
+    };
+    rank(candidate) > rank(current)
+}
+
+/// Whether any file under `dir` was modified after `time`
+fn modified_after(dir: &Path, time: SystemTime) -> bool {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| -> Option<SystemTime> { entry.ok()?.metadata().ok()?.modified().ok() })
+        .any(|file_updated| file_updated > time)
+}
+
+/// The commit of a git package source like `git+https://github.com/o/r?branch=main#0123abc`
+fn git_commit(source: &str) -> Option<&str> {
+    let (_, commit) = source.strip_prefix("git+")?.rsplit_once('#')?;
+    (!commit.is_empty()).then_some(commit)
 }
 
 impl Source for LocalSource {
-    fn lookup<'a>(&'a self, name: &str, _version: &VersionReq) -> Option<Cow<'a, CrateInfo>> {
+    fn lookup<'a>(&'a self, name: &str, version: &VersionReq) -> Option<Cow<'a, CrateInfo>> {
         // Handle "crate" alias for single-package workspaces
         let search_name = if name == "crate" {
             self.root_crate()?
@@ -291,21 +901,43 @@ impl Source for LocalSource {
             &CrateName::from(name.to_owned())
         };
 
-        self.crates.get(search_name).map(Cow::Borrowed)
+        // A version that isn't in the dependency graph falls through to docs.rs
+        let package_name = self.package_name(search_name);
+        self.crates
+            .get(package_name)
+            .into_iter()
+            .chain(self.other_versions.get(package_name).into_iter().flatten())
+            .find(|crate_info| {
+                *version == VersionReq::STAR
+                    || crate_info
+                        .version
+                        .as_ref()
+                        .is_none_or(|crate_version| version.matches(crate_version))
+            })
+            .map(Cow::Borrowed)
     }
 
-    fn load(&self, crate_name: &str, version: Option<&Version>) -> Option<RustdocData> {
+    fn load(
+        &self,
+        crate_name: &str,
+        version: Option<&Version>,
+        progress: &dyn Progress,
+    ) -> Option<RustdocData> {
         let crate_name = CrateName::from(crate_name);
 
         if self.is_workspace_package(&crate_name) {
-            self.load_workspace_crate(crate_name)
+            self.load_workspace_crate(crate_name, progress)
         } else {
-            self.load_dep(crate_name, version)
+            self.load_dep(crate_name, version, progress)
         }
     }
 
     fn list_available<'a>(&'a self) -> Box<dyn Iterator<Item = &'a CrateInfo> + '_> {
         Box::new(self.crates.values().filter(|crate_info| {
+            if self.is_ignored(&crate_info.name) {
+                return false;
+            }
+
             crate_info.provenance.is_workspace()
                 || match self.root_crate.as_ref() {
                     Some(rc) => crate_info
@@ -318,9 +950,10 @@ impl Source for LocalSource {
     }
 
     fn canonicalize(&self, input_name: &str) -> Option<CrateName<'static>> {
+        let input_name = CrateName::from(input_name);
         self.crates
-            .get_key_value(input_name)
-            .map(|(k, _)| k.clone())
+            .get_key_value(self.package_name(&input_name))
+            .map(|(k, _)| k.to_static())
     }
 }
 
@@ -329,3 +962,55 @@ impl Source for LocalSource {
 //         !c.provenance().is_local_dependency() || c.used_by().iter().any(|u| **u == **rc)
 //     })
 // })
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn package(version: &str, used_by: &[&str]) -> CrateInfo {
+        CrateInfo {
+            provenance: CrateProvenance::LocalDependency,
+            version: Version::parse(version).ok(),
+            description: None,
+            name: "syn".into(),
+            default_crate: false,
+            used_by: used_by.iter().map(|user| user.to_string()).collect(),
+            dependency_scopes: vec![],
+            json_path: None,
+            manifest_path: None,
+            features: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn the_version_the_root_package_uses_is_preferred() {
+        let root = CrateName::from("app");
+        let syn1 = package("1.0.109", &["app"]);
+        let syn2 = package("2.0.87", &["app-macros"]);
+        let transitive = package("2.0.90", &[]);
+
+        assert!(is_preferred_version(&syn1, &syn2, Some(&root)));
+        assert!(!is_preferred_version(&syn2, &syn1, Some(&root)));
+        assert!(is_preferred_version(&syn2, &syn1, None));
+        assert!(is_preferred_version(&syn2, &transitive, None));
+        assert!(is_preferred_version(
+            &transitive,
+            &package("1.0.109", &[]),
+            None
+        ));
+    }
+
+    #[test]
+    fn git_commits_are_read_from_package_sources() {
+        assert_eq!(
+            git_commit("git+https://github.com/jbr/trillium?branch=main#3f2a9c1d"),
+            Some("3f2a9c1d")
+        );
+        assert_eq!(
+            git_commit("registry+https://github.com/rust-lang/crates.io-index"),
+            None
+        );
+        assert_eq!(git_commit("git+https://github.com/jbr/trillium"), None);
+    }
+}
@@ -0,0 +1,136 @@
+//! Workspace configuration from `[workspace.metadata.ferritin]`
+//!
+//! ```toml
+//! [workspace.metadata.ferritin]
+//! guides = ["docs/architecture.md"]
+//! # Leave these out of crate listings and all-crates search
+//! ignore = ["windows-sys", "web-sys"]
+//!
+//! # Show docs for these versions instead of the ones in Cargo.lock
+//! [workspace.metadata.ferritin.pin]
+//! tokio = "1.40.0"
+//!
+//! # Fetch docs for crates from a private registry from its own docs server
+//! [workspace.metadata.ferritin.registries.internal]
+//! index = "sparse+https://cargo.example.com/index/"
+//! docs-url = "https://docs.example.com"
+//! ```
+
+use fieldwork::Fieldwork;
+use semver::Version;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct FerritinMetadata {
+    /// Markdown pages shown under `crate::guide`
+    pub(crate) guides: Vec<PathBuf>,
+    /// Crates left out of listings and all-crates search. They can still be viewed by path.
+    pub(crate) ignore: Vec<String>,
+    /// Dependencies whose docs are loaded from docs.rs at a fixed version
+    pub(crate) pin: BTreeMap<String, Version>,
+    /// Private registries, by the name cargo knows them as
+    pub(crate) registries: BTreeMap<String, Registry>,
+}
+
+/// A private registry, from `[workspace.metadata.ferritin.registries.<name>]`
+///
+/// Crates are attributed to a registry when their package source in cargo metadata is its
+/// index. Without a `docs-url`, their docs are always built locally.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Fieldwork)]
+#[serde(default, rename_all = "kebab-case")]
+#[fieldwork(get)]
+pub struct Registry {
+    /// The name cargo knows the registry as, from the table key
+    #[serde(skip)]
+    name: String,
+    /// The index URL, as it appears in `Cargo.lock`, e.g. `sparse+https://…/index/`
+    index: String,
+    /// A server with the docs.rs JSON endpoints, `{docs-url}/crate/{name}/{version}/json/{format}`
+    docs_url: Option<String>,
+}
+
+impl Registry {
+    /// Whether a package source from cargo metadata is this registry's index
+    pub(crate) fn is_source(&self, source: &str) -> bool {
+        let source = source.strip_prefix("registry+").unwrap_or(source);
+        source.trim_end_matches('/') == self.index.trim_end_matches('/')
+    }
+
+    /// The token cargo uses for this registry, from `CARGO_REGISTRIES_<NAME>_TOKEN`
+    pub fn token(&self) -> Option<String> {
+        let name = self.name.to_uppercase().replace('-', "_");
+        std::env::var(format!("CARGO_REGISTRIES_{name}_TOKEN")).ok()
+    }
+}
+
+impl FerritinMetadata {
+    /// Read the `ferritin` table from `workspace_metadata`
+    ///
+    /// A missing table is the default configuration. An invalid one is ignored with a warning,
+    /// so that a typo doesn't make the workspace unusable.
+    pub(crate) fn from_workspace_metadata(workspace_metadata: &serde_json::Value) -> Self {
+        let Some(ferritin) = workspace_metadata.get("ferritin") else {
+            return Self::default();
+        };
+
+        let mut metadata: Self = serde_json::from_value(ferritin.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid [workspace.metadata.ferritin]: {e}");
+            Self::default()
+        });
+        for (name, registry) in &mut metadata.registries {
+            registry.name = name.clone();
+        }
+        metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn ignore_and_pin_are_read_from_workspace_metadata() {
+        let metadata = FerritinMetadata::from_workspace_metadata(&json!({
+            "ferritin": {
+                "ignore": ["windows-sys"],
+                "pin": { "tokio": "1.40.0" }
+            }
+        }));
+
+        assert_eq!(metadata.ignore, ["windows-sys"]);
+        assert_eq!(metadata.pin["tokio"], Version::new(1, 40, 0));
+        assert!(metadata.guides.is_empty());
+    }
+
+    #[test]
+    fn registries_match_their_package_sources() {
+        let metadata = FerritinMetadata::from_workspace_metadata(&json!({
+            "ferritin": {
+                "registries": {
+                    "internal": {
+                        "index": "sparse+https://cargo.example.com/index/",
+                        "docs-url": "https://docs.example.com"
+                    }
+                }
+            }
+        }));
+
+        let registry = &metadata.registries["internal"];
+        assert_eq!(registry.name(), "internal");
+        assert_eq!(registry.docs_url(), Some("https://docs.example.com"));
+        assert!(registry.is_source("sparse+https://cargo.example.com/index"));
+        assert!(!registry.is_source("registry+https://github.com/rust-lang/crates.io-index"));
+    }
+
+    #[test]
+    fn invalid_config_falls_back_to_default() {
+        let metadata = FerritinMetadata::from_workspace_metadata(&json!({
+            "ferritin": { "pin": { "tokio": "not a version" } }
+        }));
+        assert!(metadata.pin.is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+//! Workspace guide pages
+//!
+//! A workspace can list extra markdown files in its manifest:
+//!
+//! ```toml
+//! [workspace.metadata.ferritin]
+//! guides = ["docs/architecture.md", "docs/getting-started.md"]
+//! ```
+//!
+//! Each page becomes a pseudo-module under `crate::guide` (e.g. `crate::guide::architecture`)
+//! whose docs are the file's contents, so guides resolve, render and index for search exactly
+//! like the API documentation around them.
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, ItemKind, ItemSummary, Module, Visibility};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The name of the pseudo-module that guide pages are placed in
+pub(crate) const GUIDE_MODULE: &str = "guide";
+
+/// A markdown page configured in `[workspace.metadata.ferritin]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GuidePage {
+    /// The item name, derived from the file stem (`getting-started.md` → `getting_started`)
+    pub(crate) name: String,
+    pub(crate) path: PathBuf,
+}
+
+impl GuidePage {
+    pub(crate) fn new(path: PathBuf) -> Option<Self> {
+        let stem = path.file_stem()?.to_str()?;
+        let name = stem
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        Some(Self { name, path })
+    }
+
+    /// Pages for the configured guide `paths`, resolved against `workspace_root`
+    pub(crate) fn from_paths(paths: &[PathBuf], workspace_root: &Path) -> Vec<Self> {
+        paths
+            .iter()
+            .filter_map(|path| {
+                let page = Self::new(workspace_root.join(path));
+                if page.is_none() {
+                    log::warn!(
+                        "Ignoring guide with no usable file name: {}",
+                        path.display()
+                    );
+                }
+                page
+            })
+            .collect()
+    }
+
+    pub(crate) fn modified(&self) -> Option<SystemTime> {
+        self.path.metadata().ok()?.modified().ok()
+    }
+}
+
+/// Add a `guide` module containing one pseudo-module per page to the crate root
+///
+/// Pages that cannot be read are skipped with a warning. Does nothing if there are no readable
+/// pages, or if the crate already has an item named `guide` at its root.
+pub(crate) fn inject_guides(crate_data: &mut Crate, pages: &[GuidePage]) {
+    let root_id = crate_data.root;
+    let Some(crate_name) = crate_data
+        .index
+        .get(&root_id)
+        .and_then(|root| root.name.clone())
+    else {
+        return;
+    };
+
+    let root_items = match &crate_data.index[&root_id].inner {
+        ItemEnum::Module(module) => &module.items,
+        _ => return,
+    };
+    if root_items.iter().any(|id| {
+        crate_data
+            .index
+            .get(id)
+            .is_some_and(|item| item.name.as_deref() == Some(GUIDE_MODULE))
+    }) {
+        log::warn!("{crate_name} already has an item named `{GUIDE_MODULE}`; not adding guides");
+        return;
+    }
+
+    // Ids only in `paths` belong to items from other crates, which must not be reused either
+    let mut next_id = crate_data
+        .index
+        .keys()
+        .chain(crate_data.paths.keys())
+        .map(|id| id.0)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut fresh_id = || {
+        let id = Id(next_id);
+        next_id += 1;
+        id
+    };
+
+    let mut page_ids = vec![];
+    for page in pages {
+        let docs = match std::fs::read_to_string(&page.path) {
+            Ok(docs) => docs,
+            Err(e) => {
+                log::warn!("Could not read guide {}: {e}", page.path.display());
+                continue;
+            }
+        };
+
+        let id = fresh_id();
+        insert_module(
+            crate_data,
+            id,
+            &page.name,
+            docs,
+            vec![],
+            [&*crate_name, GUIDE_MODULE, page.name.as_str()],
+        );
+        page_ids.push(id);
+    }
+
+    if page_ids.is_empty() {
+        return;
+    }
+
+    let guide_id = fresh_id();
+    insert_module(
+        crate_data,
+        guide_id,
+        GUIDE_MODULE,
+        String::from("Guides for this workspace"),
+        page_ids,
+        [&*crate_name, GUIDE_MODULE],
+    );
+
+    if let Some(Item {
+        inner: ItemEnum::Module(root),
+        ..
+    }) = crate_data.index.get_mut(&root_id)
+    {
+        root.items.push(guide_id);
+    }
+}
+
+fn insert_module<'a>(
+    crate_data: &mut Crate,
+    id: Id,
+    name: &str,
+    docs: String,
+    items: Vec<Id>,
+    path: impl IntoIterator<Item = &'a str>,
+) {
+    crate_data.index.insert(
+        id,
+        Item {
+            id,
+            crate_id: 0,
+            name: Some(name.to_string()),
+            span: None,
+            visibility: Visibility::Public,
+            docs: Some(docs),
+            links: Default::default(),
+            attrs: vec![],
+            deprecation: None,
+            inner: ItemEnum::Module(Module {
+                is_crate: false,
+                items,
+                is_stripped: false,
+            }),
+        },
+    );
+
+    crate_data.paths.insert(
+        id,
+        ItemSummary {
+            crate_id: 0,
+            path: path.into_iter().map(String::from).collect(),
+            kind: ItemKind::Module,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+    use crate::sources::LocalSource;
+
+    fn children<'a>(crate_data: &'a Crate, id: &Id) -> Vec<&'a Item> {
+        match &crate_data.index[id].inner {
+            ItemEnum::Module(module) => module
+                .items
+                .iter()
+                .map(|id| &crate_data.index[id])
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn guide_names_come_from_file_stems() {
+        let page = GuidePage::new(PathBuf::from("docs/Getting-Started.md")).unwrap();
+        assert_eq!(page.name, "getting_started");
+    }
+
+    #[test]
+    fn guides_are_added_under_the_crate_root() {
+        let path = std::env::temp_dir().join(format!("ferritin-guide-{}.md", std::process::id()));
+        std::fs::write(&path, "# Architecture\n\nHow the pieces fit together").unwrap();
+
+        let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tests/fixture-crate");
+        let source = LocalSource::load(&fixture).unwrap();
+        let mut crate_data = source
+            .load_workspace_crate("fixture-crate".into(), &NoProgress)
+            .unwrap()
+            .crate_data;
+
+        // An item from another crate, known only by its path
+        let foreign_id = Id(crate_data.index.keys().map(|id| id.0).max().unwrap() + 1);
+        let foreign = ItemSummary {
+            crate_id: 1,
+            path: vec!["other".into(), "Item".into()],
+            kind: ItemKind::Struct,
+        };
+        crate_data.paths.insert(foreign_id, foreign.clone());
+
+        let page = GuidePage::new(path.clone()).unwrap();
+        inject_guides(&mut crate_data, &[page.clone()]);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(crate_data.paths[&foreign_id], foreign);
+
+        let root = crate_data.root;
+        let guide = children(&crate_data, &root)
+            .into_iter()
+            .find(|item| item.name.as_deref() == Some(GUIDE_MODULE))
+            .expect("guide module added to the crate root");
+
+        let pages = children(&crate_data, &guide.id);
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].name.as_deref(), Some(&*page.name));
+        assert!(pages[0].docs.as_deref().unwrap().contains("How the pieces"));
+        assert_eq!(
+            crate_data.paths[&pages[0].id].path[1..],
+            [GUIDE_MODULE, page.name.as_str()]
+        );
+    }
+
+    #[test]
+    fn changing_the_configured_guides_drops_the_index() {
+        let dir = std::env::temp_dir().join(format!("ferritin-guides-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let json_path = dir.join("crate.json");
+        let index_path = json_path.with_extension("index");
+        let page = GuidePage::new(dir.join("architecture.md")).unwrap();
+
+        std::fs::write(&index_path, "").unwrap();
+        LocalSource::invalidate_stale_index(&json_path, std::slice::from_ref(&page));
+        assert!(!index_path.exists());
+
+        // Unchanged guides keep the index
+        std::fs::write(&index_path, "").unwrap();
+        LocalSource::invalidate_stale_index(&json_path, std::slice::from_ref(&page));
+        assert!(index_path.exists());
+
+        // Removing the guides drops it again
+        LocalSource::invalidate_stale_index(&json_path, &[]);
+        assert!(!index_path.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,14 +1,16 @@
 use crate::CrateName;
 use crate::RustdocData;
 use crate::navigator::CrateInfo;
+use crate::parsed_cache::{self, ParsedCrate};
+use crate::progress::{Progress, ProgressTask};
 use crate::sources::CrateProvenance;
 use crate::sources::Source;
 use fieldwork::Fieldwork;
 use rustc_hash::FxHashMap;
-use rustdoc_types::{Crate, FORMAT_VERSION};
 use semver::Version;
 use semver::VersionReq;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -28,35 +30,73 @@ const STD_DESCRIPTIONS: [(&str, &str); 6] = [
     ("std_detect", ""), // we claim to have a "std_detect" crate that we then fail to load
 ];
 
+/// The toolchain std docs are loaded from unless another is chosen
+pub const DEFAULT_TOOLCHAIN: &str = "nightly";
+
 /// Source for std library documentation (rustup-managed)
 #[derive(Debug, Clone, Fieldwork)]
 #[field(get)]
 pub struct StdSource {
     docs_path: PathBuf,
     rustc_version: Version,
+    /// The rustup toolchain the docs come from, like `nightly` or `nightly-2024-05-01`
+    toolchain: String,
     crates: FxHashMap<&'static str, CrateInfo>,
 }
 
+/// An installed rustup toolchain, and whether it has the `rust-docs-json` component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolchainDocs {
+    pub name: String,
+    pub is_default: bool,
+    pub has_docs_json: bool,
+}
+
+/// Where the `rust-docs-json` component puts std's rustdoc JSON in a toolchain's sysroot
+fn docs_json_path(toolchain: &str) -> Option<PathBuf> {
+    let sysroot = Command::new("rustup")
+        .args(["run", toolchain, "rustc", "--print", "sysroot"])
+        .output()
+        .ok()?;
+
+    if !sysroot.status.success() {
+        return None;
+    }
+
+    let s = std::str::from_utf8(&sysroot.stdout).ok()?;
+    Some(PathBuf::from(s.trim()).join("share/doc/rust/json/"))
+}
+
+/// Toolchain names from `rustup toolchain list`, with whether each is the default
+fn parse_toolchain_list(output: &str) -> Vec<(String, bool)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, annotations) = line
+                .trim()
+                .split_once(char::is_whitespace)
+                .unwrap_or((line.trim(), ""));
+            (!name.is_empty()).then(|| (name.to_string(), annotations.contains("default")))
+        })
+        .collect()
+}
+
 impl StdSource {
-    /// Try to create a StdSource from the current rustup installation
+    /// Try to create a StdSource from the nightly toolchain of the current rustup installation
     pub fn from_rustup() -> Option<Self> {
-        let sysroot = Command::new("rustup")
-            .args(["run", "nightly", "rustc", "--print", "sysroot"])
-            .output()
-            .ok()?;
-
-        if !sysroot.status.success() {
-            return None;
-        }
+        Self::from_toolchain(DEFAULT_TOOLCHAIN)
+    }
 
-        let s = std::str::from_utf8(&sysroot.stdout).ok()?;
-        let docs_path = PathBuf::from(s.trim()).join("share/doc/rust/json/");
+    /// Try to create a StdSource from an installed toolchain like `stable`, `1.74.0` or
+    /// `nightly-2024-05-01`, if it has the `rust-docs-json` component
+    pub fn from_toolchain(toolchain: &str) -> Option<Self> {
+        let docs_path = docs_json_path(toolchain)?;
         if !docs_path.exists() {
             return None;
         }
 
         let version = Command::new("rustup")
-            .args(["run", "nightly", "rustc", "--version", "--verbose"])
+            .args(["run", toolchain, "rustc", "--version", "--verbose"])
             .output()
             .ok()?;
 
@@ -84,8 +124,11 @@ impl StdSource {
                         name: name.to_string(),
                         default_crate: false,
                         used_by: vec![],
+                        dependency_scopes: vec![],
                         json_path: (name != "std_detect")
                             .then(|| docs_path.join(format!("{name}.json"))),
+                        manifest_path: None,
+                        features: BTreeMap::new(),
                     },
                 )
             })
@@ -94,9 +137,29 @@ impl StdSource {
         Some(Self {
             docs_path,
             rustc_version,
+            toolchain: toolchain.to_string(),
             crates,
         })
     }
+
+    /// Every installed rustup toolchain, with whether std docs can be loaded from it
+    pub fn installed_toolchains() -> Vec<ToolchainDocs> {
+        let Ok(output) = Command::new("rustup").args(["toolchain", "list"]).output() else {
+            return vec![];
+        };
+        if !output.status.success() {
+            return vec![];
+        }
+
+        parse_toolchain_list(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .map(|(name, is_default)| ToolchainDocs {
+                has_docs_json: docs_json_path(&name).is_some_and(|path| path.exists()),
+                name,
+                is_default,
+            })
+            .collect()
+    }
 }
 
 impl Source for StdSource {
@@ -105,27 +168,34 @@ impl Source for StdSource {
         self.crates.get(&*canonical).map(Cow::Borrowed)
     }
 
-    fn load(&self, crate_name: &str, _version: Option<&Version>) -> Option<RustdocData> {
+    fn load(
+        &self,
+        crate_name: &str,
+        _version: Option<&Version>,
+        progress: &dyn Progress,
+    ) -> Option<RustdocData> {
         let crate_info = self.lookup(crate_name, &VersionReq::STAR)?;
         let json_path = crate_info.json_path.as_ref()?.to_owned();
+        let _task = ProgressTask::start(progress, &format!("Loading {crate_name} docs"));
         let content = std::fs::read(&json_path).ok()?;
 
-        let Ok(FORMAT_VERSION) = sonic_rs::get_from_slice(&content, &["format_version"])
-            .ok()?
-            .as_raw_str()
-            .parse()
-        else {
-            return None;
-        };
-
-        let crate_data: Crate = sonic_rs::serde::from_slice(&content).ok()?;
+        // Older toolchains write older format versions, which are converted
+        let ParsedCrate {
+            crate_data,
+            path_to_id,
+            lazy_items,
+        } = parsed_cache::load_or_parse(&json_path, content, None)
+            .inspect_err(|error| log::warn!("Could not load {crate_name} docs: {error:?}"))
+            .ok()?;
         Some(RustdocData {
             crate_data,
             name: crate_name.to_string(),
             provenance: CrateProvenance::Std,
             fs_path: json_path,
             version: Some(self.rustc_version.clone()),
-            path_to_id: Default::default(),
+            path_to_id,
+            lazy_items,
+            versioned_paths: false,
         })
     }
 
@@ -151,3 +221,26 @@ impl Source for StdSource {
         Some(CrateName::from(canonical))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toolchain_list_marks_the_default() {
+        let output = "stable-x86_64-unknown-linux-gnu (active, default)\n\
+                      nightly-2024-05-01-x86_64-unknown-linux-gnu\n\
+                      1.74.0-x86_64-unknown-linux-gnu\n";
+        assert_eq!(
+            parse_toolchain_list(output),
+            [
+                ("stable-x86_64-unknown-linux-gnu".to_string(), true),
+                (
+                    "nightly-2024-05-01-x86_64-unknown-linux-gnu".to_string(),
+                    false
+                ),
+                ("1.74.0-x86_64-unknown-linux-gnu".to_string(), false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,332 @@
+//! Rustdoc JSON built locally from the crate sources cargo has downloaded, for crate versions
+//! that docs.rs has no JSON for
+
+use super::{CrateProvenance, Source, run_cargo};
+use crate::parsed_cache::{self, ParsedCrate};
+use crate::progress::{Progress, ProgressTask};
+use crate::{CrateInfo, RustdocData};
+use anyhow::{Context, Result, anyhow};
+use fieldwork::Fieldwork;
+use semver::{Version, VersionReq};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Source that documents registry crates from `$CARGO_HOME/registry/src` with nightly rustdoc
+///
+/// Building is slow, so each result is cached as `{cache_dir}/{crate}/{version}.json`.
+#[derive(Debug, Fieldwork)]
+pub struct VendoredSource {
+    /// `$CARGO_HOME/registry/src`, which has one directory of unpacked crates per registry
+    #[field(get)]
+    registry_src: PathBuf,
+    #[field(get)]
+    cache_dir: PathBuf,
+    /// Pass `--offline` to cargo, so that building never touches the network
+    #[field(get = "is_offline", with)]
+    offline: bool,
+}
+
+impl VendoredSource {
+    pub fn new(registry_src: PathBuf, cache_dir: PathBuf) -> Self {
+        Self {
+            registry_src,
+            cache_dir,
+            offline: false,
+        }
+    }
+
+    /// Use the registry sources and docs cache in the default cargo home
+    pub fn from_default_cargo_home() -> Option<Self> {
+        let cargo_home = home::cargo_home().ok()?;
+        Some(Self::new(
+            cargo_home.join("registry").join("src"),
+            cargo_home.join("rustdoc-json").join("vendored"),
+        ))
+    }
+
+    /// Every unpacked version of a crate, newest first, with the directory it is in
+    fn vendored_versions(&self, crate_name: &str) -> Vec<(Version, PathBuf)> {
+        let mut versions = std::fs::read_dir(&self.registry_src)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .flat_map(|registry| std::fs::read_dir(registry.path()).into_iter().flatten())
+            .flatten()
+            .filter_map(|entry| {
+                let version = vendored_version(entry.file_name().to_str()?, crate_name)?;
+                Some((version, entry.path()))
+            })
+            .filter(|(_, dir)| dir.join("Cargo.toml").is_file())
+            .collect::<Vec<_>>();
+        versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+        versions.dedup_by(|(a, _), (b, _)| a == b);
+        versions
+    }
+
+    /// The directory cargo unpacked this crate version into, if it has
+    pub fn source_dir(&self, crate_name: &str, version: &Version) -> Option<PathBuf> {
+        self.vendored_versions(crate_name)
+            .into_iter()
+            .find(|(vendored, _)| vendored == version)
+            .map(|(_, dir)| dir)
+    }
+
+    fn cache_path(&self, crate_name: &str, version: &Version) -> PathBuf {
+        self.cache_dir
+            .join(crate_name)
+            .join(format!("{version}.json"))
+    }
+
+    /// Document an unpacked crate with nightly rustdoc, copying the JSON into the cache
+    ///
+    /// Cargo writes a `Cargo.lock` next to the manifest it builds, and the registry sources
+    /// are shared with every other build on the machine, so the crate is copied out and
+    /// built from the copy.
+    fn build(
+        &self,
+        crate_name: &str,
+        version: &Version,
+        source_dir: &Path,
+        progress: &dyn Progress,
+    ) -> Result<()> {
+        log::info!(
+            "Building rustdoc JSON for {crate_name}@{version} from {}",
+            source_dir.display()
+        );
+
+        let build_dir = self
+            .cache_dir
+            .join("build")
+            .join(format!("{crate_name}-{version}"));
+        if build_dir.exists() {
+            std::fs::remove_dir_all(&build_dir).context("Failed to clear build directory")?;
+        }
+        copy_dir(source_dir, &build_dir)
+            .with_context(|| format!("Failed to copy {}", source_dir.display()))?;
+        let built = self.build_copy(crate_name, version, &build_dir, progress);
+        if let Err(error) = std::fs::remove_dir_all(&build_dir) {
+            log::warn!("Could not remove {}: {error}", build_dir.display());
+        }
+        built
+    }
+
+    fn build_copy(
+        &self,
+        crate_name: &str,
+        version: &Version,
+        source_dir: &Path,
+        progress: &dyn Progress,
+    ) -> Result<()> {
+        let target_dir = self.cache_dir.join("target");
+        let mut command = Command::new("rustup");
+        command
+            .args([
+                "run",
+                "nightly",
+                "cargo",
+                "rustdoc",
+                "--lib",
+                "--manifest-path",
+            ])
+            .arg(source_dir.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .args(["-Z", "unstable-options", "--output-format", "json"]);
+        if self.offline {
+            command.arg("--offline");
+        }
+
+        let output = run_cargo(&mut command, progress)?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("cargo rustdoc failed: {stderr}"));
+        }
+
+        let built = target_dir
+            .join("doc")
+            .join(format!("{}.json", crate_name.replace('-', "_")));
+        let cache_path = self.cache_path(crate_name, version);
+        if let Some(parent) = cache_path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create cache directory")?;
+        }
+        std::fs::rename(&built, &cache_path)
+            .or_else(|_| std::fs::copy(&built, &cache_path).map(|_| ()))
+            .with_context(|| format!("Failed to cache {}", built.display()))
+    }
+
+    fn load_cached(&self, crate_name: &str, version: &Version) -> Result<Option<RustdocData>> {
+        let path = self.cache_path(crate_name, version);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let json = std::fs::read(&path).context("Failed to read cached file")?;
+        let ParsedCrate {
+            crate_data,
+            path_to_id,
+            lazy_items,
+        } = parsed_cache::load_or_parse(&path, json, None)
+            .context("Failed to normalize locally built JSON")?;
+
+        Ok(Some(RustdocData {
+            crate_data,
+            name: crate_name.to_string(),
+            provenance: CrateProvenance::DocsRs,
+            fs_path: path,
+            version: Some(version.clone()),
+            path_to_id,
+            lazy_items,
+            versioned_paths: false,
+        }))
+    }
+}
+
+/// Copy the directory tree at `from` to `to`, leaving out any build output
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let destination = to.join(entry.file_name());
+        if file_type.is_dir() {
+            if entry.file_name() != "target" {
+                copy_dir(&entry.path(), &destination)?;
+            }
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// The version in an unpacked crate directory name like `serde-json-1.0.140`, if it is a
+/// version of `crate_name` (which may be spelled with dashes or underscores)
+fn vendored_version(dir_name: &str, crate_name: &str) -> Option<Version> {
+    let (name, version) = parse_package_dir(dir_name)?;
+    (name.replace('_', "-") == crate_name.replace('_', "-")).then_some(version)
+}
+
+/// The crate name and version in an unpacked crate directory name like `serde-json-1.0.140`
+pub fn parse_package_dir(dir_name: &str) -> Option<(&str, Version)> {
+    dir_name
+        .char_indices()
+        .filter(|&(index, ch)| {
+            ch == '-'
+                && dir_name[index + 1..]
+                    .chars()
+                    .next()
+                    .is_some_and(|next| next.is_ascii_digit())
+        })
+        .find_map(|(index, _)| {
+            Some((
+                &dir_name[..index],
+                Version::parse(&dir_name[index + 1..]).ok()?,
+            ))
+        })
+}
+
+impl Source for VendoredSource {
+    /// Only used when neither the workspace nor docs.rs know the crate, which happens when
+    /// offline or when crates.io can't be reached
+    fn lookup<'a>(&'a self, name: &str, version_req: &VersionReq) -> Option<Cow<'a, CrateInfo>> {
+        let (version, dir) = self
+            .vendored_versions(name)
+            .into_iter()
+            .find(|(version, _)| version_req.matches(version))?;
+
+        Some(Cow::Owned(CrateInfo {
+            provenance: CrateProvenance::DocsRs,
+            version: Some(version),
+            description: None,
+            name: name.to_string(),
+            default_crate: false,
+            used_by: vec![],
+            dependency_scopes: vec![],
+            json_path: None,
+            manifest_path: Some(dir.join("Cargo.toml")),
+            features: BTreeMap::new(),
+        }))
+    }
+
+    fn load(
+        &self,
+        crate_name: &str,
+        version: Option<&Version>,
+        progress: &dyn Progress,
+    ) -> Option<RustdocData> {
+        let version = version?;
+        let load = || -> Result<Option<RustdocData>> {
+            if let Some(data) = self.load_cached(crate_name, version)? {
+                return Ok(Some(data));
+            }
+
+            let Some((_, source_dir)) = self
+                .vendored_versions(crate_name)
+                .into_iter()
+                .find(|(vendored, _)| vendored == version)
+            else {
+                return Ok(None);
+            };
+
+            let _task = ProgressTask::start(
+                progress,
+                &format!("Building docs for {crate_name} {version} from its registry source"),
+            );
+            self.build(crate_name, version, &source_dir, progress)?;
+            self.load_cached(crate_name, version)
+        };
+
+        load()
+            .inspect_err(|error| log::warn!("Could not build {crate_name}@{version}: {error:?}"))
+            .ok()
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+
+    #[test]
+    fn versions_are_parsed_from_unpacked_directory_names() {
+        assert_eq!(
+            vendored_version("serde_json-1.0.140", "serde-json"),
+            Some(Version::new(1, 0, 140))
+        );
+        assert_eq!(
+            vendored_version("pin-project-lite-0.2.16", "pin_project_lite"),
+            Some(Version::new(0, 2, 16))
+        );
+        assert_eq!(
+            vendored_version("windows-sys-0.59.0-rc.1", "windows-sys"),
+            Version::parse("0.59.0-rc.1").ok()
+        );
+        assert_eq!(vendored_version("serde-1.0.219", "serde_json"), None);
+        assert_eq!(vendored_version("x11-dl-2.21.0", "x11"), None);
+    }
+
+    #[test]
+    fn builds_leave_the_registry_source_untouched() {
+        let root = std::env::temp_dir().join(format!("ferritin-vendored-{}", std::process::id()));
+        let source_dir = root.join("src").join("index").join("tiny-0.1.0");
+        std::fs::create_dir_all(source_dir.join("src")).unwrap();
+        std::fs::write(
+            source_dir.join("Cargo.toml"),
+            "[package]\nname = \"tiny\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::write(source_dir.join("src").join("lib.rs"), "pub fn tiny() {}\n").unwrap();
+
+        let source = VendoredSource::new(root.join("src"), root.join("cache")).with_offline(true);
+        // Building needs nightly, which may not be installed; either way the source is left
+        // as cargo unpacked it
+        let _ = source.build("tiny", &Version::new(0, 1, 0), &source_dir, &NoProgress);
+
+        assert!(!source_dir.join("Cargo.lock").exists());
+        assert!(!root.join("cache").join("build").join("tiny-0.1.0").exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -11,6 +11,45 @@ pub(crate) fn case_aware_jaro_winkler(a: &str, b: &str) -> f64 {
     (1.0 - CASE_WEIGHT) * case_insensitive + CASE_WEIGHT * case_sensitive
 }
 
+/// Whether two names are the same apart from ASCII case and at most one typo: a substituted,
+/// missing or extra character, or one pair of swapped neighbouring characters
+///
+/// A missing or extra underscore isn't a typo, so that `incrementCount` isn't taken for
+/// `increment_count`: a name in another case convention is a different name.
+pub(crate) fn is_near_miss(a: &str, b: &str) -> bool {
+    let a = a.to_ascii_lowercase();
+    let b = b.to_ascii_lowercase();
+    a == b || (a.replace('_', "") != b.replace('_', "") && strsim::osa_distance(&a, &b) == 1)
+}
+
+#[cfg(test)]
+mod near_miss_tests {
+    use crate::string_utils::is_near_miss;
+
+    #[test]
+    fn case_substitutions_and_swaps_are_near_misses() {
+        assert!(is_near_miss("mutex", "Mutex"));
+        assert!(is_near_miss("deserialize", "Deserialize"));
+        assert!(is_near_miss("Deserialise", "Deserialize"));
+        assert!(is_near_miss("Deserialzie", "Deserialize"));
+    }
+
+    #[test]
+    fn missing_and_extra_characters_are_near_misses() {
+        assert!(is_near_miss("Mutx", "Mutex"));
+        assert!(is_near_miss("mutexx", "Mutex"));
+        assert!(is_near_miss("Deserializ", "Deserialize"));
+    }
+
+    #[test]
+    fn other_differences_are_not() {
+        assert!(!is_near_miss("incrementCount", "increment_count"));
+        assert!(!is_near_miss("get_value", "getvalue"));
+        assert!(!is_near_miss("Dserialise", "Deserialize"));
+        assert!(!is_near_miss("abcd", "badc"));
+    }
+}
+
 #[cfg(test)]
 mod case_aware_jaro_winkler_tests {
     use crate::string_utils::case_aware_jaro_winkler;
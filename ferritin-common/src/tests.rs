@@ -1,4 +1,5 @@
 use rustdoc_types::ItemKind;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 use crate::{
@@ -10,7 +11,7 @@ fn get_fixture_crate_path() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tests/fixture-crate")
 }
 
-fn test_navigator() -> Navigator {
+pub(crate) fn test_navigator() -> Navigator {
     Navigator::default()
         .with_local_source(LocalSource::load(&get_fixture_crate_path()).ok())
         .with_std_source(StdSource::from_rustup())
@@ -198,3 +199,555 @@ fn private_module_path_resolves_via_index() {
         "re-export and private-module path should resolve to the same item"
     );
 }
+
+/// Build, dev, and target-specific dependencies are labeled unless also a plain dependency.
+#[test]
+fn dependency_labels_describe_scopes() {
+    use crate::navigator::CrateInfo;
+    use crate::sources::{CrateProvenance, DependencyKind, DependencyScope};
+
+    let info = |dependency_scopes: Vec<DependencyScope>| CrateInfo {
+        provenance: CrateProvenance::LocalDependency,
+        version: None,
+        description: None,
+        name: "cc".into(),
+        default_crate: false,
+        used_by: vec!["fixture-crate".into()],
+        dependency_scopes,
+        json_path: None,
+        manifest_path: None,
+        features: BTreeMap::new(),
+    };
+    let scope = |kind: DependencyKind, target: Option<&str>| DependencyScope {
+        kind,
+        target: target.map(String::from),
+    };
+
+    assert_eq!(
+        info(vec![
+            scope(DependencyKind::Build, None),
+            scope(DependencyKind::Dev, Some("cfg(windows)")),
+        ])
+        .dependency_labels(),
+        vec!["build", "dev, cfg(windows)"]
+    );
+
+    assert_eq!(
+        info(vec![scope(DependencyKind::Normal, Some("cfg(unix)"))]).dependency_labels(),
+        vec!["cfg(unix)"]
+    );
+
+    assert!(
+        info(vec![
+            scope(DependencyKind::Normal, None),
+            scope(DependencyKind::Build, None),
+        ])
+        .dependency_labels()
+        .is_empty()
+    );
+}
+
+/// `#[doc(alias = "...")]` names resolve to the aliased item.
+#[test]
+fn doc_alias_resolves_to_item() {
+    let nav = test_navigator();
+
+    let function = resolve(&nav, "crate::test_function");
+    assert_eq!(function.doc_aliases().collect::<Vec<_>>(), vec!["process"]);
+    assert_eq!(resolve(&nav, "crate::process"), function);
+    assert_eq!(resolve(&nav, "crate::fn@process"), function);
+}
+
+/// Globs must match a whole name or path; regexes can match anywhere.
+#[test]
+fn path_patterns_match_names_and_paths() {
+    use crate::search::PathPattern;
+
+    let glob = PathPattern::glob("De*ializer").unwrap();
+    assert!(glob.is_match("Deserializer", "serde::Deserializer"));
+    assert!(!glob.is_match("DeserializerExt", "serde::DeserializerExt"));
+
+    let glob = PathPattern::glob("std::fs::*dir").unwrap();
+    assert!(glob.is_match("create_dir", "std::fs::create_dir"));
+
+    let regex = PathPattern::regex("^try_.*parse").unwrap();
+    assert!(regex.is_match("try_parse_from", "clap::Parser::try_parse_from"));
+    assert!(!regex.is_match("parse", "std::str::parse"));
+
+    assert!(PathPattern::regex("(unclosed").is_err());
+}
+
+/// Pattern search walks the fixture crate's index and matches item names.
+#[test]
+fn search_by_pattern_finds_items() {
+    use crate::search::PathPattern;
+
+    let nav = test_navigator();
+    let pattern = PathPattern::glob("test_*").unwrap();
+    let matches = nav
+        .search_by_pattern(&pattern, &["fixture-crate"])
+        .unwrap_or_else(|_| panic!("fixture crate should be searchable"));
+
+    let function = resolve(&nav, "crate::test_function");
+    assert!(
+        matches.iter().any(|m| m.item == function),
+        "expected test_function in {:?}",
+        matches.iter().map(|m| &m.path).collect::<Vec<_>>()
+    );
+}
+
+/// A failed lookup records where resolution diverged, including discriminator mismatches.
+#[test]
+fn resolution_trace_explains_failure() {
+    use crate::{ResolutionStep, ResolutionTrace, SegmentOutcome};
+
+    let nav = test_navigator();
+    let mut trace = ResolutionTrace::new();
+    let resolved = nav.resolve_path_traced("crate::struct@test_function", &mut vec![], &mut trace);
+    assert!(resolved.is_none());
+
+    assert!(matches!(
+        trace.steps().first(),
+        Some(ResolutionStep::Crate {
+            loaded: Some(_),
+            ..
+        })
+    ));
+
+    assert!(trace.steps().iter().any(|step| matches!(
+        step,
+        ResolutionStep::Segment {
+            name,
+            kind_filter: Some(ItemKind::Struct),
+            outcome: SegmentOutcome::NotFound { kind_mismatches, .. },
+            ..
+        } if name == "test_function" && kind_mismatches == &[ItemKind::Function]
+    )));
+
+    assert!(
+        trace
+            .steps()
+            .iter()
+            .any(|step| matches!(step, ResolutionStep::PathIndex { found: false, .. }))
+    );
+}
+
+/// Searching from an item ranks that item's members first.
+#[test]
+fn search_context_boosts_members() {
+    use crate::search::SearchContext;
+
+    let nav = test_navigator();
+    let context = resolve(&nav, "crate::link_resolution_tests::LinkTestStruct");
+    let results = nav
+        .search("new", &["fixture-crate"], Some(SearchContext::new(context)))
+        .unwrap_or_else(|_| panic!("fixture crate should be searchable"));
+
+    let top = results.first().expect("results for `new`");
+    let (_, path) = nav
+        .get_item_from_id_path(top.crate_name, &top.id_path)
+        .unwrap();
+    assert_eq!(
+        path.join("::"),
+        "fixture-crate::link_resolution_tests::LinkTestStruct::new"
+    );
+}
+
+/// Bare prelude names resolve through std, following the fixture crate's 2021 prelude.
+#[test]
+fn prelude_names_resolve_without_crate_prefix() {
+    let nav = test_navigator();
+    assert_eq!(
+        resolve(&nav, "Option::map").id,
+        resolve(&nav, "std::option::Option::map").id
+    );
+    assert_eq!(
+        resolve(&nav, "TryFrom").id,
+        resolve(&nav, "std::convert::TryFrom").id
+    );
+    assert!(nav.resolve_path("IntoFuture", &mut vec![]).is_none());
+}
+
+/// Bare primitive names resolve to std's primitive docs and their inherent items, and
+/// otherwise to std's module of the same name.
+#[test]
+fn primitives_resolve_without_crate_prefix() {
+    let nav = test_navigator();
+    let str_primitive = resolve(&nav, "str");
+    assert_eq!(str_primitive.kind(), ItemKind::Primitive);
+    assert_eq!(str_primitive.id, resolve(&nav, "std::prim@str").id);
+
+    let trim = resolve(&nav, "str::trim");
+    assert_eq!(trim.name(), Some("trim"));
+    assert_eq!(trim.kind(), ItemKind::Function);
+    assert_eq!(resolve(&nav, "u32::MAX").kind(), ItemKind::AssocConst);
+    assert_eq!(resolve(&nav, "slice::iter").name(), Some("iter"));
+
+    assert_eq!(
+        resolve(&nav, "str::from_utf8").id,
+        resolve(&nav, "std::str::from_utf8").id
+    );
+}
+
+/// Keyword docs resolve through a `keyword:` prefix, and a bare name that isn't a crate falls
+/// back to them.
+#[test]
+fn keywords_resolve_to_std_keyword_docs() {
+    let nav = test_navigator();
+    let match_keyword = resolve(&nav, "keyword:match");
+    assert_eq!(match_keyword.kind(), ItemKind::Keyword);
+    assert_eq!(match_keyword.name(), Some("match"));
+    assert_eq!(resolve(&nav, "match").id, match_keyword.id);
+    assert_eq!(
+        resolve(&nav, "std::keyword@async").kind(),
+        ItemKind::Keyword
+    );
+    assert!(
+        nav.resolve_path("keyword:not_a_keyword", &mut vec![])
+            .is_none()
+    );
+}
+
+#[test]
+fn crate_specifiers_pin_full_versions_exactly() {
+    use crate::navigator::parse_crate_specifier;
+    use semver::{Version, VersionReq};
+
+    let (name, version_req) = parse_crate_specifier("serde@1.0.100");
+    assert_eq!(name, "serde");
+    assert!(version_req.matches(&Version::new(1, 0, 100)));
+    assert!(!version_req.matches(&Version::new(1, 0, 200)));
+
+    let (_, version_req) = parse_crate_specifier("tokio@1");
+    assert!(version_req.matches(&Version::new(1, 40, 0)));
+    assert_eq!(parse_crate_specifier("serde"), ("serde", VersionReq::STAR));
+
+    let nav = test_navigator();
+    assert_eq!(
+        resolve(&nav, "fixture-crate@0.1.0::TestStruct").id,
+        resolve(&nav, "crate::TestStruct").id
+    );
+    assert!(
+        nav.resolve_path("fixture-crate@0.2.0::TestStruct", &mut vec![])
+            .is_none()
+    );
+}
+
+#[test]
+fn prefetch_loads_crates_into_working_set() {
+    use semver::VersionReq;
+
+    let nav = test_navigator();
+    let key = crate::CrateName::from("fixture-crate@0.1.0");
+    assert!(nav.working_set.get(&key).is_none());
+
+    nav.prefetch(&["fixture-crate", "fixture-crate"]);
+    assert!(nav.working_set.get(&key).is_some_and(|data| data.is_some()));
+    assert!(
+        nav.working_set
+            .get(&nav.working_set_key("fixture-crate", &VersionReq::STAR))
+            .is_none()
+    );
+}
+
+#[test]
+fn complete_path_completes_the_last_segment() {
+    let nav = test_navigator();
+
+    let completions = nav.complete_path("crate::Test");
+    assert!(completions.contains(&"crate::TestStruct".to_string()));
+    assert!(completions.contains(&"crate::TestTrait".to_string()));
+    assert!(
+        completions
+            .iter()
+            .all(|path| path.starts_with("crate::Test"))
+    );
+
+    assert_eq!(
+        nav.complete_path("crate::submodule::sub_f"),
+        ["crate::submodule::sub_function"]
+    );
+    assert!(
+        nav.complete_path("fixture")
+            .contains(&"fixture-crate".to_string())
+    );
+}
+
+#[test]
+fn members_are_fields_and_trait_items() {
+    let nav = test_navigator();
+    let item = resolve(&nav, "crate::TestStruct");
+    let fields = item
+        .members()
+        .filter_map(|member| member.name())
+        .collect::<Vec<_>>();
+    assert_eq!(fields, ["field", "count"]);
+    let count = item
+        .members()
+        .find(|member| member.name() == Some("count"))
+        .unwrap();
+    assert!(
+        count
+            .discriminated_path()
+            .unwrap()
+            .ends_with("::field@count")
+    );
+
+    let item = resolve(&nav, "crate::TestTrait");
+    let kinds = item
+        .members()
+        .map(|member| member.kind())
+        .collect::<Vec<_>>();
+    assert!(kinds.contains(&ItemKind::AssocConst));
+    assert!(kinds.contains(&ItemKind::AssocType));
+    assert!(kinds.contains(&ItemKind::Function));
+
+    assert_eq!(resolve(&nav, "crate::submodule").members().count(), 0);
+}
+
+/// The best suggestion for a path that doesn't resolve, with its score
+fn best_suggestion<'a>(nav: &'a Navigator, path: &str) -> crate::Suggestion<'a> {
+    let mut suggestions = vec![];
+    assert!(nav.resolve_path(path, &mut suggestions).is_none());
+    suggestions
+        .into_iter()
+        .max_by(|a, b| a.score().total_cmp(&b.score()))
+        .unwrap_or_else(|| panic!("no suggestions for {path:?}"))
+}
+
+#[test]
+fn suggestions_weigh_kind_and_location() {
+    let nav = test_navigator();
+
+    // The function outranks the module of the same name when `fn@` was typed
+    let suggestion = best_suggestion(&nav, "crate::namespace_collisions::fn@bth");
+    assert_eq!(suggestion.path(), "crate::namespace_collisions::both");
+    assert_eq!(suggestion.item().unwrap().kind(), ItemKind::Function);
+
+    // An item in another module is found through the crate's path index
+    let suggestion = best_suggestion(&nav, "crate::markdown_test::SubStruct");
+    assert_eq!(suggestion.path(), "crate::submodule::SubStruct");
+    assert!(suggestion.score() < 1.0);
+    assert_eq!(
+        suggestion.summary().as_deref(),
+        Some("A struct in a submodule")
+    );
+}
+
+#[test]
+fn segments_tolerate_near_misses_unless_exact() {
+    use crate::{ResolutionStep, ResolutionTrace, SegmentOutcome};
+
+    let nav = test_navigator();
+    let test_struct = resolve(&nav, "crate::TestStruct");
+
+    assert_eq!(resolve(&nav, "crate::teststruct").id, test_struct.id);
+    assert_eq!(resolve(&nav, "crate::TestStrcut").id, test_struct.id);
+    assert_eq!(
+        resolve(&nav, "crate::submodule::substruct::get_vlaue").name(),
+        Some("get_value")
+    );
+    assert_eq!(resolve(&nav, "crate::TestStrct").id, test_struct.id);
+    // Another case convention is a different name
+    assert!(
+        nav.resolve_path("crate::TestStruct::getfield", &mut vec![])
+            .is_none()
+    );
+    assert!(
+        nav.resolve_path("crate::TestStruct::incrementCount", &mut vec![])
+            .is_none()
+    );
+
+    let mut trace = ResolutionTrace::new();
+    nav.resolve_path_traced("crate::teststruct", &mut vec![], &mut trace);
+    assert!(trace.steps().iter().any(|step| matches!(
+        step,
+        ResolutionStep::Segment {
+            outcome: SegmentOutcome::MatchedNearMiss { name, .. },
+            ..
+        } if name == "TestStruct"
+    )));
+
+    // Paths written in docs and code are taken as written
+    assert_eq!(nav.find_path("crate::TestStruct"), Some(test_struct));
+    assert!(nav.find_path("crate::teststruct").is_none());
+
+    let nav = test_navigator().with_exact_paths(true);
+    assert!(nav.resolve_path("crate::teststruct", &mut vec![]).is_none());
+}
+
+#[test]
+fn methods_resolve_through_trait_impls() {
+    use crate::{ResolutionStep, ResolutionTrace, SegmentOutcome};
+
+    let nav = test_navigator();
+    let clone = resolve(&nav, "crate::TestStruct::clone");
+    assert_eq!(clone.name(), Some("clone"));
+    assert_eq!(clone.kind(), ItemKind::Function);
+
+    let test_struct = resolve(&nav, "crate::TestStruct");
+    let method_match = test_struct.find_method("clone").unwrap();
+    assert_eq!(method_match.trait_name().as_deref(), Some("Clone"));
+    assert!(method_match.derefs().is_empty());
+
+    let mut trace = ResolutionTrace::new();
+    nav.resolve_path_traced("crate::TestStruct::clone", &mut vec![], &mut trace);
+    assert!(trace.steps().iter().any(|step| matches!(
+        step,
+        ResolutionStep::Segment {
+            outcome: SegmentOutcome::MatchedMethod { trait_name: Some(trait_name), .. },
+            ..
+        } if trait_name == "Clone"
+    )));
+
+    assert!(test_struct.find_method("no_such_method").is_none());
+}
+
+#[test]
+fn methods_resolve_through_deref() {
+    use crate::{ResolutionStep, ResolutionTrace, SegmentOutcome};
+
+    let nav = test_navigator();
+    let len = resolve(&nav, "String::len");
+    assert_eq!(len.id, resolve(&nav, "str::len").id);
+
+    let string = resolve(&nav, "String");
+    let method_match = string.find_method("len").unwrap();
+    assert!(method_match.trait_name().is_none());
+    assert_eq!(
+        method_match
+            .derefs()
+            .iter()
+            .map(|target| target.name())
+            .collect::<Vec<_>>(),
+        [Some("str")]
+    );
+
+    let mut trace = ResolutionTrace::new();
+    nav.resolve_path_traced("String::len", &mut vec![], &mut trace);
+    assert!(trace.steps().iter().any(|step| matches!(
+        step,
+        ResolutionStep::Segment {
+            outcome: SegmentOutcome::MatchedMethod { derefs, trait_name: None, .. },
+            ..
+        } if derefs == &["str"]
+    )));
+}
+
+/// A navigator that finds the fixture crate's docs as two versions of a crate named `tiny`,
+/// 0.1.0 and 0.2.0, as if they had been built from registry sources into `dir`
+fn two_version_navigator(dir: &std::path::Path) -> Navigator {
+    use crate::sources::VendoredSource;
+
+    let fixture_json = test_navigator()
+        .load_crate("crate", &semver::VersionReq::STAR)
+        .expect("fixture crate docs")
+        .fs_path()
+        .to_path_buf();
+    for version in ["0.1.0", "0.2.0"] {
+        let source_dir = dir
+            .join("src")
+            .join("index")
+            .join(format!("tiny-{version}"));
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::write(source_dir.join("Cargo.toml"), "").unwrap();
+
+        let cache_dir = dir.join("cache").join("tiny");
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        std::fs::copy(&fixture_json, cache_dir.join(format!("{version}.json"))).unwrap();
+    }
+
+    Navigator::default().with_vendored_source(Some(VendoredSource::new(
+        dir.join("src"),
+        dir.join("cache"),
+    )))
+}
+
+#[test]
+fn paths_into_an_older_version_carry_it() {
+    let dir = std::env::temp_dir().join(format!("ferritin-versioned-paths-{}", std::process::id()));
+    let nav = two_version_navigator(&dir);
+
+    let old = resolve(&nav, "tiny@0.1.0::TestStruct");
+    assert_eq!(old.link_path().as_deref(), Some("tiny@0.1.0::TestStruct"));
+    assert_eq!(
+        old.discriminated_path().as_deref(),
+        Some("tiny@0.1.0::struct@TestStruct")
+    );
+    let followed = resolve(&nav, &old.link_path().unwrap());
+    assert_eq!(followed.crate_docs().version(), old.crate_docs().version());
+
+    // The version the name alone loads needs no version in its paths
+    let latest = resolve(&nav, "tiny@0.2.0::TestStruct");
+    assert_eq!(
+        latest.discriminated_path().as_deref(),
+        Some("tiny::struct@TestStruct")
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn each_version_of_a_crate_is_loaded_once() {
+    use semver::{Version, VersionReq};
+
+    let dir = std::env::temp_dir().join(format!("ferritin-two-versions-{}", std::process::id()));
+    let nav = two_version_navigator(&dir);
+
+    let old = nav
+        .load_crate("tiny", &VersionReq::parse("=0.1.0").unwrap())
+        .unwrap();
+    let new = nav
+        .load_crate("tiny", &VersionReq::parse("=0.2.0").unwrap())
+        .unwrap();
+    assert_eq!(old.version(), Some(&Version::new(0, 1, 0)));
+    assert_eq!(new.version(), Some(&Version::new(0, 2, 0)));
+
+    // The name alone loads the newest version, which is already open
+    let latest = nav.load_crate("tiny", &VersionReq::STAR).unwrap();
+    assert!(std::ptr::eq(latest, new));
+
+    for key in ["tiny@0.1.0", "tiny@0.2.0"] {
+        assert!(
+            nav.working_set.get(key).is_some(),
+            "{key} is in the working set"
+        );
+    }
+    assert!(nav.working_set.get("tiny").is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parsed_docs_read_back_from_the_cache_match_the_json() {
+    use crate::parsed_cache::{CACHE_EXTENSION, load_or_parse};
+
+    let fixture_json = test_navigator()
+        .load_crate("crate", &semver::VersionReq::STAR)
+        .expect("fixture crate docs")
+        .fs_path()
+        .to_path_buf();
+    let dir = std::env::temp_dir().join(format!("ferritin-parsed-cache-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let json_path = dir.join("fixture_crate.json");
+    std::fs::copy(&fixture_json, &json_path).unwrap();
+    let json = std::fs::read(&json_path).unwrap();
+
+    let parsed = load_or_parse(&json_path, json.clone(), None).unwrap();
+    assert!(parsed.lazy_items.is_none());
+    assert!(!parsed.path_to_id.is_empty());
+
+    // The cache is a 16 byte header and then the bincode of the crate and its path index
+    let cache = std::fs::read(json_path.with_extension(CACHE_EXTENSION)).unwrap();
+    let (crate_data, path_to_id): (rustdoc_types::Crate, HashMap<String, rustdoc_types::Id>) =
+        bincode::deserialize(&cache[16..]).unwrap();
+    assert_eq!(crate_data, parsed.crate_data);
+    assert_eq!(path_to_id, parsed.path_to_id);
+
+    // A second load reads the same docs back from the cache
+    let cached = load_or_parse(&json_path, json, None).unwrap();
+    assert_eq!(cached.crate_data, parsed.crate_data);
+    assert_eq!(cached.path_to_id, parsed.path_to_id);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
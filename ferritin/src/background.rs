@@ -0,0 +1,142 @@
+//! Whether the terminal has a light or a dark background
+//!
+//! The terminal is asked for its background color with an OSC 11 query, which most terminals
+//! answer. Terminals that don't answer within a moment are left alone, and `COLORFGBG`, set by
+//! rxvt and Konsole among others, is used instead.
+
+use std::env;
+
+/// How long to wait for the terminal to answer
+#[cfg(unix)]
+const REPLY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Background {
+    Light,
+    Dark,
+}
+
+/// The terminal's background, if it can be found out
+pub(crate) fn detect() -> Option<Background> {
+    query_background_color().or_else(|| {
+        let colorfgbg = env::var("COLORFGBG").ok()?;
+        from_colorfgbg(&colorfgbg)
+    })
+}
+
+/// `COLORFGBG` is `foreground;background` in terminal color numbers, where 7 and 9 to 15 are
+/// light colors
+fn from_colorfgbg(colorfgbg: &str) -> Option<Background> {
+    let background = colorfgbg.rsplit(';').next()?.parse::<u8>().ok()?;
+    Some(match background {
+        7 | 9..=15 => Background::Light,
+        _ => Background::Dark,
+    })
+}
+
+/// Read a reply like `ESC ] 11 ; rgb:ffff/ffff/ffff BEL`, with one to four hex digits for each
+/// channel
+fn from_osc_11_reply(reply: &str) -> Option<Background> {
+    let rgb = reply.split_once("rgb:")?.1;
+    let rgb = rgb
+        .trim_end_matches(['\x07', '\\'])
+        .trim_end_matches('\x1b');
+
+    let mut channels = rgb.split('/').map(|channel| {
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        let max = 16u32.checked_pow(u32::try_from(channel.len()).ok()?)? - 1;
+        Some(f64::from(value) / f64::from(max))
+    });
+    let (red, green, blue) = (channels.next()??, channels.next()??, channels.next()??);
+
+    let luminance = 0.2126 * red + 0.7152 * green + 0.0722 * blue;
+    Some(if luminance > 0.5 {
+        Background::Light
+    } else {
+        Background::Dark
+    })
+}
+
+#[cfg(unix)]
+fn query_background_color() -> Option<Background> {
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+    use std::fs::OpenOptions;
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    // The reply isn't echoed or held back until a newline in raw mode
+    enable_raw_mode().ok()?;
+    let reply = read_reply(&mut tty);
+    let _ = disable_raw_mode();
+
+    from_osc_11_reply(&reply?)
+}
+
+#[cfg(unix)]
+fn read_reply(tty: &mut std::fs::File) -> Option<String> {
+    use rustix::event::{PollFd, PollFlags, Timespec, poll};
+    use std::io::{Read, Write};
+    use std::time::Instant;
+
+    tty.write_all(b"\x1b]11;?\x1b\\").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + REPLY_TIMEOUT;
+    let mut reply = Vec::new();
+    loop {
+        let remaining = deadline.checked_duration_since(Instant::now())?;
+        let timeout = Timespec {
+            tv_sec: 0,
+            tv_nsec: remaining.subsec_nanos().into(),
+        };
+        let ready = poll(&mut [PollFd::new(&*tty, PollFlags::IN)], Some(&timeout)).ok()?;
+        if ready == 0 {
+            return None;
+        }
+
+        let mut buffer = [0; 64];
+        let read = tty.read(&mut buffer).ok()?;
+        if read == 0 {
+            return None;
+        }
+        reply.extend_from_slice(&buffer[..read]);
+        if reply.ends_with(b"\x07") || reply.ends_with(b"\x1b\\") {
+            return String::from_utf8(reply).ok();
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn query_background_color() -> Option<Background> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_background_from_replies() {
+        assert_eq!(
+            from_osc_11_reply("\x1b]11;rgb:ffff/ffff/ffff\x07"),
+            Some(Background::Light)
+        );
+        assert_eq!(
+            from_osc_11_reply("\x1b]11;rgb:1e1e/1e1e/2e2e\x1b\\"),
+            Some(Background::Dark)
+        );
+        assert_eq!(
+            from_osc_11_reply("\x1b]11;rgb:ee/ee/ee\x07"),
+            Some(Background::Light)
+        );
+        assert_eq!(from_osc_11_reply("\x1b[?1;2c"), None);
+
+        assert_eq!(from_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(from_colorfgbg("15;default;0"), Some(Background::Dark));
+        assert_eq!(from_colorfgbg("default"), None);
+    }
+}
@@ -0,0 +1,93 @@
+//! Batch mode for `get`, for scripts and pipelines
+//!
+//! `ferritin get --batch` reads one item path per line on stdin and writes the docs of each
+//! as it goes, from docs loaded once, so a script looking up many items pays the startup cost
+//! of loading them only once. Documents are written with a separator line between them, or
+//! with `--json` as one JSON object per line, holding the path, the document as plain text and
+//! its tree of nodes the way `ferritin rpc` answers `get`, and an error object with the closest
+//! paths for a path that doesn't resolve.
+
+use crate::commands::Commands;
+use crate::commands::get::{self, Failure};
+use crate::render_context::RenderContext;
+use crate::renderer::{OutputMode, render};
+use crate::request::Request;
+use crate::rpc::document_json;
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+/// Write the docs for each path read from `input` on `output` until the input ends, with the
+/// options of `command`. Returns whether every path was found.
+pub(crate) fn serve(
+    request: &Request,
+    command: &Commands,
+    render_context: &RenderContext,
+    separator: &str,
+    json: bool,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<bool> {
+    let mut all_found = true;
+    let mut first = true;
+    for line in input.lines() {
+        let line = line?;
+        let path = line.trim();
+        if path.is_empty() {
+            continue;
+        }
+
+        let command = command.clone().with_path(path);
+        if json {
+            let (value, failure) = json_document(request, command, path);
+            all_found &= failure.is_none();
+            serde_json::to_writer(&mut output, &value)?;
+            output.write_all(b"\n")?;
+        } else {
+            let (document, is_error, _) = command.execute(request);
+            all_found &= !is_error;
+            let mut text = String::new();
+            if render(&document, render_context, &mut text).is_err() {
+                text.clear();
+            }
+            if !first {
+                writeln!(output, "{separator}")?;
+            }
+            output.write_all(text.as_bytes())?;
+            if !text.is_empty() && !text.ends_with('\n') {
+                output.write_all(b"\n")?;
+            }
+        }
+        // Each document goes out whole before the next path is read, for a reader that
+        // answers them one at a time
+        output.flush()?;
+        first = false;
+    }
+
+    Ok(all_found)
+}
+
+/// The docs `command` shows for `path` as a JSON object, with an `error` object if the path
+/// doesn't resolve, and why it doesn't
+pub(crate) fn json_document(
+    request: &Request,
+    command: Commands,
+    path: &str,
+) -> (Value, Option<Failure>) {
+    // Like the rpc answers, JSON documents carry text for the reader to wrap
+    let render_context = RenderContext::new()
+        .with_output_mode(OutputMode::Plain)
+        .with_wrap(false);
+
+    let (document, is_error, _) = command.execute(request);
+    let mut value = document_json(&document, is_error, &render_context);
+    value["path"] = path.into();
+    if !is_error {
+        return (value, None);
+    }
+
+    let failure = get::failure_json(request, path).map(|(failure, error)| {
+        value["error"] = error;
+        failure
+    });
+    (value, failure.or(Some(Failure::NotFound)))
+}
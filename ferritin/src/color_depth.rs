@@ -0,0 +1,314 @@
+//! How many colors the terminal can show
+//!
+//! Themes are truecolor, which not every terminal supports. `COLORTERM=truecolor` (or
+//! `24bit`) and a `TERM` ending in `-direct` mean full color, a `TERM` naming `256color` means
+//! the xterm 256-color palette, and any other `TERM` gets the 16 standard colors. Colors a
+//! terminal can't show are replaced by the nearest ones it can.
+//!
+//! `--color=never`, or `NO_COLOR` without `--color=always`, turns colors off, leaving bold,
+//! italics and underlines.
+
+use clap::ValueEnum;
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use std::env;
+
+/// The `--color` flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ColorChoice {
+    /// Color on a terminal, unless `NO_COLOR` is set
+    #[default]
+    Auto,
+    /// Color even when writing to a file or pipe
+    Always,
+    /// No color
+    Never,
+}
+
+impl ColorChoice {
+    /// The colors to render with
+    pub(crate) fn color_depth(self) -> ColorDepth {
+        match self {
+            Self::Never => ColorDepth::Monochrome,
+            Self::Always => ColorDepth::detect().max(ColorDepth::Ansi16),
+            Self::Auto if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) => {
+                ColorDepth::Monochrome
+            }
+            Self::Auto => ColorDepth::detect(),
+        }
+    }
+}
+
+/// The colors a terminal can show, from fewest to most
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub(crate) enum ColorDepth {
+    /// No colors at all
+    Monochrome,
+    /// The 16 standard colors
+    Ansi16,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// Any RGB color
+    #[default]
+    TrueColor,
+}
+
+/// The 16 standard colors, in palette order
+const ANSI_16: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// The RGB values xterm uses for the 16 standard colors
+const ANSI_16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The channel levels of the 6×6×6 color cube in the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// The terminal's colors, going by `COLORTERM` and `TERM`
+    pub(crate) fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").ok();
+        Self::from_env(&colorterm, term.as_deref())
+    }
+
+    fn from_env(colorterm: &str, term: Option<&str>) -> Self {
+        if matches!(colorterm, "truecolor" | "24bit") {
+            return Self::TrueColor;
+        }
+        match term {
+            // Windows terminals don't set TERM, and have long supported truecolor
+            None => Self::TrueColor,
+            Some(term) if term.ends_with("-direct") => Self::TrueColor,
+            Some(term) if term.contains("256color") => Self::Ansi256,
+            Some("dumb") => Self::Monochrome,
+            Some(_) => Self::Ansi16,
+        }
+    }
+
+    /// The nearest color to `color` that this terminal can show
+    pub(crate) fn convert(self, color: Color) -> Color {
+        match (self, color) {
+            (Self::Monochrome, _) => Color::Reset,
+            (Self::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256(r, g, b)),
+            (Self::Ansi16, Color::Rgb(r, g, b)) => ANSI_16[usize::from(nearest_16(r, g, b))],
+            (Self::Ansi16, Color::Indexed(index)) => {
+                let (r, g, b) = indexed_rgb(index);
+                ANSI_16[usize::from(nearest_16(r, g, b))]
+            }
+            _ => color,
+        }
+    }
+
+    /// The SGR parameters that set the foreground to an RGB color, if there's any color to set
+    pub(crate) fn foreground_sgr(self, r: u8, g: u8, b: u8) -> Option<String> {
+        match self {
+            Self::Monochrome => None,
+            Self::Ansi16 => {
+                let index = nearest_16(r, g, b);
+                Some(if index < 8 {
+                    format!("{}", 30 + index)
+                } else {
+                    format!("{}", 90 + index - 8)
+                })
+            }
+            Self::Ansi256 => Some(format!("38;5;{}", nearest_256(r, g, b))),
+            Self::TrueColor => Some(format!("38;2;{r};{g};{b}")),
+        }
+    }
+
+    /// Replace the colors in a rendered frame with ones this terminal can show
+    pub(crate) fn downsample(self, buffer: &mut Buffer) {
+        if self == Self::TrueColor {
+            return;
+        }
+        for cell in &mut buffer.content {
+            cell.fg = self.convert(cell.fg);
+            cell.bg = self.convert(cell.bg);
+        }
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+    channel(r1, r2) + channel(g1, g2) + channel(b1, b2)
+}
+
+/// The index of the closest of the 16 standard colors
+///
+/// Their exact RGB values differ between terminals, so rather than measuring distances to them,
+/// a color picks the standard color of its hue, in the bright variant if it's light. Colors
+/// with little saturation become black, white or one of the two grays.
+fn nearest_16(r: u8, g: u8, b: u8) -> u8 {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+
+    if chroma == 0 || chroma < max / 4 {
+        let lightness = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+        return match lightness {
+            0..48 => 0,
+            48..128 => 8,
+            128..208 => 7,
+            _ => 15,
+        };
+    }
+
+    let (red, green, blue) = (f32::from(r), f32::from(g), f32::from(b));
+    let chroma = f32::from(chroma);
+    let hue = if max == r {
+        60.0 * ((green - blue) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((blue - red) / chroma + 2.0)
+    } else {
+        60.0 * ((red - green) / chroma + 4.0)
+    };
+
+    // Palette indices 1 to 6 are red, green, yellow, blue, magenta and cyan
+    let index = match hue as u16 {
+        30..90 => 3,
+        90..150 => 2,
+        150..210 => 6,
+        210..270 => 4,
+        270..330 => 5,
+        _ => 1,
+    };
+    if max >= 192 { index + 8 } else { index }
+}
+
+/// The index of the closest color in the cube or the gray ramp of the 256-color palette
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |channel: u8| {
+        (0..6u8)
+            .min_by_key(|&level| CUBE_LEVELS[usize::from(level)].abs_diff(channel))
+            .unwrap_or(0)
+    };
+    let (cube_r, cube_g, cube_b) = (level(r), level(g), level(b));
+    let cube_index = 16 + 36 * cube_r + 6 * cube_g + cube_b;
+
+    // The gray ramp runs from 8 to 238 in steps of 10
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_index = 232 + gray_step;
+
+    if distance((r, g, b), indexed_rgb(gray_index)) < distance((r, g, b), indexed_rgb(cube_index)) {
+        gray_index
+    } else {
+        cube_index
+    }
+}
+
+/// The RGB value xterm shows for a 256-color palette index
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..16 => ANSI_16_RGB[usize::from(index)],
+        16..232 => {
+            let index = index - 16;
+            (
+                CUBE_LEVELS[usize::from(index / 36)],
+                CUBE_LEVELS[usize::from(index / 6 % 6)],
+                CUBE_LEVELS[usize::from(index % 6)],
+            )
+        }
+        232.. => {
+            let gray = 8 + 10 * (index - 232);
+            (gray, gray, gray)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_color_depth_from_the_environment() {
+        assert_eq!(
+            ColorDepth::from_env("truecolor", Some("xterm")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env("", Some("xterm-direct")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env("", Some("tmux-256color")),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(ColorDepth::from_env("", Some("xterm")), ColorDepth::Ansi16);
+        assert_eq!(
+            ColorDepth::from_env("", Some("dumb")),
+            ColorDepth::Monochrome
+        );
+    }
+
+    #[test]
+    fn converts_to_the_nearest_color() {
+        let orange = Color::Rgb(239, 159, 118);
+        assert_eq!(ColorDepth::TrueColor.convert(orange), orange);
+        assert_eq!(ColorDepth::Ansi256.convert(orange), Color::Indexed(216));
+        assert_eq!(ColorDepth::Ansi16.convert(orange), Color::LightRed);
+        assert_eq!(ColorDepth::Monochrome.convert(orange), Color::Reset);
+
+        // Near-grays land on the gray ramp rather than the coarser cube
+        assert_eq!(
+            ColorDepth::Ansi256.convert(Color::Rgb(48, 52, 70)),
+            Color::Indexed(237)
+        );
+        assert_eq!(ColorDepth::Ansi16.convert(Color::DarkGray), Color::DarkGray);
+
+        assert_eq!(
+            ColorDepth::Ansi16.foreground_sgr(0, 0, 0).as_deref(),
+            Some("30")
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.foreground_sgr(255, 255, 255).as_deref(),
+            Some("97")
+        );
+        assert_eq!(ColorDepth::Monochrome.foreground_sgr(1, 2, 3), None);
+    }
+}
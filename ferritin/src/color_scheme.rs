@@ -73,6 +73,38 @@ impl ColorScheme {
                 default_style.foreground,
             ),
         );
+        colors.insert(
+            SpanStyle::MacroName,
+            Self::color_for_scope_with_fallback(
+                &highlighter,
+                &[
+                    "entity.name.function.macro",
+                    "support.macro",
+                    "entity.name.function",
+                ],
+                default_style.foreground,
+            ),
+        );
+        colors.insert(
+            SpanStyle::ConstName,
+            Self::color_for_scope_with_fallback(
+                &highlighter,
+                &["constant.other", "variable.other.constant", "constant"],
+                default_style.foreground,
+            ),
+        );
+        colors.insert(
+            SpanStyle::ModuleName,
+            Self::color_for_scope_with_fallback(
+                &highlighter,
+                &[
+                    "entity.name.namespace",
+                    "entity.name.module",
+                    "entity.name.type",
+                ],
+                default_style.foreground,
+            ),
+        );
         colors.insert(
             SpanStyle::Operator,
             Self::color_for_scope_with_fallback(
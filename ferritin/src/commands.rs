@@ -1,26 +1,110 @@
+use crate::completions::Shell;
+use crate::format_context::FormatContext;
 use crate::renderer::HistoryEntry;
 use crate::request::Request;
-use crate::styled_string::Document;
+use crate::styled_string::{Document, DocumentNode, Span};
+use crate::verbosity::Verbosity;
+use cache::CacheCommand;
+use deps::DepsFormat;
+use list::{ListFilter, ListOptions, ListSort};
+use rustdoc_types::ItemKind;
+use search::SearchMode;
+use semver::Version;
 use std::fmt::Display;
+use std::path::PathBuf;
 
-mod get;
+pub(crate) mod api_diff;
+mod bundle_deps;
+mod cache;
+mod cli;
+mod deps;
+pub(crate) mod get;
+pub(crate) mod home;
 pub(crate) mod list;
+mod outdated_docs;
+pub(crate) mod resolve;
+pub(crate) mod run_example;
 pub(crate) mod search;
+mod toolchains;
+mod tree;
 
-#[derive(clap::Subcommand, Debug)]
+#[derive(clap::Subcommand, Debug, Clone)]
 pub(crate) enum Commands {
     /// Show documentation for an item
     Get {
         /// Path to the item (e.g., "std::vec::Vec" or "serde::Serialize")
-        path: String,
+        #[arg(required_unless_present = "batch")]
+        path: Option<String>,
 
-        /// Show source code
+        /// Show source code, numbered, with the file path and line to open it at
         #[arg(short, long)]
         source: bool,
 
-        /// Recursively show nested items
+        /// Recursively show nested items, as an outline of names and one-line summaries
         #[arg(short, long)]
         recursive: bool,
+
+        /// How many levels of nested items to show (implies --recursive)
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+
+        /// Only list items of these kinds, like `fn,struct`
+        #[arg(long, value_delimiter = ',', value_parser = parse_item_kind)]
+        kinds: Vec<ItemKind>,
+
+        /// List private items too, where the docs have them (as they do for binaries)
+        #[arg(long, overrides_with = "public_only")]
+        all: bool,
+
+        /// List only public items (the default)
+        #[arg(long, overrides_with = "all")]
+        public_only: bool,
+
+        /// Show only signatures: function headers, fields, variants and trait items, with no
+        /// docs
+        #[arg(long, conflicts_with = "source")]
+        signature: bool,
+
+        /// If the path cannot be found, show each resolution step that was attempted
+        #[arg(long)]
+        explain_resolution: bool,
+
+        /// Read item paths from stdin, one per line, and show the docs of each, loading the
+        /// docs only once
+        #[arg(long, conflicts_with = "path")]
+        batch: bool,
+
+        /// Line written between the documents of a batch
+        #[arg(long, requires = "batch", default_value = "---")]
+        separator: String,
+
+        /// Write the docs as a line of JSON, with the path, plain text and tree of nodes, or
+        /// an error object with the closest paths if the path doesn't resolve. A batch is
+        /// written one line per path.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Resolve a path without rendering its docs: print the item's discriminated path, kind,
+    /// crate and source location, or the closest paths and their scores if it doesn't resolve
+    Resolve {
+        /// Path to resolve (e.g., "std::vec::Vec")
+        path: String,
+
+        /// Print the resolution as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the public modules and items of a crate as a tree, with how many items of each
+    /// kind every module holds
+    Tree {
+        /// Crate or module to show (e.g., "serde" or "std::collections")
+        path: String,
+
+        /// Show only modules, still with the counts of what's in them
+        #[arg(long)]
+        modules_only: bool,
     },
 
     /// Search for items by name or documentation
@@ -35,18 +119,150 @@ pub(crate) enum Commands {
         /// Maximum number of results
         #[arg(short, long, default_value = "10")]
         limit: usize,
+
+        /// Match the query as a regular expression against item names and paths
+        #[arg(long, conflicts_with = "glob")]
+        regex: bool,
+
+        /// Match the query as a glob (`*` and `?`) against item names and paths
+        #[arg(long)]
+        glob: bool,
     },
 
     /// List available crates
-    List,
+    List {
+        /// Only show dependencies added, removed or updated since the last `list --changed`
+        #[arg(long, conflicts_with_all = ["filter", "sort", "json"])]
+        changed: bool,
+
+        /// Only list dev-dependencies, workspace crates, crates with docs from docs.rs, or the
+        /// standard library
+        #[arg(long, value_enum)]
+        filter: Option<ListFilter>,
+
+        /// Order crates by name, by version, or by the size of their docs, largest first
+        #[arg(long, value_enum, default_value_t)]
+        sort: ListSort,
+
+        /// Print the crates as a JSON array, with their versions, docs and sizes
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show the public items added and removed in a dependency since an earlier version
+    ApiDiff {
+        /// Crate to compare
+        #[arg(value_name = "CRATE")]
+        crate_: String,
+
+        /// Version to compare against (defaults to the version `list --changed` last saw it
+        /// updated from)
+        #[arg(long)]
+        from: Option<Version>,
+    },
+
+    /// Show the workspace dependency graph as an indented tree, and where each crate's docs are
+    /// available from
+    Deps {
+        /// Print the graph in Graphviz DOT format instead
+        #[arg(long, conflicts_with = "why")]
+        dot: bool,
+
+        /// Explain which workspace crates depend on this crate, and through what
+        #[arg(long, value_name = "CRATE")]
+        why: Option<String>,
+    },
+
+    /// Write a Markdown overview of each dependency to a directory, for retrieval tools.
+    /// Dependencies whose version hasn't changed since the last run are skipped.
+    BundleDeps {
+        /// Only bundle the workspace's direct dependencies
+        #[arg(long)]
+        direct_only: bool,
+
+        /// Directory to write the Markdown files to
+        #[arg(long, default_value = "context")]
+        out: PathBuf,
+    },
+
+    /// List, clean, or garbage-collect docs downloaded from docs.rs
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+
+    /// Report cached and locally built docs that don't match the versions in Cargo.lock
+    OutdatedDocs {
+        /// Rebuild or re-download the outdated docs
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Compile and run a rust example from an item's documentation
+    RunExample {
+        /// Path to the item (e.g., "std::vec::Vec")
+        path: String,
+
+        /// Which example to run, counting rust code blocks from 1
+        #[arg(default_value = "1")]
+        index: usize,
+
+        /// Capture output and include it in the rendered document instead of streaming it
+        #[arg(long)]
+        capture: bool,
+    },
+
+    /// Show the command line reference of a workspace binary, synthesized from its clap
+    /// derive attributes and doc comments
+    Cli {
+        /// Binary target to document (defaults to the workspace's only binary)
+        bin: Option<String>,
+    },
+
+    /// List installed rustup toolchains and which of them have std docs for `--toolchain`
+    Toolchains,
+
+    /// Keep this project's docs loaded and answer one-shot `get`, `search` and `list` from
+    /// them, which skips the startup cost of loading docs on every run. Docs for workspace
+    /// crates are loaded once, so restart the daemon to see edits to them.
+    Daemon,
+
+    /// Answer JSON-RPC requests on stdin, one per line, for editor integrations. Methods are
+    /// resolve, get, search and impls.
+    Rpc,
+
+    /// Print a completion script that completes subcommands and item paths
+    Completions {
+        /// Shell to write the script for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Print the item paths that complete a partly typed one, one per line, for the
+    /// completion scripts
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// The path typed so far
+        #[arg(default_value = "")]
+        partial: String,
+    },
 }
 
 impl Commands {
     pub fn get(path: impl Display) -> Self {
         Self::Get {
-            path: path.to_string(),
+            path: Some(path.to_string()),
             source: false,
             recursive: false,
+            depth: None,
+            kinds: vec![],
+            all: false,
+            public_only: false,
+            signature: false,
+            explain_resolution: false,
+            batch: false,
+            separator: "---".to_string(),
+            json: false,
         }
     }
 
@@ -55,90 +271,352 @@ impl Commands {
             query: query.to_string(),
             limit: 10,
             crate_: None,
+            regex: false,
+            glob: false,
         }
     }
 
     pub fn list() -> Self {
-        Self::List
+        Self::List {
+            changed: false,
+            filter: None,
+            sort: ListSort::Name,
+            json: false,
+        }
     }
 
-    pub fn with_source(self) -> Self {
+    pub fn run_example(path: impl Display, index: usize) -> Self {
+        Self::RunExample {
+            path: path.to_string(),
+            index,
+            capture: false,
+        }
+    }
+
+    pub fn with_captured_output(self) -> Self {
         match self {
-            Self::Get {
-                path, recursive, ..
-            } => Self::Get {
+            Self::RunExample { path, index, .. } => Self::RunExample {
                 path,
-                source: true,
-                recursive,
+                index,
+                capture: true,
             },
             other => other,
         }
     }
 
+    pub fn with_source(mut self) -> Self {
+        if let Self::Get { source, .. } = &mut self {
+            *source = true;
+        }
+        self
+    }
+
     pub fn in_crate(self, crate_: impl Display) -> Self {
         match self {
-            Self::Search { query, limit, .. } => Self::Search {
+            Self::Search {
+                query,
+                limit,
+                regex,
+                glob,
+                ..
+            } => Self::Search {
                 query,
                 limit,
                 crate_: Some(crate_.to_string()),
+                regex,
+                glob,
             },
             other => other,
         }
     }
 
-    pub fn recursive(self) -> Self {
+    /// This `get` for another path, as one of a batch
+    pub fn with_path(mut self, item_path: impl Display) -> Self {
+        if let Self::Get { path, batch, .. } = &mut self {
+            *path = Some(item_path.to_string());
+            *batch = false;
+        }
+        self
+    }
+
+    pub fn recursive(mut self) -> Self {
+        if let Self::Get { recursive, .. } = &mut self {
+            *recursive = true;
+        }
+        self
+    }
+
+    /// This `get` as a recursive listing `max_depth` levels deep
+    pub fn with_depth(mut self, max_depth: usize) -> Self {
+        if let Self::Get { depth, .. } = &mut self {
+            *depth = Some(max_depth);
+        }
+        self
+    }
+
+    /// This `get` listing only items of `item_kinds`, and where they are
+    pub fn with_kinds(mut self, item_kinds: impl IntoIterator<Item = ItemKind>) -> Self {
+        if let Self::Get { kinds, .. } = &mut self {
+            *kinds = item_kinds.into_iter().collect();
+        }
+        self
+    }
+
+    /// This `get` showing only signatures
+    pub fn with_signature(mut self) -> Self {
+        if let Self::Get { signature, .. } = &mut self {
+            *signature = true;
+        }
+        self
+    }
+
+    /// Whether a daemon can run this command for a client. Commands that write files relative
+    /// to the client's directory or stream output as they go always run in-process.
+    pub fn is_served_by_daemon(&self) -> bool {
+        matches!(
+            self,
+            Self::Get {
+                batch: false,
+                json: false,
+                ..
+            } | Self::Search { .. }
+                | Self::List { changed: false, .. }
+        )
+    }
+
+    /// The exit code for this command having failed: `get` tells a path that isn't in its
+    /// crate from a crate that isn't available, and any other failure exits with 1
+    pub fn failure_exit_code(&self, request: &Request) -> u8 {
         match self {
-            Self::Get { path, source, .. } => Self::Get {
-                path,
-                source,
-                recursive: true,
-            },
-            other => other,
+            Self::Get {
+                path: Some(path), ..
+            } => get::failure(request, path).map_or(1, get::Failure::exit_code),
+            _ => 1,
         }
     }
 
     pub fn with_limit(self, limit: usize) -> Self {
         match self {
-            Self::Search { query, crate_, .. } => Self::Search {
+            Self::Search {
+                query,
+                crate_,
+                regex,
+                glob,
+                ..
+            } => Self::Search {
                 query,
                 limit,
                 crate_,
+                regex,
+                glob,
             },
             other => other,
         }
     }
 
+    /// Execute the command, passing the top-level nodes of its output to `emit` as they are
+    /// formatted, and return whether it failed
+    ///
+    /// Only `get` hands over its output a part at a time; other commands pass on their whole
+    /// document once it is done. The first error from `emit` stops the command.
+    pub fn execute_streamed<'a, E>(
+        self,
+        request: &'a Request,
+        emit: &mut impl FnMut(DocumentNode<'a>) -> Result<(), E>,
+    ) -> Result<bool, E> {
+        if let Self::Get {
+            path: Some(path),
+            explain_resolution,
+            ..
+        } = &self
+        {
+            let format_context = self.get_format_context(request.format_context());
+            let item = get::stream(request, path, &format_context, *explain_resolution, emit)?;
+            return Ok(item.is_none());
+        }
+
+        let (document, is_error, _) = self.execute(request);
+        document.nodes.into_iter().try_for_each(emit)?;
+        Ok(is_error)
+    }
+
+    /// `base`, the format context the command runs with, with a `get` command's flags applied
+    fn get_format_context(&self, base: &FormatContext) -> FormatContext {
+        let format_context = base.clone();
+        let Self::Get {
+            source,
+            recursive,
+            depth,
+            kinds,
+            all,
+            public_only,
+            signature,
+            ..
+        } = self
+        else {
+            return format_context;
+        };
+
+        let format_context = format_context
+            .with_include_source(*source)
+            .with_recursion(*recursive || depth.is_some())
+            .with_depth(*depth)
+            .with_kinds(kinds.clone())
+            .with_private_items(*all && !public_only);
+        if *signature {
+            format_context.with_verbosity(Verbosity::Signature)
+        } else {
+            format_context
+        }
+    }
+
     pub fn execute<'a>(
         self,
         request: &'a Request,
+    ) -> (Document<'a>, bool, Option<HistoryEntry<'a>>) {
+        self.execute_with(request, request.format_context())
+    }
+
+    /// Execute the command, formatting with `format_context` in place of the request's own
+    ///
+    /// The daemon formats each client's command for that client's terminal this way.
+    pub fn execute_with<'a>(
+        self,
+        request: &'a Request,
+        format_context: &FormatContext,
     ) -> (Document<'a>, bool, Option<HistoryEntry<'a>>) {
         match self {
             Commands::Get {
-                path,
-                source,
-                recursive,
+                ref path,
+                explain_resolution,
+                ..
             } => {
-                let (doc, is_error, item_ref) = get::execute(request, &path, source, recursive);
+                // A batch is read from stdin, which only one-shot mode does
+                let Some(path) = path else {
+                    return (
+                        Document::from(vec![DocumentNode::paragraph(vec![
+                            Span::inline_code("--batch"),
+                            Span::plain(" reads paths from stdin, outside of interactive mode."),
+                        ])]),
+                        true,
+                        None,
+                    );
+                };
+                let format_context = self.get_format_context(format_context);
+                let (doc, is_error, item_ref) =
+                    get::execute(request, path, &format_context, explain_resolution);
                 let history_entry = item_ref.map(HistoryEntry::Item);
                 (doc, is_error, history_entry)
             }
+            Commands::Resolve { path, json } => {
+                let (doc, is_error) = resolve::execute(request, &path, json);
+                (doc, is_error, None)
+            }
+            Commands::Tree { path, modules_only } => {
+                let (doc, is_error) = tree::execute(request, &path, modules_only);
+                (doc, is_error, None)
+            }
             Commands::Search {
                 query,
                 limit,
                 crate_,
+                regex,
+                glob,
             } => {
-                let (doc, is_error) = search::execute(request, &query, limit, crate_.as_deref());
+                let mode = if regex {
+                    SearchMode::Regex
+                } else if glob {
+                    SearchMode::Glob
+                } else {
+                    SearchMode::Terms
+                };
+                let (doc, is_error) =
+                    search::execute(request, &query, limit, crate_.as_deref(), mode, None);
                 let history_entry = Some(HistoryEntry::Search {
                     query,
                     crate_name: crate_,
+                    mode,
                 });
                 (doc, is_error, history_entry)
             }
-            Commands::List => {
-                let (doc, is_error, default_crate) = list::execute(request);
+            Commands::List {
+                changed: false,
+                filter,
+                sort,
+                json,
+            } => {
+                let options = ListOptions { filter, sort, json };
+                let (doc, is_error, default_crate) = list::execute(request, options);
                 let history_entry = Some(HistoryEntry::List { default_crate });
                 (doc, is_error, history_entry)
             }
+            Commands::List { changed: true, .. } => {
+                let (doc, is_error) = list::changed(request);
+                (doc, is_error, None)
+            }
+            Commands::ApiDiff { crate_, from } => {
+                let (doc, is_error) = api_diff::execute(request, &crate_, from.as_ref());
+                (doc, is_error, None)
+            }
+            Commands::Deps { dot, why } => {
+                let format = if dot {
+                    DepsFormat::Dot
+                } else {
+                    DepsFormat::Tree
+                };
+                let (doc, is_error) = deps::execute(request, format, why.as_deref());
+                (doc, is_error, None)
+            }
+            Commands::BundleDeps { direct_only, out } => {
+                let (doc, is_error) = bundle_deps::execute(request, direct_only, &out);
+                (doc, is_error, None)
+            }
+            Commands::Cache { command } => {
+                let (doc, is_error) = cache::execute(request, command);
+                (doc, is_error, None)
+            }
+            Commands::OutdatedDocs { refresh } => {
+                let (doc, is_error) = outdated_docs::execute(request, refresh);
+                (doc, is_error, None)
+            }
+            Commands::RunExample {
+                path,
+                index,
+                capture,
+            } => {
+                let (doc, is_error, item_ref) =
+                    run_example::execute(request, &path, index, capture);
+                let history_entry = item_ref.map(HistoryEntry::Item);
+                (doc, is_error, history_entry)
+            }
+            Commands::Cli { bin } => {
+                let (doc, is_error) = cli::execute(request, bin.as_deref());
+                (doc, is_error, None)
+            }
+            Commands::Toolchains => {
+                let (doc, is_error) = toolchains::execute();
+                (doc, is_error, None)
+            }
+            Commands::Daemon => (command_line_only("ferritin daemon"), true, None),
+            Commands::Rpc => (command_line_only("ferritin rpc"), true, None),
+            Commands::Completions { .. } => (command_line_only("ferritin completions"), true, None),
+            Commands::Complete { .. } => (command_line_only("ferritin __complete"), true, None),
         }
     }
 }
+
+/// Explains that a long-running mode can't be started from inside interactive mode
+fn command_line_only<'a>(command: &'static str) -> Document<'a> {
+    Document::from(vec![DocumentNode::paragraph(vec![
+        Span::plain("This runs from the command line, as "),
+        Span::inline_code(command),
+        Span::plain("."),
+    ])])
+}
+
+/// Parse an item kind for `--kinds`, spelled like a rustdoc discriminator (`fn`, `struct`)
+fn parse_item_kind(kind: &str) -> Result<ItemKind, String> {
+    ferritin_common::parse_kind_discriminator(kind).ok_or_else(|| {
+        format!("{kind:?} is not an item kind, like mod, struct, enum, trait, fn or const")
+    })
+}
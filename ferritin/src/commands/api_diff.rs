@@ -0,0 +1,182 @@
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span};
+use crate::workspace_history::WorkspaceHistory;
+use ferritin_common::RustdocData;
+use ferritin_common::sources::Source;
+use rustdoc_types::{ItemKind, Visibility};
+use semver::{Version, VersionReq};
+use std::collections::BTreeMap;
+
+/// Public items added and removed between two versions of a crate, by path
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ApiDiff {
+    added: Vec<(String, ItemKind)>,
+    removed: Vec<(String, ItemKind)>,
+}
+
+impl ApiDiff {
+    fn new(old: &BTreeMap<String, ItemKind>, new: &BTreeMap<String, ItemKind>) -> Self {
+        let only_in = |a: &BTreeMap<String, ItemKind>,
+                       b: &BTreeMap<String, ItemKind>|
+         -> Vec<(String, ItemKind)> {
+            a.iter()
+                .filter(|(path, _)| !b.contains_key(*path))
+                .map(|(path, kind)| (path.clone(), *kind))
+                .collect()
+        };
+
+        Self {
+            added: only_in(new, old),
+            removed: only_in(old, new),
+        }
+    }
+}
+
+/// The path and kind of every public item defined in a crate
+fn public_paths(data: &RustdocData) -> BTreeMap<String, ItemKind> {
+    data.paths
+        .iter()
+        .filter(|(_, summary)| summary.crate_id == 0)
+        .filter(|(id, _)| {
+            data.item(id)
+                .is_some_and(|item| item.visibility == Visibility::Public)
+        })
+        .map(|(_, summary)| (summary.path.join("::"), summary.kind))
+        .collect()
+}
+
+/// Compare the public API of a dependency's current version against an earlier one from
+/// docs.rs. Without `from`, the version it was updated from, as recorded by
+/// `list --changed`, is used.
+pub(crate) fn execute<'a>(
+    request: &'a Request,
+    crate_name: &str,
+    from: Option<&Version>,
+) -> (Document<'a>, bool) {
+    let error = |message: String| {
+        (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(message)])]),
+            true,
+        )
+    };
+
+    let Some(crate_info) = request.lookup_crate(crate_name, &VersionReq::STAR) else {
+        return error(format!("Could not find crate {crate_name}"));
+    };
+    let Some(current) = crate_info.version().cloned() else {
+        return error(format!("{crate_name} has no version to compare"));
+    };
+    let name = crate_info.name().to_string();
+
+    let from = match from {
+        Some(from) => from.clone(),
+        None => {
+            let history = WorkspaceHistory::for_request(request);
+            let previous = history
+                .previous_version(&name, &current.to_string())
+                .and_then(|previous| Version::parse(previous).ok());
+            let Some(previous) = previous else {
+                return error(format!(
+                    "No earlier version of {name} has been recorded. Pass --from to choose \
+                     one, or run `ferritin list --changed` after the next update."
+                ));
+            };
+            previous
+        }
+    };
+
+    let Some(new) = request.load_crate(&name, &VersionReq::STAR) else {
+        return error(format!("Could not load docs for {name}@{current}"));
+    };
+    let Some(old) = request
+        .docsrs_source()
+        .and_then(|docsrs| docsrs.load(&name, Some(&from), request.progress()))
+    else {
+        return error(format!(
+            "Could not load docs for {name}@{from} from docs.rs"
+        ));
+    };
+
+    let diff = ApiDiff::new(&public_paths(&old), &public_paths(new));
+
+    let mut nodes = vec![DocumentNode::Heading {
+        level: HeadingLevel::Title,
+        spans: vec![Span::plain(format!(
+            "API changes in {name} {from} → {current}"
+        ))],
+    }];
+
+    if diff.added.is_empty() && diff.removed.is_empty() {
+        nodes.push(DocumentNode::paragraph(vec![Span::plain(
+            "No public items were added or removed.",
+        )]));
+    }
+
+    if !diff.added.is_empty() {
+        let count = diff.added.len();
+        let items = diff
+            .added
+            .into_iter()
+            .map(|(path, kind)| {
+                ListItem::new(vec![DocumentNode::paragraph(vec![
+                    Span::plain(path.clone()).with_path(path),
+                    Span::plain(format!(" ({kind:?})")),
+                ])])
+            })
+            .collect();
+        nodes.push(DocumentNode::section(
+            vec![Span::plain(format!("Added ({count})"))],
+            vec![DocumentNode::list(items)],
+        ));
+    }
+
+    if !diff.removed.is_empty() {
+        let count = diff.removed.len();
+        let items = diff
+            .removed
+            .into_iter()
+            .map(|(path, kind)| {
+                ListItem::new(vec![DocumentNode::paragraph(vec![
+                    Span::plain(path),
+                    Span::plain(format!(" ({kind:?})")),
+                ])])
+            })
+            .collect();
+        nodes.push(DocumentNode::section(
+            vec![Span::plain(format!("Removed ({count})"))],
+            vec![DocumentNode::list(items)],
+        ));
+    }
+
+    nodes.push(DocumentNode::paragraph(vec![Span::emphasis(
+        "Only item paths are compared; changes to signatures aren't shown.",
+    )]));
+
+    (Document::from(nodes), false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lists_paths_only_in_one_version() {
+        let old = BTreeMap::from([
+            ("serde::Serialize".to_string(), ItemKind::Trait),
+            ("serde::de::IgnoredAny".to_string(), ItemKind::Struct),
+        ]);
+        let new = BTreeMap::from([
+            ("serde::Serialize".to_string(), ItemKind::Trait),
+            ("serde::de::value::Error".to_string(), ItemKind::Struct),
+        ]);
+
+        assert_eq!(
+            ApiDiff::new(&old, &new),
+            ApiDiff {
+                added: vec![("serde::de::value::Error".into(), ItemKind::Struct)],
+                removed: vec![("serde::de::IgnoredAny".into(), ItemKind::Struct)],
+            }
+        );
+        assert_eq!(ApiDiff::new(&old, &old), ApiDiff::default());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::renderer::render_plain;
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span, TruncationLevel};
+use rustdoc_types::ItemKind;
+use semver::VersionReq;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Records the version each bundle file was generated from, as `crate\tversion` lines
+const MANIFEST_FILE: &str = ".ferritin-bundle";
+
+/// Key item docs are added to a crate's file until it reaches this size, so that each file fits
+/// comfortably in a retrieval tool's context
+const MAX_FILE_BYTES: usize = 32 * 1024;
+
+/// Item kinds whose docs are included in full after the crate overview
+const KEY_ITEM_KINDS: &[ItemKind] = &[
+    ItemKind::Trait,
+    ItemKind::Struct,
+    ItemKind::Enum,
+    ItemKind::Function,
+    ItemKind::Macro,
+];
+
+/// Which files a bundle run writes, keeps and deletes
+#[derive(Debug, Default, PartialEq, Eq)]
+struct BundlePlan {
+    write: Vec<String>,
+    unchanged: Vec<String>,
+    remove: Vec<String>,
+}
+
+impl BundlePlan {
+    /// Compare the versions bundled last time against the current dependency versions.
+    /// `exists` reports whether a crate's file is still on disk.
+    fn new(
+        previous: &BTreeMap<String, String>,
+        current: &BTreeMap<String, String>,
+        exists: impl Fn(&str) -> bool,
+    ) -> Self {
+        let mut plan = Self::default();
+        for (crate_name, version) in current {
+            if previous.get(crate_name) == Some(version) && exists(crate_name) {
+                plan.unchanged.push(crate_name.clone());
+            } else {
+                plan.write.push(crate_name.clone());
+            }
+        }
+        plan.remove = previous
+            .keys()
+            .filter(|crate_name| !current.contains_key(*crate_name))
+            .cloned()
+            .collect();
+        plan
+    }
+}
+
+fn parse_manifest(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(crate_name, version)| (crate_name.to_string(), version.to_string()))
+        .collect()
+}
+
+fn bundle_file(out: &Path, crate_name: &str) -> PathBuf {
+    out.join(format!("{crate_name}.md"))
+}
+
+/// Write a Markdown overview of each dependency to `out`, skipping dependencies whose version
+/// hasn't changed since the last run
+pub(crate) fn execute<'a>(
+    request: &'a Request,
+    direct_only: bool,
+    out: &Path,
+) -> (Document<'a>, bool) {
+    let error = |message: String| {
+        (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(message)])]),
+            true,
+        )
+    };
+
+    let Some(local_source) = request.local_source() else {
+        return error(String::from(
+            "No Rust project detected, so there are no dependencies to bundle.",
+        ));
+    };
+
+    let current = local_source
+        .packages()
+        .filter(|crate_info| !crate_info.provenance().is_workspace())
+        .filter(|crate_info| !direct_only || !crate_info.used_by().is_empty())
+        .filter(|crate_info| !local_source.is_ignored(crate_info.name()))
+        .filter_map(|crate_info| {
+            Some((
+                crate_info.name().to_string(),
+                crate_info.version()?.to_string(),
+            ))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    if let Err(e) = fs::create_dir_all(out) {
+        return error(format!("Could not create {}: {e}", out.display()));
+    }
+
+    let manifest_path = out.join(MANIFEST_FILE);
+    let previous = fs::read_to_string(&manifest_path)
+        .map(|content| parse_manifest(&content))
+        .unwrap_or_default();
+    let plan = BundlePlan::new(&previous, &current, |crate_name| {
+        bundle_file(out, crate_name).exists()
+    });
+
+    let mut failures = vec![];
+    let mut written = 0;
+    let mut bundled = BTreeMap::new();
+    for crate_name in &plan.unchanged {
+        bundled.insert(crate_name.clone(), current[crate_name].clone());
+    }
+
+    // Parse every crate's docs up front in parallel, rather than one at a time below
+    request.prefetch(&plan.write.iter().map(String::as_str).collect::<Vec<_>>());
+
+    for crate_name in &plan.write {
+        let result = render_crate(request, crate_name).and_then(|markdown| {
+            fs::write(bundle_file(out, crate_name), markdown)
+                .map_err(|e| format!("could not write it: {e}"))
+        });
+        match result {
+            Ok(()) => {
+                written += 1;
+                bundled.insert(crate_name.clone(), current[crate_name].clone());
+            }
+            Err(reason) => failures.push(format!("{crate_name}: {reason}")),
+        }
+    }
+
+    for crate_name in &plan.remove {
+        let _ = fs::remove_file(bundle_file(out, crate_name));
+    }
+
+    let manifest = bundled
+        .iter()
+        .map(|(crate_name, version)| format!("{crate_name}\t{version}\n"))
+        .collect::<String>();
+    if let Err(e) = fs::write(&manifest_path, manifest) {
+        failures.push(format!("{}: {e}", manifest_path.display()));
+    }
+
+    let mut nodes = vec![
+        DocumentNode::Heading {
+            level: HeadingLevel::Title,
+            spans: vec![Span::plain(format!(
+                "Bundled docs for {} dependencies in {}",
+                bundled.len(),
+                out.display()
+            ))],
+        },
+        DocumentNode::paragraph(vec![Span::plain(format!(
+            "{written} written, {} unchanged, {} removed",
+            plan.unchanged.len(),
+            plan.remove.len()
+        ))]),
+    ];
+
+    let is_error = !failures.is_empty();
+    if is_error {
+        nodes.push(DocumentNode::section(
+            vec![Span::plain("Failed")],
+            vec![DocumentNode::list(
+                failures
+                    .into_iter()
+                    .map(|failure| {
+                        ListItem::new(vec![DocumentNode::paragraph(vec![Span::plain(failure)])])
+                    })
+                    .collect(),
+            )],
+        ));
+    }
+
+    (Document::from(nodes), is_error)
+}
+
+/// Render a crate's overview, followed by the full docs of its top-level items for as long as
+/// the file stays within [`MAX_FILE_BYTES`]
+fn render_crate(request: &Request, crate_name: &str) -> Result<String, String> {
+    let crate_data = request
+        .load_crate(crate_name, &VersionReq::STAR)
+        .ok_or_else(|| String::from("no docs are available"))?;
+    let root = crate_data.root_item(request);
+
+    let mut markdown = String::new();
+    render_plain(&Document::from(request.format_item(root)), &mut markdown)
+        .map_err(|e| e.to_string())?;
+
+    let mut key_items = root
+        .child_items()
+        .filter(|item| KEY_ITEM_KINDS.contains(&item.kind()))
+        .filter(|item| item.docs.as_deref().is_some_and(|docs| !docs.is_empty()))
+        .collect::<Vec<_>>();
+    key_items.sort_by_key(|item| {
+        (
+            KEY_ITEM_KINDS.iter().position(|kind| *kind == item.kind()),
+            item.name(),
+        )
+    });
+
+    for item in key_items {
+        let name = item.name().unwrap_or("<unnamed>");
+        let mut nodes = vec![DocumentNode::Heading {
+            level: HeadingLevel::Section,
+            spans: vec![Span::plain(format!(
+                "{crate_name}::{name} ({:?})",
+                item.kind()
+            ))],
+        }];
+        nodes.extend(
+            request
+                .docs_to_show(item, TruncationLevel::Full)
+                .unwrap_or_default(),
+        );
+
+        let mut section = String::from("\n\n");
+        render_plain(&Document::from(nodes), &mut section).map_err(|e| e.to_string())?;
+        if markdown.len() + section.len() > MAX_FILE_BYTES {
+            break;
+        }
+        markdown.push_str(&section);
+    }
+
+    Ok(markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_rewrites_only_changed_or_missing_crates() {
+        let previous = parse_manifest("serde\t1.0.200\ntokio\t1.40.0\nrand\t0.8.5\n");
+        let current = BTreeMap::from([
+            ("serde".to_string(), "1.0.210".to_string()),
+            ("tokio".to_string(), "1.40.0".to_string()),
+            ("log".to_string(), "0.4.22".to_string()),
+        ]);
+
+        let plan = BundlePlan::new(&previous, &current, |_| true);
+        assert_eq!(
+            plan,
+            BundlePlan {
+                write: vec!["log".into(), "serde".into()],
+                unchanged: vec!["tokio".into()],
+                remove: vec!["rand".into()],
+            }
+        );
+
+        let plan = BundlePlan::new(&previous, &current, |crate_name| crate_name != "tokio");
+        assert!(plan.write.contains(&"tokio".to_string()));
+    }
+}
@@ -0,0 +1,279 @@
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, Span, TableCell};
+use ferritin_common::CrateName;
+use ferritin_common::sources::CacheEntry;
+use semver::Version;
+use std::time::{Duration, SystemTime};
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub(crate) enum CacheCommand {
+    /// List cached docs.rs downloads with their sizes and when they were last used
+    Ls,
+
+    /// Remove cached docs for a crate, or for one version of it
+    Clean {
+        /// Crate to remove
+        #[arg(value_name = "CRATE")]
+        crate_: String,
+
+        /// Only remove this version
+        version: Option<Version>,
+    },
+
+    /// Remove the least recently used cached docs until the cache fits in a size budget
+    Gc {
+        /// Size budget, in bytes or with a K, M or G suffix
+        #[arg(long, default_value = "2G", value_parser = parse_size)]
+        max_size: u64,
+    },
+}
+
+pub(crate) fn execute<'a>(request: &'a Request, command: CacheCommand) -> (Document<'a>, bool) {
+    let Some(docsrs) = request.docsrs_source() else {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(
+                "The docs.rs cache is unavailable, since the cargo home directory couldn't be found.",
+            )])]),
+            true,
+        );
+    };
+
+    let mut entries = docsrs.cache_entries();
+    let cache_dir = docsrs.cache_dir().display().to_string();
+
+    match command {
+        CacheCommand::Ls => {
+            entries.sort_by(|a, b| {
+                (&a.crate_name, &a.version, a.format_version).cmp(&(
+                    &b.crate_name,
+                    &b.version,
+                    b.format_version,
+                ))
+            });
+
+            let total = entries.iter().map(|entry| entry.size).sum();
+            let now = SystemTime::now();
+            let rows = entries
+                .iter()
+                .map(|entry| {
+                    vec![
+                        TableCell::from_span(Span::plain(entry.crate_name.clone())),
+                        TableCell::from_span(Span::plain(entry.version.to_string())),
+                        TableCell::from_span(Span::plain(entry.format_version.to_string())),
+                        TableCell::from_span(Span::plain(format_size(entry.size))),
+                        TableCell::from_span(Span::plain(format_age(entry.last_used, now))),
+                    ]
+                })
+                .collect::<Vec<_>>();
+
+            let mut nodes = vec![DocumentNode::Heading {
+                level: HeadingLevel::Title,
+                spans: vec![Span::plain(format!("Cached docs in {cache_dir}:"))],
+            }];
+            if !rows.is_empty() {
+                let header = ["Crate", "Version", "Format", "Size", "Last used"]
+                    .into_iter()
+                    .map(|title| TableCell::from_span(Span::strong(title)))
+                    .collect();
+                nodes.push(DocumentNode::table(Some(header), rows));
+            }
+            nodes.push(DocumentNode::paragraph(vec![Span::plain(format!(
+                "{} cached crate versions, {} in total",
+                entries.len(),
+                format_size(total)
+            ))]));
+
+            (Document::from(nodes), false)
+        }
+
+        CacheCommand::Clean { crate_, version } => {
+            let crate_name = CrateName::from(crate_.as_str());
+            let matching = entries
+                .iter()
+                .filter(|entry| CrateName::from(entry.crate_name.as_str()) == crate_name)
+                .filter(|entry| {
+                    version
+                        .as_ref()
+                        .is_none_or(|version| &entry.version == version)
+                })
+                .collect::<Vec<_>>();
+
+            if matching.is_empty() {
+                let target = match &version {
+                    Some(version) => format!("{crate_}@{version}"),
+                    None => crate_,
+                };
+                return (
+                    Document::from(vec![DocumentNode::paragraph(vec![Span::plain(format!(
+                        "Nothing cached for {target}"
+                    ))])]),
+                    true,
+                );
+            }
+
+            remove_entries(&matching, "Removed")
+        }
+
+        CacheCommand::Gc { max_size } => {
+            let victims = gc_victims(&entries, max_size);
+            if victims.is_empty() {
+                let total = entries.iter().map(|entry| entry.size).sum();
+                return (
+                    Document::from(vec![DocumentNode::paragraph(vec![Span::plain(format!(
+                        "The cache is {}, within the {} budget",
+                        format_size(total),
+                        format_size(max_size)
+                    ))])]),
+                    false,
+                );
+            }
+
+            remove_entries(&victims, "Evicted")
+        }
+    }
+}
+
+/// Delete cache entries, reporting how much space was freed and any failures
+fn remove_entries<'a>(entries: &[&CacheEntry], verb: &str) -> (Document<'a>, bool) {
+    let mut freed = 0;
+    let mut removed = 0;
+    let mut nodes = vec![];
+    for entry in entries {
+        match entry.remove() {
+            Ok(()) => {
+                freed += entry.size;
+                removed += 1;
+            }
+            Err(error) => nodes.push(DocumentNode::paragraph(vec![Span::plain(format!(
+                "Could not remove {}@{}: {error}",
+                entry.crate_name, entry.version
+            ))])),
+        }
+    }
+
+    let is_error = !nodes.is_empty();
+    nodes.insert(
+        0,
+        DocumentNode::paragraph(vec![Span::plain(format!(
+            "{verb} {removed} cached crate versions, freeing {}",
+            format_size(freed)
+        ))]),
+    );
+    (Document::from(nodes), is_error)
+}
+
+/// The entries to remove, least recently used first, to bring the cache within `budget` bytes
+fn gc_victims(entries: &[CacheEntry], budget: u64) -> Vec<&CacheEntry> {
+    let mut total: u64 = entries.iter().map(|entry| entry.size).sum();
+    let mut by_last_use = entries.iter().collect::<Vec<_>>();
+    // Entries without a recorded time sort first, as the least likely to be in use
+    by_last_use.sort_by_key(|entry| entry.last_used);
+
+    by_last_use
+        .into_iter()
+        .take_while(|entry| {
+            let over_budget = total > budget;
+            total = total.saturating_sub(entry.size);
+            over_budget
+        })
+        .collect()
+}
+
+/// Parse a size like `500M` or `2G` into bytes
+fn parse_size(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    let (number, multiplier) = match input.char_indices().last() {
+        Some((index, suffix)) if suffix.is_ascii_alphabetic() => {
+            let multiplier = match suffix.to_ascii_uppercase() {
+                'K' => 1 << 10,
+                'M' => 1 << 20,
+                'G' => 1 << 30,
+                _ => {
+                    return Err(format!(
+                        "unknown size suffix {suffix:?}, expected K, M or G"
+                    ));
+                }
+            };
+            (&input[..index], multiplier)
+        }
+        _ => (input, 1),
+    };
+
+    let number = number
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| format!("{input:?} is not a size"))?;
+    Ok((number * multiplier as f64) as u64)
+}
+
+pub(super) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+fn format_age(last_used: Option<SystemTime>, now: SystemTime) -> String {
+    let Some(age) = last_used.and_then(|last_used| now.duration_since(last_used).ok()) else {
+        return String::from("unknown");
+    };
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+    if age < HOUR {
+        String::from("within the hour")
+    } else if age < DAY {
+        format!("{} hours ago", age.as_secs() / HOUR.as_secs())
+    } else {
+        format!("{} days ago", age.as_secs() / DAY.as_secs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn entry(crate_name: &str, size: u64, days_ago: u64) -> CacheEntry {
+        CacheEntry {
+            crate_name: crate_name.into(),
+            version: Version::new(1, 0, 0),
+            format_version: 57,
+            path: PathBuf::from(format!("{crate_name}.json")),
+            size,
+            last_used: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1000 - days_ago)),
+        }
+    }
+
+    #[test]
+    fn gc_evicts_least_recently_used_until_within_budget() {
+        let entries = vec![
+            entry("serde", 40, 1),
+            entry("tokio", 50, 9),
+            entry("rand", 30, 5),
+        ];
+        let victims = gc_victims(&entries, 60)
+            .into_iter()
+            .map(|entry| entry.crate_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(victims, ["tokio", "rand"]);
+        assert!(gc_victims(&entries, 120).is_empty());
+    }
+
+    #[test]
+    fn sizes_parse_with_suffixes() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("2K"), Ok(2048));
+        assert_eq!(parse_size("1.5g"), Ok(3 << 29));
+        assert!(parse_size("10X").is_err());
+        assert_eq!(format_size(3 << 29), "1.5 GB");
+    }
+}
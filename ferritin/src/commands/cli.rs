@@ -0,0 +1,620 @@
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span};
+use rustdoc_types::{
+    Attribute, Crate, GenericArg, GenericArgs, Item, ItemEnum, StructKind, Type, VariantKind,
+};
+
+/// A command line synthesized from clap derive attributes and doc comments
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CliCommand {
+    name: String,
+    about: Option<String>,
+    args: Vec<CliArg>,
+    subcommands: Vec<CliCommand>,
+}
+
+/// A positional argument or option of a [`CliCommand`]
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CliArg {
+    /// How the argument is written, like `-s, --source` or `<PATH>`
+    usage: String,
+    positional: bool,
+    help: Option<String>,
+    default_value: Option<String>,
+    env: Option<String>,
+    possible_values: Vec<String>,
+}
+
+/// The `key` and `key = value` settings from an item's `#[arg(...)]`, `#[command(...)]`,
+/// `#[clap(...)]` and `#[value(...)]` attributes
+#[derive(Debug, Default)]
+struct ClapSettings(Vec<(String, Option<String>)>);
+
+impl ClapSettings {
+    fn from_item(item: &Item) -> Self {
+        Self(
+            item.attrs
+                .iter()
+                .filter_map(|attr| match attr {
+                    Attribute::Other(attr) => clap_attribute(attr),
+                    _ => None,
+                })
+                .flat_map(split_settings)
+                .collect(),
+        )
+    }
+
+    fn has(&self, key: &str) -> bool {
+        self.0.iter().any(|(k, _)| k == key)
+    }
+
+    fn value(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.has("skip") || (self.has("hide") && self.value("hide") != Some("false"))
+    }
+}
+
+/// The contents of a clap attribute like `#[arg(short, long)]`, if it is one
+fn clap_attribute(attr: &str) -> Option<&str> {
+    let attr = attr.trim().strip_prefix("#[")?.strip_suffix(']')?;
+    let (name, settings) = attr.split_once('(')?;
+    matches!(name.trim(), "arg" | "command" | "clap" | "value")
+        .then(|| settings.strip_suffix(')'))
+        .flatten()
+}
+
+/// Split attribute settings on top-level commas into keys and unquoted values
+fn split_settings(settings: &str) -> Vec<(String, Option<String>)> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (index, ch) in settings.char_indices() {
+        match (quote, ch) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(open), ch) if ch == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(&settings[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&settings[start..]);
+
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.split_once('=') {
+            Some((key, value)) => (key.trim().to_string(), Some(unquote(value.trim()))),
+            None => (part.to_string(), None),
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// Clap's default name for a field or variant: `explain_resolution` becomes
+/// `explain-resolution` and `BundleDeps` becomes `bundle-deps`
+fn kebab_case(name: &str) -> String {
+    let name = name.strip_prefix("r#").unwrap_or(name);
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut kebab = String::new();
+    let mut boundary = false;
+    for (index, &ch) in chars.iter().enumerate() {
+        if ch == '_' || ch == '-' {
+            boundary = true;
+            continue;
+        }
+
+        if ch.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                boundary = true;
+            }
+        }
+
+        if boundary && !kebab.is_empty() {
+            kebab.push('-');
+        }
+        boundary = false;
+        kebab.extend(ch.to_lowercase());
+    }
+    kebab
+}
+
+/// The help text clap takes from a doc comment: its first paragraph
+fn doc_help(item: &Item) -> Option<String> {
+    let first_paragraph = item.docs.as_deref()?.trim().split("\n\n").next()?;
+    let help = first_paragraph
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!help.is_empty()).then_some(help)
+}
+
+/// Whether a struct or enum implements a trait, going by the trait's name
+fn implements(krate: &Crate, item: &Item, trait_name: &str) -> bool {
+    let impls = match &item.inner {
+        ItemEnum::Struct(struct_) => &struct_.impls,
+        ItemEnum::Enum(enum_) => &enum_.impls,
+        _ => return false,
+    };
+
+    impls
+        .iter()
+        .filter_map(|id| krate.index.get(id))
+        .any(|impl_block| match &impl_block.inner {
+            ItemEnum::Impl(impl_) => impl_
+                .trait_
+                .as_ref()
+                .is_some_and(|trait_| trait_.path.rsplit("::").next() == Some(trait_name)),
+            _ => false,
+        })
+}
+
+/// How many values a field's type takes, with the type of each value
+enum Arity<'a> {
+    Switch,
+    Required(&'a Type),
+    Optional(&'a Type),
+    Multiple(&'a Type),
+}
+
+impl<'a> Arity<'a> {
+    fn of(ty: &'a Type) -> Self {
+        match ty {
+            Type::Primitive(name) if name == "bool" => Self::Switch,
+            Type::ResolvedPath(path) => {
+                let inner = path.args.as_deref().and_then(|args| match args {
+                    GenericArgs::AngleBracketed { args, .. } => {
+                        args.iter().find_map(|arg| match arg {
+                            GenericArg::Type(ty) => Some(ty),
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                });
+                match (path.path.rsplit("::").next(), inner) {
+                    (Some("Option"), Some(inner)) => Self::Optional(inner),
+                    (Some("Vec"), Some(inner)) => Self::Multiple(inner),
+                    _ => Self::Required(ty),
+                }
+            }
+            _ => Self::Required(ty),
+        }
+    }
+
+    fn value_type(&self) -> Option<&'a Type> {
+        match self {
+            Self::Switch => None,
+            Self::Required(ty) | Self::Optional(ty) | Self::Multiple(ty) => Some(ty),
+        }
+    }
+}
+
+/// The struct or enum a type refers to, if it is defined in this crate
+fn local_type<'a>(krate: &'a Crate, ty: &Type) -> Option<&'a Item> {
+    match ty {
+        Type::ResolvedPath(path) => krate.index.get(&path.id),
+        _ => None,
+    }
+}
+
+/// The values of a `ValueEnum`, as they are written on the command line
+fn possible_values(krate: &Crate, ty: &Type) -> Vec<String> {
+    let Some(item) = local_type(krate, ty) else {
+        return vec![];
+    };
+    let ItemEnum::Enum(enum_) = &item.inner else {
+        return vec![];
+    };
+    if !implements(krate, item, "ValueEnum") {
+        return vec![];
+    }
+
+    enum_
+        .variants
+        .iter()
+        .filter_map(|id| krate.index.get(id))
+        .filter_map(|variant| {
+            let settings = ClapSettings::from_item(variant);
+            if settings.is_hidden() {
+                return None;
+            }
+            Some(match settings.value("name") {
+                Some(name) => name.to_string(),
+                None => kebab_case(variant.name.as_deref()?),
+            })
+        })
+        .collect()
+}
+
+impl CliCommand {
+    /// The command defined by a `Parser` struct or enum
+    fn from_parser(krate: &Crate, item: &Item, name: &str) -> Self {
+        let settings = ClapSettings::from_item(item);
+        let mut command = Self {
+            name: settings.value("name").unwrap_or(name).to_string(),
+            about: settings
+                .value("about")
+                .map(String::from)
+                .or_else(|| doc_help(item)),
+            ..Self::default()
+        };
+        command.add_members(krate, item);
+        command
+    }
+
+    /// The subcommand for a variant of a `Subcommand` enum
+    fn from_variant(krate: &Crate, variant: &Item) -> Option<Self> {
+        let settings = ClapSettings::from_item(variant);
+        if settings.is_hidden() {
+            return None;
+        }
+        let ItemEnum::Variant(inner) = &variant.inner else {
+            return None;
+        };
+
+        let mut command = Self {
+            name: match settings.value("name") {
+                Some(name) => name.to_string(),
+                None => kebab_case(variant.name.as_deref()?),
+            },
+            about: settings
+                .value("about")
+                .map(String::from)
+                .or_else(|| doc_help(variant)),
+            ..Self::default()
+        };
+
+        match &inner.kind {
+            VariantKind::Plain => {}
+            VariantKind::Struct { fields, .. } => {
+                for field in fields.iter().filter_map(|id| krate.index.get(id)) {
+                    command.add_field(krate, field);
+                }
+            }
+            // A tuple variant holds an `Args` struct, or a nested `Subcommand` enum
+            VariantKind::Tuple(fields) => {
+                for field in fields.iter().flatten().filter_map(|id| krate.index.get(id)) {
+                    if let ItemEnum::StructField(ty) = &field.inner
+                        && let Some(item) = local_type(krate, ty)
+                    {
+                        command.add_members(krate, item);
+                    }
+                }
+            }
+        }
+
+        Some(command)
+    }
+
+    /// Add the fields of a struct as arguments, or the variants of an enum as subcommands
+    fn add_members(&mut self, krate: &Crate, item: &Item) {
+        match &item.inner {
+            ItemEnum::Struct(struct_) => {
+                if let StructKind::Plain { fields, .. } = &struct_.kind {
+                    for field in fields.iter().filter_map(|id| krate.index.get(id)) {
+                        self.add_field(krate, field);
+                    }
+                }
+            }
+            ItemEnum::Enum(enum_) => self.subcommands.extend(
+                enum_
+                    .variants
+                    .iter()
+                    .filter_map(|id| krate.index.get(id))
+                    .filter_map(|variant| Self::from_variant(krate, variant)),
+            ),
+            _ => {}
+        }
+    }
+
+    fn add_field(&mut self, krate: &Crate, field: &Item) {
+        let settings = ClapSettings::from_item(field);
+        let ItemEnum::StructField(ty) = &field.inner else {
+            return;
+        };
+        if settings.is_hidden() {
+            return;
+        }
+
+        let arity = Arity::of(ty);
+        if settings.has("subcommand") || settings.has("flatten") {
+            if let Some(item) = arity.value_type().and_then(|ty| local_type(krate, ty)) {
+                self.add_members(krate, item);
+            }
+            return;
+        }
+
+        let Some(name) = field.name.as_deref() else {
+            return;
+        };
+        let kebab = kebab_case(name);
+        let counts_or_sets = settings.value("action").is_some_and(|action| {
+            ["Count", "SetTrue", "SetFalse"]
+                .iter()
+                .any(|a| action.ends_with(a))
+        });
+        let takes_value = !matches!(arity, Arity::Switch) && !counts_or_sets;
+        let value_name = settings
+            .value("value_name")
+            .map(String::from)
+            .unwrap_or_else(|| kebab.replace('-', "_").to_uppercase());
+        let repeat = if matches!(arity, Arity::Multiple(_)) {
+            "..."
+        } else {
+            ""
+        };
+
+        let long = settings.has("long").then(|| {
+            settings
+                .value("long")
+                .map_or_else(|| kebab.clone(), String::from)
+        });
+        let short = settings.has("short").then(|| {
+            settings
+                .value("short")
+                .map(String::from)
+                .unwrap_or_else(|| kebab.chars().next().map(String::from).unwrap_or_default())
+        });
+
+        let positional = long.is_none() && short.is_none();
+        let usage = if positional {
+            match arity {
+                Arity::Optional(_) => format!("[{value_name}]"),
+                _ => format!("<{value_name}>{repeat}"),
+            }
+        } else {
+            let flags = short
+                .map(|short| format!("-{short}"))
+                .into_iter()
+                .chain(long.map(|long| format!("--{long}")))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if takes_value {
+                format!("{flags} <{value_name}>{repeat}")
+            } else {
+                flags
+            }
+        };
+
+        self.args.push(CliArg {
+            usage,
+            positional,
+            help: settings
+                .value("help")
+                .map(String::from)
+                .or_else(|| doc_help(field)),
+            default_value: settings
+                .value("default_value")
+                .or_else(|| settings.value("default_value_t"))
+                .map(String::from),
+            env: settings.value("env").map(String::from),
+            possible_values: arity
+                .value_type()
+                .map(|ty| possible_values(krate, ty))
+                .unwrap_or_default(),
+        });
+    }
+
+    fn usage(&self, path: &str) -> String {
+        let mut usage = path.to_string();
+        if self.args.iter().any(|arg| !arg.positional) {
+            usage.push_str(" [OPTIONS]");
+        }
+        for arg in self.args.iter().filter(|arg| arg.positional) {
+            usage.push(' ');
+            usage.push_str(&arg.usage);
+        }
+        if !self.subcommands.is_empty() {
+            usage.push_str(" <COMMAND>");
+        }
+        usage
+    }
+
+    /// The reference for this command and, in nested sections, its subcommands
+    fn render<'a>(&self, path: &str) -> Vec<DocumentNode<'a>> {
+        let mut nodes = vec![];
+        if let Some(about) = &self.about {
+            nodes.push(DocumentNode::paragraph(vec![Span::plain(about.clone())]));
+        }
+        nodes.push(DocumentNode::paragraph(vec![
+            Span::strong("Usage: "),
+            Span::inline_code(self.usage(path)),
+        ]));
+
+        let (positional, options): (Vec<_>, Vec<_>) =
+            self.args.iter().partition(|arg| arg.positional);
+        for (title, args) in [("Arguments", positional), ("Options", options)] {
+            if !args.is_empty() {
+                nodes.push(DocumentNode::section(
+                    vec![Span::plain(title)],
+                    vec![DocumentNode::list(
+                        args.into_iter().map(render_arg).collect(),
+                    )],
+                ));
+            }
+        }
+
+        if !self.subcommands.is_empty() {
+            let items = self
+                .subcommands
+                .iter()
+                .map(|subcommand| {
+                    let mut spans = vec![Span::inline_code(subcommand.name.clone())];
+                    if let Some(about) = &subcommand.about {
+                        spans.push(Span::plain(format!(" — {about}")));
+                    }
+                    ListItem::new(vec![DocumentNode::paragraph(spans)])
+                })
+                .collect();
+            nodes.push(DocumentNode::section(
+                vec![Span::plain("Commands")],
+                vec![DocumentNode::list(items)],
+            ));
+        }
+
+        for subcommand in &self.subcommands {
+            if subcommand.args.is_empty() && subcommand.subcommands.is_empty() {
+                continue;
+            }
+            let subcommand_path = format!("{path} {}", subcommand.name);
+            nodes.push(DocumentNode::section(
+                vec![Span::plain(subcommand_path.clone())],
+                subcommand.render(&subcommand_path),
+            ));
+        }
+
+        nodes
+    }
+}
+
+fn render_arg<'a>(arg: &CliArg) -> ListItem<'a> {
+    let mut item_nodes = vec![DocumentNode::paragraph(vec![Span::inline_code(
+        arg.usage.clone(),
+    )])];
+
+    let mut spans = vec![];
+    if let Some(help) = &arg.help {
+        spans.push(Span::plain(help.clone()));
+    }
+    if let Some(default_value) = &arg.default_value {
+        spans.push(Span::emphasis(format!(" [default: {default_value}]")));
+    }
+    if !arg.possible_values.is_empty() {
+        spans.push(Span::emphasis(format!(
+            " [possible values: {}]",
+            arg.possible_values.join(", ")
+        )));
+    }
+    if let Some(env) = &arg.env {
+        spans.push(Span::emphasis(format!(" [env: {env}]")));
+    }
+    if !spans.is_empty() {
+        item_nodes.push(DocumentNode::paragraph(spans));
+    }
+
+    ListItem::new(item_nodes)
+}
+
+/// Show a command line reference for a workspace binary, synthesized from the clap derive
+/// attributes and doc comments on its `Parser` type
+pub(crate) fn execute<'a>(request: &'a Request, bin: Option<&str>) -> (Document<'a>, bool) {
+    let error = |message: String| {
+        (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(message)])]),
+            true,
+        )
+    };
+
+    let Some(local_source) = request.local_source() else {
+        return error(String::from(
+            "No Rust project detected, so there are no binaries to document.",
+        ));
+    };
+
+    let mut binaries = local_source.binaries().collect::<Vec<_>>();
+    binaries.sort();
+    let bin = match (bin, binaries.as_slice()) {
+        (Some(bin), _) => bin,
+        (None, [only]) => *only,
+        (None, []) => return error(String::from("This workspace has no binary targets.")),
+        (None, _) => {
+            return error(format!(
+                "This workspace has several binaries; choose one of: {}",
+                binaries.join(", ")
+            ));
+        }
+    };
+
+    if !binaries.contains(&bin) {
+        return error(format!(
+            "{bin} is not a binary in this workspace. Binaries: {}",
+            binaries.join(", ")
+        ));
+    }
+
+    let Some(crate_data) = local_source.load_binary(bin, request.progress()) else {
+        return error(format!("Could not build docs for the {bin} binary"));
+    };
+
+    let Some(parser) = crate_data
+        .index
+        .values()
+        .filter(|item| implements(&crate_data, item, "Parser"))
+        .min_by_key(|item| item.name.as_deref())
+    else {
+        return error(format!(
+            "No clap `Parser` was found in {bin}. Only command lines defined with clap's \
+             derive macros can be documented."
+        ));
+    };
+
+    let command = CliCommand::from_parser(&crate_data, parser, bin);
+    let mut nodes = vec![DocumentNode::Heading {
+        level: HeadingLevel::Title,
+        spans: vec![Span::plain(format!("{} command line", command.name))],
+    }];
+    nodes.extend(command.render(&command.name));
+
+    (Document::from(nodes), false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clap_attributes_are_split_into_settings() {
+        let settings = clap_attribute(
+            r#"#[arg(short, long = "crate", default_value = "a, b", value_parser = parse(1, 2))]"#,
+        )
+        .map(split_settings)
+        .unwrap();
+        assert_eq!(
+            settings,
+            [
+                ("short".to_string(), None),
+                ("long".to_string(), Some("crate".to_string())),
+                ("default_value".to_string(), Some("a, b".to_string())),
+                ("value_parser".to_string(), Some("parse(1, 2)".to_string())),
+            ]
+        );
+        assert_eq!(clap_attribute("#[serde(rename = \"x\")]"), None);
+    }
+
+    #[test]
+    fn names_are_kebab_cased_like_clap() {
+        assert_eq!(kebab_case("explain_resolution"), "explain-resolution");
+        assert_eq!(kebab_case("BundleDeps"), "bundle-deps");
+        assert_eq!(kebab_case("crate_"), "crate");
+        assert_eq!(kebab_case("HTTPServer"), "http-server");
+        assert_eq!(kebab_case("r#type"), "type");
+    }
+}
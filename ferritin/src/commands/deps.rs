@@ -0,0 +1,371 @@
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span};
+use ferritin_common::sources::Source;
+use ferritin_common::{CrateInfo, CrateName};
+use semver::VersionReq;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
+
+/// How `ferritin deps` presents the dependency graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DepsFormat {
+    Tree,
+    Dot,
+}
+
+/// Where the rustdoc JSON for a dependency would be loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Availability {
+    /// Built into the workspace's `target/doc`
+    Local,
+    /// Previously downloaded from docs.rs
+    DocsRsCache,
+    /// Neither, so viewing it needs a build or a download
+    Missing,
+}
+
+impl Availability {
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            Availability::Local => "local",
+            Availability::DocsRsCache => "docs.rs cache",
+            Availability::Missing => "missing",
+        }
+    }
+
+    fn dot_color(self) -> &'static str {
+        match self {
+            Availability::Local => "palegreen",
+            Availability::DocsRsCache => "lightblue",
+            Availability::Missing => "lightgray",
+        }
+    }
+}
+
+/// Where the rustdoc JSON for `crate_info` would be loaded from
+pub(super) fn docs_availability(request: &Request, crate_info: &CrateInfo) -> Availability {
+    if crate_info
+        .json_path()
+        .is_some_and(|json_path| json_path.exists())
+    {
+        Availability::Local
+    } else if let Some(version) = crate_info.version()
+        && request
+            .docsrs_source()
+            .is_some_and(|docsrs| docsrs.is_cached(crate_info.name(), version))
+    {
+        Availability::DocsRsCache
+    } else {
+        Availability::Missing
+    }
+}
+
+/// The workspace crates and everything they transitively depend on
+#[derive(Debug, Default)]
+struct DependencyGraph {
+    roots: Vec<String>,
+    edges: BTreeMap<String, Vec<String>>,
+}
+
+impl DependencyGraph {
+    /// Walk the resolved dependencies of each root
+    fn new(roots: Vec<String>, dependencies: impl Fn(&str) -> Vec<String>) -> Self {
+        let mut edges = BTreeMap::new();
+        let mut queue = roots.iter().cloned().collect::<VecDeque<_>>();
+        while let Some(name) = queue.pop_front() {
+            if edges.contains_key(&name) {
+                continue;
+            }
+            let deps = dependencies(&name);
+            queue.extend(deps.iter().cloned());
+            edges.insert(name, deps);
+        }
+
+        Self { roots, edges }
+    }
+
+    fn dependencies(&self, name: &str) -> &[String] {
+        self.edges.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// The shortest chain of dependencies from each root that leads to `target`
+    fn why(&self, target: &str) -> Vec<Vec<&str>> {
+        let target = CrateName::from(target);
+        let mut chains = vec![];
+
+        for root in &self.roots {
+            let mut parents: BTreeMap<&str, &str> = BTreeMap::new();
+            let mut queue = VecDeque::from([root.as_str()]);
+            let mut found = None;
+
+            while let Some(name) = queue.pop_front() {
+                if CrateName::from(name) == target {
+                    found = Some(name);
+                    break;
+                }
+                for dep in self.dependencies(name) {
+                    if dep != root && !parents.contains_key(dep.as_str()) {
+                        parents.insert(dep.as_str(), name);
+                        queue.push_back(dep);
+                    }
+                }
+            }
+
+            if let Some(mut name) = found {
+                let mut chain = vec![name];
+                while let Some(&parent) = parents.get(name) {
+                    chain.push(parent);
+                    name = parent;
+                }
+                chain.reverse();
+                chains.push(chain);
+            }
+        }
+
+        chains
+    }
+
+    /// Graphviz source with one node per crate, coloured by where its docs come from
+    fn to_dot(&self, availability: impl Fn(&str) -> (String, Availability)) -> String {
+        let mut dot = String::from("digraph dependencies {\n    node [shape=box, style=filled];\n");
+        for name in self.edges.keys() {
+            let (version, availability) = availability(name);
+            let _ = writeln!(
+                dot,
+                "    \"{name}\" [label=\"{name} {version}\\n{}\", fillcolor={}];",
+                availability.label(),
+                availability.dot_color()
+            );
+        }
+        for (name, deps) in &self.edges {
+            for dep in deps {
+                let _ = writeln!(dot, "    \"{name}\" -> \"{dep}\";");
+            }
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+/// Render the workspace dependency graph, or explain why one dependency is in it
+pub(crate) fn execute<'a>(
+    request: &'a Request,
+    format: DepsFormat,
+    why: Option<&str>,
+) -> (Document<'a>, bool) {
+    let Some(local_source) = request.local_source() else {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(
+                "No Rust project detected, so there is no dependency graph to show.",
+            )])]),
+            true,
+        );
+    };
+
+    let mut roots = request
+        .list_available_crates()
+        .filter(|crate_info| crate_info.provenance().is_workspace())
+        .map(|crate_info| crate_info.name().to_string())
+        .collect::<Vec<_>>();
+    roots.sort();
+
+    let graph = DependencyGraph::new(roots, |name| local_source.dependencies(name).to_vec());
+
+    let availability = |name: &str| {
+        let info = local_source.lookup(name, &VersionReq::STAR);
+        let version = info.as_ref().and_then(|info| info.version().cloned());
+        let availability = info.map_or(Availability::Missing, |info| {
+            docs_availability(request, &info)
+        });
+        (
+            version.map(|v| v.to_string()).unwrap_or_default(),
+            availability,
+        )
+    };
+
+    if let Some(target) = why {
+        return why_document(&graph, target);
+    }
+
+    match format {
+        DepsFormat::Dot => (dot_document(&graph, availability), false),
+        DepsFormat::Tree => {
+            let mut seen = BTreeSet::new();
+            let items = graph
+                .roots
+                .iter()
+                .map(|root| tree_item(&graph, root, &availability, &mut seen))
+                .collect();
+
+            (
+                Document::from(vec![
+                    DocumentNode::Heading {
+                        level: HeadingLevel::Title,
+                        spans: vec![Span::plain("Dependencies:")],
+                    },
+                    DocumentNode::list(items),
+                ]),
+                false,
+            )
+        }
+    }
+}
+
+/// The graph as Graphviz source, for piping into `dot`
+fn dot_document<'a>(
+    graph: &DependencyGraph,
+    availability: impl Fn(&str) -> (String, Availability),
+) -> Document<'a> {
+    // Generated code is written as-is, where a paragraph would be wrapped
+    Document::from(vec![DocumentNode::generated_code(vec![Span::plain(
+        graph.to_dot(availability),
+    )])])
+}
+
+/// One crate and, the first time it appears, its dependencies
+fn tree_item<'a>(
+    graph: &DependencyGraph,
+    name: &str,
+    availability: &impl Fn(&str) -> (String, Availability),
+    seen: &mut BTreeSet<String>,
+) -> ListItem<'a> {
+    let (version, docs) = availability(name);
+    let mut spans = vec![Span::strong(name.to_string()).with_path(name.to_string())];
+    if !version.is_empty() {
+        spans.push(Span::plain(format!(" {version}")));
+    }
+    spans.push(Span::plain(" ["));
+    spans.push(Span::emphasis(docs.label()));
+    spans.push(Span::plain("]"));
+
+    let deps = graph.dependencies(name);
+    if deps.is_empty() {
+        return ListItem::new(vec![DocumentNode::paragraph(spans)]);
+    }
+
+    // Like `cargo tree`, a crate's dependencies are only expanded once
+    if !seen.insert(name.to_string()) {
+        spans.push(Span::plain(" (*)"));
+        return ListItem::new(vec![DocumentNode::paragraph(spans)]);
+    }
+
+    let children = deps
+        .iter()
+        .map(|dep| tree_item(graph, dep, availability, seen))
+        .collect();
+
+    ListItem::new(vec![
+        DocumentNode::paragraph(spans),
+        DocumentNode::list(children),
+    ])
+}
+
+fn why_document<'a>(graph: &DependencyGraph, target: &str) -> (Document<'a>, bool) {
+    let chains = graph.why(target);
+    if chains.is_empty() {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![
+                Span::strong(target.to_string()),
+                Span::plain(" is not a dependency of any workspace crate."),
+            ])]),
+            true,
+        );
+    }
+
+    let items = chains
+        .into_iter()
+        .map(|chain| {
+            let mut spans = vec![];
+            for (i, name) in chain.into_iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::plain(" → "));
+                }
+                spans.push(Span::strong(name.to_string()).with_path(name.to_string()));
+            }
+            ListItem::new(vec![DocumentNode::paragraph(spans)])
+        })
+        .collect();
+
+    (
+        Document::from(vec![
+            DocumentNode::Heading {
+                level: HeadingLevel::Title,
+                spans: vec![Span::plain(format!("Why {target} is a dependency:"))],
+            },
+            DocumentNode::list(items),
+        ]),
+        false,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> DependencyGraph {
+        let edges = BTreeMap::from([
+            ("app", vec!["serde", "tokio"]),
+            ("tokio", vec!["mio", "pin-project-lite"]),
+            ("mio", vec!["libc"]),
+            ("serde", vec![]),
+        ]);
+        DependencyGraph::new(vec!["app".into()], |name| {
+            edges
+                .get(name)
+                .into_iter()
+                .flatten()
+                .map(|dep| dep.to_string())
+                .collect()
+        })
+    }
+
+    #[test]
+    fn why_finds_the_shortest_chain_from_each_root() {
+        let graph = graph();
+        assert_eq!(graph.why("libc"), [["app", "tokio", "mio", "libc"]]);
+        assert_eq!(
+            graph.why("pin_project_lite"),
+            [["app", "tokio", "pin-project-lite"]]
+        );
+        assert!(graph.why("rand").is_empty());
+    }
+
+    #[test]
+    fn dot_has_a_node_per_crate_and_an_edge_per_dependency() {
+        let dot = graph().to_dot(|_| (String::from("1.0.0"), Availability::Missing));
+        assert!(dot.starts_with("digraph dependencies {"));
+        assert!(dot.contains("\"libc\" [label=\"libc 1.0.0\\nmissing\", fillcolor=lightgray];"));
+        assert!(dot.contains("\"tokio\" -> \"mio\";"));
+        assert_eq!(dot.matches(" -> ").count(), 5);
+    }
+
+    #[test]
+    fn dot_lines_are_not_wrapped() {
+        use crate::render_context::RenderContext;
+        use crate::renderer::{OutputMode, render};
+
+        let long = "a-crate-with-a-name-long-enough-that-an-edge-to-it-is-wider-than-a-terminal";
+        let graph = DependencyGraph::new(vec!["app".into()], |name| match name {
+            "app" => vec![long.to_string(), "serde".to_string()],
+            _ => vec![],
+        });
+
+        let document = dot_document(&graph, |_| (String::from("1.0.0"), Availability::Missing));
+        let mut output = String::new();
+        let render_context = RenderContext::new()
+            .with_output_mode(OutputMode::Plain)
+            .with_terminal_width(80);
+        render(&document, &render_context, &mut output).unwrap();
+
+        let edges = output
+            .lines()
+            .filter_map(|line| line.trim().strip_suffix(';')?.split_once(" -> "))
+            .map(|(from, to)| (from.trim_matches('"'), to.trim_matches('"')))
+            .collect::<Vec<_>>();
+        assert_eq!(edges, [("app", long), ("app", "serde")], "{output}");
+        assert_eq!(
+            output.trim_end(),
+            graph.to_dot(|_| (String::from("1.0.0"), Availability::Missing))
+        );
+    }
+}
@@ -1,57 +1,365 @@
-use ferritin_common::DocRef;
-use rustdoc_types::Item;
+use ferritin_common::{DocRef, ResolutionTrace};
+use rustdoc_types::{Item, ItemKind};
+use semver::VersionReq;
+use serde_json::{Value, json};
+use std::convert::Infallible;
 
+use crate::commands::resolve::{best_suggestions, kind_name};
+use crate::format_context::FormatContext;
 use crate::request::Request;
 use crate::styled_string::{Document, DocumentNode, ListItem, Span};
 
 pub(crate) fn execute<'a>(
     request: &'a Request,
     path: &str,
-    source: bool,
-    recursive: bool,
+    format_context: &FormatContext,
+    explain_resolution: bool,
 ) -> (Document<'a>, bool, Option<DocRef<'a, Item>>) {
-    request
-        .format_context()
-        .set_include_source(source)
-        .set_recursive(recursive);
+    let mut nodes = vec![];
+    let Ok(item) = stream(
+        request,
+        path,
+        format_context,
+        explain_resolution,
+        &mut |node| {
+            nodes.push(node);
+            Ok::<_, Infallible>(())
+        },
+    );
+    (Document::from(nodes), item.is_none(), item)
+}
+
+/// Look up `path`, passing the nodes of its page to `emit` as they are formatted
+///
+/// Returns the item that was found, or `None` once the nodes telling why it wasn't have been
+/// passed on. The first error from `emit` stops formatting and is returned.
+pub(crate) fn stream<'a, E>(
+    request: &'a Request,
+    path: &str,
+    format_context: &FormatContext,
+    explain_resolution: bool,
+    emit: &mut impl FnMut(DocumentNode<'a>) -> Result<(), E>,
+) -> Result<Option<DocRef<'a, Item>>, E> {
+    let dependency_path = dependency_line_path(path);
+    let path = dependency_path.as_deref().unwrap_or(path);
 
     let mut suggestions = vec![];
+    let mut trace = ResolutionTrace::new();
     log::info!("Getting {path}...");
 
-    match request.resolve_path(path, &mut suggestions) {
+    let resolved = if explain_resolution {
+        request.resolve_path_traced(path, &mut suggestions, &mut trace)
+    } else {
+        request.resolve_path(path, &mut suggestions)
+    };
+
+    match resolved {
         Some(item) => {
             if let Some(name) = item.name() {
                 log::info!("Resolved {name}");
             }
             let start = std::time::Instant::now();
-            let doc_nodes = request.format_item(item);
+            if let Some(note) = method_note(request, path, item) {
+                emit(note)?;
+            }
+            request.format_item_streamed(item, format_context, emit)?;
             let format_elapsed = start.elapsed();
             if let Some(name) = item.name() {
                 log::debug!("⏱️ Formatted {name} in {:?}", format_elapsed);
             }
-            (Document::from(doc_nodes), false, Some(item))
+            Ok(Some(item))
         }
         None => {
             let mut nodes = vec![DocumentNode::paragraph(vec![Span::plain(format!(
                 "Could not find '{path}'",
             ))])];
 
+            let crate_name = crate_name(path);
+            if request
+                .docsrs_source()
+                .is_some_and(|docsrs| docsrs.is_offline())
+                && request
+                    .lookup_crate(crate_name, &VersionReq::STAR)
+                    .is_none()
+            {
+                nodes.push(DocumentNode::paragraph(vec![
+                    Span::plain(format!(
+                        "{crate_name} isn't available locally or in the docs.rs cache, and \
+                         ferritin is offline. Run once without "
+                    )),
+                    Span::inline_code("--offline"),
+                    Span::plain(" to download it."),
+                ]));
+            }
+
             if !suggestions.is_empty() {
                 nodes.push(DocumentNode::paragraph(vec![Span::plain("Did you mean:")]));
-                let items = suggestions
-                    .iter()
-                    .take(5)
-                    .map(|s| {
-                        ListItem::new(vec![DocumentNode::paragraph(vec![
-                            Span::plain(s.path().to_string()).with_target(s.item().copied()),
-                        ])])
+                let items = best_suggestions(&suggestions)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, s)| {
+                        let mut spans =
+                            vec![Span::plain(s.path().to_string()).with_target(s.item().copied())];
+                        // The best match's summary often answers the question without another
+                        // lookup
+                        if index == 0
+                            && let Some(summary) = s.summary()
+                        {
+                            spans.push(Span::plain(format!(": {summary}")));
+                        }
+                        ListItem::new(vec![DocumentNode::paragraph(spans)])
                     })
                     .collect();
 
                 nodes.push(DocumentNode::List { items });
             }
 
-            (Document::from(nodes), true, None)
+            if explain_resolution && !trace.is_empty() {
+                let steps = trace
+                    .steps()
+                    .iter()
+                    .map(|step| {
+                        ListItem::new(vec![DocumentNode::paragraph(vec![Span::plain(
+                            step.to_string(),
+                        )])])
+                    })
+                    .collect();
+
+                nodes.push(DocumentNode::section(
+                    vec![Span::plain("Resolution steps")],
+                    vec![DocumentNode::List { items: steps }],
+                ));
+            }
+
+            nodes.into_iter().try_for_each(emit)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Where a method reached through a trait impl or `Deref` actually lives, like "`len` is
+/// defined on `str`, which `String` dereferences to", when `path` names it on a type that
+/// doesn't define it
+fn method_note<'a>(
+    request: &'a Request,
+    path: &str,
+    item: DocRef<'a, Item>,
+) -> Option<DocumentNode<'a>> {
+    if item.kind() != ItemKind::Function {
+        return None;
+    }
+    let name = item.name()?;
+    let (parent_path, _) = path.rsplit_once("::")?;
+    let parent = request.find_path(parent_path)?;
+    if parent.child_items().any(|child| child.name() == Some(name)) {
+        return None;
+    }
+    let method_match = parent.find_method(name)?;
+    if method_match.method().id != item.id {
+        return None;
+    }
+
+    let type_name = parent.name().unwrap_or(parent_path);
+    let owner = method_match
+        .derefs()
+        .last()
+        .and_then(|target| target.name())
+        .unwrap_or(type_name);
+
+    let mut spans = vec![Span::inline_code(name.to_string())];
+    match method_match.trait_name() {
+        Some(trait_name) => {
+            spans.push(Span::plain(" comes from "));
+            spans.push(Span::inline_code(owner.to_string()));
+            spans.push(Span::plain("'s implementation of "));
+            spans.push(Span::inline_code(trait_name.to_string()));
+        }
+        None => {
+            spans.push(Span::plain(" is defined on "));
+            spans.push(Span::inline_code(owner.to_string()));
+        }
+    }
+    if !method_match.derefs().is_empty() {
+        spans.push(Span::plain(", which "));
+        spans.push(Span::inline_code(type_name.to_string()));
+        spans.push(Span::plain(" dereferences to"));
+    }
+    Some(DocumentNode::paragraph(spans))
+}
+
+/// Why a path doesn't resolve, which `get` tells scripts with its exit code and, with `--json`,
+/// an error object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Failure {
+    /// Nothing in the crate has this path
+    NotFound,
+    /// The crate the path starts with isn't available locally or from docs.rs
+    CrateUnavailable,
+}
+
+impl Failure {
+    /// The exit code for this failure. Other failures exit with 1, and clap exits with 2 for a
+    /// command line it can't parse.
+    pub(crate) fn exit_code(self) -> u8 {
+        match self {
+            Failure::NotFound => 3,
+            Failure::CrateUnavailable => 4,
+        }
+    }
+
+    /// The name of this failure in JSON error objects
+    fn code(self) -> &'static str {
+        match self {
+            Failure::NotFound => "not_found",
+            Failure::CrateUnavailable => "crate_unavailable",
+        }
+    }
+}
+
+/// Why `path` doesn't resolve, or `None` if it does
+pub(crate) fn failure(request: &Request, path: &str) -> Option<Failure> {
+    failure_json(request, path).map(|(failure, _)| failure)
+}
+
+/// Why `path` doesn't resolve with the closest paths that do, as a JSON error object like
+///
+/// ```json
+/// {"code": "not_found", "exit_code": 3, "message": "Could not find 'std::vec::Vecc'",
+///  "suggestions": [{"path": "std::vec::Vec", "discriminated_path": "std::vec::struct@Vec",
+///                   "kind": "struct", "score": 0.98,
+///                   "summary": "A contiguous growable array type, written as `Vec<T>`..."}]}
+/// ```
+///
+/// or `None` if it resolves
+pub(crate) fn failure_json(request: &Request, path: &str) -> Option<(Failure, Value)> {
+    let dependency_path = dependency_line_path(path);
+    let path = dependency_path.as_deref().unwrap_or(path);
+
+    let mut suggestions = vec![];
+    if request.resolve_path(path, &mut suggestions).is_some() {
+        return None;
+    }
+
+    // A path of one segment may be a name to look for rather than a crate
+    let crate_name = crate_name(path);
+    let (failure, message) = if crate_name != path
+        && request
+            .lookup_crate(crate_name, &VersionReq::STAR)
+            .is_none()
+    {
+        (
+            Failure::CrateUnavailable,
+            format!("{crate_name} isn't available locally or from docs.rs"),
+        )
+    } else {
+        (Failure::NotFound, format!("Could not find '{path}'"))
+    };
+
+    let suggestions = best_suggestions(&suggestions)
+        .into_iter()
+        .map(|suggestion| {
+            let item = suggestion.item();
+            json!({
+                "path": suggestion.path(),
+                "discriminated_path": item.and_then(|item| item.discriminated_path()),
+                "kind": item.map(|item| kind_name(item.kind())),
+                "score": suggestion.score(),
+                "summary": suggestion.summary(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Some((
+        failure,
+        json!({
+            "code": failure.code(),
+            "exit_code": failure.exit_code(),
+            "message": message,
+            "suggestions": suggestions,
+        }),
+    ))
+}
+
+/// The name of the crate a path starts with, without a version
+fn crate_name(path: &str) -> &str {
+    let crate_name = path.split("::").next().unwrap_or(path);
+    crate_name.split('@').next().unwrap_or(crate_name)
+}
+
+/// The crate path for a dependency line pasted from a Cargo.toml
+///
+/// `serde_json = "1.0"` becomes `serde_json@1.0`, and a renamed dependency like
+/// `json = { package = "serde_json", version = "1" }` becomes `serde_json@1`. Anything that
+/// isn't shaped like a dependency line is left alone.
+fn dependency_line_path(line: &str) -> Option<String> {
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim().trim_matches('"');
+    let key = key.strip_suffix(".workspace").unwrap_or(key);
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+
+    let value = value.trim();
+    let (name, version) = if let Some(table) = value.strip_prefix('{') {
+        (
+            inline_table_string(table, "package").unwrap_or(key),
+            inline_table_string(table, "version"),
+        )
+    } else {
+        (key, quoted(value))
+    };
+
+    Some(
+        match version.filter(|version| VersionReq::parse(version).is_ok()) {
+            Some(version) => format!("{name}@{version}"),
+            None => name.to_string(),
+        },
+    )
+}
+
+/// The string value for `key` in the body of an inline table like `version = "1", features = []`
+fn inline_table_string<'a>(table: &'a str, key: &str) -> Option<&'a str> {
+    table.split(',').find_map(|entry| {
+        let (entry_key, value) = entry.split_once('=')?;
+        if entry_key.trim() == key {
+            quoted(value.trim())
+        } else {
+            None
         }
+    })
+}
+
+/// The contents of a string that starts with a double quote
+fn quoted(value: &str) -> Option<&str> {
+    value.strip_prefix('"')?.split('"').next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dependency_lines_become_crate_paths() {
+        assert_eq!(
+            dependency_line_path(r#"serde_json = "1.0""#).as_deref(),
+            Some("serde_json@1.0")
+        );
+        assert_eq!(
+            dependency_line_path(
+                r#"json = { package = "serde_json", version = "1", features = ["std", "alloc"] }"#
+            )
+            .as_deref(),
+            Some("serde_json@1")
+        );
+        assert_eq!(
+            dependency_line_path("tokio.workspace = true").as_deref(),
+            Some("tokio")
+        );
+        assert_eq!(dependency_line_path("serde_json"), None);
+        assert_eq!(dependency_line_path("std::vec::Vec"), None);
     }
 }
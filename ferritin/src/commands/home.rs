@@ -0,0 +1,112 @@
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span, TuiAction};
+use crate::workspace_history::WorkspaceHistory;
+
+/// The start page shown when the interactive browser is opened without a path
+///
+/// Lists the workspace's own crates, recently viewed items and saved searches, so that common
+/// starting points are one click away.
+pub(crate) fn execute<'a>(request: &'a Request) -> Document<'a> {
+    let mut nodes = vec![
+        DocumentNode::Heading {
+            level: HeadingLevel::Title,
+            spans: vec![Span::plain("ferritin")],
+        },
+        DocumentNode::paragraph(vec![
+            Span::plain("Press "),
+            Span::strong("s"),
+            Span::plain(" to search, "),
+            Span::strong("g"),
+            Span::plain(" to go to a path, "),
+            Span::strong("l"),
+            Span::plain(" to list all crates or "),
+            Span::strong("?"),
+            Span::plain(" for help."),
+        ]),
+    ];
+
+    let mut workspace_crates = request
+        .list_available_crates()
+        .filter(|crate_info| crate_info.provenance().is_workspace())
+        .map(|crate_info| crate_info.name().to_string())
+        .collect::<Vec<_>>();
+    workspace_crates.sort();
+
+    if workspace_crates.is_empty() {
+        nodes.push(DocumentNode::paragraph(vec![Span::plain(
+            "No Rust project detected. Standard library and docs.rs crates are still available.",
+        )]));
+    } else {
+        push_section(
+            &mut nodes,
+            "Workspace crates:",
+            workspace_crates
+                .into_iter()
+                .map(|name| vec![Span::strong(name.clone()).with_path(name)])
+                .collect(),
+        );
+    }
+
+    let workspace_history = WorkspaceHistory::for_request(request);
+
+    push_section(
+        &mut nodes,
+        "Recently viewed:",
+        workspace_history
+            .viewed()
+            .iter()
+            .rev()
+            .map(|path| {
+                vec![
+                    Span::plain(path.clone()).with_action(TuiAction::NavigateToPath {
+                        path: path.clone().into(),
+                        url: None,
+                    }),
+                ]
+            })
+            .collect(),
+    );
+
+    // Bookmarks are searches saved with `S`
+    push_section(
+        &mut nodes,
+        "Saved searches:",
+        workspace_history
+            .saved()
+            .iter()
+            .map(|saved| {
+                vec![
+                    Span::strong(saved.name.clone()).with_action(TuiAction::Search {
+                        query: saved.query.clone().into(),
+                        crate_name: saved.crate_name.clone().map(Into::into),
+                    }),
+                    Span::plain(format!(
+                        " \"{}\" in {}",
+                        saved.query,
+                        saved.crate_name.as_deref().unwrap_or("all crates")
+                    )),
+                ]
+            })
+            .collect(),
+    );
+
+    Document::from(nodes)
+}
+
+/// Append a section heading and a list with one paragraph per entry, unless there are none
+fn push_section<'a>(nodes: &mut Vec<DocumentNode<'a>>, heading: &str, entries: Vec<Vec<Span<'a>>>) {
+    if entries.is_empty() {
+        return;
+    }
+
+    nodes.push(DocumentNode::Heading {
+        level: HeadingLevel::Section,
+        spans: vec![Span::plain(heading.to_string())],
+    });
+    nodes.push(DocumentNode::List {
+        items: entries
+            .into_iter()
+            .map(|spans| ListItem::new(vec![DocumentNode::paragraph(spans)]))
+            .collect(),
+    });
+}
@@ -1,7 +1,115 @@
+use super::cache::format_size;
+use super::deps::{Availability, docs_availability};
 use crate::request::Request;
-use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, ShowWhen, Span};
+use crate::styled_string::{
+    Document, DocumentNode, HeadingLevel, ListItem, ShowWhen, Span, TuiAction,
+};
+use crate::workspace_history::{VersionChange, WorkspaceHistory};
+use ferritin_common::sources::{CacheEntry, CrateProvenance};
+use ferritin_common::{CrateInfo, CrateName};
+use serde_json::json;
+use std::cmp::Reverse;
 
-pub(crate) fn execute<'a>(request: &'a Request) -> (Document<'a>, bool, Option<&'a str>) {
+/// Which crates `ferritin list --filter` keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ListFilter {
+    /// Crates declared in `[dev-dependencies]`
+    Dev,
+    /// Crates in the workspace
+    Workspace,
+    /// Crates whose docs come from docs.rs
+    Docsrs,
+    /// The standard library crates
+    Std,
+}
+
+/// The order `ferritin list --sort` lists crates in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub(crate) enum ListSort {
+    #[default]
+    Name,
+    /// Oldest version first
+    Version,
+    /// Largest rustdoc JSON first
+    Size,
+}
+
+/// How `ferritin list` picks, orders and prints crates
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ListOptions {
+    pub filter: Option<ListFilter>,
+    pub sort: ListSort,
+    pub json: bool,
+}
+
+/// A short name for where a crate's docs come from
+pub(crate) fn provenance_label(provenance: CrateProvenance) -> &'static str {
+    match provenance {
+        CrateProvenance::Workspace => "workspace",
+        CrateProvenance::LocalDependency => "dependency",
+        CrateProvenance::Std => "std",
+        CrateProvenance::DocsRs => "docs.rs",
+    }
+}
+
+/// One line of the list: a crate, or one more version of a crate in the dependency graph
+/// more than once
+struct Entry<'a> {
+    /// What to go to for the crate, like `serde` or `serde@1.0.100`
+    path: String,
+    crate_info: &'a CrateInfo,
+    docs: Availability,
+    /// Size of the rustdoc JSON, when it's on disk and asked for
+    size: Option<u64>,
+}
+
+impl ListFilter {
+    fn keeps(self, entry: &Entry<'_>) -> bool {
+        let provenance = entry.crate_info.provenance();
+        match self {
+            ListFilter::Dev => entry
+                .crate_info
+                .dependency_scopes()
+                .iter()
+                .any(|scope| scope.kind().is_dev()),
+            ListFilter::Workspace => provenance.is_workspace(),
+            ListFilter::Docsrs => {
+                provenance.is_docs_rs() || entry.docs == Availability::DocsRsCache
+            }
+            ListFilter::Std => provenance.is_std(),
+        }
+    }
+}
+
+/// Size of the rustdoc JSON that `docs` says a crate has, looked up in the docs.rs cache's
+/// `cache_entries` for downloaded docs
+fn docs_size(
+    crate_info: &CrateInfo,
+    docs: Availability,
+    cache_entries: &[CacheEntry],
+) -> Option<u64> {
+    let path = match docs {
+        Availability::Local => crate_info.json_path()?.to_path_buf(),
+        Availability::DocsRsCache => {
+            let name = CrateName::from(crate_info.name());
+            cache_entries
+                .iter()
+                .find(|entry| {
+                    CrateName::from(entry.crate_name.as_str()) == name
+                        && Some(&entry.version) == crate_info.version()
+                })?
+                .path
+                .clone()
+        }
+        Availability::Missing => return None,
+    };
+    Some(std::fs::metadata(path).ok()?.len())
+}
+
+pub(crate) fn execute<'a>(
+    request: &'a Request,
+    options: ListOptions,
+) -> (Document<'a>, bool, Option<&'a str>) {
     let mut nodes = vec![DocumentNode::Heading {
         level: HeadingLevel::Title,
         spans: vec![Span::plain("Available crates:")],
@@ -33,13 +141,74 @@ pub(crate) fn execute<'a>(request: &'a Request) -> (Document<'a>, bool, Option<&
         )]));
     }
 
+    // Sizes are only worth reading the docs.rs cache for when they are shown
+    let with_sizes = options.json || options.sort == ListSort::Size;
+    let cache_entries = match request.docsrs_source() {
+        Some(docsrs) if with_sizes => docsrs.cache_entries(),
+        _ => vec![],
+    };
+
+    // Crates that are in the dependency graph at several versions are listed once per version,
+    // with the extra versions linking to `name@version`
+    let mut entries = available_crates
+        .into_iter()
+        .flat_map(|crate_info| {
+            let other_versions = request
+                .local_source()
+                .map_or(&[][..], |local| local.other_versions(crate_info.name()));
+            std::iter::once((crate_info.name().to_string(), crate_info)).chain(
+                other_versions.iter().map(|other| {
+                    let version = other.version().map(|v| format!("@{v}")).unwrap_or_default();
+                    (format!("{}{version}", other.name()), other)
+                }),
+            )
+        })
+        .map(|(path, crate_info)| {
+            let docs = docs_availability(request, crate_info);
+            let size = with_sizes
+                .then(|| docs_size(crate_info, docs, &cache_entries))
+                .flatten();
+            Entry {
+                path,
+                crate_info,
+                docs,
+                size,
+            }
+        })
+        .filter(|entry| options.filter.is_none_or(|filter| filter.keeps(entry)))
+        .collect::<Vec<_>>();
+
+    // Sorting is stable, so crates stay in name order among equals
+    match options.sort {
+        ListSort::Name => {}
+        // Crates without a version, like workspace crates, go last
+        ListSort::Version => entries.sort_by_key(|entry| {
+            (
+                entry.crate_info.version().is_none(),
+                entry.crate_info.version(),
+            )
+        }),
+        ListSort::Size => entries.sort_by_key(|entry| Reverse(entry.size)),
+    }
+
+    if options.json {
+        return (json_document(&entries), false, default_crate);
+    }
+
     // Format all crates uniformly - extract all needed data to avoid lifetime issues
-    for crate_info in available_crates {
+    for Entry {
+        path,
+        crate_info,
+        docs,
+        size,
+    } in entries
+    {
         let crate_name = crate_info.name().to_string();
         let is_default = crate_info.is_default_crate();
         let is_workspace = crate_info.provenance().is_workspace();
         let version = crate_info.version();
         let used_by = crate_info.used_by();
+        let dependency_labels = crate_info.dependency_labels();
         let description = crate_info.description().as_ref().map(|d| d.to_string());
 
         let mut spans = vec![];
@@ -64,6 +233,19 @@ pub(crate) fn execute<'a>(request: &'a Request) -> (Document<'a>, bool, Option<&
                 }
                 spans.push(Span::plain(")"));
             }
+
+            if !dependency_labels.is_empty() {
+                spans.push(Span::plain(format!(" [{}]", dependency_labels.join("; "))));
+            }
+
+            // Std docs come with the toolchain, but a dependency's may need a build or a
+            // download before they can be read
+            if !crate_info.provenance().is_std() {
+                let size = size.map(|size| format!(", {}", format_size(size)));
+                spans.push(Span::plain(" — docs: "));
+                spans.push(Span::emphasis(docs.label()));
+                spans.push(Span::plain(size.unwrap_or_default()));
+            }
         }
 
         if let Some(description) = description {
@@ -73,7 +255,7 @@ pub(crate) fn execute<'a>(request: &'a Request) -> (Document<'a>, bool, Option<&
         }
 
         // Prepend crate name label to spans
-        let mut all_spans = vec![Span::strong(crate_name.clone()).with_path(crate_name)];
+        let mut all_spans = vec![Span::strong(crate_name).with_path(path)];
         if !spans.is_empty() {
             all_spans.push(Span::plain(" "));
             all_spans.extend(spans);
@@ -101,3 +283,128 @@ pub(crate) fn execute<'a>(request: &'a Request) -> (Document<'a>, bool, Option<&
 
     (Document::from(nodes), false, default_crate)
 }
+
+/// The list as a JSON array, with an object per crate
+fn json_document<'a>(entries: &[Entry<'_>]) -> Document<'a> {
+    let crates = entries
+        .iter()
+        .map(|entry| {
+            let crate_info = entry.crate_info;
+            json!({
+                "name": crate_info.name(),
+                "path": entry.path,
+                "version": crate_info.version().map(ToString::to_string),
+                "provenance": provenance_label(crate_info.provenance()),
+                "default": crate_info.is_default_crate(),
+                "used_by": crate_info.used_by(),
+                "dependency_labels": crate_info.dependency_labels(),
+                "docs": entry.docs.label(),
+                "size": entry.size,
+                "description": crate_info.description(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let json = serde_json::to_string_pretty(&crates).unwrap_or_default();
+    // Generated code is written as-is, where a paragraph would be wrapped
+    Document::from(vec![DocumentNode::generated_code(vec![Span::plain(json)])])
+}
+
+/// List dependencies added, removed or updated since the last time this was run, recording
+/// the current versions for next time
+pub(crate) fn changed<'a>(request: &'a Request) -> (Document<'a>, bool) {
+    if request.local_source().is_none() {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(
+                "No Rust project detected, so there are no dependency versions to compare.",
+            )])]),
+            true,
+        );
+    }
+
+    let current = request
+        .list_available_crates()
+        .filter(|crate_info| !crate_info.provenance().is_workspace())
+        .filter_map(|crate_info| {
+            Some((
+                crate_info.name().to_string(),
+                crate_info.version()?.to_string(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let mut history = WorkspaceHistory::for_request(request);
+    let Some(changes) = history.record_versions(current) else {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(
+                "Recorded the current dependency versions. Run this again after Cargo.lock \
+                 changes to see what was updated.",
+            )])]),
+            false,
+        );
+    };
+
+    if changes.is_empty() {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(
+                "No dependencies have changed since the last check.",
+            )])]),
+            false,
+        );
+    }
+
+    let items = changes
+        .into_iter()
+        .map(
+            |VersionChange {
+                 crate_name,
+                 from,
+                 to,
+             }| {
+                let mut spans = vec![];
+                match (from, to) {
+                    (Some(from), Some(to)) => {
+                        spans.push(Span::strong(crate_name.clone()).with_path(crate_name.clone()));
+                        spans.push(Span::plain(format!(" {from} → {to} ")));
+                        spans.push(
+                            Span::plain("[API changes]").with_action(TuiAction::ApiDiff {
+                                crate_name: crate_name.into(),
+                                from: from.into(),
+                            }),
+                        );
+                    }
+                    (None, Some(to)) => {
+                        spans.push(Span::strong(crate_name.clone()).with_path(crate_name));
+                        spans.push(Span::plain(format!(" {to} (added)")));
+                    }
+                    (from, None) => {
+                        spans.push(Span::strong(crate_name));
+                        if let Some(from) = from {
+                            spans.push(Span::plain(format!(" {from}")));
+                        }
+                        spans.push(Span::plain(" (removed)"));
+                    }
+                }
+                ListItem::new(vec![DocumentNode::paragraph(spans)])
+            },
+        )
+        .collect();
+
+    (
+        Document::from(vec![
+            DocumentNode::Heading {
+                level: HeadingLevel::Title,
+                spans: vec![Span::plain("Changed since the last check:")],
+            },
+            DocumentNode::list(items),
+            DocumentNode::Conditional {
+                show_when: ShowWhen::NonInteractive,
+                nodes: vec![DocumentNode::paragraph(vec![
+                    Span::plain("Run "),
+                    Span::inline_code("ferritin api-diff <crate>"),
+                    Span::plain(" to see the public API changes in an updated crate."),
+                ])],
+            },
+        ]),
+        false,
+    )
+}
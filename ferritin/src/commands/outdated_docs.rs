@@ -0,0 +1,196 @@
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span};
+use rustdoc_types::FORMAT_VERSION;
+use semver::Version;
+
+/// Docs on disk that don't match the version in Cargo.lock
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Stale {
+    /// The local build in `target/doc` is of another version, or an old JSON format
+    Local {
+        built: Option<Version>,
+        format_version: u32,
+    },
+    /// The docs.rs cache has other versions of the crate, but not this one
+    DocsRs { cached: Vec<Version> },
+}
+
+impl Stale {
+    fn describe(&self, locked: &Version) -> String {
+        match self {
+            Stale::Local {
+                format_version,
+                built: _,
+            } if *format_version != FORMAT_VERSION => format!(
+                "local build uses rustdoc JSON format {format_version}, ferritin reads \
+                 {FORMAT_VERSION}"
+            ),
+            Stale::Local { built, .. } => match built {
+                Some(built) => format!("local build is of {built}, Cargo.lock has {locked}"),
+                None => format!("local build has no version, Cargo.lock has {locked}"),
+            },
+            Stale::DocsRs { cached } => {
+                let cached = cached
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("docs.rs cache has {cached}, Cargo.lock has {locked}")
+            }
+        }
+    }
+}
+
+/// Whether locally built docs are for `locked` in the format this build of ferritin reads
+fn local_staleness(locked: &Version, built: Option<Version>, format_version: u32) -> Option<Stale> {
+    (format_version != FORMAT_VERSION || built.as_ref() != Some(locked)).then_some(Stale::Local {
+        built,
+        format_version,
+    })
+}
+
+/// Whether the docs.rs cache has other versions of a crate but not `locked`
+fn docsrs_staleness(locked: &Version, cached: Vec<Version>) -> Option<Stale> {
+    (!cached.is_empty() && !cached.contains(locked)).then_some(Stale::DocsRs { cached })
+}
+
+/// Report dependencies whose cached or locally built docs don't match Cargo.lock, and with
+/// `refresh`, rebuild or re-download them
+pub(crate) fn execute<'a>(request: &'a Request, refresh: bool) -> (Document<'a>, bool) {
+    let Some(local_source) = request.local_source() else {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(
+                "No Rust project detected, so there is no Cargo.lock to compare against.",
+            )])]),
+            true,
+        );
+    };
+
+    let mut packages = local_source
+        .packages()
+        .filter(|crate_info| !crate_info.provenance().is_workspace())
+        .filter_map(|crate_info| {
+            Some((
+                crate_info.name(),
+                crate_info.version()?,
+                crate_info.provenance().is_docs_rs(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    packages.sort();
+
+    let mut stale = vec![];
+    for (name, locked, is_pinned) in packages {
+        // Pinned crates are always loaded from docs.rs, so their local build doesn't matter
+        if !is_pinned
+            && let Some(built_docs) = local_source.built_docs(name)
+            && let Some(entry) =
+                local_staleness(locked, built_docs.crate_version, built_docs.format_version)
+        {
+            stale.push((name, locked, entry));
+        }
+
+        if let Some(docsrs) = request.docsrs_source()
+            && let Some(entry) = docsrs_staleness(locked, docsrs.cached_versions(name))
+        {
+            stale.push((name, locked, entry));
+        }
+    }
+
+    if stale.is_empty() {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(
+                "All cached and locally built docs match Cargo.lock.",
+            )])]),
+            false,
+        );
+    }
+
+    let mut is_error = false;
+    let items = stale
+        .into_iter()
+        .map(|(name, locked, entry)| {
+            let mut spans = vec![
+                Span::strong(name.to_string()).with_path(name.to_string()),
+                Span::plain(format!(": {}", entry.describe(locked))),
+            ];
+
+            if refresh {
+                let result = match entry {
+                    Stale::Local { .. } => {
+                        local_source.rebuild(name, Some(locked), request.progress())
+                    }
+                    Stale::DocsRs { .. } => request
+                        .docsrs_source()
+                        .map_or(Ok(false), |docsrs| {
+                            docsrs.download(name, locked, request.progress())
+                        })
+                        .and_then(|found| {
+                            if found {
+                                Ok(())
+                            } else {
+                                Err(anyhow::anyhow!("docs.rs has no docs for {name}@{locked}"))
+                            }
+                        }),
+                };
+
+                match result {
+                    Ok(()) => spans.push(Span::emphasis(" (refreshed)")),
+                    Err(error) => {
+                        is_error = true;
+                        log::error!("Failed to refresh {name}: {error:?}");
+                        spans.push(Span::emphasis(format!(" (refresh failed: {error})")));
+                    }
+                }
+            }
+
+            ListItem::new(vec![DocumentNode::paragraph(spans)])
+        })
+        .collect();
+
+    let mut nodes = vec![
+        DocumentNode::Heading {
+            level: HeadingLevel::Title,
+            spans: vec![Span::plain("Outdated docs:")],
+        },
+        DocumentNode::list(items),
+    ];
+
+    if !refresh {
+        nodes.push(DocumentNode::paragraph(vec![
+            Span::plain("Run with "),
+            Span::inline_code("--refresh"),
+            Span::plain(" to rebuild or re-download them."),
+        ]));
+    }
+
+    (Document::from(nodes), is_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staleness_compares_against_the_locked_version() {
+        let locked = Version::new(1, 0, 210);
+        let old = Version::new(1, 0, 190);
+
+        assert_eq!(
+            local_staleness(&locked, Some(locked.clone()), FORMAT_VERSION),
+            None
+        );
+        assert!(local_staleness(&locked, Some(old.clone()), FORMAT_VERSION).is_some());
+        assert!(local_staleness(&locked, Some(locked.clone()), FORMAT_VERSION - 1).is_some());
+
+        assert_eq!(docsrs_staleness(&locked, vec![]), None);
+        assert_eq!(
+            docsrs_staleness(&locked, vec![locked.clone(), old.clone()]),
+            None
+        );
+        assert_eq!(
+            docsrs_staleness(&locked, vec![old.clone()]),
+            Some(Stale::DocsRs { cached: vec![old] })
+        );
+    }
+}
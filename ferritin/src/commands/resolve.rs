@@ -0,0 +1,172 @@
+use ferritin_common::{DocRef, Suggestion};
+use rustdoc_types::{Item, ItemKind};
+use serde_json::{Value, json};
+
+use crate::generate_docsrs_url::generate_docsrs_url;
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, ListItem, Span};
+
+/// How many "did you mean" suggestions are shown when a path doesn't resolve
+const SUGGESTIONS: usize = 5;
+
+/// Resolve a path without rendering its docs, describing the item it names, or the closest
+/// paths if it names nothing
+pub(crate) fn execute<'a>(request: &'a Request, path: &str, as_json: bool) -> (Document<'a>, bool) {
+    let mut suggestions = vec![];
+    let resolved = request.resolve_path(path, &mut suggestions);
+    let suggestions = best_suggestions(&suggestions);
+    let is_error = resolved.is_none();
+
+    if as_json {
+        let value = json!({
+            "path": path,
+            "item": resolved.map(|item| item_json(request, item)),
+            "suggestions": suggestions
+                .iter()
+                .map(|suggestion| json!({
+                    "path": suggestion.path(),
+                    "score": suggestion.score(),
+                    "summary": suggestion.summary(),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let json = serde_json::to_string_pretty(&value).unwrap_or_default();
+        // Generated code is written as-is, where a paragraph would be wrapped
+        return (
+            Document::from(vec![DocumentNode::generated_code(vec![Span::plain(json)])]),
+            is_error,
+        );
+    }
+
+    let Some(item) = resolved else {
+        let mut nodes = vec![DocumentNode::paragraph(vec![Span::plain(format!(
+            "Could not resolve '{path}'"
+        ))])];
+        if !suggestions.is_empty() {
+            nodes.push(DocumentNode::paragraph(vec![Span::plain("Did you mean:")]));
+            let items = suggestions
+                .iter()
+                .enumerate()
+                .map(|(index, suggestion)| {
+                    let mut spans = vec![
+                        Span::plain(suggestion.path().to_string())
+                            .with_target(suggestion.item().copied()),
+                        Span::plain(format!(" (score {:.2})", suggestion.score())),
+                    ];
+                    // The best match's summary often answers the question without another
+                    // lookup
+                    if index == 0
+                        && let Some(summary) = suggestion.summary()
+                    {
+                        spans.push(Span::plain(format!(": {summary}")));
+                    }
+                    ListItem::new(vec![DocumentNode::paragraph(spans)])
+                })
+                .collect();
+            nodes.push(DocumentNode::list(items));
+        }
+        return (Document::from(nodes), true);
+    };
+
+    let mut fields = vec![(
+        "path",
+        Span::plain(item_path(item).unwrap_or_else(|| path.to_string())).with_target(Some(item)),
+    )];
+    if let Some(discriminated_path) = item.discriminated_path() {
+        fields.push(("discriminated path", Span::inline_code(discriminated_path)));
+    }
+    fields.push(("kind", Span::plain(kind_name(item.kind()))));
+    fields.push(("crate", Span::plain(crate_label(item))));
+    if let Some(source) = source_location(request, item) {
+        fields.push(("source", Span::plain(source)));
+    }
+
+    let items = fields
+        .into_iter()
+        .map(|(label, value)| {
+            ListItem::new(vec![DocumentNode::paragraph(vec![
+                Span::strong(format!("{label}: ")),
+                value,
+            ])])
+        })
+        .collect();
+    (Document::from(vec![DocumentNode::list(items)]), false)
+}
+
+/// What a path resolved to, for `resolve --json` and the rpc `resolve` method
+pub(crate) fn item_json(request: &Request, item: DocRef<'_, Item>) -> Value {
+    let span = item.span.as_ref().map(|span| {
+        json!({
+            "file": request.span_file_path(span).unwrap_or_else(|| span.filename.clone()),
+            "begin": span.begin,
+            "end": span.end,
+        })
+    });
+
+    json!({
+        "name": item.name(),
+        "path": item_path(item),
+        "discriminated_path": item.discriminated_path(),
+        "kind": item.kind(),
+        "crate": item.crate_docs().name(),
+        "version": item.crate_docs().version().map(ToString::to_string),
+        "span": span,
+        "docs": item.docs,
+        "url": generate_docsrs_url(item),
+    })
+}
+
+/// The suggestions worth showing, best first
+pub(crate) fn best_suggestions<'s, 'a>(
+    suggestions: &'s [Suggestion<'a>],
+) -> Vec<&'s Suggestion<'a>> {
+    let mut suggestions = suggestions.iter().collect::<Vec<_>>();
+    suggestions.sort_by(|a, b| b.score().total_cmp(&a.score()));
+    suggestions.truncate(SUGGESTIONS);
+    suggestions
+}
+
+fn item_path(item: DocRef<'_, Item>) -> Option<String> {
+    item.path().map(|path| path.to_string())
+}
+
+/// The kind as rustdoc names it in JSON, like `struct` or `assoc_type`
+pub(crate) fn kind_name(kind: ItemKind) -> String {
+    match serde_json::to_value(kind) {
+        Ok(Value::String(name)) => name,
+        _ => format!("{kind:?}"),
+    }
+}
+
+fn crate_label(item: DocRef<'_, Item>) -> String {
+    let crate_docs = item.crate_docs();
+    match crate_docs.version() {
+        Some(version) => format!("{} {version}", crate_docs.name()),
+        None => crate_docs.name().to_string(),
+    }
+}
+
+/// `file:line:column` of the start of the item's definition
+fn source_location(request: &Request, item: DocRef<'_, Item>) -> Option<String> {
+    let span = item.span.as_ref()?;
+    let file = request
+        .span_file_path(span)
+        .unwrap_or_else(|| span.filename.clone());
+    Some(format!(
+        "{}:{}:{}",
+        file.display(),
+        span.begin.0,
+        span.begin.1
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_names_match_rustdoc_json() {
+        assert_eq!(kind_name(ItemKind::Struct), "struct");
+        assert_eq!(kind_name(ItemKind::AssocType), "assoc_type");
+    }
+}
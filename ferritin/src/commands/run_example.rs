@@ -0,0 +1,523 @@
+//! Compile and run doc examples in a scratch cargo project
+//!
+//! Examples are numbered the same way they appear in rendered docs: every rust code block
+//! in the item's documentation, starting at 1. The example is written to a per-crate
+//! project in the user's [private directory](crate::private_dir) (so repeated runs reuse the
+//! build cache), with the documented crate as a dependency: a path dependency for crates that
+//! are on disk, or a pinned registry dependency for crates fetched from docs.rs. A lock file
+//! in the project keeps two runs from writing over each other's example.
+//!
+//! Like rustdoc, examples marked `ignore` are not built at all, `compile_fail` ones must fail to
+//! compile and `no_run` ones are compiled but not run.
+
+use ferritin_common::DocRef;
+use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
+use rustdoc_types::Item;
+use semver::VersionReq;
+use std::{
+    borrow::Cow,
+    fs::{self, File},
+    io,
+    path::Path,
+    process::{Command, Output, Stdio},
+};
+
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span};
+
+/// A rust code block extracted from documentation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct DocExample {
+    /// Full source, including lines that rustdoc hides from rendered docs
+    pub(crate) code: String,
+    pub(crate) no_run: bool,
+    pub(crate) should_panic: bool,
+    pub(crate) compile_fail: bool,
+    pub(crate) ignore: bool,
+    pub(crate) edition: Option<String>,
+}
+
+/// Outcome of building and running an example
+#[derive(Debug, Clone)]
+pub(crate) struct ExampleRun {
+    /// Whether the outcome matched the example's expectation (e.g. `should_panic` panicked)
+    pub(crate) passed: bool,
+    /// One-line human readable description of what happened
+    pub(crate) summary: String,
+    /// Captured stdout (empty when output was streamed)
+    pub(crate) stdout: String,
+    /// Captured stderr (empty when output was streamed)
+    pub(crate) stderr: String,
+}
+
+/// Extract rust examples from markdown docs, in document order
+///
+/// Uses the same rule as the markdown renderer to decide whether a code block is rust,
+/// so that example numbers line up with the code blocks a user sees.
+pub(crate) fn extract_examples(docs: &str) -> Vec<DocExample> {
+    let mut examples = vec![];
+    let mut current: Option<DocExample> = None;
+
+    for event in Parser::new(docs) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                current = match kind {
+                    CodeBlockKind::Fenced(lang) => parse_lang_string(&lang),
+                    CodeBlockKind::Indented => Some(DocExample::default()),
+                };
+            }
+            Event::Text(text) => {
+                if let Some(example) = &mut current {
+                    example.code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(mut example) = current.take() {
+                    example.code = unhide_lines(&example.code);
+                    examples.push(example);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    examples
+}
+
+/// Parse a fenced code block's info string, returning None for non-rust blocks
+fn parse_lang_string(lang: &str) -> Option<DocExample> {
+    let tokens: Vec<&str> = lang
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .collect();
+
+    match tokens.first().copied() {
+        None
+        | Some(
+            "rust" | "no_run" | "should_panic" | "ignore" | "compile_fail" | "edition2015"
+            | "edition2018" | "edition2021" | "edition2024",
+        ) => {}
+        Some(_) => return None,
+    }
+
+    let mut example = DocExample::default();
+    for token in tokens {
+        match token {
+            "no_run" => example.no_run = true,
+            "should_panic" => example.should_panic = true,
+            "compile_fail" => example.compile_fail = true,
+            "ignore" => example.ignore = true,
+            edition if edition.starts_with("edition") => {
+                example.edition = Some(edition["edition".len()..].to_string());
+            }
+            _ => {}
+        }
+    }
+    Some(example)
+}
+
+/// Restore lines that rustdoc hides from display (`# ` prefix) and unescape `##`
+fn unhide_lines(code: &str) -> String {
+    code.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed == "#" {
+                Cow::Borrowed("")
+            } else if let Some(rest) = trimmed.strip_prefix("# ") {
+                Cow::Borrowed(rest)
+            } else if trimmed.starts_with("##") {
+                // Only the escaping `#` goes, so the line keeps its indentation
+                line.replacen("##", "#", 1).into()
+            } else {
+                line.into()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl DocExample {
+    /// Build a complete program from this example, wrapping it in `fn main` the way rustdoc does
+    pub(crate) fn program_source(&self) -> String {
+        if self.code.contains("fn main") {
+            return format!("#![allow(unused)]\n{}\n", self.code);
+        }
+
+        // Crate-level attributes can't live inside `fn main`
+        let (crate_attrs, body): (Vec<&str>, Vec<&str>) = self
+            .code
+            .lines()
+            .partition(|line| line.trim_start().starts_with("#!["));
+        let crate_attrs = crate_attrs.join("\n");
+        let body = body.join("\n");
+
+        // Examples that end in `Ok::<(), E>(())` use `?` and need a fallible wrapper
+        if body.trim_end().ends_with("(())") {
+            format!(
+                "#![allow(unused)]\n{crate_attrs}\nfn main() {{ fn _inner() -> \
+                 core::result::Result<(), impl core::fmt::Debug> {{\n{body}\n}} \
+                 _inner().unwrap() }}\n"
+            )
+        } else {
+            format!("#![allow(unused)]\n{crate_attrs}\nfn main() {{\n{body}\n}}\n")
+        }
+    }
+}
+
+/// Find the `index`th (1-based) example in an item's documentation
+pub(crate) fn find_example(item: DocRef<'_, Item>, index: usize) -> Result<DocExample, String> {
+    let examples = item
+        .docs
+        .as_deref()
+        .map(extract_examples)
+        .unwrap_or_default();
+
+    let name = item.name().unwrap_or("<unnamed>");
+    match examples.len() {
+        0 => Err(format!("{name} has no rust examples in its documentation")),
+        count => index
+            .checked_sub(1)
+            .and_then(|i| examples.get(i).cloned())
+            .ok_or_else(|| {
+                format!("{name} has {count} example(s); choose a number from 1 to {count}")
+            }),
+    }
+}
+
+/// The scratch cargo project for one example, which [`run_in`] writes out
+#[derive(Debug, Clone)]
+pub(crate) struct ExampleProject {
+    /// The documented crate, which names the project's directory, shared by its examples
+    crate_name: String,
+    /// Cargo.toml, depending on the documented crate
+    manifest: String,
+}
+
+/// The file in a project that [`lock_project`] locks
+const LOCK_FILE: &str = ".ferritin-lock";
+
+/// The scratch cargo project for `example`, depending on `item`'s crate
+///
+/// This only looks up the crate, and writes nothing.
+pub(crate) fn prepare_project(
+    request: &Request,
+    item: DocRef<'_, Item>,
+    example: &DocExample,
+) -> ExampleProject {
+    let crate_docs = item.crate_docs();
+    let edition = example.edition.as_deref().unwrap_or("2021");
+    let mut manifest = format!(
+        "[package]\nname = \"ferritin-example\"\nversion = \"0.0.0\"\nedition = \"{edition}\"\n\
+         publish = false\n\n[workspace]\n\n[dependencies]\n"
+    );
+
+    if !crate_docs.provenance().is_std()
+        && let Some(info) = request.lookup_crate(crate_docs.name(), &VersionReq::STAR)
+    {
+        let name = info.name();
+        if let Some(manifest_path) = info.manifest_path()
+            && let Some(package_dir) = manifest_path.parent()
+        {
+            manifest.push_str(&format!(
+                "{name} = {{ path = {:?} }}\n",
+                package_dir.to_string_lossy()
+            ));
+        } else if let Some(version) = crate_docs.version() {
+            manifest.push_str(&format!("{name} = \"={version}\"\n"));
+        } else {
+            manifest.push_str(&format!("{name} = \"*\"\n"));
+        }
+    }
+
+    ExampleProject {
+        crate_name: crate_docs.name().to_string(),
+        manifest,
+    }
+}
+
+/// Take the lock on the project in `dir`, waiting for any other run to finish with it. The
+/// lock is held until the returned file is dropped.
+fn lock_project(dir: &Path) -> io::Result<File> {
+    let lock = File::create(dir.join(LOCK_FILE))?;
+    lock.lock()?;
+    Ok(lock)
+}
+
+/// Run a cargo subcommand in the scratch project, streaming or capturing its output
+fn cargo(dir: &Path, subcommand: &str, capture: bool) -> io::Result<Output> {
+    let stdio = || {
+        if capture {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        }
+    };
+
+    let mut command = Command::new("cargo");
+    command.arg(subcommand).current_dir(dir);
+    if capture {
+        command.arg("--quiet");
+    }
+    command
+        .stdin(Stdio::null())
+        .stdout(stdio())
+        .stderr(stdio())
+        .output()
+}
+
+/// Build and (unless `ignore`/`no_run`/`compile_fail`) run an example
+///
+/// When `capture` is false, cargo and the example inherit this process's stdout/stderr so
+/// output streams to the terminal as it is produced.
+pub(crate) fn run(
+    request: &Request,
+    item: DocRef<'_, Item>,
+    example: &DocExample,
+    capture: bool,
+) -> io::Result<ExampleRun> {
+    let project = prepare_project(request, item, example);
+    run_in(&project, example, capture)
+}
+
+/// Write `example` into `project`, then build and run it
+///
+/// This doesn't need the docs, so it can happen on another thread than the one with them.
+pub(crate) fn run_in(
+    project: &ExampleProject,
+    example: &DocExample,
+    capture: bool,
+) -> io::Result<ExampleRun> {
+    if example.ignore {
+        return Ok(ExampleRun {
+            passed: true,
+            summary: "not built or run (ignore)".to_string(),
+            stdout: String::new(),
+            stderr: String::new(),
+        });
+    }
+
+    let dir = crate::private_dir::create()?
+        .join("examples")
+        .join(&project.crate_name);
+    fs::create_dir_all(dir.join("src"))?;
+    let _lock = lock_project(&dir)?;
+    fs::write(dir.join("Cargo.toml"), &project.manifest)?;
+    fs::write(dir.join("src").join("main.rs"), example.program_source())?;
+
+    log::info!("Building example in {}", dir.display());
+
+    let build = cargo(&dir, "build", capture)?;
+    let mut stdout = String::from_utf8_lossy(&build.stdout).into_owned();
+    let mut stderr = String::from_utf8_lossy(&build.stderr).into_owned();
+
+    let (passed, summary) = if example.compile_fail {
+        if build.status.success() {
+            (false, "compiled, but was marked compile_fail".to_string())
+        } else {
+            (
+                true,
+                "failed to compile, as expected (compile_fail)".to_string(),
+            )
+        }
+    } else if !build.status.success() {
+        (false, "failed to compile".to_string())
+    } else if example.no_run {
+        (
+            true,
+            "compiled successfully (no_run, so not executed)".to_string(),
+        )
+    } else {
+        log::info!("Running example");
+        let output = cargo(&dir, "run", capture)?;
+        stdout.push_str(&String::from_utf8_lossy(&output.stdout));
+        stderr.push_str(&String::from_utf8_lossy(&output.stderr));
+
+        match (output.status.success(), example.should_panic) {
+            (true, false) => (true, "ran successfully".to_string()),
+            (false, true) => (true, "panicked, as expected (should_panic)".to_string()),
+            (true, true) => (
+                false,
+                "ran successfully, but was marked should_panic".to_string(),
+            ),
+            (false, false) => (false, format!("exited with {}", output.status)),
+        }
+    };
+
+    Ok(ExampleRun {
+        passed,
+        summary,
+        stdout,
+        stderr,
+    })
+}
+
+pub(crate) fn execute<'a>(
+    request: &'a Request,
+    path: &str,
+    index: usize,
+    capture: bool,
+) -> (Document<'a>, bool, Option<DocRef<'a, Item>>) {
+    let mut suggestions = vec![];
+    let Some(item) = request.resolve_path(path, &mut suggestions) else {
+        let mut nodes = vec![DocumentNode::paragraph(vec![Span::plain(format!(
+            "Could not find '{path}'",
+        ))])];
+
+        if !suggestions.is_empty() {
+            nodes.push(DocumentNode::paragraph(vec![Span::plain("Did you mean:")]));
+            let items = suggestions
+                .iter()
+                .take(5)
+                .map(|s| {
+                    ListItem::new(vec![DocumentNode::paragraph(vec![
+                        Span::plain(s.path().to_string()).with_target(s.item().copied()),
+                    ])])
+                })
+                .collect();
+
+            nodes.push(DocumentNode::List { items });
+        }
+
+        return (Document::from(nodes), true, None);
+    };
+
+    let example = match find_example(item, index) {
+        Ok(example) => example,
+        Err(message) => {
+            let nodes = vec![DocumentNode::paragraph(vec![Span::plain(message)])];
+            return (Document::from(nodes), true, Some(item));
+        }
+    };
+
+    let result = run(request, item, &example, capture);
+
+    let title = vec![
+        Span::plain(format!("Example {index} of ")),
+        Span::inline_rust_code(path.to_string()).with_target(Some(item)),
+    ];
+    let mut nodes = vec![DocumentNode::heading(HeadingLevel::Title, title)];
+
+    let is_error = match result {
+        Ok(run) => {
+            if capture {
+                nodes.push(DocumentNode::code_block(Some("rust"), example.code));
+            }
+            if !run.stdout.is_empty() {
+                nodes.push(DocumentNode::section(
+                    vec![Span::strong("stdout")],
+                    vec![DocumentNode::code_block(Some("text"), run.stdout)],
+                ));
+            }
+            if !run.stderr.is_empty() {
+                nodes.push(DocumentNode::section(
+                    vec![Span::strong("stderr")],
+                    vec![DocumentNode::code_block(Some("text"), run.stderr)],
+                ));
+            }
+            nodes.push(DocumentNode::paragraph(vec![
+                Span::plain("Result: "),
+                Span::strong(run.summary),
+            ]));
+            !run.passed
+        }
+        Err(error) => {
+            nodes.push(DocumentNode::paragraph(vec![Span::plain(format!(
+                "Could not run example: {error}"
+            ))]));
+            true
+        }
+    };
+
+    (Document::from(nodes), is_error, Some(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_examples() {
+        let docs = "Intro\n\n```\nlet x = 1;\n```\n\n```text\nnot rust\n```\n\n\
+                    ```should_panic,edition2021\npanic!();\n```";
+        let examples = extract_examples(docs);
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].code, "let x = 1;");
+        assert!(examples[1].should_panic);
+        assert_eq!(examples[1].edition.as_deref(), Some("2021"));
+    }
+
+    #[test]
+    fn test_hidden_lines_are_restored() {
+        let docs =
+            "```\n# use std::fmt;\nlet x = 1;\n#\n## not hidden\n#[derive(Debug)]\nstruct A;\n```";
+        let examples = extract_examples(docs);
+        assert_eq!(
+            examples[0].code,
+            "use std::fmt;\nlet x = 1;\n\n# not hidden\n#[derive(Debug)]\nstruct A;"
+        );
+
+        // An escaped line keeps its indentation
+        let docs = "```\nfn f() {\n    ##[allow(unused)]\n    let x = 1;\n}\n```";
+        assert_eq!(
+            extract_examples(docs)[0].code,
+            "fn f() {\n    #[allow(unused)]\n    let x = 1;\n}"
+        );
+    }
+
+    #[test]
+    fn test_program_source_wraps_main() {
+        let example = DocExample {
+            code: "#![feature(never_type)]\nlet x = 1;".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            example.program_source(),
+            "#![allow(unused)]\n#![feature(never_type)]\nfn main() {\nlet x = 1;\n}\n"
+        );
+
+        let example = DocExample {
+            code: "fn main() {}".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            example.program_source(),
+            "#![allow(unused)]\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_ignored_examples_are_not_built() {
+        let project = ExampleProject {
+            crate_name: format!("ferritin-ignored-{}", std::process::id()),
+            manifest: String::new(),
+        };
+        let example = DocExample {
+            code: "this doesn't compile".into(),
+            ignore: true,
+            ..Default::default()
+        };
+
+        let run = run_in(&project, &example, true).unwrap();
+        assert!(run.passed);
+        assert_eq!(run.summary, "not built or run (ignore)");
+        let dir = crate::private_dir::path()
+            .join("examples")
+            .join(&project.crate_name);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_runs_in_one_project_take_turns() {
+        let dir =
+            std::env::temp_dir().join(format!("ferritin-example-lock-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let first = lock_project(&dir).unwrap();
+        let second = File::open(dir.join(LOCK_FILE)).unwrap();
+        assert!(second.try_lock().is_err());
+        drop(first);
+        assert!(second.try_lock().is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
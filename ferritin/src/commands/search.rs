@@ -1,13 +1,30 @@
+use ferritin_common::Suggestion;
+use ferritin_common::search::{PathPattern, SearchContext};
+
 use crate::request::Request;
 use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span, TruncationLevel};
 
+/// How a search query is matched against items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub(crate) enum SearchMode {
+    /// BM25 over names and documentation
+    #[default]
+    Terms,
+    /// Regular expression over names and paths
+    Regex,
+    /// Glob over names and paths
+    Glob,
+}
+
 pub(crate) fn execute<'a>(
     request: &'a Request,
     query: &str,
     limit: usize,
     crate_: Option<&str>,
+    mode: SearchMode,
+    context: Option<SearchContext<'a>>,
 ) -> (Document<'a>, bool) {
-    log::info!("Searching for {query}");
+    log::info!("Searching for {query} ({mode:?})");
 
     let crate_names: Vec<_> = match crate_ {
         Some(crate_) => vec![crate_],
@@ -17,45 +34,27 @@ pub(crate) fn execute<'a>(
             .collect(),
     };
 
-    // Search using Navigator's built-in search
-    let scored_results = match request.search(query, &crate_names) {
-        Ok(results) => results,
-        Err(suggestions) => {
-            // No crates could be loaded - show suggestions
-            let mut nodes = vec![DocumentNode::paragraph(vec![Span::plain(format!(
-                "No crates could be loaded for search."
-            ))])];
-
-            if !suggestions.is_empty() {
-                nodes.push(DocumentNode::paragraph(vec![Span::plain(
-                    "Did you mean one of these?",
-                )]));
-
-                let items: Vec<_> = suggestions
-                    .into_iter()
-                    .take(5)
-                    .filter(|s| s.score() > 0.8)
-                    .map(|s| {
-                        let mut content = vec![DocumentNode::paragraph(vec![Span::plain(
-                            s.path().to_string(),
-                        )])];
-                        if let Some(item) = s.item() {
-                            content.push(DocumentNode::paragraph(vec![Span::plain(format!(
-                                "({:?})",
-                                item.kind()
-                            ))]));
-                        }
-                        ListItem::new(content)
-                    })
-                    .collect();
-
-                if !items.is_empty() {
-                    nodes.push(DocumentNode::List { items });
-                }
-            }
+    let pattern = match mode {
+        SearchMode::Terms => None,
+        SearchMode::Regex => Some(PathPattern::regex(query)),
+        SearchMode::Glob => Some(PathPattern::glob(query)),
+    };
 
+    match pattern {
+        Some(Ok(pattern)) => return execute_pattern(request, query, &pattern, limit, &crate_names),
+        Some(Err(error)) => {
+            let nodes = vec![DocumentNode::paragraph(vec![Span::plain(format!(
+                "Invalid pattern '{query}': {error}"
+            ))])];
             return (Document::from(nodes), true);
         }
+        None => {}
+    }
+
+    // Search using Navigator's built-in search
+    let scored_results = match request.search(query, &crate_names, context) {
+        Ok(results) => results,
+        Err(suggestions) => return (no_crates_loaded(suggestions), true),
     };
 
     log::info!("Found {} matching items", scored_results.len());
@@ -159,3 +158,110 @@ pub(crate) fn execute<'a>(
 
     (Document::from(nodes), false)
 }
+
+/// Search by the shape of item names and paths rather than documentation terms
+fn execute_pattern<'a>(
+    request: &'a Request,
+    query: &str,
+    pattern: &PathPattern,
+    limit: usize,
+    crate_names: &[&str],
+) -> (Document<'a>, bool) {
+    let matches = match request.search_by_pattern(pattern, crate_names) {
+        Ok(matches) => matches,
+        Err(suggestions) => return (no_crates_loaded(suggestions), true),
+    };
+
+    log::info!("Found {} matching items", matches.len());
+
+    if matches.is_empty() {
+        let doc = Document::from(vec![
+            DocumentNode::Heading {
+                level: HeadingLevel::Title,
+                spans: vec![Span::plain("No results")],
+            },
+            DocumentNode::paragraph(vec![
+                Span::plain("No item names or paths match '"),
+                Span::plain(query.to_string()),
+                Span::plain("'"),
+            ]),
+        ]);
+        return (doc, false);
+    }
+
+    let mut nodes = vec![DocumentNode::Heading {
+        level: HeadingLevel::Title,
+        spans: vec![
+            Span::plain("Items matching '"),
+            Span::emphasis(query.to_string()),
+            Span::plain("'"),
+        ],
+    }];
+
+    let total = matches.len();
+    let list_items = matches
+        .into_iter()
+        .take(limit)
+        .map(|pattern_match| {
+            let item = pattern_match.item;
+            let mut content = vec![DocumentNode::paragraph(vec![
+                Span::plain(pattern_match.path).with_target(Some(item)),
+                Span::plain(format!(" ({:?})", item.kind())),
+            ])];
+
+            if let Some(docs) = request.docs_to_show(item, TruncationLevel::SingleLine) {
+                content.extend(docs);
+            }
+
+            ListItem::new(content)
+        })
+        .collect();
+
+    nodes.push(DocumentNode::List { items: list_items });
+
+    if total > limit {
+        nodes.push(DocumentNode::paragraph(vec![Span::plain(format!(
+            "{} more matches not shown (use --limit to see more)",
+            total - limit
+        ))]));
+    }
+
+    (Document::from(nodes), false)
+}
+
+/// Document explaining that no crates could be loaded, with suggestions
+fn no_crates_loaded<'a>(suggestions: Vec<Suggestion<'a>>) -> Document<'a> {
+    let mut nodes = vec![DocumentNode::paragraph(vec![Span::plain(
+        "No crates could be loaded for search.",
+    )])];
+
+    if !suggestions.is_empty() {
+        nodes.push(DocumentNode::paragraph(vec![Span::plain(
+            "Did you mean one of these?",
+        )]));
+
+        let items: Vec<_> = suggestions
+            .into_iter()
+            .take(5)
+            .filter(|s| s.score() > 0.8)
+            .map(|s| {
+                let mut content = vec![DocumentNode::paragraph(vec![Span::plain(
+                    s.path().to_string(),
+                )])];
+                if let Some(item) = s.item() {
+                    content.push(DocumentNode::paragraph(vec![Span::plain(format!(
+                        "({:?})",
+                        item.kind()
+                    ))]));
+                }
+                ListItem::new(content)
+            })
+            .collect();
+
+        if !items.is_empty() {
+            nodes.push(DocumentNode::List { items });
+        }
+    }
+
+    Document::from(nodes)
+}
@@ -0,0 +1,61 @@
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span};
+use ferritin_common::sources::StdSource;
+
+/// List installed rustup toolchains and whether each has the std docs that `--toolchain` needs
+pub(crate) fn execute<'a>() -> (Document<'a>, bool) {
+    let toolchains = StdSource::installed_toolchains();
+    if toolchains.is_empty() {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![Span::plain(
+                "No rustup toolchains were found. Std docs are loaded from rustup's \
+                 rust-docs-json component, so rustup needs to be installed.",
+            )])]),
+            true,
+        );
+    }
+
+    let items = toolchains
+        .iter()
+        .map(|toolchain| {
+            let mut spans = vec![Span::strong(toolchain.name.clone())];
+            if toolchain.is_default {
+                spans.push(Span::plain(" (default)"));
+            }
+            if toolchain.has_docs_json {
+                spans.push(Span::plain(" — std docs available"));
+            } else {
+                spans.push(Span::plain(" — no std docs; install them with "));
+                spans.push(Span::inline_code(format!(
+                    "rustup component add rust-docs-json --toolchain {}",
+                    toolchain.name
+                )));
+            }
+            ListItem::new(vec![DocumentNode::paragraph(spans)])
+        })
+        .collect();
+
+    let nodes = vec![
+        DocumentNode::Heading {
+            level: HeadingLevel::Title,
+            spans: vec![Span::plain("Installed toolchains:")],
+        },
+        DocumentNode::list(items),
+        DocumentNode::paragraph(vec![
+            Span::plain("Choose one with "),
+            Span::inline_code("--toolchain"),
+            Span::plain(
+                ". The rust-docs-json component is only published for nightly toolchains, so to \
+                 browse the std of an older release such as your MSRV, install a nightly from \
+                 that release's cycle with ",
+            ),
+            Span::inline_code(
+                "rustup toolchain install nightly-YYYY-MM-DD --component rust-docs-json",
+            ),
+            Span::plain(", or hide newer items with "),
+            Span::inline_code("--min-version"),
+            Span::plain("."),
+        ]),
+    ];
+
+    (Document::from(nodes), false)
+}
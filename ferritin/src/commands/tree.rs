@@ -0,0 +1,250 @@
+use ferritin_common::DocRef;
+use rustdoc_types::{Item, ItemKind, Visibility};
+
+use crate::commands::resolve::best_suggestions;
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, HeadingLevel, ListItem, Span};
+
+/// The kinds of item in the tree, in the order they're listed within a module, with the
+/// glyph that marks them and their names in a module's counts
+const KINDS: &[(ItemKind, &str, &str, &str)] = &[
+    (ItemKind::Module, "▣", "module", "modules"),
+    (ItemKind::Struct, "◆", "struct", "structs"),
+    (ItemKind::Enum, "◈", "enum", "enums"),
+    (ItemKind::Union, "◇", "union", "unions"),
+    (ItemKind::Trait, "◉", "trait", "traits"),
+    (ItemKind::TypeAlias, "≡", "type alias", "type aliases"),
+    (ItemKind::Function, "ƒ", "function", "functions"),
+    (ItemKind::Constant, "●", "constant", "constants"),
+    (ItemKind::Static, "○", "static", "statics"),
+    (ItemKind::Macro, "!", "macro", "macros"),
+    (
+        ItemKind::ProcAttribute,
+        "!",
+        "attribute macro",
+        "attribute macros",
+    ),
+    (ItemKind::ProcDerive, "!", "derive macro", "derive macros"),
+];
+
+/// An item in the tree, with the items of a module under it
+struct TreeNode<'a> {
+    item: DocRef<'a, Item>,
+    /// Where the item's kind is in [`KINDS`]
+    kind: usize,
+    children: Vec<TreeNode<'a>>,
+}
+
+/// Show the public modules and items of a crate or module as a tree, with how many items of
+/// each kind every module holds. With `modules_only`, the tree shows only the modules, still
+/// with the counts of everything in them.
+pub(crate) fn execute<'a>(
+    request: &'a Request,
+    path: &str,
+    modules_only: bool,
+) -> (Document<'a>, bool) {
+    let mut suggestions = vec![];
+    let Some(item) = request.resolve_path(path, &mut suggestions) else {
+        let mut nodes = vec![DocumentNode::paragraph(vec![Span::plain(format!(
+            "Could not find '{path}'"
+        ))])];
+
+        if !suggestions.is_empty() {
+            nodes.push(DocumentNode::paragraph(vec![Span::plain("Did you mean:")]));
+            let items = best_suggestions(&suggestions)
+                .into_iter()
+                .map(|s| {
+                    ListItem::new(vec![DocumentNode::paragraph(vec![
+                        Span::plain(s.path().to_string()).with_target(s.item().copied()),
+                    ])])
+                })
+                .collect();
+
+            nodes.push(DocumentNode::List { items });
+        }
+
+        return (Document::from(nodes), true);
+    };
+
+    if item.kind() != ItemKind::Module {
+        return (
+            Document::from(vec![DocumentNode::paragraph(vec![
+                Span::plain(format!("{path} is not a crate or module. Try ")),
+                Span::inline_code(format!("ferritin get {path}")),
+                Span::plain(" instead."),
+            ])]),
+            true,
+        );
+    }
+
+    let children = collect(request, item);
+
+    let mut heading =
+        vec![Span::item_name(path.to_string(), ItemKind::Module).with_target(Some(item))];
+    if let Some(counts) = counts(&children) {
+        heading.push(Span::plain(format!(" ({counts})")));
+    }
+
+    let mut nodes = vec![DocumentNode::Heading {
+        level: HeadingLevel::Title,
+        spans: heading,
+    }];
+
+    let items: Vec<_> = children
+        .iter()
+        .filter(|child| !modules_only || is_module(child))
+        .map(|child| tree_item(child, modules_only))
+        .collect();
+    if items.is_empty() {
+        nodes.push(DocumentNode::paragraph(vec![Span::plain(
+            "No public items.",
+        )]));
+        return (Document::from(nodes), false);
+    }
+    nodes.push(DocumentNode::list(items));
+    nodes.push(legend(&children, modules_only));
+
+    (Document::from(nodes), false)
+}
+
+/// The public items of `module`, with the items of each module under it
+///
+/// Modules re-exported from another crate aren't opened.
+fn collect<'a>(request: &'a Request, module: DocRef<'a, Item>) -> Vec<TreeNode<'a>> {
+    let is_listed = |child: DocRef<'a, Item>| {
+        child.name().is_some()
+            && matches!(child.visibility, Visibility::Public | Visibility::Default)
+            && !request.is_newer_than_min_version(child)
+    };
+    let mut nodes = module.item_tree(
+        &|child, _| child.kind() == ItemKind::Module && is_listed(child),
+        &mut |child, mut children| {
+            let kind = KINDS.iter().position(|(kind, ..)| *kind == child.kind())?;
+            if !is_listed(child) {
+                return None;
+            }
+            sort_nodes(&mut children);
+            Some(TreeNode {
+                item: child,
+                kind,
+                children,
+            })
+        },
+    );
+    sort_nodes(&mut nodes);
+    nodes
+}
+
+/// Put the nodes in the order of their kinds, then by name
+fn sort_nodes(nodes: &mut [TreeNode<'_>]) {
+    nodes.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then_with(|| a.item.name().cmp(&b.item.name()))
+    });
+}
+
+fn is_module(node: &TreeNode<'_>) -> bool {
+    KINDS[node.kind].0 == ItemKind::Module
+}
+
+/// A line of the tree, with the first sentence of the item's docs after it and the items of a
+/// module nested under it
+fn tree_item<'a>(node: &TreeNode<'a>, modules_only: bool) -> ListItem<'a> {
+    let (kind, glyph, ..) = KINDS[node.kind];
+    let mut spans = vec![
+        Span::plain(format!("{glyph} ")),
+        Span::item_name(node.item.name().unwrap_or("<unnamed>"), kind).with_target(Some(node.item)),
+    ];
+    if let Some(counts) = counts(&node.children) {
+        spans.push(Span::plain(format!(" ({counts})")));
+    }
+    if let Some(summary) = node.item.docs_summary() {
+        spans.push(Span::comment(format!(" — {summary}")));
+    }
+
+    let mut content = vec![DocumentNode::paragraph(spans)];
+    let children: Vec<_> = node
+        .children
+        .iter()
+        .filter(|child| !modules_only || is_module(child))
+        .map(|child| tree_item(child, modules_only))
+        .collect();
+    if !children.is_empty() {
+        content.push(DocumentNode::list(children));
+    }
+    ListItem::new(content)
+}
+
+/// How many items of each kind are in a module, like "2 modules, 1 struct", or None for an
+/// empty module
+fn counts(children: &[TreeNode<'_>]) -> Option<String> {
+    let mut tally = vec![0; KINDS.len()];
+    for child in children {
+        tally[child.kind] += 1;
+    }
+    let counts = format_counts(&tally);
+    (!counts.is_empty()).then_some(counts)
+}
+
+fn format_counts(tally: &[usize]) -> String {
+    tally
+        .iter()
+        .zip(KINDS)
+        .filter(|(count, _)| **count > 0)
+        .map(|(count, (_, _, singular, plural))| {
+            format!("{count} {}", if *count == 1 { singular } else { plural })
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// What the glyphs of the kinds in the tree stand for
+fn legend<'a>(children: &[TreeNode<'_>], modules_only: bool) -> DocumentNode<'a> {
+    let mut shown = vec![false; KINDS.len()];
+    let mut stack: Vec<&TreeNode<'_>> = children.iter().collect();
+    while let Some(node) = stack.pop() {
+        if modules_only && !is_module(node) {
+            continue;
+        }
+        shown[node.kind] = true;
+        stack.extend(&node.children);
+    }
+
+    let mut spans = vec![];
+    let mut last_glyph = None;
+    for (index, (_, glyph, singular, _)) in KINDS.iter().enumerate() {
+        if !shown[index] {
+            continue;
+        }
+        if !spans.is_empty() {
+            spans.push(Span::plain(if last_glyph == Some(glyph) {
+                ", "
+            } else {
+                "  "
+            }));
+        }
+        if last_glyph != Some(glyph) {
+            spans.push(Span::plain(format!("{glyph} ")));
+        }
+        spans.push(Span::emphasis(*singular));
+        last_glyph = Some(glyph);
+    }
+    DocumentNode::paragraph(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_are_listed_in_kind_order() {
+        let position = |kind| KINDS.iter().position(|(k, ..)| *k == kind).unwrap();
+        let mut tally = vec![0; KINDS.len()];
+        tally[position(ItemKind::Module)] = 2;
+        tally[position(ItemKind::Struct)] = 1;
+        tally[position(ItemKind::Function)] = 3;
+        assert_eq!(format_counts(&tally), "2 modules, 1 struct, 3 functions");
+        assert_eq!(format_counts(&vec![0; KINDS.len()]), "");
+    }
+}
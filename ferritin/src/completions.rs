@@ -0,0 +1,120 @@
+//! Shell completion scripts
+//!
+//! Subcommand names are written into the script. Item paths can't be, so for the arguments of
+//! `get`, `run-example` and `tree` the scripts call the hidden `ferritin __complete <partial>`,
+//! which prints one candidate path per line from the loaded docs.
+
+use crate::Cli;
+use clap::CommandFactory;
+
+/// A shell that `ferritin completions` writes a script for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// The completion script for `shell`
+pub(crate) fn script(shell: Shell) -> String {
+    let template = match shell {
+        Shell::Bash => BASH,
+        Shell::Zsh => ZSH,
+        Shell::Fish => FISH,
+    };
+    template.replace("@SUBCOMMANDS@", &subcommands())
+}
+
+/// The names of the subcommands that aren't hidden, separated by spaces
+fn subcommands() -> String {
+    Cli::command()
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Bash splits words at colons, so the path being completed is read from the line itself and
+/// the part before the last colon is trimmed from each candidate
+const BASH: &str = r#"_ferritin() {
+    local line=${COMP_LINE:0:COMP_POINT}
+    local cur=${line##*[[:space:]]}
+    local subcommand word
+    for word in "${COMP_WORDS[@]:1:COMP_CWORD-1}"; do
+        if [[ $word != -* ]]; then
+            subcommand=$word
+            break
+        fi
+    done
+
+    if [[ -z $subcommand ]]; then
+        COMPREPLY=($(compgen -W "@SUBCOMMANDS@" -- "$cur"))
+        return
+    fi
+
+    case $subcommand in
+        get|run-example|tree)
+            if [[ $cur != -* ]]; then
+                local IFS=$'\n'
+                local colon_prefix=${cur%"${cur##*:}"}
+                compopt -o nospace
+                COMPREPLY=($(ferritin __complete "$cur" 2>/dev/null))
+                COMPREPLY=("${COMPREPLY[@]#"$colon_prefix"}")
+            fi
+            ;;
+    esac
+}
+complete -F _ferritin ferritin
+"#;
+
+const ZSH: &str = r#"#compdef ferritin
+
+_ferritin() {
+    local -a subcommands paths
+    subcommands=(@SUBCOMMANDS@)
+    if (( CURRENT == 2 )); then
+        compadd -a subcommands
+        return
+    fi
+
+    case ${words[2]} in
+        get|run-example|tree)
+            if [[ $PREFIX != -* ]]; then
+                paths=(${(f)"$(ferritin __complete "$PREFIX" 2>/dev/null)"})
+                compadd -S '' -a paths
+            fi
+            ;;
+    esac
+}
+
+compdef _ferritin ferritin
+"#;
+
+const FISH: &str = r#"function __ferritin_complete_path
+    ferritin __complete (commandline -ct) 2>/dev/null
+end
+
+complete -c ferritin -f
+complete -c ferritin -n __fish_use_subcommand -a "@SUBCOMMANDS@"
+complete -c ferritin -n "__fish_seen_subcommand_from get run-example tree" -a "(__ferritin_complete_path)"
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_list_subcommands_and_complete_paths() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let script = script(shell);
+            assert!(!script.contains("@SUBCOMMANDS@"));
+            assert!(script.contains("get"));
+            assert!(script.contains("ferritin __complete"));
+        }
+
+        let subcommands = subcommands();
+        assert!(subcommands.split(' ').any(|name| name == "get"));
+        assert!(!subcommands.split(' ').any(|name| name == "__complete"));
+    }
+}
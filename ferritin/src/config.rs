@@ -0,0 +1,271 @@
+//! Defaults from config files
+//!
+//! Settings are read from `$XDG_CONFIG_HOME/ferritin/config.toml` (`~/.config/ferritin/config.toml`
+//! when that isn't set), and from a `.ferritin.toml` in the project directory or one of its
+//! parents, which overrides the user's file for that workspace. Command line flags and
+//! environment variables override both.
+//!
+//! The same directory holds the user's own `.tmTheme` files under `themes/`, and the theme last
+//! saved in the interactive theme picker, in `theme`.
+//!
+//! ```toml
+//! theme = "Dracula"
+//! light-theme = "Catppuccin Latte"
+//! dark-theme = "Catppuccin Mocha"
+//! format = "plain"
+//! color = "never"
+//! verbosity = "brief"
+//! pager = "less -S"
+//! offline = true
+//! toolchain = "stable"
+//! min-version = "1.74"
+//! decorations = false
+//! images = false
+//! links = "paths"
+//! scrolloff = 3
+//! cache-dir = "~/.cache/ferritin"
+//! docs-rs-url = "https://docs.example.com"
+//!
+//! [keys]
+//! search = "f"
+//! quit = ["q", "C-x"]
+//! ```
+
+use crate::color_depth::ColorChoice;
+use crate::key_bindings::{KeyBindings, Keys};
+use crate::renderer::{Links, OutputMode};
+use crate::verbosity::Verbosity;
+use ferritin_common::sources::DocsRsSource;
+use semver::Version;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The name of the per-workspace config file
+const WORKSPACE_FILE: &str = ".ferritin.toml";
+
+/// The file in the user's config directory holding the theme saved in the theme picker
+const SAVED_THEME_FILE: &str = "theme";
+
+#[derive(Debug, Error)]
+pub(crate) enum ConfigError {
+    #[error("Could not read {0}: {1}")]
+    Read(PathBuf, io::Error),
+    #[error("Could not parse {0}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("{0}")]
+    Keys(String),
+}
+
+/// Settings that are unset where no file or flag gives them
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct Config {
+    /// Syntax highlighting theme name, or path to a .tmTheme file
+    pub(crate) theme: Option<String>,
+    /// Theme for a terminal with a light background, when no theme is chosen
+    pub(crate) light_theme: Option<String>,
+    /// Theme for a terminal with a dark background, when no theme is chosen
+    pub(crate) dark_theme: Option<String>,
+    /// Output format for one-shot mode
+    pub(crate) format: Option<OutputMode>,
+    /// When to color output
+    pub(crate) color: Option<ColorChoice>,
+    /// How much of an item's docs to show
+    pub(crate) verbosity: Option<Verbosity>,
+    /// Command to page long output with. An empty command turns the pager off.
+    pub(crate) pager: Option<String>,
+    /// Never touch the network
+    pub(crate) offline: Option<bool>,
+    /// Rustup toolchain to load std docs from
+    pub(crate) toolchain: Option<String>,
+    /// Hide items stabilized after this Rust version
+    #[serde(deserialize_with = "deserialize_rust_version")]
+    pub(crate) min_version: Option<Version>,
+    /// Emoji, animations and ornamental rules in interactive mode
+    pub(crate) decorations: Option<bool>,
+    /// Images from docs drawn inline in interactive mode, in terminals that can
+    pub(crate) images: Option<bool>,
+    /// What links become in one-shot output
+    pub(crate) links: Option<Links>,
+    /// Rows kept between the focused link and the edge of the screen in interactive mode
+    pub(crate) scrolloff: Option<u16>,
+    /// Where docs downloaded from docs.rs are kept
+    pub(crate) cache_dir: Option<PathBuf>,
+    /// A docs.rs mirror to download rustdoc JSON from
+    pub(crate) docs_rs_url: Option<String>,
+    /// Keys for interactive mode actions, by action name
+    pub(crate) keys: BTreeMap<String, Keys>,
+}
+
+impl Config {
+    /// The user's config with the workspace's config for `project_path` on top
+    pub(crate) fn load(project_path: &Path) -> Result<Self, ConfigError> {
+        let user = match user_config_path() {
+            Some(path) => read(&path)?.unwrap_or_default(),
+            None => Self::default(),
+        };
+
+        for dir in project_path.ancestors() {
+            let path = dir.join(WORKSPACE_FILE);
+            if let Some(workspace) = read(&path)? {
+                return Ok(workspace.or(user));
+            }
+        }
+
+        Ok(user)
+    }
+
+    /// These settings, with any unset ones taken from `fallback`
+    pub(crate) fn or(self, fallback: Self) -> Self {
+        let mut keys = fallback.keys;
+        keys.extend(self.keys);
+        Self {
+            theme: self.theme.or(fallback.theme),
+            light_theme: self.light_theme.or(fallback.light_theme),
+            dark_theme: self.dark_theme.or(fallback.dark_theme),
+            format: self.format.or(fallback.format),
+            color: self.color.or(fallback.color),
+            verbosity: self.verbosity.or(fallback.verbosity),
+            pager: self.pager.or(fallback.pager),
+            offline: self.offline.or(fallback.offline),
+            toolchain: self.toolchain.or(fallback.toolchain),
+            min_version: self.min_version.or(fallback.min_version),
+            decorations: self.decorations.or(fallback.decorations),
+            images: self.images.or(fallback.images),
+            links: self.links.or(fallback.links),
+            scrolloff: self.scrolloff.or(fallback.scrolloff),
+            cache_dir: self.cache_dir.or(fallback.cache_dir),
+            docs_rs_url: self.docs_rs_url.or(fallback.docs_rs_url),
+            keys,
+        }
+    }
+
+    pub(crate) fn key_bindings(&self) -> Result<KeyBindings, ConfigError> {
+        KeyBindings::from_table(&self.keys).map_err(ConfigError::Keys)
+    }
+}
+
+/// A docs.rs source keeping downloads in `cache_dir` (or the default cache) and downloading
+/// from the mirror at `docs_url`, if given
+pub(crate) fn docs_rs_source(
+    cache_dir: Option<&Path>,
+    docs_url: Option<&str>,
+) -> Option<DocsRsSource> {
+    let source = match cache_dir {
+        Some(cache_dir) => DocsRsSource::new(cache_dir.to_path_buf()).ok(),
+        None => DocsRsSource::from_default_cache(),
+    };
+    source.map(|source| source.with_docs_url(docs_url.map(str::to_string)))
+}
+
+/// `$XDG_CONFIG_HOME/ferritin`, or `~/.config/ferritin`
+fn user_config_dir() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home::home_dir().map(|home| home.join(".config")))?;
+    Some(config_dir.join("ferritin"))
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Where the user's own `.tmTheme` files are found
+pub(crate) fn themes_dir() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join("themes"))
+}
+
+/// The theme last saved in the interactive theme picker
+pub(crate) fn saved_theme() -> Option<String> {
+    let saved = std::fs::read_to_string(user_config_dir()?.join(SAVED_THEME_FILE)).ok()?;
+    let saved = saved.trim();
+    (!saved.is_empty()).then(|| saved.to_string())
+}
+
+/// Remember `theme_name` as the theme to start with
+pub(crate) fn save_theme(theme_name: &str) -> io::Result<()> {
+    let dir = user_config_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(SAVED_THEME_FILE), format!("{theme_name}\n"))
+}
+
+/// The config in the file at `path`, or None if there's no such file
+fn read(path: &Path) -> Result<Option<Config>, ConfigError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(ConfigError::Read(path.to_path_buf(), error)),
+    };
+    let mut config: Config =
+        toml::from_str(&contents).map_err(|error| ConfigError::Parse(path.to_path_buf(), error))?;
+
+    // A relative cache directory is relative to the file that names it
+    if let Some(cache_dir) = &config.cache_dir {
+        let expanded = PathBuf::from(&*shellexpand::tilde(&cache_dir.to_string_lossy()));
+        config.cache_dir = Some(match path.parent() {
+            Some(dir) => dir.join(expanded),
+            None => expanded,
+        });
+    }
+
+    log::debug!("Read config from {}", path.display());
+    Ok(Some(config))
+}
+
+fn deserialize_rust_version<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Version>, D::Error> {
+    let version = String::deserialize(deserializer)?;
+    ferritin_common::parse_rust_version(&version)
+        .map(Some)
+        .ok_or_else(|| {
+            serde::de::Error::custom(format!("{version:?} is not a Rust version like 1.70"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_settings_override_user_settings() {
+        let user: Config = toml::from_str(
+            r#"
+            theme = "Dracula"
+            format = "plain"
+            offline = true
+            min-version = "1.70"
+
+            [keys]
+            search = "f"
+            "#,
+        )
+        .unwrap();
+        let workspace: Config = toml::from_str(
+            r#"
+            offline = false
+            verbosity = "brief"
+
+            [keys]
+            quit = "C-x"
+            "#,
+        )
+        .unwrap();
+
+        let config = workspace.or(user);
+        assert_eq!(config.theme.as_deref(), Some("Dracula"));
+        assert_eq!(config.format, Some(OutputMode::Plain));
+        assert_eq!(config.offline, Some(false));
+        assert_eq!(config.verbosity, Some(Verbosity::Brief));
+        assert_eq!(config.min_version, Some(Version::new(1, 70, 0)));
+        assert_eq!(config.keys.len(), 2);
+        assert!(config.key_bindings().is_ok());
+
+        assert!(toml::from_str::<Config>("colour = \"red\"").is_err());
+    }
+}
@@ -0,0 +1,395 @@
+//! A resident process that keeps docs loaded between one-shot commands
+//!
+//! Loading the workspace, std and search indexes takes seconds, and a one-shot `ferritin get`
+//! pays that on every run. `ferritin daemon` loads everything once and listens on a unix socket
+//! named after the project and the options that shape the loaded docs. One-shot `get`, `search`
+//! and `list` first try that socket, and fall back to running in-process when no daemon answers.
+//!
+//! The socket lives in a directory only the user can open, and each side checks that the other
+//! runs as the same user before it trusts what it reads.
+//!
+//! A client sends one line of JSON with its arguments and how to render, and reads back one
+//! line of JSON with the rendered output. The daemon parses the arguments again to find the command
+//! to run, but how to render it comes resolved from the client: flags, the client's environment
+//! and its config files all decide those settings, and the daemon's own would differ.
+
+use crate::renderer::{Links, OutputMode};
+use crate::{Cli, pagination::PagedWriter, render_context::RenderContext};
+use crate::{color_depth::ColorDepth, commands::Commands, config::Config, request::Request};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, BufRead, BufReader, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+/// What a client asks the daemon to run
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct DaemonRequest {
+    /// The client's command line, without the program name
+    pub(crate) args: Vec<String>,
+    /// Output mode, as detected from the client's stdout
+    pub(crate) output_mode: OutputMode,
+    pub(crate) terminal_width: usize,
+    /// Colors the client's terminal can show
+    pub(crate) color_depth: ColorDepth,
+    /// Theme name, or an absolute path to a .tmTheme file
+    pub(crate) theme: String,
+    /// What links become, from the client's flags and config files
+    #[serde(default)]
+    pub(crate) links: Option<Links>,
+    /// Whether paragraphs wrap to `terminal_width`
+    pub(crate) wrap: bool,
+    /// Whether tables get borders and other ornaments
+    pub(crate) decorations: bool,
+    /// The page of oversized output to show, or None for all of it
+    pub(crate) page: Option<NonZeroUsize>,
+}
+
+/// The daemon's answer
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub(crate) struct DaemonReply {
+    /// Rendered output, for the client's stdout
+    pub(crate) output: String,
+    /// A note for the client's stderr, like which lines were left off the page
+    pub(crate) message: Option<String>,
+    pub(crate) success: bool,
+    /// The exit code for a failure that has its own, like a path that isn't found
+    pub(crate) exit_code: Option<u8>,
+}
+
+impl DaemonReply {
+    fn failure(message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// The socket a daemon for this project listens on
+///
+/// Docs loaded with a different toolchain, `--offline`, `--min-version`, `--verbosity`, docs.rs
+/// cache or mirror would answer differently, so each combination gets its own socket, as does
+/// each ferritin version. Sockets live in the user's [private directory](crate::private_dir).
+pub(crate) fn socket_path(project_path: &Path, config: &Config) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    project_root(project_path).hash(&mut hasher);
+    config.toolchain.hash(&mut hasher);
+    config.offline.unwrap_or(false).hash(&mut hasher);
+    config
+        .min_version
+        .as_ref()
+        .map(ToString::to_string)
+        .hash(&mut hasher);
+    config.verbosity.unwrap_or_default().hash(&mut hasher);
+    config.cache_dir.hash(&mut hasher);
+    config.docs_rs_url.hash(&mut hasher);
+
+    crate::private_dir::path().join(format!("daemon-{:016x}.sock", hasher.finish()))
+}
+
+/// The directory that identifies the project at `path`: the nearest one with a Cargo.lock,
+/// which for a workspace member is the workspace root. Finding it doesn't run cargo, so a
+/// client can compute the socket path without the startup cost the daemon is there to avoid.
+fn project_root(path: &Path) -> PathBuf {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let dir = if path.is_file() {
+        path.parent().unwrap_or(&path)
+    } else {
+        &path
+    };
+
+    dir.ancestors()
+        .find(|dir| dir.join("Cargo.lock").is_file())
+        .or_else(|| dir.ancestors().find(|dir| dir.join("Cargo.toml").is_file()))
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+/// Ask the daemon listening on `socket_path` to run `daemon_request`, or None if there is no
+/// daemon to ask
+#[cfg(unix)]
+pub(crate) fn forward(socket_path: &Path, daemon_request: &DaemonRequest) -> Option<DaemonReply> {
+    use std::os::unix::net::UnixStream;
+
+    let stream = match UnixStream::connect(socket_path) {
+        Ok(stream) => stream,
+        Err(error) => {
+            log::debug!("No daemon at {}: {error}", socket_path.display());
+            return None;
+        }
+    };
+    if let Err(error) = socket_path
+        .parent()
+        .map_or(Ok(()), crate::private_dir::check)
+        .and_then(|()| check_peer(&stream))
+    {
+        log::warn!("Not using the daemon at {}: {error}", socket_path.display());
+        return None;
+    }
+
+    let exchange = || -> io::Result<DaemonReply> {
+        let mut writer = &stream;
+        serde_json::to_writer(&mut writer, daemon_request)?;
+        writer.write_all(b"\n")?;
+        stream.shutdown(std::net::Shutdown::Write)?;
+        Ok(serde_json::from_reader(&stream)?)
+    };
+
+    match exchange() {
+        Ok(reply) => Some(reply),
+        Err(error) => {
+            log::warn!(
+                "The daemon at {} did not answer, running in-process: {error}",
+                socket_path.display()
+            );
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn forward(_socket_path: &Path, _daemon_request: &DaemonRequest) -> Option<DaemonReply> {
+    None
+}
+
+/// Answer clients on `socket_path` until the process is stopped, each on its own thread
+#[cfg(unix)]
+pub(crate) fn serve(request: &Request, socket_path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    crate::private_dir::create()?;
+
+    if let Ok(metadata) = std::fs::symlink_metadata(socket_path) {
+        if UnixStream::connect(socket_path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!(
+                    "a daemon for this project is already listening on {}",
+                    socket_path.display()
+                ),
+            ));
+        }
+        if !metadata.file_type().is_socket() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} is in the way of the socket", socket_path.display()),
+            ));
+        }
+        // Left behind by a daemon that didn't get to clean up
+        std::fs::remove_file(socket_path)?;
+    }
+
+    let listener = UnixListener::bind(socket_path)?;
+    warm_up(request);
+    eprintln!("Listening on {}", socket_path.display());
+
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    scope.spawn(move || {
+                        if let Err(error) = respond(request, &stream) {
+                            log::warn!("Could not answer a client: {error}");
+                        }
+                    });
+                }
+                Err(error) => log::warn!("Could not accept a client: {error}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Load std and the workspace's own crates before the first client asks for them. Other crates
+/// load on first use, and stay loaded from then on.
+fn warm_up(request: &Request) {
+    let start = std::time::Instant::now();
+    let crate_names = request
+        .list_available_crates()
+        .filter(|crate_info| {
+            crate_info.provenance().is_std() || crate_info.provenance().is_workspace()
+        })
+        .map(|crate_info| crate_info.name())
+        .collect::<Vec<_>>();
+    request.prefetch(&crate_names);
+    log::info!(
+        "Loaded {} crates in {:?}",
+        crate_names.len(),
+        start.elapsed()
+    );
+}
+
+#[cfg(not(unix))]
+pub(crate) fn serve(_request: &Request, _socket_path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the daemon needs unix sockets, which this platform doesn't have",
+    ))
+}
+
+/// Check that the process at the other end of `stream` runs as the current user. Other unix
+/// platforms don't report peer credentials the same way, and rely on the private directory alone.
+#[cfg(unix)]
+fn check_peer(stream: &std::os::unix::net::UnixStream) -> io::Result<()> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let peer = rustix::net::sockopt::socket_peercred(stream)?;
+        if peer.uid != rustix::process::getuid() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("the other end runs as user {}", peer.uid.as_raw()),
+            ));
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let _ = stream;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn respond(request: &Request, stream: &std::os::unix::net::UnixStream) -> io::Result<()> {
+    check_peer(stream)?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+
+    let reply = match serde_json::from_str(&line) {
+        Ok(daemon_request) => run(request, daemon_request),
+        Err(error) => DaemonReply::failure(format!("Malformed daemon request: {error}")),
+    };
+
+    let mut writer = stream;
+    serde_json::to_writer(&mut writer, &reply)?;
+    writer.write_all(b"\n")
+}
+
+/// Run a client's command and render it the way the client would have
+pub(crate) fn run(request: &Request, daemon_request: DaemonRequest) -> DaemonReply {
+    let DaemonRequest {
+        args,
+        output_mode,
+        terminal_width,
+        color_depth,
+        theme,
+        links,
+        wrap,
+        decorations,
+        page,
+    } = daemon_request;
+
+    let cli = match Cli::try_parse_from(std::iter::once("ferritin".to_string()).chain(args)) {
+        Ok(cli) => cli,
+        Err(error) => return DaemonReply::failure(error.to_string()),
+    };
+    let Some(command) = cli.command.filter(Commands::is_served_by_daemon) else {
+        return DaemonReply::failure("The daemon only runs get, search and list");
+    };
+
+    let format_context = request
+        .format_context()
+        .clone()
+        .with_terminal_width(wrap.then_some(terminal_width))
+        .with_output_mode(Some(output_mode))
+        .with_theme(Some(theme));
+    let render_context = match RenderContext::new()
+        .with_terminal_width(terminal_width)
+        .with_wrap(wrap)
+        .with_color_depth(color_depth)
+        .with_decorations(decorations)
+        .with_links(links)
+        .with_format_context(&format_context)
+    {
+        Ok(render_context) => render_context,
+        Err(error) => return DaemonReply::failure(error.to_string()),
+    };
+
+    let (document, is_error, _history_entry) =
+        command.clone().execute_with(request, &format_context);
+
+    let mut output = PagedWriter::with_page(String::new(), page);
+    if crate::renderer::render(&document, &render_context, &mut output).is_err() {
+        return DaemonReply::failure("Failed to render the output");
+    }
+
+    let (message, success) = match output.omission_summary() {
+        Ok(summary) => (summary, !is_error),
+        Err(error) => (Some(error), false),
+    };
+    DaemonReply {
+        output: output.into_inner(),
+        message,
+        success,
+        exit_code: is_error.then(|| command.failure_exit_code(request)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verbosity::Verbosity;
+    use semver::Version;
+
+    #[test]
+    fn socket_path_depends_on_docs_options() {
+        let project = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let socket = socket_path(project, &Config::default());
+        let with = |config: Config| socket_path(project, &config);
+
+        assert_eq!(socket, with(Config::default()));
+        assert_eq!(
+            socket,
+            socket_path(&project.join("src"), &Config::default())
+        );
+        assert_eq!(
+            socket,
+            with(Config {
+                theme: Some("Dracula".into()),
+                ..Config::default()
+            })
+        );
+        assert_ne!(
+            socket,
+            with(Config {
+                toolchain: Some("stable".into()),
+                ..Config::default()
+            })
+        );
+        assert_ne!(
+            socket,
+            with(Config {
+                offline: Some(true),
+                ..Config::default()
+            })
+        );
+        assert_ne!(
+            socket,
+            with(Config {
+                min_version: Some(Version::new(1, 70, 0)),
+                ..Config::default()
+            })
+        );
+        assert_ne!(
+            socket,
+            with(Config {
+                verbosity: Some(Verbosity::Brief),
+                ..Config::default()
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sockets_live_in_the_private_directory() {
+        let socket = socket_path(Path::new(env!("CARGO_MANIFEST_DIR")), &Config::default());
+        assert_eq!(socket.parent(), Some(crate::private_dir::path().as_path()));
+
+        let (client, daemon) = std::os::unix::net::UnixStream::pair().unwrap();
+        assert!(check_peer(&client).is_ok());
+        assert!(check_peer(&daemon).is_ok());
+    }
+}
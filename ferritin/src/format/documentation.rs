@@ -2,9 +2,14 @@ use std::borrow::Cow;
 
 use super::*;
 use crate::markdown::MarkdownRenderer;
-use crate::styled_string::{DocumentNode, LinkTarget, TruncationLevel};
+use crate::styled_string::{
+    DocumentNode, HeadingLevel, LinkTarget, Span, TruncationLevel, TuiAction,
+};
 use rustdoc_types::ItemKind;
 
+/// Conventional rustdoc headings that are rendered as structured sections
+const CONVENTIONAL_SECTIONS: &[&str] = &["Errors", "Panics", "Safety", "Examples", "Example"];
+
 /// Information about documentation text with truncation details
 #[derive(Debug, Clone, Default)]
 pub(crate) struct DocInfo {
@@ -41,16 +46,19 @@ impl Request {
         item: DocRef<'a, Item>,
         markdown: &str,
     ) -> Vec<DocumentNode<'a>> {
-        MarkdownRenderer::render_with_resolver(markdown, |url| -> Option<LinkTarget<'a>> {
-            self.extract_link_target(item, url)
-        })
+        let mut nodes =
+            MarkdownRenderer::render_with_resolver(markdown, |url| -> Option<LinkTarget<'a>> {
+                self.extract_link_target(item, url)
+            });
+        attach_run_example_actions(&mut nodes, item, &mut 1);
+        nodes
     }
 
-    /// Extract the link target from an intra-doc link without loading external crates
+    /// Extract the link target from an intra-doc link, without loading any other crate
     ///
     /// Returns either a resolved DocRef (for same-crate items) or an unresolved path string
-    /// (for external items), avoiding the need to load external crates just for rendering.
-    /// URL generation is deferred to the renderer that needs it.
+    /// (for external items), which is loaded when the link is followed. URL
+    /// generation is deferred to the renderer that needs it.
     fn extract_link_target<'a>(
         &'a self,
         origin: DocRef<'a, Item>,
@@ -115,7 +123,10 @@ impl Request {
                     item_summary.path,
                     item_summary.kind
                 );
-                let full_path = item_summary.path.join("::");
+                let full_path = origin
+                    .crate_docs()
+                    .link_path(link_id)
+                    .unwrap_or_else(|| item_summary.path.join("::"));
                 return Some(LinkTarget::Path(Cow::Owned(full_path)));
             }
         }
@@ -123,6 +134,7 @@ impl Request {
         // Fallback: try to resolve path relative to current crate
         // Handle "crate::", "self::", and absolute paths
         log::trace!("  ✗ Not found in links map, using fallback for '{}'", path);
+        let path = path.trim_end_matches("()").trim_end_matches('!');
         let qualified_path = if let Some(rest) = path.strip_prefix("crate::") {
             format!("{}::{}", origin.crate_docs().name(), rest)
         } else if let Some(rest) = path.strip_prefix("self::") {
@@ -134,6 +146,19 @@ impl Request {
         };
 
         log::trace!("  → Qualified path: '{}'", qualified_path);
+
+        // An item of this crate can be looked up without loading anything. A link rustdoc left
+        // unresolved to another crate's item, like `serde::Deserialize`, stays a path: the
+        // navigator loads that crate when the link is followed, not each time the docs are shown.
+        // The path is taken as written, since a near-miss match would link to the wrong item.
+        let in_origin_crate = qualified_path
+            .strip_prefix(origin.crate_docs().name())
+            .is_some_and(|rest| rest.starts_with("::"));
+        if in_origin_crate && let Some(item) = self.find_path(&qualified_path) {
+            log::trace!("  ✓ Resolved in the origin crate");
+            return Some(LinkTarget::Resolved(item));
+        }
+
         Some(LinkTarget::Path(Cow::Owned(qualified_path)))
     }
 
@@ -277,6 +302,7 @@ impl Request {
                 format!("{}/{}/macro.{}.html", base, module_path, item_name)
             }
             ItemKind::Primitive => format!("{}/{}/primitive.{}.html", base, crate_name, item_name),
+            ItemKind::Keyword => format!("{}/{}/keyword.{}.html", base, crate_name, item_name),
             _ => {
                 // Fallback for unknown kinds - default to struct guess
                 format!("{}/{}/struct.{}.html", base, module_path, item_name)
@@ -393,6 +419,9 @@ impl Request {
             ItemKind::Primitive => {
                 format!("{}/{}/primitive.{}.html", html_root, crate_name, item_name)
             }
+            ItemKind::Keyword => {
+                format!("{}/{}/keyword.{}.html", html_root, crate_name, item_name)
+            }
             _ => {
                 // Fallback for unknown kinds
                 format!("{}/{}/struct.{}.html", html_root, module_path, item_name)
@@ -415,10 +444,58 @@ impl Request {
             return None;
         }
 
-        let nodes = self.render_docs(item, docs);
+        let mut nodes = self.render_docs(item, docs);
+        if truncation_level == TruncationLevel::Full {
+            nodes = self.group_conventional_sections(item, nodes);
+        }
         Some(vec![DocumentNode::truncated_block(nodes, truncation_level)])
     }
 
+    /// Gather the content under `# Errors`, `# Panics`, `# Safety` and `# Examples`
+    /// headings into titled sections
+    ///
+    /// A section runs until the next heading at the same or a higher level. Safety
+    /// sections on unsafe functions are set apart in a block quote, and examples are
+    /// collapsed when the format context asks for it.
+    fn group_conventional_sections<'a>(
+        &'a self,
+        item: DocRef<'a, Item>,
+        nodes: Vec<DocumentNode<'a>>,
+    ) -> Vec<DocumentNode<'a>> {
+        let is_unsafe_fn = matches!(item.inner(), ItemEnum::Function(f) if f.header.is_unsafe);
+        let mut grouped = Vec::with_capacity(nodes.len());
+        let mut nodes = nodes.into_iter().peekable();
+
+        while let Some(node) = nodes.next() {
+            let Some((level, name)) = conventional_heading(&node) else {
+                grouped.push(node);
+                continue;
+            };
+
+            let mut body = vec![];
+            while let Some(next) = nodes.next_if(|next| !ends_section(next, level)) {
+                body.push(next);
+            }
+
+            let section = match name.as_str() {
+                "Safety" if is_unsafe_fn => DocumentNode::section(
+                    vec![Span::keyword(name)],
+                    vec![DocumentNode::block_quote(body)],
+                ),
+                "Examples" | "Example" if self.format_context().collapse_examples() => {
+                    DocumentNode::section(
+                        vec![Span::plain(name)],
+                        vec![DocumentNode::truncated_block(body, TruncationLevel::Brief)],
+                    )
+                }
+                _ => DocumentNode::section(vec![Span::plain(name)], body),
+            };
+            grouped.push(section);
+        }
+
+        grouped
+    }
+
     /// Count the number of lines in a text string
     pub(crate) fn count_lines(&self, text: &str) -> usize {
         if text.is_empty() {
@@ -452,3 +529,64 @@ impl Request {
         lines[..cutoff].join("\n")
     }
 }
+
+/// If this node is one of the conventional rustdoc headings, return its level and text
+fn conventional_heading(node: &DocumentNode<'_>) -> Option<(HeadingLevel, String)> {
+    let DocumentNode::Heading { level, spans } = node else {
+        return None;
+    };
+
+    let text = spans.iter().map(|span| &*span.text).collect::<String>();
+    let text = text.trim().trim_end_matches(':');
+    CONVENTIONAL_SECTIONS
+        .contains(&text)
+        .then(|| (*level, text.to_string()))
+}
+
+/// Whether a node closes a section opened by a heading at `level`
+fn ends_section(node: &DocumentNode<'_>, level: HeadingLevel) -> bool {
+    match node {
+        DocumentNode::Heading {
+            level: HeadingLevel::Title,
+            ..
+        } => true,
+        DocumentNode::Heading { .. } => level == HeadingLevel::Section,
+        _ => false,
+    }
+}
+
+/// Offer a [`TuiAction::RunExample`] on each rust code block, numbered in document order
+///
+/// Numbering matches `run_example::extract_examples`, which applies the same rule as the
+/// markdown renderer for deciding whether a block is rust.
+fn attach_run_example_actions<'a>(
+    nodes: &mut [DocumentNode<'a>],
+    item: DocRef<'a, Item>,
+    next_index: &mut usize,
+) {
+    for node in nodes {
+        match node {
+            DocumentNode::CodeBlock { lang, action, .. } => {
+                if lang.as_deref() == Some("rust") {
+                    *action = Some(TuiAction::RunExample {
+                        doc_ref: item,
+                        index: *next_index,
+                    });
+                    *next_index += 1;
+                }
+            }
+            DocumentNode::Section { nodes, .. }
+            | DocumentNode::BlockQuote { nodes }
+            | DocumentNode::TruncatedBlock { nodes, .. }
+            | DocumentNode::Conditional { nodes, .. } => {
+                attach_run_example_actions(nodes, item, next_index);
+            }
+            DocumentNode::List { items } => {
+                for list_item in items {
+                    attach_run_example_actions(&mut list_item.content, item, next_index);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -7,6 +7,7 @@ impl Request {
         &'a self,
         item: DocRef<'a, Item>,
         enum_data: DocRef<'a, Enum>,
+        context: &FormatContext,
     ) -> Vec<DocumentNode<'a>> {
         let enum_name = item.name().unwrap_or("<unnamed>");
 
@@ -102,6 +103,10 @@ impl Request {
 
         // Add signature as generated code block
         doc_nodes.push(DocumentNode::generated_code(code_spans));
+        if context.verbosity().is_signature() {
+            doc_nodes.extend(self.format_associated_methods(item, context));
+            return doc_nodes;
+        }
 
         // Build variants section with List (collect documented variants)
         let variant_items: Vec<ListItem> = item
@@ -131,7 +136,8 @@ impl Request {
             doc_nodes.push(variants_section);
         }
 
-        doc_nodes.extend(self.format_associated_methods(item));
+        doc_nodes.extend(self.format_marker_notes(item, &[]));
+        doc_nodes.extend(self.format_associated_methods(item, context));
 
         doc_nodes
     }
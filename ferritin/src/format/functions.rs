@@ -2,6 +2,7 @@ use rustdoc_types::{AssocItemConstraint, AssocItemConstraintKind, TraitBoundModi
 
 use super::*;
 use crate::styled_string::{DocumentNode, Span as StyledSpan};
+use unicode_width::UnicodeWidthStr;
 
 impl Request {
     /// Format a function signature
@@ -125,28 +126,61 @@ impl Request {
         spans.push(StyledSpan::keyword("fn"));
         spans.push(StyledSpan::plain(" "));
         spans.push(StyledSpan::plain(name).with_target(Some(item)));
-        if !func.generics.params.is_empty() {
-            spans.extend(self.format_generics(item, &func.generics));
+
+        let params = func
+            .sig
+            .inputs
+            .iter()
+            .map(|(param_name, param_type)| self.format_parameter(item, param_name, param_type))
+            .collect::<Vec<_>>();
+
+        // Add return type if not unit
+        let mut output = vec![];
+        if let Some(output_type) = &func.sig.output {
+            output.push(StyledSpan::plain(" "));
+            output.push(StyledSpan::operator("->"));
+            output.push(StyledSpan::plain(" "));
+            output.extend(self.format_type(item, output_type));
         }
-        spans.push(StyledSpan::punctuation("("));
 
-        // Add parameters
-        for (i, (param_name, param_type)) in func.sig.inputs.iter().enumerate() {
-            if i > 0 {
+        // A signature too wide for the terminal puts its generics, and then its parameters, one
+        // per line, the way rustfmt lays them out
+        let max_width = self.format_context().terminal_width();
+        let fits = |width: usize| max_width.is_none_or(|max_width| width <= max_width);
+
+        let generics = self.format_generics(item, &func.generics);
+        let mut line_width = spans_width(&spans) + spans_width(&generics);
+        if generics.is_empty() || fits(line_width + 1) {
+            spans.extend(generics);
+        } else {
+            spans.extend(self.format_generics_one_per_line(item, &func.generics));
+            line_width = 1;
+        }
+
+        let separators = 2 * params.len().saturating_sub(1);
+        line_width += params.iter().map(|param| spans_width(param)).sum::<usize>()
+            + separators
+            + spans_width(&output)
+            + 2;
+        spans.push(StyledSpan::punctuation("("));
+        if params.is_empty() || fits(line_width) {
+            for (i, param) in params.into_iter().enumerate() {
+                if i > 0 {
+                    spans.push(StyledSpan::punctuation(","));
+                    spans.push(StyledSpan::plain(" "));
+                }
+                spans.extend(param);
+            }
+        } else {
+            for param in params {
+                spans.push(StyledSpan::plain("\n    "));
+                spans.extend(param);
                 spans.push(StyledSpan::punctuation(","));
-                spans.push(StyledSpan::plain(" "));
             }
-            spans.extend(self.format_parameter(item, param_name, param_type));
+            spans.push(StyledSpan::plain("\n"));
         }
         spans.push(StyledSpan::punctuation(")"));
-
-        // Add return type if not unit
-        if let Some(output) = &func.sig.output {
-            spans.push(StyledSpan::plain(" "));
-            spans.push(StyledSpan::operator("->"));
-            spans.push(StyledSpan::plain(" "));
-            spans.extend(self.format_type(item, output));
-        }
+        spans.extend(output);
 
         // Add where clause if present
         if !func.generics.where_predicates.is_empty() {
@@ -268,6 +302,24 @@ impl Request {
         spans
     }
 
+    /// Format generics with each parameter on a line of its own, for signatures too wide for
+    /// one line
+    fn format_generics_one_per_line<'a>(
+        &self,
+        item: DocRef<'a, Item>,
+        generics: &'a Generics,
+    ) -> Vec<StyledSpan<'a>> {
+        let mut spans = vec![StyledSpan::punctuation("<")];
+        for param in &generics.params {
+            spans.push(StyledSpan::plain("\n    "));
+            spans.extend(self.format_generic_param(item, param));
+            spans.push(StyledSpan::punctuation(","));
+        }
+        spans.push(StyledSpan::plain("\n"));
+        spans.push(StyledSpan::punctuation(">"));
+        spans
+    }
+
     /// Format a single generic parameter
     pub(super) fn format_generic_param<'a>(
         &self,
@@ -331,11 +383,21 @@ impl Request {
         &self,
         item: DocRef<'a, Item>,
         bounds: &'a [GenericBound],
+    ) -> Vec<StyledSpan<'a>> {
+        self.format_generic_bounds_separated(item, bounds, " + ")
+    }
+
+    /// Format generic bounds with `separator` between them, which is " + " on one line
+    fn format_generic_bounds_separated<'a>(
+        &self,
+        item: DocRef<'a, Item>,
+        bounds: &'a [GenericBound],
+        separator: &'static str,
     ) -> Vec<StyledSpan<'a>> {
         let mut spans = vec![];
         for (i, bound) in bounds.iter().enumerate() {
             if i > 0 {
-                spans.push(StyledSpan::plain(" + "));
+                spans.push(StyledSpan::plain(separator));
             }
             spans.extend(self.format_generic_bound(item, bound));
         }
@@ -403,12 +465,32 @@ impl Request {
             StyledSpan::plain("\n    "),
         ];
 
+        let max_width = self.format_context().terminal_width();
         for (i, pred) in predicates.iter().enumerate() {
             if i > 0 {
                 spans.push(StyledSpan::punctuation(","));
                 spans.push(StyledSpan::plain("\n    "));
             }
-            spans.extend(self.format_where_predicate(item, pred));
+            let mut predicate = self.format_where_predicate(item, pred);
+
+            // A predicate too wide for the terminal gets a line for each bound
+            if let WherePredicate::BoundPredicate {
+                type_,
+                bounds,
+                generic_params,
+            } = pred
+                && bounds.len() > 1
+                && max_width.is_some_and(|max_width| spans_width(&predicate) + 5 > max_width)
+            {
+                predicate = self.format_bound_predicate(
+                    item,
+                    type_,
+                    bounds,
+                    generic_params,
+                    "\n        + ",
+                );
+            }
+            spans.extend(predicate);
         }
 
         spans
@@ -425,7 +507,7 @@ impl Request {
                 type_,
                 bounds,
                 generic_params,
-            } => self.format_bound_predicate(item, type_, bounds, generic_params),
+            } => self.format_bound_predicate(item, type_, bounds, generic_params, " + "),
             WherePredicate::LifetimePredicate { lifetime, outlives } => {
                 let mut spans = vec![StyledSpan::lifetime(lifetime), StyledSpan::punctuation(":")];
                 if !outlives.is_empty() {
@@ -457,6 +539,7 @@ impl Request {
         type_: &'a Type,
         bounds: &'a [GenericBound],
         generic_params: &'a [GenericParamDef],
+        bound_separator: &'static str,
     ) -> Vec<StyledSpan<'a>> {
         let mut spans = vec![];
 
@@ -477,7 +560,7 @@ impl Request {
         spans.extend(self.format_type(item, type_));
         spans.push(StyledSpan::punctuation(":"));
         spans.push(StyledSpan::plain(" "));
-        spans.extend(self.format_generic_bounds(item, bounds));
+        spans.extend(self.format_generic_bounds_separated(item, bounds, bound_separator));
         spans
     }
 
@@ -613,3 +696,8 @@ impl Request {
         spans
     }
 }
+
+/// The columns a signature's spans take up on one line
+fn spans_width(spans: &[StyledSpan<'_>]) -> usize {
+    spans.iter().map(|span| span.text.width()).sum()
+}
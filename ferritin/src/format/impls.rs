@@ -1,4 +1,4 @@
-use ferritin_common::CrateProvenance;
+use ferritin_common::{CrateProvenance, ImplOrigin};
 use rustdoc_types::ItemKind;
 
 use super::*;
@@ -22,58 +22,113 @@ struct TraitImpl {
 
 impl Request {
     /// Add associated methods for a struct or enum
+    ///
+    /// With signatures only, the methods are listed without docs, and trait implementations
+    /// are left out.
     pub(super) fn format_associated_methods<'a>(
         &'a self,
         item: DocRef<'a, Item>,
+        context: &FormatContext,
     ) -> Vec<DocumentNode<'a>> {
         let mut doc_nodes = vec![];
-
-        let inherent_methods = item.methods().collect::<Vec<_>>();
-        // Show inherent methods first
-        if !inherent_methods.is_empty() {
-            doc_nodes.extend(self.format_item_list(inherent_methods, "Associated Types"));
+        let signature_only = context.verbosity().is_signature();
+
+        let impl_blocks = item.inherent_impls().collect::<Vec<_>>();
+        // Show inherent methods first, grouped under their impl block's docs if any have them
+        if !signature_only
+            && impl_blocks.iter().any(|impl_block| {
+                impl_block
+                    .docs
+                    .as_deref()
+                    .is_some_and(|docs| !docs.is_empty())
+            })
+        {
+            doc_nodes.extend(self.format_impl_block_groups(item, impl_blocks));
+        } else {
+            let inherent_methods = item
+                .methods()
+                .filter(|method| !self.is_newer_than_min_version(*method))
+                .collect::<Vec<_>>();
+            if !inherent_methods.is_empty() {
+                doc_nodes.extend(self.format_item_list(
+                    inherent_methods,
+                    "Associated Types",
+                    !signature_only,
+                ));
+            }
         }
 
         let trait_impls = item.traits().collect::<Vec<_>>();
         // Show trait implementations
-        if !trait_impls.is_empty() {
+        if !signature_only && !trait_impls.is_empty() {
             doc_nodes.extend(self.format_trait_implementations(&trait_impls));
         }
 
         doc_nodes
     }
 
-    fn format_item_list<'a>(
+    /// List inherent methods by impl block, with each documented block's docs as an intro to
+    /// its methods (e.g. a "Constructors" block). Methods from consecutive undocumented blocks
+    /// share a list.
+    fn format_impl_block_groups<'a>(
         &'a self,
-        mut items: Vec<DocRef<'a, Item>>,
-        title: &'a str,
+        item: DocRef<'a, Item>,
+        mut impl_blocks: Vec<DocRef<'a, Item>>,
     ) -> Vec<DocumentNode<'a>> {
-        items.sort_by(|a, b| {
-            match (&a.span, &b.span) {
-                (Some(span_a), Some(span_b)) => {
-                    // Primary sort by filename
-                    let filename_cmp = span_a.filename.cmp(&span_b.filename);
-                    if filename_cmp != Ordering::Equal {
-                        filename_cmp
-                    } else {
-                        // Secondary sort by start line
-                        let line_cmp = span_a.begin.0.cmp(&span_b.begin.0);
-                        if line_cmp != Ordering::Equal {
-                            line_cmp
-                        } else {
-                            // Tertiary sort by start column
-                            span_a.begin.1.cmp(&span_b.begin.1)
-                        }
+        sort_by_source_position(&mut impl_blocks);
+
+        let mut nodes = vec![];
+        let mut undocumented = vec![];
+        for impl_block in impl_blocks {
+            let methods = item
+                .impl_block_items(impl_block)
+                .filter(|method| !self.is_newer_than_min_version(*method))
+                .collect::<Vec<_>>();
+
+            match self.docs_to_show(impl_block, TruncationLevel::Brief) {
+                Some(intro) => {
+                    if !undocumented.is_empty() {
+                        nodes
+                            .push(self.format_method_list(std::mem::take(&mut undocumented), true));
+                    }
+                    nodes.extend(intro);
+                    if !methods.is_empty() {
+                        nodes.push(self.format_method_list(methods, true));
                     }
                 }
-                (Some(_), None) => Ordering::Less, // Items with spans come before items without
-                (None, Some(_)) => Ordering::Greater, // Items with spans come before items without
-                (None, None) => {
-                    // Both without spans, sort by name (lexicographical)
-                    a.name.cmp(&b.name)
-                }
+                None => undocumented.extend(methods),
             }
-        });
+        }
+        if !undocumented.is_empty() {
+            nodes.push(self.format_method_list(undocumented, true));
+        }
+
+        vec![DocumentNode::section(
+            vec![Span::plain("Associated Types")],
+            nodes,
+        )]
+    }
+
+    fn format_item_list<'a>(
+        &'a self,
+        items: Vec<DocRef<'a, Item>>,
+        title: &'a str,
+        with_docs: bool,
+    ) -> Vec<DocumentNode<'a>> {
+        vec![DocumentNode::section(
+            vec![Span::plain(title)],
+            vec![self.format_method_list(items, with_docs)],
+        )]
+    }
+
+    /// A list of items in source order, each with its signature and, `with_docs`, a one-line
+    /// summary
+    fn format_method_list<'a>(
+        &'a self,
+        mut items: Vec<DocRef<'a, Item>>,
+        with_docs: bool,
+    ) -> DocumentNode<'a> {
+        sort_by_source_position(&mut items);
 
         let list_items: Vec<ListItem> = items
             .iter()
@@ -122,13 +177,17 @@ impl Request {
                         signature_spans.push(Span::plain(" "));
                     }
 
-                    signature_spans.push(Span::plain(name));
+                    signature_spans.push(Span::item_name(name, kind));
                 }
 
+                signature_spans.extend(self.format_since(*item));
+
                 let mut item_nodes = vec![DocumentNode::generated_code(signature_spans)];
 
                 // Add brief doc preview
-                if let Some(docs) = self.docs_to_show(*item, TruncationLevel::SingleLine) {
+                if with_docs
+                    && let Some(docs) = self.docs_to_show(*item, TruncationLevel::SingleLine)
+                {
                     item_nodes.extend(docs);
                 }
 
@@ -136,13 +195,11 @@ impl Request {
             })
             .collect();
 
-        vec![DocumentNode::section(
-            vec![Span::plain(title)],
-            vec![DocumentNode::list(list_items)],
-        )]
+        DocumentNode::list(list_items)
     }
 
-    /// Format trait implementations with explicit category groups
+    /// Format trait implementations with explicit category groups, followed by the auto trait
+    /// and blanket impls that apply to the type, in sections of their own as rustdoc shows them
     fn format_trait_implementations<'a>(
         &self,
         trait_impls: &[DocRef<'a, Item>],
@@ -150,6 +207,8 @@ impl Request {
         let mut crate_local = Vec::new();
         let mut external = Vec::new();
         let mut std_traits = Vec::new();
+        let mut auto_traits = Vec::new();
+        let mut blanket_impls = Vec::new();
 
         // Extract trait implementations
         for impl_block in trait_impls {
@@ -158,19 +217,26 @@ impl Request {
             {
                 let full_path = impl_block
                     .crate_docs()
-                    .path(&trait_path.id)
-                    .map(|path| path.to_string())
+                    .link_path(&trait_path.id)
                     .unwrap_or(trait_path.path.clone());
 
                 // Use the simple path name for display (generics not needed in trait lists)
-                let display_name = trait_path.path.clone();
+                let display_name = if impl_item.is_negative {
+                    format!("!{}", trait_path.path)
+                } else {
+                    trait_path.path.clone()
+                };
 
                 let impl_ = self.categorize_trait(full_path, display_name);
 
-                match impl_.category {
-                    TraitCategory::CrateLocal => crate_local.push(impl_),
-                    TraitCategory::External => external.push(impl_),
-                    TraitCategory::Std => std_traits.push(impl_),
+                match impl_block.impl_origin() {
+                    Some(ImplOrigin::Auto) => auto_traits.push(impl_),
+                    Some(ImplOrigin::Blanket) => blanket_impls.push(impl_),
+                    _ => match impl_.category {
+                        TraitCategory::CrateLocal => crate_local.push(impl_),
+                        TraitCategory::External => external.push(impl_),
+                        TraitCategory::Std => std_traits.push(impl_),
+                    },
                 }
             }
         }
@@ -179,6 +245,8 @@ impl Request {
         crate_local.sort();
         external.sort();
         std_traits.sort();
+        auto_traits.sort();
+        blanket_impls.sort();
 
         // Build trait implementation content
         let mut trait_content = vec![];
@@ -189,38 +257,38 @@ impl Request {
         primary_traits.extend(external);
 
         if !primary_traits.is_empty() {
-            let mut trait_spans = vec![Span::plain("Trait Implementations: ")];
-            for t in primary_traits {
-                trait_spans.push(Span::plain(t.name).with_path(t.full_path));
-                trait_spans.push(Span::plain(" "));
-            }
-            trait_content.push(DocumentNode::paragraph(trait_spans));
+            trait_content.push(trait_list("Trait Implementations: ", primary_traits));
         }
 
         // Add std traits separately
         if !std_traits.is_empty() {
-            let mut trait_spans = vec![Span::plain("std traits: ")];
-            for t in std_traits {
-                trait_spans.push(Span::plain(t.name).with_path(t.full_path));
-                trait_spans.push(Span::plain(" "));
-            }
-            trait_content.push(DocumentNode::paragraph(trait_spans));
+            trait_content.push(trait_list("std traits: ", std_traits));
         }
 
-        // Wrap in a section if we have any trait implementations
+        let mut sections = vec![];
         if !trait_content.is_empty() {
-            vec![DocumentNode::section(
+            sections.push(DocumentNode::section(
                 vec![Span::plain("Trait Implementations")],
                 trait_content,
-            )]
-        } else {
-            vec![]
+            ));
+        }
+        for (title, traits) in [
+            ("Auto Trait Implementations", auto_traits),
+            ("Blanket Implementations", blanket_impls),
+        ] {
+            if !traits.is_empty() {
+                sections.push(DocumentNode::section(
+                    vec![Span::plain(title)],
+                    vec![trait_list("", traits)],
+                ));
+            }
         }
+        sections
     }
 
     fn categorize_trait(&self, full_path: String, rendered_path: String) -> TraitImpl {
-        // Check by explicit crate prefix (like std::fmt::Display)
-        let crate_prefix = full_path.split("::").next().unwrap_or("");
+        // Check by explicit crate prefix (like std::fmt::Display), which may carry a version
+        let crate_prefix = full_path.split(['@', ':']).next().unwrap_or("");
 
         // Use Navigator's lookup to determine provenance
         if !crate_prefix.is_empty()
@@ -246,3 +314,46 @@ impl Request {
         }
     }
 }
+
+/// A paragraph naming each trait after `label`, linked to its docs
+fn trait_list<'a>(label: &'a str, traits: Vec<TraitImpl>) -> DocumentNode<'a> {
+    let mut trait_spans = vec![];
+    if !label.is_empty() {
+        trait_spans.push(Span::plain(label));
+    }
+    for t in traits {
+        trait_spans.push(Span::plain(t.name).with_path(t.full_path));
+        trait_spans.push(Span::plain(" "));
+    }
+    DocumentNode::paragraph(trait_spans)
+}
+
+/// Sort items by where they are defined, falling back to their names for items without a span
+fn sort_by_source_position(items: &mut [DocRef<'_, Item>]) {
+    items.sort_by(|a, b| {
+        match (&a.span, &b.span) {
+            (Some(span_a), Some(span_b)) => {
+                // Primary sort by filename
+                let filename_cmp = span_a.filename.cmp(&span_b.filename);
+                if filename_cmp != Ordering::Equal {
+                    filename_cmp
+                } else {
+                    // Secondary sort by start line
+                    let line_cmp = span_a.begin.0.cmp(&span_b.begin.0);
+                    if line_cmp != Ordering::Equal {
+                        line_cmp
+                    } else {
+                        // Tertiary sort by start column
+                        span_a.begin.1.cmp(&span_b.begin.1)
+                    }
+                }
+            }
+            (Some(_), None) => Ordering::Less, // Items with spans come before items without
+            (None, Some(_)) => Ordering::Greater, // Items with spans come before items without
+            (None, None) => {
+                // Both without spans, sort by name (lexicographical)
+                a.name.cmp(&b.name)
+            }
+        }
+    });
+}
@@ -1,5 +1,6 @@
 use super::*;
 use crate::styled_string::{DocumentNode, Span};
+use rustdoc_types::ItemKind;
 
 impl Request {
     /// Format a type alias
@@ -51,7 +52,7 @@ impl Request {
         let mut spans = vec![
             Span::keyword("const"),
             Span::plain(" "),
-            Span::plain(name),
+            Span::const_name(name),
             Span::punctuation(":"),
             Span::plain(" "),
         ];
@@ -71,6 +72,41 @@ impl Request {
         vec![DocumentNode::generated_code(spans)]
     }
 
+    /// Format an associated constant or type on a page of its own: its signature, with any
+    /// bounds and default, and the trait or type it belongs to
+    pub(crate) fn format_assoc_item<'a>(&'a self, item: DocRef<'a, Item>) -> Vec<DocumentNode<'a>> {
+        let name = item.name().unwrap_or("<unnamed>");
+        let signature = match item.inner() {
+            ItemEnum::AssocConst { type_, value } => {
+                self.format_trait_assoc_const_signature(item, type_, value, name)
+            }
+            ItemEnum::AssocType {
+                generics,
+                bounds,
+                type_,
+            } => {
+                self.format_trait_assoc_type_signature(item, generics, bounds, type_.as_ref(), name)
+            }
+            _ => return vec![],
+        };
+
+        let mut nodes = vec![DocumentNode::generated_code(signature)];
+
+        if let Some(parent) = item.parent() {
+            let parent_name = parent.name().unwrap_or("<unnamed>");
+            let declared_in = match parent.kind() {
+                ItemKind::Trait => "Declared in the trait ",
+                _ => "Associated with ",
+            };
+            nodes.push(DocumentNode::paragraph(vec![
+                Span::plain(declared_in),
+                Span::type_name(parent_name).with_target(Some(parent)),
+            ]));
+        }
+
+        nodes
+    }
+
     /// Format a static
     pub(crate) fn format_static<'a>(
         &'a self,
@@ -82,7 +118,7 @@ impl Request {
         let mut spans = vec![
             Span::keyword("static"),
             Span::plain(" "),
-            Span::plain(name),
+            Span::const_name(name),
             Span::punctuation(":"),
             Span::plain(" "),
         ];
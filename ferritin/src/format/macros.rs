@@ -0,0 +1,204 @@
+use super::*;
+use crate::styled_string::{DocumentNode, ListItem, Span};
+use rustdoc_types::{MacroKind, ProcMacro};
+
+/// One rule of a `macro_rules!` macro
+#[derive(Debug, PartialEq, Eq)]
+struct MacroRule {
+    /// The delimiters the rule's matcher is written with, like `(` and `)`
+    open: char,
+    close: char,
+    /// The matcher between the delimiters, with its whitespace collapsed
+    matcher: String,
+}
+
+impl Request {
+    /// Format a `macro_rules!` macro as the ways it can be invoked, one per rule
+    ///
+    /// Falls back to the definition as rustdoc records it when it can't be split into rules,
+    /// like a `macro` 2.0 definition.
+    pub(super) fn format_macro<'a>(
+        &'a self,
+        item: DocRef<'a, Item>,
+        macro_def: &'a str,
+        signature_only: bool,
+    ) -> Vec<DocumentNode<'a>> {
+        let mut nodes = vec![];
+        let Some((name, rules)) = parse_macro_rules(macro_def) else {
+            if !signature_only {
+                nodes.push(DocumentNode::paragraph(vec![Span::plain(
+                    "Macro definition:",
+                )]));
+            }
+            nodes.push(DocumentNode::code_block(Some("rust"), macro_def));
+            return nodes;
+        };
+        let name = item.name().unwrap_or(name);
+
+        if !signature_only {
+            nodes.push(DocumentNode::paragraph(vec![Span::plain(
+                match rules.len() {
+                    1 => "Invoked as:".to_string(),
+                    count => format!("Invoked in one of {count} ways:"),
+                },
+            )]));
+        }
+        let usage = rules
+            .iter()
+            .map(|rule| format!("{name}!{}{}{}", rule.open, rule.matcher, rule.close))
+            .collect::<Vec<_>>()
+            .join("\n");
+        nodes.push(DocumentNode::code_block(Some("rust"), usage));
+        nodes
+    }
+
+    /// Format a procedural macro as it's used, with a derive's helper attributes
+    pub(super) fn format_proc_macro<'a>(
+        &'a self,
+        item: DocRef<'a, Item>,
+        proc_macro: &'a ProcMacro,
+    ) -> Vec<DocumentNode<'a>> {
+        let name = item.name().unwrap_or("<unnamed>");
+        let usage = match proc_macro.kind {
+            MacroKind::Bang => vec![
+                Span::function_name(name),
+                Span::punctuation("!("),
+                Span::plain("..."),
+                Span::punctuation(")"),
+            ],
+            MacroKind::Attr => vec![
+                Span::punctuation("#["),
+                Span::function_name(name),
+                Span::punctuation("]"),
+            ],
+            MacroKind::Derive => vec![
+                Span::punctuation("#["),
+                Span::plain("derive"),
+                Span::punctuation("("),
+                Span::type_name(name),
+                Span::punctuation(")]"),
+            ],
+        };
+        let mut nodes = vec![DocumentNode::generated_code(usage)];
+
+        if !proc_macro.helpers.is_empty() {
+            nodes.push(DocumentNode::paragraph(vec![Span::plain(
+                "Helper attributes:",
+            )]));
+            nodes.push(DocumentNode::list(
+                proc_macro
+                    .helpers
+                    .iter()
+                    .map(|helper| {
+                        ListItem::new(vec![DocumentNode::generated_code(helper_attribute(helper))])
+                    })
+                    .collect(),
+            ));
+        }
+
+        nodes
+    }
+}
+
+/// A derive's helper attribute as it's written, like `#[serde]`
+///
+/// rustdoc only records the helper's name, not whether it takes arguments, so it's shown bare.
+fn helper_attribute(helper: &str) -> Vec<Span<'_>> {
+    vec![
+        Span::punctuation("#["),
+        Span::plain(helper),
+        Span::punctuation("]"),
+    ]
+}
+
+/// Split a definition like `macro_rules! name { ($x:expr) => { ... }; }` into the macro's
+/// name and its rules
+fn parse_macro_rules(definition: &str) -> Option<(&str, Vec<MacroRule>)> {
+    let rest = definition.trim().strip_prefix("macro_rules!")?.trim_start();
+    let name_end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    let name = &rest[..name_end];
+    let body = rest[name_end..].trim().trim_end_matches(';').trim_end();
+    let mut chars = body.chars();
+    let open = chars.next()?;
+    if chars.next_back()? != closing_delimiter(open)? {
+        return None;
+    }
+    let mut body = chars.as_str().trim_start();
+
+    let mut rules = vec![];
+    while !body.is_empty() {
+        let (open, matcher, rest) = delimited_group(body)?;
+        let rest = rest.trim_start().strip_prefix("=>")?.trim_start();
+        let (_, _, rest) = delimited_group(rest)?;
+        body = rest.trim_start().trim_start_matches(';').trim_start();
+        rules.push(MacroRule {
+            open,
+            close: closing_delimiter(open)?,
+            matcher: matcher.split_whitespace().collect::<Vec<_>>().join(" "),
+        });
+    }
+
+    (!name.is_empty() && !rules.is_empty()).then_some((name, rules))
+}
+
+/// The group `text` starts with, as its opening delimiter, its contents and the text after it
+fn delimited_group(text: &str) -> Option<(char, &str, &str)> {
+    let open = text.chars().next()?;
+    closing_delimiter(open)?;
+    let mut depth = 0usize;
+    for (index, c) in text.char_indices() {
+        if closing_delimiter(c).is_some() {
+            depth += 1;
+        } else if matches!(c, ')' | ']' | '}') {
+            depth = depth.checked_sub(1)?;
+            if depth == 0 {
+                return Some((open, &text[1..index], &text[index + 1..]));
+            }
+        }
+    }
+    None
+}
+
+fn closing_delimiter(open: char) -> Option<char> {
+    match open {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn macro_rules_split_into_rules() {
+        let (name, rules) = parse_macro_rules(
+            "macro_rules! vec {\n    () => { ... };\n    ($elem:expr; $n:expr) => { ... };\n    \
+             ($($x:expr),+ $(,)?) => { ... };\n}",
+        )
+        .unwrap();
+        assert_eq!(name, "vec");
+        let matchers = rules.iter().map(|rule| &*rule.matcher).collect::<Vec<_>>();
+        assert_eq!(matchers, ["", "$elem:expr; $n:expr", "$($x:expr),+ $(,)?"]);
+        assert_eq!((rules[0].open, rules[0].close), ('(', ')'));
+
+        let (_, rules) = parse_macro_rules("macro_rules! m { [$a:ident] => {}; }").unwrap();
+        assert_eq!(rules[0].open, '[');
+        assert_eq!(rules[0].matcher, "$a:ident");
+
+        assert!(parse_macro_rules("pub macro m($x:expr) { ... }").is_none());
+    }
+
+    #[test]
+    fn helper_attributes_are_shown_bare() {
+        let text = helper_attribute("serde")
+            .iter()
+            .map(|span| &*span.text)
+            .collect::<String>();
+        assert_eq!(text, "#[serde]");
+    }
+}
@@ -0,0 +1,142 @@
+use super::*;
+use crate::styled_string::{DocumentNode, ListItem, Span};
+
+/// Auto traits whose absence is worth explaining
+const AUTO_TRAITS: &[&str] = &["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
+
+impl Request {
+    /// Explain what `PhantomData` fields and missing auto traits mean for a type
+    ///
+    /// Variance and auto traits are inferred from field types rather than declared, so a field
+    /// like `PhantomData<*const T>` is easy to misread. Nothing is added for types whose fields
+    /// have no marker and that implement all the usual auto traits.
+    pub(super) fn format_marker_notes<'a>(
+        &'a self,
+        item: DocRef<'a, Item>,
+        field_types: &[&'a Type],
+    ) -> Vec<DocumentNode<'a>> {
+        let mut notes = vec![];
+
+        for field_type in field_types {
+            if let Some(marked) = phantom_data_argument(field_type) {
+                let mut spans = self.format_type(item, field_type);
+                spans.push(Span::plain(": "));
+                spans.push(Span::plain(phantom_data_meaning(marked)));
+                notes.push(ListItem::new(vec![DocumentNode::paragraph(spans)]));
+            }
+        }
+
+        let mut missing_auto_traits = item
+            .traits()
+            .filter_map(|impl_block| match impl_block.inner() {
+                ItemEnum::Impl(impl_) if impl_.is_synthetic && impl_.is_negative => {
+                    let name = impl_.trait_.as_ref()?.path.rsplit("::").next()?;
+                    AUTO_TRAITS.contains(&name).then_some(name)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        missing_auto_traits.sort_by_key(|name| AUTO_TRAITS.iter().position(|t| t == name));
+        missing_auto_traits.dedup();
+
+        if !missing_auto_traits.is_empty() {
+            let mut spans = vec![Span::plain("Not ")];
+            for (i, name) in missing_auto_traits.iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::plain(", "));
+                }
+                spans.push(Span::type_name(*name));
+            }
+            spans.push(Span::plain(
+                ": one of its fields (possibly private) is a raw pointer, a non-thread-safe \
+                 type like `Rc` or `Cell`, or a `PhantomData` marker for one",
+            ));
+            notes.push(ListItem::new(vec![DocumentNode::paragraph(spans)]));
+        }
+
+        if notes.is_empty() {
+            return vec![];
+        }
+
+        vec![DocumentNode::section(
+            vec![Span::plain("Variance and auto traits:")],
+            vec![DocumentNode::list(notes)],
+        )]
+    }
+}
+
+/// The type parameter of a `PhantomData<T>` field type
+fn phantom_data_argument(type_: &Type) -> Option<&Type> {
+    let Type::ResolvedPath(path) = type_ else {
+        return None;
+    };
+
+    if path.path.rsplit("::").next() != Some("PhantomData") {
+        return None;
+    }
+
+    match path.args.as_deref()? {
+        GenericArgs::AngleBracketed { args, .. } => match args.first()? {
+            GenericArg::Type(marked) => Some(marked),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// What a `PhantomData<marked>` field implies for the containing type
+fn phantom_data_meaning(marked: &Type) -> &'static str {
+    match marked {
+        Type::Tuple(types) if types.is_empty() => {
+            "a zero-sized marker with no effect on variance or auto traits"
+        }
+        Type::BorrowedRef {
+            is_mutable: false, ..
+        } => {
+            "acts like a shared borrow: covariant, tied to the lifetime, and only Send when \
+             the borrowed type is Sync"
+        }
+        Type::BorrowedRef {
+            is_mutable: true, ..
+        } => {
+            "acts like a mutable borrow: invariant in the borrowed type, and tied to the \
+             lifetime"
+        }
+        Type::RawPointer {
+            is_mutable: false, ..
+        } => {
+            "covariant without implying ownership, and opts out of Send and Sync (which \
+             usually need an explicit unsafe impl)"
+        }
+        Type::RawPointer {
+            is_mutable: true, ..
+        } => "invariant without implying ownership, and opts out of Send and Sync",
+        Type::FunctionPointer(function_pointer) if function_pointer.sig.inputs.is_empty() => {
+            "covariant without implying ownership; Send and Sync regardless of the type"
+        }
+        Type::FunctionPointer(_) => {
+            "contravariant in the argument types, which must outlive any use; Send and Sync \
+             regardless of them"
+        }
+        _ => {
+            "acts as if the type owns a value of the marked type: covariant, Send, Sync and \
+             Unpin only when it is, and dropping the type may drop one"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phantom_data_meanings_follow_the_marked_type() {
+        let raw_const = Type::RawPointer {
+            is_mutable: false,
+            type_: Box::new(Type::Generic("T".into())),
+        };
+        assert!(phantom_data_meaning(&raw_const).contains("opts out of Send and Sync"));
+        assert!(phantom_data_meaning(&Type::Generic("T".into())).contains("owns"));
+        assert!(phantom_data_meaning(&Type::Tuple(vec![])).contains("zero-sized"));
+    }
+}
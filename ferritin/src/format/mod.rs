@@ -1,5 +1,9 @@
+use crate::format_context::FormatContext;
 use crate::request::Request;
-use crate::styled_string::{DocumentNode, Span as StyledSpan, TruncationLevel};
+use crate::styled_string::{
+    DocumentNode, ShowWhen, Span as StyledSpan, TruncationLevel, TuiAction,
+};
+use crate::workspace_history::WorkspaceHistory;
 use ferritin_common::doc_ref::DocRef;
 use rustdoc_types::{
     Abi, Constant, Enum, Function, FunctionPointer, GenericArg, GenericArgs, GenericBound,
@@ -14,41 +18,52 @@ mod r#enum;
 mod functions;
 mod impls;
 mod items;
+mod macros;
+mod markers;
 mod r#module;
+mod scraped_examples;
 mod source;
 mod r#struct;
 mod r#trait;
 mod types;
 
+pub(crate) use scraped_examples::ExampleFiles;
+
 impl Request {
     /// Format an item with automatic recursion tracking
     pub(crate) fn format_item<'a>(&'a self, item: DocRef<'a, Item>) -> Vec<DocumentNode<'a>> {
-        let mut doc_nodes = vec![];
-
-        // Item metadata (name, kind, visibility, location, crate)
-        doc_nodes.extend(self.format_item_metadata(item));
+        self.format_item_with(item, self.format_context())
+    }
 
-        // Add documentation if available
-        if let Some(docs) = self.docs_to_show(item, TruncationLevel::Full) {
-            doc_nodes.extend(docs);
-        };
+    /// Format an item with per-call settings, like `--source` and `--recursive`, taken from
+    /// `context` rather than from the request's own context
+    pub(crate) fn format_item_with<'a>(
+        &'a self,
+        item: DocRef<'a, Item>,
+        context: &FormatContext,
+    ) -> Vec<DocumentNode<'a>> {
+        let mut doc_nodes = self.format_item_heading(item, context);
+        let signature_only = context.verbosity().is_signature();
 
         // Handle different item types
         match item.inner() {
             ItemEnum::Module(_) => {
-                doc_nodes.extend(self.format_module(item));
+                doc_nodes.extend(self.format_module(item, context));
             }
             ItemEnum::Struct(struct_data) => {
-                doc_nodes.extend(self.format_struct(item, item.build_ref(struct_data)));
+                doc_nodes.extend(self.format_struct(item, item.build_ref(struct_data), context));
             }
             ItemEnum::Enum(enum_data) => {
-                doc_nodes.extend(self.format_enum(item, item.build_ref(enum_data)));
+                doc_nodes.extend(self.format_enum(item, item.build_ref(enum_data), context));
             }
             ItemEnum::Trait(trait_data) => {
-                doc_nodes.extend(self.format_trait(item, item.build_ref(trait_data)));
+                doc_nodes.extend(self.format_trait(item, item.build_ref(trait_data), context));
             }
             ItemEnum::Function(function_data) => {
                 doc_nodes.extend(self.format_function(item, item.build_ref(function_data)));
+                if !signature_only {
+                    doc_nodes.extend(self.format_scraped_examples(item));
+                }
             }
             ItemEnum::TypeAlias(type_alias_data) => {
                 doc_nodes.extend(self.format_type_alias(item, item.build_ref(type_alias_data)));
@@ -59,14 +74,17 @@ impl Request {
             ItemEnum::Constant { type_, const_ } => {
                 doc_nodes.extend(self.format_constant(item, type_, const_));
             }
+            ItemEnum::AssocConst { .. } | ItemEnum::AssocType { .. } => {
+                doc_nodes.extend(self.format_assoc_item(item));
+            }
             ItemEnum::Static(static_data) => {
                 doc_nodes.extend(self.format_static(item, static_data));
             }
             ItemEnum::Macro(macro_def) => {
-                doc_nodes.push(DocumentNode::paragraph(vec![StyledSpan::plain(
-                    "Macro definition:",
-                )]));
-                doc_nodes.push(DocumentNode::code_block(Some("rust"), macro_def));
+                doc_nodes.extend(self.format_macro(item, macro_def, signature_only));
+            }
+            ItemEnum::ProcMacro(proc_macro) => {
+                doc_nodes.extend(self.format_proc_macro(item, proc_macro));
             }
             _ => {
                 // For any other item, just print its name and kind
@@ -79,15 +97,127 @@ impl Request {
         }
 
         // Add source code if requested
-        if self.format_context().include_source()
+        if context.include_source()
             && let Some(span) = &item.span
         {
-            doc_nodes.extend(source::format_source_code(self, span));
+            doc_nodes.extend(source::format_source_code(self, item, span));
         }
 
         doc_nodes
     }
 
+    /// Format an item like [`Self::format_item_with`], passing each top-level node to `emit`
+    /// once it is formatted, and stopping at the first error `emit` returns
+    ///
+    /// A recursive module listing is handed over a few of its items at a time, each as a
+    /// list of its own, so that a large outline can be shown before all of it is formatted.
+    /// Anything else is formatted whole first.
+    pub(crate) fn format_item_streamed<'a, E>(
+        &'a self,
+        item: DocRef<'a, Item>,
+        context: &FormatContext,
+        emit: &mut impl FnMut(DocumentNode<'a>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if matches!(item.inner(), ItemEnum::Module(_))
+            && context.is_recursive()
+            && !context.include_source()
+        {
+            self.format_item_heading(item, context)
+                .into_iter()
+                .try_for_each(&mut *emit)?;
+            return self.stream_module_outline(item, context, emit);
+        }
+
+        self.format_item_with(item, context)
+            .into_iter()
+            .try_for_each(emit)
+    }
+
+    /// The metadata and docs that come before the part of an item's page that depends on its
+    /// kind
+    fn format_item_heading<'a>(
+        &'a self,
+        item: DocRef<'a, Item>,
+        context: &FormatContext,
+    ) -> Vec<DocumentNode<'a>> {
+        let mut doc_nodes = vec![];
+
+        if !context.verbosity().is_signature() {
+            // Item metadata (name, kind, visibility, location, crate)
+            doc_nodes.extend(self.format_item_metadata(item));
+
+            // On a dependency's crate page, point out that it was recently updated
+            doc_nodes.extend(self.format_version_change(item));
+        }
+
+        // Add documentation if available
+        if let Some(truncation_level) = context.verbosity().truncation_level()
+            && let Some(docs) = self.docs_to_show(item, truncation_level)
+        {
+            doc_nodes.extend(docs);
+        };
+
+        doc_nodes
+    }
+
+    /// If `item` is the root of a dependency that `list --changed` saw updated, a note
+    /// offering the API changes since the previous version
+    fn format_version_change<'a>(&'a self, item: DocRef<'a, Item>) -> Vec<DocumentNode<'a>> {
+        let crate_docs = item.crate_docs();
+        if item.id != crate_docs.root {
+            return vec![];
+        }
+
+        let name = crate_docs.name().to_string();
+        let Some(version) = crate_docs.version().map(ToString::to_string) else {
+            return vec![];
+        };
+        let Some(previous) = WorkspaceHistory::for_request(self)
+            .previous_version(&name, &version)
+            .map(String::from)
+        else {
+            return vec![];
+        };
+
+        vec![
+            DocumentNode::Conditional {
+                show_when: ShowWhen::Interactive,
+                nodes: vec![DocumentNode::paragraph(vec![
+                    StyledSpan::plain(format!("Updated from {previous}. ")),
+                    StyledSpan::plain("Show API changes").with_action(TuiAction::ApiDiff {
+                        crate_name: name.clone().into(),
+                        from: previous.clone().into(),
+                    }),
+                ])],
+            },
+            DocumentNode::Conditional {
+                show_when: ShowWhen::NonInteractive,
+                nodes: vec![DocumentNode::paragraph(vec![
+                    StyledSpan::plain(format!("Updated from {previous}. Run ")),
+                    StyledSpan::inline_code(format!("ferritin api-diff {name}")),
+                    StyledSpan::plain(" to see the API changes."),
+                ])],
+            },
+        ]
+    }
+
+    /// A "since 1.63" note for items that record the Rust version they were stabilized in
+    fn format_since<'a>(&self, item: DocRef<'a, Item>) -> Option<StyledSpan<'a>> {
+        let since = item.stable_since()?;
+        Some(StyledSpan::emphasis(format!(
+            " since {}",
+            format_rust_version(&since)
+        )))
+    }
+
+    /// Whether an item was stabilized after the `--min-version` being targeted
+    pub(crate) fn is_newer_than_min_version(&self, item: DocRef<'_, Item>) -> bool {
+        self.format_context()
+            .min_version()
+            .zip(item.stable_since())
+            .is_some_and(|(min_version, since)| &since > min_version)
+    }
+
     /// Format item metadata as a compact paragraph (Item, Kind, Visibility, Location, Crate)
     fn format_item_metadata<'a>(&'a self, item: DocRef<'a, Item>) -> Vec<DocumentNode<'a>> {
         let mut spans = vec![];
@@ -174,6 +304,17 @@ impl Request {
             }
         }
 
+        // Stable since
+        if let Some(since) = item.stable_since() {
+            spans.push(StyledSpan::plain("\n"));
+            spans.push(StyledSpan::strong("Stable since:"));
+            spans.push(StyledSpan::plain(" "));
+            spans.push(StyledSpan::plain(format!(
+                "Rust {}",
+                format_rust_version(&since)
+            )));
+        }
+
         vec![DocumentNode::paragraph(spans)]
     }
 
@@ -272,3 +413,12 @@ impl Request {
         vec![DocumentNode::paragraph(spans)]
     }
 }
+
+/// A Rust version as it is usually written, like `1.63` rather than `1.63.0`
+fn format_rust_version(version: &semver::Version) -> String {
+    if version.patch == 0 {
+        format!("{}.{}", version.major, version.minor)
+    } else {
+        version.to_string()
+    }
+}
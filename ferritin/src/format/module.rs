@@ -1,3 +1,4 @@
+use rayon::prelude::*;
 use rustdoc_types::ItemKind;
 
 use super::*;
@@ -18,66 +19,73 @@ const GROUP_ORDER: &[(ItemKind, &str)] = &[
     (ItemKind::Variant, "Variants"),
 ];
 
+/// An item in a module listing, with the items nested under it in a recursive outline
 #[derive(Debug)]
-struct FlatItem<'a> {
-    path: String,
+struct ListedItem<'a> {
     item: DocRef<'a, Item>,
+    children: Vec<ListedItem<'a>>,
 }
 
 impl Request {
-    /// Collect all items in a module hierarchy as flat qualified paths
-    fn collect_flat_items<'a>(
+    /// Collect the items under `item` that the context's filters let through, down to
+    /// `max_depth` levels below the listed module
+    ///
+    /// An item of a kind that isn't asked for is still listed if items nested under it are,
+    /// so the outline shows where they are. A re-export from another crate is listed without
+    /// what's in it, which is that crate's outline rather than this one's.
+    fn collect_listed_items<'a>(
         &'a self,
-        collected: &mut Vec<FlatItem<'a>>,
-        path: Option<String>,
         item: DocRef<'a, Item>,
-    ) {
-        for child in item.child_items() {
-            if let Some(item_name) = child.name() {
-                let path = path.as_deref().map_or_else(
-                    || item_name.to_string(),
-                    |path| format!("{path}::{item_name}"),
-                );
-
-                collected.push(FlatItem {
-                    path: path.clone(),
-                    item: child,
-                });
+        max_depth: Option<usize>,
+        context: &FormatContext,
+    ) -> Vec<ListedItem<'a>> {
+        if max_depth == Some(0) {
+            return vec![];
+        }
 
-                if self.format_context().is_recursive() {
-                    self.collect_flat_items(collected, Some(path), child);
+        let is_listed = |child: DocRef<'a, Item>| {
+            child.name().is_some()
+                && !self.is_newer_than_min_version(child)
+                && (context.includes_private_items() || is_public(child))
+        };
+        let mut listed = item.item_tree(
+            &|child, depth| is_listed(child) && max_depth.is_none_or(|max_depth| depth < max_depth),
+            &mut |child, mut children| {
+                let wanted = context.kinds().is_empty() || context.kinds().contains(&child.kind());
+                if !is_listed(child) || (!wanted && children.is_empty()) {
+                    return None;
                 }
-            }
-        }
+                sort_listed_items(&mut children);
+                Some(ListedItem {
+                    item: child,
+                    children,
+                })
+            },
+        );
+        sort_listed_items(&mut listed);
+        listed
     }
 
-    /// Format collected flat items with grouping by type
-    fn format_grouped_flat_items<'a>(&'a self, items: &[FlatItem<'a>]) -> Vec<DocumentNode<'a>> {
-        if items.is_empty() {
-            return vec![DocumentNode::paragraph(vec![Span::plain(
-                "No items match the current filters.",
-            )])];
-        }
-
+    /// Format listed items with grouping by type
+    fn format_grouped_items<'a>(
+        &'a self,
+        items: &[ListedItem<'a>],
+        with_docs: bool,
+    ) -> Vec<DocumentNode<'a>> {
         // Group items by filter type
-        let mut groups: HashMap<ItemKind, Vec<&FlatItem>> = HashMap::new();
-        for flat_item in items {
-            let kind = flat_item.item.kind();
-            groups.entry(kind).or_default().push(flat_item);
+        let mut groups: HashMap<ItemKind, Vec<&ListedItem>> = HashMap::new();
+        for listed_item in items {
+            let kind = listed_item.item.kind();
+            groups.entry(kind).or_default().push(listed_item);
         }
 
         let mut doc_nodes = vec![];
 
         for (kind, group_name) in GROUP_ORDER {
-            if let Some(mut group_items) = groups.remove(kind)
+            if let Some(group_items) = groups.remove(kind)
                 && !group_items.is_empty()
             {
-                group_items.sort_by_key(|a| &a.path);
-
-                let list_items: Vec<ListItem> = group_items
-                    .iter()
-                    .map(|flat_item| self.format_flat_item(flat_item))
-                    .collect();
+                let list_items = self.format_listed_items(&group_items, false, with_docs);
 
                 let section = DocumentNode::section(
                     vec![Span::plain(*group_name)],
@@ -87,13 +95,8 @@ impl Request {
             }
         }
 
-        for (kind, mut group_items) in groups {
-            group_items.sort_by_key(|a| &a.path);
-
-            let list_items: Vec<ListItem> = group_items
-                .iter()
-                .map(|flat_item| self.format_flat_item(flat_item))
-                .collect();
+        for (kind, group_items) in groups {
+            let list_items = self.format_listed_items(&group_items, false, with_docs);
 
             let section = DocumentNode::section(
                 vec![Span::plain(format!("{kind:?}"))],
@@ -105,26 +108,167 @@ impl Request {
         doc_nodes
     }
 
-    /// Format a single flat item as a ListItem
-    fn format_flat_item<'a>(&'a self, flat_item: &FlatItem<'a>) -> ListItem<'a> {
+    /// Format a group of listed items, preserving their order
+    ///
+    /// Each item renders its own docs independently, so recursive listings of large
+    /// crates are formatted in parallel.
+    fn format_listed_items<'a>(
+        &'a self,
+        items: &[&ListedItem<'a>],
+        outline: bool,
+        with_docs: bool,
+    ) -> Vec<ListItem<'a>> {
+        items
+            .par_iter()
+            .map(|listed_item| self.format_listed_item(listed_item, outline, with_docs))
+            .collect()
+    }
+
+    /// Format a single listed item as a ListItem
+    ///
+    /// In an outline, the item's kind comes before its name, and the items nested under it
+    /// follow its summary as a list of their own.
+    fn format_listed_item<'a>(
+        &'a self,
+        listed_item: &ListedItem<'a>,
+        outline: bool,
+        with_docs: bool,
+    ) -> ListItem<'a> {
+        let item = listed_item.item;
+        let kind = item.kind();
+
         // Prepend item name as a paragraph
-        let mut content = vec![DocumentNode::paragraph(vec![
-            Span::type_name(flat_item.path.clone()).with_target(Some(flat_item.item)),
-            Span::plain(" "),
-        ])];
-
-        // Add brief documentation if available
-        if let Some(docs) = self.docs_to_show(flat_item.item, TruncationLevel::SingleLine) {
-            content.extend(docs);
+        let mut name_spans = vec![];
+        if outline && let Some(label) = kind_label(kind) {
+            name_spans.push(Span::keyword(label));
+            name_spans.push(Span::plain(" "));
+        }
+        name_spans.push(
+            Span::item_name(item.name().unwrap_or("<unnamed>"), kind).with_target(Some(item)),
+        );
+        name_spans.extend(self.format_since(item));
+        name_spans.push(Span::plain(" "));
+        let mut content = vec![DocumentNode::paragraph(name_spans)];
+
+        // Add the first sentence of the item's docs
+        if with_docs && let Some(summary) = item.docs_summary() {
+            content.push(DocumentNode::truncated_block(
+                vec![DocumentNode::paragraph(vec![Span::plain(summary)])],
+                TruncationLevel::SingleLine,
+            ));
+        }
+
+        if outline && !listed_item.children.is_empty() {
+            let children = listed_item.children.iter().collect::<Vec<_>>();
+            content.push(DocumentNode::list(
+                self.format_listed_items(&children, outline, with_docs),
+            ));
         }
 
         ListItem::new(content)
     }
 
     /// Format a module
-    pub(super) fn format_module<'a>(&'a self, item: DocRef<'a, Item>) -> Vec<DocumentNode<'a>> {
-        let mut collected = Vec::new();
-        self.collect_flat_items(&mut collected, None, item);
-        self.format_grouped_flat_items(&collected)
+    ///
+    /// A recursive listing is an outline of the module's items, with the items nested in each
+    /// one under it, down to the context's depth. Otherwise the module's own items are
+    /// grouped by kind.
+    pub(super) fn format_module<'a>(
+        &'a self,
+        item: DocRef<'a, Item>,
+        context: &FormatContext,
+    ) -> Vec<DocumentNode<'a>> {
+        let outline = context.is_recursive();
+        let max_depth = if outline { context.depth() } else { Some(1) };
+        let with_docs = !context.verbosity().is_signature();
+
+        let listed = self.collect_listed_items(item, max_depth, context);
+        if listed.is_empty() {
+            return vec![no_matching_items()];
+        }
+
+        if outline {
+            let listed = listed.iter().collect::<Vec<_>>();
+            vec![DocumentNode::list(
+                self.format_listed_items(&listed, outline, with_docs),
+            )]
+        } else {
+            self.format_grouped_items(&listed, with_docs)
+        }
+    }
+
+    /// Format a recursive listing like [`Self::format_module`], passing its top-level items to
+    /// `emit` as lists of one item each
+    ///
+    /// Items are formatted in parallel a batch at a time, so the first of them are handed over
+    /// while the rest of a large crate's outline is still to be formatted.
+    pub(super) fn stream_module_outline<'a, E>(
+        &'a self,
+        item: DocRef<'a, Item>,
+        context: &FormatContext,
+        emit: &mut impl FnMut(DocumentNode<'a>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let with_docs = !context.verbosity().is_signature();
+        let listed = self.collect_listed_items(item, context.depth(), context);
+        if listed.is_empty() {
+            return emit(no_matching_items());
+        }
+
+        let listed = listed.iter().collect::<Vec<_>>();
+        for batch in listed.chunks(rayon::current_num_threads()) {
+            for list_item in self.format_listed_items(batch, true, with_docs) {
+                emit(DocumentNode::list(vec![list_item]))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a listing shows in place of items when the filters leave none
+fn no_matching_items<'a>() -> DocumentNode<'a> {
+    DocumentNode::paragraph(vec![Span::plain("No items match the current filters.")])
+}
+
+/// Put listed items in the order of their groups, then by name
+fn sort_listed_items(listed: &mut [ListedItem<'_>]) {
+    listed.sort_by(|a, b| {
+        group_position(a.item.kind())
+            .cmp(&group_position(b.item.kind()))
+            .then_with(|| a.item.name().cmp(&b.item.name()))
+    });
+}
+
+/// Where items of `kind` come in a listing: in the order of their groups, with kinds that
+/// have no group of their own last
+fn group_position(kind: ItemKind) -> usize {
+    GROUP_ORDER
+        .iter()
+        .position(|(group_kind, _)| *group_kind == kind)
+        .unwrap_or(GROUP_ORDER.len())
+}
+
+/// The keyword that introduces an item of `kind` in an outline
+fn kind_label(kind: ItemKind) -> Option<&'static str> {
+    Some(match kind {
+        ItemKind::Module => "mod",
+        ItemKind::Struct => "struct",
+        ItemKind::Enum => "enum",
+        ItemKind::Trait => "trait",
+        ItemKind::Union => "union",
+        ItemKind::TypeAlias | ItemKind::AssocType => "type",
+        ItemKind::Function => "fn",
+        ItemKind::Constant | ItemKind::AssocConst => "const",
+        ItemKind::Static => "static",
+        ItemKind::Macro => "macro",
+        _ => return None,
+    })
+}
+
+/// Whether `item` is visible outside its crate. Enum variants and the items of traits and
+/// trait impls have no visibility of their own, and are as visible as their parent.
+fn is_public(item: DocRef<'_, Item>) -> bool {
+    match item.visibility {
+        Visibility::Public | Visibility::Default => true,
+        Visibility::Crate | Visibility::Restricted { .. } => false,
     }
 }
@@ -0,0 +1,359 @@
+use super::*;
+use crate::generate_docsrs_url::generate_docsrs_url;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// Maximum number of example files to show call sites from
+const MAX_EXAMPLE_FILES: usize = 3;
+
+/// Lines of context shown on each side of a call site
+const CONTEXT_LINES: usize = 2;
+
+/// The title rustdoc gives each scraped call site, like `examples/demo.rs (line 12)`
+static SCRAPED_EXAMPLE_TITLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"class="scraped-example-title">\s*([^<]*?)\s*\(<a href="[^"]*">lines? (\d+)"#)
+        .unwrap()
+});
+
+/// A call to a function found in one of the package's examples
+struct CallSite {
+    path: PathBuf,
+    /// 0-indexed line of the call
+    line: usize,
+    snippet: String,
+}
+
+/// A file read for scraped examples, as of its modification time
+struct ExampleFile {
+    path: PathBuf,
+    modified: SystemTime,
+    content: String,
+}
+
+/// Files read for scraped examples, kept until they change on disk
+#[derive(Default)]
+pub(crate) struct ExampleFiles(Mutex<HashMap<PathBuf, Arc<ExampleFile>>>);
+
+impl ExampleFiles {
+    /// The file at `path`, read again only when it was modified since the last time. The lock
+    /// is held to look the file up and to store it, but not while reading.
+    fn read(&self, path: &Path) -> Option<Arc<ExampleFile>> {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        let cached = self
+            .0
+            .lock()
+            .ok()
+            .and_then(|files| files.get(path).cloned());
+        if let Some(file) = cached
+            && file.modified == modified
+        {
+            return Some(file);
+        }
+
+        let file = Arc::new(ExampleFile {
+            path: path.to_path_buf(),
+            modified,
+            content: fs::read_to_string(path).ok()?,
+        });
+        if let Ok(mut files) = self.0.lock() {
+            files.insert(path.to_path_buf(), file.clone());
+        }
+        Some(file)
+    }
+
+    /// The `.rs` files under `examples_dir`, sorted by path
+    fn read_dir(&self, examples_dir: &Path) -> Vec<Arc<ExampleFile>> {
+        let mut paths: Vec<PathBuf> = WalkDir::new(examples_dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "rs"))
+            .map(walkdir::DirEntry::into_path)
+            .collect();
+        paths.sort();
+        paths.iter().filter_map(|path| self.read(path)).collect()
+    }
+}
+
+impl Request {
+    /// Format call sites of a workspace function found in its package's `examples/` directory
+    ///
+    /// This is the same "Examples found in repository" listing that rustdoc's
+    /// `--scrape-examples` adds to HTML output. Rustdoc doesn't include scraped call sites in
+    /// its JSON output, so they are read from the HTML docs when those were built with
+    /// `cargo doc -Zrustdoc-scrape-examples`. Otherwise we find them by scanning the examples
+    /// that refer to the item's path (or its type's, for a method) for calls by name.
+    pub(super) fn format_scraped_examples<'a>(
+        &'a self,
+        item: DocRef<'a, Item>,
+    ) -> Vec<DocumentNode<'a>> {
+        if !item.crate_docs().provenance().is_workspace() {
+            return vec![];
+        }
+
+        let (Some(name), Some(span)) = (item.name(), &item.span) else {
+            return vec![];
+        };
+
+        // Examples get at a method through its type, and at anything else by its own path
+        let Some(import_path) = item.path().or_else(|| item.parent()?.path()) else {
+            return vec![];
+        };
+        let import_path: Vec<&str> = import_path.into_iter().collect();
+
+        let Some(package_dir) = self.span_file_path(span).as_deref().and_then(package_root) else {
+            return vec![];
+        };
+
+        let call_sites = match self.rustdoc_call_sites(item, &package_dir) {
+            Some(call_sites) => call_sites,
+            None => self
+                .example_files()
+                .read_dir(&package_dir.join("examples"))
+                .iter()
+                .filter(|file| imports(&file.content, &import_path))
+                .filter_map(|file| find_call_site(file, name))
+                .collect(),
+        };
+
+        if call_sites.is_empty() {
+            return vec![];
+        }
+
+        let mut nodes = vec![];
+        for call_site in call_sites.iter().take(MAX_EXAMPLE_FILES) {
+            let location = call_site
+                .path
+                .strip_prefix(self.project_root().unwrap_or(Path::new("")))
+                .unwrap_or(&call_site.path);
+            nodes.push(DocumentNode::paragraph(vec![StyledSpan::plain(format!(
+                "{}:{}",
+                location.display(),
+                call_site.line + 1
+            ))]));
+            nodes.push(DocumentNode::code_block(
+                Some("rust"),
+                call_site.snippet.clone(),
+            ));
+        }
+
+        let more = call_sites.len().saturating_sub(MAX_EXAMPLE_FILES);
+        if more > 0 {
+            nodes.push(DocumentNode::paragraph(vec![StyledSpan::plain(format!(
+                "…and {more} more example file{}",
+                if more == 1 { "" } else { "s" }
+            ))]));
+        }
+
+        if self.format_context().collapse_examples() {
+            nodes = vec![DocumentNode::truncated_block(nodes, TruncationLevel::Brief)];
+        }
+
+        vec![DocumentNode::section(
+            vec![StyledSpan::plain("Examples found in repository")],
+            nodes,
+        )]
+    }
+
+    /// The call sites rustdoc listed on the item's page in the HTML docs, or None if those
+    /// weren't built or were built without scraping examples
+    fn rustdoc_call_sites(
+        &self,
+        item: DocRef<'_, Item>,
+        package_dir: &Path,
+    ) -> Option<Vec<CallSite>> {
+        // The page's path under the doc directory is the same as on docs.rs
+        let docs = item.crate_docs();
+        let docs_rs = format!(
+            "https://docs.rs/{}/{}/",
+            docs.name(),
+            docs.crate_version.as_deref().unwrap_or("latest")
+        );
+        let url = generate_docsrs_url(item);
+        let (page, anchor) = match url.strip_prefix(&docs_rs)?.split_once('#') {
+            Some((page, anchor)) => (page, Some(anchor)),
+            None => (url.strip_prefix(&docs_rs)?, None),
+        };
+
+        let doc_dir = self.local_source()?.target_dir().join("doc");
+        let html = self.example_files().read(&doc_dir.join(page))?;
+
+        // Example paths are relative to the directory cargo ran rustdoc in, which is the
+        // workspace root for a workspace member
+        let call_sites: Vec<CallSite> = scraped_call_sites(&html.content, anchor)
+            .into_iter()
+            .filter_map(|(name, line)| {
+                let path = package_dir
+                    .ancestors()
+                    .map(|dir| dir.join(&name))
+                    .find(|path| path.is_file())?;
+                call_site_at(&*self.example_files().read(&path)?, line.checked_sub(1)?)
+            })
+            .collect();
+        (!call_sites.is_empty()).then_some(call_sites)
+    }
+}
+
+/// The nearest ancestor directory of `path` containing a `Cargo.toml`
+fn package_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Whether `content` refers to the item at `path`, by its full path or by importing it from
+/// its module with a `use` list or a glob
+fn imports(content: &str, path: &[&str]) -> bool {
+    let Some((_, module)) = path.split_last() else {
+        return false;
+    };
+    let module = module.join("::");
+    content.contains(&path.join("::"))
+        || (!module.is_empty()
+            && (content.contains(&format!("{module}::{{"))
+                || content.contains(&format!("{module}::*"))))
+}
+
+/// The example files and lines rustdoc lists under "Examples found in repository" in `html`,
+/// in the part of the page for `anchor` if there is one, like `method.parse`
+fn scraped_call_sites(html: &str, anchor: Option<&str>) -> Vec<(String, usize)> {
+    let html = match anchor {
+        Some(anchor) => {
+            let Some(start) = html.find(&format!("id=\"{anchor}\"")) else {
+                return vec![];
+            };
+            let section = &html[start..];
+            // The next method, or whatever else has its own anchor, ends this one's docs
+            let kind = anchor.split('.').next().unwrap_or(anchor);
+            let end = section[1..]
+                .find(&format!("id=\"{kind}."))
+                .map_or(section.len(), |end| end + 1);
+            &section[..end]
+        }
+        None => html,
+    };
+
+    SCRAPED_EXAMPLE_TITLE
+        .captures_iter(html)
+        .filter_map(|captures| Some((captures[1].to_string(), captures[2].parse().ok()?)))
+        .collect()
+}
+
+/// The first call to `name` in `file`, with surrounding context
+fn find_call_site(file: &ExampleFile, name: &str) -> Option<CallSite> {
+    let line = file.content.lines().position(|line| calls(line, name))?;
+    call_site_at(file, line)
+}
+
+/// The call at `line` (0-indexed) of `file`, with surrounding context
+fn call_site_at(file: &ExampleFile, line: usize) -> Option<CallSite> {
+    let lines: Vec<&str> = file.content.lines().collect();
+    if line >= lines.len() {
+        return None;
+    }
+
+    let start = line.saturating_sub(CONTEXT_LINES);
+    let end = (line + CONTEXT_LINES).min(lines.len() - 1);
+    let snippet = lines[start..=end].join("\n");
+
+    Some(CallSite {
+        path: file.path.clone(),
+        line,
+        snippet,
+    })
+}
+
+/// Whether `line` contains a call to `name`, as `name(`, `name::<` or `.name(`
+fn calls(line: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+    line.match_indices(name).any(|(start, _)| {
+        let before = &line[..start];
+        let after = &line[start + name.len()..];
+        !before.ends_with(is_ident)
+            && !before
+                .trim_end()
+                .strip_suffix("fn")
+                .is_some_and(|rest| !rest.ends_with(is_ident))
+            && (after.starts_with('(') || after.starts_with("::<"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn call_sites_are_matched_by_name() {
+        assert!(calls("    let x = parse(input);", "parse"));
+        assert!(calls("    value.parse::<u8>()", "parse"));
+        assert!(calls("    thing.parse()", "parse"));
+        assert!(!calls("    try_parse(input)", "parse"));
+        assert!(!calls("fn parse(input: &str) {}", "parse"));
+        assert!(!calls("    let parse = 1;", "parse"));
+    }
+
+    #[test]
+    fn examples_refer_to_the_item_by_path() {
+        let path = ["my_crate", "config", "parse"];
+        assert!(imports("use my_crate::config::parse;", &path));
+        assert!(imports("let c = my_crate::config::parse(s);", &path));
+        assert!(imports("use my_crate::config::{load, parse};", &path));
+        assert!(imports("use my_crate::config::*;", &path));
+        assert!(!imports("use other_crate::parse;", &path));
+        assert!(!imports("let x: u8 = s.parse().unwrap();", &path));
+    }
+
+    #[test]
+    fn rustdoc_call_sites_are_read_from_the_items_part_of_the_page() {
+        let title = |name: &str, lines: &str| {
+            format!(
+                "<div class=\"scraped-example-title\">\n   {name} (<a href=\"../src/x.rs.html#1\">\
+                 {lines}</a>)</div>"
+            )
+        };
+        let html = format!(
+            "<section id=\"method.load\"></section>{}\
+             <section id=\"method.parse\"></section>{}{}\
+             <section id=\"method.save\"></section>",
+            title("examples/load.rs", "line 3"),
+            title("examples/parse.rs", "line 12"),
+            title("examples/nested/more.rs", "lines 40-42"),
+        );
+
+        assert_eq!(
+            scraped_call_sites(&html, Some("method.parse")),
+            [
+                ("examples/parse.rs".to_string(), 12),
+                ("examples/nested/more.rs".to_string(), 40)
+            ]
+        );
+        assert_eq!(scraped_call_sites(&html, None).len(), 3);
+        assert!(scraped_call_sites(&html, Some("method.save")).is_empty());
+    }
+
+    #[test]
+    fn example_files_are_read_again_once_changed() {
+        let path = std::env::temp_dir().join(format!("ferritin-scraped-{}.rs", std::process::id()));
+        let files = ExampleFiles::default();
+
+        fs::write(&path, "fn main() { old(); }").unwrap();
+        let first = files.read(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &files.read(&path).unwrap()));
+
+        fs::write(&path, "fn main() { new(); }").unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(files.read(&path).unwrap().content, "fn main() { new(); }");
+
+        fs::remove_file(&path).unwrap();
+    }
+}
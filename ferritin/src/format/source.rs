@@ -1,15 +1,13 @@
 use super::*;
-use crate::styled_string::{DocumentNode, Span as StyledSpan};
+use crate::styled_string::{DocumentNode, LineNumbers, Span as StyledSpan};
 
 /// Format source code
-pub(crate) fn format_source_code<'a>(request: &'a Request, span: &Span) -> Vec<DocumentNode<'a>> {
-    // Resolve the file path - if it's relative, make it relative to the project root
-    let file_path = if span.filename.is_absolute() {
-        span.filename.clone()
-    } else if let Some(project_root) = request.project_root() {
-        project_root.join(&span.filename)
-    } else {
-        // No project and relative path - can't resolve
+pub(crate) fn format_source_code<'a>(
+    request: &'a Request,
+    item: DocRef<'a, Item>,
+    span: &Span,
+) -> Vec<DocumentNode<'a>> {
+    let Some(file_path) = request.source_file_path(item, span) else {
         return vec![];
     };
 
@@ -37,12 +35,21 @@ pub(crate) fn format_source_code<'a>(request: &'a Request, span: &Span) -> Vec<D
     // Collect source lines
     let code = lines[context_start..=context_end].join("\n");
 
-    // Build document nodes
+    // Build document nodes, with the item's first line in the header so that it can be opened
+    // from there
     vec![
         DocumentNode::paragraph(vec![StyledSpan::plain(format!(
-            "Source: {}",
-            file_path.display()
+            "Source: {}:{}",
+            file_path.display(),
+            start_line + 1
         ))]),
-        DocumentNode::code_block(Some("rust"), code),
+        DocumentNode::source_block(
+            Some("rust"),
+            code,
+            LineNumbers {
+                first: context_start + 1,
+                highlighted: start_line + 1..=end_line + 1,
+            },
+        ),
     ]
 }
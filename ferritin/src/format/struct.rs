@@ -6,14 +6,39 @@ impl Request {
         &'a self,
         item: DocRef<'a, Item>,
         r#struct: DocRef<'a, Struct>,
+        context: &FormatContext,
     ) -> Vec<DocumentNode<'a>> {
+        let signature_only = context.verbosity().is_signature();
         let mut doc_nodes = match &r#struct.kind {
             StructKind::Unit => self.format_unit_struct(r#struct, item),
-            StructKind::Tuple(fields) => self.format_tuple_struct(r#struct, item, fields),
-            StructKind::Plain { fields, .. } => self.format_plain_struct(r#struct, item, fields),
+            StructKind::Tuple(fields) => {
+                self.format_tuple_struct(r#struct, item, fields, signature_only)
+            }
+            StructKind::Plain { fields, .. } => {
+                self.format_plain_struct(r#struct, item, fields, signature_only)
+            }
         };
 
-        doc_nodes.extend(self.format_associated_methods(item));
+        if signature_only {
+            doc_nodes.extend(self.format_associated_methods(item, context));
+            return doc_nodes;
+        }
+
+        let field_ids: Vec<&Id> = match &r#struct.kind {
+            StructKind::Unit => vec![],
+            StructKind::Tuple(fields) => fields.iter().flatten().collect(),
+            StructKind::Plain { fields, .. } => fields.iter().collect(),
+        };
+        let field_types = field_ids
+            .into_iter()
+            .filter_map(|id| match item.get(id)?.inner() {
+                ItemEnum::StructField(field_type) => Some(field_type),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        doc_nodes.extend(self.format_marker_notes(item, &field_types));
+
+        doc_nodes.extend(self.format_associated_methods(item, context));
 
         doc_nodes
     }
@@ -43,6 +68,7 @@ impl Request {
         struct_data: DocRef<'a, Struct>,
         item: DocRef<'a, Item>,
         fields: &[Id],
+        signature_only: bool,
     ) -> Vec<DocumentNode<'a>> {
         use crate::styled_string::{DocumentNode, ListItem, Span};
 
@@ -107,6 +133,9 @@ impl Request {
 
         // Add signature as generated code block
         doc_nodes.push(DocumentNode::generated_code(code_spans));
+        if signature_only {
+            return doc_nodes;
+        }
 
         // Build fields section with List
         let field_items: Vec<ListItem> = visible_fields
@@ -149,6 +178,7 @@ impl Request {
         struct_data: DocRef<'a, Struct>,
         item: DocRef<'a, Item>,
         fields: &[Option<Id>],
+        signature_only: bool,
     ) -> Vec<DocumentNode<'a>> {
         use crate::styled_string::{DocumentNode, ListItem, Span};
 
@@ -221,6 +251,9 @@ impl Request {
 
         // Add signature as generated code block
         doc_nodes.push(DocumentNode::generated_code(code_spans));
+        if signature_only {
+            return doc_nodes;
+        }
 
         // Build fields section with List
         let field_items: Vec<ListItem> = visible_fields
@@ -7,6 +7,7 @@ impl Request {
         &'a self,
         item: DocRef<'a, Item>,
         trait_data: DocRef<'a, Trait>,
+        context: &FormatContext,
     ) -> Vec<DocumentNode<'a>> {
         let trait_name = item.name().unwrap_or("<unnamed>");
 
@@ -38,6 +39,10 @@ impl Request {
         let mut member_items = vec![];
 
         for trait_item in item.id_iter(&trait_data.item().items) {
+            if self.is_newer_than_min_version(trait_item) {
+                continue;
+            }
+
             let item_name = trait_item.name().unwrap_or("<unnamed>");
 
             let signature_spans = match &trait_item.item().inner {
@@ -70,12 +75,15 @@ impl Request {
             // Prepend signature as a paragraph
             let mut item_content = vec![DocumentNode::paragraph({
                 let mut sig = signature_spans;
+                sig.extend(self.format_since(trait_item));
                 sig.push(Span::plain(" "));
                 sig
             })];
 
             // Add docs if available
-            if let Some(docs) = self.docs_to_show(trait_item, TruncationLevel::SingleLine) {
+            if !context.verbosity().is_signature()
+                && let Some(docs) = self.docs_to_show(trait_item, TruncationLevel::SingleLine)
+            {
                 item_content.extend(docs);
             }
 
@@ -89,7 +97,7 @@ impl Request {
         nodes
     }
 
-    fn format_trait_assoc_const_signature<'a>(
+    pub(super) fn format_trait_assoc_const_signature<'a>(
         &self,
         item: DocRef<'a, Item>,
         type_: &'a Type,
@@ -99,7 +107,7 @@ impl Request {
         let mut spans = vec![
             Span::keyword("const"),
             Span::plain(" "),
-            Span::plain(const_name),
+            Span::const_name(const_name),
             Span::punctuation(":"),
             Span::plain(" "),
         ];
@@ -117,7 +125,7 @@ impl Request {
         spans
     }
 
-    fn format_trait_assoc_type_signature<'a>(
+    pub(super) fn format_trait_assoc_type_signature<'a>(
         &self,
         item: DocRef<'a, Item>,
         generics: &'a Generics,
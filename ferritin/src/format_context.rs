@@ -1,50 +1,211 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use rustdoc_types::ItemKind;
+use semver::Version;
+
+use crate::renderer::OutputMode;
+use crate::verbosity::Verbosity;
 
 /// Context for formatting operations
 ///
 /// This contains configuration that determines what content to include in Documents.
 /// Separate from RenderContext (which controls how to display Documents).
-#[derive(Debug)]
+///
+/// A [`Request`](crate::request::Request) holds the session's context. Settings that vary from
+/// one call to the next, like `--source`, are applied to a clone and passed to
+/// [`Request::format_item_with`](crate::request::Request::format_item_with), so nothing shared
+/// is mutated while formatting.
+#[derive(Debug, Clone, Default)]
 pub(crate) struct FormatContext {
-    /// Whether to include source code snippets (toggled at runtime)
-    include_source: AtomicBool,
+    /// Whether to include source code snippets
+    include_source: bool,
     /// Whether to show recursive/nested content
-    recursive: AtomicBool,
+    recursive: bool,
+    /// How many levels of nested items a recursive listing goes down, if it's limited
+    depth: Option<usize>,
+    /// The kinds of items a module listing shows, or every kind if empty
+    kinds: Vec<ItemKind>,
+    /// Whether module listings show private items, where the docs have them
+    include_private: bool,
+    /// Whether `# Examples` sections start collapsed (for the interactive renderer)
+    collapse_examples: bool,
+    /// Rust version being targeted: items stabilized after it are left out
+    min_version: Option<Version>,
+    /// How much of an item's own docs to show
+    verbosity: Verbosity,
+    /// Columns that signatures are laid out to fit in, over several lines if need be
+    terminal_width: Option<usize>,
+    /// How the formatted document is shown, when a client asked for it with its request
+    output_mode: Option<OutputMode>,
+    /// The theme the formatted document is shown in, when a client asked for it with its
+    /// request
+    theme: Option<String>,
 }
 
 impl FormatContext {
     pub(crate) fn new() -> Self {
-        Self {
-            include_source: AtomicBool::new(false),
-            recursive: AtomicBool::new(false),
-        }
+        Self::default()
     }
 
     /// Check if source code should be included
     pub(crate) fn include_source(&self) -> bool {
-        self.include_source.load(Ordering::Relaxed)
+        self.include_source
     }
 
-    /// Set source code inclusion (thread-safe)
-    pub(crate) fn set_include_source(&self, value: bool) -> &Self {
-        self.include_source.store(value, Ordering::Relaxed);
-        self // For chaining
+    /// Builder method for source code inclusion
+    pub(crate) fn with_include_source(mut self, value: bool) -> Self {
+        self.include_source = value;
+        self
     }
 
     /// Check if recursive display is enabled
     pub(crate) fn is_recursive(&self) -> bool {
-        self.recursive.load(Ordering::Relaxed)
+        self.recursive
     }
 
-    /// Set recursive display (thread-safe)
-    pub(crate) fn set_recursive(&self, value: bool) -> &Self {
-        self.recursive.store(value, Ordering::Relaxed);
-        self // For chaining
+    /// Builder method for recursive
+    pub(crate) fn with_recursion(mut self, value: bool) -> Self {
+        self.recursive = value;
+        self
     }
 
-    /// Builder method for recursive
-    pub(crate) fn with_recursion(self, value: bool) -> Self {
-        self.set_recursive(value);
+    /// How many levels of nested items a recursive listing goes down, if it's limited
+    pub(crate) fn depth(&self) -> Option<usize> {
+        self.depth
+    }
+
+    /// Builder method for limiting the depth of recursive listings
+    pub(crate) fn with_depth(mut self, depth: Option<usize>) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// The kinds of items a module listing shows, or every kind if empty
+    pub(crate) fn kinds(&self) -> &[ItemKind] {
+        &self.kinds
+    }
+
+    /// Builder method for the kinds of items a module listing shows
+    pub(crate) fn with_kinds(mut self, kinds: Vec<ItemKind>) -> Self {
+        self.kinds = kinds;
+        self
+    }
+
+    /// Check if module listings show private items
+    pub(crate) fn includes_private_items(&self) -> bool {
+        self.include_private
+    }
+
+    /// Builder method for showing private items in module listings
+    pub(crate) fn with_private_items(mut self, value: bool) -> Self {
+        self.include_private = value;
+        self
+    }
+
+    /// Check if examples sections should start collapsed
+    pub(crate) fn collapse_examples(&self) -> bool {
+        self.collapse_examples
+    }
+
+    /// Builder method for collapsing examples sections
+    pub(crate) fn with_collapsed_examples(mut self, value: bool) -> Self {
+        self.collapse_examples = value;
+        self
+    }
+
+    /// The Rust version being targeted, if items newer than it are hidden
+    pub(crate) fn min_version(&self) -> Option<&Version> {
+        self.min_version.as_ref()
+    }
+
+    /// Builder method for hiding items stabilized after a Rust version
+    pub(crate) fn with_min_version(mut self, min_version: Option<Version>) -> Self {
+        self.min_version = min_version;
+        self
+    }
+
+    /// How much of an item's own docs to show
+    pub(crate) fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    /// Builder method for how much of an item's own docs to show
+    pub(crate) fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Columns that signatures are laid out to fit in, or `None` to keep each on one line
+    pub(crate) fn terminal_width(&self) -> Option<usize> {
+        self.terminal_width
+    }
+
+    /// Builder method for the columns signatures are laid out to fit in
+    pub(crate) fn with_terminal_width(mut self, terminal_width: Option<usize>) -> Self {
+        self.terminal_width = terminal_width;
+        self
+    }
+
+    /// How the formatted document is shown, if the request said
+    pub(crate) fn output_mode(&self) -> Option<OutputMode> {
+        self.output_mode
+    }
+
+    /// Builder method for how the formatted document is shown
+    pub(crate) fn with_output_mode(mut self, output_mode: Option<OutputMode>) -> Self {
+        self.output_mode = output_mode;
+        self
+    }
+
+    /// The theme the formatted document is shown in, if the request said
+    pub(crate) fn theme(&self) -> Option<&str> {
+        self.theme.as_deref()
+    }
+
+    /// Builder method for the theme the formatted document is shown in
+    pub(crate) fn with_theme(mut self, theme: Option<String>) -> Self {
+        self.theme = theme;
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_survive_a_clone() {
+        let session = FormatContext::new()
+            .with_include_source(true)
+            .with_recursion(true)
+            .with_depth(Some(2))
+            .with_kinds(vec![ItemKind::Struct])
+            .with_private_items(true)
+            .with_collapsed_examples(true)
+            .with_min_version(Some(Version::new(1, 70, 0)))
+            .with_verbosity(Verbosity::Minimal)
+            .with_terminal_width(Some(80))
+            .with_output_mode(Some(OutputMode::Plain))
+            .with_theme(Some("nord".into()));
+
+        // As the daemon does: per-request settings go on a clone of the session's context
+        let request = session
+            .clone()
+            .with_terminal_width(None)
+            .with_theme(Some("dracula".into()));
+
+        for context in [&session, &request] {
+            assert!(context.include_source());
+            assert!(context.is_recursive());
+            assert_eq!(context.depth(), Some(2));
+            assert_eq!(context.kinds(), [ItemKind::Struct]);
+            assert!(context.includes_private_items());
+            assert!(context.collapse_examples());
+            assert_eq!(context.min_version(), Some(&Version::new(1, 70, 0)));
+            assert_eq!(context.verbosity(), Verbosity::Minimal);
+            assert_eq!(context.output_mode(), Some(OutputMode::Plain));
+        }
+        assert_eq!(session.terminal_width(), Some(80));
+        assert_eq!(session.theme(), Some("nord"));
+        assert_eq!(request.terminal_width(), None);
+        assert_eq!(request.theme(), Some("dracula"));
+    }
+}
@@ -98,6 +98,9 @@ fn generate_url_for_item_with_path(
                 rustdoc_types::ItemKind::Primitive => {
                     format!("{}/{}/primitive.{}.html", base, crate_name, item_name)
                 }
+                rustdoc_types::ItemKind::Keyword => {
+                    format!("{}/{}/keyword.{}.html", base, crate_name, item_name)
+                }
                 _ => {
                     // Fallback for unknown kinds
                     format!("{}/{}/", base, crate_name)
@@ -119,7 +122,7 @@ fn generate_url_for_associated_item(
     let kind = item.kind();
 
     // Search through all impl blocks to find which one contains this item
-    for impl_item in docs.index.values() {
+    for impl_item in docs.items() {
         if let ItemEnum::Impl(impl_block) = &impl_item.inner
             && impl_block.items.contains(item_id)
         {
@@ -158,7 +161,7 @@ fn generate_url_for_associated_item(
     // Check if this is an enum variant
     if matches!(kind, rustdoc_types::ItemKind::Variant) {
         // Find the parent enum
-        for enum_item in docs.index.values() {
+        for enum_item in docs.items() {
             if let ItemEnum::Enum(enum_data) = &enum_item.inner
                 && enum_data.variants.contains(item_id)
             {
@@ -172,7 +175,7 @@ fn generate_url_for_associated_item(
     // Check if this is a struct field
     if matches!(kind, rustdoc_types::ItemKind::StructField) {
         // Find the parent struct
-        for struct_item in docs.index.values() {
+        for struct_item in docs.items() {
             if let ItemEnum::Struct(struct_data) = &struct_item.inner
                 && matches!(&struct_data.kind, rustdoc_types::StructKind::Plain { fields, .. } if fields.contains(item_id))
             {
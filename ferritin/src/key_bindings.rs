@@ -0,0 +1,553 @@
+//! The keymap for interactive mode actions
+//!
+//! Each action has default keys, which the `[keys]` table of the config file can replace:
+//!
+//! ```toml
+//! [keys]
+//! down = ["k", "down"]
+//! up = ["j", "up"]
+//! search = "C-s"
+//! ```
+//!
+//! Keys are written like `x`, `S`, `C-x` (control), `M-x` (alt), or a name like `enter`,
+//! `space`, `tab`, `pagedown` or `left`, and a sequence of keys like `g t` with spaces between
+//! them. A key can only do one thing, so moving a key to another action means giving its old
+//! action other keys too. A key that is bound by itself and also starts a sequence, like `g`,
+//! does its own action when the key after it doesn't go on with a sequence. Esc and Ctrl+g
+//! always cancel.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// A key with its modifiers, apart from shift
+pub(crate) type Key = (KeyCode, KeyModifiers);
+
+/// Something a key does in interactive mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Action {
+    Quit,
+    Down,
+    Up,
+    Open,
+    NextLink,
+    PreviousLink,
+    Hints,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    Recenter,
+    RecenterTop,
+    RecenterBottom,
+    Top,
+    Bottom,
+    Back,
+    Forward,
+    GoTo,
+    Search,
+    SaveSearch,
+    Home,
+    List,
+    Crates,
+    Source,
+    ToggleBlock,
+    NewTab,
+    NextTab,
+    PreviousTab,
+    CloseTab,
+    Themes,
+    Messages,
+    Command,
+    Mouse,
+    Help,
+}
+
+impl Action {
+    pub(crate) const ALL: [Action; 35] = [
+        Action::Quit,
+        Action::Down,
+        Action::Up,
+        Action::Open,
+        Action::NextLink,
+        Action::PreviousLink,
+        Action::Hints,
+        Action::ScrollDown,
+        Action::ScrollUp,
+        Action::PageDown,
+        Action::PageUp,
+        Action::Recenter,
+        Action::RecenterTop,
+        Action::RecenterBottom,
+        Action::Top,
+        Action::Bottom,
+        Action::Back,
+        Action::Forward,
+        Action::GoTo,
+        Action::Search,
+        Action::SaveSearch,
+        Action::Home,
+        Action::List,
+        Action::Crates,
+        Action::Source,
+        Action::ToggleBlock,
+        Action::NewTab,
+        Action::NextTab,
+        Action::PreviousTab,
+        Action::CloseTab,
+        Action::Themes,
+        Action::Messages,
+        Action::Command,
+        Action::Mouse,
+        Action::Help,
+    ];
+
+    /// The action's name in the `[keys]` table
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Open => "open",
+            Action::NextLink => "next-link",
+            Action::PreviousLink => "previous-link",
+            Action::Hints => "hints",
+            Action::ScrollDown => "scroll-down",
+            Action::ScrollUp => "scroll-up",
+            Action::PageDown => "page-down",
+            Action::PageUp => "page-up",
+            Action::Recenter => "recenter",
+            Action::RecenterTop => "recenter-top",
+            Action::RecenterBottom => "recenter-bottom",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::Back => "back",
+            Action::Forward => "forward",
+            Action::GoTo => "go-to",
+            Action::Search => "search",
+            Action::SaveSearch => "save-search",
+            Action::Home => "home",
+            Action::List => "list",
+            Action::Crates => "crates",
+            Action::Source => "source",
+            Action::ToggleBlock => "toggle-block",
+            Action::NewTab => "new-tab",
+            Action::NextTab => "next-tab",
+            Action::PreviousTab => "previous-tab",
+            Action::CloseTab => "close-tab",
+            Action::Themes => "themes",
+            Action::Messages => "messages",
+            Action::Command => "command",
+            Action::Mouse => "mouse",
+            Action::Help => "help",
+        }
+    }
+
+    /// What the action does, for the help screen
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Down => "Scroll down",
+            Action::Up => "Scroll up",
+            Action::Open => "Open focused link",
+            Action::NextLink => "Focus the next link",
+            Action::PreviousLink => "Focus the previous link",
+            Action::Hints => "Follow a link by typing its hint",
+            Action::ScrollDown => "Scroll down a line",
+            Action::ScrollUp => "Scroll up a line",
+            Action::PageDown => "Page down",
+            Action::PageUp => "Page up",
+            Action::Recenter => "Scroll the focused link to the middle of the screen",
+            Action::RecenterTop => "Scroll the focused link to the top of the screen",
+            Action::RecenterBottom => "Scroll the focused link to the bottom of the screen",
+            Action::Top => "Jump to top",
+            Action::Bottom => "Jump to bottom",
+            Action::Back => "Navigate back in history",
+            Action::Forward => "Navigate forward in history",
+            Action::GoTo => "Go to item by path",
+            Action::Search => "Search (scoped to current crate)",
+            Action::SaveSearch => "Save current search to the start page",
+            Action::Home => "Go to the start page",
+            Action::List => "List available crates",
+            Action::Crates => "Switch to another crate",
+            Action::Source => "Toggle source code display",
+            Action::ToggleBlock => "Expand or collapse the section under the cursor",
+            Action::NewTab => "Open focused link in a new tab",
+            Action::NextTab => "Next tab",
+            Action::PreviousTab => "Previous tab",
+            Action::CloseTab => "Close tab",
+            Action::Themes => "Select theme",
+            Action::Messages => "Show recent messages",
+            Action::Command => "Run a command, like :messages",
+            Action::Mouse => "Toggle mouse mode (for text selection)",
+            Action::Help => "Show this help screen",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "C-c"],
+            Action::Down => &["j", "down", "C-n"],
+            Action::Up => &["k", "up", "C-p"],
+            Action::Open => &["enter", "space"],
+            Action::NextLink => &["tab"],
+            Action::PreviousLink => &["backtab"],
+            Action::Hints => &["f"],
+            Action::ScrollDown => &["C-e"],
+            Action::ScrollUp => &["C-y"],
+            Action::PageDown => &["C-d", "C-v", "pagedown"],
+            Action::PageUp => &["C-u", "M-v", "pageup"],
+            Action::Recenter => &["z z"],
+            Action::RecenterTop => &["z t"],
+            Action::RecenterBottom => &["z b"],
+            Action::Top => &["home", "M-<"],
+            Action::Bottom => &["G", "end", "M->"],
+            Action::Back => &["left", "backspace"],
+            Action::Forward => &["right"],
+            Action::GoTo => &["g"],
+            Action::Search => &["s", "/"],
+            Action::SaveSearch => &["S"],
+            Action::Home => &["H"],
+            Action::List => &["l"],
+            Action::Crates => &["C"],
+            Action::Source => &["c"],
+            Action::ToggleBlock => &["z a"],
+            Action::NewTab => &["t"],
+            Action::NextTab => &["g t"],
+            Action::PreviousTab => &["g T"],
+            Action::CloseTab => &["w"],
+            Action::Themes => &["T"],
+            Action::Messages => &["M"],
+            Action::Command => &[":"],
+            Action::Mouse => &["m"],
+            Action::Help => &["?", "h"],
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// One key or a list of keys for an action in the `[keys]` table
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Keys {
+    fn iter(&self) -> impl Iterator<Item = &str> {
+        match self {
+            Keys::One(key) => std::slice::from_ref(key).iter(),
+            Keys::Many(keys) => keys.iter(),
+        }
+        .map(String::as_str)
+    }
+}
+
+/// What a key does after the keys typed before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyMatch {
+    /// The keys are bound to an action
+    Action(Action),
+    /// The keys start a sequence, so there's more to type
+    Prefix,
+    /// The keys aren't bound to anything
+    Unbound,
+}
+
+/// The keys for each action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyBindings {
+    by_action: HashMap<Action, Vec<Vec<Key>>>,
+    by_key: HashMap<Vec<Key>, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::from_table(&BTreeMap::new()).expect("default keys are valid")
+    }
+}
+
+impl KeyBindings {
+    /// The default keymap with the actions in `table` bound to its keys instead
+    ///
+    /// Fails on an unknown action or key, a key bound to two actions, and Esc or Ctrl+g,
+    /// which are kept for cancelling.
+    pub(crate) fn from_table(table: &BTreeMap<String, Keys>) -> Result<Self, String> {
+        let mut by_action = HashMap::new();
+        for action in Action::ALL {
+            let keys = action
+                .default_keys()
+                .iter()
+                .map(|key| parse_keys(key).expect("default keys are valid"))
+                .collect::<Vec<_>>();
+            by_action.insert(action, keys);
+        }
+
+        for (name, keys) in table {
+            let Some(action) = Action::from_name(name) else {
+                let names = Action::ALL.map(Action::name);
+                return Err(format!(
+                    "Unknown action '{name}' in [keys]. Actions: {}",
+                    names.join(", ")
+                ));
+            };
+            let keys = keys
+                .iter()
+                .map(|key| match parse_keys(key) {
+                    Some(parsed) if parsed.iter().any(|&key| is_reserved(key)) => {
+                        Err(format!("'{key}' can't be bound: it cancels and quits"))
+                    }
+                    Some(parsed) => Ok(parsed),
+                    None => Err(format!("Could not read key '{key}' for {name}")),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            by_action.insert(action, keys);
+        }
+
+        let mut by_key = HashMap::new();
+        for action in Action::ALL {
+            for keys in &by_action[&action] {
+                if let Some(other) = by_key.insert(keys.clone(), action)
+                    && other != action
+                {
+                    return Err(format!(
+                        "{} is bound to both {} and {}. Give one of them other keys in [keys].",
+                        keys_label(keys),
+                        other.name(),
+                        action.name()
+                    ));
+                }
+            }
+        }
+
+        Ok(Self { by_action, by_key })
+    }
+
+    /// The action a key press performs by itself, if any
+    pub(crate) fn action(&self, key: KeyEvent) -> Option<Action> {
+        self.bound(&[key_of(key)])
+    }
+
+    /// The action bound to exactly `keys`, if any
+    pub(crate) fn bound(&self, keys: &[Key]) -> Option<Action> {
+        self.by_key.get(keys).copied()
+    }
+
+    /// What a key press does after the keys in `pending`, which start a sequence. Keys that
+    /// start a sequence wait for the next key even when they're bound themselves.
+    pub(crate) fn lookup(&self, pending: &[Key], key: KeyEvent) -> KeyMatch {
+        let mut keys = pending.to_vec();
+        keys.push(key_of(key));
+        if self
+            .by_key
+            .keys()
+            .any(|bound| bound.len() > keys.len() && bound.starts_with(&keys))
+        {
+            KeyMatch::Prefix
+        } else if let Some(action) = self.bound(&keys) {
+            KeyMatch::Action(action)
+        } else {
+            KeyMatch::Unbound
+        }
+    }
+
+    /// The keys for an action as the help screen shows them, like `j, ↓, Ctrl+n`
+    pub(crate) fn describe(&self, action: Action) -> String {
+        self.by_action[&action]
+            .iter()
+            .map(|keys| keys_label(keys))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The key pressed in `event`
+pub(crate) fn key_of(event: KeyEvent) -> Key {
+    // Whether shift was held is already in the character
+    (event.code, event.modifiers - KeyModifiers::SHIFT)
+}
+
+fn is_reserved((code, modifiers): Key) -> bool {
+    code == KeyCode::Esc || (code == KeyCode::Char('g') && modifiers == KeyModifiers::CONTROL)
+}
+
+/// A key, or a sequence of keys separated by spaces
+fn parse_keys(keys: &str) -> Option<Vec<Key>> {
+    let keys = keys
+        .split_whitespace()
+        .map(parse_key)
+        .collect::<Option<Vec<_>>>()?;
+    (!keys.is_empty()).then_some(keys)
+}
+
+fn parse_key(key: &str) -> Option<Key> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = key;
+    loop {
+        if let Some(after) = rest.strip_prefix("C-") {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("M-") {
+            modifiers |= KeyModifiers::ALT;
+            rest = after;
+        } else {
+            break;
+        }
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match &*rest.to_ascii_lowercase() {
+            "enter" => KeyCode::Enter,
+            "space" => KeyCode::Char(' '),
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "esc" => KeyCode::Esc,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            _ => return None,
+        },
+    };
+    Some((code, modifiers))
+}
+
+/// Keys as they're written in the help screen, like `Ctrl+n` or `g t`
+pub(crate) fn keys_label(keys: &[Key]) -> String {
+    keys.iter()
+        .map(|&key| key_label(key))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn key_label((code, modifiers): Key) -> String {
+    let mut label = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        label.push_str("Ctrl+");
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        label.push_str("Alt+");
+    }
+    match code {
+        KeyCode::Char(' ') => label.push_str("Space"),
+        KeyCode::Char(c) => label.push(c),
+        KeyCode::Enter => label.push_str("Enter"),
+        KeyCode::Tab => label.push_str("Tab"),
+        KeyCode::BackTab => label.push_str("Shift+Tab"),
+        KeyCode::Backspace => label.push_str("Backspace"),
+        KeyCode::Esc => label.push_str("Esc"),
+        KeyCode::Left => label.push('←'),
+        KeyCode::Right => label.push('→'),
+        KeyCode::Up => label.push('↑'),
+        KeyCode::Down => label.push('↓'),
+        KeyCode::Home => label.push_str("Home"),
+        KeyCode::End => label.push_str("End"),
+        KeyCode::PageUp => label.push_str("PgUp"),
+        KeyCode::PageDown => label.push_str("PgDn"),
+        other => label.push_str(&format!("{other:?}")),
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn table(entries: &[(&str, &[&str])]) -> BTreeMap<String, Keys> {
+        entries
+            .iter()
+            .map(|(action, keys)| {
+                let keys = keys.iter().map(|key| key.to_string()).collect();
+                (action.to_string(), Keys::Many(keys))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn default_keys() {
+        let bindings = KeyBindings::default();
+        assert_eq!(
+            bindings.action(press(KeyCode::Char('j'), KeyModifiers::NONE)),
+            Some(Action::Down)
+        );
+        assert_eq!(
+            bindings.action(press(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            Some(Action::Bottom)
+        );
+        assert_eq!(
+            bindings.action(press(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(bindings.describe(Action::Down), "j, ↓, Ctrl+n");
+    }
+
+    #[test]
+    fn sequences_wait_for_their_last_key() {
+        let bindings = KeyBindings::default();
+        let g = press(KeyCode::Char('g'), KeyModifiers::NONE);
+        // `g` goes to a path by itself, but waits in case it's the start of `g t`
+        assert_eq!(bindings.lookup(&[], g), KeyMatch::Prefix);
+        assert_eq!(bindings.action(g), Some(Action::GoTo));
+        assert_eq!(
+            bindings.lookup(&[key_of(g)], press(KeyCode::Char('t'), KeyModifiers::NONE)),
+            KeyMatch::Action(Action::NextTab)
+        );
+        assert_eq!(
+            bindings.lookup(&[key_of(g)], press(KeyCode::Char('j'), KeyModifiers::NONE)),
+            KeyMatch::Unbound
+        );
+        assert_eq!(bindings.describe(Action::PreviousTab), "g T");
+
+        // `z` does nothing by itself, so it only starts `z z`, `z t`, `z b` and `z a`
+        let z = press(KeyCode::Char('z'), KeyModifiers::NONE);
+        assert_eq!(bindings.action(z), None);
+        assert_eq!(
+            bindings.lookup(&[key_of(z)], z),
+            KeyMatch::Action(Action::Recenter)
+        );
+    }
+
+    #[test]
+    fn remapped_keys_replace_the_defaults() {
+        let bindings =
+            KeyBindings::from_table(&table(&[("down", &["k", "down"]), ("up", &["j", "up"])]))
+                .unwrap();
+        assert_eq!(
+            bindings.action(press(KeyCode::Char('j'), KeyModifiers::NONE)),
+            Some(Action::Up)
+        );
+        assert_eq!(
+            bindings.action(press(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+            None
+        );
+        assert_eq!(bindings.describe(Action::Down), "k, ↓");
+    }
+
+    #[test]
+    fn invalid_keymaps_are_rejected() {
+        for invalid in [
+            table(&[("fly", &["f"])]),
+            table(&[("search", &["C-"])]),
+            table(&[("search", &["esc"])]),
+            table(&[("search", &["l"])]),
+            table(&[("search", &["x esc"])]),
+        ] {
+            assert!(KeyBindings::from_table(&invalid).is_err(), "{invalid:?}");
+        }
+    }
+}
@@ -23,6 +23,8 @@ pub struct LogEntry {
 struct LogState {
     /// Latest message for status bar
     latest_status: Option<String>,
+    /// Warnings and errors not yet shown as notifications
+    alerts: Vec<LogEntry>,
 
     max_level: LevelFilter,
     max_status_level: LevelFilter,
@@ -48,6 +50,7 @@ impl StatusLogBackend {
     pub fn new(max_history: usize) -> (Self, LogReader) {
         let state = Arc::new(Mutex::new(LogState {
             latest_status: None,
+            alerts: Vec::new(),
             history: VecDeque::new(),
             max_history,
             max_level: LevelFilter::Debug,
@@ -98,6 +101,9 @@ impl Log for StatusLogBackend {
         if record.level() <= state.max_status_level {
             state.latest_status = Some(entry.message.clone());
         }
+        if record.level() <= Level::Warn {
+            state.alerts.push(entry.clone());
+        }
 
         // Append to history (with capacity limit)
         state.history.push_back(entry);
@@ -128,6 +134,11 @@ impl LogReader {
         self.state.lock().unwrap().latest_status.clone()
     }
 
+    /// Take the warnings and errors logged since the last call, to show as notifications
+    pub fn take_alerts(&self) -> Vec<LogEntry> {
+        std::mem::take(&mut self.state.lock().unwrap().alerts)
+    }
+
     /// Get a snapshot of current history (non-consuming)
     /// Returns all accumulated log entries
     pub fn snapshot_history(&self) -> Vec<LogEntry> {
@@ -1,39 +1,56 @@
 #![allow(dead_code)]
 
 use clap::Parser;
-
-// Include the generated themes module
-mod themes {
-    include!(concat!(env!("OUT_DIR"), "/themes.rs"));
-}
 use ferritin_common::{
     Navigator,
-    sources::{DocsRsSource, LocalSource, StdSource},
+    sources::{DEFAULT_TOOLCHAIN, LocalSource, StdSource, VendoredSource},
 };
-use std::{path::PathBuf, process::ExitCode};
+use semver::Version;
+use std::{io::Write, num::NonZeroUsize, path::PathBuf, process::ExitCode};
 use terminal_size::{Width, terminal_size};
 
 use crate::{
-    commands::Commands, format_context::FormatContext, render_context::RenderContext,
-    renderer::OutputMode, request::Request,
+    background::Background, color_depth::ColorChoice, commands::Commands, config::Config,
+    daemon::DaemonRequest, format_context::FormatContext, pager::Paging, pagination::PagedWriter,
+    progress_bar::ProgressSpinner, render_context::RenderContext, renderer::Links,
+    renderer::OutputMode, request::Request, verbosity::Verbosity,
 };
 
+mod background;
+mod batch;
+mod color_depth;
 mod color_scheme;
 mod commands;
+mod completions;
+mod config;
+mod daemon;
 mod format;
 mod format_context;
 mod generate_docsrs_url;
 mod indent;
+mod key_bindings;
 mod logging;
 mod markdown;
+mod pager;
+mod pagination;
+mod private_dir;
+mod progress_bar;
 mod render_context;
 mod renderer;
 mod request;
+mod rpc;
 mod styled_string;
 #[cfg(test)]
 mod tests;
 mod traits;
 mod verbosity;
+mod workspace_history;
+
+/// The syntax highlighting theme when nothing names one, on a terminal with a dark background
+const DEFAULT_DARK_THEME: &str = "Catppuccin Frappe";
+
+/// The syntax highlighting theme when nothing names one, on a terminal with a light background
+const DEFAULT_LIGHT_THEME: &str = "Catppuccin Latte";
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
@@ -47,37 +64,132 @@ struct Cli {
     #[arg(short, long, global = true)]
     manifest_path: Option<PathBuf>,
 
-    /// Syntax highlighting theme (theme name or path to .tmTheme file)
+    /// Syntax highlighting theme (theme name or path to .tmTheme file, defaults to
+    /// Catppuccin Frappe, or Catppuccin Latte on a light terminal)
     #[arg(
         long,
         short,
         global = true,
-        default_value = "Catppuccin Frappe",
         env = "FERRITIN_THEME",
         long_help = build_theme_help()
     )]
-    theme: String,
+    theme: Option<String>,
 
     /// Enable interactive mode with scrolling and navigation
     #[arg(short, long, global = true)]
     interactive: bool,
 
+    /// Only use docs that are available locally or already downloaded from docs.rs, never
+    /// touching the network
+    #[arg(long, global = true, env = "FERRITIN_OFFLINE")]
+    offline: bool,
+
+    /// Only resolve paths as typed, instead of taking a segment that differs in case or by
+    /// one mistyped, missing or extra character for the one item it nearly names
+    #[arg(long, global = true, env = "FERRITIN_EXACT")]
+    exact: bool,
+
+    /// Hide items stabilized after this Rust version, such as your MSRV
+    #[arg(long, global = true, value_name = "VERSION", value_parser = parse_min_version)]
+    min_version: Option<Version>,
+
+    /// Rustup toolchain to load std docs from, like stable, beta, nightly or 1.74.0
+    /// (defaults to nightly). It needs the rust-docs-json component; run `ferritin toolchains`
+    /// to see which installed toolchains have it.
+    #[arg(
+        long,
+        global = true,
+        value_name = "TOOLCHAIN",
+        env = "FERRITIN_TOOLCHAIN"
+    )]
+    toolchain: Option<String>,
+
+    /// Calmer interactive mode: no emoji, loading animation, ornamental rules or hover effects.
+    /// Tables in terminal output are drawn without borders.
+    #[arg(long, global = true, env = "FERRITIN_NO_DECORATIONS")]
+    no_decorations: bool,
+
+    /// Output format for one-shot mode (defaults to tty on a terminal, plain otherwise)
+    #[arg(long, global = true, value_enum)]
+    format: Option<OutputMode>,
+
+    /// When to color output: auto colors a terminal unless NO_COLOR is set, always colors
+    /// pipes and files too (defaults to auto)
+    #[arg(long, global = true, value_enum, value_name = "WHEN")]
+    color: Option<ColorChoice>,
+
+    /// Columns to wrap one-shot output to (defaults to $COLUMNS, then the terminal's width,
+    /// then 80)
+    #[arg(long, global = true, value_name = "COLUMNS")]
+    width: Option<NonZeroUsize>,
+
+    /// Leave paragraphs unwrapped in one-shot output, for tools that wrap text themselves
+    #[arg(long, global = true)]
+    no_wrap: bool,
+
+    /// What links become in one-shot output: docs.rs URLs (hyperlinks in a terminal, markdown
+    /// links in plain text), paths that get takes, in brackets, or only their text (defaults to
+    /// docsrs in a terminal and off otherwise)
+    #[arg(long, global = true, value_enum, value_name = "TARGET")]
+    links: Option<Links>,
+
+    /// How much of an item's own docs to show: only signatures, its first line, its summary,
+    /// or all of it (defaults to full)
+    #[arg(long, global = true, value_enum)]
+    verbosity: Option<Verbosity>,
+
+    /// Page of oversized output to show (one-shot mode only)
+    #[arg(long, global = true, default_value = "1")]
+    page: NonZeroUsize,
+
+    /// Print oversized output in full instead of paging it (one-shot mode only)
+    #[arg(long, global = true)]
+    yes_huge: bool,
+
+    /// Print to the terminal directly, even when the output is longer than one screen,
+    /// instead of showing it in $FERRITIN_PAGER, $PAGER or less
+    #[arg(long, global = true, env = "FERRITIN_NO_PAGER")]
+    no_pager: bool,
+
+    /// Show output for the terminal in the pager even when it fits on one screen
+    #[arg(long, global = true, conflicts_with = "no_pager")]
+    pager: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
+/// The width from `--width`, then `COLUMNS`, then the terminal, or 80 columns
+fn output_width(width: Option<NonZeroUsize>) -> usize {
+    width
+        .map(NonZeroUsize::get)
+        .or_else(|| {
+            std::env::var("COLUMNS")
+                .ok()?
+                .parse::<NonZeroUsize>()
+                .ok()
+                .map(NonZeroUsize::get)
+        })
+        .or_else(|| terminal_size().map(|(Width(w), _)| w as usize))
+        .unwrap_or(80)
+}
+
 fn build_theme_help() -> &'static str {
     use std::sync::OnceLock;
     static HELP: OnceLock<String> = OnceLock::new();
 
     HELP.get_or_init(|| {
-        let mut help = String::from("Syntax highlighting theme\n\n");
+        let mut help = format!(
+            "Syntax highlighting theme (defaults to the one saved in the interactive theme \
+             picker, then to {DEFAULT_DARK_THEME}, or {DEFAULT_LIGHT_THEME} on a terminal with \
+             a light background)\n\n"
+        );
         help.push_str("Can be either:\n");
         help.push_str("  - A theme name from the list below\n");
         help.push_str("  - A path to a .tmTheme file\n\n");
-        help.push_str("Available themes:\n");
+        help.push_str("Available themes, including .tmTheme files in ~/.config/ferritin/themes:\n");
 
-        for name in themes::THEME_NAMES {
+        for name in RenderContext::available_themes() {
             help.push_str(&format!("  - {}\n", name));
         }
 
@@ -85,16 +197,48 @@ fn build_theme_help() -> &'static str {
     })
 }
 
-struct IoFmtWriter<T>(T);
+/// Adapts an io writer for the renderers, remembering whether a write failed because the
+/// reader went away
+struct IoFmtWriter<T> {
+    inner: T,
+    broken_pipe: bool,
+}
+
+impl<T> IoFmtWriter<T> {
+    fn new(inner: T) -> Self {
+        Self {
+            inner,
+            broken_pipe: false,
+        }
+    }
+}
+
 impl<T> std::fmt::Write for IoFmtWriter<T>
 where
     T: std::io::Write,
 {
     fn write_str(&mut self, s: &str) -> std::fmt::Result {
-        self.0.write_all(s.as_bytes()).map_err(|_| std::fmt::Error)
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.broken_pipe = error.kind() == std::io::ErrorKind::BrokenPipe;
+            std::fmt::Error
+        })
     }
 }
 
+/// Execute `command` and render its output to `output` as it is formatted, returning whether
+/// the command failed
+fn execute_and_render(
+    command: Commands,
+    request: &Request,
+    render_context: &RenderContext,
+    output: &mut impl std::fmt::Write,
+) -> Result<bool, std::fmt::Error> {
+    let mut renderer = renderer::StreamRenderer::new(render_context, output);
+    let is_error = command.execute_streamed(request, &mut |node| renderer.push(node))?;
+    renderer.finish()?;
+    Ok(is_error)
+}
+
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
@@ -102,19 +246,89 @@ fn main() -> ExitCode {
         .manifest_path
         .unwrap_or_else(|| std::env::current_dir().unwrap());
 
+    // Flags and environment variables override .ferritin.toml, which overrides the user's
+    // config file
+    let config = match Config::load(&path) {
+        Ok(config) => Config {
+            format: cli.format,
+            color: cli.color,
+            verbosity: cli.verbosity,
+            offline: cli.offline.then_some(true),
+            toolchain: cli.toolchain,
+            min_version: cli.min_version,
+            decorations: cli.no_decorations.then_some(false),
+            links: cli.links,
+            ..Config::default()
+        }
+        .or(config),
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let key_bindings = match config.key_bindings() {
+        Ok(key_bindings) => key_bindings,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let color = config.color.unwrap_or_default();
+    // Colors asked for on a pipe need the terminal renderer to show them
+    let output_mode = config
+        .format
+        .unwrap_or_else(|| match (color, OutputMode::detect()) {
+            (ColorChoice::Always, OutputMode::Plain) => OutputMode::Tty,
+            (_, output_mode) => output_mode,
+        });
+
+    // A theme from a flag wins, then one from the config files, then the one saved in the
+    // theme picker. Without any, the theme follows the terminal's background, which is only
+    // asked about when there is output to show in the theme.
+    let shown_in_theme = (cli.interactive || output_mode == OutputMode::Tty)
+        && !matches!(
+            cli.command,
+            Some(
+                Commands::Complete { .. }
+                    | Commands::Daemon
+                    | Commands::Rpc
+                    | Commands::Get { json: true, .. }
+            )
+        );
+    let theme = cli
+        .theme
+        .or_else(|| config.theme.clone())
+        .or_else(config::saved_theme)
+        .unwrap_or_else(|| default_theme(&config, shown_in_theme));
+
     let mut render_context = RenderContext::new()
-        .with_output_mode(OutputMode::detect())
-        .with_terminal_width(
-            terminal_size()
-                .map(|(Width(w), _)| w as usize)
-                .unwrap_or(80),
-        )
-        .with_interactive(cli.interactive);
+        .with_output_mode(output_mode)
+        .with_terminal_width(output_width(cli.width))
+        .with_wrap(!cli.no_wrap)
+        .with_interactive(cli.interactive)
+        .with_color_depth(color.color_depth())
+        .with_decorations(config.decorations.unwrap_or(true))
+        .with_images(config.images.unwrap_or(true))
+        .with_links(config.links)
+        .with_key_bindings(key_bindings)
+        .with_scrolloff(config.scrolloff.unwrap_or_default());
 
-    if let Err(e) = render_context.set_theme_name(&cli.theme) {
+    if let Err(e) = render_context.set_theme_name(&theme) {
         eprintln!("{e}");
         return ExitCode::FAILURE;
     };
+    let paging = if cli.no_pager
+        || config
+            .pager
+            .as_deref()
+            .is_some_and(|pager| pager.is_empty())
+    {
+        Paging::Never
+    } else if cli.pager {
+        Paging::Always
+    } else {
+        Paging::Auto
+    };
 
     if cli.interactive {
         // Interactive mode with scrolling and navigation
@@ -125,7 +339,10 @@ fn main() -> ExitCode {
             return ExitCode::FAILURE;
         }
 
-        if let Err(e) = renderer::render_interactive(path, render_context, cli.command, log_reader)
+        // Streaming example output would corrupt the TUI, so always capture it
+        let command = cli.command.map(Commands::with_captured_output);
+        if let Err(e) =
+            renderer::render_interactive(path, render_context, command, log_reader, config)
         {
             eprintln!("Interactive mode error: {}", e);
             return ExitCode::FAILURE;
@@ -133,6 +350,64 @@ fn main() -> ExitCode {
         return ExitCode::SUCCESS;
     }
 
+    // Use env_logger for CLI mode
+    env_logger::init();
+
+    if let Some(Commands::Completions { shell }) = cli.command {
+        print!("{}", completions::script(shell));
+        return ExitCode::SUCCESS;
+    }
+
+    let socket_path = daemon::socket_path(&path, &config);
+
+    // Output is shown one page at a time unless asked for everything. An image or a man page
+    // can't be split into pages, so they are always written whole.
+    let page = (!cli.yes_huge && render_context.output_mode().is_paged()).then_some(cli.page);
+
+    // Commands a running daemon can answer go to it, which skips loading any docs here. The
+    // daemon resolves paths tolerantly, so exact resolution is done here.
+    if let Some(command) = &cli.command
+        && command.is_served_by_daemon()
+        && !cli.exact
+        && let Some(reply) = daemon::forward(
+            &socket_path,
+            &DaemonRequest {
+                args: std::env::args_os()
+                    .skip(1)
+                    .map(|arg| arg.to_string_lossy().into_owned())
+                    .collect(),
+                output_mode: render_context.output_mode(),
+                terminal_width: render_context.terminal_width(),
+                color_depth: render_context.color_depth(),
+                theme: std::fs::canonicalize(&theme)
+                    .map_or_else(|_| theme.clone(), |path| path.display().to_string()),
+                links: config.links,
+                wrap: render_context.wraps_text(),
+                decorations: render_context.show_decorations(),
+                page,
+            },
+        )
+    {
+        let written = if pager::wanted(paging) {
+            pager::show(&reply.output, config.pager.as_deref(), paging)
+        } else {
+            std::io::stdout().write_all(reply.output.as_bytes())
+        };
+        if let Err(error) = written
+            && error.kind() != std::io::ErrorKind::BrokenPipe
+        {
+            return ExitCode::FAILURE;
+        }
+        if let Some(message) = reply.message {
+            eprintln!("{message}");
+        }
+        return if reply.success {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::from(reply.exit_code.unwrap_or(1))
+        };
+    }
+
     // Non-interactive mode: build sources eagerly and handle errors upfront
     let local_source = LocalSource::load(&path);
 
@@ -142,37 +417,179 @@ fn main() -> ExitCode {
         return ExitCode::FAILURE;
     }
 
-    let std_source = StdSource::from_rustup();
-    let docsrs_source = DocsRsSource::from_default_cache();
+    let toolchain = config.toolchain.as_deref().unwrap_or(DEFAULT_TOOLCHAIN);
+    let std_source = StdSource::from_toolchain(toolchain);
+    if std_source.is_none() && config.toolchain.is_some() {
+        eprintln!(
+            "No std docs were found for the {toolchain} toolchain. Run `ferritin toolchains` to \
+             see which toolchains have them."
+        );
+    }
+    // Completion runs on every tab press, so it never waits on a download
+    let offline =
+        config.offline.unwrap_or(false) || matches!(cli.command, Some(Commands::Complete { .. }));
+    let docsrs_source =
+        config::docs_rs_source(config.cache_dir.as_deref(), config.docs_rs_url.as_deref())
+            .map(|docsrs_source| docsrs_source.with_offline(offline));
 
     let navigator = Navigator::default()
         .with_std_source(std_source)
         .with_local_source(local_source.ok())
-        .with_docsrs_source(docsrs_source);
+        .with_docsrs_source(docsrs_source)
+        .with_vendored_source(
+            VendoredSource::from_default_cargo_home()
+                .map(|vendored_source| vendored_source.with_offline(offline)),
+        )
+        .with_exact_paths(cli.exact)
+        .with_progress(ProgressSpinner::default());
 
-    let format_context = FormatContext::new();
+    let format_context = FormatContext::new()
+        .with_min_version(config.min_version.clone())
+        .with_verbosity(config.verbosity.unwrap_or_default())
+        .with_terminal_width(render_context.wrap_width());
     let request = Request::new(navigator, format_context);
 
-    // One-shot mode: execute command and render to stdout
-    // Use env_logger for CLI mode
-    env_logger::init();
-    let (document, is_error, _initial_entry) =
-        cli.command.unwrap_or_else(Commands::list).execute(&request);
-
-    // Render to stdout and exit
-    if renderer::render(
-        &document,
-        &render_context,
-        &mut IoFmtWriter(std::io::stdout()),
-    )
-    .is_err()
+    if let Some(Commands::Complete { partial }) = &cli.command {
+        for path in request.complete_path(partial) {
+            println!("{path}");
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if let Some(Commands::Daemon) = cli.command {
+        return match daemon::serve(&request, &socket_path) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("Could not start the daemon: {error}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(Commands::Rpc) = cli.command {
+        return match rpc::serve(&request, std::io::stdin().lock(), std::io::stdout().lock()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("{error}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(
+        command @ Commands::Get {
+            batch: true,
+            separator,
+            json,
+            ..
+        },
+    ) = &cli.command
     {
-        return ExitCode::FAILURE;
+        let served = batch::serve(
+            &request,
+            command,
+            &render_context,
+            separator,
+            *json,
+            std::io::stdin().lock(),
+            std::io::stdout().lock(),
+        );
+        return match served {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::FAILURE,
+            // A reader like `head` closing the pipe once it has enough output isn't a failure
+            Err(error) if error.kind() == std::io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("{error}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(
+        command @ Commands::Get {
+            path: Some(path),
+            json: true,
+            ..
+        },
+    ) = &cli.command
+    {
+        let (value, failure) = batch::json_document(&request, command.clone(), path);
+        println!("{value}");
+        return failure.map_or(ExitCode::SUCCESS, |failure| {
+            ExitCode::from(failure.exit_code())
+        });
+    }
+
+    // One-shot mode: execute command and render to stdout as its output is formatted
+    let command = cli.command.unwrap_or_else(Commands::list);
+
+    // Render to stdout, one page at a time unless asked for everything
+    let is_error;
+    let summary = if pager::wanted(paging) {
+        // Output for a terminal is collected first, to find out whether it fits on one screen
+        let mut output = PagedWriter::with_page(String::new(), page);
+        let Ok(failed) =
+            execute_and_render(command.clone(), &request, &render_context, &mut output)
+        else {
+            return ExitCode::FAILURE;
+        };
+        is_error = failed;
+        let summary = output.omission_summary();
+        if let Err(error) = pager::show(&output.into_inner(), config.pager.as_deref(), paging)
+            && error.kind() != std::io::ErrorKind::BrokenPipe
+        {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+        summary
+    } else {
+        // Stdout is line buffered and the renderers write as they go, so long output starts
+        // printing right away
+        let mut output = PagedWriter::with_page(IoFmtWriter::new(std::io::stdout()), page);
+        match execute_and_render(command.clone(), &request, &render_context, &mut output) {
+            Ok(failed) => is_error = failed,
+            // A reader like `head` closing the pipe once it has enough output isn't a failure
+            Err(_) if output.get_ref().broken_pipe => return ExitCode::SUCCESS,
+            Err(_) => return ExitCode::FAILURE,
+        }
+        output.omission_summary()
+    };
+
+    match summary {
+        Ok(Some(summary)) => eprintln!("{summary}"),
+        Ok(None) => {}
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
     }
 
     if is_error {
-        ExitCode::FAILURE
+        ExitCode::from(command.failure_exit_code(&request))
     } else {
         ExitCode::SUCCESS
     }
 }
+
+/// The configured or default theme for the terminal's background, which is only asked for
+/// when the output is going to a terminal
+fn default_theme(config: &Config, detect_background: bool) -> String {
+    let light_theme = config.light_theme.as_deref().unwrap_or(DEFAULT_LIGHT_THEME);
+    let dark_theme = config.dark_theme.as_deref().unwrap_or(DEFAULT_DARK_THEME);
+    // The terminal takes a moment to answer, so it is only asked when the answer picks a theme
+    let background = (detect_background && light_theme != dark_theme)
+        .then(background::detect)
+        .flatten();
+    match background {
+        Some(Background::Light) => light_theme,
+        Some(Background::Dark) | None => dark_theme,
+    }
+    .to_string()
+}
+
+/// Parse a Rust version like `1.70`, for `--min-version`
+fn parse_min_version(input: &str) -> Result<Version, String> {
+    ferritin_common::parse_rust_version(input)
+        .ok_or_else(|| format!("{input:?} is not a Rust version like 1.70"))
+}
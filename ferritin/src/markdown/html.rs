@@ -0,0 +1,200 @@
+//! HTML in doc comments, like `<br>`, `<details>` and `<img>`, as markdown events
+//!
+//! This isn't an HTML parser: tags are read one at a time, and the ones that have a markdown
+//! equivalent become that markdown's events. Other tags are dropped and their text is kept.
+
+use pulldown_cmark::{CowStr, Event, LinkType, Tag, TagEnd};
+
+/// A piece of HTML, as the markdown event it stands for, or the start or end of a collapsible
+/// `<details>` block, which markdown has no event for
+pub(super) enum HtmlPart<'a> {
+    Event(Event<'a>),
+    DetailsStart,
+    DetailsEnd,
+}
+
+/// The markdown events for `html`. Text in an HTML block has its whitespace collapsed, since
+/// its line breaks and indentation are the HTML's layout rather than the docs'.
+pub(super) fn translate(html: &str, block: bool) -> Vec<HtmlPart<'static>> {
+    let mut translation = Translation {
+        parts: vec![],
+        code: None,
+        row_cells: 0,
+        block,
+    };
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.split_once("-->").map_or("", |(_, after)| after);
+            continue;
+        }
+
+        let Some(tag_start) = rest.find('<') else {
+            translation.text(rest);
+            break;
+        };
+        translation.text(&rest[..tag_start]);
+        let Some(tag_len) = rest[tag_start..].find('>') else {
+            translation.text(&rest[tag_start..]);
+            break;
+        };
+        translation.tag(&rest[tag_start + 1..tag_start + tag_len]);
+        rest = &rest[tag_start + tag_len + 1..];
+    }
+
+    if block {
+        translation.event(Event::End(TagEnd::Paragraph));
+    }
+    translation.parts
+}
+
+struct Translation {
+    parts: Vec<HtmlPart<'static>>,
+    /// The text of a `<code>` element so far, which becomes one inline code span
+    code: Option<String>,
+    /// Cells in the current table row so far, to put separators between them
+    row_cells: usize,
+    block: bool,
+}
+
+impl Translation {
+    fn event(&mut self, event: Event<'static>) {
+        self.parts.push(HtmlPart::Event(event));
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = decode_entities(text);
+        if let Some(code) = &mut self.code {
+            code.push_str(&text);
+        } else if !self.block {
+            self.event(Event::Text(text.into()));
+        } else if !text.trim().is_empty() {
+            let mut collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if text.starts_with(char::is_whitespace) {
+                collapsed.insert(0, ' ');
+            }
+            if text.ends_with(char::is_whitespace) {
+                collapsed.push(' ');
+            }
+            self.event(Event::Text(collapsed.into()));
+        }
+    }
+
+    /// Translate a tag, given what's between its angle brackets
+    fn tag(&mut self, tag: &str) {
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let tag = tag.trim_end_matches('/');
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+
+        match (name.to_ascii_lowercase().as_str(), closing) {
+            ("br", _) => self.event(Event::HardBreak),
+            ("b" | "strong", false) => self.event(Event::Start(Tag::Strong)),
+            ("b" | "strong", true) => self.event(Event::End(TagEnd::Strong)),
+            ("i" | "em", false) => self.event(Event::Start(Tag::Emphasis)),
+            ("i" | "em", true) => self.event(Event::End(TagEnd::Emphasis)),
+            ("s" | "del" | "strike", false) => self.event(Event::Start(Tag::Strikethrough)),
+            ("s" | "del" | "strike", true) => self.event(Event::End(TagEnd::Strikethrough)),
+            ("code" | "kbd" | "tt", false) => self.code = Some(String::new()),
+            ("code" | "kbd" | "tt", true) => {
+                if let Some(code) = self.code.take() {
+                    self.event(Event::Code(code.into()));
+                }
+            }
+            ("a", false) => {
+                if let Some(href) = attribute(attributes, "href") {
+                    self.event(Event::Start(link(href)));
+                }
+            }
+            ("a", true) => self.event(Event::End(TagEnd::Link)),
+            ("img", _) => {
+                let Some(src) = attribute(attributes, "src") else {
+                    return;
+                };
+                self.event(Event::Start(Tag::Image {
+                    link_type: LinkType::Inline,
+                    dest_url: src.into(),
+                    title: CowStr::Borrowed(""),
+                    id: CowStr::Borrowed(""),
+                }));
+                if let Some(alt) = attribute(attributes, "alt") {
+                    self.event(Event::Text(alt.into()));
+                }
+                self.event(Event::End(TagEnd::Image));
+            }
+            ("details", false) => {
+                self.event(Event::End(TagEnd::Paragraph));
+                self.parts.push(HtmlPart::DetailsStart);
+            }
+            ("details", true) => {
+                self.event(Event::End(TagEnd::Paragraph));
+                self.parts.push(HtmlPart::DetailsEnd);
+            }
+            // A summary is the line a collapsed `<details>` shows
+            ("summary", false) => self.event(Event::Start(Tag::Strong)),
+            ("summary", true) => {
+                self.event(Event::End(TagEnd::Strong));
+                self.event(Event::End(TagEnd::Paragraph));
+            }
+            ("td" | "th", false) => {
+                if self.row_cells > 0 {
+                    self.event(Event::Text(" | ".into()));
+                }
+                self.row_cells += 1;
+            }
+            ("tr", _) => {
+                self.row_cells = 0;
+                self.event(Event::End(TagEnd::Paragraph));
+            }
+            (
+                "p" | "div" | "center" | "table" | "ul" | "ol" | "li" | "h1" | "h2" | "h3" | "h4"
+                | "h5" | "h6",
+                _,
+            ) => self.event(Event::End(TagEnd::Paragraph)),
+            _ => {}
+        }
+    }
+}
+
+fn link(url: String) -> Tag<'static> {
+    Tag::Link {
+        link_type: LinkType::Inline,
+        dest_url: url.into(),
+        title: CowStr::Borrowed(""),
+        id: CowStr::Borrowed(""),
+    }
+}
+
+/// The value of the attribute `name` in attributes like `src="logo.png" alt='Logo'`
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let mut rest = attributes;
+    loop {
+        let (key, after_key) = rest.trim_start().split_once('=')?;
+        let after_key = after_key.trim_start();
+        let (value, after_value) = match after_key.chars().next()? {
+            quote @ ('"' | '\'') => after_key[1..].split_once(quote)?,
+            _ => after_key
+                .split_once(char::is_whitespace)
+                .unwrap_or((after_key, "")),
+        };
+        // Attributes without a value, like `open`, end up in front of the next key
+        if key.split_whitespace().last()?.eq_ignore_ascii_case(name) {
+            return Some(decode_entities(value));
+        }
+        rest = after_value;
+    }
+}
+
+/// `text` with the character references that docs commonly use replaced by their characters
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,230 @@
+//! Mermaid diagrams in doc comments, as text a terminal can show
+//!
+//! Flowcharts become one line per edge and sequence diagrams one line per message, with node
+//! ids replaced by their labels. Other kinds of diagram are left as their source.
+
+use std::collections::HashMap;
+
+/// `source` as readable text, or `None` if it isn't a diagram this understands
+pub(super) fn outline(source: &str) -> Option<String> {
+    let mut lines = source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"));
+    // The header can be followed by statements, as in `graph TD; A-->B`
+    let first = lines.next()?;
+    let (header, statements) = first.split_once(';').unwrap_or((first, ""));
+    let lines = std::iter::once(statements).chain(lines);
+    let outline = match header.split_whitespace().next()? {
+        "graph" | "flowchart" => flowchart(lines),
+        "sequenceDiagram" => sequence(lines),
+        _ => return None,
+    };
+    (!outline.is_empty()).then(|| outline.join("\n"))
+}
+
+fn flowchart<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut labels = HashMap::new();
+    let mut edges = vec![];
+    let mut order = vec![];
+
+    for statement in lines.flat_map(|line| line.split(';')).map(str::trim) {
+        let keyword = statement.split_whitespace().next().unwrap_or_default();
+        if matches!(
+            keyword,
+            "" | "subgraph"
+                | "end"
+                | "direction"
+                | "style"
+                | "classDef"
+                | "class"
+                | "linkStyle"
+                | "click"
+        ) {
+            continue;
+        }
+
+        let mut rest = statement;
+        let mut previous: Option<(String, Option<String>)> = None;
+        while let Some((id, label, after_node)) = node(rest) {
+            if let Some(label) = label {
+                labels.insert(id.clone(), label);
+            }
+            if !order.contains(&id) {
+                order.push(id.clone());
+            }
+            if let Some((from, text)) = previous.take() {
+                edges.push((from, id.clone(), text));
+            }
+            let Some((text, after_edge)) = edge(after_node) else {
+                break;
+            };
+            previous = Some((id, text));
+            rest = after_edge;
+        }
+    }
+
+    let label = |id: &String| labels.get(id).unwrap_or(id).clone();
+    let mut outline = edges
+        .iter()
+        .map(|(from, to, text)| match text {
+            Some(text) => format!("{} → {} ({text})", label(from), label(to)),
+            None => format!("{} → {}", label(from), label(to)),
+        })
+        .collect::<Vec<_>>();
+    // Nodes that no edge reaches are listed on their own
+    for id in &order {
+        if !edges.iter().any(|(from, to, _)| from == id || to == id) {
+            outline.push(label(id));
+        }
+    }
+    outline
+}
+
+/// A node at the start of `text`, like `A` or `A[Label]`: its id, its label if it has one,
+/// and the text after it
+fn node(text: &str) -> Option<(String, Option<String>, &str)> {
+    let text = text.trim_start();
+    let id_len = text
+        .find(|c: char| c.is_whitespace() || "[({>-=.&|;".contains(c))
+        .unwrap_or(text.len());
+    if id_len == 0 {
+        return None;
+    }
+    let (id, rest) = text.split_at(id_len);
+
+    // Shapes are drawn with brackets like `[...]`, `((...))`, `{...}` and `>...]`
+    let opening_len = rest.len()
+        - rest
+            .trim_start_matches(['[', '(', '{', '>', '/', '\\'])
+            .len();
+    if opening_len == 0 {
+        return Some((id.to_string(), None, rest));
+    }
+    let inside = &rest[opening_len..];
+    let label_len = inside.find([']', ')', '}', '/', '\\'])?;
+    let after = inside[label_len..].trim_start_matches([']', ')', '}', '/', '\\']);
+    let label = inside[..label_len].trim().trim_matches('"').trim();
+    Some((id.to_string(), Some(label.to_string()), after))
+}
+
+/// An edge at the start of `text`, like `-->`, `-->|text|` or `-- text -->`: its text if it
+/// has some, and the text after it
+fn edge(text: &str) -> Option<(Option<String>, &str)> {
+    let text = text.trim_start();
+    let len = arrow_len(text);
+    if len == 0 {
+        return None;
+    }
+    let (arrow, rest) = text.split_at(len);
+    let rest = rest.trim_start();
+
+    if let Some(labelled) = rest.strip_prefix('|') {
+        let (label, after) = labelled.split_once('|')?;
+        return Some((Some(label.trim().to_string()), after));
+    }
+    // `-- text -->`: the start of an arrow, then the text, then the rest of the arrow
+    if matches!(arrow, "--" | "==" | "-.") {
+        let label_len = rest.find(['-', '=', '.'])?;
+        let after = &rest[label_len..];
+        return Some((
+            Some(rest[..label_len].trim().to_string()),
+            &after[arrow_len(after)..],
+        ));
+    }
+    Some((None, rest))
+}
+
+/// Bytes of the arrow at the start of `text`, like `-->`, `<==>`, `-.->`, `--o` or `--x`,
+/// or 0 if it doesn't start with one
+fn arrow_len(text: &str) -> usize {
+    let body = text.trim_start_matches('<');
+    let start = text.len() - body.len();
+    let shaft_len = body.len() - body.trim_start_matches(['-', '=', '.']).len();
+    if shaft_len < 2 {
+        return 0;
+    }
+    let after = &body[shaft_len..];
+    let head_len = if after.starts_with('>') {
+        1
+    } else if after.starts_with(['o', 'x'])
+        && after[1..]
+            .chars()
+            .next()
+            .is_none_or(|c| !c.is_alphanumeric())
+    {
+        // Circle and cross heads, which aren't the start of a node's id
+        1
+    } else {
+        0
+    };
+    start + shaft_len + head_len
+}
+
+fn sequence<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut names = HashMap::new();
+    let mut outline = vec![];
+    let mut depth = 0;
+
+    for line in lines.filter(|line| !line.is_empty()) {
+        let keyword = line.split_whitespace().next().unwrap_or_default();
+        let indent = "  ".repeat(depth);
+        match keyword {
+            "participant" | "actor" => {
+                let declaration = line[keyword.len()..].trim();
+                if let Some((id, name)) = declaration.split_once(" as ") {
+                    names.insert(id.trim().to_string(), name.trim().to_string());
+                }
+            }
+            "autonumber" | "activate" | "deactivate" => {}
+            "loop" | "alt" | "opt" | "par" | "critical" | "break" | "rect" | "box" => {
+                outline.push(format!("{indent}{line}"));
+                depth += 1;
+            }
+            "else" | "and" | "option" => {
+                let indent = "  ".repeat(depth.saturating_sub(1));
+                outline.push(format!("{indent}{line}"));
+            }
+            "end" => {
+                depth = depth.saturating_sub(1);
+                outline.push(format!("{}end", "  ".repeat(depth)));
+            }
+            _ => match message(line) {
+                Some((from, dashed, to, text)) => {
+                    let name = |id: &str| names.get(id).cloned().unwrap_or_else(|| id.to_string());
+                    let arrow = if dashed { "⇢" } else { "→" };
+                    outline.push(format!(
+                        "{indent}{} {arrow} {}: {text}",
+                        name(from),
+                        name(to)
+                    ));
+                }
+                None => outline.push(format!("{indent}{line}")),
+            },
+        }
+    }
+    outline
+}
+
+/// The sender, whether the arrow is dashed, the receiver and the text of a message like
+/// `Alice->>Bob: Hello`
+fn message(line: &str) -> Option<(&str, bool, &str, &str)> {
+    let (participants, text) = line.split_once(':')?;
+    let arrow_start = participants.find(['-', '<'])?;
+    let arrow = &participants[arrow_start..];
+    let body = arrow.trim_start_matches('<');
+    let after_shaft = body.trim_start_matches('-');
+    let dashes = body.len() - after_shaft.len();
+    let head = [">>", ">", "x", ")"]
+        .into_iter()
+        .find(|head| after_shaft.starts_with(head))?;
+    if dashes == 0 {
+        return None;
+    }
+
+    let from = participants[..arrow_start].trim();
+    let to = after_shaft[head.len()..]
+        .trim()
+        .trim_start_matches(['+', '-']);
+    (!from.is_empty() && !to.is_empty()).then_some((from, dashes > 1, to, text.trim()))
+}
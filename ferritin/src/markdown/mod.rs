@@ -1,13 +1,25 @@
+use crate::renderer::{TAB_WIDTH, grapheme_width};
 use crate::styled_string::{
-    DocumentNode, HeadingLevel, LinkTarget, ListItem, Span, SpanStyle, TuiAction,
+    DocumentNode, HeadingLevel, LinkTarget, ListItem, Span, SpanStyle, TruncationLevel, TuiAction,
 };
+use html::HtmlPart;
 use pulldown_cmark::{BrokenLink, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use std::collections::VecDeque;
+use unicode_segmentation::UnicodeSegmentation;
+
+mod html;
+mod mermaid;
 
 /// Stack item for building the document tree
 /// We need this because Lists contain ListItems (not DocumentNodes directly)
 enum StackItem<'a> {
     Node(DocumentNode<'a>),
     Item(ListItem<'a>),
+    /// A footnote's definition, which is moved to the end of the docs
+    Footnote {
+        label: String,
+        nodes: Vec<DocumentNode<'a>>,
+    },
 }
 
 pub struct MarkdownRenderer;
@@ -31,7 +43,10 @@ impl MarkdownRenderer {
 
         let mut options = Options::empty();
         options.insert(Options::ENABLE_TABLES);
-        let parser = Parser::new_with_broken_link_callback(markdown, options, Some(&callback));
+        options.insert(Options::ENABLE_FOOTNOTES);
+        options.insert(Options::ENABLE_TASKLISTS);
+        options.insert(Options::ENABLE_DEFINITION_LIST);
+        let mut parser = Parser::new_with_broken_link_callback(markdown, options, Some(&callback));
 
         let mut root: Vec<DocumentNode<'a>> = Vec::new();
         let mut stack: Vec<StackItem<'a>> = Vec::new();
@@ -47,14 +62,49 @@ impl MarkdownRenderer {
         let mut in_heading = false;
         let mut heading_level: Option<HeadingLevel> = None;
         let mut current_link_action: Option<TuiAction<'a>> = None;
+        let mut in_definition_title = false;
+        let mut footnotes: Vec<(String, Vec<DocumentNode<'a>>)> = Vec::new();
+
+        // HTML blocks are collected line by line, then translated into events that are handled
+        // before the parser's next one
+        let mut html_block = String::new();
+        let mut html_parts: VecDeque<HtmlPart<'_>> = VecDeque::new();
+
+        // An image's URL and its alt text so far
+        let mut image: Option<(String, String)> = None;
 
         // Table state
+        let mut in_table = false;
         let mut in_table_head = false;
         let mut table_header: Option<Vec<crate::styled_string::TableCell<'a>>> = None;
         let mut table_rows: Vec<Vec<crate::styled_string::TableCell<'a>>> = Vec::new();
         let mut current_row: Vec<crate::styled_string::TableCell<'a>> = Vec::new();
 
-        for event in parser {
+        while let Some(part) = html_parts
+            .pop_front()
+            .or_else(|| parser.next().map(HtmlPart::Event))
+        {
+            let event = match part {
+                HtmlPart::Event(event) => event,
+                HtmlPart::DetailsStart => {
+                    stack.push(StackItem::Node(DocumentNode::TruncatedBlock {
+                        nodes: vec![],
+                        level: TruncationLevel::SingleLine,
+                    }));
+                    continue;
+                }
+                HtmlPart::DetailsEnd => {
+                    if matches!(
+                        stack.last(),
+                        Some(StackItem::Node(DocumentNode::TruncatedBlock { .. }))
+                    ) && let Some(details) = stack.pop()
+                    {
+                        Self::push_to_parent(&mut stack, &mut root, details);
+                    }
+                    continue;
+                }
+            };
+
             match event {
                 Event::Start(tag) => match tag {
                     Tag::CodeBlock(kind) => {
@@ -65,6 +115,9 @@ impl MarkdownRenderer {
                                     "no_run" | "should_panic" | "ignore" | "compile_fail"
                                     | "edition2015" | "edition2018" | "edition2021"
                                     | "edition2024" | "rust" | "" => "rust".to_string(),
+                                    // Text laid out by hand, like tables and diagrams
+                                    "text" | "txt" | "plain" | "plaintext" | "ascii"
+                                    | "ascii-art" | "ascii_art" | "diagram" => "text".to_string(),
                                     other => other.to_string(),
                                 }
                             }
@@ -131,6 +184,7 @@ impl MarkdownRenderer {
                         stack.push(StackItem::Node(DocumentNode::BlockQuote { nodes: vec![] }));
                     }
                     Tag::Table(_) => {
+                        in_table = true;
                         table_header = None;
                         table_rows.clear();
                     }
@@ -147,13 +201,40 @@ impl MarkdownRenderer {
                     Tag::Paragraph => {
                         // Paragraphs will be created when we hit TagEnd::Paragraph
                     }
+                    Tag::FootnoteDefinition(label) => {
+                        Self::flush_paragraph(&mut current_spans, &mut stack, &mut root);
+                        stack.push(StackItem::Footnote {
+                            label: label.to_string(),
+                            nodes: vec![],
+                        });
+                    }
+                    Tag::DefinitionList => {
+                        // A definition list becomes a list with an item for each term, holding
+                        // the term in bold and its definitions under it
+                        Self::flush_paragraph(&mut current_spans, &mut stack, &mut root);
+                        stack.push(StackItem::Node(DocumentNode::List { items: vec![] }));
+                    }
+                    Tag::HtmlBlock => html_block.clear(),
+                    Tag::Image { dest_url, .. } => {
+                        image = Some((dest_url.to_string(), String::new()))
+                    }
+                    Tag::DefinitionListTitle => {
+                        Self::close_definition(&mut stack, &mut root);
+                        stack.push(StackItem::Item(ListItem::new(vec![])));
+                        in_definition_title = true;
+                    }
                     _ => {}
                 },
                 Event::End(tag_end) => match tag_end {
                     TagEnd::Paragraph => {
                         // Create a paragraph node from collected spans
                         let paragraph_spans = std::mem::take(&mut current_spans);
-                        if !paragraph_spans.is_empty() {
+                        // What's left between images, like the spaces in a row of badges, isn't
+                        // worth a paragraph
+                        if paragraph_spans
+                            .iter()
+                            .any(|span| !span.text.trim().is_empty())
+                        {
                             let para = DocumentNode::Paragraph {
                                 spans: paragraph_spans,
                             };
@@ -180,11 +261,14 @@ impl MarkdownRenderer {
                     TagEnd::CodeBlock => {
                         if in_code_block {
                             // Strip hidden lines for Rust code
-                            let code = if matches!(code_block_lang.as_deref(), Some("rust") | None)
-                            {
-                                Self::strip_hidden_lines(&code_block_content)
-                            } else {
-                                code_block_content.clone()
+                            let code = match code_block_lang.as_deref() {
+                                Some("rust") | None => {
+                                    Self::strip_hidden_lines(&code_block_content)
+                                }
+                                Some("text") => Self::expand_tabs(&code_block_content),
+                                Some("mermaid") => mermaid::outline(&code_block_content)
+                                    .unwrap_or_else(|| code_block_content.clone()),
+                                _ => code_block_content.clone(),
                             };
 
                             let code_block = DocumentNode::code_block(code_block_lang.take(), code);
@@ -210,6 +294,7 @@ impl MarkdownRenderer {
                         current_link_action = None;
                     }
                     TagEnd::BlockQuote(_) => {
+                        Self::close_details(&mut current_spans, &mut stack, &mut root);
                         // Flush any remaining spans as a paragraph before closing the blockquote
                         if !current_spans.is_empty() {
                             let para = DocumentNode::Paragraph {
@@ -234,6 +319,7 @@ impl MarkdownRenderer {
                         }
                     }
                     TagEnd::Item => {
+                        Self::close_details(&mut current_spans, &mut stack, &mut root);
                         // Flush any remaining spans as a paragraph before closing the item
                         if !current_spans.is_empty() {
                             let para = DocumentNode::Paragraph {
@@ -264,19 +350,64 @@ impl MarkdownRenderer {
                         }
                     }
                     TagEnd::Table => {
+                        in_table = false;
                         let table = DocumentNode::Table {
                             header: table_header.take(),
                             rows: std::mem::take(&mut table_rows),
                         };
                         Self::push_to_parent(&mut stack, &mut root, StackItem::Node(table));
                     }
+                    TagEnd::FootnoteDefinition => {
+                        Self::close_details(&mut current_spans, &mut stack, &mut root);
+                        Self::flush_paragraph(&mut current_spans, &mut stack, &mut root);
+                        if let Some(StackItem::Footnote { label, nodes }) = stack.pop() {
+                            footnotes.push((label, nodes));
+                        }
+                    }
+                    TagEnd::DefinitionListTitle => {
+                        in_definition_title = false;
+                        Self::flush_paragraph(&mut current_spans, &mut stack, &mut root);
+                    }
+                    TagEnd::DefinitionListDefinition => {
+                        Self::flush_paragraph(&mut current_spans, &mut stack, &mut root);
+                    }
+                    TagEnd::Image => {
+                        let Some((url, alt)) = image.take() else {
+                            continue;
+                        };
+                        if in_heading || in_table {
+                            // Images are blocks of their own, which a line can't hold
+                            current_spans.push(Span::image_link(&url.into(), &alt));
+                        } else {
+                            if current_spans.iter().all(|span| span.text.trim().is_empty()) {
+                                current_spans.clear();
+                            }
+                            Self::flush_paragraph(&mut current_spans, &mut stack, &mut root);
+                            Self::push_to_parent(
+                                &mut stack,
+                                &mut root,
+                                StackItem::Node(DocumentNode::image(url, alt)),
+                            );
+                        }
+                    }
+                    TagEnd::HtmlBlock => {
+                        html_parts.extend(html::translate(&html_block, true));
+                    }
+                    TagEnd::DefinitionList => {
+                        Self::close_definition(&mut stack, &mut root);
+                        if let Some(StackItem::Node(list)) = stack.pop() {
+                            Self::push_to_parent(&mut stack, &mut root, StackItem::Node(list));
+                        }
+                    }
                     _ => {}
                 },
                 Event::Text(text) => {
-                    if in_code_block {
+                    if let Some((_, alt)) = &mut image {
+                        alt.push_str(&text);
+                    } else if in_code_block {
                         code_block_content.push_str(&text);
                     } else {
-                        let style = if in_strong {
+                        let style = if in_strong || in_definition_title {
                             SpanStyle::Strong
                         } else if in_emphasis {
                             SpanStyle::Emphasis
@@ -295,9 +426,13 @@ impl MarkdownRenderer {
                     }
                 }
                 Event::Code(code) => {
-                    let mut span = Span::inline_code(code.to_string());
-                    span.action = current_link_action.clone();
-                    current_spans.push(span);
+                    if let Some((_, alt)) = &mut image {
+                        alt.push_str(&code);
+                    } else {
+                        let mut span = Span::inline_code(code.to_string());
+                        span.action = current_link_action.clone();
+                        current_spans.push(span);
+                    }
                 }
                 Event::SoftBreak => {
                     let mut span = Span::plain(" ");
@@ -309,6 +444,14 @@ impl MarkdownRenderer {
                     span.action = current_link_action.clone();
                     current_spans.push(span);
                 }
+                Event::Html(html) => html_block.push_str(&html),
+                Event::InlineHtml(html) => html_parts.extend(html::translate(&html, false)),
+                Event::FootnoteReference(label) => {
+                    current_spans.push(Span::plain(format!("[{label}]")));
+                }
+                Event::TaskListMarker(checked) => {
+                    current_spans.push(Span::plain(if checked { "☑ " } else { "☐ " }));
+                }
                 Event::Rule => {
                     Self::push_to_parent(
                         &mut stack,
@@ -320,14 +463,75 @@ impl MarkdownRenderer {
             }
         }
 
+        // A `<details>` block that's never closed ends with the docs
+        Self::close_details(&mut current_spans, &mut stack, &mut root);
+
         // Flush any remaining spans as a paragraph
         if !current_spans.is_empty() {
             root.push(DocumentNode::paragraph(std::mem::take(&mut current_spans)));
         }
 
+        // Footnotes go after the docs, each starting with its label, the way rustdoc shows them
+        if !footnotes.is_empty() {
+            root.push(DocumentNode::HorizontalRule);
+            for (label, mut nodes) in footnotes {
+                let label = Span::plain(format!("[{label}] "));
+                match nodes.first_mut() {
+                    Some(DocumentNode::Paragraph { spans }) => spans.insert(0, label),
+                    _ => nodes.insert(0, DocumentNode::paragraph(vec![label])),
+                }
+                root.extend(nodes);
+            }
+        }
+
         root
     }
 
+    /// Push the spans collected so far to the parent container as a paragraph, if there are any
+    fn flush_paragraph<'a>(
+        current_spans: &mut Vec<Span<'a>>,
+        stack: &mut Vec<StackItem<'a>>,
+        root: &mut Vec<DocumentNode<'a>>,
+    ) {
+        if !current_spans.is_empty() {
+            let para = DocumentNode::paragraph(std::mem::take(current_spans));
+            Self::push_to_parent(stack, root, StackItem::Node(para));
+        }
+    }
+
+    /// Close the `<details>` blocks left open on top of the stack, when the list item,
+    /// blockquote or docs they were opened in end without a `</details>`
+    fn close_details<'a>(
+        current_spans: &mut Vec<Span<'a>>,
+        stack: &mut Vec<StackItem<'a>>,
+        root: &mut Vec<DocumentNode<'a>>,
+    ) {
+        let in_details = |stack: &[StackItem<'a>]| {
+            matches!(
+                stack.last(),
+                Some(StackItem::Node(DocumentNode::TruncatedBlock { .. }))
+            )
+        };
+        if in_details(stack) {
+            Self::flush_paragraph(current_spans, stack, root);
+        }
+        while in_details(stack)
+            && let Some(details) = stack.pop()
+        {
+            Self::push_to_parent(stack, root, details);
+        }
+    }
+
+    /// Finish the definition list item on top of the stack, if there is one, when the next term
+    /// or the end of the list is reached
+    fn close_definition<'a>(stack: &mut Vec<StackItem<'a>>, root: &mut Vec<DocumentNode<'a>>) {
+        if matches!(stack.last(), Some(StackItem::Item(_)))
+            && let Some(item) = stack.pop()
+        {
+            Self::push_to_parent(stack, root, item);
+        }
+    }
+
     /// Push a completed StackItem to its parent container
     fn push_to_parent<'a>(
         stack: &mut Vec<StackItem<'a>>,
@@ -339,7 +543,7 @@ impl MarkdownRenderer {
                 // Push DocumentNode to ListItem's content
                 match item {
                     StackItem::Node(node) => list_item.content.push(node),
-                    StackItem::Item(_) => {
+                    StackItem::Item(_) | StackItem::Footnote { .. } => {
                         panic!(
                             "Cannot nest ListItem directly in ListItem - lists should be nested via DocumentNode::List"
                         )
@@ -350,7 +554,7 @@ impl MarkdownRenderer {
                 // Push ListItem to List's items
                 match item {
                     StackItem::Item(list_item) => items.push(list_item),
-                    StackItem::Node(_) => {
+                    StackItem::Node(_) | StackItem::Footnote { .. } => {
                         panic!(
                             "Cannot push DocumentNode directly to List - must be wrapped in ListItem"
                         )
@@ -361,18 +565,36 @@ impl MarkdownRenderer {
                 // Push DocumentNode to BlockQuote's nodes
                 match item {
                     StackItem::Node(node) => nodes.push(node),
-                    StackItem::Item(_) => {
+                    StackItem::Item(_) | StackItem::Footnote { .. } => {
                         panic!(
                             "Cannot push ListItem directly to BlockQuote - lists should be nested via DocumentNode::List"
                         )
                     }
                 }
             }
+            Some(StackItem::Node(DocumentNode::TruncatedBlock { nodes, .. })) => {
+                // Push DocumentNode to a `<details>` block's nodes
+                match item {
+                    StackItem::Node(node) => nodes.push(node),
+                    StackItem::Item(_) | StackItem::Footnote { .. } => {
+                        panic!("Cannot push ListItem directly to a details block")
+                    }
+                }
+            }
+            Some(StackItem::Footnote { nodes, .. }) => {
+                // Push DocumentNode to the footnote's definition
+                match item {
+                    StackItem::Node(node) => nodes.push(node),
+                    StackItem::Item(_) | StackItem::Footnote { .. } => {
+                        panic!("Cannot push ListItem directly to a footnote")
+                    }
+                }
+            }
             None => {
                 // Push to root
                 match item {
                     StackItem::Node(node) => root.push(node),
-                    StackItem::Item(_) => {
+                    StackItem::Item(_) | StackItem::Footnote { .. } => {
                         panic!("Cannot push ListItem to root - must be inside a List")
                     }
                 }
@@ -398,6 +620,26 @@ impl MarkdownRenderer {
             .collect::<Vec<_>>()
             .join("\n")
     }
+
+    /// `code` with tabs replaced by spaces up to the next tab stop, so that columns line up
+    /// the same way wherever it's shown
+    fn expand_tabs(code: &str) -> String {
+        let mut expanded = String::with_capacity(code.len());
+        for line in code.split_inclusive('\n') {
+            let mut column = 0;
+            for grapheme in line.graphemes(true) {
+                if grapheme == "\t" {
+                    let spaces = TAB_WIDTH - column % TAB_WIDTH;
+                    expanded.extend(std::iter::repeat_n(' ', spaces));
+                    column += spaces;
+                } else {
+                    expanded.push_str(grapheme);
+                    column += grapheme_width(grapheme);
+                }
+            }
+        }
+        expanded
+    }
 }
 
 #[cfg(test)]
@@ -444,6 +686,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_text_and_diagram_code_blocks() {
+        let code_blocks = |input: &str| {
+            MarkdownRenderer::render_with_resolver(input, |_| None)
+                .into_iter()
+                .filter_map(|node| match node {
+                    DocumentNode::CodeBlock { lang, code, .. } => {
+                        Some((lang.unwrap().to_string(), code.to_string()))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Text laid out by hand keeps its columns, with tabs up to the next tab stop
+        let blocks = code_blocks("```ascii-art\n+--+\n|a\t|\n# x\n```");
+        assert_eq!(blocks, [("text".into(), "+--+\n|a  |\n# x\n".into())]);
+
+        let blocks = code_blocks(
+            "```mermaid\ngraph TD\n    A[Start] --> B{Ready?}\n    B -->|Yes| C[Go]\n```",
+        );
+        assert_eq!(
+            blocks,
+            [("mermaid".into(), "Start → Ready?\nReady? → Go (Yes)".into())]
+        );
+
+        let blocks = code_blocks("```mermaid\nsequenceDiagram\n    Alice->>Bob: Hi\n```");
+        assert_eq!(blocks[0].1, "Alice → Bob: Hi");
+
+        // Diagrams this can't outline are shown as their source
+        let blocks = code_blocks("```mermaid\npie\n    \"A\" : 1\n```");
+        assert_eq!(blocks[0].1, "pie\n    \"A\" : 1\n");
+    }
+
     #[test]
     fn test_heading() {
         let input = "# Main Title\n\n## Subsection";
@@ -467,6 +743,124 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_footnotes_task_lists_and_definition_lists() {
+        let input = "Serializes data[^fmt].\n\n\
+                     - [x] Done\n- [ ] To do\n\n\
+                     Term\n: Its definition\n\n\
+                     [^fmt]: In any format.";
+        let nodes = MarkdownRenderer::render_with_resolver(input, |_| None);
+        let texts = |nodes: &[DocumentNode]| {
+            nodes
+                .iter()
+                .filter_map(|node| match node {
+                    DocumentNode::Paragraph { spans } => {
+                        Some(spans.iter().map(|span| &*span.text).collect::<String>())
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let DocumentNode::List { items } = &nodes[1] else {
+            panic!("Expected the task list, got {:?}", nodes[1]);
+        };
+        assert_eq!(texts(&items[0].content), ["☑ Done"]);
+        assert_eq!(texts(&items[1].content), ["☐ To do"]);
+
+        let DocumentNode::List { items } = &nodes[2] else {
+            panic!("Expected the definition list, got {:?}", nodes[2]);
+        };
+        assert_eq!(texts(&items[0].content), ["Term", "Its definition"]);
+
+        // The footnote's reference stays in the text and its definition moves to the end
+        assert_eq!(
+            texts(&nodes),
+            ["Serializes data[fmt].", "[fmt] In any format."]
+        );
+        assert!(matches!(nodes[3], DocumentNode::HorizontalRule));
+    }
+
+    #[test]
+    fn test_images() {
+        let input = "[![CI](https://example.com/ci.svg)](https://ci.example.com) \
+                     ![docs](https://example.com/docs.svg)\n\n\
+                     | Logo |\n|---|\n| ![logo](logo.png) |";
+        let nodes = MarkdownRenderer::render_with_resolver(input, |_| None);
+
+        // A row of badges is a row of images, without the spaces between them
+        assert_eq!(nodes.len(), 3, "{nodes:?}");
+        assert!(matches!(&nodes[0], DocumentNode::Image { alt, .. } if alt == "CI"));
+        assert!(matches!(&nodes[1], DocumentNode::Image { url, .. } if url.ends_with("docs.svg")));
+
+        // An image in a table cell stays in the cell, as a link
+        let DocumentNode::Table { rows, .. } = &nodes[2] else {
+            panic!("Expected the table, got {:?}", nodes[2]);
+        };
+        assert_eq!(rows[0][0].spans[0].text, "[image: logo]");
+    }
+
+    #[test]
+    fn test_html_fragments() {
+        let input = "Line one<br>line <b>two</b>\n\n\
+                     <img src=\"https://example.com/logo.png\" alt=\"Logo\">\n\n\
+                     <details>\n<summary>Expand</summary>\n\nHidden text\n\n</details>";
+        let nodes = MarkdownRenderer::render_with_resolver(input, |_| None);
+
+        let DocumentNode::Paragraph { spans } = &nodes[0] else {
+            panic!("Expected a paragraph, got {:?}", nodes[0]);
+        };
+        let text = spans.iter().map(|span| &*span.text).collect::<String>();
+        assert_eq!(text, "Line one\nline two");
+        assert!(
+            spans
+                .iter()
+                .any(|span| span.text == "two" && span.style == SpanStyle::Strong)
+        );
+
+        let DocumentNode::Image { url, alt } = &nodes[1] else {
+            panic!("Expected the image, got {:?}", nodes[1]);
+        };
+        assert_eq!(url, "https://example.com/logo.png");
+        assert_eq!(alt, "Logo");
+
+        let DocumentNode::TruncatedBlock { nodes, .. } = &nodes[2] else {
+            panic!("Expected the details block, got {:?}", nodes[2]);
+        };
+        let texts = nodes
+            .iter()
+            .filter_map(|node| match node {
+                DocumentNode::Paragraph { spans } => {
+                    Some(spans.iter().map(|span| &*span.text).collect::<String>())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["Expand", "Hidden text"]);
+    }
+
+    #[test]
+    fn test_unclosed_details_end_with_their_container() {
+        let input = "- <details>\n  <summary>First</summary>\n\n  Hidden\n\n- Second\n\n\
+                     > <details>\n> <summary>Quoted</summary>\n\nAfter";
+        let nodes = MarkdownRenderer::render_with_resolver(input, |_| None);
+
+        let DocumentNode::List { items } = &nodes[0] else {
+            panic!("Expected a list, got {:?}", nodes[0]);
+        };
+        let is_details =
+            |node: &DocumentNode<'_>| matches!(node, DocumentNode::TruncatedBlock { .. });
+        assert_eq!(items.len(), 2);
+        assert!(items[0].content.iter().any(is_details));
+        assert!(!items[1].content.iter().any(is_details));
+
+        let DocumentNode::BlockQuote { nodes: quoted } = &nodes[1] else {
+            panic!("Expected a blockquote, got {:?}", nodes[1]);
+        };
+        assert!(quoted.iter().any(is_details));
+        assert!(matches!(&nodes[2], DocumentNode::Paragraph { .. }));
+    }
+
     #[test]
     fn test_links_in_list_items() {
         let input = "- Item with [link](https://example.com) inline\n- Another [link](https://other.com) here";
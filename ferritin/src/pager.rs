@@ -0,0 +1,73 @@
+//! Showing long one-shot output in a pager
+//!
+//! Output for a terminal that won't fit on one screen is piped through `$FERRITIN_PAGER`, then
+//! the config file's `pager`, then `$PAGER`, then `less`, instead of scrolling away. Like git,
+//! ferritin sets `LESS=FRX` when it isn't already set, so that less keeps colors and leaves the
+//! output on screen when it exits.
+
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use terminal_size::{Height, terminal_size};
+
+/// When output for a terminal is shown in the pager
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Paging {
+    /// Never, for `--no-pager` or an empty pager in the config file
+    Never,
+    /// When the output doesn't fit on one screen
+    Auto,
+    /// Whatever the length of the output, for `--pager`
+    Always,
+}
+
+/// Whether output for stdout should be collected and shown through [`show`] rather than
+/// streamed, which is only worth it when stdout is a terminal
+pub(crate) fn wanted(paging: Paging) -> bool {
+    paging != Paging::Never && io::stdout().is_terminal()
+}
+
+/// Write `text` to stdout, through the pager if `paging` says to or it doesn't fit on one
+/// screen. `configured` is the pager from the config file.
+pub(crate) fn show(text: &str, configured: Option<&str>, paging: Paging) -> io::Result<()> {
+    let fits = terminal_size()
+        .is_some_and(|(_, Height(height))| text.lines().count() < usize::from(height));
+    if paging == Paging::Never || (fits && paging == Paging::Auto) {
+        return io::stdout().write_all(text.as_bytes());
+    }
+
+    let pager = env::var("FERRITIN_PAGER")
+        .ok()
+        .or_else(|| configured.map(str::to_string))
+        .or_else(|| env::var("PAGER").ok())
+        .unwrap_or_else(|| "less".to_string());
+    // An empty pager is how a user says they don't want one
+    let mut words = pager.split_whitespace();
+    let Some(program) = words.next() else {
+        return io::stdout().write_all(text.as_bytes());
+    };
+
+    let mut command = Command::new(program);
+    command.args(words).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            log::debug!("Could not start the pager {pager:?}: {error}");
+            return io::stdout().write_all(text.as_bytes());
+        }
+    };
+
+    // Quitting the pager before reading everything closes its input, which isn't an error
+    if let Some(mut stdin) = child.stdin.take()
+        && let Err(error) = stdin.write_all(text.as_bytes())
+        && error.kind() != io::ErrorKind::BrokenPipe
+    {
+        return Err(error);
+    }
+    child.wait()?;
+    Ok(())
+}
@@ -0,0 +1,177 @@
+//! Size guardrails for one-shot output
+//!
+//! A recursive listing of a large crate can render to tens of thousands of lines. Rather than
+//! flooding the terminal (or a script's input), one-shot mode shows the output one page at a
+//! time and reports what was left out.
+
+use std::fmt::{self, Write};
+use std::num::NonZeroUsize;
+
+/// Number of output lines shown per page
+pub(crate) const PAGE_LINES: usize = 5_000;
+
+/// A writer that passes through a single page of lines and counts the rest
+pub(crate) struct PagedWriter<W> {
+    inner: W,
+    /// 1-based page to pass through, or None to pass everything through
+    page: Option<NonZeroUsize>,
+    /// Completed lines seen so far
+    lines: usize,
+    /// Whether the last write ended partway through a line
+    partial_line: bool,
+}
+
+impl<W: Write> PagedWriter<W> {
+    /// Pass through only the lines that belong to `page`
+    pub(crate) fn new(inner: W, page: NonZeroUsize) -> Self {
+        Self {
+            inner,
+            page: Some(page),
+            lines: 0,
+            partial_line: false,
+        }
+    }
+
+    /// Pass through everything, regardless of size
+    pub(crate) fn unlimited(inner: W) -> Self {
+        Self {
+            inner,
+            page: None,
+            lines: 0,
+            partial_line: false,
+        }
+    }
+
+    /// Pass through only the lines that belong to `page`, or everything if there is none
+    pub(crate) fn with_page(inner: W, page: Option<NonZeroUsize>) -> Self {
+        match page {
+            Some(page) => Self::new(inner, page),
+            None => Self::unlimited(inner),
+        }
+    }
+
+    /// The writer that passed-through lines are written to
+    pub(crate) fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// The writer that passed-through lines were written to, once writing is done
+    pub(crate) fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Total number of lines written, including those not passed through
+    pub(crate) fn total_lines(&self) -> usize {
+        self.lines + usize::from(self.partial_line)
+    }
+
+    fn page_range(&self) -> Option<(usize, usize)> {
+        let page = self.page?.get();
+        Some(((page - 1) * PAGE_LINES, page * PAGE_LINES))
+    }
+
+    fn shows_line(&self, line: usize) -> bool {
+        self.page_range()
+            .is_none_or(|(start, end)| (start..end).contains(&line))
+    }
+
+    /// Describe what was left out, if the output did not fit on one page
+    ///
+    /// Returns `Err` with an explanation when the requested page is past the end of the output.
+    pub(crate) fn omission_summary(&self) -> Result<Option<String>, String> {
+        let Some((start, end)) = self.page_range() else {
+            return Ok(None);
+        };
+
+        let total = self.total_lines();
+        let pages = total.div_ceil(PAGE_LINES).max(1);
+        let page = start / PAGE_LINES + 1;
+
+        if page > pages {
+            return Err(format!(
+                "Page {page} is past the end of the output ({total} lines, {pages} page{})",
+                if pages == 1 { "" } else { "s" }
+            ));
+        }
+
+        if pages == 1 {
+            return Ok(None);
+        }
+
+        let shown_end = end.min(total);
+        let omitted = total - (shown_end - start);
+        let next = if page < pages {
+            format!("--page {} for the next part", page + 1)
+        } else {
+            String::from("--page N for another part")
+        };
+
+        Ok(Some(format!(
+            "Showing page {page} of {pages} (lines {}-{shown_end} of {total}); \
+             {omitted} lines omitted. Use {next}, or --yes-huge to print everything.",
+            start + 1
+        )))
+    }
+}
+
+impl<W: Write> Write for PagedWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for segment in s.split_inclusive('\n') {
+            if self.shows_line(self.lines) {
+                self.inner.write_str(segment)?;
+            }
+
+            if segment.ends_with('\n') {
+                self.lines += 1;
+                self.partial_line = false;
+            } else {
+                self.partial_line = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(count: usize) -> String {
+        (1..=count).map(|n| format!("line {n}\n")).collect()
+    }
+
+    #[test]
+    fn small_output_passes_through() {
+        let mut output = String::new();
+        let mut writer = PagedWriter::new(&mut output, NonZeroUsize::MIN);
+        writer.write_str(&lines(3)).unwrap();
+        assert_eq!(writer.omission_summary(), Ok(None));
+        assert_eq!(output, lines(3));
+    }
+
+    #[test]
+    fn large_output_is_paged() {
+        let mut output = String::new();
+        let mut writer = PagedWriter::new(&mut output, NonZeroUsize::new(2).unwrap());
+        writer.write_str(&lines(PAGE_LINES * 2 + 10)).unwrap();
+
+        let summary = writer.omission_summary().unwrap().unwrap();
+        assert!(summary.starts_with("Showing page 2 of 3"), "{summary}");
+        assert!(summary.contains("--page 3"), "{summary}");
+
+        assert_eq!(output.lines().count(), PAGE_LINES);
+        assert_eq!(
+            output.lines().next(),
+            Some(format!("line {}", PAGE_LINES + 1).as_str())
+        );
+    }
+
+    #[test]
+    fn page_past_the_end_is_an_error() {
+        let mut output = String::new();
+        let mut writer = PagedWriter::new(&mut output, NonZeroUsize::new(4).unwrap());
+        writer.write_str(&lines(10)).unwrap();
+        assert!(writer.omission_summary().is_err());
+        assert!(output.is_empty());
+    }
+}
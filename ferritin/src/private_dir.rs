@@ -0,0 +1,108 @@
+//! A directory for files only the current user may touch
+//!
+//! Daemon sockets and example projects used to live straight in the temp directory, where any
+//! user can create a file with the name ferritin is about to use. They now live in a directory
+//! named after the user, which ferritin creates with mode 0700 and which is checked to be owned
+//! by the user and closed to everyone else before anything in it is trusted.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The current user's directory, which may not exist yet: under `$XDG_RUNTIME_DIR` when that
+/// is set, and the temp directory otherwise
+#[cfg(unix)]
+pub(crate) fn path() -> PathBuf {
+    let base = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    base.join(format!("ferritin-{}", rustix::process::getuid().as_raw()))
+}
+
+/// The temp directory is already per user on platforms without unix permissions
+#[cfg(not(unix))]
+pub(crate) fn path() -> PathBuf {
+    std::env::temp_dir().join("ferritin")
+}
+
+/// Create the current user's directory if needed and check it, returning its path
+pub(crate) fn create() -> io::Result<PathBuf> {
+    let dir = path();
+    create_at(&dir)?;
+    Ok(dir)
+}
+
+#[cfg(unix)]
+fn create_at(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    check(dir)
+}
+
+#[cfg(not(unix))]
+fn create_at(dir: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dir)
+}
+
+/// Check that `dir` is a real directory, owned by the current user and closed to everyone else,
+/// so that whatever is in it was put there by this user
+#[cfg(unix)]
+pub(crate) fn check(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+    let problem = if !metadata.is_dir() {
+        "is not a directory"
+    } else if metadata.uid() != rustix::process::getuid().as_raw() {
+        "is owned by another user"
+    } else if metadata.permissions().mode() & 0o077 != 0 {
+        "can be used by other users"
+    } else {
+        return Ok(());
+    };
+
+    Err(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("{} {problem}", dir.display()),
+    ))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn check(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn only_private_directories_pass() {
+        let dir = std::env::temp_dir().join(format!("ferritin-private-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        create_at(&dir).unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(
+            check(&dir).unwrap_err().kind(),
+            io::ErrorKind::PermissionDenied
+        );
+
+        let link = dir.with_extension("link");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700)).unwrap();
+        assert!(check(&dir).is_ok());
+        assert!(check(&link).is_err());
+
+        std::fs::remove_file(&link).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
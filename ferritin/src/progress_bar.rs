@@ -0,0 +1,82 @@
+//! A spinner on stderr for slow operations in one-shot mode
+//!
+//! Downloading docs, running `cargo doc` and indexing a crate can each take tens of seconds,
+//! so the innermost task is shown while it runs and cleared when it finishes.
+
+use ferritin_common::progress::Progress;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Shows the innermost slow task as a spinner, or as a bar once it says how far it has got
+///
+/// indicatif draws nothing when stderr isn't a terminal, so piped output stays clean.
+#[derive(Default)]
+pub(crate) struct ProgressSpinner {
+    state: Mutex<SpinnerState>,
+}
+
+#[derive(Default)]
+struct SpinnerState {
+    bar: Option<ProgressBar>,
+    /// Nested task descriptions, innermost last
+    tasks: Vec<String>,
+}
+
+fn spinner_style() -> ProgressStyle {
+    ProgressStyle::with_template("{spinner} {msg} ({elapsed})").unwrap()
+}
+
+fn bar_style() -> ProgressStyle {
+    ProgressStyle::with_template("{spinner} {msg} [{bar:30}] {percent}%")
+        .unwrap()
+        .progress_chars("=> ")
+}
+
+impl Progress for ProgressSpinner {
+    fn start(&self, task: &str) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.tasks.push(task.to_string());
+
+        let bar = state.bar.get_or_insert_with(|| {
+            let bar = ProgressBar::new_spinner();
+            bar.enable_steady_tick(Duration::from_millis(100));
+            bar
+        });
+        bar.set_style(spinner_style());
+        bar.set_message(task.to_string());
+    }
+
+    fn advance(&self, done: u64, total: u64) {
+        if let Ok(state) = self.state.lock()
+            && let Some(bar) = &state.bar
+        {
+            bar.set_style(bar_style());
+            bar.set_length(total);
+            bar.set_position(done);
+        }
+    }
+
+    fn finish(&self) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.tasks.pop();
+
+        match state.tasks.last().cloned() {
+            Some(outer) => {
+                if let Some(bar) = &state.bar {
+                    bar.set_style(spinner_style());
+                    bar.set_message(outer);
+                }
+            }
+            None => {
+                if let Some(bar) = state.bar.take() {
+                    bar.finish_and_clear();
+                }
+            }
+        }
+    }
+}
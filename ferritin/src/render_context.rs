@@ -1,7 +1,11 @@
+use crate::color_depth::ColorDepth;
 use crate::color_scheme::ColorScheme;
-use crate::renderer::OutputMode;
+use crate::config;
+use crate::format_context::FormatContext;
+use crate::key_bindings::KeyBindings;
+use crate::renderer::{Links, OutputMode};
 use fieldwork::Fieldwork;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use syntect::highlighting::{Theme, ThemeSet};
 use syntect::parsing::SyntaxSet;
 use thiserror::Error;
@@ -30,8 +34,15 @@ pub(crate) struct RenderContext {
     color_scheme: ColorScheme,
     /// Terminal width for wrapping/layout
     terminal_width: usize,
+    /// Whether paragraphs are wrapped to the terminal width in one-shot output
+    #[field(get = "wraps_text")]
+    wrap: bool,
     /// Output mode (TTY, Plain, TestMode) - determines which renderer to use
+    #[field(copy)]
     output_mode: OutputMode,
+    /// How many colors the terminal can show
+    #[field(copy)]
+    color_depth: ColorDepth,
     /// Interactive mode - affects rendering decisions (e.g., link styling)
     #[field(get = "is_interactive")]
     interactive: bool,
@@ -41,12 +52,40 @@ pub(crate) struct RenderContext {
     theme: Theme,
     /// The name of the currently loaded theme
     current_theme_name: Option<String>,
+    /// Decorative emoji, animations, ornamental rules and hover highlighting in interactive mode
+    #[field(get = "show_decorations")]
+    decorations: bool,
+    /// Images from docs drawn inline in interactive mode, in terminals that can
+    #[field(get = "shows_images")]
+    images: bool,
+    /// Keys from the config file for interactive mode actions
+    key_bindings: KeyBindings,
+    /// Rows kept between the focused link and the edge of the screen in interactive mode
+    #[field(copy)]
+    scrolloff: u16,
+    /// What links become in one-shot output, if not the output mode's default
+    #[field = false]
+    links: Option<Links>,
 }
 
 impl RenderContext {
-    /// Get the list of available theme names
+    /// Get the list of available theme names: the built-in themes, then the user's own
     pub(crate) fn available_themes() -> Vec<String> {
-        themes::THEME_NAMES.iter().map(|s| s.to_string()).collect()
+        let mut names = themes::THEME_NAMES
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        for name in user_theme_names() {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Whether `theme_name` is one of the user's own themes rather than a built-in one
+    pub(crate) fn is_user_theme(theme_name: &str) -> bool {
+        !themes::THEME_NAMES.contains(&theme_name) && user_theme_path(theme_name).is_some()
     }
 
     pub(crate) fn with_theme_name(mut self, theme_name_or_path: &str) -> Result<Self, ThemeError> {
@@ -58,34 +97,73 @@ impl RenderContext {
         &mut self,
         theme_name_or_path: &str,
     ) -> Result<&mut Self, ThemeError> {
+        let load_file = |path: &Path| {
+            ThemeSet::get_theme(path).map_err(|e| {
+                ThemeError::FileLoadError(theme_name_or_path.to_string(), e.to_string())
+            })
+        };
+
         // Check if it's a file path to a .tmTheme file
         let path = Path::new(&theme_name_or_path);
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("tmTheme") {
-            // Load theme from file
-            let theme = ThemeSet::get_theme(path).map_err(|e| {
-                ThemeError::FileLoadError(theme_name_or_path.to_string(), e.to_string())
-            })?;
+        let theme =
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("tmTheme") {
+                load_file(path)?
+            } else if let Some(theme) = themes::load_theme(theme_name_or_path) {
+                // A theme name from the embedded set
+                theme
+            } else if let Some(path) = user_theme_path(theme_name_or_path) {
+                // A theme name from the user's themes directory
+                load_file(&path)?
+            } else {
+                return Err(ThemeError::ThemeNotFound(
+                    theme_name_or_path.to_string(),
+                    Self::available_themes().join(", "),
+                ));
+            };
 
-            self.color_scheme = ColorScheme::from_syntect_theme(&theme);
-            self.theme = theme;
-            self.current_theme_name = Some(theme_name_or_path.to_string());
-            return Ok(self);
-        }
+        Ok(self.set_theme(theme_name_or_path, theme))
+    }
 
-        // Try to load it as a theme name from the embedded set
-        if let Some(theme) = themes::load_theme(theme_name_or_path) {
-            self.color_scheme = ColorScheme::from_syntect_theme(&theme);
-            self.theme = theme;
-            self.current_theme_name = Some(theme_name_or_path.to_string());
-            Ok(self)
-        } else {
-            Err(ThemeError::ThemeNotFound(
-                theme_name_or_path.to_string(),
-                themes::THEME_NAMES.join(", "),
-            ))
+    /// Use an already loaded theme, under `theme_name`
+    pub(crate) fn set_theme(&mut self, theme_name: impl Into<String>, theme: Theme) -> &mut Self {
+        self.color_scheme = ColorScheme::from_syntect_theme(&theme);
+        self.theme = theme;
+        self.current_theme_name = Some(theme_name.into());
+        self
+    }
+
+    /// The width to wrap one-shot output to, if it's wrapped
+    pub(crate) fn wrap_width(&self) -> Option<usize> {
+        self.wrap.then_some(self.terminal_width)
+    }
+
+    /// Builder method for showing documents the way a request's format context asks, in its
+    /// output mode and theme where it names them
+    pub(crate) fn with_format_context(
+        mut self,
+        format_context: &FormatContext,
+    ) -> Result<Self, ThemeError> {
+        if let Some(output_mode) = format_context.output_mode() {
+            self.output_mode = output_mode;
+        }
+        match format_context.theme() {
+            Some(theme) => self.with_theme_name(theme),
+            None => Ok(self),
         }
     }
 
+    /// What links become in one-shot output
+    pub(crate) fn links(&self) -> Links {
+        self.links
+            .unwrap_or_else(|| Links::default_for(self.output_mode))
+    }
+
+    /// Builder method for what links become, or `None` for the output mode's default
+    pub(crate) fn with_links(mut self, links: Option<Links>) -> Self {
+        self.links = links;
+        self
+    }
+
     pub(crate) fn new() -> Self {
         // Load a default theme (first available theme)
         let default_theme_name = themes::THEME_NAMES[0];
@@ -95,11 +173,41 @@ impl RenderContext {
         Self {
             color_scheme: ColorScheme::default(),
             terminal_width: 80,
+            wrap: true,
             output_mode: OutputMode::TestMode,
+            color_depth: ColorDepth::TrueColor,
             interactive: false,
             syntax_set: SyntaxSet::load_defaults_newlines(),
             theme: default_theme,
             current_theme_name: Some(default_theme_name.to_string()),
+            decorations: true,
+            images: true,
+            key_bindings: KeyBindings::default(),
+            scrolloff: 0,
+            links: None,
         }
     }
 }
+
+/// The names of the `.tmTheme` files in the user's themes directory
+fn user_theme_names() -> Vec<String> {
+    let Some(entries) = config::themes_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return vec![];
+    };
+    let mut names = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("tmTheme") {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+fn user_theme_path(theme_name: &str) -> Option<PathBuf> {
+    let path = config::themes_dir()?.join(format!("{theme_name}.tmTheme"));
+    path.is_file().then_some(path)
+}
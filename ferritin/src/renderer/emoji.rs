@@ -0,0 +1,144 @@
+use crate::styled_string::{Document, DocumentNode, Span};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Remove emoji from the text of `document`, for the calmer mode without decorations
+///
+/// Code blocks are quoted as they are.
+pub(crate) fn strip(document: &mut Document<'_>) {
+    for node in &mut document.nodes {
+        strip_node(node);
+    }
+}
+
+fn strip_node(node: &mut DocumentNode<'_>) {
+    match node {
+        DocumentNode::Paragraph { spans }
+        | DocumentNode::Heading { spans, .. }
+        | DocumentNode::GeneratedCode { spans } => strip_spans(spans),
+        DocumentNode::Section { title, nodes } => {
+            if let Some(title) = title {
+                strip_spans(title);
+            }
+            for node in nodes {
+                strip_node(node);
+            }
+        }
+        DocumentNode::List { items } => {
+            for node in items.iter_mut().flat_map(|item| &mut item.content) {
+                strip_node(node);
+            }
+        }
+        DocumentNode::BlockQuote { nodes }
+        | DocumentNode::TruncatedBlock { nodes, .. }
+        | DocumentNode::Conditional { nodes, .. } => {
+            for node in nodes {
+                strip_node(node);
+            }
+        }
+        DocumentNode::Table { header, rows } => {
+            for cell in header.iter_mut().flatten().chain(rows.iter_mut().flatten()) {
+                strip_spans(&mut cell.spans);
+            }
+        }
+        DocumentNode::Image { alt, .. } => {
+            if let Some(text) = without_emoji(alt) {
+                *alt = text.into();
+            }
+        }
+        DocumentNode::CodeBlock { .. } | DocumentNode::HorizontalRule => {}
+    }
+}
+
+fn strip_spans(spans: &mut [Span<'_>]) {
+    for span in spans {
+        if let Some(text) = without_emoji(&span.text) {
+            span.text = text.into();
+        }
+    }
+}
+
+/// `text` without its emoji, or `None` when it has none
+///
+/// Whole grapheme clusters are removed, so skin tones, flags, keycaps and sequences joined
+/// with zero-width joiners go with the emoji they belong to, along with a space separating
+/// the emoji from the following word.
+pub(crate) fn without_emoji(text: &str) -> Option<String> {
+    if !text.chars().any(is_emoji) {
+        return None;
+    }
+
+    let mut stripped = String::with_capacity(text.len());
+    let mut skip_space = false;
+    for grapheme in text.graphemes(true) {
+        if grapheme.chars().any(is_emoji) {
+            skip_space = stripped.is_empty() || stripped.ends_with(char::is_whitespace);
+        } else if !(skip_space && grapheme == " ") {
+            stripped.push_str(grapheme);
+            skip_space = false;
+        }
+    }
+    Some(stripped)
+}
+
+/// Whether `c` is a pictograph shown as emoji, or marks the character before it as one
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        // Pictographs, emoticons, transport and map symbols, flags and supplemental symbols
+        '\u{1F000}'..='\u{1FAFF}'
+            // Miscellaneous symbols and dingbats
+            | '\u{2600}'..='\u{27BF}'
+            // Watch, hourglass and media controls
+            | '\u{231A}'..='\u{231B}'
+            | '\u{23E9}'..='\u{23F3}'
+            | '\u{23F8}'..='\u{23FA}'
+            // Star and circle
+            | '\u{2B50}'
+            | '\u{2B55}'
+            // Emoji presentation selector and combining keycap
+            | '\u{FE0F}'
+            | '\u{20E3}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_emoji_with_their_modifiers() {
+        assert_eq!(without_emoji("Rust"), None);
+        assert_eq!(without_emoji("🦀 Rust").as_deref(), Some("Rust"));
+        assert_eq!(
+            without_emoji("Fast 🚀 and safe").as_deref(),
+            Some("Fast and safe")
+        );
+        assert_eq!(without_emoji("Done ✅").as_deref(), Some("Done "));
+        assert_eq!(without_emoji("👍🏽 ok").as_deref(), Some("ok"));
+        assert_eq!(without_emoji("👩‍💻 team").as_deref(), Some("team"));
+        assert_eq!(without_emoji("🇳🇱 Dutch").as_deref(), Some("Dutch"));
+        assert_eq!(
+            without_emoji("Step 1️⃣ first").as_deref(),
+            Some("Step first")
+        );
+        assert_eq!(without_emoji("日本語 ❤️").as_deref(), Some("日本語 "));
+    }
+
+    #[test]
+    fn leaves_code_blocks_alone() {
+        let mut document = Document::with_nodes(vec![
+            DocumentNode::paragraph(vec![Span::plain("⚠️ Panics")]),
+            DocumentNode::code_block(Some("rust"), "println!(\"🦀\");"),
+        ]);
+        strip(&mut document);
+
+        assert!(matches!(
+            &document.nodes[0],
+            DocumentNode::Paragraph { spans } if spans[0].text == "Panics"
+        ));
+        assert!(matches!(
+            &document.nodes[1],
+            DocumentNode::CodeBlock { code, .. } if code.contains('🦀')
+        ));
+    }
+}
@@ -3,10 +3,21 @@
 use ferritin_common::DocRef;
 use rustdoc_types::Item;
 
+use super::crate_picker::CrateChoice;
 use super::history::HistoryEntry;
+use crate::commands::run_example::{DocExample, ExampleProject, ExampleRun};
+use crate::commands::search::SearchMode;
 use crate::styled_string::Document;
 use std::borrow::Cow;
 
+/// A command as the UI thread sends it, numbered so that cancelling loading applies to exactly
+/// the commands sent before it, whether or not the request thread has started on them
+#[derive(Debug)]
+pub struct SentCommand<'a> {
+    pub generation: u64,
+    pub command: UiCommand<'a>,
+}
+
 /// Commands sent from UI thread to Request thread
 #[derive(Debug)]
 pub enum UiCommand<'a> {
@@ -21,17 +32,42 @@ pub enum UiCommand<'a> {
         query: Cow<'a, str>,
         crate_name: Option<Cow<'a, str>>,
         limit: usize,
+        mode: SearchMode,
+        /// The item being viewed when the search started, used to rank nearby results higher
+        context: Option<DocRef<'a, Item>>,
     },
 
     /// Show list of available crates
     List,
 
+    /// Show the start page
+    Home,
+
     /// Toggle source code display
     ToggleSource {
         include_source: bool,
         current_item: Option<DocRef<'a, Item>>,
     },
 
+    /// Compile and run the nth (1-based) example from an item's docs
+    RunExample {
+        doc_ref: DocRef<'a, Item>,
+        index: usize,
+    },
+
+    /// Show the public API changes of a dependency since an earlier version
+    ApiDiff {
+        crate_name: Cow<'a, str>,
+        from: Cow<'a, str>,
+    },
+
+    /// Save a search under a name, to be listed on the start page
+    SaveSearch {
+        name: String,
+        query: String,
+        crate_name: Option<String>,
+    },
+
     /// Shutdown the request thread
     Shutdown,
 }
@@ -47,6 +83,30 @@ pub enum RequestResponse<'a> {
     /// An error occurred (path not found, etc.)
     Error(String),
 
+    /// A doc example was handed to the example thread, which answers with `ExampleOutput`
+    ExampleStarted { title: String },
+
+    /// A doc example finished building/running
+    ExampleOutput { title: String, run: ExampleRun },
+
+    /// Past search queries for the workspace, oldest first
+    SearchHistory(Vec<String>),
+
+    /// The available crates, for the crate switcher
+    Crates(Vec<CrateChoice>),
+
+    /// An image from the docs being shown, downloaded on the image thread, or None if it
+    /// couldn't be
+    Image { url: String, data: Option<Vec<u8>> },
+
     /// Acknowledgment that shutdown is complete
     ShuttingDown,
 }
+
+/// A doc example and the scratch project for it, for the example thread to build and run
+#[derive(Debug)]
+pub struct ExampleJob {
+    pub title: String,
+    pub project: ExampleProject,
+    pub example: DocExample,
+}
@@ -0,0 +1,242 @@
+//! The crate switcher - a modal listing every available crate, narrowed down by typing some of
+//! a crate's name, which opens the chosen crate's root module
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Modifier,
+    text::{Line, Span as RatatuiSpan},
+    widgets::{Block, Borders, Clear, Widget},
+};
+
+use super::channels::UiCommand;
+use super::state::{InteractiveState, UiMode};
+use super::utils::centered_rect;
+
+/// A crate the switcher offers, as sent by the request thread when it starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct CrateChoice {
+    pub name: String,
+    /// What to go to for the crate's root module, like `serde` or `serde@1.0.100` for a crate
+    /// that is in the dependency graph more than once
+    pub path: String,
+    pub version: Option<String>,
+    /// Where the crate's docs come from, like "workspace" or "std"
+    pub provenance: &'static str,
+}
+
+/// How well `name` matches `filter`, typed as some of its characters in order, or None if it
+/// doesn't match. Matches at the start of the name or of a word, and characters next to each
+/// other, score higher. Case and `-` or `_` don't matter.
+fn fuzzy_score(name: &str, filter: &str) -> Option<u32> {
+    let normalize = |c: char| {
+        if c == '-' {
+            '_'
+        } else {
+            c.to_ascii_lowercase()
+        }
+    };
+    let name = name.chars().map(normalize).collect::<Vec<_>>();
+
+    let mut score = 0;
+    let mut start = 0;
+    let mut previous = None;
+    for wanted in filter.chars().map(normalize) {
+        let found = start + name[start..].iter().position(|&c| c == wanted)?;
+        if found == 0 || name[found - 1] == '_' {
+            score += 2;
+        }
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 1;
+        }
+        previous = Some(found);
+        start = found + 1;
+    }
+    Some(score)
+}
+
+impl<'a> InteractiveState<'a> {
+    /// The crates that match `filter`, best first and alphabetically among equal matches
+    fn matching_crates(&self, filter: &str) -> Vec<&CrateChoice> {
+        let mut matches = self
+            .ui
+            .crates
+            .iter()
+            .filter_map(|choice| Some((fuzzy_score(&choice.name, filter)?, choice)))
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|(score, _)| Reverse(*score));
+        matches.into_iter().map(|(_, choice)| choice).collect()
+    }
+
+    /// Handle a key in the crate switcher: typing narrows the list, arrows or Ctrl+n/Ctrl+p
+    /// move through it, and Enter opens the selected crate
+    pub(super) fn handle_crate_picker_key(&mut self, key: KeyEvent) {
+        let UiMode::CratePicker { filter, .. } = &self.ui_mode else {
+            return;
+        };
+        let matches = self
+            .matching_crates(filter)
+            .into_iter()
+            .map(|choice| choice.path.clone())
+            .collect::<Vec<_>>();
+        let UiMode::CratePicker {
+            filter,
+            selected_index,
+        } = &mut self.ui_mode
+        else {
+            return;
+        };
+
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+            KeyCode::Char('p') if control => *selected_index = selected_index.saturating_sub(1),
+            KeyCode::Down if *selected_index + 1 < matches.len() => *selected_index += 1,
+            KeyCode::Char('n') if control && *selected_index + 1 < matches.len() => {
+                *selected_index += 1;
+            }
+            KeyCode::Backspace => {
+                filter.pop();
+                *selected_index = 0;
+            }
+            KeyCode::Char(c) if !control => {
+                filter.push(c);
+                *selected_index = 0;
+            }
+            KeyCode::Enter => {
+                let Some(path) = matches.get(*selected_index).cloned() else {
+                    return;
+                };
+                self.ui_mode = UiMode::Normal;
+                self.ui.debug_message = format!("Loading: {path}...").into();
+                self.send_command(UiCommand::NavigateToPath(Cow::Owned(path)));
+                self.loading.start();
+            }
+            _ => {}
+        }
+    }
+
+    /// Render the crate switcher as a modal overlay
+    pub(super) fn render_crate_picker(&mut self, buf: &mut Buffer, area: Rect) {
+        // Clear document actions - modal should block all background interactions
+        self.render_cache.actions.clear();
+
+        let UiMode::CratePicker {
+            filter,
+            selected_index,
+        } = &self.ui_mode
+        else {
+            return;
+        };
+        let matches = self.matching_crates(filter);
+
+        let modal_area = centered_rect(60, 70, area);
+        Clear.render(modal_area, buf);
+        let block = Block::default()
+            .title(format!(
+                " Crates ({}/{}) ",
+                matches.len(),
+                self.ui.crates.len()
+            ))
+            .borders(Borders::ALL)
+            .style(self.theme.help_bg_style);
+        let inner = block.inner(modal_area);
+        block.render(modal_area, buf);
+        if inner.height < 3 {
+            return;
+        }
+
+        let prompt = Line::from(vec![
+            RatatuiSpan::styled("Crate: ", self.theme.help_key_style),
+            RatatuiSpan::styled(format!("{filter}▏"), self.theme.help_desc_style),
+        ]);
+        buf.set_line(inner.x + 1, inner.y, &prompt, inner.width.saturating_sub(1));
+
+        // The rows between the prompt and the instructions, scrolled to keep the selected
+        // crate in view
+        let list_top = inner.y + 2;
+        let list_height = inner.height.saturating_sub(3);
+        let offset = selected_index.saturating_sub(usize::from(list_height.saturating_sub(1)));
+
+        if matches.is_empty() {
+            let message = if self.ui.crates.is_empty() {
+                "(the list of crates is still loading)"
+            } else {
+                "(no crates match)"
+            };
+            buf.set_string(inner.x + 2, list_top, message, self.theme.muted_style);
+        }
+
+        let selected_style = self.theme.help_key_style.add_modifier(Modifier::REVERSED);
+        for (row, (index, choice)) in matches
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(usize::from(list_height))
+            .enumerate()
+        {
+            let y = list_top + row as u16;
+            let selected = index == *selected_index;
+            let name_style = if selected {
+                selected_style
+            } else {
+                self.theme.help_key_style
+            };
+            let mut spans = vec![
+                RatatuiSpan::raw(if selected { "> " } else { "  " }),
+                RatatuiSpan::styled(choice.name.as_str(), name_style),
+            ];
+            if let Some(version) = &choice.version {
+                spans.push(RatatuiSpan::styled(
+                    format!(" {version}"),
+                    self.theme.help_desc_style,
+                ));
+            }
+            spans.push(RatatuiSpan::styled(
+                format!("  {}", choice.provenance),
+                self.theme.muted_style,
+            ));
+            buf.set_line(inner.x, y, &Line::from(spans), inner.width);
+        }
+
+        let instructions = " Type to filter  ↑/↓:Navigate  Enter:Open  Esc:Cancel ";
+        let instructions_y = inner.y + inner.height - 1;
+        let instructions_x = inner.x
+            + inner
+                .width
+                .saturating_sub(instructions.chars().count() as u16)
+                / 2;
+        buf.set_stringn(
+            instructions_x,
+            instructions_y,
+            instructions,
+            usize::from(inner.width),
+            self.theme.status_hint_style,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_matches_characters_in_order() {
+        assert_eq!(fuzzy_score("serde", ""), Some(0));
+        assert!(fuzzy_score("serde_json", "sj").is_some());
+        assert!(fuzzy_score("serde-json", "serde_j").is_some());
+        assert!(fuzzy_score("Tokio", "tok").is_some());
+        assert_eq!(fuzzy_score("serde", "sd_"), None);
+        assert_eq!(fuzzy_score("serde", "es"), None);
+
+        // The start of a word and adjacent characters count for more than scattered ones
+        let json = fuzzy_score("serde_json", "json").unwrap();
+        let scattered = fuzzy_score("jsonwebtoken_stubs", "jnts").unwrap();
+        assert!(json > scattered);
+        assert!(fuzzy_score("syn", "syn") > fuzzy_score("async", "syn"));
+    }
+}
@@ -1,48 +1,123 @@
 use super::channels::UiCommand;
+use super::state::InteractiveState;
 use super::utils::find_node_at_path_mut;
-use crate::styled_string::{Document, DocumentNode, TruncationLevel, TuiAction};
+use crate::commands::search::SearchMode;
+use crate::styled_string::{DocumentNode, NodePath, TruncationLevel, TuiAction};
 
-/// Handle a TuiAction, returning a command to send if navigation is needed
-///
-/// Some actions (ExpandBlock) mutate the document in place and return None.
-/// Navigation actions return a UiCommand that the caller should send via channel.
-pub(super) fn handle_action<'a>(
-    document: &mut Document<'a>,
-    action: TuiAction<'a>,
-) -> Option<UiCommand<'a>> {
-    match action {
-        TuiAction::ExpandBlock(path) => {
-            // Find the node at this path and expand it
-            if let Some(node) = find_node_at_path_mut(&mut document.nodes, path.indices())
-                && let DocumentNode::TruncatedBlock { level, .. } = node
-            {
-                // Cycle through truncation levels: SingleLine -> Full
-                *level = match level {
-                    TruncationLevel::SingleLine | TruncationLevel::Brief => TruncationLevel::Full,
-                    TruncationLevel::Full => TruncationLevel::Full, // Already expanded
-                };
+impl<'a> InteractiveState<'a> {
+    /// Handle a TuiAction, returning a command to send if navigation is needed
+    ///
+    /// Some actions (ExpandBlock) mutate the document in place and return None.
+    /// Navigation actions return a UiCommand that the caller should send via channel.
+    pub(super) fn handle_action(&mut self, action: TuiAction<'a>) -> Option<UiCommand<'a>> {
+        match action {
+            TuiAction::ExpandBlock(path) => {
+                self.expand_block(path);
+                None // No command needed, just mutated in place
+            }
+            TuiAction::Navigate { doc_ref, url: _ } => {
+                // Return Navigate command - caller will send it and wait for response
+                Some(UiCommand::Navigate(doc_ref))
+            }
+            TuiAction::NavigateToPath { path, url: _ } => {
+                // Return NavigateToPath command - caller will send it and wait for response
+                Some(UiCommand::NavigateToPath(path))
+            }
+            TuiAction::OpenUrl(url) => {
+                // Open external URL in browser
+                if let Err(e) = webbrowser::open(&url) {
+                    eprintln!("[ERROR] Failed to open URL {}: {}", url, e);
+                }
+                None // No command needed
+            }
+            TuiAction::RunExample { doc_ref, index } => {
+                // The request thread writes the project and the example thread runs cargo
+                Some(UiCommand::RunExample { doc_ref, index })
+            }
+            TuiAction::Search { query, crate_name } => Some(UiCommand::Search {
+                query,
+                crate_name,
+                limit: 20,
+                mode: SearchMode::Terms,
+                context: None,
+            }),
+            TuiAction::ApiDiff { crate_name, from } => {
+                Some(UiCommand::ApiDiff { crate_name, from })
+            }
+            TuiAction::SelectTheme(_) => {
+                // SelectTheme is handled specially in mouse.rs handle_click()
+                // It should never reach this function, but we need the match to be exhaustive
+                None
             }
-            None // No command needed, just mutated in place
         }
-        TuiAction::Navigate { doc_ref, url: _ } => {
-            // Return Navigate command - caller will send it and wait for response
-            Some(UiCommand::Navigate(doc_ref))
+    }
+
+    /// Show all of the truncated block at `path`, remembering that it's expanded so that it
+    /// is again when this page is shown again
+    pub(super) fn expand_block(&mut self, path: NodePath) {
+        if let Some(DocumentNode::TruncatedBlock { level, .. }) =
+            find_node_at_path_mut(&mut self.document.document.nodes, path.indices())
+            && *level != TruncationLevel::Full
+        {
+            if let Some(entry) = self.document.history.current() {
+                self.expanded_blocks.insert((entry.clone(), path), *level);
+            }
+            *level = TruncationLevel::Full;
         }
-        TuiAction::NavigateToPath { path, url: _ } => {
-            // Return NavigateToPath command - caller will send it and wait for response
-            Some(UiCommand::NavigateToPath(path))
+    }
+
+    /// Expand the truncated block under the cursor, or truncate it again if it was expanded
+    ///
+    /// The cursor is the focused link, or the mouse, or else the top of the screen.
+    pub(super) fn toggle_block_under_cursor(&mut self) {
+        let row = self
+            .current_element()
+            .map_or(self.viewport.scroll_offset, |rows| rows.start);
+
+        let entry = self.document.history.current().cloned();
+        let expanded = |path: NodePath| {
+            entry
+                .as_ref()
+                .is_some_and(|entry| self.expanded_blocks.contains_key(&(entry.clone(), path)))
+        };
+        // The innermost block around the row that can be expanded or truncated again
+        let Some(&(_, path, level)) = self
+            .render_cache
+            .truncated_blocks
+            .iter()
+            .filter(|(rows, path, level)| {
+                rows.contains(&row) && (*level != TruncationLevel::Full || expanded(*path))
+            })
+            .min_by_key(|(rows, ..)| rows.len())
+        else {
+            self.notify("No collapsed section here");
+            return;
+        };
+
+        if level != TruncationLevel::Full {
+            self.expand_block(path);
+        } else if let Some(entry) = entry
+            && let Some(previous) = self.expanded_blocks.remove(&(entry, path))
+            && let Some(DocumentNode::TruncatedBlock { level, .. }) =
+                find_node_at_path_mut(&mut self.document.document.nodes, path.indices())
+        {
+            *level = previous;
         }
-        TuiAction::OpenUrl(url) => {
-            // Open external URL in browser
-            if let Err(e) = webbrowser::open(&url) {
-                eprintln!("[ERROR] Failed to open URL {}: {}", url, e);
+        self.viewport.cached_layout = None;
+    }
+
+    /// Expand the blocks on the current page that were expanded when it was last shown
+    pub(super) fn restore_expanded_blocks(&mut self) {
+        let Some(entry) = self.document.history.current() else {
+            return;
+        };
+        for (expanded_entry, path) in self.expanded_blocks.keys() {
+            if expanded_entry == entry
+                && let Some(DocumentNode::TruncatedBlock { level, .. }) =
+                    find_node_at_path_mut(&mut self.document.document.nodes, path.indices())
+            {
+                *level = TruncationLevel::Full;
             }
-            None // No command needed
-        }
-        TuiAction::SelectTheme(_) => {
-            // SelectTheme is handled specially in mouse.rs handle_click()
-            // It should never reach this function, but we need the match to be exhaustive
-            None
         }
     }
 }
@@ -5,13 +5,15 @@ use rustdoc_types::Item;
 
 use super::channels::UiCommand;
 use super::render_document::BASELINE_LEFT_MARGIN;
+use super::render_frame::NARROW_WIDTH;
 use super::theme::InteractiveTheme;
+use crate::commands::search::SearchMode;
 use std::borrow::Cow;
 use std::fmt::{self, Display, Formatter};
 use std::ops::Range;
 
 /// Entry in navigation history
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum HistoryEntry<'a> {
     /// Regular item navigation
     Item(DocRef<'a, Item>),
@@ -19,19 +21,25 @@ pub enum HistoryEntry<'a> {
     Search {
         query: String,
         crate_name: Option<String>,
+        /// How the query is matched, so going back to the page runs the same search
+        mode: SearchMode,
     },
     /// List crates page
     List {
         /// The default crate (if any) - used for scoped search
         default_crate: Option<&'a str>,
     },
+    /// Start page
+    Home,
 }
 
 impl Display for HistoryEntry<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             HistoryEntry::Item(item) => f.write_str(item.name().unwrap_or("<unnamed>")),
-            HistoryEntry::Search { query, crate_name } => {
+            HistoryEntry::Search {
+                query, crate_name, ..
+            } => {
                 if query.is_empty() {
                     // Empty query - show "Search in crate_name" or just "Search"
                     if let Some(crate_name) = crate_name {
@@ -49,6 +57,7 @@ impl Display for HistoryEntry<'_> {
                 }
             }
             HistoryEntry::List { .. } => f.write_str("List"),
+            HistoryEntry::Home => f.write_str("Home"),
         }
     }
 }
@@ -73,6 +82,7 @@ impl<'a> HistoryEntry<'a> {
             HistoryEntry::Item(item) => Some(item.crate_docs().name()),
             HistoryEntry::Search { crate_name, .. } => crate_name.as_deref(),
             HistoryEntry::List { default_crate } => default_crate.as_deref(),
+            HistoryEntry::Home => None,
         }
     }
 
@@ -80,12 +90,19 @@ impl<'a> HistoryEntry<'a> {
     pub(super) fn to_command(&self) -> UiCommand<'a> {
         match self {
             HistoryEntry::Item(item) => UiCommand::Navigate(*item),
-            HistoryEntry::Search { query, crate_name } => UiCommand::Search {
+            HistoryEntry::Search {
+                query,
+                crate_name,
+                mode,
+            } => UiCommand::Search {
                 query: Cow::Owned(query.clone()),
                 crate_name: crate_name.as_ref().map(|c| Cow::Owned(c.clone())),
                 limit: 20,
+                mode: *mode,
+                context: None,
             },
             HistoryEntry::List { .. } => UiCommand::List,
+            HistoryEntry::Home => UiCommand::Home,
         }
     }
 }
@@ -159,12 +176,19 @@ impl<'a> History<'a> {
     }
 
     /// Render the breadcrumb bar
-    pub(super) fn render(&mut self, buf: &mut Buffer, area: Rect, theme: &InteractiveTheme) {
+    pub(super) fn render(
+        &mut self,
+        buf: &mut Buffer,
+        area: Rect,
+        theme: &InteractiveTheme,
+        decorations: bool,
+    ) {
         self.clickable_areas.clear();
         let history: &[HistoryEntry<'a>] = &self.entries;
         let current_idx = self.current_index;
         let clickable_areas: &mut Vec<(usize, std::ops::Range<u16>)> = &mut self.clickable_areas;
-        let hover_pos = self.hover_pos;
+        let hover_pos = self.hover_pos.filter(|_| decorations);
+        let crab = if decorations { " 🦀  " } else { " " };
         let bg_style = theme.breadcrumb_style;
 
         // Clear the breadcrumb line
@@ -174,7 +198,7 @@ impl<'a> History<'a> {
         }
 
         if history.is_empty() {
-            let text = " 🦀  <no history>";
+            let text = format!("{crab}<no history>");
             let mut col = BASELINE_LEFT_MARGIN;
             for ch in text.chars() {
                 if col >= area.width {
@@ -189,11 +213,18 @@ impl<'a> History<'a> {
             return;
         }
 
-        // Build breadcrumb trail: a → b → c with current item italicized
-        let mut col = BASELINE_LEFT_MARGIN;
+        // Build breadcrumb trail: a → b → c with current item italicized. Narrow terminals
+        // only have room for the current item.
+        let narrow = area.width < NARROW_WIDTH;
+        let first_idx = if narrow { current_idx } else { 0 };
+        let mut col = if narrow { 1 } else { BASELINE_LEFT_MARGIN };
 
-        // Start with icon
-        let icon = " 🦀  ";
+        // Start with icon, or an ellipsis for the items left out
+        let icon = match (narrow, first_idx) {
+            (false, _) => crab,
+            (true, 0) => "",
+            (true, _) => "… → ",
+        };
         for ch in icon.chars() {
             if col >= area.width {
                 break;
@@ -205,13 +236,13 @@ impl<'a> History<'a> {
             col += 1;
         }
 
-        for (idx, item) in history.iter().enumerate() {
+        for (idx, item) in history.iter().enumerate().skip(first_idx) {
             if col >= area.width {
                 break;
             }
 
             // Add arrow separator (except for first item)
-            if idx > 0 {
+            if idx > first_idx {
                 let arrow = " → ";
                 for ch in arrow.chars() {
                     if col >= area.width {
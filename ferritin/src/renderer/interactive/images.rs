@@ -0,0 +1,419 @@
+//! Images in docs, drawn inline with a terminal graphics protocol
+//!
+//! Kitty's and iTerm2's protocols (also spoken by Ghostty and WezTerm) take an image file as
+//! is, so no decoding is needed: Kitty's takes PNGs, iTerm2's PNGs, GIFs and JPEGs. Anything
+//! else, like the SVGs most badges are, and terminals that only speak Sixel, which needs images
+//! decoded into pixels, get a link to the image instead.
+//!
+//! ratatui doesn't know about images, so they're drawn after each frame at the places the
+//! document renderer set aside for them.
+
+use crossbeam_channel::Sender;
+use ratatui::layout::Rect;
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, Write};
+
+/// The most rows an image takes up, so that a tall diagram doesn't fill the screen
+const MAX_IMAGE_ROWS: u16 = 24;
+
+/// Kitty's protocol takes base64 data in chunks of at most this many bytes
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// A terminal graphics protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum GraphicsProtocol {
+    Kitty,
+    Iterm2,
+}
+
+impl GraphicsProtocol {
+    /// The protocol the terminal speaks, going by the environment variables terminals set
+    ///
+    /// Inside tmux or screen, graphics would have to be passed through to the outer terminal,
+    /// so images are left as links there.
+    pub(super) fn detect() -> Option<Self> {
+        let var = |name| env::var(name).unwrap_or_default();
+        if env::var_os("TMUX").is_some() || var("TERM").starts_with("screen") {
+            return None;
+        }
+
+        if env::var_os("KITTY_WINDOW_ID").is_some()
+            || var("TERM").contains("kitty")
+            || var("TERM_PROGRAM") == "ghostty"
+        {
+            Some(Self::Kitty)
+        } else if matches!(&*var("TERM_PROGRAM"), "iTerm.app" | "WezTerm")
+            || var("LC_TERMINAL") == "iTerm2"
+        {
+            Some(Self::Iterm2)
+        } else {
+            None
+        }
+    }
+
+    fn supports(self, format: ImageFormat) -> bool {
+        match self {
+            Self::Kitty => format == ImageFormat::Png,
+            Self::Iterm2 => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageFormat {
+    Png,
+    Gif,
+    Jpeg,
+}
+
+/// A downloaded image the terminal can draw
+#[derive(Debug)]
+struct Image {
+    /// The id Kitty's protocol refers to the image by
+    id: u32,
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    /// Whether the image's data has been sent to the terminal, for Kitty's protocol
+    transmitted: bool,
+    /// The image's data in base64, for iTerm2's protocol, which sends it with every draw
+    encoded: Option<String>,
+}
+
+#[derive(Debug)]
+enum ImageStatus {
+    Loading,
+    Ready(Image),
+    /// Failed to download, or in a format the terminal can't draw
+    Unavailable,
+}
+
+/// Where an image goes on screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Placement {
+    url: String,
+    area: Rect,
+}
+
+/// The images of the documents being shown, and where they're drawn
+#[derive(Debug, Default)]
+pub(super) struct Images {
+    protocol: Option<GraphicsProtocol>,
+    /// Where URLs of images to download are sent, for the image thread
+    pub fetch_tx: Option<Sender<String>>,
+    images: HashMap<String, ImageStatus>,
+    next_id: u32,
+    /// The images placed by the frame being rendered, in document area coordinates
+    placements: Vec<Placement>,
+    /// The images on screen, in screen coordinates
+    shown: Vec<Placement>,
+    /// Whether the document is covered, by the help screen or a pane, so that no images are
+    /// drawn
+    hidden: bool,
+}
+
+impl Images {
+    pub(super) fn new(protocol: Option<GraphicsProtocol>) -> Self {
+        Self {
+            protocol,
+            ..Self::default()
+        }
+    }
+
+    /// How many columns and rows the image at `url` takes up, at most `max_cols` wide, or None
+    /// when it has to be shown as a link
+    ///
+    /// The image is downloaded the first time it's asked for, and is a link until it arrives.
+    pub(super) fn size(&mut self, url: &str, max_cols: u16) -> Option<(u16, u16)> {
+        let fetch_tx = self.fetch_tx.as_ref()?;
+        self.protocol?;
+        let status = self.images.entry(url.to_string()).or_insert_with(|| {
+            match fetch_tx.send(url.to_string()) {
+                Ok(()) => ImageStatus::Loading,
+                Err(_) => ImageStatus::Unavailable,
+            }
+        });
+        let ImageStatus::Ready(image) = status else {
+            return None;
+        };
+
+        let (cell_width, cell_height) = cell_pixels();
+        let (width, height) = (f64::from(image.width), f64::from(image.height));
+        let scale = (f64::from(max_cols) * cell_width / width)
+            .min(f64::from(MAX_IMAGE_ROWS) * cell_height / height)
+            .min(1.0);
+        let cols = (width * scale / cell_width).ceil().max(1.0) as u16;
+        let rows = (height * scale / cell_height).ceil().max(1.0) as u16;
+        Some((cols.min(max_cols.max(1)), rows.min(MAX_IMAGE_ROWS)))
+    }
+
+    /// Record a downloaded image, returning whether it can be drawn, which changes the
+    /// document's layout
+    pub(super) fn loaded(&mut self, url: String, data: Option<Vec<u8>>) -> bool {
+        let image = data.and_then(|data| {
+            let (format, width, height) = sniff(&data)?;
+            let usable = self.protocol?.supports(format) && width > 0 && height > 0;
+            usable.then(|| {
+                self.next_id += 1;
+                Image {
+                    id: self.next_id,
+                    data,
+                    width,
+                    height,
+                    transmitted: false,
+                    encoded: None,
+                }
+            })
+        });
+
+        let ready = image.is_some();
+        self.images.insert(
+            url,
+            image.map_or(ImageStatus::Unavailable, ImageStatus::Ready),
+        );
+        ready
+    }
+
+    /// Forget the images placed by the last frame, before rendering the next one
+    pub(super) fn clear_placements(&mut self) {
+        self.placements.clear();
+    }
+
+    /// Draw the image at `url` over `area` of the document area once the frame is rendered
+    pub(super) fn place(&mut self, url: &str, area: Rect) {
+        self.placements.push(Placement {
+            url: url.to_string(),
+            area,
+        });
+    }
+
+    pub(super) fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden;
+    }
+
+    /// Whether the images on screen have to be erased by redrawing the whole screen before the
+    /// frame's images can be drawn
+    ///
+    /// iTerm2's protocol draws images into the cells under them, which ratatui doesn't
+    /// redraw unless their text changed, so moved images would leave copies behind.
+    pub(super) fn needs_clear(&self, top: u16) -> bool {
+        self.protocol == Some(GraphicsProtocol::Iterm2)
+            && !self.shown.is_empty()
+            && self.shown != self.on_screen(top)
+    }
+
+    /// Draw the images placed by the frame just rendered, with the document area starting at
+    /// screen row `top`, unless they're already on screen
+    pub(super) fn draw(&mut self, out: &mut impl Write, top: u16) -> io::Result<()> {
+        let Some(protocol) = self.protocol else {
+            return Ok(());
+        };
+        let placements = self.on_screen(top);
+        if placements == self.shown {
+            return Ok(());
+        }
+
+        // Save the cursor, which ratatui keeps track of, to be restored afterwards
+        write!(out, "\x1b7")?;
+        if protocol == GraphicsProtocol::Kitty {
+            // Kitty's images are a layer over the text, so the old ones are taken off it
+            write!(out, "\x1b_Ga=d,d=a,q=2\x1b\\")?;
+        }
+        for placement in &placements {
+            let Some(ImageStatus::Ready(image)) = self.images.get_mut(&placement.url) else {
+                continue;
+            };
+            let area = placement.area;
+            write!(out, "\x1b[{};{}H", area.y + 1, area.x + 1)?;
+            match protocol {
+                GraphicsProtocol::Kitty => {
+                    if !image.transmitted {
+                        transmit_kitty(out, image)?;
+                        image.transmitted = true;
+                    }
+                    write!(
+                        out,
+                        "\x1b_Ga=p,i={},c={},r={},C=1,q=2\x1b\\",
+                        image.id, area.width, area.height
+                    )?;
+                }
+                GraphicsProtocol::Iterm2 => {
+                    let size = image.data.len();
+                    let encoded = image.encoded.get_or_insert_with(|| base64(&image.data));
+                    write!(
+                        out,
+                        "\x1b]1337;File=inline=1;size={size};width={};height={};\
+                         preserveAspectRatio=1:{encoded}\x07",
+                        area.width, area.height,
+                    )?;
+                }
+            }
+        }
+        write!(out, "\x1b8")?;
+        out.flush()?;
+
+        self.shown = placements;
+        Ok(())
+    }
+
+    /// Draw the frame's images again even if they were already on screen, after something
+    /// else cleared it
+    pub(super) fn redraw(&mut self) {
+        self.shown.clear();
+    }
+
+    /// Take any images off the screen, when leaving it
+    pub(super) fn erase(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.protocol == Some(GraphicsProtocol::Kitty) && !self.shown.is_empty() {
+            write!(out, "\x1b_Ga=d,d=a,q=2\x1b\\")?;
+            out.flush()?;
+        }
+        self.shown.clear();
+        Ok(())
+    }
+
+    fn on_screen(&self, top: u16) -> Vec<Placement> {
+        if self.hidden {
+            return vec![];
+        }
+        self.placements
+            .iter()
+            .map(|placement| Placement {
+                url: placement.url.clone(),
+                area: Rect {
+                    y: placement.area.y + top,
+                    ..placement.area
+                },
+            })
+            .collect()
+    }
+}
+
+/// Send an image's data to Kitty, to be placed by its id
+fn transmit_kitty(out: &mut impl Write, image: &Image) -> io::Result<()> {
+    let data = base64(&image.data);
+    let chunks = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect::<Vec<_>>();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        if index == 0 {
+            write!(out, "\x1b_Ga=t,f=100,i={},q=2,m={more};", image.id)?;
+        } else {
+            write!(out, "\x1b_Gm={more};")?;
+        }
+        out.write_all(chunk)?;
+        write!(out, "\x1b\\")?;
+    }
+    Ok(())
+}
+
+/// The size of a character cell in pixels, falling back to a common size when the terminal
+/// doesn't say
+fn cell_pixels() -> (f64, f64) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            f64::from(size.width) / f64::from(size.columns),
+            f64::from(size.height) / f64::from(size.rows),
+        ),
+        _ => (8.0, 16.0),
+    }
+}
+
+/// The format and size in pixels of an image file, read from its header
+fn sniff(data: &[u8]) -> Option<(ImageFormat, u32, u32)> {
+    let be16 = |at: usize| {
+        Some(u32::from(u16::from_be_bytes(
+            data.get(at..at + 2)?.try_into().ok()?,
+        )))
+    };
+    let le16 = |at: usize| {
+        Some(u32::from(u16::from_le_bytes(
+            data.get(at..at + 2)?.try_into().ok()?,
+        )))
+    };
+    let be32 = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((ImageFormat::Png, be32(16)?, be32(20)?));
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some((ImageFormat::Gif, le16(6)?, le16(8)?));
+    }
+    if !data.starts_with(&[0xff, 0xd8]) {
+        return None;
+    }
+
+    // A JPEG's size is in its start-of-frame segment, after any number of others
+    let mut at = 2;
+    loop {
+        if *data.get(at)? != 0xff {
+            return None;
+        }
+        let marker = *data.get(at + 1)?;
+        match marker {
+            // Fill bytes before a marker
+            0xff => at += 1,
+            0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                return Some((ImageFormat::Jpeg, be16(at + 7)?, be16(at + 5)?));
+            }
+            _ => at += 2 + be16(at + 2)? as usize,
+        }
+    }
+}
+
+/// Standard base64 with padding, which both protocols take image data in, as does OSC 52
+pub(super) fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(char::from(
+                    ALPHABET[((triple >> (18 - 6 * index)) & 0x3f) as usize],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_headers_give_format_and_size() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend(640u32.to_be_bytes());
+        png.extend(480u32.to_be_bytes());
+        assert_eq!(sniff(&png), Some((ImageFormat::Png, 640, 480)));
+
+        let gif = b"GIF89a\x20\x00\x10\x00";
+        assert_eq!(sniff(gif), Some((ImageFormat::Gif, 32, 16)));
+
+        // An APP0 segment, then a start-of-frame segment for a 100x50 image
+        let jpeg = [
+            0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 0x00, 0x00, 0xff, 0xc0, 0x00, 0x11, 0x08, 0x00,
+            0x32, 0x00, 0x64,
+        ];
+        assert_eq!(sniff(&jpeg), Some((ImageFormat::Jpeg, 100, 50)));
+
+        assert_eq!(sniff(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"), None);
+    }
+
+    #[test]
+    fn base64_pads_to_a_multiple_of_four() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(base64(b""), "");
+    }
+}
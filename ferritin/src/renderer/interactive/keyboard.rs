@@ -4,10 +4,15 @@ use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent, KeyModifiers},
     execute,
 };
-use ratatui::{Terminal, prelude::Backend};
-
-use super::{InputMode, InteractiveState, UiMode, channels::UiCommand};
-use crate::render_context::RenderContext;
+use ratatui::{Terminal, layout::Rect, prelude::Backend};
+
+use super::{InputMode, InteractiveState, UiMode, channels::UiCommand, history::HistoryEntry};
+use crate::{
+    commands::search::SearchMode,
+    config,
+    key_bindings::{Action, KeyMatch, key_of},
+    render_context::RenderContext,
+};
 
 impl<'a> InteractiveState<'a> {
     pub(crate) fn handle_key_event(
@@ -36,6 +41,12 @@ impl<'a> InteractiveState<'a> {
                     self.ui.debug_message =
                         "ferritin - q:quit ?:help ←/→:history g:go s:search l:list c:code".into();
                 }
+                UiMode::ExampleOutput { .. }
+                | UiMode::Messages { .. }
+                | UiMode::LinkHints { .. }
+                | UiMode::CratePicker { .. } => {
+                    // Already set to Normal by replace
+                }
                 UiMode::ThemePicker {
                     saved_theme_name, ..
                 } => {
@@ -44,6 +55,15 @@ impl<'a> InteractiveState<'a> {
                     self.ui.debug_message =
                         "ferritin - q:quit ?:help ←/→:history g:go s:search l:list c:code".into();
                 }
+                UiMode::Normal if !self.ui.pending_keys.is_empty() => {
+                    // Cancel the key sequence being typed
+                    self.ui.pending_keys.clear();
+                }
+                UiMode::Normal if self.loading.pending_request => {
+                    // Stop waiting for the page being loaded, and any download for it
+                    self.loading.cancel();
+                    self.notify("Cancelled loading");
+                }
                 UiMode::Normal => {
                     return true;
                 }
@@ -51,20 +71,51 @@ impl<'a> InteractiveState<'a> {
         } else if matches!(self.ui_mode, UiMode::Help) {
             // Any key (except Escape, handled above) exits help
             self.ui_mode = UiMode::Normal;
+        } else if let UiMode::LinkHints { typed } = &mut self.ui_mode {
+            match key.code {
+                KeyCode::Char(c) => self.handle_link_hint_key(c),
+                KeyCode::Backspace => {
+                    typed.pop();
+                }
+                // Any other key leaves hint mode
+                _ => self.ui_mode = UiMode::Normal,
+            }
+        } else if matches!(self.ui_mode, UiMode::CratePicker { .. }) {
+            self.handle_crate_picker_key(key);
         } else if let UiMode::Input(ref mut input_mode) = self.ui_mode {
             match key.code {
                 KeyCode::Char(c) => match input_mode {
-                    InputMode::GoTo { buffer } => buffer.push(c),
-                    InputMode::Search { buffer, .. } => buffer.push(c),
+                    InputMode::GoTo { buffer }
+                    | InputMode::Search { buffer, .. }
+                    | InputMode::SaveSearch { buffer }
+                    | InputMode::Command { buffer } => buffer.push(c),
                 },
                 KeyCode::Backspace => match input_mode {
-                    InputMode::GoTo { buffer } => {
-                        buffer.pop();
-                    }
-                    InputMode::Search { buffer, .. } => {
+                    InputMode::GoTo { buffer }
+                    | InputMode::Search { buffer, .. }
+                    | InputMode::SaveSearch { buffer }
+                    | InputMode::Command { buffer } => {
                         buffer.pop();
                     }
                 },
+                KeyCode::Up | KeyCode::Down => {
+                    // Recall past queries, most recent first
+                    if let InputMode::Search { buffer, .. } = input_mode {
+                        let history = &self.ui.search_history;
+                        let cursor = match (key.code, self.ui.search_history_cursor) {
+                            (KeyCode::Up, None) => history.len().checked_sub(1),
+                            (KeyCode::Up, Some(index)) => Some(index.saturating_sub(1)),
+                            (_, Some(index)) if index + 1 < history.len() => Some(index + 1),
+                            _ => None,
+                        };
+
+                        self.ui.search_history_cursor = cursor;
+                        *buffer = cursor
+                            .and_then(|index| history.get(index))
+                            .cloned()
+                            .unwrap_or_default();
+                    }
+                }
                 KeyCode::Tab => {
                     // Toggle search scope (only in Search mode and only if there's a crate to scope to)
                     if let InputMode::Search { all_crates, .. } = input_mode {
@@ -81,6 +132,9 @@ impl<'a> InteractiveState<'a> {
                     }
                 }
                 KeyCode::Enter => {
+                    let mut notification = None;
+                    let mut error = None;
+                    let mut next_mode = UiMode::Normal;
                     // Execute the command based on current input mode
                     let command = match input_mode {
                         InputMode::GoTo { buffer } => {
@@ -99,19 +153,77 @@ impl<'a> InteractiveState<'a> {
                                     .map(|s| Cow::Owned(s.into()))
                             };
 
+                            self.ui.search_history_cursor = None;
                             self.ui.debug_message = format!("Searching: {buffer}...").into();
                             Some(UiCommand::Search {
                                 query: Cow::Owned(buffer.clone()),
                                 crate_name: search_crate,
                                 limit: 20,
+                                mode: SearchMode::Terms,
+                                context: self
+                                    .document
+                                    .history
+                                    .current()
+                                    .and_then(|entry| entry.item()),
                             })
                         }
+                        InputMode::SaveSearch { buffer } => {
+                            if let Some(HistoryEntry::Search {
+                                query, crate_name, ..
+                            }) = self.document.history.current()
+                                && !buffer.trim().is_empty()
+                            {
+                                notification = Some(format!("Saved search: {}", buffer.trim()));
+                                self.send_command(UiCommand::SaveSearch {
+                                    name: buffer.trim().to_string(),
+                                    query: query.clone(),
+                                    crate_name: crate_name.clone(),
+                                });
+                            }
+                            None
+                        }
+                        InputMode::Command { buffer } => {
+                            match buffer.trim() {
+                                "messages" => next_mode = UiMode::Messages { scroll: 0 },
+                                "" => {}
+                                command => error = Some(format!("Unknown command: :{command}")),
+                            }
+                            None
+                        }
                     };
 
                     if let Some(cmd) = command {
-                        let _ = self.cmd_tx.send(cmd);
+                        self.send_command(cmd);
                         self.loading.start();
                     }
+                    self.ui_mode = next_mode;
+                    if let Some(notification) = notification {
+                        self.notify(notification);
+                    }
+                    if let Some(error) = error {
+                        self.notify_error(error);
+                    }
+                }
+                _ => {}
+            }
+        } else if let UiMode::ExampleOutput { ref mut scroll, .. }
+        | UiMode::Messages { ref mut scroll } = self.ui_mode
+        {
+            // Example output and message history pane keybindings
+            match self.render_context.key_bindings().action(key) {
+                Some(Action::Up) => {
+                    *scroll = scroll.saturating_sub(1);
+                }
+                Some(Action::Down) => {
+                    *scroll = scroll.saturating_add(1);
+                }
+                Some(Action::PageUp) => {
+                    *scroll = scroll.saturating_sub(10);
+                }
+                Some(Action::PageDown) => {
+                    *scroll = scroll.saturating_add(10);
+                }
+                Some(Action::Open | Action::Quit) => {
                     self.ui_mode = UiMode::Normal;
                 }
                 _ => {}
@@ -125,8 +237,8 @@ impl<'a> InteractiveState<'a> {
             let themes = RenderContext::available_themes();
             let theme_count = themes.len();
 
-            match key.code {
-                KeyCode::Up | KeyCode::Char('k') => {
+            match self.render_context.key_bindings().action(key) {
+                Some(Action::Up) => {
                     // Move selection up
                     if *selected_index > 0 {
                         *selected_index -= 1;
@@ -136,7 +248,7 @@ impl<'a> InteractiveState<'a> {
                         }
                     }
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
+                Some(Action::Down) => {
                     // Move selection down
                     if *selected_index + 1 < theme_count {
                         *selected_index += 1;
@@ -146,48 +258,107 @@ impl<'a> InteractiveState<'a> {
                         }
                     }
                 }
-                KeyCode::Enter => {
-                    // Save current theme and exit
+                Some(Action::Open) => {
+                    // Save current theme, for this session and the next ones, and exit
                     let theme_name = self
                         .current_theme_name
                         .clone()
                         .unwrap_or_else(|| "default".into());
                     self.ui_mode = UiMode::Normal;
-                    self.ui.debug_message = format!("Theme saved: {theme_name}").into();
+                    match config::save_theme(&theme_name) {
+                        Ok(()) => self.notify_success(format!("Theme saved: {theme_name}")),
+                        Err(error) => {
+                            self.notify_error(format!("Could not save the theme: {error}"))
+                        }
+                    }
                 }
                 _ => {}
             }
         } else {
-            // Normal mode keybindings
-            match (key.code, key.modifiers) {
+            // Normal mode keybindings, from the keymap, which can be sequences like `g t`
+            let pending = std::mem::take(&mut self.ui.pending_keys);
+            let mut replay = None;
+            let action = match self.render_context.key_bindings().lookup(&pending, key) {
+                KeyMatch::Action(action) => action,
+                KeyMatch::Prefix => {
+                    self.ui.pending_keys = pending;
+                    self.ui.pending_keys.push(key_of(key));
+                    return false;
+                }
+                KeyMatch::Unbound if pending.is_empty() => {
+                    self.handle_unbound_key(key);
+                    return false;
+                }
+                // A key that doesn't go on with the sequence ends it. Keys that are bound by
+                // themselves, like `g`, then do their own action, and the key is handled after
+                // it, so that `g` followed by a path types the path into the go-to prompt.
+                KeyMatch::Unbound => {
+                    let Some(action) = self.render_context.key_bindings().bound(&pending) else {
+                        return false;
+                    };
+                    replay = Some(key);
+                    action
+                }
+            };
+            match action {
                 // Quit
-                (KeyCode::Char('q'), _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                Action::Quit => {
                     return true;
                 }
 
                 // Navigate down / scroll down
-                (KeyCode::Char('j'), _)
-                | (KeyCode::Down, _)
-                | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+                Action::Down => {
+                    let focus = self.viewport.keyboard_cursor;
                     self.handle_navigate_down();
+                    self.keep_context_around_focus(focus);
                 }
 
                 // Navigate up / scroll up
-                (KeyCode::Char('k'), _)
-                | (KeyCode::Up, _)
-                | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                Action::Up => {
+                    let focus = self.viewport.keyboard_cursor;
                     self.handle_navigate_up();
+                    self.keep_context_around_focus(focus);
+                }
+
+                // Scroll a line, leaving the links alone
+                Action::ScrollDown => {
+                    self.handle_scroll_line(true);
+                }
+                Action::ScrollUp => {
+                    self.handle_scroll_line(false);
+                }
+
+                // Scroll the focused link to the middle, top or bottom of the screen
+                Action::Recenter | Action::RecenterTop | Action::RecenterBottom => {
+                    self.handle_recenter(action);
                 }
 
                 // Activate focused link
-                (KeyCode::Enter, _) | (KeyCode::Char(' '), _) => {
+                Action::Open => {
                     self.handle_activate_focused_link();
                 }
 
+                // Move focus to the next or previous link, wherever it is
+                Action::NextLink => {
+                    self.handle_cycle_link(true);
+                }
+                Action::PreviousLink => {
+                    self.handle_cycle_link(false);
+                }
+
+                // Label the links on screen to follow one by typing its label
+                Action::Hints => {
+                    if self.link_hints().is_empty() {
+                        self.notify("No links on screen");
+                    } else {
+                        self.ui_mode = UiMode::LinkHints {
+                            typed: String::new(),
+                        };
+                    }
+                }
+
                 // Page down
-                (KeyCode::Char('d'), KeyModifiers::CONTROL)
-                | (KeyCode::Char('v'), KeyModifiers::CONTROL)
-                | (KeyCode::PageDown, _) => {
+                Action::PageDown => {
                     let Ok(size) = terminal.size() else {
                         return false;
                     };
@@ -196,9 +367,7 @@ impl<'a> InteractiveState<'a> {
                 }
 
                 // Page up
-                (KeyCode::Char('u'), KeyModifiers::CONTROL)
-                | (KeyCode::Char('v'), KeyModifiers::ALT)
-                | (KeyCode::PageUp, _) => {
+                Action::PageUp => {
                     let Ok(size) = terminal.size() else {
                         return false;
                     };
@@ -206,67 +375,36 @@ impl<'a> InteractiveState<'a> {
                     self.set_scroll_offset(self.viewport.scroll_offset.saturating_sub(page_size));
                 }
 
-                // Dump logs to disk (undocumented debug feature)
-                (KeyCode::Char('l'), KeyModifiers::ALT) => match self.dump_logs_to_disk() {
-                    Ok(filename) => {
-                        self.ui.debug_message = format!("Logs saved to {}", filename).into();
-                    }
-                    Err(e) => {
-                        self.ui.debug_message = format!("Failed to save logs: {}", e).into();
-                    }
-                },
-
-                // Toggle dev log (undocumented debug feature)
-                (KeyCode::Char('l'), KeyModifiers::CONTROL) => {
-                    match std::mem::replace(&mut self.ui_mode, UiMode::Normal) {
-                        UiMode::DevLog {
-                            previous_document,
-                            previous_scroll,
-                        } => {
-                            // Exiting dev log - restore previous state
-                            self.document.document = previous_document;
-                            self.set_scroll_offset(previous_scroll);
-                        }
-                        UiMode::Normal => {
-                            // Entering dev log - swap in dev log document
-                            let dev_log_doc = self.create_dev_log_document();
-                            let previous_document =
-                                std::mem::replace(&mut self.document.document, dev_log_doc);
-                            let previous_scroll = self.viewport.scroll_offset;
-                            self.set_scroll_offset(0);
-                            self.ui_mode = UiMode::DevLog {
-                                previous_document,
-                                previous_scroll,
-                            };
-                        }
-                        other => {
-                            // Was in a different mode, restore it
-                            self.ui_mode = other;
-                        }
-                    }
-                }
-
                 // Jump to top
-                (KeyCode::Home, _) | (KeyCode::Char('<'), KeyModifiers::ALT) => {
+                Action::Top => {
                     self.set_scroll_offset(0);
                 }
 
                 // Jump to bottom (will clamp to actual max)
-                (KeyCode::Char('G'), KeyModifiers::SHIFT)
-                | (KeyCode::End, _)
-                | (KeyCode::Char('>'), KeyModifiers::ALT) => {
+                Action::Bottom => {
                     self.set_scroll_offset(u16::MAX); // Large number, will clamp to actual max
                 }
 
                 // Enter GoTo mode
-                (KeyCode::Char('g'), _) => {
+                Action::GoTo => {
                     self.ui_mode = UiMode::Input(InputMode::GoTo {
                         buffer: String::new(),
                     });
                 }
 
+                // Name the current search so it's listed on the start page
+                Action::SaveSearch => {
+                    if let Some(HistoryEntry::Search { .. }) = self.document.history.current() {
+                        self.ui_mode = UiMode::Input(InputMode::SaveSearch {
+                            buffer: String::new(),
+                        });
+                    } else {
+                        self.notify_error("Only search results can be saved");
+                    }
+                }
+
                 // Enter Search mode
-                (KeyCode::Char('s'), _) | (KeyCode::Char('/'), _) => {
+                Action::Search => {
                     // Default to current crate only if there is one
                     let has_crate = self
                         .document
@@ -275,50 +413,100 @@ impl<'a> InteractiveState<'a> {
                         .and_then(|entry| entry.crate_name())
                         .is_some();
 
+                    self.ui.search_history_cursor = None;
                     self.ui_mode = UiMode::Input(InputMode::Search {
                         buffer: String::new(),
                         all_crates: !has_crate, // Search all crates if no current crate
                     });
                 }
 
+                // Show the history of notifications
+                Action::Messages => {
+                    self.ui_mode = UiMode::Messages { scroll: 0 };
+                }
+
+                // Enter command mode, for commands like `:messages`
+                Action::Command => {
+                    self.ui_mode = UiMode::Input(InputMode::Command {
+                        buffer: String::new(),
+                    });
+                }
+
+                // Show the start page
+                Action::Home => {
+                    self.send_command(UiCommand::Home);
+                    self.loading.start();
+                }
+
                 // Show list of crates
-                (KeyCode::Char('l'), _) => {
+                Action::List => {
                     // Send List command to request thread (non-blocking)
-                    let _ = self.cmd_tx.send(UiCommand::List);
+                    self.send_command(UiCommand::List);
                     self.loading.start();
                     self.ui.debug_message = "Loading crate list...".into();
                 }
 
+                // Pick a crate to open from a filterable list
+                Action::Crates => {
+                    self.ui_mode = UiMode::CratePicker {
+                        filter: String::new(),
+                        selected_index: 0,
+                    };
+                }
+
                 // Toggle mouse mode for text selection
-                (KeyCode::Char('m'), _) => {
+                Action::Mouse => {
                     self.ui.mouse_enabled = !self.ui.mouse_enabled;
                     if self.ui.mouse_enabled {
                         let _ = execute!(terminal.backend_mut(), EnableMouseCapture);
-                        self.ui.debug_message = "Mouse enabled (hover/click)".into();
+                        self.notify("Mouse enabled (hover/click)");
                     } else {
                         let _ = execute!(terminal.backend_mut(), DisableMouseCapture);
                         self.viewport.cursor_pos = None; // Clear cursor position
-                        self.ui.debug_message = "Mouse disabled (text selection enabled)".into();
+                        self.notify("Mouse disabled (text selection enabled)");
                     }
                 }
 
                 // Toggle source code display
-                (KeyCode::Char('c'), _) => {
+                Action::Source => {
                     self.ui.include_source = !self.ui.include_source;
                     // Send command to request thread to update FormatContext
-                    let _ = self.cmd_tx.send(UiCommand::ToggleSource {
+                    self.send_command(UiCommand::ToggleSource {
                         include_source: self.ui.include_source,
                         current_item: self.document.history.current().and_then(|e| e.item()),
                     });
-                    self.ui.debug_message = if self.ui.include_source {
-                        "Source code display enabled".into()
+                    self.notify(if self.ui.include_source {
+                        "Source code display enabled"
                     } else {
-                        "Source code display disabled".into()
-                    };
+                        "Source code display disabled"
+                    });
+                }
+
+                // Expand or collapse the truncated section under the cursor
+                Action::ToggleBlock => {
+                    self.toggle_block_under_cursor();
+                }
+
+                // Open the focused link (or a copy of this page) in a new tab
+                Action::NewTab => {
+                    self.open_tab();
+                }
+
+                // Switch tabs
+                Action::NextTab => {
+                    self.switch_tab(1);
+                }
+                Action::PreviousTab => {
+                    self.switch_tab(-1);
+                }
+
+                // Close the current tab
+                Action::CloseTab => {
+                    self.close_tab();
                 }
 
                 // Enter theme picker mode
-                (KeyCode::Char('t'), _) => {
+                Action::Themes => {
                     let themes = RenderContext::available_themes();
                     let current_theme = self
                         .current_theme_name
@@ -338,42 +526,91 @@ impl<'a> InteractiveState<'a> {
                 }
 
                 // Show help
-                (KeyCode::Char('?'), _) | (KeyCode::Char('h'), _) => {
+                Action::Help => {
                     self.ui_mode = UiMode::Help;
                 }
 
                 // Navigate back
-                (KeyCode::Left, _) | (KeyCode::Backspace, _) => {
+                Action::Back => {
                     if let Some(entry) = self.document.history.go_back() {
                         // Send command from history entry (non-blocking)
-                        let _ = self.cmd_tx.send(entry.to_command());
+                        self.send_command(entry.to_command());
                         self.loading.start();
                         self.ui.debug_message =
                             format!("Loading: {}...", entry.display_name()).into();
                     } else {
-                        self.ui.debug_message = "Already at beginning of history".into();
+                        self.notify("Already at beginning of history");
                     }
                 }
 
                 // Navigate forward
-                (KeyCode::Right, _) => {
+                Action::Forward => {
                     if let Some(entry) = self.document.history.go_forward() {
                         // Send command from history entry (non-blocking)
-                        let _ = self.cmd_tx.send(entry.to_command());
+                        self.send_command(entry.to_command());
                         self.loading.start();
                         self.ui.debug_message =
                             format!("Loading: {}...", entry.display_name()).into();
                     } else {
-                        self.ui.debug_message = "Already at end of history".into();
+                        self.notify("Already at end of history");
                     }
                 }
+            }
 
-                _ => { /*unhandled key event*/ }
+            if let Some(key) = replay {
+                return self.handle_key_event(key, terminal);
             }
         }
         false
     }
 
+    /// Keys that aren't in the keymap: the undocumented debug features
+    fn handle_unbound_key(&mut self, key: KeyEvent) {
+        match (key.code, key.modifiers) {
+            // Dump logs to disk (undocumented debug feature)
+            (KeyCode::Char('l'), KeyModifiers::ALT) => match self.dump_logs_to_disk() {
+                Ok(filename) => {
+                    self.notify_success(format!("Logs saved to {}", filename));
+                }
+                Err(e) => {
+                    self.notify_error(format!("Failed to save logs: {}", e));
+                }
+            },
+
+            // Toggle dev log (undocumented debug feature)
+            (KeyCode::Char('l'), KeyModifiers::CONTROL) => {
+                match std::mem::replace(&mut self.ui_mode, UiMode::Normal) {
+                    UiMode::DevLog {
+                        previous_document,
+                        previous_scroll,
+                    } => {
+                        // Exiting dev log - restore previous state
+                        self.document.document = previous_document;
+                        self.set_scroll_offset(previous_scroll);
+                    }
+                    UiMode::Normal => {
+                        // Entering dev log - swap in dev log document
+                        let dev_log_doc = self.create_dev_log_document();
+                        let previous_document =
+                            std::mem::replace(&mut self.document.document, dev_log_doc);
+                        let previous_scroll = self.viewport.scroll_offset;
+                        self.set_scroll_offset(0);
+                        self.ui_mode = UiMode::DevLog {
+                            previous_document,
+                            previous_scroll,
+                        };
+                    }
+                    other => {
+                        // Was in a different mode, restore it
+                        self.ui_mode = other;
+                    }
+                }
+            }
+
+            _ => { /*unhandled key event*/ }
+        }
+    }
+
     /// Handle j/↓ key: navigate to next link or scroll down
     ///
     /// Implements seamless transition between link navigation and scrolling:
@@ -558,6 +795,164 @@ impl<'a> InteractiveState<'a> {
         }
     }
 
+    /// Handle Tab/Shift+Tab: focus the next or previous link, scrolling to it
+    ///
+    /// Unlike j/k, this jumps straight to the link however far away it is. Links below the
+    /// screen may not be laid out yet, so past the last known one Tab scrolls a page, and the
+    /// next press focuses the first link there. Past either end of the page, focus wraps
+    /// around.
+    fn handle_cycle_link(&mut self, forward: bool) {
+        use super::state::KeyboardCursor;
+
+        let actions = &self.render_cache.actions;
+        let viewport_top = self.viewport.scroll_offset;
+        let viewport_bottom = viewport_top + self.viewport.last_viewport_height;
+        let target = match (self.viewport.keyboard_cursor, forward) {
+            (KeyboardCursor::VirtualTop, true) => actions
+                .iter()
+                .position(|(rect, _)| rect.bottom() > viewport_top),
+            (KeyboardCursor::Focused { action_index }, true) => {
+                Some(action_index + 1).filter(|index| *index < actions.len())
+            }
+            (KeyboardCursor::Focused { action_index }, false) => action_index.checked_sub(1),
+            (KeyboardCursor::VirtualBottom, false) => actions
+                .iter()
+                .rposition(|(rect, _)| rect.y < viewport_bottom),
+            (KeyboardCursor::VirtualBottom, true) | (KeyboardCursor::VirtualTop, false) => None,
+        };
+
+        if let Some(action_index) = target {
+            self.viewport.keyboard_cursor = KeyboardCursor::Focused { action_index };
+            self.scroll_to_link(action_index);
+            return;
+        }
+
+        let old_offset = self.viewport.scroll_offset;
+        if forward {
+            self.set_scroll_offset(old_offset.saturating_add(self.viewport.last_viewport_height));
+            if self.viewport.scroll_offset > old_offset {
+                // More of the document below: focus its first link on the next press
+                self.viewport.keyboard_cursor = KeyboardCursor::VirtualTop;
+            } else if !self.render_cache.actions.is_empty() {
+                self.set_scroll_offset(0);
+                self.viewport.keyboard_cursor = KeyboardCursor::Focused { action_index: 0 };
+                self.scroll_to_link(0);
+            }
+        } else {
+            // Links near the bottom are laid out once it's on screen, for the next press
+            self.set_scroll_offset(u16::MAX);
+            self.viewport.keyboard_cursor = KeyboardCursor::VirtualBottom;
+        }
+    }
+
+    /// Rows to keep between the focused link and the edge of the screen, from the config, and
+    /// at most half of the screen
+    fn scrolloff(&self) -> u16 {
+        self.render_context
+            .scrolloff()
+            .min(self.viewport.last_viewport_height.saturating_sub(1) / 2)
+    }
+
+    /// Scroll just far enough that the link at `action_index` is on screen, with `scrolloff`
+    /// rows around it
+    fn scroll_to_link(&mut self, action_index: usize) {
+        let Some((rect, _)) = self.render_cache.actions.get(action_index) else {
+            return;
+        };
+        let scrolloff = self.scrolloff();
+        let (top, bottom) = (rect.y.saturating_sub(scrolloff), rect.bottom() + scrolloff);
+        let viewport_height = self.viewport.last_viewport_height;
+        if top < self.viewport.scroll_offset {
+            self.set_scroll_offset(top);
+        } else if bottom > self.viewport.scroll_offset + viewport_height {
+            self.set_scroll_offset(bottom.saturating_sub(viewport_height));
+        }
+    }
+
+    /// After j/k, scroll to keep `scrolloff` rows around a newly focused link
+    fn keep_context_around_focus(&mut self, previous: super::state::KeyboardCursor) {
+        use super::state::KeyboardCursor;
+
+        if let KeyboardCursor::Focused { action_index } = self.viewport.keyboard_cursor
+            && self.viewport.keyboard_cursor != previous
+        {
+            self.scroll_to_link(action_index);
+        }
+    }
+
+    /// Handle Ctrl+e/Ctrl+y: scroll a line down or up
+    ///
+    /// Unlike j/k this doesn't go through the links. When the focused link gets closer to the
+    /// edge than `scrolloff` allows, focus moves to the nearest link that isn't, if there is
+    /// one; at the top and bottom of the document, links can go right up to the edge.
+    fn handle_scroll_line(&mut self, down: bool) {
+        use super::state::KeyboardCursor;
+
+        let old_offset = self.viewport.scroll_offset;
+        if down {
+            self.set_scroll_offset(old_offset.saturating_add(1));
+        } else {
+            self.set_scroll_offset(old_offset.saturating_sub(1));
+        }
+        let KeyboardCursor::Focused { action_index } = self.viewport.keyboard_cursor else {
+            return;
+        };
+
+        let scrolloff = self.scrolloff();
+        let scroll_offset = self.viewport.scroll_offset;
+        let height = self.viewport.last_viewport_height;
+        let at_bottom = self
+            .viewport
+            .cached_layout
+            .is_none_or(|cache| scroll_offset + height >= cache.document_height);
+        let top = if scroll_offset == 0 {
+            0
+        } else {
+            scroll_offset + scrolloff
+        };
+        let bottom = if at_bottom {
+            scroll_offset + height
+        } else {
+            (scroll_offset + height).saturating_sub(scrolloff)
+        };
+        let in_view = |rect: &Rect| rect.y >= top && rect.bottom() <= bottom;
+
+        let actions = &self.render_cache.actions;
+        if actions
+            .get(action_index)
+            .is_none_or(|(rect, _)| in_view(rect))
+        {
+            return;
+        }
+        let nearest = if down {
+            actions.iter().position(|(rect, _)| in_view(rect))
+        } else {
+            actions.iter().rposition(|(rect, _)| in_view(rect))
+        };
+        if let Some(action_index) = nearest {
+            self.viewport.keyboard_cursor = KeyboardCursor::Focused { action_index };
+        }
+    }
+
+    /// Handle z z, z t and z b: scroll the current element (the focused link, or the line under
+    /// the mouse) to the middle, the top or the bottom of the screen
+    fn handle_recenter(&mut self, action: Action) {
+        let Some(rows) = self.current_element() else {
+            self.notify("Nothing focused to scroll to");
+            return;
+        };
+        let scrolloff = self.scrolloff();
+        let height = self.viewport.last_viewport_height;
+        let offset = match action {
+            Action::RecenterTop => rows.start.saturating_sub(scrolloff),
+            Action::RecenterBottom => (rows.end + scrolloff).saturating_sub(height),
+            _ => (rows.start + rows.end)
+                .div_ceil(2)
+                .saturating_sub(height / 2),
+        };
+        self.set_scroll_offset(offset);
+    }
+
     /// Handle Enter/Space: activate the focused link
     ///
     /// Activates the currently focused link (if any), triggering the same action
@@ -565,7 +960,7 @@ impl<'a> InteractiveState<'a> {
     /// request thread and reset keyboard focus to VirtualTop for the new document.
     /// ExpandBlock actions mutate the document in place and preserve focus.
     /// Does nothing when in VirtualTop or VirtualBottom states.
-    fn handle_activate_focused_link(&mut self) {
+    pub(super) fn handle_activate_focused_link(&mut self) {
         use super::state::KeyboardCursor;
 
         if let KeyboardCursor::Focused { action_index } = self.viewport.keyboard_cursor {
@@ -585,11 +980,11 @@ impl<'a> InteractiveState<'a> {
                             *selected_index = idx;
                         }
                     }
-                    self.ui.debug_message = format!("Selected theme: {theme_name}").into();
+                    self.notify(format!("Selected theme: {theme_name}"));
                 } else {
-                    match super::events::handle_action(&mut self.document.document, action) {
+                    match self.handle_action(action) {
                         Some(command) => {
-                            let _ = self.cmd_tx.send(command);
+                            self.send_command(command);
                             self.loading.start();
                             // Reset keyboard cursor on navigation
                             self.viewport.keyboard_cursor = KeyboardCursor::VirtualTop;
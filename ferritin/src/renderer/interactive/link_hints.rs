@@ -0,0 +1,112 @@
+use ratatui::buffer::Buffer;
+
+use super::state::{InteractiveState, KeyboardCursor, UiMode};
+use crate::styled_string::TuiAction;
+
+/// Keys that hints are made of, easiest to reach first
+const HINT_KEYS: &str = "asdfjklghqweruioptyzxcvbnm";
+
+/// Labels for `count` links, all the same length so that none is the start of another
+fn hint_labels(count: usize) -> Vec<String> {
+    let keys = HINT_KEYS.chars().collect::<Vec<_>>();
+    let mut length = 1;
+    while keys.len().pow(length) < count {
+        length += 1;
+    }
+
+    (0..count)
+        .map(|mut index| {
+            let mut label = vec![' '; length as usize];
+            for slot in label.iter_mut().rev() {
+                *slot = keys[index % keys.len()];
+                index /= keys.len();
+            }
+            label.into_iter().collect()
+        })
+        .collect()
+}
+
+impl<'a> InteractiveState<'a> {
+    /// The links on screen, as their hint and index in the actions list
+    pub(super) fn link_hints(&self) -> Vec<(String, usize)> {
+        let top = self.viewport.scroll_offset;
+        let bottom = top + self.viewport.last_viewport_height;
+        let links = self
+            .render_cache
+            .actions
+            .iter()
+            .enumerate()
+            .filter(|(_, (rect, action))| {
+                rect.y >= top
+                    && rect.y < bottom
+                    && matches!(
+                        action,
+                        TuiAction::Navigate { .. }
+                            | TuiAction::NavigateToPath { .. }
+                            | TuiAction::OpenUrl(_)
+                    )
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        hint_labels(links.len()).into_iter().zip(links).collect()
+    }
+
+    /// Draw the hints that start with what's been typed so far over their links
+    pub(super) fn render_link_hints(&self, buf: &mut Buffer, typed: &str) {
+        let style = self.theme.link_hint_style;
+        for (label, index) in self.link_hints() {
+            if !label.starts_with(typed) {
+                continue;
+            }
+            let rect = self.render_cache.actions[index].0;
+            self.write_text(buf, rect.y, rect.x, &label, self.layout.area, style);
+        }
+    }
+
+    /// Handle a key typed in hint mode: follow the link once its whole hint is typed, and
+    /// leave hint mode on a key that isn't part of any hint
+    pub(super) fn handle_link_hint_key(&mut self, key: char) {
+        let UiMode::LinkHints { typed } = &mut self.ui_mode else {
+            return;
+        };
+        typed.push(key);
+        let typed = typed.clone();
+
+        let hints = self.link_hints();
+        let mut matching = hints.iter().filter(|(label, _)| label.starts_with(&typed));
+        match (matching.next(), matching.next()) {
+            (None, _) => {
+                self.ui_mode = UiMode::Normal;
+                self.notify(format!("No link has the hint {typed}"));
+            }
+            (Some((label, index)), None) if *label == typed => {
+                self.ui_mode = UiMode::Normal;
+                self.viewport.keyboard_cursor = KeyboardCursor::Focused {
+                    action_index: *index,
+                };
+                self.handle_activate_focused_link();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_have_one_length_and_are_unique() {
+        assert_eq!(hint_labels(3), ["a", "s", "d"]);
+
+        let labels = hint_labels(30);
+        assert!(labels.iter().all(|label| label.len() == 2));
+        assert_eq!(&labels[..2], ["aa", "as"]);
+        let mut unique = labels.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 30);
+
+        assert!(hint_labels(0).is_empty());
+    }
+}
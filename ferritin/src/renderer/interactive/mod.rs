@@ -70,13 +70,17 @@
 //! The layout state is saved and restored when rendering children at different indentation levels.
 
 mod channels;
+mod crate_picker;
 mod dev_log;
 mod events;
 mod history;
+mod images;
 mod keyboard;
+mod link_hints;
 mod mouse;
 mod render_code_block;
 mod render_document;
+mod render_example_output;
 mod render_frame;
 mod render_help_screen;
 mod render_loading_bar;
@@ -88,16 +92,19 @@ mod render_table;
 mod render_theme_picker;
 mod request_thread;
 mod response;
+mod selection;
 mod span_style;
 mod state;
+mod tabs;
+mod task_status;
 mod theme;
+mod toasts;
 mod utils;
 mod write_text;
 
 #[cfg(test)]
 mod tests;
 
-use events::handle_action;
 use theme::InteractiveTheme;
 
 pub use history::HistoryEntry;
@@ -105,7 +112,11 @@ pub use history::HistoryEntry;
 use utils::set_cursor_shape;
 
 use crate::{
-    commands::Commands,
+    commands::{
+        Commands, home,
+        run_example::{self, ExampleRun},
+    },
+    config::Config,
     logging::LogReader,
     render_context::RenderContext,
     renderer::interactive::state::{InputMode, InteractiveState, UiMode},
@@ -120,12 +131,14 @@ use crossterm::{
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::{
-    io::{self, stdout},
+    io::{self, Stdout, stdout},
+    sync::Arc,
     thread,
 };
 
-use channels::{RequestResponse, UiCommand};
-use request_thread::request_thread_loop;
+use channels::{ExampleJob, RequestResponse, SentCommand};
+use request_thread::{request_thread_loop, respond};
+use task_status::TaskStatus;
 
 /// Create a static loading document to show while sources are being loaded
 fn initial_document() -> Document<'static> {
@@ -146,16 +159,37 @@ pub fn render_interactive(
     render_context: RenderContext,
     initial_command: Option<Commands>,
     log_reader: LogReader,
+    config: Config,
 ) -> io::Result<()> {
     use crate::format_context::FormatContext;
 
     // Create lazy Request - exists immediately but Navigator not built yet
-    let format_context = FormatContext::new();
-    let request = Request::lazy(manifest_path, format_context);
+    let format_context = FormatContext::new()
+        .with_collapsed_examples(true)
+        .with_min_version(config.min_version)
+        .with_verbosity(config.verbosity.unwrap_or_default())
+        .with_terminal_width(Some(render_context.terminal_width()));
+    // Downloads and doc builds on the request thread are shown in the status bar. This thread
+    // becomes the request thread, so it's the one whose downloads can be cancelled.
+    let tasks = Arc::new(TaskStatus::default());
+    let mut request = Request::lazy(manifest_path, format_context)
+        .with_offline(config.offline.unwrap_or(false))
+        .with_docs_rs(config.cache_dir, config.docs_rs_url)
+        .with_progress(tasks.clone());
+    if let Some(toolchain) = config.toolchain {
+        request = request.with_toolchain(toolchain);
+    }
 
     // Use scoped threads so request can be borrowed by both threads
     thread::scope(|scope| {
-        render_interactive_impl(scope, &request, render_context, initial_command, log_reader)
+        render_interactive_impl(
+            scope,
+            &request,
+            render_context,
+            initial_command,
+            log_reader,
+            tasks,
+        )
     })
 }
 
@@ -165,42 +199,101 @@ fn render_interactive_impl<'scope, 'env: 'scope>(
     render_context: RenderContext,
     initial_command: Option<Commands>,
     log_reader: LogReader,
+    tasks: Arc<TaskStatus>,
 ) -> io::Result<()> {
     // Build interactive theme from render context
     let interactive_theme = InteractiveTheme::from_render_context(&render_context);
 
     // Create channels for communication between UI and request threads
-    let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded::<UiCommand<'env>>();
+    let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded::<SentCommand<'env>>();
     let (resp_tx, resp_rx) = crossbeam_channel::unbounded::<RequestResponse<'env>>();
+    let (image_tx, image_rx) = crossbeam_channel::unbounded::<String>();
 
     // Spawn UI thread - it only renders and handles input
     // UI thread starts without a document - will receive initial document via channel
+    let ui_tasks = tasks.clone();
     let ui_handle = scope.spawn(|| -> io::Result<()> {
         ui_thread_loop(
             render_context,
             interactive_theme,
             cmd_tx,
             resp_rx,
+            image_tx,
             log_reader,
+            ui_tasks,
         )
     });
 
+    // Crates linked from the pages being shown are loaded on a third thread, so that following
+    // a link doesn't wait for rustdoc JSON to be parsed
+    let (prefetch_tx, prefetch_rx) = crossbeam_channel::unbounded::<Vec<String>>();
+    scope.spawn(move || {
+        for crate_names in prefetch_rx {
+            let crate_names = crate_names.iter().map(String::as_str).collect::<Vec<_>>();
+            request.prefetch(&crate_names);
+        }
+    });
+
     // Main thread becomes request thread - populate Navigator and do all formatting
     // This is where the slow source loading happens (after UI thread is running)
     request.populate();
 
-    // Execute initial command and send to UI
-    let (document, _is_error, initial_entry) = initial_command
-        .unwrap_or_else(Commands::list)
-        .execute(request);
+    // Images in the docs being shown are downloaded on their own thread too, so that a slow
+    // image host doesn't hold up navigation
+    let image_resp_tx = resp_tx.clone();
+    scope.spawn(move || {
+        for url in image_rx {
+            let data = request.docsrs_source().and_then(|docsrs| {
+                docsrs
+                    .image(&url)
+                    .inspect_err(|error| log::debug!("{error}"))
+                    .ok()
+            });
+            let _ = image_resp_tx.send(RequestResponse::Image { url, data });
+        }
+    });
 
-    let _ = resp_tx.send(RequestResponse::Document {
-        doc: document,
-        entry: initial_entry,
+    // Doc examples are built and run on their own thread as well, since cargo can take minutes
+    let (example_tx, example_rx) = crossbeam_channel::unbounded::<ExampleJob>();
+    let example_resp_tx = resp_tx.clone();
+    scope.spawn(move || {
+        for ExampleJob {
+            title,
+            project,
+            example,
+        } in example_rx
+        {
+            let run =
+                run_example::run_in(&project, &example, true).unwrap_or_else(|e| ExampleRun {
+                    passed: false,
+                    summary: format!("could not be run: {e}"),
+                    stdout: String::new(),
+                    stderr: String::new(),
+                });
+            let _ = example_resp_tx.send(RequestResponse::ExampleOutput { title, run });
+        }
     });
 
+    // Execute initial command and send to UI, starting at the home page if there is none
+    let (document, initial_entry) = match initial_command {
+        Some(command) => {
+            let (document, _is_error, entry) = command.execute(request);
+            (document, entry)
+        }
+        None => (home::execute(request), Some(HistoryEntry::Home)),
+    };
+
+    respond(
+        request,
+        &resp_tx,
+        RequestResponse::Document {
+            doc: document,
+            entry: initial_entry,
+        },
+    );
+
     // Run request thread loop
-    request_thread_loop(request, cmd_rx, resp_tx);
+    request_thread_loop(request, cmd_rx, resp_tx, prefetch_tx, example_tx, &tasks);
 
     // Wait for UI thread to complete and return its result
     ui_handle.join().unwrap()?;
@@ -212,9 +305,11 @@ fn render_interactive_impl<'scope, 'env: 'scope>(
 fn ui_thread_loop<'a>(
     render_context: RenderContext,
     interactive_theme: InteractiveTheme,
-    cmd_tx: crossbeam_channel::Sender<UiCommand<'a>>,
+    cmd_tx: crossbeam_channel::Sender<SentCommand<'a>>,
     resp_rx: crossbeam_channel::Receiver<RequestResponse<'a>>,
+    image_tx: crossbeam_channel::Sender<String>,
     log_reader: LogReader,
+    tasks: Arc<TaskStatus>,
 ) -> io::Result<()> {
     // Set up terminal
     enable_raw_mode()?;
@@ -234,6 +329,8 @@ fn ui_thread_loop<'a>(
         interactive_theme,
         log_reader,
     );
+    state.loading.tasks = tasks;
+    state.images.fetch_tx = Some(image_tx);
 
     // Spawn event reader thread that blocks on crossterm events
     let (event_tx, event_rx) = crossbeam_channel::unbounded();
@@ -250,8 +347,7 @@ fn ui_thread_loop<'a>(
     let timer_tick = crossbeam_channel::tick(std::time::Duration::from_millis(30));
 
     // Initial render before entering event loop
-    terminal.draw(|frame| state.render_frame(frame))?;
-    state.update_cursor(&mut terminal);
+    draw(&mut terminal, &mut state)?;
 
     // Main event loop using select! for efficient blocking
     let result = loop {
@@ -267,10 +363,13 @@ fn ui_thread_loop<'a>(
                 }
             }
 
-            // Timer ticks for spinner animation - only render if loading
+            // Timer ticks for spinner animation and notifications - only render if loading or
+            // the toasts changed
             recv(timer_tick) -> _ => {
-                if !state.loading.pending_request {
-                    continue; // Skip render if not loading
+                let toasts_expired = state.ui.toasts.expire(std::time::Instant::now());
+                let toasts_added = state.notify_background_events();
+                if !state.loading.pending_request && !toasts_expired && !toasts_added {
+                    continue; // Skip render if nothing on screen changed
                 }
                 // Fall through to render below
             }
@@ -301,6 +400,8 @@ fn ui_thread_loop<'a>(
                     Ok(Event::Mouse(mouse_event)) => {
                         state.handle_mouse_event(mouse_event, &terminal);
                     }
+                    // ratatui clears the screen when it's resized, images and all
+                    Ok(Event::Resize(..)) => state.images.redraw(),
                     Ok(_) => {}
                     Err(_) => {
                         // Event reader thread exited
@@ -315,11 +416,11 @@ fn ui_thread_loop<'a>(
         state.handle_click();
 
         // Render
-        terminal.draw(|frame| state.render_frame(frame))?;
-        state.update_cursor(&mut terminal);
+        draw(&mut terminal, &mut state)?;
     };
 
     // Clean up terminal
+    state.images.erase(terminal.backend_mut())?;
     disable_raw_mode()?;
 
     // Restore default cursor shape before exiting
@@ -337,6 +438,30 @@ fn ui_thread_loop<'a>(
     result
 }
 
+/// Render a frame, then draw the document's images over it, which ratatui doesn't know about
+fn draw(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    state: &mut InteractiveState<'_>,
+) -> io::Result<()> {
+    terminal.draw(|frame| state.render_frame(frame))?;
+    // Images are only shown with the document, not under the screens that cover it
+    state.images.set_hidden(!matches!(
+        state.ui_mode,
+        UiMode::Normal | UiMode::Input(_) | UiMode::LinkHints { .. }
+    ));
+    let top = state.tab_bar_height();
+    if state.images.needs_clear(top) {
+        terminal.clear()?;
+        terminal.draw(|frame| state.render_frame(frame))?;
+    }
+    state.images.draw(terminal.backend_mut(), top)?;
+    if let Some(text) = state.ui.clipboard.take() {
+        selection::copy_to_clipboard(terminal.backend_mut(), &text)?;
+    }
+    state.update_cursor(terminal);
+    Ok(())
+}
+
 #[cfg(test)]
 pub fn render_to_test_backend(
     document: Document<'_>,
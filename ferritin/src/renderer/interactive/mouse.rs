@@ -4,12 +4,11 @@ use crossterm::event::{MouseEvent, MouseEventKind};
 use ratatui::{Terminal, layout::Position, prelude::Backend};
 
 use crate::{
-    render_context::RenderContext,
-    renderer::interactive::{handle_action, set_cursor_shape},
+    render_context::RenderContext, renderer::interactive::set_cursor_shape,
     styled_string::TuiAction,
 };
 
-use super::UiMode;
+use super::{UiMode, selection::Selection};
 
 impl<'a> super::InteractiveState<'a> {
     pub(super) fn handle_mouse_event(
@@ -40,18 +39,25 @@ impl<'a> super::InteractiveState<'a> {
 
                 let terminal_height = size.height;
                 let terminal_width = size.width;
-                let content_height = terminal_height.saturating_sub(2); // Exclude 2 status lines
+                let tab_bar_height = self.tab_bar_height();
+                // Exclude the tab bar and 2 status lines
+                let content_height = terminal_height.saturating_sub(2 + tab_bar_height);
                 let content_width = terminal_width.saturating_sub(1); // Exclude scrollbar column
                 let breadcrumb_row = terminal_height.saturating_sub(2);
+                let content_row = row
+                    .checked_sub(tab_bar_height)
+                    .filter(|content_row| *content_row < content_height);
 
                 // Check if hovering over scrollbar
                 self.viewport.scrollbar_hovered =
-                    row < content_height && column == content_width && self.scrollbar_visible();
+                    content_row.is_some() && column == content_width && self.scrollbar_visible();
 
-                if row < content_height {
+                if let Some(content_row) = content_row {
                     // Mouse in main content area
-                    self.viewport.cursor_pos =
-                        Some(Position::new(column, row + self.viewport.scroll_offset));
+                    self.viewport.cursor_pos = Some(Position::new(
+                        column,
+                        content_row + self.viewport.scroll_offset,
+                    ));
                     self.document.history.clear_hover();
                 } else if row == breadcrumb_row {
                     // Mouse over breadcrumb bar
@@ -70,14 +76,26 @@ impl<'a> super::InteractiveState<'a> {
                 kind: MouseEventKind::ScrollDown,
                 ..
             } => {
-                self.set_scroll_offset(self.viewport.scroll_offset.saturating_add(1));
+                if let UiMode::ExampleOutput { scroll, .. } | UiMode::Messages { scroll } =
+                    &mut self.ui_mode
+                {
+                    *scroll = scroll.saturating_add(1);
+                } else {
+                    self.set_scroll_offset(self.viewport.scroll_offset.saturating_add(1));
+                }
             }
 
             MouseEvent {
                 kind: MouseEventKind::ScrollUp,
                 ..
             } => {
-                self.set_scroll_offset(self.viewport.scroll_offset.saturating_sub(1));
+                if let UiMode::ExampleOutput { scroll, .. } | UiMode::Messages { scroll } =
+                    &mut self.ui_mode
+                {
+                    *scroll = scroll.saturating_sub(1);
+                } else {
+                    self.set_scroll_offset(self.viewport.scroll_offset.saturating_sub(1));
+                }
             }
 
             MouseEvent {
@@ -91,20 +109,32 @@ impl<'a> super::InteractiveState<'a> {
                 };
 
                 let terminal_height = size.height;
-                let content_height = terminal_height.saturating_sub(2); // Exclude 2 status lines
+                let tab_bar_height = self.tab_bar_height();
+                // Exclude the tab bar and 2 status lines
+                let content_height = terminal_height.saturating_sub(2 + tab_bar_height);
                 let content_width = size.width.saturating_sub(1); // Exclude scrollbar column
                 let breadcrumb_row = terminal_height.saturating_sub(2);
+                let content_row = row
+                    .checked_sub(tab_bar_height)
+                    .filter(|content_row| *content_row < content_height);
 
                 // Check if click is in scrollbar column
-                if row < content_height && column == content_width && self.scrollbar_visible() {
+                if let Some(content_row) = content_row
+                    && column == content_width
+                    && self.scrollbar_visible()
+                {
                     // Start scrollbar drag
                     self.viewport.scrollbar_dragging = true;
                     // Calculate scroll position from click Y
-                    self.handle_scrollbar_drag(row, content_height);
-                } else if row < content_height {
-                    // Click in main content area
-                    self.viewport.clicked_position =
-                        Some(Position::new(column, row + self.viewport.scroll_offset));
+                    self.handle_scrollbar_drag(content_row, content_height);
+                } else if let Some(content_row) = content_row {
+                    // Press in main content area: it's a click if the button is let go without
+                    // dragging, and otherwise selects text
+                    self.viewport.selection = None;
+                    self.viewport.pressed_position = Some(Position::new(
+                        column,
+                        content_row + self.viewport.scroll_offset,
+                    ));
                 } else if row == breadcrumb_row {
                     // Click on breadcrumb bar
                     if let Some(entry) = self
@@ -113,7 +143,7 @@ impl<'a> super::InteractiveState<'a> {
                         .handle_click(Position::new(column, row))
                     {
                         // Send command from history entry (non-blocking)
-                        let _ = self.cmd_tx.send(entry.to_command());
+                        self.send_command(entry.to_command());
                         self.loading.start();
                     }
                 }
@@ -121,15 +151,32 @@ impl<'a> super::InteractiveState<'a> {
 
             MouseEvent {
                 kind: MouseEventKind::Drag(_),
+                column,
                 row,
                 ..
             } => {
+                let Ok(size) = terminal.size() else {
+                    return;
+                };
+                let tab_bar_height = self.tab_bar_height();
+                let content_height = size.height.saturating_sub(2 + tab_bar_height);
                 if self.viewport.scrollbar_dragging {
-                    let Ok(size) = terminal.size() else {
-                        return;
-                    };
-                    let content_height = size.height.saturating_sub(2);
-                    self.handle_scrollbar_drag(row, content_height);
+                    self.handle_scrollbar_drag(row.saturating_sub(tab_bar_height), content_height);
+                } else if let Some(anchor) = self.viewport.pressed_position
+                    && matches!(self.ui_mode, UiMode::Normal)
+                {
+                    // Dragging past the content selects up to its edge
+                    let content_row = row
+                        .saturating_sub(tab_bar_height)
+                        .min(content_height.saturating_sub(1));
+                    let head = Position::new(column, content_row + self.viewport.scroll_offset);
+                    if head != anchor || self.viewport.selection.is_some() {
+                        self.viewport.selection = Some(Selection {
+                            anchor,
+                            head,
+                            copy: false,
+                        });
+                    }
                 }
             }
 
@@ -139,6 +186,12 @@ impl<'a> super::InteractiveState<'a> {
             } => {
                 if self.viewport.scrollbar_dragging {
                     self.viewport.scrollbar_dragging = false;
+                } else if let Some(pressed) = self.viewport.pressed_position.take() {
+                    match &mut self.viewport.selection {
+                        // Copy the text the drag selected, once it's drawn
+                        Some(selection) => selection.copy = true,
+                        None => self.viewport.clicked_position = Some(pressed),
+                    }
                 }
             }
             _ => { /*unhandled*/ }
@@ -173,12 +226,12 @@ impl<'a> super::InteractiveState<'a> {
                         }
                     }
 
-                    self.ui.debug_message = format!("Selected theme: {theme_name}").into();
+                    self.notify(format!("Selected theme: {theme_name}"));
                 } else {
-                    match handle_action(&mut self.document.document, action) {
+                    match self.handle_action(action) {
                         Some(command) => {
                             // Send command to request thread (non-blocking)
-                            let _ = self.cmd_tx.send(command);
+                            self.send_command(command);
                             self.loading.start();
                         }
                         None => {
@@ -203,27 +256,37 @@ impl<'a> super::InteractiveState<'a> {
         match self.viewport.keyboard_cursor {
             KeyboardCursor::Focused { action_index } => {
                 if let Some((_, action)) = self.render_cache.actions.get(action_index) {
-                    self.ui.debug_message = match action {
+                    let message: String = match action {
                         TuiAction::Navigate { doc_ref, url: _ } => {
                             if let Some(path) = doc_ref.path() {
-                                format!("Navigate: {path} (⏎ to activate)").into()
+                                format!("Navigate: {path} (⏎ to activate)")
                             } else if let Some(name) = doc_ref.name() {
-                                format!("Navigate: {name} (⏎ to activate)").into()
+                                format!("Navigate: {name} (⏎ to activate)")
                             } else {
                                 "Navigate: <unknown> (⏎ to activate)".into()
                             }
                         }
                         TuiAction::NavigateToPath { path, url: _ } => {
-                            format!("Go to: {} (⏎ to activate)", path).into()
+                            format!("Go to: {} (⏎ to activate)", path)
                         }
                         TuiAction::ExpandBlock(path) => {
-                            format!("Expand: {:?} (⏎ to activate)", path.indices()).into()
+                            format!("Expand: {:?} (⏎ to activate)", path.indices())
                         }
-                        TuiAction::OpenUrl(url) => format!("Open: {} (⏎ to activate)", url).into(),
+                        TuiAction::OpenUrl(url) => format!("Open: {} (⏎ to activate)", url),
                         TuiAction::SelectTheme(theme_name) => {
-                            format!("Preview theme: {} (⏎ to activate)", theme_name).into()
+                            format!("Preview theme: {} (⏎ to activate)", theme_name)
+                        }
+                        TuiAction::RunExample { index, .. } => {
+                            format!("Run example {index} (⏎ to activate)")
+                        }
+                        TuiAction::Search { query, .. } => {
+                            format!("Search: {query} (⏎ to activate)")
+                        }
+                        TuiAction::ApiDiff { crate_name, from } => {
+                            format!("API changes in {crate_name} since {from} (⏎ to activate)")
                         }
                     };
+                    self.announce_hover(message);
                     return; // Keyboard focus takes priority
                 }
                 // Focused on invalid action_index - fall through to mouse hover
@@ -242,44 +305,56 @@ impl<'a> super::InteractiveState<'a> {
                     .iter()
                     .find(|(rect, _)| rect.contains(pos))
                 {
-                    self.ui.debug_message = match action {
+                    let message: String = match action {
                         TuiAction::Navigate { doc_ref, url: _ } => {
                             if let Some(path) = doc_ref.path() {
-                                format!("Navigate: {path}").into()
+                                format!("Navigate: {path}")
                             } else if let Some(name) = doc_ref.name() {
-                                format!("Navigate: {name}").into()
+                                format!("Navigate: {name}")
                             } else {
                                 "Navigate: <unknown>".into()
                             }
                         }
                         TuiAction::NavigateToPath { path, url: _ } => {
-                            format!("Go to: {}", path).into()
+                            format!("Go to: {}", path)
                         }
                         TuiAction::ExpandBlock(path) => {
-                            format!("Expand: {:?}", path.indices()).into()
+                            format!("Expand: {:?}", path.indices())
                         }
-                        TuiAction::OpenUrl(url) => format!("Open: {}", url).into(),
+                        TuiAction::OpenUrl(url) => format!("Open: {}", url),
                         TuiAction::SelectTheme(theme_name) => {
-                            format!("Preview theme: {}", theme_name).into()
+                            format!("Preview theme: {}", theme_name)
+                        }
+                        TuiAction::RunExample { index, .. } => {
+                            format!("Run example {index}")
+                        }
+                        TuiAction::Search { query, .. } => format!("Search: {query}"),
+                        TuiAction::ApiDiff { crate_name, from } => {
+                            format!("API changes in {crate_name} since {from}")
                         }
                     };
-                } else {
-                    self.ui.debug_message = format!(
-                        "Pos: ({}, {}) | Scroll: {} | Mouse: ON | Source: {}",
-                        pos.x,
-                        pos.y,
-                        self.viewport.scroll_offset,
-                        if self.ui.include_source { "ON" } else { "OFF" }
-                    )
-                    .into();
+                    self.announce_hover(message);
+                    return;
                 }
             }
         } else {
-            self.ui.debug_message = format!(
+            self.announce_hover(format!(
                 "Mouse: OFF (text selection enabled - m to re-enable) | Source: {}",
                 if self.ui.include_source { "ON" } else { "OFF" }
-            )
-            .into();
+            ));
+            return;
+        }
+
+        // Nothing is hovered, so moving back onto the same link announces it again
+        self.ui.hover_message = None;
+    }
+
+    /// Show what the focused or hovered link does as a notification, once each time it
+    /// changes, so that it also lands in the `:messages` view
+    fn announce_hover(&mut self, message: String) {
+        if self.ui.hover_message.as_ref() != Some(&message) {
+            self.notify(message.clone());
+            self.ui.hover_message = Some(message);
         }
     }
 
@@ -1,11 +1,14 @@
 use ratatui::{
     buffer::Buffer,
-    style::{Color, Style},
+    layout::Rect,
+    style::{Color, Modifier, Style},
 };
 use syntect::easy::HighlightLines;
 use syntect::util::LinesWithEndings;
 
 use super::state::InteractiveState;
+use crate::renderer::{display_width, is_preformatted, truncate_to_width};
+use crate::styled_string::{LineNumbers, TuiAction};
 
 // Code block borders are outdented to the left of content so that the code text
 // aligns with surrounding prose, and the border is purely decorative.
@@ -14,7 +17,17 @@ const CODE_BLOCK_BORDER_OUTDENT: i16 = -2; // Draw border 2 columns left of cont
 
 impl<'a> InteractiveState<'a> {
     /// Render code block with syntax highlighting
-    pub(super) fn render_code_block(&mut self, lang: Option<&str>, code: &str, buf: &mut Buffer) {
+    ///
+    /// If the block carries an action (e.g. running a doc example), a clickable label is
+    /// drawn in the bottom border. Quoted source is numbered in a gutter inside the border.
+    pub(super) fn render_code_block(
+        &mut self,
+        lang: Option<&str>,
+        code: &str,
+        action: Option<&TuiAction<'a>>,
+        line_numbers: Option<&LineNumbers>,
+        buf: &mut Buffer,
+    ) {
         let lang_display = match lang {
             Some("no_run") | Some("should_panic") | Some("ignore") | Some("compile_fail")
             | Some("edition2015") | Some("edition2018") | Some("edition2021")
@@ -30,20 +43,30 @@ impl<'a> InteractiveState<'a> {
             .saturating_add_signed(CODE_BLOCK_BORDER_OUTDENT);
         let content_col = self.layout.indent; // Code content stays at indent
 
+        // Line numbers push the code right of the indent
+        let line_count = code.lines().count();
+        let gutter_width = line_numbers.map_or(0, |line_numbers| {
+            display_width(&line_numbers.gutter(line_numbers.first, line_count))
+        });
+        let code_col = content_col + gutter_width as u16;
+
         // Calculate code block dimensions accounting for content position
         let available_width = self.layout.area.width.saturating_sub(content_col);
-        let max_line_width = code
-            .lines()
-            .map(|line| line.len())
-            .max()
-            .unwrap_or(0)
+        let max_line_width = (code.lines().map(display_width).max().unwrap_or(0) + gutter_width)
             .min((available_width.saturating_sub(4)) as usize); // Leave room for border and padding
 
         // Account for language label in border width: ╭───❬rust❭─╮
         let lang_label = format!("❬{}❭", lang_display);
-        // Count actual display width (number of grapheme clusters, not bytes)
-        let label_display_width = lang_label.chars().count();
-        let min_border_for_label = label_display_width as u16 + 6; // label + some padding
+        // Count actual display width (terminal columns, not bytes)
+        let label_display_width = display_width(&lang_label);
+        let action_label = "❬▶ run❭";
+        let action_label_width = display_width(action_label);
+        let widest_label = if action.is_some() {
+            label_display_width.max(action_label_width)
+        } else {
+            label_display_width
+        };
+        let min_border_for_label = widest_label as u16 + 6; // label + some padding
         let border_width = ((max_line_width + 4).max(min_border_for_label as usize))
             .min(available_width as usize) as u16;
 
@@ -116,15 +139,18 @@ impl<'a> InteractiveState<'a> {
         self.layout.pos.y += 1;
 
         // Render code content with side borders (no background color)
-        if let Some(syntax) = self
-            .render_context
-            .syntax_set()
-            .find_syntax_by_token(lang_display)
-        {
+        let syntax = if is_preformatted(lang) {
+            None
+        } else {
+            self.render_context
+                .syntax_set()
+                .find_syntax_by_token(lang_display)
+        };
+        if let Some(syntax) = syntax {
             let theme = self.render_context.theme();
             let mut highlighter = HighlightLines::new(syntax, theme);
 
-            for line in LinesWithEndings::from(code) {
+            for (idx, line) in LinesWithEndings::from(code).enumerate() {
                 if self.layout.pos.y >= self.viewport.scroll_offset
                     && self.layout.pos.y < self.viewport.scroll_offset + self.layout.area.height
                 {
@@ -137,8 +163,9 @@ impl<'a> InteractiveState<'a> {
                         self.layout.area,
                         border_style,
                     );
+                    self.write_line_number(buf, line_numbers, idx, line_count, content_col);
 
-                    let mut col = content_col;
+                    let mut col = code_col;
 
                     if let Ok(ranges) =
                         highlighter.highlight_line(line, self.render_context.syntax_set())
@@ -156,13 +183,13 @@ impl<'a> InteractiveState<'a> {
                                 self.layout.area,
                                 ratatui_style,
                             );
-                            col += text.len() as u16;
+                            col += display_width(text) as u16;
                         }
                     } else {
                         self.write_text(
                             buf,
                             self.layout.pos.y,
-                            content_col,
+                            code_col,
                             line.trim_end_matches('\n'),
                             self.layout.area,
                             Style::default(),
@@ -183,7 +210,7 @@ impl<'a> InteractiveState<'a> {
                 self.layout.pos.y += 1;
             }
         } else {
-            for line in code.lines() {
+            for (idx, line) in code.lines().enumerate() {
                 if self.layout.pos.y >= self.viewport.scroll_offset
                     && self.layout.pos.y < self.viewport.scroll_offset + self.layout.area.height
                 {
@@ -196,13 +223,16 @@ impl<'a> InteractiveState<'a> {
                         self.layout.area,
                         border_style,
                     );
+                    self.write_line_number(buf, line_numbers, idx, line_count, content_col);
 
-                    // Code content
+                    // Code content, cut off inside the right border
+                    let inner_width = (border_col + border_width.saturating_sub(2))
+                        .saturating_sub(code_col) as usize;
                     self.write_text(
                         buf,
                         self.layout.pos.y,
-                        content_col,
-                        line,
+                        code_col,
+                        truncate_to_width(line, inner_width),
                         self.layout.area,
                         Style::default(),
                     );
@@ -221,7 +251,7 @@ impl<'a> InteractiveState<'a> {
             }
         }
 
-        // Bottom border: ╰─────╯
+        // Bottom border: ╰─────╯, or ╰───❬▶ run❭─╯ when the block has an action
         if self.layout.pos.y >= self.viewport.scroll_offset
             && self.layout.pos.y < self.viewport.scroll_offset + self.layout.area.height
         {
@@ -252,6 +282,71 @@ impl<'a> InteractiveState<'a> {
                 border_style,
             );
         }
+
+        if let Some(action) = action {
+            // Same placement as the language label: right side, one dash before the corner
+            let label_start =
+                border_col + border_width.saturating_sub(action_label_width as u16 + 2);
+            let label_row = self.layout.pos.y;
+
+            // Highlight on mouse hover or keyboard focus, like other links
+            let mouse_hover = self.viewport.cursor_pos.is_some_and(|cursor| {
+                cursor.y == label_row
+                    && cursor.x >= label_start
+                    && cursor.x < label_start + action_label_width as u16
+            });
+            let keyboard_focus = matches!(
+                self.viewport.keyboard_cursor,
+                super::state::KeyboardCursor::Focused { action_index }
+                    if action_index == self.render_cache.actions.len()
+            );
+            let label_style = if mouse_hover || keyboard_focus {
+                border_style.add_modifier(Modifier::REVERSED)
+            } else {
+                border_style
+            };
+
+            self.write_text(
+                buf,
+                label_row,
+                label_start,
+                action_label,
+                self.layout.area,
+                label_style,
+            );
+
+            let rect = Rect::new(label_start, label_row, action_label_width as u16, 1);
+            self.render_cache.actions.push((rect, action.clone()));
+        }
         self.layout.pos.y += 1;
     }
+
+    /// Write the gutter for the `idx`th line of a numbered code block at `col`, dimming the
+    /// context around the item's own lines
+    fn write_line_number(
+        &self,
+        buf: &mut Buffer,
+        line_numbers: Option<&LineNumbers>,
+        idx: usize,
+        line_count: usize,
+        col: u16,
+    ) {
+        let Some(line_numbers) = line_numbers else {
+            return;
+        };
+        let (number, highlighted) = line_numbers.line(idx);
+        let style = if highlighted {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            self.theme.muted_style
+        };
+        self.write_text(
+            buf,
+            self.layout.pos.y,
+            col,
+            &line_numbers.gutter(number, line_count),
+            self.layout.area,
+            style,
+        );
+    }
 }
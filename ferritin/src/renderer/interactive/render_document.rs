@@ -8,19 +8,31 @@ use crate::styled_string::NodePath;
 
 // Baseline left margin for all content - provides breathing room and space for outdented borders
 pub(super) const BASELINE_LEFT_MARGIN: u16 = 3;
+// Left margin on narrow terminals, where every column counts
+const NARROW_LEFT_MARGIN: u16 = 1;
 
 impl<'a> InteractiveState<'a> {
     /// Render document nodes to buffer, updating action map
-    pub(super) fn render_document(&mut self, _area: Rect, buf: &mut Buffer) {
+    ///
+    /// Returns whether the document was laid out anew and scrolled to keep the node that was at
+    /// the top of the screen there, in which case it needs rendering again
+    pub(super) fn render_document(&mut self, _area: Rect, buf: &mut Buffer) -> bool {
         self.render_cache.actions.clear();
+        self.render_cache.truncated_blocks.clear();
+        self.images.clear_placements();
 
         // Layout state already initialized in render_frame with area
         // Set initial position and indent
+        let left_margin = if self.layout.narrow {
+            NARROW_LEFT_MARGIN
+        } else {
+            BASELINE_LEFT_MARGIN
+        };
         self.layout.pos = Position {
-            x: BASELINE_LEFT_MARGIN,
+            x: left_margin,
             y: 0,
         };
-        self.layout.indent = BASELINE_LEFT_MARGIN;
+        self.layout.indent = left_margin;
 
         // Check if we need to recalculate height (cache invalid or missing)
         let need_height_calc = self
@@ -28,6 +40,8 @@ impl<'a> InteractiveState<'a> {
             .cached_layout
             .map(|cache| cache.render_width != self.layout.area.width)
             .unwrap_or(true);
+        self.layout.top_node = None;
+        self.layout.anchor_row = None;
 
         // Use raw pointer to avoid borrow checker issues when calling render_node
         let nodes_ptr = self.document.document.nodes.as_ptr();
@@ -61,6 +75,36 @@ impl<'a> InteractiveState<'a> {
                 render_width: self.layout.area.width,
                 document_height: self.layout.pos.y,
             });
+
+            if let Some((_, rows_above)) = self.viewport.scroll_anchor
+                && let Some(row) = self.layout.anchor_row
+                && row + rows_above != self.viewport.scroll_offset
+            {
+                self.set_scroll_offset(row + rows_above);
+                return true;
+            }
+        }
+
+        self.viewport.scroll_anchor = self.layout.top_node;
+        false
+    }
+
+    /// Note where the node at `layout.node_path` starts: the innermost node starting at or above
+    /// the top of the screen is the one to keep there, and the anchor's start is where to scroll
+    /// after the document is laid out anew
+    pub(super) fn track_scroll_anchor(&mut self) {
+        let row = self.layout.pos.y;
+        let scroll_offset = self.viewport.scroll_offset;
+        if row <= scroll_offset {
+            self.layout.top_node = Some((self.layout.node_path, scroll_offset - row));
+        }
+        if self.layout.anchor_row.is_none()
+            && self
+                .viewport
+                .scroll_anchor
+                .is_some_and(|(path, _)| path == self.layout.node_path)
+        {
+            self.layout.anchor_row = Some(row);
         }
     }
 }
@@ -0,0 +1,88 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Modifier,
+    text::{Line, Span as RatatuiSpan},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use super::{
+    state::{InteractiveState, UiMode},
+    utils::centered_rect,
+};
+
+impl<'a> InteractiveState<'a> {
+    /// Render the doc example result pane as a modal overlay
+    pub(super) fn render_example_output(&mut self, buf: &mut Buffer, area: Rect) {
+        // Clear document actions - modal should block all background interactions
+        self.render_cache.actions.clear();
+
+        let UiMode::ExampleOutput { title, run, scroll } = &self.ui_mode else {
+            return;
+        };
+
+        let modal_area = centered_rect(80, 80, area);
+        Clear.render(modal_area, buf);
+
+        let title_style = self.theme.help_title_style;
+        let text_style = self.theme.help_desc_style;
+        let muted_style = self.theme.muted_style;
+
+        let mut lines = vec![
+            Line::from(RatatuiSpan::styled(
+                run.summary.as_str(),
+                title_style.add_modifier(Modifier::BOLD),
+            )),
+            Line::default(),
+        ];
+
+        for (label, output) in [("stdout", &run.stdout), ("stderr", &run.stderr)] {
+            if output.is_empty() {
+                continue;
+            }
+            lines.push(Line::from(RatatuiSpan::styled(label, title_style)));
+            lines.extend(
+                output
+                    .lines()
+                    .map(|line| Line::from(RatatuiSpan::styled(line, text_style))),
+            );
+            lines.push(Line::default());
+        }
+
+        if run.stdout.is_empty() && run.stderr.is_empty() {
+            lines.push(Line::from(RatatuiSpan::styled("(no output)", muted_style)));
+        }
+
+        let block = Block::default()
+            .title(format!(" {title} "))
+            .borders(Borders::ALL)
+            .style(self.theme.help_bg_style);
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((*scroll, 0))
+            .render(modal_area, buf);
+
+        // Render instructions at the bottom of the modal
+        let instruction_y = modal_area.y + modal_area.height.saturating_sub(1);
+        if instruction_y < area.height {
+            let instructions = " ↑/↓:Scroll  Esc:Close ";
+            let instruction_x = modal_area.x
+                + (modal_area
+                    .width
+                    .saturating_sub(instructions.chars().count() as u16))
+                    / 2;
+
+            for (i, ch) in instructions.chars().enumerate() {
+                let x = instruction_x + i as u16;
+                if x < modal_area.x + modal_area.width
+                    && let Some(cell) = buf.cell_mut((x, instruction_y))
+                {
+                    cell.set_char(ch);
+                    cell.set_style(self.theme.status_hint_style);
+                }
+            }
+        }
+    }
+}
@@ -1,19 +1,54 @@
 use ratatui::{
     Frame,
-    layout::{Position, Rect},
+    buffer::Buffer,
+    layout::{Alignment, Position, Rect},
+    text::Line,
+    widgets::{Paragraph, Widget, Wrap},
 };
 
 use super::{InteractiveState, UiMode};
 use crate::styled_string::NodePath;
 
+/// Below this width, the compact layout drops the scrollbar and most of the left margin,
+/// shows tables as lists and only shows the current item in the breadcrumb bar
+pub(super) const NARROW_WIDTH: u16 = 50;
+
+/// Below this width nothing is rendered but a request for a wider terminal
+pub(super) const MIN_WIDTH: u16 = 30;
+
 impl<'a> InteractiveState<'a> {
     pub(super) fn render_frame(&mut self, frame: &mut Frame) {
-        // Reserve last 2 lines for status bars and rightmost column for scrollbar
+        self.render_screen(frame);
+
+        // Themes are truecolor, so terminals with fewer colors get the nearest ones they have
+        self.render_context
+            .color_depth()
+            .downsample(frame.buffer_mut());
+    }
+
+    fn render_screen(&mut self, frame: &mut Frame) {
+        if frame.area().width < MIN_WIDTH {
+            self.render_too_narrow(frame.buffer_mut(), frame.area());
+            return;
+        }
+
+        // Reserve the first line for the tab bar (if there are tabs), last 2 lines for status
+        // bars and rightmost column for scrollbar (except on narrow terminals)
+        self.layout.narrow = frame.area().width < NARROW_WIDTH;
+        let scrollbar_width = u16::from(!self.layout.narrow);
+        let tab_bar_height = self.tab_bar_height();
         let main_area = Rect {
+            x: frame.area().x,
+            y: frame.area().y + tab_bar_height,
+            width: frame.area().width.saturating_sub(scrollbar_width),
+            height: frame.area().height.saturating_sub(2 + tab_bar_height),
+        };
+
+        let tab_bar_area = Rect {
             x: frame.area().x,
             y: frame.area().y,
-            width: frame.area().width.saturating_sub(1), // Reserve rightmost column for scrollbar
-            height: frame.area().height.saturating_sub(2),
+            width: frame.area().width,
+            height: tab_bar_height,
         };
 
         let breadcrumb_area = Rect {
@@ -37,28 +72,26 @@ impl<'a> InteractiveState<'a> {
         } else {
             // Normal mode or DevLog mode - both render self.document.document
             // (DevLog has already swapped in its document)
-            // Clear main area with theme background
-            for y in 0..main_area.height {
-                for x in 0..main_area.width {
-                    frame
-                        .buffer_mut()
-                        .cell_mut((x, y))
-                        .unwrap()
-                        .set_style(self.theme.document_bg_style);
+            if tab_bar_height == 0 {
+                self.render_content(main_area, frame.buffer_mut());
+            } else {
+                // Document rendering works in coordinates starting at the top of the screen,
+                // so render below the tab bar by way of an offscreen buffer
+                let content_area = Rect { y: 0, ..main_area };
+                let mut content = Buffer::empty(content_area);
+                self.render_content(content_area, &mut content);
+                for y in 0..content_area.height {
+                    for x in 0..content_area.width {
+                        if let Some(cell) = content.cell((x, y))
+                            && let Some(target) = frame.buffer_mut().cell_mut((x, y + main_area.y))
+                        {
+                            *target = cell.clone();
+                        }
+                    }
                 }
-            }
-
-            // Store viewport height for scroll clamping
-            self.viewport.last_viewport_height = main_area.height;
-
-            // Reset layout state for this frame
-            self.layout.pos = Position::default();
-            self.layout.indent = 0;
-            self.layout.node_path = NodePath::new();
-            self.layout.area = main_area;
 
-            // Render main document (will update cache if needed)
-            self.render_document(main_area, frame.buffer_mut());
+                self.render_tab_bar(frame.buffer_mut(), tab_bar_area);
+            }
 
             // Render breadcrumb bar or loading animation
             if self.loading.pending_request {
@@ -66,16 +99,21 @@ impl<'a> InteractiveState<'a> {
                 self.render_loading_bar(frame.buffer_mut(), breadcrumb_area);
             } else {
                 // Show normal breadcrumb/history bar
-                self.document
-                    .history
-                    .render(frame.buffer_mut(), breadcrumb_area, &self.theme);
+                self.document.history.render(
+                    frame.buffer_mut(),
+                    breadcrumb_area,
+                    &self.theme,
+                    self.render_context.show_decorations(),
+                );
             }
 
             // Render status bar
             self.render_status_bar(frame.buffer_mut(), status_area);
 
             // Render scrollbar if we have cached layout information
-            if let Some(layout_cache) = self.viewport.cached_layout {
+            if let Some(layout_cache) = self.viewport.cached_layout
+                && !self.layout.narrow
+            {
                 self.render_scrollbar(frame.buffer_mut(), main_area, layout_cache.document_height);
             }
 
@@ -84,6 +122,78 @@ impl<'a> InteractiveState<'a> {
                 let area = frame.area();
                 self.render_theme_picker(frame.buffer_mut(), area, selected_index);
             }
+
+            if matches!(self.ui_mode, UiMode::CratePicker { .. }) {
+                let area = frame.area();
+                self.render_crate_picker(frame.buffer_mut(), area);
+            }
+
+            // Render example output overlay if an example run just finished
+            if matches!(self.ui_mode, UiMode::ExampleOutput { .. }) {
+                let area = frame.area();
+                self.render_example_output(frame.buffer_mut(), area);
+            }
+
+            if matches!(self.ui_mode, UiMode::Messages { .. }) {
+                let area = frame.area();
+                self.render_messages(frame.buffer_mut(), area);
+            } else {
+                self.render_toasts(frame.buffer_mut(), main_area);
+            }
+        }
+    }
+
+    /// Ask for a wider terminal, in place of a layout that can't fit
+    fn render_too_narrow(&mut self, buf: &mut Buffer, area: Rect) {
+        // Nothing is clickable while the document isn't shown
+        self.render_cache.actions.clear();
+
+        let message = format!(
+            "ferritin needs a terminal at least {MIN_WIDTH} columns wide. Widen the window, or \
+             press q to quit."
+        );
+        let vertical_padding = area.height.saturating_sub(4) / 2;
+        let lines = std::iter::repeat_n(Line::default(), vertical_padding.into())
+            .chain([Line::from(message)])
+            .collect::<Vec<_>>();
+
+        Paragraph::new(lines)
+            .style(self.theme.document_bg_style)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+
+    /// Clear the content area and render the current document into it
+    fn render_content(&mut self, area: Rect, buf: &mut Buffer) {
+        // Clear main area with theme background
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let cell = buf.cell_mut((x, y)).unwrap();
+                cell.reset();
+                cell.set_style(self.theme.document_bg_style);
+            }
+        }
+
+        // Store viewport height for scroll clamping
+        self.viewport.last_viewport_height = area.height;
+
+        // Reset layout state for this frame
+        self.layout.pos = Position::default();
+        self.layout.indent = 0;
+        self.layout.node_path = NodePath::new();
+        self.layout.area = area;
+
+        // Render main document (will update cache if needed), and again if it was laid out anew
+        // and scrolled to keep the same content on screen
+        if self.render_document(area, buf) {
+            self.render_content(area, buf);
+            return;
+        }
+        self.render_selection(buf, area);
+
+        if let UiMode::LinkHints { typed } = &self.ui_mode {
+            self.render_link_hints(buf, typed);
         }
     }
 }
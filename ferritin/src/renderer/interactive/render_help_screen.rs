@@ -1,6 +1,7 @@
 use ratatui::{buffer::Buffer, layout::Rect};
 
 use super::state::InteractiveState;
+use crate::key_bindings::Action;
 
 impl<'a> InteractiveState<'a> {
     /// Render help screen showing all available keybindings
@@ -18,49 +19,86 @@ impl<'a> InteractiveState<'a> {
             }
         }
 
-        let help_text = vec![
-            ("", "FERRITIN INTERACTIVE MODE - KEYBINDINGS", title_style),
-            ("", "", bg_style),
-            ("Navigation:", "", title_style),
-            ("  j, ↓, Ctrl+n", "Scroll down", key_style),
-            ("  k, ↑, Ctrl+p", "Scroll up", key_style),
-            ("  Ctrl+d, Ctrl+v, PgDn", "Page down", key_style),
-            ("  Ctrl+u, Alt+v, PgUp", "Page up", key_style),
-            ("  Home, Alt+<", "Jump to top", key_style),
-            ("  Shift+G, End, Alt+>", "Jump to bottom", key_style),
-            ("  ←, Backspace", "Navigate back in history", key_style),
-            ("  →", "Navigate forward in history", key_style),
-            ("", "", bg_style),
-            ("Commands:", "", title_style),
-            ("  g", "Go to item by path", key_style),
-            ("  s, /", "Search (scoped to current crate)", key_style),
+        // Keys for actions come from the active keymap, so remapped keys are listed
+        let key_bindings = self.render_context.key_bindings();
+        let binding = |action: Action| {
             (
+                format!("  {}", key_bindings.describe(action)),
+                action.description(),
+                key_style,
+            )
+        };
+        let line = |key: &str, desc, style| (key.to_string(), desc, style);
+
+        let help_text = vec![
+            line("", "FERRITIN INTERACTIVE MODE - KEYBINDINGS", title_style),
+            line("", "", bg_style),
+            line("Navigation:", "", title_style),
+            binding(Action::Down),
+            binding(Action::Up),
+            binding(Action::ScrollDown),
+            binding(Action::ScrollUp),
+            binding(Action::PageDown),
+            binding(Action::PageUp),
+            binding(Action::Recenter),
+            binding(Action::RecenterTop),
+            binding(Action::RecenterBottom),
+            binding(Action::Top),
+            binding(Action::Bottom),
+            binding(Action::Back),
+            binding(Action::Forward),
+            line("", "", bg_style),
+            line("Commands:", "", title_style),
+            binding(Action::Open),
+            binding(Action::NextLink),
+            binding(Action::PreviousLink),
+            binding(Action::Hints),
+            binding(Action::GoTo),
+            binding(Action::Search),
+            line(
                 "    Tab",
                 "  Toggle search scope (current/all crates)",
                 key_style,
             ),
-            ("  l", "List available crates", key_style),
-            ("  c", "Toggle source code display", key_style),
-            ("  t", "Select theme", key_style),
-            (
+            line("    ↑, ↓", "  Recall previous searches", key_style),
+            binding(Action::SaveSearch),
+            binding(Action::Home),
+            binding(Action::List),
+            binding(Action::Crates),
+            binding(Action::Source),
+            binding(Action::ToggleBlock),
+            binding(Action::NewTab),
+            binding(Action::NextTab),
+            binding(Action::PreviousTab),
+            binding(Action::CloseTab),
+            binding(Action::Themes),
+            binding(Action::Messages),
+            binding(Action::Command),
+            line(
                 "  Esc, Ctrl+g",
-                "Cancel input mode / Exit help / Quit",
+                "Cancel loading or input mode / Exit help / Quit",
+                key_style,
+            ),
+            line("", "", bg_style),
+            line("Mouse:", "", title_style),
+            binding(Action::Mouse),
+            line("  Click", "Navigate to item / Expand block", key_style),
+            line(
+                "  Click ❬▶ run❭",
+                "Compile and run a doc example",
                 key_style,
             ),
-            ("", "", bg_style),
-            ("Mouse:", "", title_style),
-            ("  m", "Toggle mouse mode (for text selection)", key_style),
-            ("  Click", "Navigate to item / Expand block", key_style),
-            ("  Hover", "Show preview in status bar", key_style),
-            ("  Scroll", "Scroll content", key_style),
-            ("", "", bg_style),
-            ("Help:", "", title_style),
-            ("  ?, h", "Show this help screen", key_style),
-            ("", "", bg_style),
-            ("Other:", "", title_style),
-            ("  q, Ctrl+c", "Quit", key_style),
-            ("", "", bg_style),
-            ("", "Press any key to close help", desc_style),
+            line("  Hover", "Show preview in status bar", key_style),
+            line("  Drag", "Select text and copy it", key_style),
+            line("  Scroll", "Scroll content", key_style),
+            line("", "", bg_style),
+            line("Help:", "", title_style),
+            binding(Action::Help),
+            line("", "", bg_style),
+            line("Other:", "", title_style),
+            binding(Action::Quit),
+            line("", "", bg_style),
+            line("", "Press any key to close help", desc_style),
         ];
 
         // Calculate maximum width for consistent formatting
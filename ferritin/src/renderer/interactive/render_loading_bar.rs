@@ -74,6 +74,18 @@ impl<'a> InteractiveState<'a> {
 
         const LINE_CHAR: char = '▂';
 
+        // Without decorations, show a steady bar instead of the moving wave
+        if !self.render_context.show_decorations() {
+            for x in 0..area.width {
+                let cell = buf.cell_mut((x, area.y)).unwrap();
+                cell.reset();
+                cell.set_char(LINE_CHAR);
+                cell.set_fg(loading_bg);
+                cell.set_bg(doc_bg);
+            }
+            return;
+        }
+
         // Render full-width animated bar with varying foreground color on document background
         for x in 0..area.width {
             let fg = animated_background(x, elapsed_ms, status_bg, loading_bg);
@@ -1,7 +1,10 @@
 use ratatui::{buffer::Buffer, layout::Rect, style::Modifier};
 
 use super::{state::InteractiveState, utils::find_paragraph_truncation_point};
-use crate::styled_string::{DocumentNode, HeadingLevel, ShowWhen, TruncationLevel, TuiAction};
+use crate::renderer::display_width;
+use crate::styled_string::{
+    DocumentNode, HeadingLevel, ShowWhen, Span, TruncationLevel, TuiAction,
+};
 
 // Truncated block borders are outdented (to the left of content) so that content
 // doesn't shift when expanding/collapsing the block. The border is purely decorative.
@@ -26,6 +29,7 @@ impl<'a> InteractiveState<'a> {
 
     /// Render a single node
     pub(super) fn render_node(&mut self, node: &DocumentNode<'a>, buf: &mut Buffer) {
+        self.track_scroll_anchor();
         match node {
             DocumentNode::Paragraph { spans } => {
                 // Block element: unconditionally position at indent
@@ -159,11 +163,22 @@ impl<'a> InteractiveState<'a> {
                 // Container: children handle their own spacing
             }
 
-            DocumentNode::CodeBlock { lang, code } => {
+            DocumentNode::CodeBlock {
+                lang,
+                code,
+                action,
+                line_numbers,
+            } => {
                 // Block element: unconditionally position at indent
                 self.layout.pos.x = self.layout.indent;
 
-                self.render_code_block(lang.as_deref(), code, buf);
+                self.render_code_block(
+                    lang.as_deref(),
+                    code,
+                    action.as_ref(),
+                    line_numbers.as_ref(),
+                    buf,
+                );
 
                 // Block element: increment y when done
                 self.layout.pos.y += 1;
@@ -194,8 +209,12 @@ impl<'a> InteractiveState<'a> {
                     && self.layout.pos.y < self.viewport.scroll_offset + self.layout.area.height
                 {
                     let rule_style = self.theme.muted_style;
-                    // Use a decorative pattern: ─── • ───
-                    let pattern = ['─', '─', '─', ' ', '•', ' '];
+                    // Use a decorative pattern: ─── • ───, or a plain line without decorations
+                    let pattern: &[char] = if self.render_context.show_decorations() {
+                        &['─', '─', '─', ' ', '•', ' ']
+                    } else {
+                        &['─']
+                    };
                     for c in 0..self.layout.area.width {
                         let ch = pattern[(c as usize) % pattern.len()];
                         if let Some(cell) =
@@ -211,6 +230,44 @@ impl<'a> InteractiveState<'a> {
                 self.layout.pos.y += 1;
             }
 
+            DocumentNode::Image { url, alt } => {
+                // Block element: unconditionally position at indent
+                self.layout.pos.x = self.layout.indent;
+                self.draw_blockquote_markers(buf);
+
+                let max_cols = self.layout.area.width.saturating_sub(self.layout.indent);
+                let Some((cols, rows)) = self.images.size(url, max_cols) else {
+                    // Shown as a link while it downloads, or if the terminal can't draw it
+                    self.render_span(&Span::image_link(url, alt), buf);
+                    self.layout.pos.y += 1;
+                    return;
+                };
+
+                let area = Rect::new(self.layout.pos.x, self.layout.pos.y, cols, rows);
+                // Images are only drawn whole, so one partly scrolled off is left out
+                let viewport_top = self.viewport.scroll_offset;
+                if area.y >= viewport_top && area.bottom() <= viewport_top + self.layout.area.height
+                {
+                    self.images.place(
+                        url,
+                        Rect {
+                            y: area.y - viewport_top,
+                            ..area
+                        },
+                    );
+                }
+                self.render_cache
+                    .actions
+                    .push((area, TuiAction::OpenUrl(url.clone())));
+
+                for _ in 1..rows {
+                    self.layout.pos.y += 1;
+                    self.draw_blockquote_markers(buf);
+                }
+                // Block element: increment y when done
+                self.layout.pos.y += 1;
+            }
+
             DocumentNode::BlockQuote { nodes } => {
                 // Add this blockquote's marker position to the stack
                 let marker_x = self.layout.indent;
@@ -245,7 +302,11 @@ impl<'a> InteractiveState<'a> {
                 // Block element: unconditionally position at indent
                 self.layout.pos.x = self.layout.indent;
 
-                self.render_table(header.as_deref(), rows, buf);
+                if self.layout.narrow {
+                    self.render_table_linear(header.as_deref(), rows, buf);
+                } else {
+                    self.render_table(header.as_deref(), rows, buf);
+                }
 
                 // Block element: increment y when done
                 self.layout.pos.y += 1;
@@ -404,14 +465,16 @@ impl<'a> InteractiveState<'a> {
                     let ellipsis_row = self.layout.pos.y;
 
                     // Check if hovered
-                    let is_hovered = self.viewport.cursor_pos.map_or_else(
-                        || false,
-                        |cursor_pos| {
-                            cursor_pos.y == ellipsis_row
-                                && cursor_pos.x >= border_col
-                                && cursor_pos.x < border_col + ellipsis_text.len() as u16
-                        },
-                    );
+                    let is_hovered = self.render_context.show_decorations()
+                        && self.viewport.cursor_pos.map_or_else(
+                            || false,
+                            |cursor_pos| {
+                                cursor_pos.y == ellipsis_row
+                                    && cursor_pos.x >= border_col
+                                    && cursor_pos.x
+                                        < border_col + display_width(ellipsis_text) as u16
+                            },
+                        );
 
                     let final_style = if is_hovered {
                         border_style.add_modifier(Modifier::REVERSED)
@@ -430,7 +493,12 @@ impl<'a> InteractiveState<'a> {
                     );
 
                     // Track the action with the current path
-                    let rect = Rect::new(border_col, ellipsis_row, ellipsis_text.len() as u16, 1);
+                    let rect = Rect::new(
+                        border_col,
+                        ellipsis_row,
+                        display_width(ellipsis_text) as u16,
+                        1,
+                    );
                     self.render_cache
                         .actions
                         .push((rect, TuiAction::ExpandBlock(self.layout.node_path)));
@@ -438,6 +506,12 @@ impl<'a> InteractiveState<'a> {
                     // Increment y to account for ellipsis line
                     self.layout.pos.y += 1;
                 }
+
+                self.render_cache.truncated_blocks.push((
+                    start_row..self.layout.pos.y,
+                    self.layout.node_path,
+                    *level,
+                ));
                 // Transparent container: no additional spacing
             }
 
@@ -456,7 +530,10 @@ impl<'a> InteractiveState<'a> {
                         if idx > 0 {
                             self.layout.pos.y += 1;
                         }
+                        let saved_path = self.layout.node_path;
+                        self.layout.node_path.push(idx);
                         self.render_node(node, buf);
+                        self.layout.node_path = saved_path;
                     }
                 }
                 // Transparent container: no additional spacing
@@ -1,6 +1,8 @@
 use ratatui::{buffer::Buffer, layout::Rect, style::Modifier};
+use unicode_segmentation::UnicodeSegmentation;
 
 use super::state::InteractiveState;
+use crate::renderer::{display_width, grapheme_width};
 use crate::styled_string::Span;
 
 impl<'a> InteractiveState<'a> {
@@ -47,7 +49,7 @@ impl<'a> InteractiveState<'a> {
                 _ => false,
             };
 
-            mouse_hover || keyboard_focus
+            (mouse_hover && self.render_context.show_decorations()) || keyboard_focus
         } else {
             false
         };
@@ -197,11 +199,6 @@ impl<'a> InteractiveState<'a> {
     }
 }
 
-/// Calculate the display width of text, accounting for tabs rendered as 4 spaces
-fn display_width(text: &str) -> usize {
-    text.chars().map(|ch| if ch == '\t' { 4 } else { 1 }).sum()
-}
-
 /// Find the best position to wrap text within a given width
 /// Returns the position after which to break, or None if no good break point exists
 fn find_wrap_position(text: &str, max_width: usize) -> Option<usize> {
@@ -209,16 +206,17 @@ fn find_wrap_position(text: &str, max_width: usize) -> Option<usize> {
         return None;
     }
 
-    // Find the byte position that corresponds to max_width display columns (accounting for tabs)
+    // Find the byte position that corresponds to max_width display columns (accounting for
+    // tabs and wide characters)
     let mut display_cols = 0;
     let mut search_end = 0;
-    for (idx, ch) in text.char_indices() {
-        let char_width = if ch == '\t' { 4 } else { 1 };
-        if display_cols + char_width > max_width {
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        let width = grapheme_width(grapheme);
+        if display_cols + width > max_width {
             break;
         }
-        display_cols += char_width;
-        search_end = idx + ch.len_utf8();
+        display_cols += width;
+        search_end = idx + grapheme.len();
     }
 
     if search_end == 0 {
@@ -254,14 +252,13 @@ fn find_wrap_position(text: &str, max_width: usize) -> Option<usize> {
 
     // Third priority: break at word boundaries (after lowercase before uppercase)
     // This helps with camelCase or PascalCase identifiers
-    for i in (1..search_range.len()).rev() {
-        let chars: Vec<char> = search_range.chars().collect();
-        if i < chars.len() - 1 {
-            let prev = chars[i - 1];
-            let curr = chars[i];
-            if prev.is_lowercase() && curr.is_uppercase() {
-                return Some(i);
-            }
+    let pairs = search_range
+        .char_indices()
+        .zip(search_range.chars().skip(1))
+        .collect::<Vec<_>>();
+    for &((idx, prev), curr) in pairs.iter().rev().skip(1) {
+        if prev.is_lowercase() && curr.is_uppercase() {
+            return Some(idx + prev.len_utf8());
         }
     }
 
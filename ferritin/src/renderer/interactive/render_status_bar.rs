@@ -4,6 +4,8 @@ use super::{
     render_document::BASELINE_LEFT_MARGIN,
     state::{InputMode, InteractiveState, UiMode},
 };
+use crate::key_bindings::keys_label;
+use crate::renderer::{display_width, truncate_to_width};
 
 impl<'a> InteractiveState<'a> {
     /// Render status bar at the bottom of the screen
@@ -17,48 +19,78 @@ impl<'a> InteractiveState<'a> {
             buf.cell_mut((x, area.y)).unwrap().set_style(style);
         }
 
-        // Determine what to display based on UI mode
-        let (display_text, hint_text) = match &self.ui_mode {
-            UiMode::Normal | UiMode::Help | UiMode::DevLog { .. } | UiMode::ThemePicker { .. } => {
-                (self.ui.debug_message.clone(), None)
-            }
+        // While loading, a slow download or build says what it is doing. Otherwise, what to
+        // display depends on the UI mode
+        let task = if self.loading.pending_request {
+            self.loading.tasks.current()
+        } else {
+            None
+        };
+        let (display_text, hint_text) = if let Some(task) = task {
+            (task.into(), Some("[esc] cancel"))
+        } else {
+            match &self.ui_mode {
+                UiMode::Normal if !self.ui.pending_keys.is_empty() => (
+                    format!("{} …", keys_label(&self.ui.pending_keys)).into(),
+                    Some("[esc] cancel"),
+                ),
+
+                UiMode::Normal if self.loading.pending_request => {
+                    (self.ui.debug_message.clone(), Some("[esc] cancel"))
+                }
 
-            _ if self.loading.pending_request => (self.ui.debug_message.clone(), None),
+                UiMode::Normal
+                | UiMode::Help
+                | UiMode::DevLog { .. }
+                | UiMode::ThemePicker { .. }
+                | UiMode::ExampleOutput { .. }
+                | UiMode::Messages { .. }
+                | UiMode::CratePicker { .. } => (self.ui.debug_message.clone(), None),
 
-            UiMode::Input(InputMode::GoTo { buffer }) => {
-                (format!("Go to: {}", buffer).into(), None)
-            }
-            UiMode::Input(InputMode::Search {
-                buffer, all_crates, ..
-            }) => {
-                // Get current crate name for search scope display
-                let current_crate = self
-                    .document
-                    .history
-                    .current()
-                    .and_then(|entry| entry.crate_name());
+                _ if self.loading.pending_request => (self.ui.debug_message.clone(), None),
 
-                let scope = if *all_crates {
-                    "all crates".to_string()
-                } else {
-                    current_crate
-                        .map(|c| c.to_string())
-                        .unwrap_or_else(|| "current crate".to_string())
-                };
+                UiMode::LinkHints { typed } => {
+                    (format!("Follow link: {typed}").into(), Some("[esc] cancel"))
+                }
+                UiMode::Input(InputMode::GoTo { buffer }) => {
+                    (format!("Go to: {}", buffer).into(), None)
+                }
+                UiMode::Input(InputMode::SaveSearch { buffer }) => {
+                    (format!("Save search as: {}", buffer).into(), None)
+                }
+                UiMode::Input(InputMode::Command { buffer }) => (format!(":{buffer}").into(), None),
+                UiMode::Input(InputMode::Search {
+                    buffer, all_crates, ..
+                }) => {
+                    // Get current crate name for search scope display
+                    let current_crate = self
+                        .document
+                        .history
+                        .current()
+                        .and_then(|entry| entry.crate_name());
 
-                // Only show toggle hint if there's a crate to toggle to
-                let hint = if current_crate.is_some() {
-                    Some("[tab] toggle scope")
-                } else {
-                    None
-                };
+                    let scope = if *all_crates {
+                        "all crates".to_string()
+                    } else {
+                        current_crate
+                            .map(|c| c.to_string())
+                            .unwrap_or_else(|| "current crate".to_string())
+                    };
 
-                (format!("Search in {}: {}", scope, buffer).into(), hint)
+                    // Only show toggle hint if there's a crate to toggle to
+                    let hint = if current_crate.is_some() {
+                        Some("[↑] history [tab] toggle scope")
+                    } else {
+                        Some("[↑] history")
+                    };
+
+                    (format!("Search in {}: {}", scope, buffer).into(), hint)
+                }
             }
         };
 
         // Calculate space for hint text (accounting for left margin)
-        let hint_len = hint_text.as_ref().map(|h| h.len()).unwrap_or(0);
+        let hint_len = hint_text.map(display_width).unwrap_or(0);
         let available_width = (area.width as usize).saturating_sub(BASELINE_LEFT_MARGIN as usize);
         let text_max_width = if hint_len > 0 {
             available_width.saturating_sub(hint_len + 2) // +2 for spacing
@@ -67,41 +99,28 @@ impl<'a> InteractiveState<'a> {
         };
 
         // Render main text (truncate if needed)
-        let truncated = if display_text.len() > text_max_width {
-            &display_text[..text_max_width]
-        } else {
-            &display_text
-        };
-
-        let mut col = BASELINE_LEFT_MARGIN;
-        for ch in truncated.chars() {
-            if col >= area.width {
-                break;
-            }
-            buf.cell_mut((col, area.y))
-                .unwrap()
-                .set_char(ch)
-                .set_style(style);
-            col += 1;
-        }
+        let truncated = truncate_to_width(&display_text, text_max_width);
+        buf.set_stringn(
+            BASELINE_LEFT_MARGIN,
+            area.y,
+            truncated,
+            available_width,
+            style,
+        );
 
         // Render right-justified hint text if present (within margin-adjusted area)
         if let Some(hint) = hint_text {
             let hint_start = area
                 .width
-                .saturating_sub(hint.len() as u16)
+                .saturating_sub(hint_len as u16)
                 .max(BASELINE_LEFT_MARGIN);
-            let mut hint_col = hint_start;
-            for ch in hint.chars() {
-                if hint_col >= area.width {
-                    break;
-                }
-                buf.cell_mut((hint_col, area.y))
-                    .unwrap()
-                    .set_char(ch)
-                    .set_style(hint_style);
-                hint_col += 1;
-            }
+            buf.set_stringn(
+                hint_start,
+                area.y,
+                hint,
+                area.width.saturating_sub(hint_start).into(),
+                hint_style,
+            );
         }
     }
 }
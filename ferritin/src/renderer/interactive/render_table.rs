@@ -4,9 +4,44 @@ use ratatui::{
 };
 
 use super::state::InteractiveState;
-use crate::styled_string::TableCell;
+use crate::renderer::{display_width, truncate_to_width};
+use crate::styled_string::{Span, TableCell};
 
 impl<'a> InteractiveState<'a> {
+    /// Render a table one row at a time, with each cell on its own line labelled by its
+    /// column header, for terminals too narrow for columns
+    pub(super) fn render_table_linear(
+        &mut self,
+        header: Option<&[TableCell<'a>]>,
+        rows: &[Vec<TableCell<'a>>],
+        buf: &mut Buffer,
+    ) {
+        for (row_idx, row_cells) in rows.iter().enumerate() {
+            // Blank line between rows
+            if row_idx > 0 {
+                self.layout.pos.y += 2;
+            }
+
+            for (col_idx, cell) in row_cells.iter().enumerate() {
+                if col_idx > 0 {
+                    self.layout.pos.y += 1;
+                }
+                self.layout.pos.x = self.layout.indent;
+
+                if let Some(header_cell) = header.and_then(|header| header.get(col_idx)) {
+                    for span in &header_cell.spans {
+                        self.render_span_with_modifier(span, Modifier::BOLD, buf);
+                    }
+                    self.render_span(&Span::plain(": "), buf);
+                }
+
+                for span in &cell.spans {
+                    self.render_span(span, buf);
+                }
+            }
+        }
+    }
+
     /// Render table with unicode borders
     pub(super) fn render_table(
         &mut self,
@@ -35,7 +70,11 @@ impl<'a> InteractiveState<'a> {
         // Measure header widths
         if let Some(header_cells) = header {
             for (col_idx, cell) in header_cells.iter().enumerate() {
-                let width = cell.spans.iter().map(|s| s.text.len()).sum::<usize>();
+                let width = cell
+                    .spans
+                    .iter()
+                    .map(|s| display_width(&s.text))
+                    .sum::<usize>();
                 col_widths[col_idx] = col_widths[col_idx].max(width);
             }
         }
@@ -44,7 +83,11 @@ impl<'a> InteractiveState<'a> {
         for row_cells in rows {
             for (col_idx, cell) in row_cells.iter().enumerate() {
                 if col_idx < num_cols {
-                    let width = cell.spans.iter().map(|s| s.text.len()).sum::<usize>();
+                    let width = cell
+                        .spans
+                        .iter()
+                        .map(|s| display_width(&s.text))
+                        .sum::<usize>();
                     col_widths[col_idx] = col_widths[col_idx].max(width);
                 }
             }
@@ -127,11 +170,9 @@ impl<'a> InteractiveState<'a> {
                     // Render cell content (bold for headers)
                     let mut cell_col = col_pos;
                     for span in &cell.spans {
-                        let span_text = if span.text.len() > col_widths[col_idx] {
-                            &span.text[..col_widths[col_idx]]
-                        } else {
-                            &span.text
-                        };
+                        let cell_width_left = (col_pos as usize + col_widths[col_idx])
+                            .saturating_sub(cell_col as usize);
+                        let span_text = truncate_to_width(&span.text, cell_width_left);
 
                         let mut style = self.style(span.style);
                         style = style.add_modifier(Modifier::BOLD);
@@ -144,7 +185,7 @@ impl<'a> InteractiveState<'a> {
                             self.layout.area,
                             style,
                         );
-                        cell_col += span_text.len() as u16;
+                        cell_col += display_width(span_text) as u16;
                     }
 
                     // Pad to column width
@@ -250,11 +291,9 @@ impl<'a> InteractiveState<'a> {
                     // Render cell content
                     let mut cell_col = col_pos;
                     for span in &cell.spans {
-                        let span_text = if span.text.len() > col_widths[col_idx] {
-                            &span.text[..col_widths[col_idx]]
-                        } else {
-                            &span.text
-                        };
+                        let cell_width_left = (col_pos as usize + col_widths[col_idx])
+                            .saturating_sub(cell_col as usize);
+                        let span_text = truncate_to_width(&span.text, cell_width_left);
 
                         let style = self.style(span.style);
                         self.write_text(
@@ -265,7 +304,7 @@ impl<'a> InteractiveState<'a> {
                             self.layout.area,
                             style,
                         );
-                        cell_col += span_text.len() as u16;
+                        cell_col += display_width(span_text) as u16;
                     }
 
                     // Pad to column width
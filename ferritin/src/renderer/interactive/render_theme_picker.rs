@@ -1,13 +1,13 @@
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Layout, Rect},
+    layout::Rect,
     style::{Modifier, Style},
     text::Line,
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Widget},
 };
 use std::borrow::Cow;
 
-use super::state::InteractiveState;
+use super::{state::InteractiveState, utils::centered_rect};
 use crate::render_context::RenderContext;
 use crate::styled_string::TuiAction;
 
@@ -53,10 +53,17 @@ impl<'a> InteractiveState<'a> {
             }
         }
 
-        // Create list items from theme names
+        // Create list items from theme names, marking the user's own themes
         let items: Vec<ListItem> = themes
             .iter()
-            .map(|theme_name| ListItem::new(Line::from(format!("  {}", theme_name))))
+            .map(|theme_name| {
+                let label = if RenderContext::is_user_theme(theme_name) {
+                    format!("  {theme_name} (user theme)")
+                } else {
+                    format!("  {theme_name}")
+                };
+                ListItem::new(Line::from(label))
+            })
             .collect();
 
         // Create list state for selection
@@ -133,20 +140,3 @@ impl<'a> InteractiveState<'a> {
         }
     }
 }
-
-/// Helper function to create a centered rect using up certain percentage of the available rect
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::vertical([
-        Constraint::Percentage((100 - percent_y) / 2),
-        Constraint::Percentage(percent_y),
-        Constraint::Percentage((100 - percent_y) / 2),
-    ])
-    .split(r);
-
-    Layout::horizontal([
-        Constraint::Percentage((100 - percent_x) / 2),
-        Constraint::Percentage(percent_x),
-        Constraint::Percentage((100 - percent_x) / 2),
-    ])
-    .split(popup_layout[1])[1]
-}
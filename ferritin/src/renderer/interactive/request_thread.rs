@@ -1,44 +1,86 @@
 //! Request thread - handles Navigator operations and document formatting
 
-use super::channels::{RequestResponse, UiCommand};
+use super::channels::{ExampleJob, RequestResponse, SentCommand, UiCommand};
+use super::crate_picker::CrateChoice;
 use super::history::HistoryEntry;
-use crate::commands::{list, search};
+use super::task_status::TaskStatus;
+use crate::commands::{api_diff, home, list, run_example, search};
+use crate::styled_string::TuiAction;
+use crate::workspace_history::WorkspaceHistory;
 use crate::{request::Request, styled_string::Document};
 use crossbeam_channel::{Receiver, Sender};
+use ferritin_common::search::SearchContext;
+use std::collections::BTreeSet;
 
 /// Request thread loop - processes commands from UI thread
 pub(super) fn request_thread_loop<'a>(
     request: &'a Request,
-    cmd_rx: Receiver<UiCommand<'a>>,
+    cmd_rx: Receiver<SentCommand<'a>>,
     resp_tx: Sender<RequestResponse<'a>>,
+    prefetch_tx: Sender<Vec<String>>,
+    example_tx: Sender<ExampleJob>,
+    tasks: &TaskStatus,
 ) {
-    for cmd in cmd_rx {
-        match cmd {
+    let mut workspace_history = WorkspaceHistory::for_request(request);
+    // Toggling source display only changes this thread's copy of the context
+    let mut format_context = request.format_context().clone();
+    let _ = resp_tx.send(RequestResponse::SearchHistory(
+        workspace_history.queries().to_vec(),
+    ));
+    let _ = resp_tx.send(RequestResponse::Crates(crate_choices(request)));
+
+    for SentCommand {
+        generation,
+        command,
+    } in cmd_rx
+    {
+        tasks.begin_command(generation);
+        match command {
             UiCommand::Navigate(doc_ref) => {
                 // Format the already-resolved item (e.g., from clicking a link)
-                let doc_nodes = request.format_item(doc_ref);
+                let doc_nodes = request.format_item_with(doc_ref, &format_context);
                 let doc = Document::from(doc_nodes);
+                let _ = prefetch_tx.send(linked_crates(request, &doc));
                 let entry = HistoryEntry::Item(doc_ref);
+                if let Some(path) = doc_ref.link_path() {
+                    workspace_history.record_viewed(&path);
+                }
 
-                let _ = resp_tx.send(RequestResponse::Document {
-                    doc,
-                    entry: Some(entry),
-                });
+                respond(
+                    request,
+                    &resp_tx,
+                    RequestResponse::Document {
+                        doc,
+                        entry: Some(entry),
+                    },
+                );
             }
 
             UiCommand::NavigateToPath(path) => {
                 let mut suggestions = vec![];
                 if let Some(item) = request.resolve_path(path.as_ref(), &mut suggestions) {
-                    let doc_nodes = request.format_item(item);
+                    let doc_nodes = request.format_item_with(item, &format_context);
                     let doc = Document::from(doc_nodes);
+                    let _ = prefetch_tx.send(linked_crates(request, &doc));
                     let entry = HistoryEntry::Item(item);
+                    if let Some(path) = item.link_path() {
+                        workspace_history.record_viewed(&path);
+                    }
 
-                    let _ = resp_tx.send(RequestResponse::Document {
-                        doc,
-                        entry: Some(entry),
-                    });
+                    respond(
+                        request,
+                        &resp_tx,
+                        RequestResponse::Document {
+                            doc,
+                            entry: Some(entry),
+                        },
+                    );
                 } else {
-                    let _ = resp_tx.send(RequestResponse::Error(format!("Not found: {}", path)));
+                    respond(
+                        request,
+                        &resp_tx,
+                        RequestResponse::Error(format!("Not found: {}", path)),
+                    );
                 }
             }
 
@@ -46,49 +88,126 @@ pub(super) fn request_thread_loop<'a>(
                 query,
                 crate_name,
                 limit,
+                mode,
+                context,
             } => {
                 let (search_doc, _is_error) = search::execute(
                     request,
                     query.as_ref(),
                     limit,
                     crate_name.as_ref().map(|c| c.as_ref()),
+                    mode,
+                    context.map(SearchContext::new),
                 );
 
+                workspace_history.record_search(&query);
+
                 // Always create history entry for searches
                 let entry = HistoryEntry::Search {
                     query: query.into_owned(),
                     crate_name: crate_name.map(|c| c.into_owned()),
+                    mode,
                 };
 
-                let _ = resp_tx.send(RequestResponse::Document {
-                    doc: search_doc,
-                    entry: Some(entry),
-                });
+                respond(
+                    request,
+                    &resp_tx,
+                    RequestResponse::Document {
+                        doc: search_doc,
+                        entry: Some(entry),
+                    },
+                );
+
+                let _ = resp_tx.send(RequestResponse::SearchHistory(
+                    workspace_history.queries().to_vec(),
+                ));
+            }
+
+            UiCommand::SaveSearch {
+                name,
+                query,
+                crate_name,
+            } => {
+                workspace_history.save(&name, &query, crate_name.as_deref());
             }
 
             UiCommand::List => {
-                let (list_doc, _is_error, default_crate) = list::execute(request);
+                let (list_doc, _is_error, default_crate) =
+                    list::execute(request, list::ListOptions::default());
                 let entry = HistoryEntry::List { default_crate };
 
-                let _ = resp_tx.send(RequestResponse::Document {
-                    doc: list_doc,
-                    entry: Some(entry),
-                });
+                respond(
+                    request,
+                    &resp_tx,
+                    RequestResponse::Document {
+                        doc: list_doc,
+                        entry: Some(entry),
+                    },
+                );
+            }
+
+            UiCommand::Home => {
+                respond(
+                    request,
+                    &resp_tx,
+                    RequestResponse::Document {
+                        doc: home::execute(request),
+                        entry: Some(HistoryEntry::Home),
+                    },
+                );
             }
 
             UiCommand::ToggleSource {
                 include_source,
                 current_item,
             } => {
-                request.format_context().set_include_source(include_source);
+                format_context = format_context.with_include_source(include_source);
                 if let Some(current_item) = current_item {
-                    let _ = resp_tx.send(RequestResponse::Document {
-                        doc: Document::from(request.format_item(current_item)),
-                        entry: None,
-                    });
+                    respond(
+                        request,
+                        &resp_tx,
+                        RequestResponse::Document {
+                            doc: Document::from(
+                                request.format_item_with(current_item, &format_context),
+                            ),
+                            entry: None,
+                        },
+                    );
+                }
+            }
+
+            UiCommand::RunExample { doc_ref, index } => {
+                let title = match doc_ref.path() {
+                    Some(path) => format!("Example {index} of {path}"),
+                    None => format!("Example {index}"),
+                };
+
+                // Cargo runs on the example thread, so that navigating doesn't wait for the
+                // example to build
+                match run_example::find_example(doc_ref, index) {
+                    Ok(example) => {
+                        let project = run_example::prepare_project(request, doc_ref, &example);
+                        let _ = example_tx.send(ExampleJob {
+                            title: title.clone(),
+                            project,
+                            example,
+                        });
+                        respond(request, &resp_tx, RequestResponse::ExampleStarted { title });
+                    }
+                    Err(message) => respond(request, &resp_tx, RequestResponse::Error(message)),
                 }
             }
 
+            UiCommand::ApiDiff { crate_name, from } => {
+                let from = semver::Version::parse(&from).ok();
+                let (doc, _is_error) = api_diff::execute(request, &crate_name, from.as_ref());
+                respond(
+                    request,
+                    &resp_tx,
+                    RequestResponse::Document { doc, entry: None },
+                );
+            }
+
             UiCommand::Shutdown => {
                 let _ = resp_tx.send(RequestResponse::ShuttingDown);
                 break;
@@ -96,3 +215,66 @@ pub(super) fn request_thread_loop<'a>(
         }
     }
 }
+
+/// Send the response to a command, unless the UI stopped waiting for it
+pub(super) fn respond<'a>(
+    request: &Request,
+    resp_tx: &Sender<RequestResponse<'a>>,
+    response: RequestResponse<'a>,
+) {
+    if !request.progress().is_cancelled() {
+        let _ = resp_tx.send(response);
+    }
+}
+
+/// The crates for the crate switcher, sorted by name, with a choice for each version of a
+/// crate that is in the dependency graph more than once
+fn crate_choices(request: &Request) -> Vec<CrateChoice> {
+    let mut available = request.list_available_crates().collect::<Vec<_>>();
+    available.sort_by(|a, b| a.name().cmp(b.name()));
+
+    let mut choices = vec![];
+    for crate_info in available {
+        let other_versions = request
+            .local_source()
+            .map_or(&[][..], |local| local.other_versions(crate_info.name()));
+        let versions = std::iter::once((crate_info.name().to_string(), crate_info)).chain(
+            other_versions.iter().map(|other| {
+                let version = other.version().map(|v| format!("@{v}")).unwrap_or_default();
+                (format!("{}{version}", other.name()), other)
+            }),
+        );
+        for (path, crate_info) in versions {
+            choices.push(CrateChoice {
+                name: crate_info.name().to_string(),
+                path,
+                version: crate_info.version().map(ToString::to_string),
+                provenance: list::provenance_label(crate_info.provenance()),
+            });
+        }
+    }
+    choices
+}
+
+/// The crates that `doc` links to by path, so that they can be prefetched while the page is
+/// being read
+///
+/// Only std and local crates are considered: looking up other names could mean a request to
+/// docs.rs for every intra-doc link.
+fn linked_crates(request: &Request, doc: &Document<'_>) -> Vec<String> {
+    let available = request
+        .list_available_crates()
+        .map(|crate_info| crate_info.name().replace('-', "_"))
+        .collect::<BTreeSet<_>>();
+
+    let mut linked = BTreeSet::new();
+    doc.visit_actions(&mut |action| {
+        if let TuiAction::NavigateToPath { path, .. } = action
+            && let Some(crate_name) = path.split("::").next()
+            && available.contains(&crate_name.replace('-', "_"))
+        {
+            linked.insert(crate_name.to_string());
+        }
+    });
+    linked.into_iter().collect()
+}
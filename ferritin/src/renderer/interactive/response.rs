@@ -1,3 +1,4 @@
+use crate::renderer::emoji;
 use crate::renderer::interactive::UiMode;
 
 use super::InteractiveState;
@@ -22,11 +23,50 @@ impl<'a> InteractiveState<'a> {
     /// Handle a single response from the request thread
     /// Returns true if the UI should exit
     pub fn handle_response(&mut self, response: RequestResponse<'a>) -> bool {
+        // Search history arrives alongside other responses, so it doesn't end a pending request
+        if let RequestResponse::SearchHistory(queries) = response {
+            self.ui.search_history = queries;
+            return false;
+        }
+        if let RequestResponse::Crates(crates) = response {
+            self.ui.crates = crates;
+            return false;
+        }
+
+        // Images arrive in the background too
+        if let RequestResponse::Image { url, data } = response {
+            if self.images.loaded(url, data) {
+                self.viewport.cached_layout = None;
+            }
+            return false;
+        }
+
+        // So does the result of an example run, which can finish long after other pages were
+        // asked for
+        if let RequestResponse::ExampleOutput { title, run } = response {
+            // A prompt or picker that was opened meanwhile keeps the screen
+            if matches!(self.ui_mode, UiMode::Normal) {
+                self.ui.debug_message = format!("{title}: {}", run.summary).into();
+                self.ui_mode = UiMode::ExampleOutput {
+                    title,
+                    run,
+                    scroll: 0,
+                };
+            } else {
+                self.notify(format!("{title}: {}", run.summary));
+            }
+            return false;
+        }
+
         self.loading.pending_request = false;
         match response {
-            RequestResponse::Document { doc, entry } => {
+            RequestResponse::Document { mut doc, entry } => {
+                if !self.render_context.show_decorations() {
+                    emoji::strip(&mut doc);
+                }
                 self.document.document = doc;
                 self.set_scroll_offset(0);
+                self.viewport.selection = None;
                 // Invalidate layout cache when document changes
                 self.viewport.cached_layout = None;
                 // Reset keyboard cursor to virtual top when navigating to new document
@@ -36,14 +76,26 @@ impl<'a> InteractiveState<'a> {
                 if let Some(new_entry) = entry {
                     self.document.history.push(new_entry);
                 }
+                // Keep the sections expanded that were the last time this page was shown
+                self.restore_expanded_blocks();
                 false
             }
 
             RequestResponse::Error(err) => {
-                self.ui.debug_message = err.into();
+                self.notify_error(err);
                 false
             }
 
+            RequestResponse::ExampleStarted { title } => {
+                self.ui.debug_message = format!("Building {title}...").into();
+                false
+            }
+
+            RequestResponse::SearchHistory(_)
+            | RequestResponse::Crates(_)
+            | RequestResponse::Image { .. }
+            | RequestResponse::ExampleOutput { .. } => false,
+
             RequestResponse::ShuttingDown => true,
         }
     }
@@ -0,0 +1,150 @@
+//! Selecting text in the document by dragging the mouse, and copying it
+//!
+//! Mouse capture keeps the terminal from selecting text itself, so a drag selects in the
+//! document instead, and letting go copies the selection with OSC 52, which asks the terminal
+//! to put text on the system clipboard.
+
+use std::io::{self, Write};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+};
+
+use super::images::base64;
+use super::state::InteractiveState;
+use crate::renderer::display_width;
+
+/// Text selected by dragging, between two positions in document coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Selection {
+    /// Where the drag started
+    pub anchor: Position,
+    /// Where the mouse is, or was let go
+    pub head: Position,
+    /// Whether the mouse was let go and the text is yet to be copied
+    pub copy: bool,
+}
+
+impl Selection {
+    /// The ends of the selection in reading order
+    fn ordered(&self) -> (Position, Position) {
+        let key = |position: Position| (position.y, position.x);
+        if key(self.anchor) <= key(self.head) {
+            (self.anchor, self.head)
+        } else {
+            (self.head, self.anchor)
+        }
+    }
+
+    /// Whether the cell at `position` is selected: the rows between the ends are selected
+    /// whole, like in a terminal
+    pub(super) fn contains(&self, position: Position) -> bool {
+        let (start, end) = self.ordered();
+        let key = (position.y, position.x);
+        (start.y, start.x) <= key && key <= (end.y, end.x)
+    }
+}
+
+impl<'a> InteractiveState<'a> {
+    /// Highlight the selection, and copy it once the mouse is let go
+    pub(super) fn render_selection(&mut self, buf: &mut Buffer, area: Rect) {
+        let Some(selection) = self.viewport.selection else {
+            return;
+        };
+        let top = self.viewport.scroll_offset;
+        for y in 0..area.height {
+            for x in 0..area.width {
+                if selection.contains(Position::new(x, y + top))
+                    && let Some(cell) = buf.cell_mut((area.x + x, area.y + y))
+                {
+                    cell.set_style(Style::new().add_modifier(Modifier::REVERSED));
+                }
+            }
+        }
+
+        if selection.copy {
+            let text = selected_text(buf, area, top, selection);
+            if let Some(selection) = &mut self.viewport.selection {
+                selection.copy = false;
+            }
+            if text.is_empty() {
+                return;
+            }
+            let lines = text.lines().count();
+            self.ui.clipboard = Some(text);
+            self.notify_success(if lines == 1 {
+                "Copied 1 line".to_string()
+            } else {
+                format!("Copied {lines} lines")
+            });
+        }
+    }
+}
+
+/// The selected text that's on screen, a line per row without trailing spaces
+fn selected_text(buf: &Buffer, area: Rect, top: u16, selection: Selection) -> String {
+    let mut lines = vec![];
+    for y in 0..area.height {
+        let mut line = String::new();
+        let mut selected = false;
+        let mut x = 0;
+        while x < area.width {
+            let Some(cell) = buf.cell((area.x + x, area.y + y)) else {
+                break;
+            };
+            if selection.contains(Position::new(x, y + top)) {
+                line.push_str(cell.symbol());
+                selected = true;
+            }
+            // A wide character covers the cells after it too
+            x += display_width(cell.symbol()).max(1) as u16;
+        }
+        if selected {
+            lines.push(line.trim_end().to_string());
+        }
+    }
+    lines.join("\n")
+}
+
+/// Ask the terminal to put `text` on the clipboard, with OSC 52
+pub(super) fn copy_to_clipboard(out: &mut impl Write, text: &str) -> io::Result<()> {
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_runs_in_reading_order() {
+        let selection = Selection {
+            anchor: Position::new(5, 3),
+            head: Position::new(2, 1),
+            copy: false,
+        };
+        assert!(selection.contains(Position::new(2, 1)));
+        assert!(selection.contains(Position::new(70, 2)));
+        assert!(selection.contains(Position::new(5, 3)));
+        assert!(!selection.contains(Position::new(1, 1)));
+        assert!(!selection.contains(Position::new(6, 3)));
+    }
+
+    #[test]
+    fn selected_text_is_read_from_the_screen() {
+        let area = Rect::new(0, 0, 10, 3);
+        let mut buf = Buffer::empty(area);
+        buf.set_string(0, 0, "first", Style::new());
+        buf.set_string(0, 1, "second", Style::new());
+        buf.set_string(0, 2, "third", Style::new());
+        // Rows 10 to 12 of the document are on screen
+        let selection = Selection {
+            anchor: Position::new(2, 10),
+            head: Position::new(2, 11),
+            copy: true,
+        };
+        assert_eq!(selected_text(&buf, area, 10, selection), "rst\nsec");
+    }
+}
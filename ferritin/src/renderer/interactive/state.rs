@@ -1,14 +1,25 @@
 use ratatui::layout::{Position, Rect};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
 use std::time::Instant;
 
-use super::channels::{RequestResponse, UiCommand};
+use super::channels::{RequestResponse, SentCommand, UiCommand};
+use super::crate_picker::CrateChoice;
 use super::history::{History, HistoryEntry};
+use super::images::{GraphicsProtocol, Images};
+use super::selection::Selection;
+use super::task_status::TaskStatus;
 use super::theme::InteractiveTheme;
+use super::toasts::Toasts;
 use super::utils::supports_cursor_shape;
+use crate::commands::run_example::ExampleRun;
+use crate::key_bindings::Key;
 use crate::logging::LogReader;
 use crate::render_context::{RenderContext, ThemeError};
-use crate::styled_string::{Document, NodePath, TuiAction};
+use crate::renderer::emoji;
+use crate::styled_string::{Document, NodePath, TruncationLevel, TuiAction};
 use crossbeam_channel::{Receiver, Sender};
 
 /// UI mode - makes the modal structure of the interface explicit
@@ -33,6 +44,30 @@ pub(super) enum UiMode<'a> {
         /// Theme name to restore on cancel
         saved_theme_name: String,
     },
+    /// Result pane showing the output of a doc example run
+    ExampleOutput {
+        title: String,
+        run: ExampleRun,
+        /// Scroll offset within the pane
+        scroll: u16,
+    },
+    /// Message history, listing every toast shown this session
+    Messages {
+        /// Scroll offset within the pane
+        scroll: u16,
+    },
+    /// Link hints (f pressed) - type a link's hint to follow it
+    LinkHints {
+        /// The start of a hint typed so far
+        typed: String,
+    },
+    /// Crate switcher modal (C pressed) - type some of a crate's name to open it
+    CratePicker {
+        /// What has been typed to narrow the list
+        filter: String,
+        /// Index of the selected crate among those matching `filter`
+        selected_index: usize,
+    },
 }
 
 /// Input mode with mode-specific state
@@ -42,6 +77,10 @@ pub(super) enum InputMode {
     GoTo { buffer: String },
     /// Search mode (s pressed) - search for items
     Search { buffer: String, all_crates: bool },
+    /// Save-search mode (S pressed on search results) - name the current search
+    SaveSearch { buffer: String },
+    /// Command mode (: pressed) - run a command like `messages`
+    Command { buffer: String },
 }
 
 /// Document and navigation state
@@ -51,6 +90,23 @@ pub(super) struct DocumentState<'a> {
     pub history: History<'a>,
 }
 
+/// A tab that isn't being displayed
+#[derive(Debug)]
+pub(super) struct Tab<'a> {
+    pub document: DocumentState<'a>,
+    pub scroll_offset: u16,
+}
+
+/// Open tabs, in display order
+///
+/// The active tab's document and history live in `InteractiveState::document` while it is
+/// displayed, leaving its slot here empty; switching tabs swaps them.
+#[derive(Debug)]
+pub(super) struct TabState<'a> {
+    pub tabs: Vec<Option<Tab<'a>>>,
+    pub active: usize,
+}
+
 /// Cached document layout information
 #[derive(Debug, Clone, Copy)]
 pub(super) struct DocumentLayoutCache {
@@ -93,6 +149,10 @@ pub(super) struct ViewportState {
     pub scroll_offset: u16,
     pub cursor_pos: Option<Position>,
     pub clicked_position: Option<Position>,
+    /// Where the mouse button went down, to click there when it's let go without a drag
+    pub pressed_position: Option<Position>,
+    /// Text selected by dragging the mouse
+    pub selection: Option<Selection>,
     pub cached_layout: Option<DocumentLayoutCache>,
     /// Last known viewport height for scroll clamping
     pub last_viewport_height: u16,
@@ -101,12 +161,18 @@ pub(super) struct ViewportState {
     pub scrollbar_dragging: bool,
     /// Keyboard navigation cursor
     pub keyboard_cursor: KeyboardCursor,
+    /// The node at the top of the screen and how many of its rows are scrolled past, to keep
+    /// it there when the document is laid out again
+    pub scroll_anchor: Option<(NodePath, u16)>,
 }
 
 /// Rendering state computed each frame
 #[derive(Debug)]
 pub(super) struct RenderCache<'a> {
     pub actions: Vec<(Rect, TuiAction<'a>)>,
+    /// Rows each truncated block takes, with its path and level, to find the one under the
+    /// cursor
+    pub truncated_blocks: Vec<(Range<u16>, NodePath, TruncationLevel)>,
 }
 
 /// UI display state
@@ -117,6 +183,21 @@ pub(super) struct UiState {
     pub is_hovering: bool,
     pub supports_cursor: bool,
     pub include_source: bool,
+    /// Past search queries, oldest first, for recall with ↑/↓ in search mode
+    pub search_history: Vec<String>,
+    /// The crates offered by the crate switcher, sent by the request thread when it starts
+    pub crates: Vec<CrateChoice>,
+    /// Position in `search_history` while recalling, or None when editing a new query
+    pub search_history_cursor: Option<usize>,
+    /// Keys typed so far of a key sequence like `g t`
+    pub pending_keys: Vec<Key>,
+    /// Notifications on screen, and the history of all of them
+    pub toasts: Toasts,
+    /// What the focused or hovered link does, as last announced, so that it's announced once
+    /// rather than on every frame
+    pub hover_message: Option<String>,
+    /// Selected text to put on the clipboard after the next frame is drawn
+    pub clipboard: Option<String>,
 }
 
 /// Request/response tracking state
@@ -125,6 +206,8 @@ pub(super) struct LoadingState {
     pub pending_request: bool,
     pub was_loading: bool,
     pub started_at: Instant,
+    /// What the request thread is busy with, for the status bar
+    pub tasks: Arc<TaskStatus>,
 }
 
 impl LoadingState {
@@ -132,6 +215,12 @@ impl LoadingState {
         self.pending_request = true;
         self.started_at = Instant::now();
     }
+
+    /// Stop waiting for the request thread, which gives up on any download under way
+    pub fn cancel(&mut self) {
+        self.pending_request = false;
+        self.tasks.cancel();
+    }
 }
 
 /// Layout state - cursor position, indentation, and viewport
@@ -145,21 +234,32 @@ pub(super) struct LayoutState {
     /// Stack of x positions where blockquote markers should be drawn
     /// When rendering content, markers are drawn at each of these positions
     pub blockquote_markers: Vec<u16>,
+    /// Whether the terminal is narrower than `NARROW_WIDTH`, for the compact layout
+    pub narrow: bool,
+    /// The innermost node starting at or above the top of the screen, and how far above
+    pub top_node: Option<(NodePath, u16)>,
+    /// Where the node in `ViewportState::scroll_anchor` starts in this layout
+    pub anchor_row: Option<u16>,
 }
 
 /// Main interactive state - composes all UI state
 #[derive(Debug)]
 pub(super) struct InteractiveState<'a> {
     pub document: DocumentState<'a>,
+    pub tabs: TabState<'a>,
     pub viewport: ViewportState,
     pub render_cache: RenderCache<'a>,
     pub layout: LayoutState,
     pub ui_mode: UiMode<'a>,
     pub ui: UiState,
     pub loading: LoadingState,
+    pub images: Images,
+    /// Truncated blocks expanded this session, by page and path, with the level each was at
+    /// before, so that they're expanded again when the page is shown again
+    pub expanded_blocks: HashMap<(HistoryEntry<'a>, NodePath), TruncationLevel>,
 
     // Thread communication
-    pub cmd_tx: Sender<UiCommand<'a>>,
+    pub cmd_tx: Sender<SentCommand<'a>>,
     pub resp_rx: Receiver<RequestResponse<'a>>,
     pub log_reader: LogReader,
 
@@ -172,9 +272,9 @@ pub(super) struct InteractiveState<'a> {
 impl<'a> InteractiveState<'a> {
     /// Create new interactive state from initial components
     pub(super) fn new(
-        initial_document: Document<'a>,
+        mut initial_document: Document<'a>,
         initial_entry: Option<HistoryEntry<'a>>,
-        cmd_tx: Sender<UiCommand<'a>>,
+        cmd_tx: Sender<SentCommand<'a>>,
         resp_rx: Receiver<RequestResponse<'a>>,
         render_context: RenderContext,
         theme: InteractiveTheme,
@@ -184,23 +284,34 @@ impl<'a> InteractiveState<'a> {
             .current_theme_name()
             .as_ref()
             .map(|s| s.to_string());
+        if !render_context.show_decorations() {
+            emoji::strip(&mut initial_document);
+        }
         Self {
             document: DocumentState {
                 document: initial_document,
                 history: History::new(initial_entry),
             },
+            tabs: TabState {
+                tabs: vec![None],
+                active: 0,
+            },
             viewport: ViewportState {
                 scroll_offset: 0,
                 cursor_pos: None,
                 clicked_position: None,
+                pressed_position: None,
+                selection: None,
                 cached_layout: None,
                 last_viewport_height: 0,
                 scrollbar_hovered: false,
                 scrollbar_dragging: false,
                 keyboard_cursor: KeyboardCursor::VirtualTop,
+                scroll_anchor: None,
             },
             render_cache: RenderCache {
                 actions: Vec::new(),
+                truncated_blocks: Vec::new(),
             },
             layout: LayoutState {
                 pos: Position::default(),
@@ -208,6 +319,9 @@ impl<'a> InteractiveState<'a> {
                 node_path: NodePath::new(),
                 area: Rect::default(),
                 blockquote_markers: Vec::new(),
+                narrow: false,
+                top_node: None,
+                anchor_row: None,
             },
             ui_mode: UiMode::Normal,
             ui: UiState {
@@ -217,12 +331,27 @@ impl<'a> InteractiveState<'a> {
                 is_hovering: false,
                 supports_cursor: supports_cursor_shape(),
                 include_source: false,
+                search_history: Vec::new(),
+                crates: Vec::new(),
+                search_history_cursor: None,
+                pending_keys: Vec::new(),
+                toasts: Toasts::default(),
+                hover_message: None,
+                clipboard: None,
             },
             loading: LoadingState {
                 pending_request: true,
                 was_loading: false,
                 started_at: Instant::now(),
+                tasks: Arc::default(),
             },
+            images: Images::new(
+                render_context
+                    .shows_images()
+                    .then(GraphicsProtocol::detect)
+                    .flatten(),
+            ),
+            expanded_blocks: HashMap::new(),
             cmd_tx,
             resp_rx,
             log_reader,
@@ -232,6 +361,15 @@ impl<'a> InteractiveState<'a> {
         }
     }
 
+    /// Send a command to the request thread, numbered so that cancelling loading stops it
+    pub(super) fn send_command(&self, command: UiCommand<'a>) {
+        let generation = self.loading.tasks.next_command();
+        let _ = self.cmd_tx.send(SentCommand {
+            generation,
+            command,
+        });
+    }
+
     pub(super) fn set_debug_message(&mut self, message: impl Into<Cow<'static, str>>) {
         if !self.loading.pending_request {
             self.ui.debug_message = message.into();
@@ -249,6 +387,8 @@ impl<'a> InteractiveState<'a> {
     /// Set scroll offset with automatic clamping to valid range
     pub(super) fn set_scroll_offset(&mut self, offset: u16) {
         self.viewport.scroll_offset = offset;
+        // Whatever is at the new offset is what to keep on screen from now on
+        self.viewport.scroll_anchor = None;
         // Clamp to valid range if we have layout info
         if let Some(cache) = self.viewport.cached_layout {
             let max_scroll = cache
@@ -258,6 +398,18 @@ impl<'a> InteractiveState<'a> {
         }
     }
 
+    /// The rows of the element keyboard commands act on: the focused link, or else the line
+    /// under the mouse
+    pub(super) fn current_element(&self) -> Option<Range<u16>> {
+        if let KeyboardCursor::Focused { action_index } = self.viewport.keyboard_cursor
+            && let Some((rect, _)) = self.render_cache.actions.get(action_index)
+        {
+            return Some(rect.y..rect.bottom());
+        }
+        let row = self.viewport.cursor_pos?.y;
+        Some(row..row + 1)
+    }
+
     /// Check if position is in the scrollbar column
     pub(super) fn is_in_scrollbar(&self, pos: Position, content_area_width: u16) -> bool {
         // Scrollbar is at content_area_width (which is frame.width - 1)
@@ -270,6 +422,7 @@ impl<'a> InteractiveState<'a> {
             .cached_layout
             .map(|cache| cache.document_height > self.viewport.last_viewport_height)
             .unwrap_or(false)
+            && !self.layout.narrow
     }
 
     /// Check if a link (by action index) is visible in the current viewport
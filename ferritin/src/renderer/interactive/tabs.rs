@@ -0,0 +1,198 @@
+//! Tabs - several open documents, each with its own history and scroll position
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Modifier};
+use std::mem;
+
+use super::history::History;
+use super::render_document::BASELINE_LEFT_MARGIN;
+use super::state::{DocumentState, InteractiveState, KeyboardCursor, Tab, UiMode};
+use crate::styled_string::{Document, TuiAction};
+
+impl<'a> InteractiveState<'a> {
+    /// Number of rows taken by the tab bar, which is only shown with more than one tab
+    pub(super) fn tab_bar_height(&self) -> u16 {
+        u16::from(self.tabs.tabs.len() > 1)
+    }
+
+    /// Open a new tab after the current one, showing the focused link if there is one and a
+    /// copy of the current document otherwise
+    pub(super) fn open_tab(&mut self) {
+        if !self.can_leave_tab() {
+            return;
+        }
+
+        let focused_action = match self.viewport.keyboard_cursor {
+            KeyboardCursor::Focused { action_index } => self
+                .render_cache
+                .actions
+                .get(action_index)
+                .map(|(_, action)| action.clone())
+                .filter(|action| {
+                    matches!(
+                        action,
+                        TuiAction::Navigate { .. }
+                            | TuiAction::NavigateToPath { .. }
+                            | TuiAction::Search { .. }
+                    )
+                }),
+            _ => None,
+        };
+
+        let mut history = History::new(None);
+        if focused_action.is_none()
+            && let Some(entry) = self.document.history.current()
+        {
+            history.push(entry.clone());
+        }
+
+        let new_tab = DocumentState {
+            document: self.document.document.clone(),
+            history,
+        };
+        let scroll_offset = if focused_action.is_some() {
+            0
+        } else {
+            self.viewport.scroll_offset
+        };
+
+        self.park_active_tab();
+        self.tabs.active += 1;
+        self.tabs.tabs.insert(self.tabs.active, None);
+        self.restore_tab(Tab {
+            document: new_tab,
+            scroll_offset,
+        });
+
+        if let Some(action) = focused_action
+            && let Some(command) = self.handle_action(action)
+        {
+            self.send_command(command);
+            self.loading.start();
+        }
+
+        self.notify(format!("Opened tab {}", self.tabs.active + 1));
+    }
+
+    /// Switch to the tab `offset` places away, wrapping around at either end
+    pub(super) fn switch_tab(&mut self, offset: isize) {
+        let count = self.tabs.tabs.len();
+        if count < 2 {
+            return;
+        }
+
+        if !self.can_leave_tab() {
+            return;
+        }
+
+        let target = (self.tabs.active as isize + offset).rem_euclid(count as isize) as usize;
+        self.park_active_tab();
+        self.tabs.active = target;
+        if let Some(tab) = self.tabs.tabs[target].take() {
+            self.restore_tab(tab);
+        }
+        self.notify(format!("Tab {} of {count}", target + 1));
+    }
+
+    /// Close the current tab, moving to the one after it (or before it, if it was the last)
+    pub(super) fn close_tab(&mut self) {
+        if self.tabs.tabs.len() < 2 {
+            self.notify_error("Can't close the last tab");
+            return;
+        }
+
+        if !self.can_leave_tab() {
+            return;
+        }
+
+        self.tabs.tabs.remove(self.tabs.active);
+        self.tabs.active = self.tabs.active.min(self.tabs.tabs.len() - 1);
+        if let Some(tab) = self.tabs.tabs[self.tabs.active].take() {
+            self.restore_tab(tab);
+        }
+        self.notify(format!("Closed tab, {} left", self.tabs.tabs.len()));
+    }
+
+    /// Whether the current tab can be parked. A pending response belongs to the current tab,
+    /// and the dev log temporarily replaces its document.
+    fn can_leave_tab(&mut self) -> bool {
+        if matches!(self.ui_mode, UiMode::DevLog { .. }) {
+            return false;
+        }
+
+        if self.loading.pending_request {
+            self.notify_error("Wait for the page to load before changing tabs");
+            return false;
+        }
+
+        true
+    }
+
+    /// Move the active tab's state into its slot
+    fn park_active_tab(&mut self) {
+        let document = mem::replace(
+            &mut self.document,
+            DocumentState {
+                document: Document::default(),
+                history: History::new(None),
+            },
+        );
+        self.tabs.tabs[self.tabs.active] = Some(Tab {
+            document,
+            scroll_offset: self.viewport.scroll_offset,
+        });
+    }
+
+    /// Make `tab` the displayed document
+    fn restore_tab(&mut self, tab: Tab<'a>) {
+        self.document = tab.document;
+        self.viewport.cached_layout = None;
+        self.set_scroll_offset(tab.scroll_offset);
+        self.viewport.selection = None;
+        self.reset_keyboard_cursor();
+    }
+
+    /// Render the tab bar: one label per tab, with the active one highlighted
+    pub(super) fn render_tab_bar(&self, buf: &mut Buffer, area: Rect) {
+        let bg_style = self.theme.breadcrumb_style;
+        for x in area.x..area.x + area.width {
+            if let Some(cell) = buf.cell_mut((x, area.y)) {
+                cell.reset();
+                cell.set_style(bg_style);
+            }
+        }
+
+        let mut col = area.x + BASELINE_LEFT_MARGIN;
+        for (index, slot) in self.tabs.tabs.iter().enumerate() {
+            let history = match slot {
+                Some(tab) => &tab.document.history,
+                None => &self.document.history,
+            };
+            let name = history
+                .current()
+                .map(|entry| entry.display_name())
+                .unwrap_or_else(|| String::from("…"));
+            let label = format!(" {}:{name} ", index + 1);
+
+            let style = if index == self.tabs.active {
+                self.theme
+                    .breadcrumb_current_style
+                    .add_modifier(Modifier::REVERSED)
+            } else {
+                bg_style
+            };
+
+            for ch in label.chars() {
+                if col >= area.x + area.width {
+                    return;
+                }
+                if let Some(cell) = buf.cell_mut((col, area.y)) {
+                    cell.set_char(ch).set_style(style);
+                }
+                col += 1;
+            }
+
+            // Gap between tabs
+            col += 1;
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Slow operations reported by the request thread, shown in the status bar while loading, and
+//! cancelled from the UI thread when the user stops waiting for them
+
+use ferritin_common::progress::Progress;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+/// Tasks that take at least this long say that they're done when they finish
+const SLOW_TASK: Duration = Duration::from_secs(3);
+
+/// The downloads, doc builds and indexing that the request thread is working through
+///
+/// Commands are numbered by the UI thread as it sends them, starting from one, with zero for
+/// the first page. Cancelling applies to every command sent so far, even one the request
+/// thread hasn't started on yet.
+#[derive(Debug)]
+pub(super) struct TaskStatus {
+    /// Nested tasks, innermost last
+    tasks: Mutex<Vec<Task>>,
+    /// Messages for slow tasks that finished, until the UI thread shows them
+    finished: Mutex<Vec<String>>,
+    /// The number of the last command the UI thread sent
+    sent: AtomicU64,
+    /// The number of the command the request thread is working on
+    running: AtomicU64,
+    /// The number of the last command sent when loading was cancelled, plus one so that zero
+    /// means it never was
+    cancelled: AtomicU64,
+    /// The thread that runs commands, the one that created this. Work on other threads, like
+    /// prefetching linked crates, isn't part of a command and is never cancelled.
+    command_thread: ThreadId,
+}
+
+impl Default for TaskStatus {
+    fn default() -> Self {
+        Self {
+            tasks: Mutex::default(),
+            finished: Mutex::default(),
+            sent: AtomicU64::new(0),
+            running: AtomicU64::new(0),
+            cancelled: AtomicU64::new(0),
+            command_thread: thread::current().id(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Task {
+    description: String,
+    /// Done and total, once the task has reported them
+    progress: Option<(u64, u64)>,
+    started: Instant,
+}
+
+impl TaskStatus {
+    /// The innermost task, like "Indexing serde for search..." or with a percentage when known
+    pub(super) fn current(&self) -> Option<String> {
+        let tasks = self.tasks.lock().ok()?;
+        let task = tasks.last()?;
+        Some(match task.progress {
+            Some((done, total)) if total > 0 => {
+                format!("{}... {}%", task.description, done * 100 / total)
+            }
+            _ => format!("{}...", task.description),
+        })
+    }
+
+    /// Number a command the UI thread is about to send
+    pub(super) fn next_command(&self) -> u64 {
+        self.sent.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// The request thread is starting on the command numbered `generation`, which is only
+    /// cancelled if loading was cancelled after it was sent
+    pub(super) fn begin_command(&self, generation: u64) {
+        self.running.store(generation, Ordering::SeqCst);
+    }
+
+    /// Cancel the commands sent so far: downloads for them stop, and their responses aren't
+    /// sent
+    pub(super) fn cancel(&self) {
+        let sent = self.sent.load(Ordering::SeqCst);
+        self.cancelled.store(sent + 1, Ordering::SeqCst);
+    }
+
+    /// Messages like "Building docs for serde: done in 12s" for the slow tasks that finished
+    /// since the last call
+    pub(super) fn take_finished(&self) -> Vec<String> {
+        self.finished
+            .lock()
+            .map(|mut finished| std::mem::take(&mut *finished))
+            .unwrap_or_default()
+    }
+}
+
+impl Progress for TaskStatus {
+    fn start(&self, task: &str) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.push(Task {
+                description: task.to_string(),
+                progress: None,
+                started: Instant::now(),
+            });
+        }
+    }
+
+    fn advance(&self, done: u64, total: u64) {
+        if let Ok(mut tasks) = self.tasks.lock()
+            && let Some(task) = tasks.last_mut()
+        {
+            task.progress = Some((done, total));
+        }
+    }
+
+    fn finish(&self) {
+        let Some(task) = self.tasks.lock().ok().and_then(|mut tasks| tasks.pop()) else {
+            return;
+        };
+        let elapsed = task.started.elapsed();
+        if elapsed >= SLOW_TASK
+            && !self.is_cancelled()
+            && let Ok(mut finished) = self.finished.lock()
+        {
+            finished.push(format!(
+                "{}: done in {}s",
+                task.description,
+                elapsed.as_secs()
+            ));
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        thread::current().id() == self.command_thread
+            && self.running.load(Ordering::SeqCst) < self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelling_only_stops_commands_already_sent() {
+        let tasks = TaskStatus::default();
+        assert!(!tasks.is_cancelled());
+
+        // The first page is loaded before any command arrives
+        tasks.cancel();
+        assert!(tasks.is_cancelled());
+
+        let first = tasks.next_command();
+        tasks.begin_command(first);
+        assert!(!tasks.is_cancelled());
+        tasks.cancel();
+        assert!(tasks.is_cancelled());
+
+        // A command cancelled before the request thread gets to it stays cancelled
+        let second = tasks.next_command();
+        tasks.cancel();
+        tasks.begin_command(second);
+        assert!(tasks.is_cancelled());
+
+        let third = tasks.next_command();
+        tasks.begin_command(third);
+        assert!(!tasks.is_cancelled());
+    }
+
+    #[test]
+    fn other_threads_are_not_cancelled() {
+        let tasks = TaskStatus::default();
+        tasks.cancel();
+        assert!(tasks.is_cancelled());
+        thread::scope(|scope| {
+            scope.spawn(|| assert!(!tasks.is_cancelled()));
+        });
+    }
+}
@@ -1,9 +1,11 @@
 use super::*;
 use crate::{
+    commands::search::SearchMode,
     logging::StatusLogBackend,
     styled_string::{Document, DocumentNode, Span, SpanStyle},
 };
 use crossbeam_channel::unbounded as channel;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{Terminal, backend::TestBackend};
 
 /// Helper to create a minimal test state
@@ -107,6 +109,319 @@ fn test_input_mode_buffer_manipulation() {
     }
 }
 
+#[test]
+fn test_tabs_keep_their_own_history() {
+    let mut state = create_test_state();
+    state.loading.pending_request = false;
+    state.document.history.push(HistoryEntry::Home);
+    assert_eq!(state.tab_bar_height(), 0);
+
+    // With no focused link, a new tab starts as a copy of the current page
+    state.open_tab();
+    assert_eq!(state.tabs.tabs.len(), 2);
+    assert_eq!(state.tabs.active, 1);
+    assert_eq!(state.tab_bar_height(), 1);
+    assert_eq!(state.document.history.current(), Some(&HistoryEntry::Home));
+
+    state.document.history.push(HistoryEntry::List {
+        default_crate: None,
+    });
+
+    // Switching back restores the first tab's history, and wraps around
+    state.switch_tab(1);
+    assert_eq!(state.tabs.active, 0);
+    assert_eq!(state.document.history.current(), Some(&HistoryEntry::Home));
+    state.switch_tab(-1);
+    assert!(matches!(
+        state.document.history.current(),
+        Some(HistoryEntry::List { .. })
+    ));
+
+    state.close_tab();
+    assert_eq!(state.tabs.tabs.len(), 1);
+    assert_eq!(state.document.history.current(), Some(&HistoryEntry::Home));
+
+    // The last tab can't be closed
+    state.close_tab();
+    assert_eq!(state.tabs.tabs.len(), 1);
+}
+
+#[test]
+fn test_g_then_t_switches_tabs() {
+    let mut state = create_test_state();
+    state.loading.pending_request = false;
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let press = |c: char| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+    state.open_tab();
+    assert_eq!(state.tabs.active, 1);
+    state.handle_key_event(press('g'), &mut terminal);
+    state.handle_key_event(press('t'), &mut terminal);
+    assert_eq!(state.tabs.active, 0);
+    state.handle_key_event(press('g'), &mut terminal);
+    state.handle_key_event(press('T'), &mut terminal);
+    assert_eq!(state.tabs.active, 1);
+
+    // Any other key after `g` is the start of a path to go to
+    state.handle_key_event(press('g'), &mut terminal);
+    state.handle_key_event(press('s'), &mut terminal);
+    assert!(matches!(
+        &state.ui_mode,
+        UiMode::Input(InputMode::GoTo { buffer }) if buffer == "s"
+    ));
+}
+
+#[test]
+fn test_expanded_blocks_stay_expanded() {
+    use crate::styled_string::TruncationLevel;
+
+    let truncated_document = || Document {
+        nodes: vec![DocumentNode::TruncatedBlock {
+            level: TruncationLevel::Brief,
+            nodes: vec![
+                DocumentNode::paragraph(vec![Span::plain("First paragraph.")]),
+                DocumentNode::paragraph(vec![Span::plain("Second paragraph.")]),
+                DocumentNode::paragraph(vec![Span::plain("Third paragraph.")]),
+            ],
+        }],
+    };
+    let level = |state: &InteractiveState| match &state.document.document.nodes[0] {
+        DocumentNode::TruncatedBlock { level, .. } => *level,
+        other => panic!("Expected a truncated block, got {other:?}"),
+    };
+
+    let mut state = create_test_state();
+    state.loading.pending_request = false;
+    state.document.history.push(HistoryEntry::Home);
+    state.document.document = truncated_document();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+
+    state.toggle_block_under_cursor();
+    assert_eq!(level(&state), TruncationLevel::Full);
+
+    // Showing the page again, like going back to it, keeps the block expanded
+    state.handle_response(RequestResponse::Document {
+        doc: truncated_document(),
+        entry: None,
+    });
+    assert_eq!(level(&state), TruncationLevel::Full);
+
+    // Toggling it again truncates it as it was
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    state.toggle_block_under_cursor();
+    assert_eq!(level(&state), TruncationLevel::Brief);
+}
+
+#[test]
+fn test_link_hints_follow_the_link() {
+    use super::state::KeyboardCursor;
+
+    let mut state = create_test_state();
+    state.loading.pending_request = false;
+    state.document.document = Document {
+        nodes: vec![DocumentNode::paragraph(vec![
+            Span::plain("See "),
+            Span::type_name("Vec").with_path("std::vec::Vec"),
+            Span::plain(" and "),
+            Span::type_name("String").with_path("std::string::String"),
+        ])],
+    };
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+
+    let hints = state.link_hints();
+    assert_eq!(hints.len(), 2);
+    assert_eq!(hints[1].0, "s");
+
+    // The hints are drawn over the links
+    state.ui_mode = UiMode::LinkHints {
+        typed: String::new(),
+    };
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let rect = state.render_cache.actions[hints[1].1].0;
+    assert_eq!(buffer[(rect.x, rect.y)].symbol(), "s");
+
+    // Typing a hint follows its link
+    state.handle_link_hint_key('s');
+    assert!(matches!(state.ui_mode, UiMode::Normal));
+    assert_eq!(
+        state.viewport.keyboard_cursor,
+        KeyboardCursor::Focused {
+            action_index: hints[1].1
+        }
+    );
+    assert!(state.loading.pending_request);
+}
+
+#[test]
+fn test_resizing_keeps_the_top_of_the_screen() {
+    let mut state = create_test_state();
+    state.loading.pending_request = false;
+    state.document.document = Document {
+        nodes: (0..30)
+            .map(|i| {
+                DocumentNode::paragraph(vec![Span::plain(format!(
+                    "Paragraph {i} says lorem ipsum dolor sit amet"
+                ))])
+            })
+            .collect(),
+    };
+    let first_row = |terminal: &Terminal<TestBackend>| {
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.width)
+            .map(|x| buffer[(x, 0)].symbol().to_string())
+            .collect::<String>()
+    };
+
+    // Each paragraph takes one line and a blank one at this width
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    state.set_scroll_offset(20);
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    assert!(first_row(&terminal).contains("Paragraph 10 says"));
+
+    // Paragraphs wrap when narrower, and the same one stays at the top
+    terminal.backend_mut().resize(30, 24);
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    assert!(first_row(&terminal).contains("Paragraph 10 says"));
+
+    terminal.backend_mut().resize(80, 24);
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    assert!(first_row(&terminal).contains("Paragraph 10 says"));
+    assert_eq!(state.viewport.scroll_offset, 20);
+}
+
+#[test]
+fn test_dragging_selects_and_copies_text() {
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+    let mut state = create_test_state();
+    state.loading.pending_request = false;
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+
+    let mouse = |kind, column| MouseEvent {
+        kind,
+        column,
+        row: 0,
+        modifiers: KeyModifiers::NONE,
+    };
+    let left = MouseButton::Left;
+
+    // "Test document" starts after the left margin
+    state.handle_mouse_event(mouse(MouseEventKind::Down(left), 3), &terminal);
+    state.handle_mouse_event(mouse(MouseEventKind::Drag(left), 6), &terminal);
+    state.handle_mouse_event(mouse(MouseEventKind::Up(left), 6), &terminal);
+    assert_eq!(state.viewport.clicked_position, None);
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    assert_eq!(state.ui.clipboard.as_deref(), Some("Test"));
+
+    // Without a drag in between it's a click, and the selection goes away
+    state.handle_mouse_event(mouse(MouseEventKind::Down(left), 3), &terminal);
+    state.handle_mouse_event(mouse(MouseEventKind::Up(left), 3), &terminal);
+    assert_eq!(state.viewport.selection, None);
+    assert!(state.viewport.clicked_position.is_some());
+}
+
+#[test]
+fn test_logged_warnings_become_toasts() {
+    use super::toasts::Severity;
+    use log::Log;
+
+    let (backend, log_reader) = StatusLogBackend::new(100);
+    let mut state = create_test_state();
+    state.log_reader = log_reader;
+
+    backend.log(
+        &log::Record::builder()
+            .level(log::Level::Info)
+            .args(format_args!("Loading serde"))
+            .build(),
+    );
+    assert!(!state.notify_background_events());
+
+    backend.log(
+        &log::Record::builder()
+            .level(log::Level::Warn)
+            .args(format_args!("Could not build serde@1.0.0"))
+            .build(),
+    );
+    assert!(state.notify_background_events());
+    let toasts = state.ui.toasts.visible().collect::<Vec<_>>();
+    assert_eq!(toasts.len(), 1);
+    assert_eq!(toasts[0].message, "Could not build serde@1.0.0");
+    assert_eq!(toasts[0].severity, Severity::Warning);
+
+    // Each warning is shown once
+    assert!(!state.notify_background_events());
+}
+
+#[test]
+fn test_hover_messages_are_announced_once() {
+    let mut state = create_test_state();
+    state.loading.pending_request = false;
+    state.ui.mouse_enabled = false;
+
+    state.handle_hover();
+    state.handle_hover();
+    let messages = state
+        .ui
+        .toasts
+        .history()
+        .map(|toast| toast.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        ["Mouse: OFF (text selection enabled - m to re-enable) | Source: OFF"]
+    );
+}
+
+#[test]
+fn test_crate_picker_filters_and_opens_a_crate() {
+    use super::crate_picker::CrateChoice;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let mut state = create_test_state();
+    state.loading.pending_request = false;
+    state.ui.crates = ["serde", "serde_json", "tokio"]
+        .map(|name| CrateChoice {
+            name: name.to_string(),
+            path: name.to_string(),
+            version: Some("1.0.0".to_string()),
+            provenance: "dependency",
+        })
+        .to_vec();
+    state.ui_mode = UiMode::CratePicker {
+        filter: String::new(),
+        selected_index: 0,
+    };
+
+    let press = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    state.handle_crate_picker_key(press(KeyCode::Char('s')));
+    state.handle_crate_picker_key(press(KeyCode::Char('j')));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    let screen = (0..24)
+        .map(|y| {
+            (0..80)
+                .map(|x| terminal.backend().buffer().cell((x, y)).unwrap().symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(screen.contains("Crates (1/3)"));
+    assert!(screen.contains("serde_json 1.0.0  dependency"));
+    assert!(!screen.contains("tokio"));
+
+    state.handle_crate_picker_key(press(KeyCode::Enter));
+    assert!(matches!(state.ui_mode, UiMode::Normal));
+    assert!(state.loading.pending_request);
+    assert_eq!(state.ui.debug_message, "Loading: serde_json...");
+}
+
 #[test]
 fn test_history_navigation() {
     let mut state = create_test_state();
@@ -127,6 +442,7 @@ fn test_history_navigation() {
     state.document.history.push(HistoryEntry::Search {
         query: "test".to_string(),
         crate_name: None,
+        mode: SearchMode::Terms,
     });
     // Now we can go back (two entries, at index 1)
     assert!(state.document.history.can_go_back());
@@ -145,6 +461,47 @@ fn test_history_navigation() {
     assert!(!state.document.history.can_go_forward());
 }
 
+#[test]
+fn test_messages_command_shows_messages() {
+    let mut state = create_test_state();
+    state.loading.pending_request = false;
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let keys = |command: &str| {
+        format!(":{command}")
+            .chars()
+            .map(|c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+            .chain([KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)])
+            .collect::<Vec<_>>()
+    };
+
+    for key in keys("messages") {
+        state.handle_key_event(key, &mut terminal);
+    }
+    assert!(matches!(state.ui_mode, UiMode::Messages { scroll: 0 }));
+
+    state.ui_mode = UiMode::Normal;
+    for key in keys("nonsense") {
+        state.handle_key_event(key, &mut terminal);
+    }
+    assert!(matches!(state.ui_mode, UiMode::Normal));
+}
+
+#[test]
+fn test_going_back_to_a_search_keeps_its_mode() {
+    let entry = HistoryEntry::Search {
+        query: "^try_".to_string(),
+        crate_name: None,
+        mode: SearchMode::Regex,
+    };
+    assert!(matches!(
+        entry.to_command(),
+        UiCommand::Search {
+            mode: SearchMode::Regex,
+            ..
+        }
+    ));
+}
+
 #[test]
 fn test_rendering_to_test_backend() {
     let mut state = create_test_state();
@@ -169,6 +526,120 @@ fn test_rendering_to_test_backend() {
     );
 }
 
+#[test]
+fn test_rules_without_decorations_are_plain() {
+    let mut state = create_test_state();
+    state.document.document = Document {
+        nodes: vec![DocumentNode::HorizontalRule],
+    };
+    let backend = TestBackend::new(40, 10);
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    let first_row = |terminal: &Terminal<TestBackend>| {
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.width)
+            .map(|x| buffer[(x, 0)].symbol().to_string())
+            .collect::<String>()
+    };
+
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    assert!(first_row(&terminal).contains('•'));
+
+    state.render_context = RenderContext::new().with_decorations(false);
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+    assert_eq!(first_row(&terminal), "─".repeat(40));
+}
+
+#[test]
+fn test_wide_characters_take_two_columns() {
+    let mut state = create_test_state();
+    state.document.document = Document {
+        nodes: vec![DocumentNode::paragraph(vec![Span::plain("日本 ok")])],
+    };
+    let backend = TestBackend::new(40, 10);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let (x, y) = (0..buffer.area.height)
+        .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+        .find(|&position| buffer[position].symbol() == "日")
+        .expect("the text should be rendered");
+    assert_eq!(buffer[(x + 2, y)].symbol(), "本");
+    assert_eq!(buffer[(x + 5, y)].symbol(), "o");
+}
+
+#[test]
+fn test_source_lines_are_numbered_in_the_code_block() {
+    use crate::styled_string::LineNumbers;
+
+    let mut state = create_test_state();
+    state.document.document = Document {
+        nodes: vec![DocumentNode::source_block(
+            Some("rust"),
+            "fn a() {}\nfn b() {}",
+            LineNumbers {
+                first: 9,
+                highlighted: 10..=10,
+            },
+        )],
+    };
+    let backend = TestBackend::new(40, 10);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let rows = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol().to_string())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    assert!(rows.iter().any(|row| row.contains("│  9 │ fn a() {}")));
+    assert!(rows.iter().any(|row| row.contains("│ 10 ┃ fn b() {}")));
+}
+
+#[test]
+fn test_help_screen_lists_the_active_keymap() {
+    use crate::key_bindings::{KeyBindings, Keys};
+    use std::collections::BTreeMap;
+
+    let mut state = create_test_state();
+    let keys = BTreeMap::from([
+        ("down".to_string(), Keys::One("k".into())),
+        ("up".to_string(), Keys::One("j".into())),
+    ]);
+    state.render_context =
+        RenderContext::new().with_key_bindings(KeyBindings::from_table(&keys).unwrap());
+    state.ui_mode = UiMode::Help;
+
+    let backend = TestBackend::new(80, 60);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(|frame| state.render_frame(frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let rows = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol().to_string())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    let row_for = |description: &str| {
+        rows.iter()
+            .find(|row| row.contains(description))
+            .map(|row| row.trim().to_string())
+    };
+
+    assert!(row_for("Scroll down").unwrap().starts_with("k "));
+    assert!(row_for("Scroll up").unwrap().starts_with("j "));
+    assert!(
+        rows.iter()
+            .any(|row| row.trim().starts_with("q, Ctrl+c ") && row.contains("Quit"))
+    );
+}
+
 #[test]
 fn test_brief_truncation_with_code_block() {
     use crate::styled_string::TruncationLevel;
@@ -186,6 +657,8 @@ fn test_brief_truncation_with_code_block() {
                 DocumentNode::CodeBlock {
                     lang: Some("rust".into()),
                     code: "fn example() {\n    println!(\"Hello\");\n    let x = 42;\n    let y = 100;\n    let z = x + y;\n}\n".into(),
+                    action: None,
+                    line_numbers: None,
                 },
                 DocumentNode::paragraph(vec![Span::plain("Third paragraph after code.")]),
             ],
@@ -262,6 +735,8 @@ fn test_brief_with_short_code_block() {
                 DocumentNode::CodeBlock {
                     lang: Some("rust".into()),
                     code: "let x = 42;".into(),
+                    action: None,
+                    line_numbers: None,
                 },
             ],
         }],
@@ -496,6 +971,8 @@ fn test_code_block_spacing() {
             DocumentNode::CodeBlock {
                 lang: Some("rust".into()),
                 code: "let x = vec![1, 2, 3];".into(),
+                action: None,
+                line_numbers: None,
             },
             DocumentNode::paragraph(vec![Span::plain("More content after the code block.")]),
         ],
@@ -544,3 +1021,29 @@ fn test_code_block_spacing() {
 
     // TODO: Once we fix spacing, assert blank_lines_before_code == 1
 }
+
+#[test]
+fn test_example_results_arrive_in_the_background() {
+    use crate::commands::run_example::ExampleRun;
+
+    let mut state = create_test_state();
+    state.loading.start();
+    state.handle_response(RequestResponse::ExampleStarted {
+        title: "Example 1 of crate::f".into(),
+    });
+    assert!(!state.loading.pending_request);
+
+    // A page asked for while the example builds is still being waited for when it finishes
+    state.loading.start();
+    state.handle_response(RequestResponse::ExampleOutput {
+        title: "Example 1 of crate::f".into(),
+        run: ExampleRun {
+            passed: true,
+            summary: "ran successfully".into(),
+            stdout: "hello\n".into(),
+            stderr: String::new(),
+        },
+    });
+    assert!(state.loading.pending_request);
+    assert!(matches!(state.ui_mode, UiMode::ExampleOutput { .. }));
+}
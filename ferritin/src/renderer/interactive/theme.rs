@@ -39,6 +39,9 @@ pub(super) struct InteractiveTheme {
 
     /// Code block border style
     pub code_block_border_style: Style,
+
+    /// Link hints, drawn over links in the loading bar's contrasting colors
+    pub link_hint_style: Style,
 }
 
 impl InteractiveTheme {
@@ -104,6 +107,11 @@ impl InteractiveTheme {
                 .fg(to_ratatui(default_fg)),
 
             code_block_border_style: Style::default().fg(to_ratatui(code_block_border)),
+
+            link_hint_style: Style::default()
+                .bg(to_ratatui(status_loading_bg))
+                .fg(to_ratatui(status_loading_fg))
+                .add_modifier(Modifier::BOLD),
         }
     }
 }
@@ -0,0 +1,276 @@
+//! Toasts - short notifications shown over the document that dismiss themselves, so that a
+//! message isn't lost when the status bar moves on. Every toast is also kept in a message
+//! history, shown by the `:messages` view (or `M`).
+//!
+//! Besides what the UI thread reports itself, slow background tasks announce that they're
+//! done, and warnings and errors logged on the other threads are shown as toasts too.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span as RatatuiSpan},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::{
+    state::{InteractiveState, UiMode},
+    utils::centered_rect,
+};
+use crate::renderer::{display_width, truncate_to_width};
+
+/// How long an informational toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+/// Warnings and errors stay up longer, since they usually need reading
+const ERROR_TOAST_DURATION: Duration = Duration::from_secs(8);
+/// Older toasts are dismissed early when more than this many are showing
+const MAX_VISIBLE_TOASTS: usize = 3;
+/// Number of messages kept for the message history
+const MAX_MESSAGES: usize = 200;
+
+/// How much a notification matters, which decides how it looks and how long it stays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn prefix(self) -> &'static str {
+        match self {
+            Severity::Info => "",
+            Severity::Success => "✓ ",
+            Severity::Warning => "! ",
+            Severity::Error => "✗ ",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Toast {
+    pub message: String,
+    pub severity: Severity,
+    pub created: Instant,
+}
+
+impl Toast {
+    fn is_expired(&self, now: Instant) -> bool {
+        let duration = if self.severity >= Severity::Warning {
+            ERROR_TOAST_DURATION
+        } else {
+            TOAST_DURATION
+        };
+        now.duration_since(self.created) >= duration
+    }
+}
+
+/// Toasts on screen, and every message shown this session
+#[derive(Debug, Default)]
+pub(super) struct Toasts {
+    visible: VecDeque<Toast>,
+    history: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub(super) fn push(&mut self, message: String, severity: Severity) {
+        let toast = Toast {
+            message,
+            severity,
+            created: Instant::now(),
+        };
+
+        self.history.push_back(toast.clone());
+        if self.history.len() > MAX_MESSAGES {
+            self.history.pop_front();
+        }
+
+        self.visible.push_back(toast);
+        if self.visible.len() > MAX_VISIBLE_TOASTS {
+            self.visible.pop_front();
+        }
+    }
+
+    /// Dismiss toasts that have been shown long enough, returning whether any were
+    pub(super) fn expire(&mut self, now: Instant) -> bool {
+        let before = self.visible.len();
+        self.visible.retain(|toast| !toast.is_expired(now));
+        self.visible.len() != before
+    }
+
+    pub(super) fn visible(&self) -> impl DoubleEndedIterator<Item = &Toast> {
+        self.visible.iter()
+    }
+
+    pub(super) fn history(&self) -> impl DoubleEndedIterator<Item = &Toast> {
+        self.history.iter()
+    }
+}
+
+impl<'a> InteractiveState<'a> {
+    /// Show a notification that dismisses itself after a few seconds
+    pub(super) fn notify(&mut self, message: impl Into<String>) {
+        self.ui.toasts.push(message.into(), Severity::Info);
+    }
+
+    /// Show a notification that something finished or worked
+    pub(super) fn notify_success(&mut self, message: impl Into<String>) {
+        self.ui.toasts.push(message.into(), Severity::Success);
+    }
+
+    /// Show a warning, which stays up as long as an error
+    pub(super) fn notify_warning(&mut self, message: impl Into<String>) {
+        self.ui.toasts.push(message.into(), Severity::Warning);
+    }
+
+    /// Show an error notification, which stays up longer than others
+    pub(super) fn notify_error(&mut self, message: impl Into<String>) {
+        self.ui.toasts.push(message.into(), Severity::Error);
+    }
+
+    /// Show what happened on the other threads since the last call: slow tasks that finished,
+    /// and warnings and errors that were logged. Returns whether there was anything.
+    pub(super) fn notify_background_events(&mut self) -> bool {
+        let finished = self.loading.tasks.take_finished();
+        let alerts = self.log_reader.take_alerts();
+        let any = !finished.is_empty() || !alerts.is_empty();
+        for message in finished {
+            self.notify_success(message);
+        }
+        for entry in alerts {
+            if entry.level == log::Level::Error {
+                self.notify_error(entry.message);
+            } else {
+                self.notify_warning(entry.message);
+            }
+        }
+        any
+    }
+
+    /// Render the visible toasts stacked at the bottom right of `area`, newest lowest
+    pub(super) fn render_toasts(&self, buf: &mut Buffer, area: Rect) {
+        let info_style = Style::default()
+            .bg(self.theme.status_loading_bg)
+            .fg(self.theme.status_loading_fg);
+        let warning_style = info_style.add_modifier(Modifier::BOLD);
+        // Errors are in the same colors the other way around, to stand out from the rest
+        let error_style = Style::default()
+            .bg(self.theme.status_loading_fg)
+            .fg(self.theme.status_loading_bg)
+            .add_modifier(Modifier::BOLD);
+
+        for (row, toast) in self.ui.toasts.visible().rev().enumerate() {
+            let Some(y) = (area.y + area.height).checked_sub(row as u16 + 1) else {
+                break;
+            };
+            if y < area.y {
+                break;
+            }
+
+            let style = match toast.severity {
+                Severity::Info | Severity::Success => info_style,
+                Severity::Warning => warning_style,
+                Severity::Error => error_style,
+            };
+            let text = format!(
+                " {}{} ",
+                toast.severity.prefix(),
+                toast.message.replace('\n', " ")
+            );
+            let max_width = area.width.saturating_sub(2) as usize;
+            let text = truncate_to_width(&text, max_width);
+            let width = display_width(text) as u16;
+
+            let x = area.x + area.width.saturating_sub(width + 1);
+            buf.set_stringn(x, y, text, width.into(), style);
+        }
+    }
+
+    /// Render the message history as a modal overlay, most recent first
+    pub(super) fn render_messages(&mut self, buf: &mut Buffer, area: Rect) {
+        // Clear document actions - modal should block all background interactions
+        self.render_cache.actions.clear();
+
+        let UiMode::Messages { scroll } = self.ui_mode else {
+            return;
+        };
+
+        let modal_area = centered_rect(80, 80, area);
+        Clear.render(modal_area, buf);
+
+        let now = Instant::now();
+        let mut lines = self
+            .ui
+            .toasts
+            .history()
+            .rev()
+            .map(|toast| {
+                let age = now.duration_since(toast.created).as_secs();
+                let style = if toast.severity >= Severity::Warning {
+                    self.theme.help_title_style
+                } else {
+                    self.theme.help_desc_style
+                };
+                Line::from(vec![
+                    RatatuiSpan::styled(format!("{age:>5}s ago  "), self.theme.muted_style),
+                    RatatuiSpan::styled(toast.severity.prefix(), style),
+                    RatatuiSpan::styled(toast.message.as_str(), style),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        if lines.is_empty() {
+            lines.push(Line::from(RatatuiSpan::styled(
+                "(no messages yet)",
+                self.theme.muted_style,
+            )));
+        }
+
+        let block = Block::default()
+            .title(" Messages ")
+            .borders(Borders::ALL)
+            .style(self.theme.help_bg_style);
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .render(modal_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toasts_expire_but_stay_in_the_history() {
+        let mut toasts = Toasts::default();
+        toasts.push("Theme saved".into(), Severity::Success);
+        toasts.push("Could not load serde".into(), Severity::Error);
+
+        let later = Instant::now() + TOAST_DURATION;
+        assert!(toasts.expire(later));
+        let visible = toasts.visible().map(|t| &*t.message).collect::<Vec<_>>();
+        assert_eq!(visible, ["Could not load serde"]);
+        assert_eq!(toasts.history().count(), 2);
+
+        assert!(toasts.expire(later + ERROR_TOAST_DURATION));
+        assert_eq!(toasts.visible().count(), 0);
+        assert!(!toasts.expire(later + ERROR_TOAST_DURATION));
+    }
+
+    #[test]
+    fn only_the_newest_toasts_are_shown() {
+        let mut toasts = Toasts::default();
+        for n in 0..5 {
+            toasts.push(format!("message {n}"), Severity::Info);
+        }
+        let visible = toasts.visible().map(|t| &*t.message).collect::<Vec<_>>();
+        assert_eq!(visible, ["message 2", "message 3", "message 4"]);
+        assert_eq!(toasts.history().count(), 5);
+    }
+}
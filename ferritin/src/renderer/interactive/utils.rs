@@ -1,6 +1,9 @@
 use crate::styled_string::DocumentNode;
 use crossterm::{queue, style::Print};
-use ratatui::prelude::Backend;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    prelude::Backend,
+};
 use std::{env, io};
 
 /// Detect if the terminal supports mouse cursor shape changes
@@ -45,9 +48,8 @@ pub(super) fn find_node_at_path_mut<'a, 'b>(
     match &mut nodes[idx] {
         DocumentNode::Section { nodes, .. }
         | DocumentNode::BlockQuote { nodes }
-        | DocumentNode::TruncatedBlock { nodes, .. } => {
-            find_node_at_path_mut(nodes, remaining_path)
-        }
+        | DocumentNode::TruncatedBlock { nodes, .. }
+        | DocumentNode::Conditional { nodes, .. } => find_node_at_path_mut(nodes, remaining_path),
         DocumentNode::List { items } => {
             // Path into list items
             if remaining_path.is_empty() {
@@ -92,3 +94,20 @@ pub(super) fn estimate_node_lines(node: &DocumentNode, _screen_width: u16) -> u1
         _ => 2,                                             // Default estimate for other nodes
     }
 }
+
+/// Helper function to create a centered rect using up certain percentage of the available rect
+pub(super) fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::vertical([
+        Constraint::Percentage((100 - percent_y) / 2),
+        Constraint::Percentage(percent_y),
+        Constraint::Percentage((100 - percent_y) / 2),
+    ])
+    .split(r);
+
+    Layout::horizontal([
+        Constraint::Percentage((100 - percent_x) / 2),
+        Constraint::Percentage(percent_x),
+        Constraint::Percentage((100 - percent_x) / 2),
+    ])
+    .split(popup_layout[1])[1]
+}
@@ -1,6 +1,8 @@
 use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use unicode_segmentation::UnicodeSegmentation;
 
 use super::state::InteractiveState;
+use crate::renderer::grapheme_width;
 
 impl<'a> InteractiveState<'a> {
     /// Write text to buffer at position
@@ -20,16 +22,20 @@ impl<'a> InteractiveState<'a> {
         let screen_row = row - self.viewport.scroll_offset;
         let mut current_col = col;
 
-        for ch in text.chars() {
+        for grapheme in text.graphemes(true) {
             if current_col >= area.width {
                 break; // Past right edge
             }
 
+            let width = grapheme_width(grapheme) as u16;
+            if width == 0 {
+                continue; // Control characters take no columns
+            }
+
             // Handle tabs: replace with spaces to avoid column counting mismatches
             // Tabs display as multiple spaces in terminals but count as 1 character
-            if ch == '\t' {
-                // Write 4 spaces for each tab (Rust convention)
-                for _ in 0..4 {
+            if grapheme == "\t" {
+                for _ in 0..width {
                     if current_col >= area.width {
                         break;
                     }
@@ -40,11 +46,22 @@ impl<'a> InteractiveState<'a> {
                     current_col += 1;
                 }
             } else {
+                // A wide character that the right edge would cut in half isn't written
+                if current_col + width > area.width {
+                    break;
+                }
                 if let Some(cell) = buf.cell_mut((current_col, screen_row)) {
-                    cell.set_char(ch);
+                    cell.set_symbol(grapheme);
                     cell.set_style(style);
                 }
-                current_col += 1;
+                // The terminal draws a wide character over the cells after it
+                for hidden_col in current_col + 1..current_col + width {
+                    if let Some(cell) = buf.cell_mut((hidden_col, screen_row)) {
+                        cell.set_char(' ');
+                        cell.set_style(style);
+                    }
+                }
+                current_col += width;
             }
         }
     }
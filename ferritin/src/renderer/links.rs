@@ -0,0 +1,170 @@
+use super::OutputMode;
+use crate::styled_string::{Document, DocumentNode, Span, TuiAction};
+use std::borrow::Cow;
+
+/// What links to items and URLs become in one-shot output
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Links {
+    /// docs.rs URLs: hyperlinks in a terminal, markdown links in plain text
+    Docsrs,
+    /// Paths that `ferritin get` takes, in brackets after the link text
+    Paths,
+    /// Only the link text
+    Off,
+}
+
+impl Links {
+    /// What links become when no flag or config file says: hyperlinks in a terminal, and
+    /// only the text anywhere else
+    pub(crate) fn default_for(output_mode: OutputMode) -> Self {
+        match output_mode {
+            OutputMode::Tty => Links::Docsrs,
+            _ => Links::Off,
+        }
+    }
+}
+
+/// `document` with its links rewritten into text for `links`, or as it is when the renderer
+/// for `output_mode` already shows them that way or isn't a one-shot text renderer
+///
+/// Links in signatures and other generated code are left out rather than written into the
+/// code.
+pub(super) fn apply<'a, 'b>(
+    document: &'b Document<'a>,
+    output_mode: OutputMode,
+    links: Links,
+) -> Cow<'b, Document<'a>> {
+    match (output_mode, links) {
+        (OutputMode::Tty, Links::Paths | Links::Off)
+        | (OutputMode::Plain, Links::Docsrs | Links::Paths) => {
+            let mut document = document.clone();
+            for node in &mut document.nodes {
+                rewrite_node(node, links);
+            }
+            Cow::Owned(document)
+        }
+        _ => Cow::Borrowed(document),
+    }
+}
+
+fn rewrite_node(node: &mut DocumentNode<'_>, links: Links) {
+    match node {
+        DocumentNode::Paragraph { spans } | DocumentNode::Heading { spans, .. } => {
+            rewrite_spans(spans, links, true);
+        }
+        DocumentNode::Section { title, nodes } => {
+            if let Some(title) = title {
+                rewrite_spans(title, links, true);
+            }
+            for node in nodes {
+                rewrite_node(node, links);
+            }
+        }
+        DocumentNode::List { items } => {
+            for node in items.iter_mut().flat_map(|item| &mut item.content) {
+                rewrite_node(node, links);
+            }
+        }
+        DocumentNode::GeneratedCode { spans } => rewrite_spans(spans, links, false),
+        DocumentNode::BlockQuote { nodes }
+        | DocumentNode::TruncatedBlock { nodes, .. }
+        | DocumentNode::Conditional { nodes, .. } => {
+            for node in nodes {
+                rewrite_node(node, links);
+            }
+        }
+        DocumentNode::Table { header, rows } => {
+            for cell in header.iter_mut().flatten().chain(rows.iter_mut().flatten()) {
+                rewrite_spans(&mut cell.spans, links, true);
+            }
+        }
+        DocumentNode::Image { url, alt } => {
+            *node = DocumentNode::paragraph(vec![Span::image_link(url, alt)]);
+            rewrite_node(node, links);
+        }
+        DocumentNode::CodeBlock { .. } | DocumentNode::HorizontalRule => {}
+    }
+}
+
+/// Replace each link in `spans` with its text, followed by its target when it's in prose
+fn rewrite_spans<'a>(spans: &mut Vec<Span<'a>>, links: Links, in_prose: bool) {
+    for mut span in std::mem::take(spans) {
+        if !matches!(
+            span.action,
+            Some(
+                TuiAction::Navigate { .. }
+                    | TuiAction::NavigateToPath { .. }
+                    | TuiAction::OpenUrl(_)
+            )
+        ) {
+            spans.push(span);
+            continue;
+        }
+
+        let target = match links {
+            Links::Docsrs => span.url().map(|url| format!("]({url})")),
+            Links::Paths => item_path(&span)
+                .filter(|path| *path != span.text)
+                .map(|path| format!(" [{path}]")),
+            Links::Off => None,
+        };
+        span.action = None;
+
+        match target.filter(|_| in_prose) {
+            Some(target) => {
+                if links == Links::Docsrs {
+                    spans.push(Span::plain("["));
+                }
+                spans.push(span);
+                spans.push(Span::plain(target));
+            }
+            None => spans.push(span),
+        }
+    }
+}
+
+/// The path of the item a span links to, which `ferritin get` resolves
+fn item_path(span: &Span<'_>) -> Option<String> {
+    match &span.action {
+        Some(TuiAction::Navigate { doc_ref, .. }) => doc_ref.link_path(),
+        Some(TuiAction::NavigateToPath { path, .. }) => Some(path.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::plain;
+
+    fn rewritten(links: Links) -> String {
+        let document = Document::with_nodes(vec![
+            DocumentNode::paragraph(vec![
+                Span::plain("See "),
+                Span::inline_code("Vec").with_path("std::vec::Vec"),
+                Span::plain("."),
+            ]),
+            DocumentNode::generated_code(vec![Span::type_name("Vec").with_path("std::vec::Vec")]),
+        ]);
+        let mut output = String::new();
+        let document = apply(&document, OutputMode::Plain, links);
+        plain::render(&document, None, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn links_become_urls_paths_or_nothing() {
+        let output = rewritten(Links::Paths);
+        assert!(output.contains("See Vec [std::vec::Vec]."));
+        assert!(output.lines().any(|line| line == "Vec"));
+
+        let output = rewritten(Links::Docsrs);
+        assert!(output.contains("See [Vec](https://doc.rust-lang.org/nightly/std/"));
+
+        let output = rewritten(Links::Off);
+        assert!(output.contains("See Vec."));
+    }
+}
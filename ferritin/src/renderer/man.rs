@@ -0,0 +1,214 @@
+//! roff renderer, for reading docs with man(1)
+//!
+//! The output uses the man macro package: `ferritin get std::fs::File --format man | man -l -`
+//! shows it like any other man page. The document's first title becomes the page name, titles
+//! become `.SH` sections and section headings `.SS` subsections. Code is set unfilled so that
+//! man keeps its line breaks.
+
+use std::fmt::{Result, Write};
+
+use crate::styled_string::{
+    Document, DocumentNode, HeadingLevel, ListItem, ShowWhen, Span, SpanStyle, TableCell,
+    TruncationLevel,
+};
+
+/// man section for library documentation
+const MAN_SECTION: &str = "3";
+
+/// Render a document as a man page
+pub fn render(document: &Document, output: &mut impl Write) -> Result {
+    let title = document
+        .nodes
+        .iter()
+        .find_map(|node| match node {
+            DocumentNode::Heading {
+                level: HeadingLevel::Title,
+                spans,
+            } => Some(plain_text(spans)),
+            _ => None,
+        })
+        .unwrap_or_else(|| "ferritin".to_string());
+
+    writeln!(
+        output,
+        ".TH \"{}\" {MAN_SECTION} \"\" \"ferritin\" \"Rust Documentation\"",
+        quoted(&title)
+    )?;
+    render_nodes(&document.nodes, output)
+}
+
+fn render_nodes(nodes: &[DocumentNode], output: &mut impl Write) -> Result {
+    for node in nodes {
+        render_node(node, output)?;
+    }
+    Ok(())
+}
+
+fn render_node(node: &DocumentNode, output: &mut impl Write) -> Result {
+    match node {
+        DocumentNode::Paragraph { spans } => {
+            writeln!(output, ".PP")?;
+            write_text_lines(&styled_text(spans), output)
+        }
+        DocumentNode::Heading { level, spans } => {
+            let request = match level {
+                HeadingLevel::Title => ".SH",
+                HeadingLevel::Section => ".SS",
+            };
+            writeln!(output, "{request} \"{}\"", quoted(&plain_text(spans)))
+        }
+        DocumentNode::Section { title, nodes } => {
+            if let Some(title) = title {
+                writeln!(output, ".SS \"{}\"", quoted(&plain_text(title)))?;
+            }
+            render_nodes(nodes, output)
+        }
+        DocumentNode::List { items } => {
+            for item in items {
+                render_list_item(item, output)?;
+            }
+            Ok(())
+        }
+        DocumentNode::CodeBlock { code, .. } => {
+            writeln!(output, ".PP\n.RS 4\n.nf")?;
+            write_text_lines(&escape(code.trim_end_matches('\n')), output)?;
+            writeln!(output, ".fi\n.RE")
+        }
+        DocumentNode::GeneratedCode { spans } => {
+            writeln!(output, ".PP\n.nf")?;
+            write_text_lines(&styled_text(spans), output)?;
+            writeln!(output, ".fi")
+        }
+        DocumentNode::HorizontalRule => writeln!(output, ".sp"),
+        DocumentNode::Image { url, alt } => render_node(
+            &DocumentNode::paragraph(vec![Span::image_link(url, alt)]),
+            output,
+        ),
+        DocumentNode::BlockQuote { nodes } => {
+            writeln!(output, ".RS 4")?;
+            render_nodes(nodes, output)?;
+            writeln!(output, ".RE")
+        }
+        DocumentNode::Table { header, rows } => {
+            writeln!(output, ".PP\n.nf")?;
+            for row in header.iter().chain(rows) {
+                write_text_lines(&table_row(row), output)?;
+            }
+            writeln!(output, ".fi")
+        }
+        DocumentNode::TruncatedBlock { nodes, level } => match level {
+            TruncationLevel::SingleLine | TruncationLevel::Brief => nodes
+                .first()
+                .map_or(Ok(()), |node| render_node(node, output)),
+            TruncationLevel::Full => render_nodes(nodes, output),
+        },
+        DocumentNode::Conditional { show_when, nodes } => match show_when {
+            ShowWhen::Interactive => Ok(()),
+            ShowWhen::Always | ShowWhen::NonInteractive => render_nodes(nodes, output),
+        },
+    }
+}
+
+fn render_list_item(item: &ListItem, output: &mut impl Write) -> Result {
+    writeln!(output, ".IP \\(bu 2")?;
+    let mut content = item.content.iter();
+    if let Some(first) = content.next() {
+        // The first paragraph goes on the bullet's line rather than starting a new one
+        match first {
+            DocumentNode::Paragraph { spans } => write_text_lines(&styled_text(spans), output)?,
+            other => render_node(other, output)?,
+        }
+    }
+
+    let rest = content.as_slice();
+    if !rest.is_empty() {
+        writeln!(output, ".RS 2")?;
+        render_nodes(rest, output)?;
+        writeln!(output, ".RE")?;
+    }
+    Ok(())
+}
+
+fn table_row(cells: &[TableCell]) -> String {
+    cells
+        .iter()
+        .map(|cell| styled_text(&cell.spans))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn plain_text(spans: &[Span]) -> String {
+    spans.iter().map(|span| &*span.text).collect()
+}
+
+/// Escaped text with font changes for emphasis and code
+fn styled_text(spans: &[Span]) -> String {
+    let mut text = String::new();
+    for span in spans {
+        let font = match span.style {
+            SpanStyle::Strong
+            | SpanStyle::Keyword
+            | SpanStyle::InlineCode
+            | SpanStyle::InlineRustCode => Some('B'),
+            SpanStyle::Emphasis | SpanStyle::TypeName => Some('I'),
+            _ => None,
+        };
+        match font {
+            Some(font) if !span.text.trim().is_empty() => {
+                let _ = write!(text, "\\f{font}{}\\fR", escape(&span.text));
+            }
+            _ => text.push_str(&escape(&span.text)),
+        }
+    }
+    text
+}
+
+/// Escape characters that roff would otherwise interpret
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\e").replace('-', "\\-")
+}
+
+/// Escaped text for a macro argument in double quotes, which can't contain them
+fn quoted(text: &str) -> String {
+    escape(text).replace('"', "")
+}
+
+/// Write escaped text one line at a time, protecting lines that roff would read as requests
+fn write_text_lines(text: &str, output: &mut impl Write) -> Result {
+    for line in text.split('\n') {
+        if line.starts_with('.') || line.starts_with('\'') {
+            output.write_str("\\&")?;
+        }
+        writeln!(output, "{line}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_a_man_page() {
+        let doc = Document::with_nodes(vec![
+            DocumentNode::heading(HeadingLevel::Title, vec![Span::plain("Item: File")]),
+            DocumentNode::paragraph(vec![
+                Span::plain("Opens a "),
+                Span::strong("file"),
+                Span::plain(".\n.hidden -flag"),
+            ]),
+            DocumentNode::list(vec![ListItem::new(vec![DocumentNode::paragraph(vec![
+                Span::plain("First"),
+            ])])]),
+        ]);
+
+        let mut output = String::new();
+        render(&doc, &mut output).unwrap();
+
+        assert!(output.starts_with(".TH \"Item: File\" 3"));
+        assert!(output.contains(".SH \"Item: File\""));
+        assert!(output.contains("Opens a \\fBfile\\fR."));
+        assert!(output.contains("\n\\&.hidden \\-flag\n"));
+        assert!(output.contains(".IP \\(bu 2\nFirst\n"));
+    }
+}
@@ -1,15 +1,25 @@
-use crate::{render_context::RenderContext, styled_string::Document};
+use crate::{
+    render_context::RenderContext,
+    styled_string::{Document, DocumentNode},
+};
 use std::{
     fmt::Write,
     io::{self, IsTerminal},
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+mod emoji;
 mod interactive;
+mod links;
+mod man;
 mod plain;
+mod svg;
 mod test_mode;
 mod tty;
 
 pub use interactive::{HistoryEntry, render_interactive};
+pub use links::Links;
 
 /// Bullet characters for list items at different nesting levels
 /// Cycles through these as lists nest deeper
@@ -26,21 +36,77 @@ pub(crate) fn bullet_for_indent(indent: u16) -> char {
     LIST_BULLETS[nesting_level % LIST_BULLETS.len()]
 }
 
+/// Tabs are laid out as this many spaces (Rust convention)
+pub(crate) const TAB_WIDTH: usize = 4;
+
+/// Columns `text` takes on screen: tabs are written as four spaces, CJK and most emoji take
+/// two columns and combining marks none
+pub(crate) fn display_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+/// Columns one grapheme cluster takes on screen
+pub(crate) fn grapheme_width(grapheme: &str) -> usize {
+    if grapheme == "\t" {
+        TAB_WIDTH
+    } else {
+        grapheme.width()
+    }
+}
+
+/// Whether code blocks in `lang` are text laid out by hand, like diagrams, which are shown
+/// as they are: without highlighting, and cut off at the edge rather than wrapped
+pub(crate) fn is_preformatted(lang: Option<&str>) -> bool {
+    matches!(lang, Some("text" | "mermaid"))
+}
+
+/// The longest start of `text` that fits in `max_width` columns, without splitting a character
+pub(crate) fn truncate_to_width(text: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        width += grapheme_width(grapheme);
+        if width > max_width {
+            return &text[..idx];
+        }
+    }
+    text
+}
+
 #[cfg(test)]
 pub use interactive::render_to_test_backend;
 
 /// Output mode for rendering documents
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+// Daemons and clients of earlier versions spell the variants as they are named
+#[serde(rename_all = "lowercase")]
 pub enum OutputMode {
     /// ANSI escape codes for terminal colors/styles
+    #[serde(alias = "Tty")]
     Tty,
     /// Plain text, no decoration
+    #[serde(alias = "Plain")]
     Plain,
+    /// A standalone SVG image in the selected theme's colors
+    #[serde(alias = "Svg")]
+    Svg,
+    /// A man page in roff, for `man -l -`
+    #[serde(alias = "Man")]
+    Man,
     /// Pseudo-XML tags for testing (e.g., <keyword>struct</keyword>)
+    #[value(skip)]
+    #[serde(alias = "TestMode")]
     TestMode,
 }
 
 impl OutputMode {
+    /// Whether oversized output can be split into pages of lines. An image or a man page
+    /// can't be cut off partway, so they are always written whole.
+    pub fn is_paged(self) -> bool {
+        !matches!(self, OutputMode::Svg | OutputMode::Man)
+    }
+
     /// Detect the appropriate output mode based on environment
     pub fn detect() -> Self {
         if std::env::var("FERRITIN_TEST_MODE").is_ok() {
@@ -53,23 +119,92 @@ impl OutputMode {
     }
 }
 
+/// Render a document as plain, markdown-like text, for writing to files
+pub(crate) fn render_plain(document: &Document, output: &mut impl Write) -> std::fmt::Result {
+    plain::render(document, None, output)
+}
+
 /// Render a document to a string based on the output mode
 pub fn render(
     document: &Document,
     render_context: &RenderContext,
     output: &mut impl Write,
 ) -> std::fmt::Result {
-    match render_context.output_mode() {
-        OutputMode::Tty => tty::render(document, render_context, output),
-        OutputMode::Plain => plain::render(document, output),
-        OutputMode::TestMode => test_mode::render(document, output),
+    let output_mode = render_context.output_mode();
+    let mut document = links::apply(document, output_mode, render_context.links());
+    if !render_context.show_decorations() {
+        emoji::strip(document.to_mut());
+    }
+    match output_mode {
+        OutputMode::Tty => tty::render(&document, render_context, output),
+        OutputMode::Plain => plain::render(&document, render_context.wrap_width(), output),
+        OutputMode::Svg => svg::render(&document, render_context, output),
+        OutputMode::Man => man::render(&document, output),
+        OutputMode::TestMode => test_mode::render(&document, output),
+    }
+}
+
+/// Renders a document a top-level node at a time, as the nodes are formatted
+///
+/// The terminal and plain text renderers write each node as it comes, so that long output
+/// starts printing before the rest of it is formatted. An image, a man page or test output is
+/// laid out as a whole, and is rendered from the collected nodes by [`Self::finish`].
+pub struct StreamRenderer<'a, 'r, W> {
+    render_context: &'r RenderContext,
+    output: &'r mut W,
+    /// Nodes held for renderers that need the whole document
+    collected: Vec<DocumentNode<'a>>,
+    started: bool,
+}
+
+impl<'a, 'r, W: Write> StreamRenderer<'a, 'r, W> {
+    pub fn new(render_context: &'r RenderContext, output: &'r mut W) -> Self {
+        Self {
+            render_context,
+            output,
+            collected: vec![],
+            started: false,
+        }
+    }
+
+    /// Render the next top-level node, or hold it for [`Self::finish`]
+    pub fn push(&mut self, node: DocumentNode<'a>) -> std::fmt::Result {
+        if !matches!(
+            self.render_context.output_mode(),
+            OutputMode::Tty | OutputMode::Plain
+        ) {
+            self.collected.push(node);
+            return Ok(());
+        }
+
+        if self.started {
+            writeln!(self.output)?; // Blank line between consecutive blocks
+        }
+        self.started = true;
+        render(
+            &Document::with_nodes(vec![node]),
+            self.render_context,
+            self.output,
+        )
+    }
+
+    /// Render the nodes held for a renderer that lays out the whole document
+    pub fn finish(self) -> std::fmt::Result {
+        if self.collected.is_empty() {
+            return Ok(());
+        }
+        render(
+            &Document::with_nodes(self.collected),
+            self.render_context,
+            self.output,
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::styled_string::{DocumentNode, HeadingLevel, Span};
+    use crate::styled_string::{HeadingLevel, ListItem, Span};
 
     #[test]
     fn test_render_modes() {
@@ -109,4 +244,67 @@ mod tests {
         assert!(!plain_output.is_empty());
         assert!(!test_output.is_empty());
     }
+
+    #[test]
+    fn streamed_nodes_render_like_the_whole_document() {
+        let item = |name: &'static str| {
+            ListItem::new(vec![DocumentNode::paragraph(vec![Span::type_name(name)])])
+        };
+        let heading = DocumentNode::heading(HeadingLevel::Title, vec![Span::plain("Outline")]);
+        let document = Document::with_nodes(vec![
+            heading.clone(),
+            DocumentNode::list(vec![item("Foo"), item("Bar")]),
+        ]);
+
+        for output_mode in [OutputMode::Tty, OutputMode::Plain, OutputMode::TestMode] {
+            let render_context = RenderContext::new().with_output_mode(output_mode);
+            let mut whole = String::new();
+            render(&document, &render_context, &mut whole).unwrap();
+
+            let mut streamed = String::new();
+            let mut renderer = StreamRenderer::new(&render_context, &mut streamed);
+            renderer.push(heading.clone()).unwrap();
+            renderer
+                .push(DocumentNode::list(vec![item("Foo")]))
+                .unwrap();
+            renderer
+                .push(DocumentNode::list(vec![item("Bar")]))
+                .unwrap();
+            renderer.finish().unwrap();
+
+            if output_mode == OutputMode::TestMode {
+                assert!(streamed.contains("Foo") && streamed.contains("Bar"));
+            } else {
+                assert_eq!(streamed, whole, "{output_mode:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn output_modes_read_as_earlier_versions_wrote_them() {
+        for (json, output_mode) in [
+            ("\"tty\"", OutputMode::Tty),
+            ("\"Tty\"", OutputMode::Tty),
+            ("\"Plain\"", OutputMode::Plain),
+            ("\"TestMode\"", OutputMode::TestMode),
+        ] {
+            assert_eq!(
+                serde_json::from_str::<OutputMode>(json).unwrap(),
+                output_mode
+            );
+        }
+        assert_eq!(serde_json::to_string(&OutputMode::Man).unwrap(), "\"man\"");
+    }
+
+    #[test]
+    fn measures_text_in_terminal_columns() {
+        assert_eq!(display_width("Vec"), 3);
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("a\tb"), 6);
+
+        assert_eq!(truncate_to_width("日本語", 5), "日本");
+        assert_eq!(truncate_to_width("e\u{301}te", 2), "e\u{301}t");
+        assert_eq!(truncate_to_width("Vec", 5), "Vec");
+    }
 }
@@ -17,34 +17,75 @@
 
 use std::fmt::{Result, Write};
 
+use crate::renderer::display_width;
 use crate::styled_string::{
-    Document, DocumentNode, HeadingLevel, ListItem, ShowWhen, Span, TruncationLevel,
+    Document, DocumentNode, HeadingLevel, ListItem, ShowWhen, Span, TableCell, TruncationLevel,
 };
 
+/// Width of rules and heading underlines when lines aren't wrapped to a width
+const DEFAULT_RULE_WIDTH: usize = 80;
+
 /// Plain text renderer state
 struct PlainRenderer<'w, W: Write> {
-    output: &'w mut W,
+    output: ColumnTracker<&'w mut W>,
     indent: String,
+    /// Paragraphs are re-flowed to fit this many columns, if set
+    width: Option<usize>,
+}
+
+/// Passes writes through, keeping track of the column that the next one starts at
+struct ColumnTracker<W> {
+    inner: W,
+    column: usize,
+}
+
+impl<W: Write> Write for ColumnTracker<W> {
+    fn write_str(&mut self, s: &str) -> Result {
+        match s.rsplit_once('\n') {
+            Some((_, last_line)) => self.column = display_width(last_line),
+            None => self.column += display_width(s),
+        }
+        self.inner.write_str(s)
+    }
 }
 
-/// Render a document as plain text without any styling
-pub fn render(document: &Document, output: &mut impl Write) -> Result {
-    let mut renderer = PlainRenderer::new(output);
+/// Render a document as plain text without any styling, re-flowing paragraphs to `width`
+/// columns if given
+pub fn render(document: &Document, width: Option<usize>, output: &mut impl Write) -> Result {
+    let mut renderer = PlainRenderer::new(output, width);
     renderer.render_block_sequence(&document.nodes)
 }
 
 impl<'w, W: Write> PlainRenderer<'w, W> {
-    fn new(output: &'w mut W) -> Self {
+    fn new(output: &'w mut W, width: Option<usize>) -> Self {
         Self {
-            output,
+            output: ColumnTracker {
+                inner: output,
+                column: 0,
+            },
             indent: String::new(),
+            width,
         }
     }
 
+    /// Indent a new line. Content that follows a list bullet or a quote marker on the same
+    /// line is already in place.
     fn write_indent(&mut self) -> Result {
+        if self.output.column > 0 {
+            return Ok(());
+        }
         write!(self.output, "{}", self.indent)
     }
 
+    /// Write a rule of `ch` across the rest of the line
+    fn write_rule(&mut self, ch: char) -> Result {
+        let width = match self.width {
+            Some(width) => width.saturating_sub(self.output.column),
+            None => DEFAULT_RULE_WIDTH,
+        };
+        writeln!(self.output, "{}", ch.to_string().repeat(width))
+    }
+
     /// Render a sequence of block nodes with blank lines between them
     fn render_block_sequence(&mut self, nodes: &[DocumentNode]) -> Result {
         for (idx, node) in nodes.iter().enumerate() {
@@ -67,7 +108,10 @@ impl<'w, W: Write> PlainRenderer<'w, W> {
         match node {
             DocumentNode::Paragraph { spans } => {
                 self.write_indent()?;
-                self.render_spans(spans)?;
+                match self.width {
+                    Some(width) => self.render_reflowed_spans(spans, width)?,
+                    None => self.render_spans(spans)?,
+                }
                 writeln!(self.output)?; // Single newline
                 Ok(())
             }
@@ -78,20 +122,9 @@ impl<'w, W: Write> PlainRenderer<'w, W> {
                 // Add underlines for headings
                 self.write_indent()?;
                 match level {
-                    HeadingLevel::Title => {
-                        for _ in 0..80 {
-                            write!(self.output, "=")?;
-                        }
-                        writeln!(self.output)?;
-                    }
-                    HeadingLevel::Section => {
-                        for _ in 0..80 {
-                            write!(self.output, "-")?;
-                        }
-                        writeln!(self.output)?;
-                    }
+                    HeadingLevel::Title => self.write_rule('='),
+                    HeadingLevel::Section => self.write_rule('-'),
                 }
-                Ok(())
             }
             DocumentNode::Section { title, nodes } => {
                 if let Some(title_spans) = title {
@@ -111,12 +144,21 @@ impl<'w, W: Write> PlainRenderer<'w, W> {
                 }
                 Ok(())
             }
-            DocumentNode::CodeBlock { code, .. } => {
+            DocumentNode::CodeBlock {
+                code, line_numbers, ..
+            } => {
                 self.write_indent()?;
                 writeln!(self.output, "```")?;
-                for line in code.lines() {
+                let line_count = code.lines().count();
+                for (idx, line) in code.lines().enumerate() {
                     self.write_indent()?;
-                    writeln!(self.output, "{line}")?;
+                    if let Some(line_numbers) = line_numbers {
+                        let (number, _) = line_numbers.line(idx);
+                        let gutter = line_numbers.gutter(number, line_count);
+                        writeln!(self.output, "{}", format!("{gutter}{line}").trim_end())?;
+                    } else {
+                        writeln!(self.output, "{line}")?;
+                    }
                 }
                 if !code.ends_with('\n') && !code.is_empty() {
                     writeln!(self.output)?;
@@ -131,12 +173,12 @@ impl<'w, W: Write> PlainRenderer<'w, W> {
                 writeln!(self.output)?; // Single newline
                 Ok(())
             }
+            DocumentNode::Image { url, alt } => {
+                self.render_node(&DocumentNode::paragraph(vec![Span::image_link(url, alt)]))
+            }
             DocumentNode::HorizontalRule => {
                 self.write_indent()?;
-                for _ in 0..80 {
-                    write!(self.output, "─")?;
-                }
-                writeln!(self.output)?;
+                self.write_rule('─')?;
                 Ok(())
             }
             DocumentNode::BlockQuote { nodes } => {
@@ -154,20 +196,7 @@ impl<'w, W: Write> PlainRenderer<'w, W> {
                 }
                 Ok(())
             }
-            DocumentNode::Table { header, rows } => {
-                // Placeholder for table rendering
-                let row_count = rows.len();
-                let col_count = header
-                    .as_ref()
-                    .map_or_else(|| rows.first().map_or(0, |r| r.len()), |h| h.len());
-                self.write_indent()?;
-                writeln!(
-                    self.output,
-                    "[Table: {} columns × {} rows]",
-                    col_count, row_count
-                )?;
-                Ok(())
-            }
+            DocumentNode::Table { header, rows } => self.render_table(header.as_deref(), rows),
             DocumentNode::TruncatedBlock { nodes, level } => {
                 // Transparent container - just controls truncation
                 match level {
@@ -239,6 +268,45 @@ impl<'w, W: Write> PlainRenderer<'w, W> {
         Ok(())
     }
 
+    /// Write spans with line breaks between words so that lines fit in `width` columns.
+    /// Wrapped lines line up under the first one, and explicit line breaks and the spacing
+    /// between words that stay on a line are kept.
+    fn render_reflowed_spans(&mut self, spans: &[Span], width: usize) -> Result {
+        let text = spans.iter().map(|span| &*span.text).collect::<String>();
+        for (idx, line) in text.split('\n').enumerate() {
+            if idx > 0 {
+                writeln!(self.output)?;
+                self.write_indent()?;
+            }
+
+            let start = self.output.column;
+            let mut rest = line;
+            while !rest.is_empty() {
+                let word_start = rest
+                    .find(|c: char| !c.is_whitespace())
+                    .unwrap_or(rest.len());
+                let (space, after_space) = rest.split_at(word_start);
+                let word_end = after_space
+                    .find(char::is_whitespace)
+                    .unwrap_or(after_space.len());
+                let (word, after_word) = after_space.split_at(word_end);
+
+                if !word.is_empty()
+                    && self.output.column > start
+                    && self.output.column + display_width(space) + display_width(word) > width
+                {
+                    writeln!(self.output)?;
+                    write!(self.output, "{}", " ".repeat(start))?;
+                } else {
+                    write!(self.output, "{space}")?;
+                }
+                write!(self.output, "{word}")?;
+                rest = after_word;
+            }
+        }
+        Ok(())
+    }
+
     fn render_span(&mut self, Span { text, .. }: &Span) -> Result {
         // Handle newlines in span text to maintain indentation
         for (idx, line) in text.split('\n').enumerate() {
@@ -251,6 +319,56 @@ impl<'w, W: Write> PlainRenderer<'w, W> {
         Ok(())
     }
 
+    /// Render a table markdown-style, with `|` between columns padded to line up
+    fn render_table(&mut self, header: Option<&[TableCell]>, rows: &[Vec<TableCell>]) -> Result {
+        let num_cols = header
+            .map(|h| h.len())
+            .or_else(|| rows.first().map(|r| r.len()))
+            .unwrap_or(0);
+        if num_cols == 0 {
+            return Ok(());
+        }
+
+        let cells_text = |cells: &[TableCell]| {
+            (0..num_cols)
+                .map(|col_idx| cells.get(col_idx).map(cell_text).unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+        let header = header.map(cells_text);
+        let rows = rows.iter().map(|row| cells_text(row)).collect::<Vec<_>>();
+
+        // Separator dashes need at least three columns
+        let mut col_widths = vec![3; num_cols];
+        for row in header.iter().chain(&rows) {
+            for (col_idx, text) in row.iter().enumerate() {
+                col_widths[col_idx] = col_widths[col_idx].max(display_width(text));
+            }
+        }
+
+        if let Some(header) = &header {
+            self.render_table_row(header, &col_widths)?;
+            let separator = col_widths
+                .iter()
+                .map(|&width| "-".repeat(width))
+                .collect::<Vec<_>>();
+            self.render_table_row(&separator, &col_widths)?;
+        }
+        for row in &rows {
+            self.render_table_row(row, &col_widths)?;
+        }
+        Ok(())
+    }
+
+    fn render_table_row(&mut self, cells: &[String], col_widths: &[usize]) -> Result {
+        self.write_indent()?;
+        write!(self.output, "|")?;
+        for (text, &width) in cells.iter().zip(col_widths) {
+            let padding = width.saturating_sub(display_width(text));
+            write!(self.output, " {text}{} |", " ".repeat(padding))?;
+        }
+        writeln!(self.output)
+    }
+
     fn render_list_item(&mut self, item: &ListItem) -> Result {
         self.write_indent()?;
         let bullet = crate::renderer::bullet_for_indent(self.indent.len() as u16);
@@ -277,9 +395,23 @@ impl<'w, W: Write> PlainRenderer<'w, W> {
     }
 }
 
+/// A cell's text on one line, with `|` escaped so it doesn't end the cell
+fn cell_text(cell: &TableCell) -> String {
+    let text = cell
+        .spans
+        .iter()
+        .map(|span| &*span.text)
+        .collect::<String>();
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::styled_string::LineNumbers;
 
     #[test]
     fn test_render_heading() {
@@ -288,7 +420,7 @@ mod tests {
             vec![Span::plain("Item: "), Span::type_name("Vec")],
         )]);
         let mut output = String::new();
-        render(&doc, &mut output).unwrap();
+        render(&doc, None, &mut output).unwrap();
         assert!(output.contains("Item: Vec"));
         assert!(output.contains("===="));
     }
@@ -301,10 +433,82 @@ mod tests {
         ])]);
 
         let mut output = String::new();
-        render(&doc, &mut output).unwrap();
+        render(&doc, None, &mut output).unwrap();
         dbg!(&output);
 
         assert!(output.contains("  ◦ First"));
         assert!(output.contains("  ◦ Second"));
     }
+
+    #[test]
+    fn test_paragraphs_are_wrapped_to_the_width() {
+        let doc = Document::with_nodes(vec![
+            DocumentNode::paragraph(vec![
+                Span::plain("The quick brown "),
+                Span::inline_code("fox"),
+                Span::plain(" jumps over the lazy dog"),
+            ]),
+            DocumentNode::list(vec![ListItem::new(vec![DocumentNode::paragraph(vec![
+                Span::plain("alpha beta gamma delta"),
+            ])])]),
+        ]);
+
+        let mut output = String::new();
+        render(&doc, Some(20), &mut output).unwrap();
+        assert!(output.starts_with("The quick brown fox\njumps over the lazy\ndog\n"));
+        assert!(output.contains("  ◦ alpha beta gamma\n    delta\n"));
+
+        let mut unwrapped = String::new();
+        render(&doc, None, &mut unwrapped).unwrap();
+        assert!(unwrapped.starts_with("The quick brown fox jumps over the lazy dog\n"));
+    }
+
+    #[test]
+    fn test_source_lines_are_numbered() {
+        let doc = Document::with_nodes(vec![DocumentNode::source_block(
+            Some("rust"),
+            "#[derive(Debug)]\npub struct Unit;\n\n",
+            LineNumbers {
+                first: 9,
+                highlighted: 10..=10,
+            },
+        )]);
+
+        let mut output = String::new();
+        render(&doc, None, &mut output).unwrap();
+        assert_eq!(
+            output,
+            "```\n 9 │ #[derive(Debug)]\n10 ┃ pub struct Unit;\n11 │\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_render_table() {
+        let doc = Document::with_nodes(vec![DocumentNode::table(
+            Some(vec![
+                TableCell::from_span(Span::plain("Feature")),
+                TableCell::from_span(Span::plain("Default")),
+            ]),
+            vec![
+                vec![
+                    TableCell::from_span(Span::inline_code("std")),
+                    TableCell::from_span(Span::plain("yes")),
+                ],
+                vec![TableCell::new(vec![
+                    Span::plain("a | b\n"),
+                    Span::plain("c"),
+                ])],
+            ],
+        )]);
+
+        let mut output = String::new();
+        render(&doc, None, &mut output).unwrap();
+        assert_eq!(
+            output,
+            "| Feature  | Default |\n\
+             | -------- | ------- |\n\
+             | std      | yes     |\n\
+             | a \\| b c |         |\n"
+        );
+    }
 }
@@ -0,0 +1,189 @@
+//! SVG renderer for sharing styled output as an image.
+//!
+//! Lays out the document exactly as the TTY renderer does, then draws each line as SVG text in
+//! the selected theme's colors, so doc snippets can be embedded in slides and READMEs.
+
+use std::fmt::{Result, Write};
+
+use super::tty::{RenderBudget, build_lines};
+use crate::render_context::RenderContext;
+use crate::styled_string::Document;
+use ratatui::{
+    style::{Color, Modifier},
+    text::Span as RatatuiSpan,
+};
+use unicode_width::UnicodeWidthStr;
+
+const FONT_SIZE: f32 = 14.0;
+/// Advance width of one column, for typical monospace fonts
+const CHAR_WIDTH: f32 = FONT_SIZE * 0.6;
+const LINE_HEIGHT: f32 = FONT_SIZE * 1.4;
+const PADDING: f32 = 16.0;
+const FONT_FAMILY: &str = "ui-monospace, SFMono-Regular, Menlo, Consolas, monospace";
+
+/// Render a document as a standalone SVG image
+pub fn render(
+    document: &Document,
+    render_context: &RenderContext,
+    output: &mut impl Write,
+) -> Result {
+    let mut budget = RenderBudget::Unlimited;
+    let lines = build_lines(&document.nodes, render_context, &mut budget);
+
+    let columns = lines
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| split_hyperlink(&span.content).1.width())
+                .sum::<usize>()
+        })
+        .max()
+        .unwrap_or(0);
+
+    let width = columns as f32 * CHAR_WIDTH + 2.0 * PADDING;
+    let height = lines.len() as f32 * LINE_HEIGHT + 2.0 * PADDING;
+    let color_scheme = render_context.color_scheme();
+    let background = color_scheme.default_background();
+    let foreground = color_scheme.default_foreground();
+
+    writeln!(
+        output,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="{FONT_FAMILY}" font-size="{FONT_SIZE}">"#
+    )?;
+    writeln!(
+        output,
+        r##"<rect width="100%" height="100%" rx="6" fill="#{:02x}{:02x}{:02x}"/>"##,
+        background.r, background.g, background.b
+    )?;
+
+    for (index, line) in lines.iter().enumerate() {
+        if line.spans.iter().all(|span| span.content.is_empty()) {
+            continue;
+        }
+
+        // Baseline sits a little above the bottom of each line box
+        let y = PADDING + (index as f32 + 0.75) * LINE_HEIGHT;
+        write!(
+            output,
+            r##"<text x="{PADDING}" y="{y}" xml:space="preserve" fill="#{:02x}{:02x}{:02x}">"##,
+            foreground.r, foreground.g, foreground.b
+        )?;
+        for span in &line.spans {
+            write_span(span, output)?;
+        }
+        writeln!(output, "</text>")?;
+    }
+
+    writeln!(output, "</svg>")
+}
+
+/// Write a styled span as a `<tspan>`, wrapped in a link if it carries an OSC8 hyperlink
+fn write_span(span: &RatatuiSpan, output: &mut impl Write) -> Result {
+    let (url, text) = split_hyperlink(&span.content);
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(url) = url {
+        output.write_str(r#"<a href=""#)?;
+        write_escaped(url, output)?;
+        output.write_str(r#"">"#)?;
+    }
+
+    let style = span.style;
+    output.write_str("<tspan")?;
+    if let Some(fill) = style.fg.and_then(hex_color) {
+        write!(output, r#" fill="{fill}""#)?;
+    }
+    if style.add_modifier.contains(Modifier::BOLD) {
+        output.write_str(r#" font-weight="bold""#)?;
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        output.write_str(r#" font-style="italic""#)?;
+    }
+    if style.add_modifier.contains(Modifier::UNDERLINED) {
+        output.write_str(r#" text-decoration="underline""#)?;
+    } else if style.add_modifier.contains(Modifier::CROSSED_OUT) {
+        output.write_str(r#" text-decoration="line-through""#)?;
+    }
+    output.write_str(">")?;
+    write_escaped(text, output)?;
+    output.write_str("</tspan>")?;
+
+    if url.is_some() {
+        output.write_str("</a>")?;
+    }
+
+    Ok(())
+}
+
+/// Separate an OSC8 hyperlink (as written by the TTY renderer) from its text
+fn split_hyperlink(content: &str) -> (Option<&str>, &str) {
+    if let Some(rest) = content.strip_prefix("\x1b]8;;")
+        && let Some((url, rest)) = rest.split_once("\x1b\\")
+        && let Some(text) = rest.strip_suffix("\x1b]8;;\x1b\\")
+    {
+        (Some(url), text)
+    } else {
+        (None, content)
+    }
+}
+
+fn hex_color(color: Color) -> Option<String> {
+    match color {
+        Color::Rgb(r, g, b) => Some(format!("#{r:02x}{g:02x}{b:02x}")),
+        Color::DarkGray => Some(String::from("#808080")),
+        Color::Gray => Some(String::from("#c0c0c0")),
+        _ => None,
+    }
+}
+
+/// Escape text for XML, dropping control characters (which XML cannot represent)
+fn write_escaped(text: &str, output: &mut impl Write) -> Result {
+    for c in text.chars() {
+        match c {
+            '&' => output.write_str("&amp;")?,
+            '<' => output.write_str("&lt;")?,
+            '>' => output.write_str("&gt;")?,
+            '"' => output.write_str("&quot;")?,
+            c if c.is_control() => {}
+            c => output.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::OutputMode;
+    use crate::styled_string::{DocumentNode, Span};
+
+    #[test]
+    fn test_render_svg() {
+        let doc = Document::with_nodes(vec![DocumentNode::paragraph(vec![
+            Span::keyword("struct"),
+            Span::plain(" "),
+            Span::type_name("Foo<T>"),
+        ])]);
+        let mut output = String::new();
+        let render_context = RenderContext::new().with_output_mode(OutputMode::Svg);
+        render(&doc, &render_context, &mut output).unwrap();
+
+        assert!(output.starts_with("<svg"));
+        assert!(output.trim_end().ends_with("</svg>"));
+        assert!(output.contains(">struct</tspan>"));
+        assert!(output.contains("Foo&lt;T&gt;"));
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_split_hyperlink() {
+        assert_eq!(
+            split_hyperlink("\x1b]8;;https://docs.rs\x1b\\docs\x1b]8;;\x1b\\"),
+            (Some("https://docs.rs"), "docs")
+        );
+        assert_eq!(split_hyperlink("plain"), (None, "plain"));
+    }
+}
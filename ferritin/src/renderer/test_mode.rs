@@ -53,12 +53,28 @@ fn render_node(node: &DocumentNode, output: &mut impl Write) -> Result {
             writeln!(output, "</list>")?;
             Ok(())
         }
-        DocumentNode::CodeBlock { lang, code } => {
+        DocumentNode::CodeBlock {
+            lang,
+            code,
+            line_numbers,
+            ..
+        } => {
             let lang_attr = lang
                 .as_ref()
                 .map(|l| format!(" lang=\"{}\"", l))
                 .unwrap_or_default();
-            writeln!(output, "<code-block{}>", lang_attr)?;
+            let line_numbers_attr = line_numbers
+                .as_ref()
+                .map(|line_numbers| {
+                    format!(
+                        " first-line=\"{}\" highlighted=\"{}-{}\"",
+                        line_numbers.first,
+                        line_numbers.highlighted.start(),
+                        line_numbers.highlighted.end()
+                    )
+                })
+                .unwrap_or_default();
+            writeln!(output, "<code-block{lang_attr}{line_numbers_attr}>")?;
             write!(output, "{code}")?;
             if !code.ends_with('\n') {
                 writeln!(output)?;
@@ -76,6 +92,10 @@ fn render_node(node: &DocumentNode, output: &mut impl Write) -> Result {
             writeln!(output, "<hr/>")?;
             Ok(())
         }
+        DocumentNode::Image { url, alt } => {
+            writeln!(output, "<image url=\"{url}\" alt=\"{alt}\"/>")?;
+            Ok(())
+        }
         DocumentNode::BlockQuote { nodes } => {
             writeln!(output, "<blockquote>")?;
             render_nodes(nodes, output)?;
@@ -173,6 +193,9 @@ fn render_span(span: &Span, output: &mut impl Write) -> Result {
         SpanStyle::FieldName => "field-name",
         SpanStyle::Lifetime => "lifetime",
         SpanStyle::Generic => "generic",
+        SpanStyle::MacroName => "macro-name",
+        SpanStyle::ConstName => "const-name",
+        SpanStyle::ModuleName => "module-name",
         SpanStyle::Plain => {
             // Plain text has no tag
             write!(output, "{}", &span.text)?;
@@ -221,6 +244,7 @@ fn count_chars_in_node(node: &DocumentNode) -> usize {
         DocumentNode::CodeBlock { code, .. } => code.len(),
         DocumentNode::GeneratedCode { spans } => spans.iter().map(|s| s.text.len()).sum(),
         DocumentNode::HorizontalRule => 3, // "---"
+        DocumentNode::Image { alt, .. } => alt.len(),
         DocumentNode::BlockQuote { nodes } => count_chars_in_nodes(nodes),
         DocumentNode::Table { header, rows } => {
             let header_len = header.as_ref().map_or(0, |h| {
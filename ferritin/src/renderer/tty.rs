@@ -14,9 +14,12 @@
 
 use std::fmt::{Result, Write};
 
+use crate::color_depth::ColorDepth;
 use crate::render_context::RenderContext;
+use crate::renderer::{display_width, is_preformatted, truncate_to_width};
 use crate::styled_string::{
-    Document, DocumentNode, HeadingLevel, ShowWhen, Span, SpanStyle, TruncationLevel,
+    Document, DocumentNode, HeadingLevel, LineNumbers, ListItem, ShowWhen, Span, SpanStyle,
+    TableCell, TruncationLevel,
 };
 use ratatui::{
     style::{Color, Modifier, Style},
@@ -24,6 +27,7 @@ use ratatui::{
 };
 use syntect::easy::HighlightLines;
 use syntect::util::LinesWithEndings;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Render budget for truncation
 #[derive(Clone)]
@@ -61,8 +65,9 @@ impl RenderBudget {
                 }
 
                 // Check budget
-                if text.len() <= *remaining {
-                    *remaining -= text.len();
+                let width = display_width(text);
+                if width <= *remaining {
+                    *remaining -= width;
                     None
                 } else {
                     let truncated = truncate_at_word_boundary(text, *remaining);
@@ -103,15 +108,16 @@ impl RenderBudget {
 
 /// Truncate at word boundary
 fn truncate_at_word_boundary(text: &str, max_chars: usize) -> &str {
-    if text.len() <= max_chars {
+    let truncated = truncate_to_width(text, max_chars);
+    if truncated.len() == text.len() {
         return text;
     }
 
     // Find last whitespace before max_chars
-    if let Some(pos) = text[..max_chars].rfind(char::is_whitespace) {
+    if let Some(pos) = truncated.rfind(char::is_whitespace) {
         &text[..pos]
     } else {
-        &text[..max_chars]
+        truncated
     }
 }
 
@@ -122,25 +128,19 @@ fn find_wrap_position(text: &str, max_width: usize) -> Option<usize> {
         return None;
     }
 
-    // Find the byte position that corresponds to max_width characters (char-boundary-safe)
-    let search_end = text
-        .char_indices()
-        .take(max_width)
-        .last()
-        .map(|(idx, ch)| idx + ch.len_utf8())
-        .unwrap_or(0);
-
-    let search_range = &text[..search_end];
+    // Find the byte position that corresponds to max_width columns (char-boundary-safe)
+    let search_range = truncate_to_width(text, max_width);
 
     // First priority: break at whitespace
     if let Some(pos) = search_range.rfind(char::is_whitespace) {
         // Avoid breaking if it would leave a very short word (< 3 chars) on next line
         // This prevents orphans like "a" or "is" at the start of a line
-        if pos > 0 && text.len() - pos > 3 {
+        let remaining_width = display_width(&text[pos..]);
+        if pos > 0 && remaining_width > 3 {
             return Some(pos);
         }
         // If the remaining part is short enough, it's ok to break here
-        if text.len() - pos <= max_width / 2 {
+        if remaining_width <= max_width / 2 {
             return Some(pos);
         }
     }
@@ -158,14 +158,13 @@ fn find_wrap_position(text: &str, max_width: usize) -> Option<usize> {
 
     // Third priority: break at word boundaries (after lowercase before uppercase)
     // This helps with camelCase or PascalCase identifiers
-    for i in (1..search_range.len()).rev() {
-        let chars: Vec<char> = search_range.chars().collect();
-        if i < chars.len() - 1 {
-            let prev = chars[i - 1];
-            let curr = chars[i];
-            if prev.is_lowercase() && curr.is_uppercase() {
-                return Some(i);
-            }
+    let pairs = search_range
+        .char_indices()
+        .zip(search_range.chars().skip(1))
+        .collect::<Vec<_>>();
+    for &((idx, prev), curr) in pairs.iter().rev().skip(1) {
+        if prev.is_lowercase() && curr.is_uppercase() {
+            return Some(idx + prev.len_utf8());
         }
     }
 
@@ -217,34 +216,91 @@ fn has_meaningful_content(nodes: &[DocumentNode]) -> bool {
 }
 
 /// Render a document with ratatui for one-shot terminal output
+///
+/// Lines are written as each block or list item is laid out, rather than after laying out the
+/// whole document, so a long listing starts printing right away and is never held in memory
+/// as lines all at once.
 pub fn render(
     document: &Document,
     render_context: &RenderContext,
     output: &mut impl Write,
 ) -> Result {
-    // Build ratatui lines from document
+    write_block_sequence(&document.nodes, render_context, output)
+}
+
+/// Write blocks with blank lines between them, as [`build_lines`] lays them out
+fn write_block_sequence(
+    nodes: &[DocumentNode],
+    render_context: &RenderContext,
+    output: &mut impl Write,
+) -> Result {
+    for (idx, node) in nodes.iter().enumerate() {
+        if idx > 0 {
+            writeln!(output)?; // Blank line between consecutive blocks
+        }
+        write_node(node, render_context, output)?;
+    }
+    Ok(())
+}
+
+/// Write a top-level block, descending into sections and lists so that each of their children
+/// is written as soon as it is laid out
+fn write_node(
+    node: &DocumentNode,
+    render_context: &RenderContext,
+    output: &mut impl Write,
+) -> Result {
     let mut budget = RenderBudget::Unlimited;
-    let lines = build_lines(&document.nodes, render_context, &mut budget);
+    let mut lines = Vec::new();
+
+    match node {
+        DocumentNode::Section { title, nodes } => {
+            if let Some(title_spans) = title {
+                build_section_title_lines(title_spans, render_context, &mut lines);
+                write_lines(&lines, render_context.color_depth(), output)?;
+            }
+            write_block_sequence(nodes, render_context, output)
+        }
+        DocumentNode::List { items } => {
+            for (idx, item) in items.iter().enumerate() {
+                if idx > 0 {
+                    writeln!(output)?; // Blank line between list items
+                }
+                lines.clear();
+                build_list_item_lines(item, render_context, &budget, &mut lines, 0);
+                write_lines(&lines, render_context.color_depth(), output)?;
+            }
+            Ok(())
+        }
+        _ => {
+            build_node_lines(node, render_context, &mut budget, &mut lines, 0);
+            write_lines(&lines, render_context.color_depth(), output)
+        }
+    }
+}
 
-    // Write lines directly to output
+fn write_lines(lines: &[Line], color_depth: ColorDepth, output: &mut impl Write) -> Result {
     for line in lines {
-        write_line_to_output(&line, output)?;
+        write_line_to_output(line, color_depth, output)?;
         writeln!(output)?;
     }
-
     Ok(())
 }
 
 /// Write a ratatui Line to output with ANSI codes
-fn write_line_to_output(line: &Line, output: &mut impl Write) -> Result {
+fn write_line_to_output(line: &Line, color_depth: ColorDepth, output: &mut impl Write) -> Result {
     for span in &line.spans {
-        write_styled_span(span, output)?;
+        write_styled_span(span, color_depth, output)?;
     }
     Ok(())
 }
 
-/// Write a styled span with ANSI codes
-fn write_styled_span(span: &RatatuiSpan, output: &mut impl Write) -> Result {
+/// Write a styled span with ANSI codes, in the nearest colors the terminal can show
+fn write_styled_span(
+    span: &RatatuiSpan,
+    color_depth: ColorDepth,
+    output: &mut impl Write,
+) -> Result {
     let style = span.style;
 
     // Build ANSI escape sequence
@@ -252,8 +308,9 @@ fn write_styled_span(span: &RatatuiSpan, output: &mut impl Write) -> Result {
 
     if let Some(fg) = style.fg
         && let Color::Rgb(r, g, b) = fg
+        && let Some(code) = color_depth.foreground_sgr(r, g, b)
     {
-        codes.push(format!("38;2;{};{};{}", r, g, b));
+        codes.push(code);
     }
 
     if style.add_modifier.contains(Modifier::BOLD) {
@@ -303,6 +360,54 @@ pub(super) fn build_lines<'a>(
     lines
 }
 
+/// Build the bold title line of a section, and the blank line after it
+fn build_section_title_lines<'a>(
+    title_spans: &'a [Span],
+    render_context: &RenderContext,
+    lines: &mut Vec<Line<'a>>,
+) {
+    let mut heading_spans = Vec::new();
+    for span in title_spans {
+        heading_spans.push(convert_span_bold(span, render_context));
+    }
+    lines.push(Line::from(heading_spans));
+    lines.push(Line::from(vec![])); // Blank line after section title
+}
+
+/// Build lines for one list item, with its bullet
+fn build_list_item_lines<'a>(
+    item: &'a ListItem,
+    render_context: &RenderContext,
+    budget: &RenderBudget,
+    lines: &mut Vec<Line<'a>>,
+    indent: usize,
+) {
+    // Render item content with proper indentation
+    if item.content.is_empty() {
+        return;
+    }
+    let start_idx = lines.len();
+
+    // Render all content nodes
+    for node in &item.content {
+        let mut item_budget = budget.clone();
+        build_node_lines(node, render_context, &mut item_budget, lines, 4);
+    }
+
+    // Add bullet and indentation to all lines
+    for (line_idx, line) in lines[start_idx..].iter_mut().enumerate() {
+        if line_idx == 0 {
+            // First line: add bullet based on nesting level
+            let bullet = crate::renderer::bullet_for_indent(indent as u16);
+            line.spans
+                .insert(0, RatatuiSpan::raw(format!("  {} ", bullet)));
+        } else {
+            // Subsequent lines: add indentation (4 spaces to align with content after bullet)
+            line.spans.insert(0, RatatuiSpan::raw("    "));
+        }
+    }
+}
+
 /// Build lines for a single node
 fn build_node_lines<'a>(
     node: &'a DocumentNode,
@@ -319,7 +424,7 @@ fn build_node_lines<'a>(
         DocumentNode::Paragraph { spans } => {
             // Start a new line for paragraph
             let start_idx = lines.len();
-            let terminal_width = render_context.terminal_width() as usize;
+            let terminal_width = render_context.wrap_width().unwrap_or(usize::MAX);
 
             // Track current line position for word wrapping (accounting for indent)
             let mut current_line_len = indent;
@@ -361,7 +466,7 @@ fn build_node_lines<'a>(
                             continue;
                         }
 
-                        if remaining.len() <= available_width {
+                        if display_width(remaining) <= available_width {
                             // Fits on current line
                             let span_to_add = RatatuiSpan::styled(make_text(remaining), style);
                             if lines.len() == start_idx {
@@ -374,7 +479,7 @@ fn build_node_lines<'a>(
                                 // Continuing current line
                                 lines.last_mut().unwrap().spans.push(span_to_add);
                             }
-                            current_line_len += remaining.len();
+                            current_line_len += display_width(remaining);
                             break;
                         } else {
                             // Need to wrap - find best break point
@@ -397,7 +502,7 @@ fn build_node_lines<'a>(
                                 // Look for the next break point beyond the available width
                                 if let Some(next_space) = remaining.find(char::is_whitespace) {
                                     // Check if the word will fit on the current line
-                                    if next_space <= available_width {
+                                    if display_width(&remaining[..next_space]) <= available_width {
                                         // Word fits on current line, write it
                                         let (chunk, rest) = remaining.split_at(next_space);
                                         let span_to_add =
@@ -419,7 +524,7 @@ fn build_node_lines<'a>(
                                 } else {
                                     // No whitespace at all in remaining text
                                     // If it fits, write it; otherwise we need to hard-break
-                                    if remaining.len() <= available_width {
+                                    if display_width(remaining) <= available_width {
                                         let span_to_add =
                                             RatatuiSpan::styled(make_text(remaining), style);
                                         if lines.len() == start_idx {
@@ -429,7 +534,7 @@ fn build_node_lines<'a>(
                                         } else {
                                             lines.last_mut().unwrap().spans.push(span_to_add);
                                         }
-                                        current_line_len += remaining.len();
+                                        current_line_len += display_width(remaining);
                                         break;
                                     } else {
                                         // Doesn't fit even on a new line - need to hard-break mid-word
@@ -438,8 +543,15 @@ fn build_node_lines<'a>(
                                             // Already on a fresh line, must hard-break
                                             let max_fit =
                                                 terminal_width.saturating_sub(indent).max(1);
-                                            let (chunk, rest) =
-                                                remaining.split_at(max_fit.min(remaining.len()));
+                                            let mut chunk = truncate_to_width(remaining, max_fit);
+                                            if chunk.is_empty() {
+                                                // A wide character on a very narrow terminal
+                                                chunk = remaining
+                                                    .graphemes(true)
+                                                    .next()
+                                                    .unwrap_or(remaining);
+                                            }
+                                            let rest = &remaining[chunk.len()..];
                                             let span_to_add =
                                                 RatatuiSpan::styled(make_text(chunk), style);
                                             lines.push(Line::from(vec![span_to_add]));
@@ -487,12 +599,7 @@ fn build_node_lines<'a>(
         }
         DocumentNode::Section { title, nodes } => {
             if let Some(title_spans) = title {
-                let mut heading_spans = Vec::new();
-                for span in title_spans {
-                    heading_spans.push(convert_span_bold(span, render_context));
-                }
-                lines.push(Line::from(heading_spans));
-                lines.push(Line::from(vec![])); // Blank line after section title
+                build_section_title_lines(title_spans, render_context, lines);
             }
 
             // Render children with blank lines between them
@@ -511,38 +618,26 @@ fn build_node_lines<'a>(
                 if idx > 0 {
                     lines.push(Line::from(vec![])); // Blank line between list items
                 }
-
-                // Render item content with proper indentation
-                if !item.content.is_empty() {
-                    let start_idx = lines.len();
-
-                    // Render all content nodes
-                    for node in &item.content {
-                        let mut item_budget = budget.clone();
-                        build_node_lines(node, render_context, &mut item_budget, lines, 4);
-                    }
-
-                    // Add bullet and indentation to all lines
-                    for (line_idx, line) in lines[start_idx..].iter_mut().enumerate() {
-                        if line_idx == 0 {
-                            // First line: add bullet based on nesting level
-                            let bullet = crate::renderer::bullet_for_indent(indent as u16);
-                            line.spans
-                                .insert(0, RatatuiSpan::raw(format!("  {} ", bullet)));
-                        } else {
-                            // Subsequent lines: add indentation (4 spaces to align with content after bullet)
-                            line.spans.insert(0, RatatuiSpan::raw("    "));
-                        }
-                    }
-                }
+                build_list_item_lines(item, render_context, budget, lines, indent);
             }
         }
-        DocumentNode::CodeBlock { lang, code } => {
+        DocumentNode::CodeBlock {
+            lang,
+            code,
+            line_numbers,
+            ..
+        } => {
             if matches!(budget, RenderBudget::Characters { .. }) {
                 return;
             }
 
-            lines.extend(render_code_block(lang.as_deref(), code, render_context));
+            lines.extend(render_code_block(
+                lang.as_deref(),
+                code,
+                line_numbers.as_ref(),
+                render_context,
+                indent,
+            ));
         }
         DocumentNode::GeneratedCode { spans } => {
             let code_spans: Vec<_> = spans
@@ -552,6 +647,19 @@ fn build_node_lines<'a>(
             lines.push(Line::from(code_spans));
             // Spacing between blocks handled by containers
         }
+        DocumentNode::Image { url, alt } => {
+            if matches!(budget, RenderBudget::Characters { .. }) {
+                return;
+            }
+
+            let link = Span::image_link(url, alt);
+            let style = span_style_to_ratatui(link.style, render_context)
+                .add_modifier(Modifier::UNDERLINED);
+            lines.push(Line::from(RatatuiSpan::styled(
+                wrap_with_osc8(&link.text, url),
+                style,
+            )));
+        }
         DocumentNode::HorizontalRule => {
             if matches!(budget, RenderBudget::Characters { .. }) {
                 return;
@@ -582,7 +690,12 @@ fn build_node_lines<'a>(
                 return;
             }
 
-            lines.extend(render_table(header.as_deref(), rows, render_context));
+            lines.extend(render_table(
+                header.as_deref(),
+                rows,
+                render_context,
+                indent,
+            ));
         }
         DocumentNode::TruncatedBlock { nodes, level } => {
             // For SingleLine with heading as first node, just show the heading text (no decoration)
@@ -722,20 +835,16 @@ fn build_node_lines<'a>(
     }
 }
 
-/// Render table with UTF-8 borders
+/// Render a table, wrapping cells so that it fits the terminal. Columns are drawn with
+/// UTF-8 borders, or just set apart by spaces without decorations.
 fn render_table<'a>(
-    header: Option<&[crate::styled_string::TableCell<'a>]>,
-    rows: &[Vec<crate::styled_string::TableCell<'a>>],
+    header: Option<&[TableCell<'a>]>,
+    rows: &[Vec<TableCell<'a>>],
     render_context: &RenderContext,
+    indent: usize,
 ) -> Vec<Line<'a>> {
     let mut lines = Vec::new();
 
-    if rows.is_empty() && header.is_none() {
-        return lines;
-    }
-
-    let border_style = Style::default().fg(Color::DarkGray);
-
     // Calculate column widths based on content
     let num_cols = header
         .map(|h| h.len())
@@ -746,151 +855,227 @@ fn render_table<'a>(
         return lines;
     }
 
-    let mut col_widths = vec![0usize; num_cols];
+    let bordered = render_context.show_decorations();
+    let border_style = Style::default().fg(Color::DarkGray);
 
-    // Measure header widths
-    if let Some(header_cells) = header {
-        for (col_idx, cell) in header_cells.iter().enumerate() {
-            let width = cell.spans.iter().map(|s| s.text.len()).sum::<usize>();
-            col_widths[col_idx] = col_widths[col_idx].max(width);
+    let mut natural_widths = vec![0usize; num_cols];
+    for row_cells in header.into_iter().chain(rows.iter().map(Vec::as_slice)) {
+        for (col_idx, cell) in row_cells.iter().enumerate().take(num_cols) {
+            natural_widths[col_idx] = natural_widths[col_idx].max(cell_width(&cell.spans));
         }
     }
 
-    // Measure row widths
-    for row_cells in rows {
-        for (col_idx, cell) in row_cells.iter().enumerate() {
-            if col_idx < num_cols {
-                let width = cell.spans.iter().map(|s| s.text.len()).sum::<usize>();
-                col_widths[col_idx] = col_widths[col_idx].max(width);
+    // Each column has "│ " before it and a space after it, and the last one a closing "│"
+    let chrome = if bordered {
+        3 * num_cols + 1
+    } else {
+        COLUMN_GAP * (num_cols - 1)
+    };
+    let available = render_context
+        .wrap_width()
+        .unwrap_or(usize::MAX)
+        .saturating_sub(indent + chrome);
+    let col_widths = balance_column_widths(&natural_widths, available);
+
+    let rule = |left: char, middle: char, right: char| {
+        let mut rule = String::new();
+        rule.push(left);
+        for (idx, &width) in col_widths.iter().enumerate() {
+            rule.push_str(&"─".repeat(width + 2));
+            rule.push(if idx < col_widths.len() - 1 {
+                middle
+            } else {
+                right
+            });
+        }
+        Line::from(vec![RatatuiSpan::styled(rule, border_style)])
+    };
+
+    let push_row = |lines: &mut Vec<Line<'a>>, cells: &[TableCell<'a>], modifier: Modifier| {
+        let wrapped = col_widths
+            .iter()
+            .enumerate()
+            .map(|(col_idx, &width)| {
+                let spans = cells.get(col_idx).map_or(&[][..], |cell| &cell.spans[..]);
+                wrap_cell(spans, width, render_context, modifier)
+            })
+            .collect::<Vec<_>>();
+        let height = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+
+        for line_idx in 0..height {
+            let mut spans = Vec::new();
+            for (col_idx, cell_lines) in wrapped.iter().enumerate() {
+                let cell_line = cell_lines.get(line_idx).cloned().unwrap_or_default();
+                let line_width = cell_line
+                    .iter()
+                    .map(|span| display_width(&span.content))
+                    .sum::<usize>();
+                let padding = " ".repeat(col_widths[col_idx].saturating_sub(line_width));
+
+                if bordered {
+                    spans.push(RatatuiSpan::styled("│ ", border_style));
+                } else if col_idx > 0 {
+                    spans.push(RatatuiSpan::raw(" ".repeat(COLUMN_GAP)));
+                }
+                spans.extend(cell_line);
+                if bordered || col_idx < col_widths.len() - 1 {
+                    spans.push(RatatuiSpan::raw(padding));
+                }
+                if bordered {
+                    spans.push(RatatuiSpan::raw(" "));
+                }
+            }
+            if bordered {
+                spans.push(RatatuiSpan::styled("│", border_style));
             }
+            lines.push(Line::from(spans));
         }
-    }
+    };
 
-    // Cap column widths to reasonable sizes
-    let max_col_width = 40;
-    for width in &mut col_widths {
-        *width = (*width).min(max_col_width);
+    if bordered {
+        // Top border: ┌─────┬─────┐
+        lines.push(rule('┌', '┬', '┐'));
     }
 
-    // Top border: ┌─────┬─────┐
-    let mut top_border = String::new();
-    top_border.push('┌');
-    for (idx, &width) in col_widths.iter().enumerate() {
-        top_border.push_str(&"─".repeat(width));
-        if idx < col_widths.len() - 1 {
-            top_border.push('┬');
+    if let Some(header_cells) = header {
+        push_row(&mut lines, header_cells, Modifier::BOLD);
+
+        // Header separator: ├─────┼─────┤, or a rule under each column
+        if bordered {
+            lines.push(rule('├', '┼', '┤'));
+        } else {
+            let underlines = col_widths
+                .iter()
+                .map(|&width| "─".repeat(width))
+                .collect::<Vec<_>>()
+                .join(&" ".repeat(COLUMN_GAP));
+            lines.push(Line::from(vec![RatatuiSpan::styled(
+                underlines,
+                border_style,
+            )]));
         }
     }
-    top_border.push('┐');
-    lines.push(Line::from(vec![RatatuiSpan::styled(
-        top_border,
-        border_style,
-    )]));
 
-    // Render header if present
-    if let Some(header_cells) = header {
-        let mut header_spans = vec![RatatuiSpan::styled("│", border_style)];
+    for row_cells in rows {
+        push_row(&mut lines, row_cells, Modifier::empty());
+    }
 
-        for (col_idx, cell) in header_cells.iter().enumerate() {
-            let mut cell_text = String::new();
-            for span in &cell.spans {
-                let span_text = if span.text.len() > col_widths[col_idx] {
-                    &span.text[..col_widths[col_idx]]
-                } else {
-                    &span.text
-                };
-                cell_text.push_str(span_text);
-            }
+    if bordered {
+        // Bottom border: └─────┴─────┘
+        lines.push(rule('└', '┴', '┘'));
+    }
 
-            // Pad to column width
-            while cell_text.len() < col_widths[col_idx] {
-                cell_text.push(' ');
-            }
+    // Add blank line after table
+    lines.push(Line::from(vec![]));
 
-            let mut style = span_style_to_ratatui(
-                cell.spans
-                    .first()
-                    .map(|s| s.style)
-                    .unwrap_or(crate::styled_string::SpanStyle::Plain),
-                render_context,
-            );
-            style = style.add_modifier(Modifier::BOLD);
-            header_spans.push(RatatuiSpan::styled(cell_text, style));
-            header_spans.push(RatatuiSpan::styled("│", border_style));
-        }
-        lines.push(Line::from(header_spans));
+    lines
+}
 
-        // Header separator: ├─────┼─────┤
-        let mut header_sep = String::new();
-        header_sep.push('├');
-        for (idx, &width) in col_widths.iter().enumerate() {
-            header_sep.push_str(&"─".repeat(width));
-            if idx < col_widths.len() - 1 {
-                header_sep.push('┼');
-            }
-        }
-        header_sep.push('┤');
-        lines.push(Line::from(vec![RatatuiSpan::styled(
-            header_sep,
-            border_style,
-        )]));
+/// Spaces between columns of a table without borders
+const COLUMN_GAP: usize = 2;
+
+/// Width of a cell's text on one line, with runs of whitespace collapsed to a space
+fn cell_width(spans: &[Span]) -> usize {
+    let text = spans.iter().map(|span| &*span.text).collect::<String>();
+    let words = text
+        .split_whitespace()
+        .map(display_width)
+        .collect::<Vec<_>>();
+    words.iter().sum::<usize>() + words.len().saturating_sub(1)
+}
+
+/// Share `available` columns between table columns that would like `natural_widths`. Columns
+/// narrower than an even share keep their width, and the rest split what's left evenly, so
+/// one long column doesn't squeeze every other column.
+fn balance_column_widths(natural_widths: &[usize], available: usize) -> Vec<usize> {
+    if natural_widths.iter().sum::<usize>() <= available {
+        return natural_widths.to_vec();
     }
 
-    // Render rows
-    for row_cells in rows.iter() {
-        let mut row_spans = vec![RatatuiSpan::styled("│", border_style)];
+    let mut by_width = (0..natural_widths.len()).collect::<Vec<_>>();
+    by_width.sort_by_key(|&idx| natural_widths[idx]);
 
-        for (col_idx, cell) in row_cells.iter().enumerate() {
-            if col_idx >= num_cols {
-                break;
-            }
+    let mut widths = vec![0; natural_widths.len()];
+    let mut remaining = available;
+    for (position, &idx) in by_width.iter().enumerate() {
+        let share = remaining / (by_width.len() - position);
+        widths[idx] = natural_widths[idx].min(share).max(1);
+        remaining = remaining.saturating_sub(widths[idx]);
+    }
+    widths
+}
 
-            let mut cell_text = String::new();
-            for span in &cell.spans {
-                let span_text = if span.text.len() > col_widths[col_idx] {
-                    &span.text[..col_widths[col_idx]]
-                } else {
-                    &span.text
-                };
-                cell_text.push_str(span_text);
+/// A cell's text as lines no wider than `width`, breaking between words where possible and
+/// keeping each span's style
+fn wrap_cell(
+    spans: &[Span],
+    width: usize,
+    render_context: &RenderContext,
+    modifier: Modifier,
+) -> Vec<Vec<RatatuiSpan<'static>>> {
+    // Words, each made of pieces of differently styled text
+    let mut words: Vec<Vec<(String, Style)>> = Vec::new();
+    let mut in_word = false;
+    for span in spans {
+        let style = span_style_to_ratatui(span.style, render_context).add_modifier(modifier);
+        for ch in span.text.chars() {
+            if ch.is_whitespace() {
+                in_word = false;
+                continue;
             }
-
-            // Pad to column width
-            while cell_text.len() < col_widths[col_idx] {
-                cell_text.push(' ');
+            if !in_word {
+                words.push(Vec::new());
+                in_word = true;
+            }
+            let Some(word) = words.last_mut() else {
+                continue;
+            };
+            match word.last_mut() {
+                Some((text, piece_style)) if *piece_style == style => text.push(ch),
+                _ => word.push((ch.to_string(), style)),
             }
-
-            let style = span_style_to_ratatui(
-                cell.spans
-                    .first()
-                    .map(|s| s.style)
-                    .unwrap_or(crate::styled_string::SpanStyle::Plain),
-                render_context,
-            );
-            row_spans.push(RatatuiSpan::styled(cell_text, style));
-            row_spans.push(RatatuiSpan::styled("│", border_style));
         }
-        lines.push(Line::from(row_spans));
     }
 
-    // Bottom border: └─────┴─────┘
-    let mut bottom_border = String::new();
-    bottom_border.push('└');
-    for (idx, &width) in col_widths.iter().enumerate() {
-        bottom_border.push_str(&"─".repeat(width));
-        if idx < col_widths.len() - 1 {
-            bottom_border.push('┴');
+    let mut lines = vec![Vec::new()];
+    let mut line_width = 0;
+    for word in words {
+        let word_width = word
+            .iter()
+            .map(|(text, _)| display_width(text))
+            .sum::<usize>();
+        if line_width > 0 && line_width + 1 + word_width > width {
+            lines.push(Vec::new());
+            line_width = 0;
+        }
+        if line_width > 0 {
+            lines.last_mut().unwrap().push(RatatuiSpan::raw(" "));
+            line_width += 1;
         }
-    }
-    bottom_border.push('┘');
-    lines.push(Line::from(vec![RatatuiSpan::styled(
-        bottom_border,
-        border_style,
-    )]));
-
-    // Add blank line after table
-    lines.push(Line::from(vec![]));
 
+        // A word wider than the column is broken wherever it runs out of room
+        for (text, style) in word {
+            let mut rest = text.as_str();
+            while !rest.is_empty() {
+                let mut chunk = truncate_to_width(rest, width.saturating_sub(line_width));
+                if chunk.is_empty() {
+                    if line_width > 0 {
+                        lines.push(Vec::new());
+                        line_width = 0;
+                        continue;
+                    }
+                    // A wide character in a column too narrow for it
+                    chunk = rest.graphemes(true).next().unwrap_or(rest);
+                }
+                lines
+                    .last_mut()
+                    .unwrap()
+                    .push(RatatuiSpan::styled(chunk.to_string(), style));
+                line_width += display_width(chunk);
+                rest = &rest[chunk.len()..];
+            }
+        }
+    }
     lines
 }
 
@@ -898,10 +1083,25 @@ fn render_table<'a>(
 fn render_code_block<'a>(
     lang: Option<&str>,
     code: &'a str,
+    line_numbers: Option<&LineNumbers>,
     render_context: &RenderContext,
+    indent: usize,
 ) -> Vec<Line<'a>> {
     let mut lines = Vec::new();
 
+    // Text laid out by hand is cut off at the edge, since wrapping would break up its columns
+    if is_preformatted(lang) {
+        let width = render_context
+            .wrap_width()
+            .map_or(usize::MAX, |width| width.saturating_sub(indent));
+        lines.extend(
+            code.lines()
+                .map(|line| Line::from(truncate_to_width(line, width))),
+        );
+        lines.push(Line::from(""));
+        return lines;
+    }
+
     // Normalize rustdoc pseudo-languages to "rust"
     let lang = match lang {
         Some("no_run") | Some("should_panic") | Some("ignore") | Some("compile_fail")
@@ -937,6 +1137,21 @@ fn render_code_block<'a>(
         }
     }
 
+    // Number the lines of quoted source, dimming the context around the item's own lines
+    if let Some(line_numbers) = line_numbers {
+        let line_count = lines.len();
+        for (idx, line) in lines.iter_mut().enumerate() {
+            let (number, highlighted) = line_numbers.line(idx);
+            let style = if highlighted {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            let gutter = line_numbers.gutter(number, line_count);
+            line.spans.insert(0, RatatuiSpan::styled(gutter, style));
+        }
+    }
+
     lines.push(Line::from(""));
     lines
 }
@@ -1048,4 +1263,138 @@ mod tests {
         // Should have decorative underline
         assert!(output.contains("=========="));
     }
+
+    #[test]
+    fn paragraphs_are_left_unwrapped_without_wrapping() {
+        let text = "a paragraph that is longer than the terminal is wide";
+        let doc = Document::with_nodes(vec![DocumentNode::paragraph(vec![Span::plain(text)])]);
+        let render_context = RenderContext::new()
+            .with_output_mode(OutputMode::Tty)
+            .with_terminal_width(20)
+            .with_wrap(false);
+
+        let lines = build_lines(&doc.nodes, &render_context, &mut RenderBudget::Unlimited);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].to_string(), text);
+    }
+
+    #[test]
+    fn wide_characters_are_laid_out_by_columns() {
+        let doc = Document::with_nodes(vec![
+            DocumentNode::paragraph(vec![Span::plain("日本語の 文章を 折り返す テスト")]),
+            DocumentNode::table(
+                Some(vec![
+                    TableCell::from_span(Span::plain("名前")),
+                    TableCell::from_span(Span::plain("kind")),
+                ]),
+                vec![vec![
+                    TableCell::from_span(Span::plain("Vec")),
+                    TableCell::from_span(Span::plain("構造体")),
+                ]],
+            ),
+        ]);
+        let render_context = RenderContext::new()
+            .with_output_mode(OutputMode::Tty)
+            .with_terminal_width(16);
+
+        let lines = build_lines(&doc.nodes, &render_context, &mut RenderBudget::Unlimited);
+        let widths = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| display_width(&span.content))
+                    .sum::<usize>()
+            })
+            .collect::<Vec<_>>();
+
+        // Each wrapped line fits, and every row of the table is as wide as its borders
+        assert!(widths.iter().all(|&width| width <= 16), "{widths:?}");
+        let table_widths = &widths[widths.len() - 6..widths.len() - 1];
+        assert!(
+            table_widths.iter().all(|&width| width == table_widths[0]),
+            "{widths:?}"
+        );
+    }
+
+    #[test]
+    fn tables_are_balanced_to_fit_the_terminal() {
+        assert_eq!(balance_column_widths(&[3, 50, 10], 30), vec![3, 17, 10]);
+        assert_eq!(balance_column_widths(&[3, 5], 30), vec![3, 5]);
+
+        let doc = Document::with_nodes(vec![DocumentNode::table(
+            Some(vec![
+                TableCell::from_span(Span::plain("Feature")),
+                TableCell::from_span(Span::plain("Description")),
+            ]),
+            vec![vec![
+                TableCell::from_span(Span::inline_code("std")),
+                TableCell::from_span(Span::plain(
+                    "Enables the standard library, and with it everything that allocates",
+                )),
+            ]],
+        )]);
+        let render_context = RenderContext::new()
+            .with_output_mode(OutputMode::Tty)
+            .with_terminal_width(40);
+
+        let lines = build_lines(&doc.nodes, &render_context, &mut RenderBudget::Unlimited);
+        let text = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            text.iter().all(|line| display_width(line) <= 40),
+            "{text:#?}"
+        );
+        assert_eq!(text[1], "│ Feature │ Description                │");
+        assert_eq!(text[3], "│ std     │ Enables the standard       │");
+        assert_eq!(text[4], "│         │ library, and with it       │");
+
+        // Without decorations, columns are only set apart by spaces
+        let render_context = render_context.with_decorations(false);
+        let lines = build_lines(&doc.nodes, &render_context, &mut RenderBudget::Unlimited);
+        let header = lines[0]
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect::<String>();
+        assert_eq!(header, "Feature  Description");
+    }
+
+    #[test]
+    fn streamed_output_matches_laid_out_lines() {
+        let doc = Document::with_nodes(vec![
+            DocumentNode::heading(HeadingLevel::Title, vec![Span::plain("Title")]),
+            DocumentNode::section(
+                vec![Span::plain("Structs")],
+                vec![DocumentNode::list(vec![
+                    ListItem::new(vec![DocumentNode::paragraph(vec![Span::plain("First")])]),
+                    ListItem::new(vec![
+                        DocumentNode::paragraph(vec![Span::plain("Second")]),
+                        DocumentNode::paragraph(vec![Span::plain("More about it")]),
+                    ]),
+                ])],
+            ),
+            DocumentNode::paragraph(vec![Span::plain("After")]),
+        ]);
+        let render_context = RenderContext::new()
+            .with_output_mode(OutputMode::Tty)
+            .with_terminal_width(40);
+
+        let mut streamed = String::new();
+        render(&doc, &render_context, &mut streamed).unwrap();
+
+        let mut laid_out = String::new();
+        let lines = build_lines(&doc.nodes, &render_context, &mut RenderBudget::Unlimited);
+        write_lines(&lines, render_context.color_depth(), &mut laid_out).unwrap();
+
+        assert_eq!(streamed, laid_out);
+    }
 }
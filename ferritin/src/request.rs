@@ -1,11 +1,14 @@
 use ferritin_common::{
     Navigator,
-    sources::{DocsRsSource, LocalSource, StdSource},
+    progress::Progress,
+    sources::{DEFAULT_TOOLCHAIN, LocalSource, StdSource, VendoredSource},
 };
 use std::ops::Deref;
 use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 
+use crate::config;
+use crate::format::ExampleFiles;
 use crate::format_context::FormatContext;
 
 /// Wrapper around Navigator that adds formatting capabilities
@@ -13,6 +16,18 @@ pub(crate) struct Request {
     inner: OnceLock<Navigator>,
     manifest_path: PathBuf,
     format_context: FormatContext,
+    /// Whether the docs.rs source built by populate() may use the network
+    offline: bool,
+    /// The rustup toolchain populate() loads std docs from
+    toolchain: String,
+    /// Where populate()'s docs.rs source keeps downloads, if not the default cache
+    docs_rs_cache_dir: Option<PathBuf>,
+    /// The docs.rs mirror populate()'s docs.rs source downloads from, if not docs.rs
+    docs_rs_url: Option<String>,
+    /// Where the Navigator built by populate() reports slow operations
+    progress: Option<Arc<dyn Progress>>,
+    /// Workspace crates' example files, scanned for call sites of the items shown
+    example_files: ExampleFiles,
 }
 
 impl Deref for Request {
@@ -32,6 +47,12 @@ impl Request {
             inner: OnceLock::from(navigator),
             manifest_path: PathBuf::new(), // Not used in eager mode
             format_context,
+            offline: false,                           // Not used in eager mode
+            toolchain: DEFAULT_TOOLCHAIN.to_string(), // Not used in eager mode
+            docs_rs_cache_dir: None,                  // Not used in eager mode
+            docs_rs_url: None,                        // Not used in eager mode
+            progress: None,                           // Not used in eager mode
+            example_files: ExampleFiles::default(),
         }
     }
 
@@ -41,16 +62,51 @@ impl Request {
             inner: OnceLock::new(),
             manifest_path,
             format_context,
+            offline: false,
+            toolchain: DEFAULT_TOOLCHAIN.to_string(),
+            docs_rs_cache_dir: None,
+            docs_rs_url: None,
+            progress: None,
+            example_files: ExampleFiles::default(),
         }
     }
 
+    /// Restrict the docs.rs source to previously downloaded docs
+    pub(crate) fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Load std docs from this rustup toolchain instead of nightly
+    pub(crate) fn with_toolchain(mut self, toolchain: impl Into<String>) -> Self {
+        self.toolchain = toolchain.into();
+        self
+    }
+
+    /// Keep docs.rs downloads in `cache_dir` and download them from the mirror at `url`,
+    /// where given
+    pub(crate) fn with_docs_rs(mut self, cache_dir: Option<PathBuf>, url: Option<String>) -> Self {
+        self.docs_rs_cache_dir = cache_dir;
+        self.docs_rs_url = url;
+        self
+    }
+
+    /// Report downloads, doc builds and indexing to `progress`
+    pub(crate) fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
     /// Populate the Navigator with sources (if not already populated)
     /// This is the slow operation that loads all documentation sources
     pub(crate) fn populate(&self) {
         let manifest_path = &self.manifest_path;
         self.inner.get_or_init(|| {
-            log::info!("Checking for std documentation from rustup");
-            let std_source = StdSource::from_rustup();
+            log::info!(
+                "Checking for std documentation from the {} toolchain",
+                self.toolchain
+            );
+            let std_source = StdSource::from_toolchain(&self.toolchain);
             if let Some(std_source) = &std_source {
                 log::info!(
                     "Found std docs for {} at {}",
@@ -71,7 +127,11 @@ impl Request {
                 );
             }
             log::info!("Building a docs.rs client");
-            let docsrs_source = DocsRsSource::from_default_cache();
+            let docsrs_source = config::docs_rs_source(
+                self.docs_rs_cache_dir.as_deref(),
+                self.docs_rs_url.as_deref(),
+            )
+            .map(|docsrs_source| docsrs_source.with_offline(self.offline));
             if let Some(docsrs_source) = &docsrs_source {
                 log::info!(
                     "Built new docs.rs client with cache at {}",
@@ -79,10 +139,18 @@ impl Request {
                 );
             }
 
-            Navigator::default()
+            let vendored_source = VendoredSource::from_default_cargo_home()
+                .map(|vendored_source| vendored_source.with_offline(self.offline));
+
+            let navigator = Navigator::default()
                 .with_std_source(std_source)
                 .with_local_source(local_source)
                 .with_docsrs_source(docsrs_source)
+                .with_vendored_source(vendored_source);
+            match &self.progress {
+                Some(progress) => navigator.with_progress(progress.clone()),
+                None => navigator,
+            }
         });
     }
 
@@ -90,4 +158,9 @@ impl Request {
     pub(crate) fn format_context(&self) -> &FormatContext {
         &self.format_context
     }
+
+    /// Workspace crates' example files, read once per crate
+    pub(crate) fn example_files(&self) -> &ExampleFiles {
+        &self.example_files
+    }
 }
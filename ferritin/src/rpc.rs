@@ -0,0 +1,362 @@
+//! JSON-RPC over stdio, for editor integrations
+//!
+//! `ferritin rpc` reads one JSON-RPC 2.0 message per line on stdin and writes one response per
+//! line on stdout, so an editor plugin can keep a single process with docs loaded and ask it for
+//! hovers and side panels. The methods run the same commands as the CLI:
+//!
+//! - `resolve {path}`: the item a path names, as `ferritin resolve --json` describes it, or null
+//! - `get {path, source?, recursive?, signature?}`: an item's docs, or only its signatures
+//! - `search {query, crate?, limit?}`: items matching a query
+//! - `impls {path}`: the traits a type implements and its methods, or a trait's implementors
+//!
+//! `get` and `search` answer with the document as plain text, and as a tree of nodes whose
+//! spans carry their style and link target so a plugin can highlight and link them itself.
+
+use crate::commands::{Commands, resolve};
+use crate::render_context::RenderContext;
+use crate::renderer::{OutputMode, render};
+use crate::request::Request;
+use crate::styled_string::{Document, DocumentNode, ShowWhen, Span, TableCell, TuiAction};
+use ferritin_common::DocRef;
+use rustdoc_types::{Item, ItemEnum, Type};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::io::{self, BufRead, Write};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// In the range JSON-RPC leaves to servers
+const ITEM_NOT_FOUND: i64 = -32001;
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PathParams {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct GetParams {
+    path: String,
+    #[serde(default)]
+    source: bool,
+    #[serde(default)]
+    recursive: bool,
+    #[serde(default)]
+    signature: bool,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    query: String,
+    #[serde(rename = "crate")]
+    crate_: Option<String>,
+    #[serde(default = "default_search_limit")]
+    limit: usize,
+}
+
+fn default_search_limit() -> usize {
+    10
+}
+
+/// Answer each message read from `input` on `output` until the input ends
+pub(crate) fn serve(
+    request: &Request,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    // Editors wrap the docs to their own windows
+    let render_context = RenderContext::new()
+        .with_output_mode(OutputMode::Plain)
+        .with_wrap(false);
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = respond(request, &render_context, &line) {
+            serde_json::to_writer(&mut output, &response)?;
+            output.write_all(b"\n")?;
+            output.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The response to one message, or None for a notification, which has no id to answer
+fn respond(request: &Request, render_context: &RenderContext, line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(error) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, error.to_string()),
+            ));
+        }
+    };
+
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            id.unwrap_or(Value::Null),
+            RpcError::new(INVALID_REQUEST, "The message has no method"),
+        ));
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = call(request, render_context, method, params);
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => error_response(id, error),
+    })
+}
+
+fn error_response(id: Value, RpcError { code, message }: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn call(
+    request: &Request,
+    render_context: &RenderContext,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    match method {
+        "resolve" => {
+            let PathParams { path } = parse_params(params)?;
+            Ok(request
+                .find_path(&path)
+                .map_or(Value::Null, |item| resolve::item_json(request, item)))
+        }
+        "get" => {
+            let GetParams {
+                path,
+                source,
+                recursive,
+                signature,
+            } = parse_params(params)?;
+            let command = Commands::Get {
+                path: Some(path),
+                source,
+                recursive,
+                depth: None,
+                kinds: vec![],
+                all: false,
+                public_only: false,
+                signature,
+                explain_resolution: false,
+                batch: false,
+                separator: String::new(),
+                json: false,
+            };
+            let (document, is_error, _) = command.execute(request);
+            Ok(document_json(&document, is_error, render_context))
+        }
+        "search" => {
+            let SearchParams {
+                query,
+                crate_,
+                limit,
+            } = parse_params(params)?;
+            let command = Commands::Search {
+                query,
+                crate_,
+                limit,
+                regex: false,
+                glob: false,
+            };
+            let (document, is_error, _) = command.execute(request);
+            Ok(document_json(&document, is_error, render_context))
+        }
+        "impls" => {
+            let PathParams { path } = parse_params(params)?;
+            let item = request
+                .find_path(&path)
+                .ok_or_else(|| RpcError::new(ITEM_NOT_FOUND, format!("Could not find '{path}'")))?;
+            Ok(impls_json(item))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method '{method}'"),
+        )),
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|error| RpcError::new(INVALID_PARAMS, error.to_string()))
+}
+
+/// The traits implemented by a type and its inherent methods, or the types implementing a
+/// trait
+fn impls_json(item: DocRef<'_, Item>) -> Value {
+    if let ItemEnum::Trait(trait_item) = item.inner() {
+        let implementors = trait_item
+            .implementations
+            .iter()
+            .filter_map(|id| item.get(id))
+            .filter_map(|impl_block| match impl_block.inner() {
+                ItemEnum::Impl(impl_item) => Some(match &impl_item.for_ {
+                    Type::ResolvedPath(path) => impl_block
+                        .crate_docs()
+                        .path(&path.id)
+                        .map_or_else(|| path.path.clone(), |path| path.to_string()),
+                    _ => "(other type)".to_string(),
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        return json!({ "implementors": implementors });
+    }
+
+    let traits = item
+        .traits()
+        .filter_map(|impl_block| {
+            let ItemEnum::Impl(impl_item) = impl_block.inner() else {
+                return None;
+            };
+            let trait_path = impl_item.trait_.as_ref()?;
+            let path = impl_block
+                .crate_docs()
+                .path(&trait_path.id)
+                .map_or_else(|| trait_path.path.clone(), |path| path.to_string());
+            Some(json!({
+                "trait": path,
+                "synthetic": impl_item.is_synthetic,
+                "blanket": impl_item.blanket_impl.is_some(),
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    let methods = item
+        .methods()
+        .filter_map(|method| method.name())
+        .collect::<Vec<_>>();
+
+    json!({ "traits": traits, "methods": methods })
+}
+
+/// A document as plain text, for showing as it is, and as a tree of nodes
+pub(crate) fn document_json(
+    document: &Document<'_>,
+    is_error: bool,
+    render_context: &RenderContext,
+) -> Value {
+    let mut text = String::new();
+    if render(document, render_context, &mut text).is_err() {
+        text.clear();
+    }
+
+    json!({
+        "is_error": is_error,
+        "text": text,
+        "nodes": nodes_json(&document.nodes),
+    })
+}
+
+fn nodes_json(nodes: &[DocumentNode<'_>]) -> Vec<Value> {
+    let mut values = vec![];
+    for node in nodes {
+        push_node_json(node, &mut values);
+    }
+    values
+}
+
+fn push_node_json(node: &DocumentNode<'_>, values: &mut Vec<Value>) {
+    let value = match node {
+        DocumentNode::Paragraph { spans } => {
+            json!({ "type": "paragraph", "spans": spans_json(spans) })
+        }
+        DocumentNode::Heading { level, spans } => {
+            json!({ "type": "heading", "level": level, "spans": spans_json(spans) })
+        }
+        DocumentNode::Section { title, nodes } => json!({
+            "type": "section",
+            "title": title.as_deref().map(spans_json),
+            "nodes": nodes_json(nodes),
+        }),
+        DocumentNode::List { items } => json!({
+            "type": "list",
+            "items": items.iter().map(|item| nodes_json(&item.content)).collect::<Vec<_>>(),
+        }),
+        DocumentNode::CodeBlock { lang, code, .. } => {
+            json!({ "type": "code_block", "lang": lang, "code": code })
+        }
+        DocumentNode::GeneratedCode { spans } => {
+            json!({ "type": "generated_code", "spans": spans_json(spans) })
+        }
+        DocumentNode::HorizontalRule => json!({ "type": "horizontal_rule" }),
+        DocumentNode::Image { url, alt } => json!({ "type": "image", "url": url, "alt": alt }),
+        DocumentNode::BlockQuote { nodes } => {
+            json!({ "type": "block_quote", "nodes": nodes_json(nodes) })
+        }
+        DocumentNode::Table { header, rows } => json!({
+            "type": "table",
+            "header": header.as_deref().map(cells_json),
+            "rows": rows.iter().map(|row| cells_json(row)).collect::<Vec<_>>(),
+        }),
+        DocumentNode::TruncatedBlock { nodes, level } => {
+            json!({ "type": "truncated_block", "level": level, "nodes": nodes_json(nodes) })
+        }
+        // An editor panel isn't the interactive renderer, so its content is left out
+        DocumentNode::Conditional {
+            show_when: ShowWhen::Interactive,
+            ..
+        } => return,
+        DocumentNode::Conditional { nodes, .. } => {
+            for node in nodes {
+                push_node_json(node, values);
+            }
+            return;
+        }
+    };
+    values.push(value);
+}
+
+fn cells_json(cells: &[TableCell<'_>]) -> Vec<Value> {
+    cells.iter().map(|cell| spans_json(&cell.spans)).collect()
+}
+
+fn spans_json(spans: &[Span<'_>]) -> Value {
+    spans
+        .iter()
+        .map(|span| {
+            let path = match &span.action {
+                Some(TuiAction::Navigate { doc_ref, .. }) => {
+                    doc_ref.path().map(|path| path.to_string())
+                }
+                Some(TuiAction::NavigateToPath { path, .. }) => Some(path.to_string()),
+                _ => None,
+            };
+            json!({
+                "text": span.text,
+                "style": span.style,
+                "path": path,
+                "url": span.url(),
+            })
+        })
+        .collect()
+}
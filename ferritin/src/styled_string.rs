@@ -1,7 +1,8 @@
 use ferritin_common::DocRef;
 use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
-use rustdoc_types::Item;
+use rustdoc_types::{Item, ItemKind};
 use std::borrow::Cow;
+use std::ops::RangeInclusive;
 
 /// Interactive action that can be attached to a span
 #[derive(Debug, Clone)]
@@ -25,6 +26,22 @@ pub enum TuiAction<'a> {
     OpenUrl(Cow<'a, str>),
     /// Select a theme (interactive mode only)
     SelectTheme(Cow<'a, str>),
+    /// Compile and run the nth (1-based) rust example in an item's docs
+    RunExample {
+        doc_ref: DocRef<'a, Item>,
+        index: usize,
+    },
+    /// Run a search (interactive mode only)
+    Search {
+        query: Cow<'a, str>,
+        /// The crate to search, or None for all crates
+        crate_name: Option<Cow<'a, str>>,
+    },
+    /// Show the public API changes of a dependency since an earlier version
+    ApiDiff {
+        crate_name: Cow<'a, str>,
+        from: Cow<'a, str>,
+    },
 }
 
 impl<'a> TuiAction<'a> {
@@ -50,6 +67,9 @@ impl<'a> TuiAction<'a> {
             TuiAction::ExpandBlock(_) => None,
             TuiAction::OpenUrl(cow) => Some(cow.clone()),
             TuiAction::SelectTheme(_) => None,
+            TuiAction::RunExample { .. } => None,
+            TuiAction::Search { .. } => None,
+            TuiAction::ApiDiff { .. } => None,
         }
     }
 }
@@ -99,7 +119,7 @@ fn generate_url_from_path(path: &str) -> String {
 
 /// Path to a node in the document tree using indices
 /// Example: [2, 3, 1] means nodes[2].children[3].children[1]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct NodePath {
     indices: [u16; 8], // 8 levels deep should be enough
     len: u8,
@@ -176,6 +196,10 @@ pub enum DocumentNode<'a> {
     CodeBlock {
         lang: Option<Cow<'a, str>>,
         code: Cow<'a, str>,
+        /// Action offered alongside the code (e.g. running a doc example)
+        action: Option<TuiAction<'a>>,
+        /// Line numbers for code quoted from a source file, shown in a gutter
+        line_numbers: Option<LineNumbers>,
     },
 
     /// Generated code with pre-styled spans (for signatures, etc.)
@@ -184,6 +208,13 @@ pub enum DocumentNode<'a> {
     /// Horizontal rule/divider
     HorizontalRule,
 
+    /// An image from the docs, shown inline by terminals that can, and as a link elsewhere
+    Image {
+        url: Cow<'a, str>,
+        /// The image's alt text
+        alt: Cow<'a, str>,
+    },
+
     /// Block quote
     BlockQuote { nodes: Vec<DocumentNode<'a>> },
 
@@ -208,6 +239,36 @@ pub enum DocumentNode<'a> {
     },
 }
 
+/// Numbering for the lines of a code block quoted from a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineNumbers {
+    /// The number of the block's first line
+    pub first: usize,
+    /// The lines of the item the code was quoted for, as opposed to the context around it
+    pub highlighted: RangeInclusive<usize>,
+}
+
+impl LineNumbers {
+    /// The number of the `idx`th line of the block, and whether it's highlighted
+    pub fn line(&self, idx: usize) -> (usize, bool) {
+        let number = self.first + idx;
+        (number, self.highlighted.contains(&number))
+    }
+
+    /// The gutter in front of line `number` of a block of `line_count` lines: the right-aligned
+    /// number and a bar, heavy for highlighted lines
+    pub fn gutter(&self, number: usize, line_count: usize) -> String {
+        let last = self.first + line_count.saturating_sub(1);
+        let width = last.to_string().len();
+        let bar = if self.highlighted.contains(&number) {
+            '┃'
+        } else {
+            '│'
+        };
+        format!("{number:>width$} {bar} ")
+    }
+}
+
 /// A single cell in a table
 #[derive(Debug, Clone)]
 pub struct TableCell<'a> {
@@ -221,14 +282,16 @@ pub struct ListItem<'a> {
 }
 
 /// Heading level for semantic structure
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HeadingLevel {
     Title,   // Top-level item name: "Item: Vec"
     Section, // Section header: "Fields:", "Methods:"
 }
 
 /// Truncation level hint for renderers
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TruncationLevel {
     /// Single-line summary (for listings)
     SingleLine,
@@ -253,7 +316,8 @@ impl<'a> Span<'a> {
 }
 
 /// Semantic styling categories for Rust code elements
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SpanStyle {
     // Rust code semantic elements
     Keyword,      // struct, enum, pub, fn, const, etc.
@@ -262,6 +326,9 @@ pub enum SpanStyle {
     FieldName,    // field names in structs
     Lifetime,     // 'a, 'static, etc.
     Generic,      // T, U, generic parameters
+    MacroName,    // vec!, println!, derive macros
+    ConstName,    // constants, statics and associated constants
+    ModuleName,   // std::io, my_module, etc.
 
     // Structural elements
     Plain,       // unstyled text, whitespace
@@ -329,6 +396,44 @@ impl<'a> Span<'a> {
         }
     }
 
+    pub fn macro_name(text: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            text: text.into(),
+            style: SpanStyle::MacroName,
+            action: None,
+        }
+    }
+
+    pub fn const_name(text: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            text: text.into(),
+            style: SpanStyle::ConstName,
+            action: None,
+        }
+    }
+
+    pub fn module_name(text: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            text: text.into(),
+            style: SpanStyle::ModuleName,
+            action: None,
+        }
+    }
+
+    /// The name or path of an item, styled according to its kind
+    pub fn item_name(text: impl Into<Cow<'a, str>>, kind: ItemKind) -> Self {
+        match kind {
+            ItemKind::Module | ItemKind::ExternCrate => Self::module_name(text),
+            ItemKind::Function => Self::function_name(text),
+            ItemKind::Constant | ItemKind::Static | ItemKind::AssocConst => Self::const_name(text),
+            ItemKind::Macro | ItemKind::ProcAttribute | ItemKind::ProcDerive => {
+                Self::macro_name(text)
+            }
+            ItemKind::StructField => Self::field_name(text),
+            _ => Self::type_name(text),
+        }
+    }
+
     // Structural element constructors
     pub fn plain(text: impl Into<Cow<'a, str>>) -> Self {
         Self {
@@ -427,6 +532,16 @@ impl<'a> Span<'a> {
         });
         self
     }
+
+    /// A link to an image, like `[image: Logo]`, for where the image itself can't be shown
+    pub fn image_link(url: &Cow<'a, str>, alt: &str) -> Self {
+        let text = if alt.is_empty() {
+            "[image]".to_string()
+        } else {
+            format!("[image: {alt}]")
+        };
+        Span::plain(text).with_action(TuiAction::OpenUrl(url.clone()))
+    }
 }
 
 impl<'a> Document<'a> {
@@ -437,6 +552,13 @@ impl<'a> Document<'a> {
     pub fn with_nodes(nodes: Vec<DocumentNode<'a>>) -> Self {
         Self { nodes }
     }
+
+    /// Call `visit` with every action in the document, in document order
+    pub fn visit_actions(&self, visit: &mut impl FnMut(&TuiAction<'a>)) {
+        for node in &self.nodes {
+            node.visit_actions(visit);
+        }
+    }
 }
 
 impl<'a> Default for Document<'a> {
@@ -501,6 +623,22 @@ impl<'a> DocumentNode<'a> {
         DocumentNode::CodeBlock {
             lang: lang.map(Into::into),
             code: code.into(),
+            action: None,
+            line_numbers: None,
+        }
+    }
+
+    /// Convenience constructor for a code block quoted from a source file
+    pub fn source_block(
+        lang: Option<impl Into<Cow<'a, str>>>,
+        code: impl Into<Cow<'a, str>>,
+        line_numbers: LineNumbers,
+    ) -> Self {
+        DocumentNode::CodeBlock {
+            lang: lang.map(Into::into),
+            code: code.into(),
+            action: None,
+            line_numbers: Some(line_numbers),
         }
     }
 
@@ -514,6 +652,14 @@ impl<'a> DocumentNode<'a> {
         DocumentNode::HorizontalRule
     }
 
+    /// Convenience constructor for an image
+    pub fn image(url: impl Into<Cow<'a, str>>, alt: impl Into<Cow<'a, str>>) -> Self {
+        DocumentNode::Image {
+            url: url.into(),
+            alt: alt.into(),
+        }
+    }
+
     /// Convenience constructor for a block quote
     pub fn block_quote(nodes: Vec<DocumentNode<'a>>) -> Self {
         DocumentNode::BlockQuote { nodes }
@@ -528,6 +674,43 @@ impl<'a> DocumentNode<'a> {
     pub fn truncated_block(nodes: Vec<DocumentNode<'a>>, level: TruncationLevel) -> Self {
         DocumentNode::TruncatedBlock { nodes, level }
     }
+
+    /// Call `visit` with every action in this node and its children, in document order
+    pub fn visit_actions(&self, visit: &mut impl FnMut(&TuiAction<'a>)) {
+        let mut visit_spans = |spans: &[Span<'a>]| {
+            spans
+                .iter()
+                .filter_map(|span| span.action.as_ref())
+                .for_each(&mut *visit);
+        };
+        match self {
+            DocumentNode::Paragraph { spans }
+            | DocumentNode::Heading { spans, .. }
+            | DocumentNode::GeneratedCode { spans } => visit_spans(spans),
+            DocumentNode::Section { title, nodes } => {
+                if let Some(title) = title {
+                    visit_spans(title);
+                }
+                nodes.iter().for_each(|node| node.visit_actions(visit));
+            }
+            DocumentNode::List { items } => items
+                .iter()
+                .flat_map(|item| &item.content)
+                .for_each(|node| node.visit_actions(visit)),
+            DocumentNode::CodeBlock { action, .. } => action.iter().for_each(visit),
+            DocumentNode::HorizontalRule | DocumentNode::Image { .. } => {}
+            DocumentNode::BlockQuote { nodes }
+            | DocumentNode::TruncatedBlock { nodes, .. }
+            | DocumentNode::Conditional { nodes, .. } => {
+                nodes.iter().for_each(|node| node.visit_actions(visit));
+            }
+            DocumentNode::Table { header, rows } => header
+                .iter()
+                .chain(rows)
+                .flatten()
+                .for_each(|cell| visit_spans(&cell.spans)),
+        }
+    }
 }
 
 impl<'a> TableCell<'a> {
@@ -574,6 +757,16 @@ mod tests {
         assert!(matches!(span.style, SpanStyle::Keyword));
     }
 
+    #[test]
+    fn test_item_name_style() {
+        let style = |kind| Span::item_name("x", kind).style;
+        assert_eq!(style(ItemKind::Module), SpanStyle::ModuleName);
+        assert_eq!(style(ItemKind::Static), SpanStyle::ConstName);
+        assert_eq!(style(ItemKind::ProcDerive), SpanStyle::MacroName);
+        assert_eq!(style(ItemKind::Function), SpanStyle::FunctionName);
+        assert_eq!(style(ItemKind::Struct), SpanStyle::TypeName);
+    }
+
     #[test]
     fn test_section() {
         let section = DocumentNode::section(
@@ -625,7 +818,7 @@ mod tests {
     fn test_code_block() {
         let code = DocumentNode::code_block(Some("rust".to_string()), "fn main() {}".to_string());
 
-        if let DocumentNode::CodeBlock { lang, code } = code {
+        if let DocumentNode::CodeBlock { lang, code, .. } = code {
             assert_eq!(lang, Some("rust".into()));
             assert_eq!(code, "fn main() {}");
         } else {
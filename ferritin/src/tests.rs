@@ -1,5 +1,8 @@
 use crate::{
-    commands::Commands,
+    commands::{
+        Commands,
+        list::{ListFilter, ListSort},
+    },
     format_context::FormatContext,
     render_context::RenderContext,
     renderer::{OutputMode, render},
@@ -10,6 +13,7 @@ use ferritin_common::{
     sources::{LocalSource, StdSource},
 };
 use ratatui::backend::TestBackend;
+use rustdoc_types::ItemKind;
 use std::path::PathBuf;
 
 /// Get the path to our test crate (fast to build, minimal dependencies)
@@ -19,10 +23,15 @@ fn get_fixture_crate_path() -> PathBuf {
 
 /// Create a test state with isolated session
 fn create_test_state() -> Request {
+    create_test_state_with(FormatContext::new())
+}
+
+/// Create a test state that formats with `format_context`
+fn create_test_state_with(format_context: FormatContext) -> Request {
     let navigator = Navigator::default()
         .with_local_source(LocalSource::load(&get_fixture_crate_path()).ok())
         .with_std_source(StdSource::from_rustup());
-    Request::new(navigator, FormatContext::new())
+    Request::new(navigator, format_context)
 }
 
 /// Convert OSC8 hyperlinks to markdown-style [text](url) before stripping ANSI
@@ -39,7 +48,11 @@ fn render_for_tests(command: Commands, output_mode: OutputMode) -> String {
     let request = create_test_state();
     let (document, _, _) = command.execute(&request);
     let mut output = String::new();
-    let render_context = RenderContext::new().with_output_mode(output_mode);
+    // Plain snapshots keep each paragraph on one line, so that they show the docs' text as
+    // written; the plain renderer's own tests cover wrapping
+    let render_context = RenderContext::new()
+        .with_output_mode(output_mode)
+        .with_wrap(output_mode != OutputMode::Plain);
     render(&document, &render_context, &mut output).unwrap();
 
     // For TTY mode: convert OSC8 links to markdown, then strip remaining ANSI codes
@@ -177,7 +190,536 @@ test_all_modes!(
 
 test_all_modes!(get_std, Commands::get("std"));
 
+#[test]
+fn conventional_sections_plain_mode() {
+    insta::assert_snapshot!(render_for_tests(
+        Commands::get("crate::conventional_headings::parse_port"),
+        OutputMode::Plain
+    ));
+}
+
+#[test]
+fn conventional_sections_interactive_mode() {
+    use crate::renderer::render_to_test_backend;
+
+    // The interactive renderer starts `# Examples` sections collapsed
+    let request = create_test_state_with(FormatContext::new().with_collapsed_examples(true));
+    let (document, _, _) =
+        Commands::get("crate::conventional_headings::parse_port").execute(&request);
+    insta::assert_snapshot!(render_to_test_backend(document, RenderContext::new()));
+}
+
+#[test]
+fn unsafe_fn_safety_section_plain_mode() {
+    insta::assert_snapshot!(render_for_tests(
+        Commands::get("crate::conventional_headings::read_byte"),
+        OutputMode::Plain
+    ));
+}
+
+#[test]
+fn unsafe_fn_safety_section_interactive_mode() {
+    insta::assert_snapshot!(render_interactive_for_tests(Commands::get(
+        "crate::conventional_headings::read_byte"
+    )));
+}
+
+#[test]
+fn impl_blocks_in_source_order_plain_mode() {
+    insta::assert_snapshot!(render_for_tests(
+        Commands::get("crate::impl_blocks::Counter"),
+        OutputMode::Plain
+    ));
+}
+
+#[test]
+fn impl_blocks_in_source_order_interactive_mode() {
+    insta::assert_snapshot!(render_interactive_for_tests(Commands::get(
+        "crate::impl_blocks::Counter"
+    )));
+}
+
+#[test]
+fn std_items_show_the_version_they_were_stabilized_in() {
+    let option = render_for_tests(Commands::get("std::option::Option"), OutputMode::Plain);
+    let zip = option
+        .lines()
+        .find(|line| line.contains("fn zip"))
+        .expect("Option::zip is listed");
+    assert!(zip.contains("since 1.46"), "no since badge on: {zip}");
+
+    let zip = render_for_tests(Commands::get("std::option::Option::zip"), OutputMode::Plain);
+    assert!(zip.contains("Stable since: Rust 1.46"), "{zip}");
+}
+
 test_all_modes!(
     get_markdown_test,
     Commands::get("fixture-crate::markdown_test")
 );
+
+#[test]
+fn rpc_answers_each_request() {
+    let request = create_test_state();
+    let input = [
+        r#"{"jsonrpc": "2.0", "id": 1, "method": "resolve", "params": {"path": "crate::TestStruct"}}"#,
+        r#"{"jsonrpc": "2.0", "id": 2, "method": "get", "params": {"path": "crate::TestStruct"}}"#,
+        r#"{"jsonrpc": "2.0", "id": 3, "method": "impls", "params": {"path": "crate::TestStruct"}}"#,
+        r#"{"jsonrpc": "2.0", "method": "get", "params": {"path": "crate"}}"#,
+        r#"{"jsonrpc": "2.0", "id": 4, "method": "frobnicate"}"#,
+    ]
+    .join("\n");
+
+    let mut output = vec![];
+    crate::rpc::serve(&request, input.as_bytes(), &mut output).unwrap();
+    let responses = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    // The notification without an id gets no response
+    assert_eq!(responses.len(), 4);
+    assert_eq!(responses[0]["result"]["name"], "TestStruct");
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(responses[1]["result"]["is_error"], false);
+    assert!(
+        responses[1]["result"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("TestStruct")
+    );
+    assert!(
+        responses[2]["result"]["traits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|impl_| impl_["trait"].as_str().unwrap().ends_with("TestTrait"))
+    );
+    assert_eq!(responses[3]["error"]["code"], -32601);
+}
+
+#[test]
+fn batch_shows_each_path_read() {
+    let request = create_test_state();
+    let command = Commands::get("");
+    let render_context = RenderContext::new().with_output_mode(OutputMode::Plain);
+    let input = "crate::TestStruct\n\n  crate::submodule  \ncrate::Nonexistent\n";
+
+    let mut output = vec![];
+    let all_found = crate::batch::serve(
+        &request,
+        &command,
+        &render_context,
+        "%%",
+        false,
+        input.as_bytes(),
+        &mut output,
+    )
+    .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(!all_found);
+    let documents = output.split("\n%%\n").collect::<Vec<_>>();
+    assert_eq!(documents.len(), 3);
+    assert!(documents[0].contains("A simple struct for testing"));
+    assert!(documents[1].contains("SubStruct"));
+    assert!(documents[2].contains("Could not find 'crate::Nonexistent'"));
+
+    let mut output = vec![];
+    crate::batch::serve(
+        &request,
+        &command,
+        &render_context,
+        "---",
+        true,
+        input.as_bytes(),
+        &mut output,
+    )
+    .unwrap();
+    let lines = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[1]["path"], "crate::submodule");
+    assert_eq!(lines[1]["is_error"], false);
+    assert!(lines[0]["text"].as_str().unwrap().contains("TestStruct"));
+    assert_eq!(lines[2]["is_error"], true);
+}
+
+#[test]
+fn get_failures_have_exit_codes_and_suggestions() {
+    let request = create_test_state();
+    let json_document =
+        |path: &str| crate::batch::json_document(&request, Commands::get(path), path);
+
+    let (found, failure) = json_document("crate::TestStruct");
+    assert_eq!(failure, None);
+    assert_eq!(found["is_error"], false);
+    assert!(found.get("error").is_none());
+
+    let (not_found, failure) = json_document("crate::TestStructure");
+    assert_eq!(failure.map(|failure| failure.exit_code()), Some(3));
+    assert_eq!(not_found["is_error"], true);
+    assert_eq!(not_found["error"]["code"], "not_found");
+    assert_eq!(not_found["error"]["exit_code"], 3);
+    let suggestion = &not_found["error"]["suggestions"][0];
+    assert!(suggestion["path"].as_str().unwrap().ends_with("TestStruct"));
+    assert_eq!(suggestion["kind"], "struct");
+    assert!(suggestion["score"].as_f64().unwrap() > 0.5);
+    assert!(
+        suggestion["summary"]
+            .as_str()
+            .unwrap()
+            .contains("simple struct")
+    );
+
+    let (unavailable, failure) = json_document("no_such_crate::Thing");
+    assert_eq!(failure.map(|failure| failure.exit_code()), Some(4));
+    assert_eq!(unavailable["error"]["code"], "crate_unavailable");
+
+    assert_eq!(
+        Commands::get("crate::TestStructure").failure_exit_code(&request),
+        3
+    );
+    assert_eq!(Commands::list().failure_exit_code(&request), 1);
+}
+
+#[test]
+fn get_suggestions_show_the_best_match_summary() {
+    let output = render_for_tests(Commands::get("crate::TestStructure"), OutputMode::Plain);
+
+    assert!(output.contains("Could not find 'crate::TestStructure'"));
+    assert!(output.contains("Did you mean:"));
+    assert!(output.contains("crate::TestStruct: A simple struct for testing basic functionality."));
+}
+
+#[test]
+fn resolve_json_describes_the_item_or_suggests_paths() {
+    let request = create_test_state();
+    let render_context = RenderContext::new().with_output_mode(OutputMode::Plain);
+    let resolve_json = |path: &str| {
+        let (document, is_error, _) = Commands::Resolve {
+            path: path.to_string(),
+            json: true,
+        }
+        .execute(&request);
+        let mut output = String::new();
+        render(&document, &render_context, &mut output).unwrap();
+        (
+            serde_json::from_str::<serde_json::Value>(&output).unwrap(),
+            is_error,
+        )
+    };
+
+    let (resolved, is_error) = resolve_json("crate::TestStruct");
+    assert!(!is_error);
+    assert_eq!(resolved["item"]["kind"], "struct");
+    assert_eq!(
+        resolved["item"]["discriminated_path"],
+        "fixture-crate::struct@TestStruct"
+    );
+    assert!(resolved["item"]["span"]["file"].is_string());
+
+    let (unresolved, is_error) = resolve_json("crate::TestStruct::test_metod");
+    assert!(is_error);
+    assert!(unresolved["item"].is_null());
+    assert!(unresolved["suggestions"][0]["path"].is_string());
+    assert!(unresolved["suggestions"][0]["score"].is_number());
+}
+
+#[test]
+fn list_json_filters_and_describes_crates() {
+    let request = create_test_state();
+    let render_context = RenderContext::new().with_output_mode(OutputMode::Plain);
+    let list_json = |filter| {
+        let (document, _, _) = Commands::List {
+            changed: false,
+            filter,
+            sort: ListSort::Name,
+            json: true,
+        }
+        .execute(&request);
+        let mut output = String::new();
+        render(&document, &render_context, &mut output).unwrap();
+        serde_json::from_str::<Vec<serde_json::Value>>(&output).unwrap()
+    };
+
+    let workspace = list_json(Some(ListFilter::Workspace));
+    assert_eq!(workspace.len(), 1);
+    assert_eq!(workspace[0]["name"], "fixture-crate");
+    assert_eq!(workspace[0]["provenance"], "workspace");
+    assert_eq!(workspace[0]["default"], true);
+    assert_eq!(workspace[0]["description"], "this is the crate description");
+
+    let std = list_json(Some(ListFilter::Std));
+    assert!(std.iter().any(|crate_| crate_["name"] == "core"));
+    assert!(std.iter().all(|crate_| crate_["provenance"] == "std"));
+
+    assert_eq!(list_json(None).len(), workspace.len() + std.len());
+}
+
+#[test]
+fn associated_items_get_their_own_page() {
+    let output = render_for_tests(
+        Commands::get("crate::ComplexTrait::Output"),
+        OutputMode::Plain,
+    );
+    assert!(output.contains("Kind: AssocType"));
+    assert!(output.contains("type Output: "));
+    assert!(output.contains("Display;"));
+    assert!(output.contains("Declared in the trait ComplexTrait"));
+
+    let output = render_for_tests(
+        Commands::get("crate::ComplexTrait::MAX_SIZE"),
+        OutputMode::Plain,
+    );
+    assert!(output.contains("Kind: AssocConst"));
+    assert!(output.contains("const MAX_SIZE: usize = 100;"));
+}
+
+#[test]
+fn links_to_dependencies_are_followed_by_path() {
+    use crate::styled_string::{DocumentNode, TuiAction};
+
+    let request = create_test_state();
+    let generic_enum = request
+        .resolve_path("crate::GenericEnum", &mut vec![])
+        .unwrap();
+    let info = generic_enum
+        .crate_docs()
+        .items()
+        .find(|item| item.name.as_deref() == Some("info"))
+        .map(|item| generic_enum.build_ref(item))
+        .unwrap();
+
+    let nodes = request.render_docs(info, info.docs.as_deref().unwrap());
+    let link_path = |text: &str| {
+        let action = nodes
+            .iter()
+            .flat_map(|node| match node {
+                DocumentNode::Paragraph { spans } => spans.as_slice(),
+                _ => &[],
+            })
+            .find(|span| span.text == text)
+            .and_then(|span| span.action.as_ref());
+        let Some(TuiAction::NavigateToPath { path, .. }) = action else {
+            panic!("expected a link to follow by path, found {action:?}");
+        };
+        path.to_string()
+    };
+    assert_eq!(link_path("serde::Serialize"), "serde::Serialize");
+
+    // proc_macro isn't in scope for rustdoc, but ships with Rust, so following the link loads it
+    let path = link_path("proc_macro::TokenStream");
+    assert_eq!(path, "proc_macro::TokenStream");
+    let token_stream = request.resolve_path(&path, &mut vec![]).unwrap();
+    assert_eq!(token_stream.name(), Some("TokenStream"));
+    assert_eq!(token_stream.crate_docs().name(), "proc_macro");
+}
+
+#[test]
+fn long_signatures_are_laid_out_over_lines() {
+    let request = create_test_state_with(FormatContext::new().with_terminal_width(Some(16)));
+    let render_context = RenderContext::new()
+        .with_output_mode(OutputMode::Plain)
+        .with_wrap(false);
+    let get = |path: &str| {
+        let (document, _, _) = Commands::get(path).execute(&request);
+        let mut output = String::new();
+        render(&document, &render_context, &mut output).unwrap();
+        output
+    };
+
+    let output = get("crate::async_function");
+    assert!(output.contains("async fn async_function(\n    delay: u64,\n) -> Result<"));
+
+    let output = get("crate::ComplexTrait");
+    assert!(output.contains("where\n    T: Clone\n        + Send"));
+
+    let request = create_test_state();
+    let (document, _, _) = Commands::get("crate::async_function").execute(&request);
+    let mut output = String::new();
+    render(&document, &render_context, &mut output).unwrap();
+    assert!(output.contains("async fn async_function(delay: u64) -> Result<"));
+}
+
+#[test]
+fn daemon_lays_out_signatures_for_each_client_width() {
+    use crate::{color_depth::ColorDepth, daemon::DaemonRequest};
+
+    let request = create_test_state();
+    let run_at = |terminal_width: usize| {
+        crate::daemon::run(
+            &request,
+            DaemonRequest {
+                args: vec!["get".into(), "crate::async_function".into()],
+                output_mode: OutputMode::Plain,
+                terminal_width,
+                color_depth: ColorDepth::Monochrome,
+                theme: crate::DEFAULT_DARK_THEME.into(),
+                links: None,
+                wrap: true,
+                decorations: true,
+                page: None,
+            },
+        )
+        .output
+    };
+
+    assert!(run_at(16).contains("async fn async_function(\n    delay: u64,\n) -> Result<"));
+    assert!(run_at(200).contains("async fn async_function(delay: u64) -> Result<"));
+}
+
+#[test]
+fn daemon_renders_with_the_clients_resolved_settings() {
+    use crate::{color_depth::ColorDepth, daemon::DaemonRequest};
+    use std::num::NonZeroUsize;
+
+    const PARAGRAPH: &str = "This struct demonstrates basic usage patterns and should show \
+                              completely since it only has one paragraph of documentation.";
+
+    // The client's flags, environment and config files were already applied to the settings
+    // it sends, so they win over whatever the arguments say when the daemon parses them again
+    let request = create_test_state();
+    let run = |args: &[&str], wrap: bool, page: Option<NonZeroUsize>| {
+        crate::daemon::run(
+            &request,
+            DaemonRequest {
+                args: args.iter().map(ToString::to_string).collect(),
+                output_mode: OutputMode::Plain,
+                terminal_width: 40,
+                color_depth: ColorDepth::Monochrome,
+                theme: crate::DEFAULT_DARK_THEME.into(),
+                links: None,
+                wrap,
+                decorations: true,
+                page,
+            },
+        )
+        .output
+    };
+
+    let unwrapped = run(&["get", "crate::TestStruct"], false, None);
+    assert!(
+        unwrapped.lines().any(|line| line == PARAGRAPH),
+        "{unwrapped}"
+    );
+
+    let wrapped = run(&["--no-wrap", "get", "crate::TestStruct"], true, None);
+    assert!(!wrapped.lines().any(|line| line == PARAGRAPH), "{wrapped}");
+    assert!(
+        wrapped
+            .lines()
+            .any(|line| line.starts_with("This struct") && line.len() <= 40),
+        "{wrapped}"
+    );
+
+    let second_page = run(&["get", "crate::TestStruct"], true, NonZeroUsize::new(2));
+    assert!(!second_page.contains("This struct"), "{second_page}");
+}
+
+#[test]
+fn plain_paragraphs_are_wrapped_to_the_width() {
+    const PARAGRAPH: &str = "This struct demonstrates basic usage patterns and should show \
+                             completely since it only has one paragraph of documentation.";
+
+    let request = create_test_state();
+    let (document, _, _) = Commands::get("crate::TestStruct").execute(&request);
+    let render_at = |render_context: RenderContext| {
+        let mut output = String::new();
+        render(
+            &document,
+            &render_context.with_output_mode(OutputMode::Plain),
+            &mut output,
+        )
+        .unwrap();
+        output
+    };
+
+    let unwrapped = render_at(RenderContext::new().with_wrap(false));
+    assert!(unwrapped.lines().any(|line| line == PARAGRAPH));
+
+    let wrapped = render_at(RenderContext::new().with_terminal_width(40));
+    let paragraph = wrapped
+        .lines()
+        .skip_while(|line| !line.starts_with("This struct"))
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    assert!(paragraph.len() > 1, "{wrapped}");
+    assert!(paragraph.iter().all(|line| line.len() <= 40), "{wrapped}");
+    assert_eq!(paragraph.join(" "), PARAGRAPH);
+}
+
+#[test]
+fn get_signature_leaves_out_docs() {
+    let command = Commands::get("crate::TestStruct").with_signature();
+    let output = render_for_tests(command, OutputMode::Plain);
+
+    assert!(output.contains("    pub count: u32,"));
+    assert!(output.contains("pub fn get_field(&self) -> &str"));
+    assert!(!output.contains("Kind: Struct"));
+    assert!(!output.contains("A simple struct for testing"));
+    assert!(!output.contains("Get the field value"));
+    assert!(!output.contains("Trait Implementations"));
+}
+
+#[test]
+fn recursive_listing_honors_depth_and_kinds() {
+    let command = Commands::get("crate::submodule")
+        .with_depth(1)
+        .with_kinds([ItemKind::Function]);
+    let output = render_for_tests(command, OutputMode::Plain);
+
+    assert!(output.contains("  ◦ fn sub_function"));
+    assert!(!output.contains("struct SubStruct"));
+    assert!(!output.contains("fn double"));
+    assert!(!output.contains("enum TestEnum"));
+
+    let command = Commands::get("crate::submodule")
+        .with_depth(2)
+        .with_kinds([ItemKind::Function]);
+    let output = render_for_tests(command, OutputMode::Plain);
+
+    assert!(output.contains("  ◦ struct SubStruct"));
+    assert!(output.contains("      ▪ fn double"));
+    assert!(!output.contains("VariantA"));
+}
+
+#[test]
+fn tree_shows_modules_with_their_counts() {
+    let command = Commands::Tree {
+        path: "crate".to_string(),
+        modules_only: false,
+    };
+    let output = render_for_tests(command, OutputMode::Plain);
+
+    assert!(output.contains("▣ submodule (1 struct, 1 enum, 1 function)"));
+    assert!(output.contains("◆ SubStruct"));
+    assert!(output.contains("▣ deeply_nested"));
+    assert!(!output.contains("VariantA"));
+
+    let command = Commands::Tree {
+        path: "crate".to_string(),
+        modules_only: true,
+    };
+    let output = render_for_tests(command, OutputMode::Plain);
+
+    assert!(output.contains("▣ submodule (1 struct, 1 enum, 1 function)"));
+    assert!(output.contains("▣ deeply_nested"));
+    assert!(!output.contains("◆ TestStruct"));
+}
+
+#[test]
+fn tree_suggests_paths_it_cannot_find() {
+    let command = Commands::Tree {
+        path: "crate::no_such_module".to_string(),
+        modules_only: false,
+    };
+    let output = render_for_tests(command, OutputMode::Plain);
+
+    assert!(output.contains("Could not find 'crate::no_such_module'"));
+    assert!(output.contains("Did you mean:"));
+}
@@ -1,8 +1,12 @@
 use clap::ValueEnum;
 
+use crate::styled_string::TruncationLevel;
+
 /// Controls the verbosity level of documentation display
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default, Hash, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub(crate) enum Verbosity {
+    Signature,
     Minimal,
     Brief,
     #[default]
@@ -13,4 +17,19 @@ impl Verbosity {
     pub(crate) fn is_full(self) -> bool {
         matches!(self, Self::Full)
     }
+
+    /// Whether only signatures are shown, leaving out docs, examples and trait implementations
+    pub(crate) fn is_signature(self) -> bool {
+        matches!(self, Self::Signature)
+    }
+
+    /// How much of an item's own docs are shown on its page, if any are
+    pub(crate) fn truncation_level(self) -> Option<TruncationLevel> {
+        match self {
+            Self::Signature => None,
+            Self::Minimal => Some(TruncationLevel::SingleLine),
+            Self::Brief => Some(TruncationLevel::Brief),
+            Self::Full => Some(TruncationLevel::Full),
+        }
+    }
 }
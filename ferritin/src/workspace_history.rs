@@ -0,0 +1,442 @@
+//! Past search queries, named saved searches, recently viewed items and dependency versions,
+//! persisted per workspace
+//!
+//! Stored as a small tab-separated file in the workspace's target directory, next to the
+//! rustdoc JSON it describes:
+//!
+//! ```text
+//! query	iterator adapters
+//! saved	Readers	std	read
+//! viewed	std::io::Read
+//! version	serde	1.0.210	1.0.190
+//! ```
+
+use crate::request::Request;
+use ferritin_common::CrateName;
+use std::fs;
+use std::path::PathBuf;
+
+/// How many past queries are kept
+const MAX_QUERIES: usize = 100;
+
+/// How many recently viewed items are kept
+const MAX_VIEWED: usize = 20;
+
+/// A search saved under a name, shown on the start page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SavedSearch {
+    pub(crate) name: String,
+    pub(crate) query: String,
+    /// The crate the search is scoped to, or None for all crates
+    pub(crate) crate_name: Option<String>,
+}
+
+/// The version of a dependency seen by the last `list --changed`, and the one before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SeenVersion {
+    pub(crate) crate_name: String,
+    pub(crate) version: String,
+    /// The version this one replaced, or None if it hasn't changed since it was first seen
+    pub(crate) previous: Option<String>,
+}
+
+/// A dependency that was added, removed or updated since versions were last recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VersionChange {
+    pub(crate) crate_name: String,
+    /// None for a newly added dependency
+    pub(crate) from: Option<String>,
+    /// None for a removed dependency
+    pub(crate) to: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct WorkspaceHistory {
+    /// Where the history is stored, or None to keep it in memory only
+    path: Option<PathBuf>,
+    /// Past queries, oldest first
+    queries: Vec<String>,
+    saved: Vec<SavedSearch>,
+    /// Paths of recently viewed items, oldest first
+    viewed: Vec<String>,
+    /// Dependency versions as of the last `list --changed`
+    versions: Vec<SeenVersion>,
+}
+
+impl WorkspaceHistory {
+    /// Load the history for the request's workspace
+    ///
+    /// Without a local workspace, history is kept for the session only.
+    pub(crate) fn for_request(request: &Request) -> Self {
+        let path = request
+            .local_source()
+            .map(|source| source.target_dir().join("ferritin").join("history"));
+
+        let mut history = path
+            .as_deref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default();
+        history.path = path;
+        history
+    }
+
+    /// Past queries, oldest first
+    pub(crate) fn queries(&self) -> &[String] {
+        &self.queries
+    }
+
+    pub(crate) fn saved(&self) -> &[SavedSearch] {
+        &self.saved
+    }
+
+    /// Paths of recently viewed items, oldest first
+    pub(crate) fn viewed(&self) -> &[String] {
+        &self.viewed
+    }
+
+    /// The version a dependency was updated from, if it has changed since it was first seen
+    /// and is still at `version`
+    pub(crate) fn previous_version(&self, crate_name: &str, version: &str) -> Option<&str> {
+        let crate_name = CrateName::from(crate_name);
+        self.versions
+            .iter()
+            .find(|seen| {
+                CrateName::from(seen.crate_name.as_str()) == crate_name && seen.version == version
+            })
+            .and_then(|seen| seen.previous.as_deref())
+    }
+
+    /// Record the current dependency versions, returning what changed since they were last
+    /// recorded, or None the first time, when there is nothing to compare against
+    ///
+    /// Workspace members can depend on different versions of the same crate, so versions are
+    /// matched exactly first, and only the ones left over on each side are paired as updates.
+    pub(crate) fn record_versions(
+        &mut self,
+        current: impl IntoIterator<Item = (String, String)>,
+    ) -> Option<Vec<VersionChange>> {
+        let first_run = self.versions.is_empty();
+        let mut current = current
+            .into_iter()
+            .map(|(crate_name, version)| (clean(&crate_name), clean(&version)))
+            .collect::<Vec<_>>();
+        current.sort();
+        current.dedup();
+
+        let mut unmatched = self
+            .versions
+            .iter()
+            .filter(|seen| {
+                !current.iter().any(|(crate_name, version)| {
+                    *crate_name == seen.crate_name && *version == seen.version
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut changes = vec![];
+        let mut versions = vec![];
+
+        for (crate_name, version) in current {
+            let unchanged = self
+                .versions
+                .iter()
+                .find(|seen| seen.crate_name == crate_name && seen.version == version);
+            let updated = unmatched
+                .iter()
+                .position(|seen| seen.crate_name == crate_name);
+
+            let previous = match (unchanged, updated) {
+                (Some(seen), _) => seen.previous.clone(),
+                (None, Some(index)) => {
+                    let seen = unmatched.remove(index);
+                    changes.push(VersionChange {
+                        crate_name: crate_name.clone(),
+                        from: Some(seen.version.clone()),
+                        to: Some(version.clone()),
+                    });
+                    Some(seen.version.clone())
+                }
+                (None, None) => {
+                    if !first_run {
+                        changes.push(VersionChange {
+                            crate_name: crate_name.clone(),
+                            from: None,
+                            to: Some(version.clone()),
+                        });
+                    }
+                    None
+                }
+            };
+
+            versions.push(SeenVersion {
+                crate_name,
+                version,
+                previous,
+            });
+        }
+
+        for seen in unmatched {
+            changes.push(VersionChange {
+                crate_name: seen.crate_name.clone(),
+                from: Some(seen.version.clone()),
+                to: None,
+            });
+        }
+
+        changes.sort_by(|a, b| a.crate_name.cmp(&b.crate_name));
+        self.versions = versions;
+        self.write();
+        (!first_run).then_some(changes)
+    }
+
+    /// Add a query as the most recent, moving it if it was already present
+    pub(crate) fn record_search(&mut self, query: &str) {
+        push_recent(&mut self.queries, clean(query), MAX_QUERIES);
+        self.write();
+    }
+
+    /// Add an item path as the most recently viewed, moving it if it was already present
+    pub(crate) fn record_viewed(&mut self, path: &str) {
+        push_recent(&mut self.viewed, clean(path), MAX_VIEWED);
+        self.write();
+    }
+
+    /// Save a search under a name, replacing any saved search with the same name
+    pub(crate) fn save(&mut self, name: &str, query: &str, crate_name: Option<&str>) {
+        let saved = SavedSearch {
+            name: clean(name),
+            query: clean(query),
+            crate_name: crate_name.map(clean).filter(|c| !c.is_empty()),
+        };
+
+        match self.saved.iter_mut().find(|s| s.name == saved.name) {
+            Some(existing) => *existing = saved,
+            None => self.saved.push(saved),
+        }
+        self.write();
+    }
+
+    fn parse(content: &str) -> Self {
+        let mut history = Self::default();
+        for line in content.lines() {
+            let mut fields = line.split('\t');
+            match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                (Some("query"), Some(query), None, None) => {
+                    history.queries.push(query.to_string());
+                }
+                (Some("viewed"), Some(path), None, None) => {
+                    history.viewed.push(path.to_string());
+                }
+                (Some("version"), Some(crate_name), Some(version), Some(previous)) => {
+                    history.versions.push(SeenVersion {
+                        crate_name: crate_name.to_string(),
+                        version: version.to_string(),
+                        previous: Some(previous).filter(|p| !p.is_empty()).map(String::from),
+                    });
+                }
+                (Some("saved"), Some(name), Some(crate_name), Some(query)) => {
+                    history.saved.push(SavedSearch {
+                        name: name.to_string(),
+                        query: query.to_string(),
+                        crate_name: Some(crate_name).filter(|c| !c.is_empty()).map(String::from),
+                    });
+                }
+                _ => log::debug!("Skipping unrecognized search history line: {line}"),
+            }
+        }
+        history
+    }
+
+    fn serialize(&self) -> String {
+        let mut content = String::new();
+        for query in &self.queries {
+            content.push_str(&format!("query\t{query}\n"));
+        }
+        for saved in &self.saved {
+            content.push_str(&format!(
+                "saved\t{}\t{}\t{}\n",
+                saved.name,
+                saved.crate_name.as_deref().unwrap_or_default(),
+                saved.query
+            ));
+        }
+        for path in &self.viewed {
+            content.push_str(&format!("viewed\t{path}\n"));
+        }
+        for seen in &self.versions {
+            content.push_str(&format!(
+                "version\t{}\t{}\t{}\n",
+                seen.crate_name,
+                seen.version,
+                seen.previous.as_deref().unwrap_or_default()
+            ));
+        }
+        content
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, self.serialize()));
+        if let Err(e) = result {
+            log::warn!("Could not save search history to {}: {e}", path.display());
+        }
+    }
+}
+
+/// Move `entry` to the end of `entries`, dropping the oldest entries beyond `max`
+fn push_recent(entries: &mut Vec<String>, entry: String, max: usize) {
+    if entry.is_empty() {
+        return;
+    }
+
+    entries.retain(|existing| *existing != entry);
+    entries.push(entry);
+    let excess = entries.len().saturating_sub(max);
+    entries.drain(..excess);
+}
+
+/// Collapse tabs and newlines, which separate fields and entries in the history file
+fn clean(text: &str) -> String {
+    text.split(['\t', '\n', '\r'])
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn history_round_trips() {
+        let mut history = WorkspaceHistory::default();
+        history.record_search("iterator");
+        history.record_search("read\tline");
+        history.record_viewed("std::io::Read");
+        history.save("Readers", "read", Some("std"));
+        history.save("Everything", "spawn", None);
+
+        let parsed = WorkspaceHistory::parse(&history.serialize());
+        assert_eq!(parsed, history);
+        assert_eq!(parsed.queries(), ["iterator", "read line"]);
+        assert_eq!(parsed.saved()[1].crate_name, None);
+    }
+
+    #[test]
+    fn repeated_queries_move_to_the_end() {
+        let mut history = WorkspaceHistory::default();
+        for query in ["a", "b", "a", ""] {
+            history.record_search(query);
+        }
+        assert_eq!(history.queries(), ["b", "a"]);
+    }
+
+    #[test]
+    fn tabs_and_newlines_do_not_split_entries() {
+        let path = std::env::temp_dir().join(format!("ferritin-history-{}", std::process::id()));
+        let mut history = WorkspaceHistory {
+            path: Some(path.clone()),
+            ..Default::default()
+        };
+        history.record_search("read\nline");
+        history.record_viewed("std::io::Read\r\n");
+        history.save("My\treaders", "read\tto\nend", Some("std\n"));
+        history.record_versions(versions(&[("serde\t", "1.0.210\n")]));
+
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(content.lines().count(), 4, "{content}");
+
+        let parsed = WorkspaceHistory::parse(&content);
+        assert_eq!(parsed.queries(), ["read line"]);
+        assert_eq!(parsed.viewed(), ["std::io::Read"]);
+        assert_eq!(
+            parsed.saved(),
+            [SavedSearch {
+                name: "My readers".into(),
+                query: "read to end".into(),
+                crate_name: Some("std".into()),
+            }]
+        );
+        assert_eq!(parsed.versions, history.versions);
+        assert_eq!(parsed.versions[0].version, "1.0.210");
+    }
+
+    #[test]
+    fn version_changes_are_relative_to_the_last_recording() {
+        let mut history = WorkspaceHistory::default();
+        let first = history.record_versions(versions(&[("serde", "1.0.190"), ("log", "0.4.20")]));
+        assert_eq!(first, None);
+
+        let changes = history.record_versions(versions(&[("serde", "1.0.210"), ("rand", "0.8.5")]));
+        assert_eq!(
+            changes.unwrap(),
+            [
+                VersionChange {
+                    crate_name: "log".into(),
+                    from: Some("0.4.20".into()),
+                    to: None,
+                },
+                VersionChange {
+                    crate_name: "rand".into(),
+                    from: None,
+                    to: Some("0.8.5".into()),
+                },
+                VersionChange {
+                    crate_name: "serde".into(),
+                    from: Some("1.0.190".into()),
+                    to: Some("1.0.210".into()),
+                },
+            ]
+        );
+
+        let parsed = WorkspaceHistory::parse(&history.serialize());
+        assert_eq!(parsed.previous_version("serde", "1.0.210"), Some("1.0.190"));
+        assert_eq!(parsed.previous_version("rand", "0.8.5"), None);
+        assert!(
+            history
+                .record_versions(versions(&[("serde", "1.0.210"), ("rand", "0.8.5")]))
+                .is_some_and(|changes| changes.is_empty())
+        );
+        assert_eq!(
+            history.previous_version("serde", "1.0.210"),
+            Some("1.0.190")
+        );
+    }
+
+    #[test]
+    fn versions_of_one_crate_used_side_by_side_are_tracked_separately() {
+        let mut history = WorkspaceHistory::default();
+        history.record_versions(versions(&[("rand", "0.7.3"), ("rand", "0.8.5")]));
+
+        let unchanged = history.record_versions(versions(&[("rand", "0.8.5"), ("rand", "0.7.3")]));
+        assert_eq!(unchanged, Some(vec![]));
+
+        let changes = history.record_versions(versions(&[("rand", "0.8.5"), ("rand", "0.9.0")]));
+        assert_eq!(
+            changes.unwrap(),
+            [VersionChange {
+                crate_name: "rand".into(),
+                from: Some("0.7.3".into()),
+                to: Some("0.9.0".into()),
+            }]
+        );
+
+        let parsed = WorkspaceHistory::parse(&history.serialize());
+        assert_eq!(parsed.previous_version("rand", "0.9.0"), Some("0.7.3"));
+        assert_eq!(parsed.previous_version("rand", "0.8.5"), None);
+    }
+}
@@ -42,6 +42,14 @@ impl FormatContext {
         }
     }
 
+    /// Create context for one item's complete docs, as the get_item_doc tool shows them
+    pub(crate) fn full_docs() -> Self {
+        Self {
+            verbosity: Verbosity::Full,
+            ..Self::default()
+        }
+    }
+
     pub(crate) fn filter_match_kind(&self, kind: ItemKind) -> bool {
         self.filters.iter().any(|filter| filter.matches_kind(kind))
     }
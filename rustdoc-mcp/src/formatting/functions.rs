@@ -338,7 +338,7 @@ impl Request {
     }
 
     /// Format a path
-    pub(super) fn format_path(&self, path: &Path) -> String {
+    pub(crate) fn format_path(&self, path: &Path) -> String {
         let mut result = path.path.clone();
         if let Some(args) = &path.args {
             result.push_str(&self.format_generic_args(args));
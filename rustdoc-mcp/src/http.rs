@@ -0,0 +1,239 @@
+//! Plain HTTP+JSON access to the read-only tools, for clients that don't speak MCP
+//!
+//! Each tool is a `POST /<tool name>` endpoint taking the same JSON arguments as the MCP tool
+//! and returning `{"result": "..."}` with the same formatted text. `GET /openapi.json`
+//! describes the endpoints.
+
+use crate::state::RustdocTools;
+use crate::tools::Tools;
+use anyhow::{Context, Result};
+use mcplease::serde_json::{self, Map, Value, json};
+use mcplease::traits::{AsToolsList, Tool};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+/// Address used when none is given on the command line
+pub(crate) const DEFAULT_ADDRESS: &str = "127.0.0.1:8717";
+
+/// Tools exposed over HTTP. `set_working_directory` is left out: the API is read-only and
+/// serves the directory the server was started in.
+const READ_ONLY_TOOLS: &[&str] = &[
+    "crate_overview",
+    "find_implementations",
+    "find_trait_impls_for_type",
+    "get_item",
+    "get_item_doc",
+    "get_source",
+    "search",
+    "search_docs",
+    "list_crates",
+    "list_item_children",
+    "resolve",
+];
+
+/// Largest request body accepted, in bytes
+const MAX_BODY: usize = 64 * 1024;
+
+/// How long a client may take to send its request, or to take the response
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Serve requests until the process is stopped, each connection on its own thread
+///
+/// Tools share the session state, so they run one at a time, but a slow client only holds up
+/// its own connection.
+pub(crate) fn serve(state: RustdocTools, address: &str) -> Result<()> {
+    let listener =
+        TcpListener::bind(address).with_context(|| format!("could not listen on {address}"))?;
+    eprintln!("Serving rustdoc JSON API on http://{address} (described at /openapi.json)");
+
+    let state = Mutex::new(state);
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let state = &state;
+                    scope.spawn(move || {
+                        if let Err(error) = handle(state, &stream) {
+                            log::warn!("{error}");
+                        }
+                    });
+                }
+                Err(error) => log::warn!("{error}"),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Answer the request on `stream`
+pub(crate) fn handle(state: &Mutex<RustdocTools>, mut stream: &TcpStream) -> Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    // None once a Content-Length header fails to parse
+    let mut content_length = Some(0);
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().ok();
+        }
+    }
+
+    let (status, body) = match content_length {
+        None => (400, json!({ "error": "invalid Content-Length header" })),
+        Some(content_length) if content_length > MAX_BODY => {
+            (413, json!({ "error": "request body too large" }))
+        }
+        Some(content_length) => {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            // A tool that panicked on another connection doesn't stop this one being answered
+            let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+            route(&mut state, method, path, &body)
+        }
+    };
+
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason(status),
+        body.len()
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Dispatch a request, returning the status code and JSON body
+pub(crate) fn route(
+    state: &mut RustdocTools,
+    method: &str,
+    path: &str,
+    body: &[u8],
+) -> (u16, Value) {
+    let path = path.split('?').next().unwrap_or_default();
+    let name = path.trim_start_matches('/');
+
+    match (method, name) {
+        ("GET", "openapi.json") => (200, openapi()),
+
+        ("POST", name) if READ_ONLY_TOOLS.contains(&name) => {
+            let arguments: Value = if body.is_empty() {
+                json!({})
+            } else {
+                match serde_json::from_slice(body) {
+                    Ok(arguments) => arguments,
+                    Err(error) => return (400, json!({ "error": error.to_string() })),
+                }
+            };
+
+            let tool: Tools =
+                match serde_json::from_value(json!({ "name": name, "arguments": arguments })) {
+                    Ok(tool) => tool,
+                    Err(error) => return (400, json!({ "error": error.to_string() })),
+                };
+
+            match tool.execute(state) {
+                Ok(result) => (200, json!({ "result": result })),
+                Err(error) => (500, json!({ "error": error.to_string() })),
+            }
+        }
+
+        _ => (
+            404,
+            json!({ "error": format!("no such endpoint: {method} {path}") }),
+        ),
+    }
+}
+
+/// An OpenAPI description of the endpoints, built from the tools' input schemas
+fn openapi() -> Value {
+    let error_response = |description: &str| {
+        json!({
+            "description": description,
+            "content": {
+                "application/json": { "schema": { "$ref": "#/components/schemas/Error" } }
+            }
+        })
+    };
+
+    let paths: Map<String, Value> = Tools::tools_list()
+        .into_iter()
+        .filter(|tool| READ_ONLY_TOOLS.contains(&&*tool.name))
+        .map(|tool| {
+            let operation = json!({
+                "post": {
+                    "operationId": tool.name,
+                    "description": tool.description,
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": tool.input_schema } }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Formatted documentation text",
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/Result" }
+                                }
+                            }
+                        },
+                        "400": error_response("Invalid arguments"),
+                        "500": error_response("The tool failed"),
+                    }
+                }
+            });
+            (format!("/{}", tool.name), operation)
+        })
+        .collect();
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": env!("CARGO_PKG_DESCRIPTION"),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": {
+                "Result": {
+                    "type": "object",
+                    "required": ["result"],
+                    "properties": { "result": { "type": "string" } }
+                },
+                "Error": {
+                    "type": "object",
+                    "required": ["error"],
+                    "properties": { "error": { "type": "string" } }
+                }
+            }
+        }
+    })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    }
+}
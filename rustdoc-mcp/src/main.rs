@@ -1,8 +1,11 @@
 mod filter;
 mod format_context;
 mod formatting;
+mod http;
 mod indent;
 mod request;
+mod resources;
+mod serve;
 mod state;
 mod tools;
 mod traits;
@@ -26,6 +29,27 @@ fn main() -> Result<()> {
 
     let mut state = RustdocTools::new(storage_path)?;
 
+    // `rustdoc-mcp http [address]` serves the read-only tools over plain HTTP+JSON, in its own
+    // session rooted at the current directory
+    if env::args().nth(1).as_deref() == Some("http") {
+        let address = env::args()
+            .nth(2)
+            .unwrap_or_else(|| http::DEFAULT_ADDRESS.to_string());
+        state.set_default_session_id("http");
+        state.set_working_directory(env::current_dir()?, None)?;
+        return http::serve(state, &address);
+    }
+
+    // The MCP server is run here rather than by mcplease, which doesn't answer resource
+    // requests
+    if env::args().nth(1).as_deref() == Some("serve") {
+        return serve::serve(
+            &mut state,
+            std::io::stdin().lock(),
+            std::io::stdout().lock(),
+        );
+    }
+
     mcplease::run::<Tools, _>(&mut state, server_info!(), Some(INSTRUCTIONS))
 }
 
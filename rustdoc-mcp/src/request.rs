@@ -1,5 +1,5 @@
 use ferritin_common::Navigator;
-use ferritin_common::sources::{DocsRsSource, LocalSource, StdSource};
+use ferritin_common::sources::{DocsRsSource, LocalSource, StdSource, VendoredSource};
 use std::ops::Deref;
 use std::path::PathBuf;
 
@@ -23,7 +23,8 @@ impl Request {
         let navigator = Navigator::default()
             .with_std_source(StdSource::from_rustup())
             .with_local_source(LocalSource::load(&manifest_path).ok())
-            .with_docsrs_source(DocsRsSource::from_default_cache());
+            .with_docsrs_source(DocsRsSource::from_default_cache())
+            .with_vendored_source(VendoredSource::from_default_cargo_home());
 
         Self { navigator }
     }
@@ -0,0 +1,217 @@
+//! MCP resources: crate docs addressed by `ferretin://` URIs
+//!
+//! `ferretin://serde_json/value/Value` names the item `serde_json::value::Value`, and reading
+//! it returns the same text get_item shows for it. The resource list holds the root of every
+//! available crate, followed by the public items of the workspace's crates, a page at a time;
+//! the items of other crates are read through the URI template.
+
+use crate::format_context::FormatContext;
+use crate::request::Request;
+use crate::state::RustdocTools;
+use ferritin_common::DocRef;
+use mcplease::serde_json::{Map, Value, json};
+use mcplease::types::McpError;
+use rustdoc_types::{Item, ItemKind, Visibility};
+use std::path::PathBuf;
+
+/// The scheme of the URIs that name crates and items
+pub(crate) const SCHEME: &str = "ferretin://";
+
+/// How many resources are listed in each page of `resources/list`
+pub(crate) const PAGE_SIZE: usize = 100;
+
+/// JSON-RPC error code for parameters that don't make sense
+const INVALID_PARAMS: i32 = -32602;
+
+/// MCP error code for a resource that doesn't exist
+const RESOURCE_NOT_FOUND: i32 = -32002;
+
+/// The resources `resources/list` pages through, kept between pages so that the offsets in
+/// the cursors stay meaningful and the crates aren't walked again for every page
+#[derive(Debug)]
+pub(crate) struct ListedResources {
+    manifest_path: PathBuf,
+    resources: Vec<Value>,
+}
+
+/// Answer `resources/list`, starting after the resources of the pages before `cursor`
+///
+/// The first page builds the list, and the pages after it are taken from that list as long as
+/// the working directory stays the same.
+pub(crate) fn list(state: &mut RustdocTools, cursor: Option<&str>) -> Result<Value, McpError> {
+    let offset = match cursor {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .map_err(|_| error(INVALID_PARAMS, format!("Invalid cursor: {cursor}")))?,
+        None => 0,
+    };
+
+    let manifest_path = manifest_path(state)?;
+    let is_listed = cursor.is_some()
+        && state
+            .listed_resources()
+            .is_some_and(|listed| listed.manifest_path == manifest_path);
+    if !is_listed {
+        let resources = collect_resources(&Request::new(manifest_path.clone()));
+        state.set_listed_resources(Some(ListedResources {
+            manifest_path,
+            resources,
+        }));
+    }
+    let resources = state
+        .listed_resources()
+        .map_or(&[][..], |listed| listed.resources.as_slice());
+
+    if offset > resources.len() {
+        return Err(error(INVALID_PARAMS, format!("Invalid cursor: {offset}")));
+    }
+    let end = (offset + PAGE_SIZE).min(resources.len());
+    let mut result = json!({ "resources": &resources[offset..end] });
+    if end < resources.len() {
+        result["nextCursor"] = end.to_string().into();
+    }
+    Ok(result)
+}
+
+/// The root of every available crate, followed by the public items of the workspace's crates
+fn collect_resources(request: &Request) -> Vec<Value> {
+    let mut resources = vec![];
+
+    let mut crates = request.list_available_crates().collect::<Vec<_>>();
+    crates.sort_by(|a, b| a.name().cmp(b.name()));
+    for crate_info in &crates {
+        let mut resource = Map::new();
+        resource.insert(
+            "uri".into(),
+            format!("{SCHEME}{}", crate_info.name()).into(),
+        );
+        resource.insert("name".into(), crate_info.name().into());
+        if let Some(description) = crate_info.description() {
+            resource.insert("description".into(), description.replace('\n', " ").into());
+        }
+        resource.insert("mimeType".into(), "text/plain".into());
+        resources.push(Value::Object(resource));
+    }
+
+    for crate_info in crates
+        .iter()
+        .filter(|crate_info| crate_info.provenance().is_workspace())
+    {
+        if let Some(root) = request.find_path(crate_info.name()) {
+            let mut path = vec![crate_info.name()];
+            add_items(&collect_items(root), &mut path, &mut resources);
+        }
+    }
+
+    resources
+}
+
+/// Answer `resources/templates/list`
+pub(crate) fn templates() -> Value {
+    json!({
+        "resourceTemplates": [{
+            "uriTemplate": format!("{SCHEME}{{crate}}/{{+path}}"),
+            "name": "Rust item documentation",
+            "description": "The docs of an item in any available crate, like \
+                            ferretin://std/vec/Vec for std::vec::Vec",
+            "mimeType": "text/plain",
+        }]
+    })
+}
+
+/// Answer `resources/read`
+pub(crate) fn read(state: &mut RustdocTools, uri: &str) -> Result<Value, McpError> {
+    let Some(path) = uri_to_path(uri) else {
+        return Err(error(INVALID_PARAMS, format!("Not a {SCHEME} URI: {uri}")));
+    };
+
+    let request = request(state)?;
+    let Some(item) = request.find_path(&path) else {
+        return Err(error(
+            RESOURCE_NOT_FOUND,
+            format!("Resource not found: {uri}"),
+        ));
+    };
+
+    let text = request.format_item(item, &FormatContext::default());
+    Ok(json!({
+        "contents": [{ "uri": uri, "mimeType": "text/plain", "text": text }]
+    }))
+}
+
+/// The item path a URI names, like `std::vec::Vec` for `ferretin://std/vec/Vec`
+pub(crate) fn uri_to_path(uri: &str) -> Option<String> {
+    let segments = uri
+        .strip_prefix(SCHEME)?
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    (!segments.is_empty()).then(|| segments.join("::"))
+}
+
+/// A public item to list, with the items under it when it's a module
+struct ListedItem<'a> {
+    item: DocRef<'a, Item>,
+    children: Vec<ListedItem<'a>>,
+}
+
+/// The public items in `module` and, within the same crate, in its submodules, by name
+fn collect_items(module: DocRef<'_, Item>) -> Vec<ListedItem<'_>> {
+    let is_listed = |child: DocRef<'_, Item>| {
+        child.name().is_some()
+            && !matches!(child.kind(), ItemKind::Impl | ItemKind::Use)
+            && matches!(child.visibility, Visibility::Public | Visibility::Default)
+    };
+    let mut items = module.item_tree(
+        &|child, _| child.kind() == ItemKind::Module && is_listed(child),
+        &mut |child, mut children| {
+            children.sort_by_key(|listed: &ListedItem<'_>| listed.item.name());
+            is_listed(child).then_some(ListedItem {
+                item: child,
+                children,
+            })
+        },
+    );
+    items.sort_by_key(|listed| listed.item.name());
+    items
+}
+
+/// Add a resource for each of `items`, each followed by the items under it
+fn add_items<'a>(items: &[ListedItem<'a>], path: &mut Vec<&'a str>, resources: &mut Vec<Value>) {
+    for listed in items {
+        let Some(name) = listed.item.name() else {
+            continue;
+        };
+        path.push(name);
+
+        let mut resource = Map::new();
+        resource.insert("uri".into(), format!("{SCHEME}{}", path.join("/")).into());
+        resource.insert("name".into(), path.join("::").into());
+        if let Some(summary) = listed.item.docs_summary() {
+            resource.insert("description".into(), summary.into());
+        }
+        resource.insert("mimeType".into(), "text/plain".into());
+        resources.push(Value::Object(resource));
+
+        add_items(&listed.children, path, resources);
+        path.pop();
+    }
+}
+
+fn request(state: &mut RustdocTools) -> Result<Request, McpError> {
+    Ok(Request::new(manifest_path(state)?))
+}
+
+fn manifest_path(state: &mut RustdocTools) -> Result<PathBuf, McpError> {
+    state
+        .resolve_path("Cargo.toml", None)
+        .map_err(|resolve_error| error(INVALID_PARAMS, resolve_error.to_string()))
+}
+
+fn error(code: i32, message: String) -> McpError {
+    McpError {
+        code,
+        message,
+        data: None,
+    }
+}
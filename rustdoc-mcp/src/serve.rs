@@ -0,0 +1,100 @@
+//! The MCP server on stdin and stdout
+//!
+//! mcplease answers the tool methods; the resource methods are answered here, and the
+//! `initialize` response is extended to say that the server has resources.
+
+use crate::resources;
+use crate::state::RustdocTools;
+use crate::tools::Tools;
+use anyhow::Result;
+use env_logger::{Builder, Target};
+use mcplease::serde_json::{self, Value, json};
+use mcplease::server_info;
+use mcplease::types::{McpMessage, McpRequest, McpResponse};
+use std::fs::OpenOptions;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// Answer requests from `input` on `output` until the input ends
+pub(crate) fn serve(
+    state: &mut RustdocTools,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<()> {
+    init_logging()?;
+
+    for line in input.lines() {
+        let line = line?;
+        log::trace!("<- {line}");
+        match serde_json::from_str(&line) {
+            Ok(McpMessage::Request(request)) => {
+                let response = serde_json::to_string(&respond(state, request))?;
+                log::trace!("-> {response}");
+                writeln!(output, "{response}")?;
+                output.flush()?;
+            }
+            Ok(McpMessage::Notification(notification)) => {
+                log::trace!("received {notification:?}, ignoring");
+            }
+            Err(error) => log::error!("{error:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Answer one request
+pub(crate) fn respond(state: &mut RustdocTools, request: McpRequest) -> McpResponse {
+    let param = |name: &str| {
+        request
+            .params
+            .as_ref()
+            .and_then(|params| params.get(name))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    let result = match request.method.as_str() {
+        "resources/list" => resources::list(state, param("cursor").as_deref()),
+        "resources/templates/list" => Ok(resources::templates()),
+        "resources/read" => resources::read(state, &param("uri").unwrap_or_default()),
+        _ => {
+            let is_initialize = request.method == "initialize";
+            let mut response = request.execute::<RustdocTools, Tools>(
+                state,
+                Some(crate::INSTRUCTIONS),
+                &server_info!(),
+            );
+            if is_initialize && let Some(result) = &mut response.result {
+                result["capabilities"]["resources"] = json!({});
+            }
+            return response;
+        }
+    };
+
+    match result {
+        Ok(result) => McpResponse::success(request.id, result),
+        Err(error) => McpResponse {
+            jsonrpc: "2.0",
+            id: request.id,
+            result: None,
+            error: Some(error),
+        },
+    }
+}
+
+/// Log to the file named by `MCP_LOG_LOCATION`, as mcplease does for the servers it runs
+fn init_logging() -> Result<()> {
+    let Ok(log_location) = std::env::var("MCP_LOG_LOCATION") else {
+        return Ok(());
+    };
+    let path = PathBuf::from(&*shellexpand::tilde(&log_location));
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Builder::from_default_env()
+        .target(Target::Pipe(Box::new(file)))
+        .try_init()?;
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::resources::ListedResources;
 use anyhow::{Result, anyhow};
 use fieldwork::Fieldwork;
 use mcplease::session::SessionStore;
@@ -20,6 +21,10 @@ pub(crate) struct RustdocTools {
 
     #[field(set, with)]
     default_session_id: &'static str,
+
+    /// The resources listed by the last `resources/list`, for the pages after the first
+    #[field(set)]
+    listed_resources: Option<ListedResources>,
 }
 
 impl RustdocTools {
@@ -30,6 +35,7 @@ impl RustdocTools {
         Ok(Self {
             shared_context_store,
             default_session_id: "default",
+            listed_resources: None,
         })
     }
 
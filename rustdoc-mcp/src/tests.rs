@@ -1,10 +1,13 @@
 use crate::{
     filter::Filter,
     state::RustdocTools,
-    tools::{GetItem, ListCrates, Search, SetWorkingDirectory},
+    tools::{
+        CrateOverview, FindImplementations, FindTraitImplsForType, GetItem, GetItemDoc, GetSource,
+        ListCrates, ListItemChildren, Search, SearchDocs, SetWorkingDirectory,
+    },
     verbosity::Verbosity,
 };
-use mcplease::traits::Tool;
+use mcplease::{serde_json, traits::Tool};
 use std::path::PathBuf;
 
 /// Get the path to our test crate (fast to build, minimal dependencies)
@@ -567,3 +570,376 @@ fn search_2() {
     .unwrap();
     insta::assert_snapshot!(result);
 }
+
+#[test]
+fn test_search_docs_returns_scored_json() {
+    let mut state = create_test_state();
+    let result = SearchDocs {
+        query: "generic struct".into(),
+        crates: Some(vec!["crate".into()]),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    let entries: serde_json::Value = serde_json::from_str(&result).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert!(!entries.is_empty());
+    assert!(
+        entries
+            .iter()
+            .any(|entry| entry["discriminated_path"] == "fixture-crate::struct@GenericStruct")
+    );
+    assert!(entries.iter().all(|entry| entry["crate"].is_string()));
+    assert!(entries.iter().all(|entry| entry["score"].is_number()));
+    assert!(
+        entries
+            .windows(2)
+            .all(|pair| pair[0]["score"].as_f64() >= pair[1]["score"].as_f64())
+    );
+
+    let result = SearchDocs {
+        query: "generic struct".into(),
+        crates: Some(vec!["crate".into()]),
+        filter: Some(vec![Filter::Enum]),
+        limit: Some(1),
+    }
+    .execute(&mut state)
+    .unwrap();
+    let entries: serde_json::Value = serde_json::from_str(&result).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert!(entries.len() <= 1);
+    assert!(entries.iter().all(|entry| entry["kind"] == "enum"));
+
+    let result = SearchDocs {
+        query: "generic struct".into(),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    let entries: serde_json::Value = serde_json::from_str(&result).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert!(!entries.is_empty());
+    assert!(
+        entries
+            .iter()
+            .all(|entry| entry["crate"] == "fixture-crate")
+    );
+}
+
+#[test]
+fn test_list_item_children() {
+    let mut state = create_test_state();
+
+    let result = ListItemChildren {
+        name: "crate::TestStruct".to_string(),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(result.contains("• count (StructField) - "));
+    assert!(result.contains("field@count"));
+    assert!(result.contains("    Another public field"));
+    assert!(result.contains("• new (Function) - "));
+    assert!(!result.contains("private_field"));
+
+    let result = ListItemChildren {
+        name: "crate".to_string(),
+        filter: Some(vec![Filter::Module]),
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(result.contains("• submodule (Module) - fixture-crate::mod@submodule"));
+    assert!(!result.contains("TestStruct"));
+
+    let result = ListItemChildren {
+        name: "crate::TestTrait".to_string(),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(result.contains("• test_method (Function)"));
+    assert!(result.contains("• T (AssocType)"));
+}
+
+#[test]
+fn test_get_source() {
+    let mut state = create_test_state();
+
+    let result = GetSource {
+        name: "crate::TestStruct".to_string(),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    let first_line = result.lines().next().unwrap();
+    assert!(first_line.starts_with("File: ") && first_line.ends_with("lib.rs"));
+    assert!(result.contains("pub struct TestStruct {"));
+    assert!(result.contains("    pub count: u32,"));
+    assert!(!result.contains("showing"));
+
+    let result = GetSource {
+        name: "crate::TestStruct".to_string(),
+        context_lines: Some(2),
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(result.contains("(showing "));
+    assert!(result.contains("#[derive(Debug, Clone)]"));
+}
+
+#[test]
+fn test_resources() {
+    use crate::serve::respond;
+    use mcplease::serde_json::{Value, json};
+
+    let mut state = create_test_state();
+    let mut call = |method: &str, params: Value| {
+        let request = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))
+        .unwrap();
+        serde_json::to_value(respond(&mut state, request)).unwrap()
+    };
+
+    let initialized = call("initialize", json!({}));
+    assert!(initialized["result"]["capabilities"]["resources"].is_object());
+    assert!(initialized["result"]["capabilities"]["tools"].is_object());
+
+    let mut uris = vec![];
+    let mut cursor = Value::Null;
+    loop {
+        let page = call("resources/list", json!({ "cursor": cursor }));
+        let resources = page["result"]["resources"].as_array().unwrap();
+        assert!(resources.len() <= crate::resources::PAGE_SIZE);
+        uris.extend(
+            resources
+                .iter()
+                .map(|resource| resource["uri"].as_str().unwrap().to_string()),
+        );
+        cursor = page["result"]["nextCursor"].clone();
+        if cursor.is_null() {
+            break;
+        }
+    }
+    assert!(uris.contains(&"ferretin://fixture-crate".to_string()));
+    assert!(uris.contains(&"ferretin://fixture-crate/TestStruct".to_string()));
+    assert!(uris.contains(&"ferretin://fixture-crate/submodule/SubStruct".to_string()));
+
+    let read = call(
+        "resources/read",
+        json!({ "uri": "ferretin://fixture-crate/submodule/SubStruct" }),
+    );
+    let contents = &read["result"]["contents"][0];
+    assert_eq!(
+        contents["uri"],
+        "ferretin://fixture-crate/submodule/SubStruct"
+    );
+    assert!(contents["text"].as_str().unwrap().contains("SubStruct"));
+
+    let missing = call(
+        "resources/read",
+        json!({ "uri": "ferretin://fixture-crate/DoesNotExist" }),
+    );
+    assert_eq!(missing["error"]["code"], -32002);
+
+    let templates = call("resources/templates/list", json!({}));
+    assert_eq!(
+        templates["result"]["resourceTemplates"][0]["uriTemplate"],
+        "ferretin://{crate}/{+path}"
+    );
+}
+
+#[test]
+fn test_find_implementations() {
+    let mut state = create_test_state();
+
+    let result = FindImplementations {
+        trait_name: "crate::TestTrait".to_string(),
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(result.contains("• TestStruct - fixture-crate::struct@TestStruct"));
+
+    let not_a_trait = FindImplementations {
+        trait_name: "crate::TestStruct".to_string(),
+    }
+    .execute(&mut state);
+    assert!(not_a_trait.is_err());
+
+    let result = FindTraitImplsForType {
+        type_name: "crate::TestStruct".to_string(),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(result.contains("• TestTrait - fixture-crate::trait@TestTrait"));
+    assert!(!result.contains("(auto)"));
+
+    let result = FindTraitImplsForType {
+        type_name: "crate::TestStruct".to_string(),
+        include_auto_traits: Some(true),
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(result.contains("Send"));
+    assert!(result.contains("(auto)"));
+
+    let result = FindTraitImplsForType {
+        type_name: "u32".to_string(),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(result.starts_with("Traits implemented by u32"));
+    assert!(result.contains("Display"));
+}
+
+#[test]
+fn test_crate_overview() {
+    let mut state = create_test_state();
+
+    let result = CrateOverview {
+        crate_name: "crate".to_string(),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(result.starts_with("# fixture"));
+    assert!(result.contains("this is the crate description"));
+    assert!(result.contains("A minimal test crate for rustdoc JSON testing"));
+    assert!(result.contains("## Modules\n"));
+    assert!(result.contains("• submodule"));
+    assert!(result.contains("## Re-exports\n"));
+    assert!(result.contains("• std::vec::Vec\n"));
+    assert!(result.contains("submodule::*"));
+
+    let short = CrateOverview {
+        crate_name: "crate".to_string(),
+        max_tokens: Some(40),
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(short.len() < result.len());
+    assert!(short.contains("more]"));
+}
+
+#[test]
+fn test_http_routes() {
+    use crate::http::route;
+
+    let mut state = create_test_state();
+
+    let (status, body) = route(
+        &mut state,
+        "POST",
+        "/get_item",
+        br#"{"name": "crate::TestStruct"}"#,
+    );
+    assert_eq!(status, 200);
+    assert!(body["result"].as_str().unwrap().contains("TestStruct"));
+
+    let (status, body) = route(&mut state, "GET", "/openapi.json", b"");
+    assert_eq!(status, 200);
+    assert!(body["paths"]["/search"]["post"].is_object());
+    assert!(body["paths"].get("/set_working_directory").is_none());
+
+    let (status, _) = route(&mut state, "POST", "/set_working_directory", b"{}");
+    assert_eq!(status, 404);
+
+    let (status, _) = route(&mut state, "POST", "/get_item", b"not json");
+    assert_eq!(status, 400);
+
+    let (status, body) = route(
+        &mut state,
+        "POST",
+        "/resolve",
+        br#"{"path": "crate::TestStruct"}"#,
+    );
+    assert_eq!(status, 200);
+    assert!(body["result"].as_str().unwrap().contains("Kind: Struct"));
+}
+
+#[test]
+fn test_http_rejects_a_bad_content_length() {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    let state = std::sync::Mutex::new(create_test_state());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    client
+        .write_all(b"POST /get_item HTTP/1.1\r\nContent-Length: lots\r\n\r\n")
+        .unwrap();
+
+    let (server, _) = listener.accept().unwrap();
+    crate::http::handle(&state, &server).unwrap();
+    drop(server);
+
+    let mut response = String::new();
+    client.read_to_string(&mut response).unwrap();
+    assert!(
+        response.starts_with("HTTP/1.1 400 Bad Request"),
+        "{response}"
+    );
+}
+
+#[test]
+fn test_get_item_doc_cuts_to_budget() {
+    let mut state = create_test_state();
+
+    let whole = GetItemDoc {
+        name: "crate::TestTrait".to_string(),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert!(!whole.contains("[Truncated"));
+
+    let first = GetItemDoc {
+        name: "crate::TestTrait".to_string(),
+        max_chars: Some(400),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    let (text, hint) = first
+        .split_once("\n\n[Truncated: this is section 1/")
+        .unwrap();
+    assert!(text.chars().count() <= 400);
+    assert!(text.lines().all(|line| whole.contains(line)));
+    assert!(hint.contains("Request section 2 for more."));
+
+    // A token is counted as four characters
+    let by_tokens = GetItemDoc {
+        name: "crate::TestTrait".to_string(),
+        max_tokens: Some(100),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert_eq!(by_tokens, first);
+
+    let second = GetItemDoc {
+        name: "crate::TestTrait".to_string(),
+        max_chars: Some(400),
+        section: Some(2),
+        ..Default::default()
+    }
+    .execute(&mut state)
+    .unwrap();
+    assert_ne!(second, first);
+    let text = second.split("\n\n[").next().unwrap();
+    assert!(text.lines().all(|line| whole.contains(line)));
+
+    let past_the_end = GetItemDoc {
+        name: "crate::TestTrait".to_string(),
+        max_chars: Some(400),
+        section: Some(1000),
+        ..Default::default()
+    }
+    .execute(&mut state);
+    assert!(past_the_end.is_err());
+}
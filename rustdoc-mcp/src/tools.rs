@@ -6,7 +6,23 @@ mcplease::tools!(
         set_working_directory,
         "set_working_directory"
     ),
+    (CrateOverview, crate_overview, "crate_overview"),
+    (
+        FindImplementations,
+        find_implementations,
+        "find_implementations"
+    ),
+    (
+        FindTraitImplsForType,
+        find_trait_impls_for_type,
+        "find_trait_impls_for_type"
+    ),
     (GetItem, get_item, "get_item"),
+    (GetItemDoc, get_item_doc, "get_item_doc"),
+    (GetSource, get_source, "get_source"),
     (ListCrates, list_crates, "list_crates"),
-    (Search, search, "search")
+    (ListItemChildren, list_item_children, "list_item_children"),
+    (Resolve, resolve, "resolve"),
+    (Search, search, "search"),
+    (SearchDocs, search_docs, "search_docs")
 );
@@ -0,0 +1,194 @@
+use crate::request::Request;
+use crate::state::RustdocTools;
+use crate::tools::get_item::not_found;
+use crate::tools::get_item_doc::{CHARS_PER_TOKEN, sections};
+use crate::traits::WriteFmt;
+use anyhow::{Result, bail};
+use clap::Args;
+use mcplease::{
+    traits::{Tool, WithExamples},
+    types::Example,
+};
+use rustdoc_types::{ItemEnum, Visibility};
+use schemars::JsonSchema;
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+
+/// Get an overview of a crate to start working with it: its root docs, top-level modules,
+/// re-exports and feature flags in one document of bounded size
+///
+/// ## Usage Patterns
+/// - **Unfamiliar dependency**: `crate_name: "serde_json"` - What the crate is and where to look
+/// - **Shorter**: `crate_name: "tokio", max_tokens: 1000` - The same, in fewer tokens
+///
+/// The crate's docs are cut to leave room for the lists, and a list that doesn't fit ends
+/// with how many entries were left out. Use get_item_doc for the crate's complete docs.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Args)]
+#[serde(rename = "crate_overview")]
+pub struct CrateOverview {
+    /// The crate to describe. Use `crate` for the current crate.
+    pub crate_name: String,
+
+    /// Largest number of tokens to return, counting about four characters per token
+    /// (default: 2000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub max_tokens: Option<usize>,
+}
+
+impl CrateOverview {
+    /// The most characters to return
+    pub(crate) fn budget(&self) -> usize {
+        self.max_tokens
+            .unwrap_or(2000)
+            .saturating_mul(CHARS_PER_TOKEN)
+            .max(1)
+    }
+}
+
+impl WithExamples for CrateOverview {
+    fn examples() -> Vec<Example<Self>> {
+        vec![
+            Example {
+                description: "Get an overview of a dependency",
+                item: Self {
+                    crate_name: "serde_json".to_string(),
+                    ..Default::default()
+                },
+            },
+            Example {
+                description: "Get a short overview of the current crate",
+                item: Self {
+                    crate_name: "crate".to_string(),
+                    max_tokens: Some(1000),
+                },
+            },
+        ]
+    }
+}
+
+impl Tool<RustdocTools> for CrateOverview {
+    fn execute(self, tools: &mut RustdocTools) -> Result<String> {
+        let manifest_path = tools.resolve_path("Cargo.toml", None)?;
+        let request = Request::new(manifest_path);
+
+        let mut suggestions = vec![];
+        let Some(root) = request.resolve_path(&self.crate_name, &mut suggestions) else {
+            return Ok(not_found(&self.crate_name, suggestions));
+        };
+        let ItemEnum::Module(module) = root.inner() else {
+            bail!("{} is not a crate", self.crate_name);
+        };
+        let crate_docs = root.crate_docs();
+        let crate_info = request.lookup_crate(crate_docs.name(), &VersionReq::STAR);
+
+        let mut header = format!("# {}", crate_docs.name());
+        if let Some(version) = crate_docs.version() {
+            header.write_fmt(format_args!(" {version}"));
+        }
+        header.push('\n');
+        if let Some(description) = crate_info.as_ref().and_then(|info| info.description()) {
+            header.write_fmt(format_args!("{}\n", description.trim()));
+        }
+
+        let mut modules = vec![];
+        let mut reexports = vec![];
+        for child in root.id_iter(&module.items).with_include_use(true) {
+            if !matches!(child.visibility, Visibility::Public) {
+                continue;
+            }
+            match child.inner() {
+                ItemEnum::Module(_) => {
+                    let name = child.name().unwrap_or("<unnamed>");
+                    modules.push(match child.docs_summary() {
+                        Some(summary) => format!("{name} - {summary}"),
+                        None => name.to_string(),
+                    });
+                }
+                ItemEnum::Use(use_item) if use_item.is_glob => {
+                    reexports.push(format!("{}::*", use_item.source));
+                }
+                ItemEnum::Use(use_item) => {
+                    let source_name = use_item.source.rsplit("::").next();
+                    reexports.push(if source_name == Some(use_item.name.as_str()) {
+                        use_item.source.clone()
+                    } else {
+                        format!("{} as {}", use_item.source, use_item.name)
+                    });
+                }
+                _ => {}
+            }
+        }
+        modules.sort();
+        reexports.sort();
+
+        let mut features = vec![];
+        if let Some(info) = &crate_info {
+            if let Some(default) = info.features().get("default") {
+                features.push(format!("default = [{}]", default.join(", ")));
+            }
+            for (feature, enables) in info.features() {
+                if feature == "default" {
+                    continue;
+                }
+                features.push(if enables.is_empty() {
+                    feature.clone()
+                } else {
+                    format!("{feature} = [{}]", enables.join(", "))
+                });
+            }
+        }
+
+        let lists = [
+            ("Modules", modules),
+            ("Re-exports", reexports),
+            ("Feature flags", features),
+        ];
+
+        // The docs get what the lists leave, but no less than half the budget
+        let budget = self.budget();
+        let lists_size: usize = lists
+            .iter()
+            .filter(|(_, lines)| !lines.is_empty())
+            .map(|(title, lines)| {
+                title.len() + 6 + lines.iter().map(|line| line.len() + 5).sum::<usize>()
+            })
+            .sum();
+        let docs_budget = budget
+            .saturating_sub(header.len() + lists_size)
+            .max(budget / 2);
+
+        let mut result = header;
+        if let Some(docs) = &root.docs {
+            let sections = sections(docs, docs_budget);
+            if let Some(first) = sections.first() {
+                result.push('\n');
+                result.push_str(first.trim_end());
+                result.push('\n');
+            }
+            if sections.len() > 1 {
+                result.write_fmt(format_args!(
+                    "\n[Docs truncated. Use get_item_doc with name \"{}\" for the rest.]\n",
+                    self.crate_name
+                ));
+            }
+        }
+
+        for (title, lines) in lists {
+            if lines.is_empty() {
+                continue;
+            }
+            result.write_fmt(format_args!("\n## {title}\n"));
+            let total = lines.len();
+            for (shown, line) in lines.into_iter().enumerate() {
+                if shown > 0 && result.len() + line.len() + 3 > budget {
+                    result.write_fmt(format_args!("• [+{} more]\n", total - shown));
+                    break;
+                }
+                result.write_fmt(format_args!("• {line}\n"));
+            }
+        }
+
+        Ok(result)
+    }
+}
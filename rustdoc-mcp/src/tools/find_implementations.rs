@@ -0,0 +1,152 @@
+use crate::request::Request;
+use crate::state::RustdocTools;
+use crate::tools::get_item::not_found;
+use crate::traits::WriteFmt;
+use anyhow::{Result, bail};
+use clap::Args;
+use ferritin_common::DocRef;
+use mcplease::{
+    traits::{Tool, WithExamples},
+    types::Example,
+};
+use rustdoc_types::{Impl, Item, ItemEnum, Type};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Find the types that implement a trait
+///
+/// ## Usage Patterns
+/// - **Implementors**: `trait_name: "std::iter::Iterator"` - The types implementing Iterator
+/// - **In your workspace**: `trait_name: "serde::Serialize"` - The implementations in the trait's
+///   own crate and in the workspace's crates
+///
+/// Each type is shown with a path that can be passed to get_item or get_item_doc. Blanket
+/// implementations, like `impl<T: Display> ToString for T`, are marked as such.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Args)]
+#[serde(rename = "find_implementations")]
+pub struct FindImplementations {
+    /// The trait whose implementations to find (e.g., "std::fmt::Display", "crate::MyTrait")
+    pub trait_name: String,
+}
+
+impl WithExamples for FindImplementations {
+    fn examples() -> Vec<Example<Self>> {
+        vec![
+            Example {
+                description: "Find the types implementing a std trait",
+                item: Self {
+                    trait_name: "std::error::Error".to_string(),
+                },
+            },
+            Example {
+                description: "Find the types implementing a trait of the current crate",
+                item: Self {
+                    trait_name: "crate::MyTrait".to_string(),
+                },
+            },
+        ]
+    }
+}
+
+impl Tool<RustdocTools> for FindImplementations {
+    fn execute(self, tools: &mut RustdocTools) -> Result<String> {
+        let manifest_path = tools.resolve_path("Cargo.toml", None)?;
+        let request = Request::new(manifest_path);
+
+        let mut suggestions = vec![];
+        let Some(trait_item) = request.resolve_path(&self.trait_name, &mut suggestions) else {
+            return Ok(not_found(&self.trait_name, suggestions));
+        };
+        let ItemEnum::Trait(trait_data) = trait_item.inner() else {
+            bail!(
+                "{} is a {:?}, not a trait. Use find_trait_impls_for_type to find the traits \
+                 a type implements.",
+                self.trait_name,
+                trait_item.kind()
+            );
+        };
+
+        // The trait's own crate lists every implementation it knows of; the workspace's
+        // crates may implement it too
+        let mut impl_blocks: Vec<_> = trait_data
+            .implementations
+            .iter()
+            .filter_map(|id| trait_item.get(id))
+            .collect();
+        for crate_info in request
+            .list_available_crates()
+            .filter(|crate_info| crate_info.provenance().is_workspace())
+        {
+            let Some(root) = request.find_path(crate_info.name()) else {
+                continue;
+            };
+            if std::ptr::eq(root.crate_docs(), trait_item.crate_docs()) {
+                continue;
+            }
+            impl_blocks.extend(
+                root.crate_docs()
+                    .items()
+                    .map(|item| root.build_ref(item))
+                    .filter(|impl_block| implements(*impl_block, trait_item)),
+            );
+        }
+
+        let mut lines = impl_blocks
+            .into_iter()
+            .filter_map(|impl_block| match impl_block.inner() {
+                ItemEnum::Impl(impl_item) => {
+                    Some(implementor_line(&request, impl_block, impl_item))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        lines.sort();
+        lines.dedup();
+
+        let mut result = String::new();
+        if lines.is_empty() {
+            result.write_fmt(format_args!(
+                "No implementations of {} found.\n",
+                self.trait_name
+            ));
+            return Ok(result);
+        }
+        result.write_fmt(format_args!(
+            "Implementations of {} ({}):\n\n",
+            self.trait_name,
+            lines.len()
+        ));
+        for line in lines {
+            result.write_fmt(format_args!("• {line}\n"));
+        }
+        Ok(result)
+    }
+}
+
+/// The implementing type of an impl block, with its path for follow-up calls if it's a named
+/// type
+fn implementor_line(request: &Request, impl_block: DocRef<'_, Item>, impl_item: &Impl) -> String {
+    let mut line = request.format_type(&impl_item.for_);
+    if let Type::ResolvedPath(path) = &impl_item.for_
+        && let Some(path) = impl_block
+            .get_path(path.id)
+            .and_then(|implementor| implementor.discriminated_path())
+    {
+        line.write_fmt(format_args!(" - {path}"));
+    }
+    if impl_item.blanket_impl.is_some() {
+        line.push_str(" (blanket)");
+    }
+    line
+}
+
+/// Whether `impl_block` is an impl of `trait_item`
+fn implements<'a>(impl_block: DocRef<'a, Item>, trait_item: DocRef<'a, Item>) -> bool {
+    if let ItemEnum::Impl(impl_item) = impl_block.inner()
+        && let Some(trait_path) = &impl_item.trait_
+    {
+        impl_block.get_path(trait_path.id) == Some(trait_item)
+    } else {
+        false
+    }
+}
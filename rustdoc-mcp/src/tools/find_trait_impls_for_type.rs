@@ -0,0 +1,180 @@
+use crate::request::Request;
+use crate::state::RustdocTools;
+use crate::tools::get_item::not_found;
+use crate::traits::WriteFmt;
+use anyhow::{Result, bail};
+use clap::{ArgAction, Args};
+use ferritin_common::DocRef;
+use mcplease::{
+    traits::{Tool, WithExamples},
+    types::Example,
+};
+use rustdoc_types::{Item, ItemEnum, ItemKind, Type};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Find the traits that a type implements
+///
+/// ## Usage Patterns
+/// - **Implemented traits**: `type_name: "std::string::String"` - The traits String implements
+/// - **Primitives**: `type_name: "u32"` - The traits a primitive type implements
+/// - **With auto traits**: `type_name: "crate::MyStruct", include_auto_traits: true` - Also
+///   lists the traits the compiler implements, like Send and Sync
+///
+/// Each trait is shown with a path that can be passed to get_item or get_item_doc, and with
+/// the type arguments of the implementation, as in `From<&str>`. Implementations in the
+/// workspace's crates are found as well as those in the type's own crate.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Args)]
+#[serde(rename = "find_trait_impls_for_type")]
+pub struct FindTraitImplsForType {
+    /// The type whose trait implementations to find (e.g., "std::vec::Vec", "crate::MyStruct",
+    /// "u32")
+    pub type_name: String,
+
+    /// Whether to include auto traits like Send and Sync, which the compiler implements
+    /// (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long, action = ArgAction::SetTrue)]
+    pub include_auto_traits: Option<bool>,
+}
+
+impl FindTraitImplsForType {
+    /// Get include_auto_traits with default
+    pub(crate) fn include_auto_traits(&self) -> bool {
+        self.include_auto_traits.unwrap_or(false)
+    }
+}
+
+impl WithExamples for FindTraitImplsForType {
+    fn examples() -> Vec<Example<Self>> {
+        vec![
+            Example {
+                description: "Find the traits a std type implements",
+                item: Self {
+                    type_name: "std::string::String".to_string(),
+                    ..Default::default()
+                },
+            },
+            Example {
+                description: "Find every trait a type of the current crate implements",
+                item: Self {
+                    type_name: "crate::MyStruct".to_string(),
+                    include_auto_traits: Some(true),
+                },
+            },
+        ]
+    }
+}
+
+impl Tool<RustdocTools> for FindTraitImplsForType {
+    fn execute(self, tools: &mut RustdocTools) -> Result<String> {
+        let manifest_path = tools.resolve_path("Cargo.toml", None)?;
+        let request = Request::new(manifest_path);
+
+        let mut suggestions = vec![];
+        let Some(item) = request.resolve_path(&self.type_name, &mut suggestions) else {
+            return Ok(not_found(&self.type_name, suggestions));
+        };
+        if !matches!(
+            item.kind(),
+            ItemKind::Struct | ItemKind::Enum | ItemKind::Union | ItemKind::Primitive
+        ) {
+            bail!(
+                "{} is a {:?}, not a type. Use find_implementations to find the types \
+                 implementing a trait.",
+                self.type_name,
+                item.kind()
+            );
+        }
+
+        // The type's own crate lists the impls it knows of; the workspace's crates may
+        // implement their traits for it too
+        let mut impl_blocks: Vec<_> = item.traits().collect();
+        for crate_info in request
+            .list_available_crates()
+            .filter(|crate_info| crate_info.provenance().is_workspace())
+        {
+            let Some(root) = request.find_path(crate_info.name()) else {
+                continue;
+            };
+            if std::ptr::eq(root.crate_docs(), item.crate_docs()) {
+                continue;
+            }
+            impl_blocks.extend(
+                root.crate_docs()
+                    .items()
+                    .map(|impl_block| root.build_ref(impl_block))
+                    .filter(|impl_block| is_trait_impl_for(*impl_block, item)),
+            );
+        }
+
+        let mut lines = vec![];
+        for impl_block in impl_blocks {
+            let ItemEnum::Impl(impl_item) = impl_block.inner() else {
+                continue;
+            };
+            let Some(trait_path) = &impl_item.trait_ else {
+                continue;
+            };
+            if impl_item.is_synthetic && !self.include_auto_traits() {
+                continue;
+            }
+
+            let mut line = String::new();
+            if impl_item.is_negative {
+                line.push('!');
+            }
+            line.push_str(&request.format_path(trait_path));
+            if let Some(path) = impl_block
+                .get_path(trait_path.id)
+                .and_then(|trait_item| trait_item.discriminated_path())
+            {
+                line.write_fmt(format_args!(" - {path}"));
+            }
+            if impl_item.blanket_impl.is_some() {
+                line.push_str(" (blanket)");
+            }
+            if impl_item.is_synthetic {
+                line.push_str(" (auto)");
+            }
+            lines.push(line);
+        }
+        lines.sort();
+        lines.dedup();
+
+        let mut result = String::new();
+        if lines.is_empty() {
+            result.write_fmt(format_args!(
+                "No trait implementations found for {}.\n",
+                self.type_name
+            ));
+            return Ok(result);
+        }
+        result.write_fmt(format_args!(
+            "Traits implemented by {} ({}):\n\n",
+            self.type_name,
+            lines.len()
+        ));
+        for line in lines {
+            result.write_fmt(format_args!("• {line}\n"));
+        }
+        Ok(result)
+    }
+}
+
+/// Whether `impl_block` implements a trait for `type_item`
+fn is_trait_impl_for<'a>(impl_block: DocRef<'a, Item>, type_item: DocRef<'a, Item>) -> bool {
+    let ItemEnum::Impl(impl_item) = impl_block.inner() else {
+        return false;
+    };
+    if impl_item.trait_.is_none() {
+        return false;
+    }
+    match &impl_item.for_ {
+        Type::ResolvedPath(path) => impl_block.get_path(path.id) == Some(type_item),
+        Type::Primitive(name) => {
+            type_item.kind() == ItemKind::Primitive && type_item.name() == Some(name.as_str())
+        }
+        _ => false,
+    }
+}
@@ -6,6 +6,7 @@ use crate::traits::WriteFmt;
 use crate::verbosity::Verbosity;
 use anyhow::Result;
 use clap::{ArgAction, Args};
+use ferritin_common::Suggestion;
 use mcplease::{
     traits::{Tool, WithExamples},
     types::Example,
@@ -167,18 +168,35 @@ impl Tool<RustdocTools> for GetItem {
 
             Ok(request.format_item(item, &context))
         } else {
-            let mut result = format!("`{}` not found. Did you mean one of these?\n\n", self.name);
-            suggestions.sort_by(|a, b| b.score().total_cmp(&a.score()));
-            for suggestion in suggestions.into_iter().take(5).filter(|s| s.score() > 0.8) {
-                result.write_fmt(format_args!("• `{}` ", suggestion.path()));
-
-                if let Some(item) = suggestion.item() {
-                    result.write_fmt(format_args!("({:?})\n", item.kind()));
-                } else {
-                    result.push_str("(Crate)\n");
-                }
-            }
-            Ok(result)
+            Ok(not_found(&self.name, suggestions))
         }
     }
 }
+
+/// The answer for a name that doesn't resolve, with the closest names that do
+pub(super) fn not_found(name: &str, mut suggestions: Vec<Suggestion<'_>>) -> String {
+    let mut result = format!("`{name}` not found. Did you mean one of these?\n\n");
+    suggestions.sort_by(|a, b| b.score().total_cmp(&a.score()));
+    for (index, suggestion) in suggestions
+        .into_iter()
+        .take(5)
+        .filter(|s| s.score() > 0.8)
+        .enumerate()
+    {
+        result.write_fmt(format_args!("• `{}` ", suggestion.path()));
+
+        if let Some(item) = suggestion.item() {
+            result.write_fmt(format_args!("({:?})\n", item.kind()));
+        } else {
+            result.push_str("(Crate)\n");
+        }
+
+        // The best match's summary often makes another lookup unnecessary
+        if index == 0
+            && let Some(summary) = suggestion.summary()
+        {
+            result.write_fmt(format_args!("  {summary}\n"));
+        }
+    }
+    result
+}
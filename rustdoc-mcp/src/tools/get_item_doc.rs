@@ -0,0 +1,221 @@
+use crate::format_context::FormatContext;
+use crate::request::Request;
+use crate::state::RustdocTools;
+use crate::tools::get_item::not_found;
+use anyhow::{Result, bail};
+use clap::Args;
+use mcplease::{
+    traits::{Tool, WithExamples},
+    types::Example,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Rough number of characters in a token, for turning `max_tokens` into characters
+pub(super) const CHARS_PER_TOKEN: usize = 4;
+
+/// Get the complete documentation of a single item as plain text, cut to a size budget
+///
+/// ## Usage Patterns
+/// - **Whole docs**: `name: "std::vec::Vec"` - Everything, like get_item with full verbosity
+/// - **Within a budget**: `name: "std::vec::Vec", max_tokens: 2000` - The first section
+/// - **Reading on**: `name: "std::vec::Vec", max_tokens: 2000, section: 2` - The next section
+///
+/// The docs are cut between paragraphs, and a code block is never split unless it is bigger
+/// than the budget on its own. A cut section ends with a hint naming the section to ask for
+/// next; the hint isn't counted in the budget.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Args)]
+#[serde(rename = "get_item_doc")]
+pub struct GetItemDoc {
+    /// The name of the item to show (e.g., "crate::MyStruct", "serde_json::Value", "std::vec::Vec")
+    pub name: String,
+
+    /// Largest number of tokens to return, counting about four characters per token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub max_tokens: Option<usize>,
+
+    /// Largest number of characters to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub max_chars: Option<usize>,
+
+    /// Which section of docs cut to the budget to return, counting from 1 (default: 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub section: Option<usize>,
+}
+
+impl GetItemDoc {
+    /// The most characters to return, if there's a budget
+    pub(crate) fn budget(&self) -> Option<usize> {
+        let from_tokens = self
+            .max_tokens
+            .map(|tokens| tokens.saturating_mul(CHARS_PER_TOKEN));
+        match (self.max_chars, from_tokens) {
+            (Some(chars), Some(tokens)) => Some(chars.min(tokens)),
+            (chars, tokens) => chars.or(tokens),
+        }
+        .map(|budget| budget.max(1))
+    }
+
+    /// Get section with default
+    pub(crate) fn section(&self) -> usize {
+        self.section.unwrap_or(1)
+    }
+}
+
+impl WithExamples for GetItemDoc {
+    fn examples() -> Vec<Example<Self>> {
+        vec![
+            Example {
+                description: "Get the complete docs of a type",
+                item: Self {
+                    name: "serde_json::Value".to_string(),
+                    ..Default::default()
+                },
+            },
+            Example {
+                description: "Get as much of a type's docs as fits in 2000 tokens",
+                item: Self {
+                    name: "std::vec::Vec".to_string(),
+                    max_tokens: Some(2000),
+                    ..Default::default()
+                },
+            },
+            Example {
+                description: "Get the part of the docs after the first 2000 tokens",
+                item: Self {
+                    name: "std::vec::Vec".to_string(),
+                    max_tokens: Some(2000),
+                    section: Some(2),
+                    ..Default::default()
+                },
+            },
+        ]
+    }
+}
+
+impl Tool<RustdocTools> for GetItemDoc {
+    fn execute(self, tools: &mut RustdocTools) -> Result<String> {
+        let manifest_path = tools.resolve_path("Cargo.toml", None)?;
+        let request = Request::new(manifest_path);
+
+        let mut suggestions = vec![];
+        let Some(item) = request.resolve_path(&self.name, &mut suggestions) else {
+            return Ok(not_found(&self.name, suggestions));
+        };
+
+        let docs = request.format_item(item, &FormatContext::full_docs());
+        let Some(budget) = self.budget() else {
+            return Ok(docs);
+        };
+
+        let sections = sections(&docs, budget);
+        let total = sections.len();
+        let section = self.section();
+        if section == 0 || section > total {
+            bail!(
+                "There is no section {section}: the docs of {} come in {total} section{} of up \
+                 to {budget} characters",
+                self.name,
+                if total == 1 { "" } else { "s" }
+            );
+        }
+
+        let mut result = sections[section - 1].clone();
+        if section < total {
+            let next = section + 1;
+            result.push_str(&format!(
+                "\n\n[Truncated: this is section {section}/{total}. Request section {next} for more.]\n"
+            ));
+        } else if total > 1 {
+            result.push_str(&format!("\n\n[Section {section}/{total}, the last.]\n"));
+        }
+        Ok(result)
+    }
+}
+
+/// Cut `docs` into sections of at most `budget` characters, between paragraphs where it can
+///
+/// Paragraphs are separated by blank lines, and a fenced code block counts as one paragraph
+/// even with blank lines in it. A paragraph bigger than the budget is cut between its lines,
+/// and a line bigger than the budget wherever it runs out of room.
+pub(super) fn sections(docs: &str, budget: usize) -> Vec<String> {
+    let mut sections = vec![];
+    let mut current = String::new();
+    for paragraph in paragraphs(docs) {
+        let separator = if current.is_empty() { 0 } else { 2 };
+        if char_count(&current) + separator + char_count(&paragraph) <= budget {
+            if separator > 0 {
+                current.push_str("\n\n");
+            }
+            current.push_str(&paragraph);
+            continue;
+        }
+
+        if !current.is_empty() {
+            sections.push(std::mem::take(&mut current));
+        }
+        if char_count(&paragraph) <= budget {
+            current = paragraph;
+            continue;
+        }
+
+        for line in paragraph.lines() {
+            let separator = if current.is_empty() { 0 } else { 1 };
+            if char_count(&current) + separator + char_count(line) <= budget {
+                if separator > 0 {
+                    current.push('\n');
+                }
+                current.push_str(line);
+                continue;
+            }
+
+            if !current.is_empty() {
+                sections.push(std::mem::take(&mut current));
+            }
+            let mut chars = line.chars().peekable();
+            while chars.peek().is_some() {
+                current = chars.by_ref().take(budget).collect();
+                if chars.peek().is_some() {
+                    sections.push(std::mem::take(&mut current));
+                }
+            }
+        }
+    }
+    if !current.is_empty() || sections.is_empty() {
+        sections.push(current);
+    }
+    sections
+}
+
+/// The paragraphs of `docs`, keeping each fenced code block whole
+fn paragraphs(docs: &str) -> Vec<String> {
+    let mut paragraphs = vec![];
+    let mut current = String::new();
+    let mut in_fence = false;
+    for line in docs.trim_end().lines() {
+        if line.trim().is_empty() && !in_fence {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
+
+fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
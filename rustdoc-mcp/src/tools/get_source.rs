@@ -0,0 +1,118 @@
+use crate::request::Request;
+use crate::state::RustdocTools;
+use crate::tools::get_item::not_found;
+use crate::traits::WriteFmt;
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use mcplease::{
+    traits::{Tool, WithExamples},
+    types::Example,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Get the source code of an item, with the file it's in and its line range
+///
+/// ## Usage Patterns
+/// - **Implementation**: `name: "crate::MyStruct::new"` - Just the item's own lines
+/// - **With surroundings**: `name: "std::vec::Vec::push", context_lines: 5` - Five more lines
+///   above and below
+///
+/// Works for items in the workspace, and for dependencies whose source cargo has downloaded or
+/// that can be fetched from crates.io.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Args)]
+#[serde(rename = "get_source")]
+pub struct GetSource {
+    /// The name of the item whose source to show (e.g., "crate::MyStruct", "std::vec::Vec::push")
+    pub name: String,
+
+    /// Lines to show above and below the item (default: 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub context_lines: Option<usize>,
+}
+
+impl GetSource {
+    /// Get context_lines with default
+    pub(crate) fn context_lines(&self) -> usize {
+        self.context_lines.unwrap_or(0)
+    }
+}
+
+impl WithExamples for GetSource {
+    fn examples() -> Vec<Example<Self>> {
+        vec![
+            Example {
+                description: "Get the source of a function in the current crate",
+                item: Self {
+                    name: "crate::main".to_string(),
+                    ..Default::default()
+                },
+            },
+            Example {
+                description: "Get the source of a method with five lines around it",
+                item: Self {
+                    name: "std::vec::Vec::push".to_string(),
+                    context_lines: Some(5),
+                },
+            },
+        ]
+    }
+}
+
+impl Tool<RustdocTools> for GetSource {
+    fn execute(self, tools: &mut RustdocTools) -> Result<String> {
+        let manifest_path = tools.resolve_path("Cargo.toml", None)?;
+        let request = Request::new(manifest_path);
+
+        let mut suggestions = vec![];
+        let Some(item) = request.resolve_path(&self.name, &mut suggestions) else {
+            return Ok(not_found(&self.name, suggestions));
+        };
+
+        let Some(span) = &item.span else {
+            bail!("The docs of {} don't say where its source is", self.name);
+        };
+        let Some(file_path) = request.source_file_path(item, span) else {
+            bail!(
+                "The source of {} is in {}, which isn't available",
+                self.name,
+                span.filename.display()
+            );
+        };
+        let file_content = fs::read_to_string(&file_path)
+            .with_context(|| format!("Could not read {}", file_path.display()))?;
+        let lines: Vec<&str> = file_content.lines().collect();
+
+        // rustdoc spans are 1-indexed
+        let start_line = span.begin.0.max(1);
+        let end_line = span.end.0.max(start_line).min(lines.len());
+        if start_line > lines.len() {
+            bail!(
+                "{} ends before line {start_line}, where the source of {} should start",
+                file_path.display(),
+                self.name
+            );
+        }
+
+        let context_start = start_line.saturating_sub(self.context_lines()).max(1);
+        let context_end = end_line
+            .saturating_add(self.context_lines())
+            .min(lines.len());
+
+        let mut result = String::new();
+        result.write_fmt(format_args!("File: {}\n", file_path.display()));
+        result.write_fmt(format_args!("Lines: {start_line}-{end_line}"));
+        if (context_start, context_end) != (start_line, end_line) {
+            result.write_fmt(format_args!(" (showing {context_start}-{context_end})"));
+        }
+        result.push_str("\n\n```rust\n");
+        for line in &lines[context_start - 1..context_end] {
+            result.write_fmt(format_args!("{line}\n"));
+        }
+        result.push_str("```\n");
+
+        Ok(result)
+    }
+}
@@ -60,7 +60,14 @@ impl Tool<RustdocTools> for ListCrates {
                     String::new()
                 };
 
-                format!(" {version}{usage_info}")
+                let dependency_labels = crate_info.dependency_labels();
+                let scope_info = if !dependency_labels.is_empty() {
+                    format!(" [{}]", dependency_labels.join("; "))
+                } else {
+                    String::new()
+                };
+
+                format!(" {version}{usage_info}{scope_info}")
             } else {
                 String::new()
             };
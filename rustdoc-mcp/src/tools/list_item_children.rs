@@ -0,0 +1,135 @@
+use crate::filter::Filter;
+use crate::indent::Indent;
+use crate::request::Request;
+use crate::state::RustdocTools;
+use crate::tools::get_item::not_found;
+use crate::traits::WriteFmt;
+use anyhow::Result;
+use clap::Args;
+use mcplease::{
+    traits::{Tool, WithExamples},
+    types::Example,
+};
+use rustdoc_types::ItemKind;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::VariantArray;
+
+/// List the children of an item one line each, to explore a crate a step at a time
+///
+/// ## Usage Patterns
+/// - **Module contents**: `name: "std::collections"` - The items in a module
+/// - **Type members**: `name: "std::vec::Vec"` - A struct's fields and methods, or an enum's
+///   variants and methods
+/// - **Trait members**: `name: "std::iter::Iterator"` - A trait's methods, types and constants
+///
+/// Each child is shown with its kind, the path to pass back to this tool or to get_item, and
+/// the first sentence of its docs, without the rest of its documentation.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Args)]
+#[serde(rename = "list_item_children")]
+pub struct ListItemChildren {
+    /// The item whose children to list (e.g., "crate", "std::collections", "std::vec::Vec")
+    pub name: String,
+
+    /// Only list children of these kinds: struct, enum, trait, function, constant, static,
+    /// module, union, macro, type, variant
+    /// Default: all item types
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long, value_enum)]
+    pub filter: Option<Vec<Filter>>,
+}
+
+impl ListItemChildren {
+    /// Get filters with default
+    pub(crate) fn filters(&self) -> &[Filter] {
+        self.filter.as_deref().unwrap_or(Filter::VARIANTS)
+    }
+}
+
+impl WithExamples for ListItemChildren {
+    fn examples() -> Vec<Example<Self>> {
+        vec![
+            Example {
+                description: "List the items in a module",
+                item: Self {
+                    name: "std::collections".to_string(),
+                    ..Default::default()
+                },
+            },
+            Example {
+                description: "List the fields and methods of a struct",
+                item: Self {
+                    name: "std::vec::Vec".to_string(),
+                    ..Default::default()
+                },
+            },
+            Example {
+                description: "List only the traits in the current crate's root",
+                item: Self {
+                    name: "crate".to_string(),
+                    filter: Some(vec![Filter::Trait]),
+                },
+            },
+        ]
+    }
+}
+
+impl Tool<RustdocTools> for ListItemChildren {
+    fn execute(self, tools: &mut RustdocTools) -> Result<String> {
+        let manifest_path = tools.resolve_path("Cargo.toml", None)?;
+        let request = Request::new(manifest_path);
+
+        let mut suggestions = vec![];
+        let Some(item) = request.resolve_path(&self.name, &mut suggestions) else {
+            return Ok(not_found(&self.name, suggestions));
+        };
+
+        let filters = self.filters();
+        let children: Vec<_> = item
+            .members()
+            .chain(item.child_items())
+            .filter(|child| {
+                child.name().is_some()
+                    && child.kind() != ItemKind::Impl
+                    && filters
+                        .iter()
+                        .any(|filter| filter.matches_kind(child.kind()))
+            })
+            .collect();
+
+        let mut result = String::new();
+        result.write_fmt(format_args!("{} ({:?})", self.name, item.kind()));
+        if let Some(path) = item.discriminated_path() {
+            result.write_fmt(format_args!(" - {path}"));
+        }
+        result.push('\n');
+
+        if children.is_empty() {
+            result.push_str("\nNo children match the current filters.\n");
+            return Ok(result);
+        }
+
+        result.write_fmt(format_args!(
+            "\n{} {}:\n",
+            children.len(),
+            if children.len() == 1 {
+                "child"
+            } else {
+                "children"
+            }
+        ));
+        for child in children {
+            let name = child.name().unwrap_or("<unnamed>");
+            result.write_fmt(format_args!("• {name} ({:?})", child.kind()));
+            if let Some(path) = child.discriminated_path() {
+                result.write_fmt(format_args!(" - {path}"));
+            }
+            result.push('\n');
+            if let Some(summary) = child.docs_summary() {
+                result.write_fmt(format_args!("{}", Indent::new(&summary, 4)));
+            }
+        }
+
+        Ok(result)
+    }
+}
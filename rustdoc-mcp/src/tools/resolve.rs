@@ -0,0 +1,79 @@
+use crate::request::Request;
+use crate::state::RustdocTools;
+use crate::tools::get_item::not_found;
+use crate::traits::WriteFmt;
+use anyhow::Result;
+use clap::Args;
+use mcplease::{
+    traits::{Tool, WithExamples},
+    types::Example,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Resolve an item path without formatting its documentation
+///
+/// Returns the item's path, its kind-discriminated path (which always resolves back to the same
+/// item), its kind, the crate that defines it and where its source is. When the path doesn't
+/// resolve, returns the closest paths instead.
+///
+/// ## Usage Patterns
+/// - **Check a path**: `path: "std::vec::Vec::push"` - Is this a real item, and what kind?
+/// - **Find a re-export's origin**: `path: "tokio::spawn"` - Which crate and file defines it
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Args)]
+#[serde(rename = "resolve")]
+pub struct Resolve {
+    /// The path to resolve (e.g., "std::vec::Vec", "crate::MyStruct::new")
+    pub path: String,
+}
+
+impl WithExamples for Resolve {
+    fn examples() -> Vec<Example<Self>> {
+        vec![Example {
+            description: "Resolve a method in std",
+            item: Self {
+                path: "std::vec::Vec::push".to_string(),
+            },
+        }]
+    }
+}
+
+impl Tool<RustdocTools> for Resolve {
+    fn execute(self, tools: &mut RustdocTools) -> Result<String> {
+        let manifest_path = tools.resolve_path("Cargo.toml", None)?;
+        let request = Request::new(manifest_path);
+
+        let mut suggestions = vec![];
+        let Some(item) = request.resolve_path(&self.path, &mut suggestions) else {
+            return Ok(not_found(&self.path, suggestions));
+        };
+
+        let mut result = String::new();
+        match item.path() {
+            Some(path) => result.write_fmt(format_args!("Path: {path}\n")),
+            None => result.write_fmt(format_args!("Path: {}\n", self.path)),
+        }
+        if let Some(discriminated_path) = item.discriminated_path() {
+            result.write_fmt(format_args!("Discriminated path: {discriminated_path}\n"));
+        }
+        result.write_fmt(format_args!("Kind: {:?}\n", item.kind()));
+        result.write_fmt(format_args!("Crate: {}", item.crate_docs().name()));
+        if let Some(version) = item.crate_docs().version() {
+            result.write_fmt(format_args!(" {version}"));
+        }
+        result.push('\n');
+        if let Some(span) = &item.span {
+            let file = request
+                .source_file_path(item, span)
+                .unwrap_or_else(|| span.filename.clone());
+            result.write_fmt(format_args!(
+                "Source: {}:{}-{}\n",
+                file.display(),
+                span.begin.0,
+                span.end.0
+            ));
+        }
+
+        Ok(result)
+    }
+}
@@ -56,7 +56,7 @@ impl Tool<RustdocTools> for Search {
         // Perform search using Navigator's built-in search
         let limit = self.limit.unwrap_or(10);
         let crate_names = [self.crate_name.as_str()];
-        let results = match request.search(&self.query, &crate_names) {
+        let results = match request.search(&self.query, &crate_names, None) {
             Ok(results) => results,
             Err(mut suggestions) => {
                 let mut result = format!(
@@ -0,0 +1,146 @@
+use crate::filter::Filter;
+use crate::request::Request;
+use crate::state::RustdocTools;
+use anyhow::{Result, bail};
+use clap::Args;
+use mcplease::{
+    serde_json::{self, Value, json},
+    traits::{Tool, WithExamples},
+    types::Example,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::VariantArray;
+
+/// Search for items across crates, returning the results as JSON for further processing
+///
+/// ## Usage Patterns
+/// - **Workspace**: `query: "parse json"` - Searches the workspace's own crates
+/// - **Scoped**: `query: "Error", crates: ["std", "crate"]` - Searches only the named crates
+/// - **By kind**: `query: "iterator", filter: ["trait"]` - Only returns items of the given kinds
+///
+/// The result is a JSON array, best match first, of objects with the item's
+/// `discriminated_path` (which can be passed to get_item as is), `kind`, `crate`, `score` and
+/// the first sentence of its docs as `summary`.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema, Args)]
+#[serde(rename = "search_docs")]
+pub struct SearchDocs {
+    /// The search query to look for. Individual terms will be combined additively.
+    pub query: String,
+
+    /// The crates to search within. Use `crate` for the current crate.
+    /// Default: the workspace's crates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub crates: Option<Vec<String>>,
+
+    /// Only return items of these kinds: struct, enum, trait, function, constant, static,
+    /// module, union, macro, type, variant
+    /// Default: all item types
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long, value_enum)]
+    pub filter: Option<Vec<Filter>>,
+
+    /// Maximum number of results to return (default: 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+}
+
+impl SearchDocs {
+    /// Get limit with default
+    pub(crate) fn limit(&self) -> usize {
+        self.limit.unwrap_or(10)
+    }
+
+    /// Get filters with default
+    pub(crate) fn filters(&self) -> &[Filter] {
+        self.filter.as_deref().unwrap_or(Filter::VARIANTS)
+    }
+}
+
+impl WithExamples for SearchDocs {
+    fn examples() -> Vec<Example<Self>> {
+        vec![
+            Example {
+                description: "Search the workspace's crates for 'parse json'",
+                item: Self {
+                    query: "parse json".into(),
+                    ..Default::default()
+                },
+            },
+            Example {
+                description: "Search for traits about iterators in std",
+                item: Self {
+                    query: "iterator".into(),
+                    crates: Some(vec!["std".into()]),
+                    filter: Some(vec![Filter::Trait]),
+                    limit: Some(5),
+                },
+            },
+        ]
+    }
+}
+
+impl Tool<RustdocTools> for SearchDocs {
+    fn execute(self, state: &mut RustdocTools) -> Result<String> {
+        let manifest_path = state.resolve_path("Cargo.toml", None)?;
+        let request = Request::new(manifest_path);
+
+        let crate_names: Vec<&str> = match &self.crates {
+            Some(crates) => crates.iter().map(String::as_str).collect(),
+            None => request
+                .list_available_crates()
+                .filter(|crate_info| crate_info.provenance().is_workspace())
+                .map(|crate_info| crate_info.name())
+                .collect(),
+        };
+
+        let results = match request.search(&self.query, &crate_names, None) {
+            Ok(results) => results,
+            Err(mut suggestions) => {
+                suggestions.sort_by(|a, b| b.score().total_cmp(&a.score()));
+                let suggestions = suggestions
+                    .iter()
+                    .take(5)
+                    .filter(|suggestion| suggestion.score() > 0.8)
+                    .map(|suggestion| format!("`{}`", suggestion.path()))
+                    .collect::<Vec<_>>();
+                if suggestions.is_empty() {
+                    bail!("None of the crates {crate_names:?} could be loaded");
+                }
+                bail!(
+                    "None of the crates {crate_names:?} could be loaded. Did you mean one of \
+                     these? {}",
+                    suggestions.join(", ")
+                );
+            }
+        };
+
+        let filters = self.filters();
+        let entries: Vec<Value> = results
+            .into_iter()
+            .filter_map(|result| {
+                let (item, path) =
+                    request.get_item_from_id_path(result.crate_name, &result.id_path)?;
+                filters
+                    .iter()
+                    .any(|filter| filter.matches_kind(item.kind()))
+                    .then(|| {
+                        json!({
+                            "discriminated_path": item
+                                .discriminated_path()
+                                .unwrap_or_else(|| path.join("::")),
+                            "kind": item.kind(),
+                            "crate": item.crate_docs().name(),
+                            "score": result.score,
+                            "summary": item.docs_summary(),
+                        })
+                    })
+            })
+            .take(self.limit())
+            .collect();
+
+        Ok(serde_json::to_string_pretty(&entries)?)
+    }
+}
@@ -126,6 +126,7 @@ impl TestTrait for TestStruct {
 }
 
 /// A public function
+#[doc(alias = "process")]
 pub fn test_function(input: &str) -> String {
     format!("processed: {}", input)
 }
@@ -231,6 +232,8 @@ where
     Mixed {
         data: T,
         /// Info can be any U as long as it's [`std::fmt::Display`]
+        ///
+        /// Other crates can be linked too, like [`serde::Serialize`] or [`proc_macro::TokenStream`]
         info: U,
     },
 }
@@ -362,3 +365,90 @@ mod private_detail {
     }
 }
 pub use private_detail::ReachableViaPrivateModule;
+
+/// Module for testing conventional doc sections
+pub mod conventional_headings {
+    /// Parse a port number
+    ///
+    /// # Errors:
+    ///
+    /// Returns the input if it is not a number from 0 to 65535.
+    ///
+    /// # Safety
+    ///
+    /// Safe to call with any input.
+    ///
+    /// # Examples
+    ///
+    /// Port numbers are parsed:
+    ///
+    /// ```
+    /// # use fixture_crate::conventional_headings::parse_port;
+    /// assert_eq!(parse_port("8080"), Ok(8080));
+    /// ```
+    ///
+    /// ## Invalid input
+    ///
+    /// ```
+    /// # use fixture_crate::conventional_headings::parse_port;
+    /// assert!(parse_port("http").is_err());
+    /// ```
+    pub fn parse_port(input: &str) -> Result<u16, String> {
+        input.parse().map_err(|_| input.to_string())
+    }
+
+    /// Read the byte a pointer points to
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be valid for reads.
+    pub unsafe fn read_byte(ptr: *const u8) -> u8 {
+        *ptr
+    }
+}
+
+/// Module for testing how impl blocks are grouped
+pub mod impl_blocks {
+    impl Counter {
+        /// Read the count
+        pub fn get(&self) -> u32 {
+            self.count
+        }
+    }
+
+    /// A counter whose methods are spread over several impl blocks
+    pub struct Counter {
+        /// The current count
+        pub count: u32,
+    }
+
+    impl Counter {
+        /// Add one to the count
+        pub fn increment(&mut self) {
+            self.count += 1;
+        }
+    }
+
+    /// Ways to make a counter
+    impl Counter {
+        /// Start counting from the given number
+        pub fn starting_at(count: u32) -> Self {
+            Self { count }
+        }
+    }
+
+    /// Ways to go back to zero
+    impl Counter {
+        /// Set the count back to zero
+        pub fn reset(&mut self) {
+            self.count = 0;
+        }
+    }
+
+    impl Counter {
+        /// Whether the count is zero
+        pub fn is_zero(&self) -> bool {
+            self.count == 0
+        }
+    }
+}